@@ -1,12 +1,7 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use core::task::Poll;
-use s2n_quic::{
-    client::Connect,
-    provider::datagram::default::{Endpoint, Receiver},
-    Client,
-};
+use s2n_quic::{client::Connect, provider::datagram::default::Endpoint, Client};
 use std::{error::Error, net::SocketAddr};
 
 /// NOTE: this certificate/key pair is to be used for demonstration purposes only!
@@ -36,28 +31,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let addr: SocketAddr = "127.0.0.1:4433".parse()?;
     let connect = Connect::new(addr).with_server_name("localhost");
-    let mut connection = client.connect(connect).await?;
+    let connection = client.connect(connect).await?;
 
+    // The default datagram receiver allows awaiting datagrams sent by the peer
     loop {
-        let recv_result = futures::future::poll_fn(|cx| {
-            // datagram_mut takes a closure which calls the requested datagram function. The type
-            // of the closure parameter should be either the datagram Sender type or the
-            // datagram Receiver type. The datagram_mut function will check this type against
-            // its stored datagram Sender and Receiver, and if the type matches, the requested
-            // function will execute. Here, that requested function is poll_recv_datagram.
-            match connection.datagram_mut(|recv: &mut Receiver| recv.poll_recv_datagram(cx)) {
-                // If the function is successfully called on the provider, it will return Poll<Bytes>.
-                // Here we send an Ok() to wrap around the Bytes so the poll_fn doesn't complain.
-                Ok(poll_value) => poll_value.map(|x| Ok(x)),
-                // The datagram_mut function may return a query error if it can't find the type
-                // referenced in the closure. Here we wrap the error in a Poll::Ready enum so the
-                // poll_fn doesn't complain.
-                Err(query_err) => return Poll::Ready(Err(query_err)),
-            }
-        })
-        .await;
-
-        match recv_result {
+        match connection.recv_datagram().await {
             Ok(value) => eprintln!("RECV {:?}", value),
             Err(err) => {
                 eprintln!("{:?}", err);
@@ -16,6 +16,15 @@ const RECOMMENDED_ELICITATION_INTERVAL: u8 = 4;
 /// The recommended number of packet number ranges that an endpoint should store
 const RECOMMENDED_RANGES_LIMIT: u8 = 10;
 
+// An ACK frame SHOULD be generated for at least every 10th ack-eliciting packet
+/// The recommended value for the packet_tolerance setting
+const RECOMMENDED_PACKET_TOLERANCE: u8 = 10;
+
+/// The recommended value for the reordering_threshold setting
+///
+/// A threshold of 1 immediately acknowledges any out-of-order packet, as described in RFC 9000.
+const RECOMMENDED_REORDERING_THRESHOLD: u8 = 1;
+
 /// Settings for ACK frames
 #[derive(Clone, Copy, Debug)]
 pub struct Settings {
@@ -39,6 +48,18 @@ pub struct Settings {
 
     /// The number of packet number intervals an endpoint is willing to store
     pub ack_ranges_limit: u8,
+
+    /// The number of packets received before an ACK frame is sent without delay
+    ///
+    /// The peer can update this value with an ACK_FREQUENCY frame.
+    pub packet_tolerance: u8,
+
+    /// The packet number distance of an out-of-order packet that causes an ACK frame
+    /// to be sent without delay
+    ///
+    /// A value of 0 disables immediately acknowledging out-of-order packets. The peer
+    /// can update this value with an ACK_FREQUENCY frame.
+    pub reordering_threshold: u8,
 }
 
 impl Default for Settings {
@@ -72,6 +93,8 @@ impl Settings {
         ack_delay_exponent: AckDelayExponent::RECOMMENDED.as_u8(),
         ack_elicitation_interval: RECOMMENDED_ELICITATION_INTERVAL,
         ack_ranges_limit: RECOMMENDED_RANGES_LIMIT,
+        packet_tolerance: RECOMMENDED_PACKET_TOLERANCE,
+        reordering_threshold: RECOMMENDED_REORDERING_THRESHOLD,
     };
 
     /// Decodes the peer's `Ack Delay` field
@@ -0,0 +1,69 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replay protection for 0-RTT data accepted by a server
+
+/// The length of the `random` field in a TLS ClientHello
+pub const CLIENT_HELLO_RANDOM_LEN: usize = 32;
+
+/// Information about the ClientHello which is attempting to send early data
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct Context<'a> {
+    /// The `random` field of the ClientHello
+    ///
+    /// A replayed ClientHello carries the same `random` value as the original.
+    pub client_hello_random: &'a [u8; CLIENT_HELLO_RANDOM_LEN],
+}
+
+impl<'a> Context<'a> {
+    #[inline]
+    pub fn new(client_hello_random: &'a [u8; CLIENT_HELLO_RANDOM_LEN]) -> Self {
+        Self {
+            client_hello_random,
+        }
+    }
+}
+
+/// The decision made by a [`Guard`] for an attempt to send early data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The TLS provider is allowed to accept early data
+    Accept,
+    /// Early data is rejected in the handshake and the client sends the data again after the
+    /// handshake completes
+    Reject,
+}
+
+impl Outcome {
+    #[inline]
+    pub fn is_accepted(self) -> bool {
+        matches!(self, Self::Accept)
+    }
+}
+
+/// Decides if a server accepts early data from a ClientHello
+///
+/// The guard is consulted for each ClientHello that offers early data, before the TLS provider
+/// accepts it. Implementations should reject ClientHello messages which have been seen before.
+///
+/// The guard is cloned into each server connection so implementations should share their
+/// state between clones, e.g. with an `Arc`.
+pub trait Guard: 'static + Send + Clone {
+    /// Called when a ClientHello attempts to send early data
+    fn on_early_data(&self, context: &Context) -> Outcome;
+}
+
+/// An anti-replay guard that rejects all early data
+//= https://www.rfc-editor.org/rfc/rfc9001#section-9.2
+//# Disabling 0-RTT entirely is the most effective defense against replay
+//# attack.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Disabled(());
+
+impl Guard for Disabled {
+    #[inline]
+    fn on_early_data(&self, _context: &Context) -> Outcome {
+        Outcome::Reject
+    }
+}
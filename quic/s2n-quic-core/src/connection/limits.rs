@@ -9,7 +9,7 @@ use crate::{
         AckDelayExponent, ActiveConnectionIdLimit, InitialFlowControlLimits, InitialMaxData,
         InitialMaxStreamDataBidiLocal, InitialMaxStreamDataBidiRemote, InitialMaxStreamDataUni,
         InitialMaxStreamsBidi, InitialMaxStreamsUni, InitialStreamLimits, MaxAckDelay,
-        MaxDatagramFrameSize, MaxIdleTimeout, TransportParameters,
+        MaxDatagramFrameSize, MaxIdleTimeout, MinAckDelay, TransportParameters,
     },
 };
 use core::{convert::TryInto, time::Duration};
@@ -64,6 +64,11 @@ pub struct Limits {
     pub(crate) max_handshake_duration: Duration,
     pub(crate) max_keep_alive_period: Duration,
     pub(crate) max_datagram_frame_size: MaxDatagramFrameSize,
+    pub(crate) dscp: inet::Dscp,
+    pub(crate) min_ack_delay: Option<MinAckDelay>,
+    pub(crate) max_data_window: u32,
+    pub(crate) max_stream_data_window: u32,
+    pub(crate) send_buffer_auto_sizing: bool,
 }
 
 impl Default for Limits {
@@ -102,6 +107,11 @@ impl Limits {
             max_handshake_duration: MAX_HANDSHAKE_DURATION_DEFAULT,
             max_keep_alive_period: MAX_KEEP_ALIVE_PERIOD_DEFAULT,
             max_datagram_frame_size: MaxDatagramFrameSize::DEFAULT,
+            dscp: inet::Dscp::DEFAULT,
+            min_ack_delay: None,
+            max_data_window: 0,
+            max_stream_data_window: 0,
+            send_buffer_auto_sizing: false,
         }
     }
 
@@ -184,6 +194,59 @@ impl Limits {
     );
     setter!(with_max_keep_alive_period, max_keep_alive_period, Duration);
 
+    /// Sets the Differentiated Services Code Point marked on the packets sent by the connection
+    ///
+    /// The code point can be changed after the connection is established with
+    /// `connection::Handle::set_dscp`.
+    pub fn with_dscp(mut self, value: inet::Dscp) -> Result<Self, ValidationError> {
+        self.dscp = value;
+        Ok(self)
+    }
+
+    /// Enables the ACK frequency extension, allowing the peer to control how often the
+    /// connection acknowledges packets
+    ///
+    /// The value is the minimum amount of time the connection can delay an acknowledgement,
+    /// and is capped to the `max_ack_delay`.
+    pub fn with_min_ack_delay(mut self, value: Duration) -> Result<Self, ValidationError> {
+        self.min_ack_delay = Some(value.try_into()?);
+        Ok(self)
+    }
+
+    /// Enables auto-tuning of the connection receive window, up to the provided size
+    ///
+    /// The window starts at the `data_window` and grows when the application reads data fast
+    /// enough for the window to limit the peer's sending rate. Values less than the
+    /// `data_window` disable auto-tuning.
+    pub fn with_max_data_window(mut self, value: u64) -> Result<Self, ValidationError> {
+        self.max_data_window = value.try_into()?;
+        Ok(self)
+    }
+
+    /// Enables auto-tuning of stream receive windows, up to the provided size
+    ///
+    /// Stream windows start at the configured bidirectional or unidirectional data window and
+    /// grow when the application reads data fast enough for the window to limit the peer's
+    /// sending rate.
+    ///
+    /// The connection window is grown along with the stream windows, but never past the maximum
+    /// set with [`Self::with_max_data_window`]. If that isn't set, the connection window stays
+    /// at the `data_window` and limits the total throughput of all streams.
+    pub fn with_max_stream_data_window(mut self, value: u64) -> Result<Self, ValidationError> {
+        self.max_stream_data_window = value.try_into()?;
+        Ok(self)
+    }
+
+    /// Sizes stream send buffers from the bandwidth-delay product of the path
+    ///
+    /// Send buffers start small and grow with the congestion window, up to the
+    /// `max_send_buffer_size`. When disabled, each stream may buffer up to the
+    /// `max_send_buffer_size`.
+    pub fn with_send_buffer_auto_sizing(mut self, enabled: bool) -> Result<Self, ValidationError> {
+        self.send_buffer_auto_sizing = enabled;
+        Ok(self)
+    }
+
     // internal APIs
 
     #[doc(hidden)]
@@ -201,6 +264,7 @@ impl Limits {
             max_ack_delay: self.max_ack_delay.as_duration(),
             ack_ranges_limit: self.ack_ranges_limit,
             ack_elicitation_interval: self.ack_elicitation_interval,
+            ..ack::Settings::RECOMMENDED
         }
     }
 
@@ -256,6 +320,40 @@ impl Limits {
     pub fn max_keep_alive_period(&self) -> Duration {
         self.max_keep_alive_period
     }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn dscp(&self) -> inet::Dscp {
+        self.dscp
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn max_data_window(&self) -> u32 {
+        self.max_data_window
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn max_stream_data_window(&self) -> u32 {
+        self.max_stream_data_window
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn send_buffer_auto_sizing(&self) -> bool {
+        self.send_buffer_auto_sizing
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn min_ack_delay(&self) -> Option<Duration> {
+        self.min_ack_delay.map(|min_ack_delay| {
+            min_ack_delay
+                .as_duration()
+                .min(self.max_ack_delay.as_duration())
+        })
+    }
 }
 
 /// Creates limits for a given connection
@@ -12,8 +12,12 @@ use hex_literal::hex;
 pub trait InitialKey: crypto::Key + Sized {
     type HeaderKey: crypto::HeaderKey;
 
-    fn new_server(connection_id: &[u8]) -> (Self, Self::HeaderKey);
-    fn new_client(connection_id: &[u8]) -> (Self, Self::HeaderKey);
+    /// Creates the server keys for the QUIC `version` from the client's
+    /// Destination Connection ID
+    fn new_server(version: u32, connection_id: &[u8]) -> (Self, Self::HeaderKey);
+    /// Creates the client keys for the QUIC `version` from the client's
+    /// Destination Connection ID
+    fn new_client(version: u32, connection_id: &[u8]) -> (Self, Self::HeaderKey);
 }
 
 /// Types for which are able to perform initial header cryptography.
@@ -28,6 +32,11 @@ pub trait InitialHeaderKey: crypto::HeaderKey {}
 
 pub const INITIAL_SALT: [u8; 20] = hex!("38762cf7f55934b34d179ae6a4c80cadccbb7f0a");
 
+// The initial salt for QUIC version 2.
+// See https://www.rfc-editor.org/rfc/rfc9369#section-3.3.1
+
+pub const INITIAL_SALT_V2: [u8; 20] = hex!("0dede3def700a6db819381be6e269dcbf9bd2ed9");
+
 //= https://www.rfc-editor.org/rfc/rfc9001#section-5.2
 //# client_initial_secret = HKDF-Expand-Label(initial_secret,
 //#                                           "client in", "",
@@ -294,6 +303,18 @@ pub const EXAMPLE_SERVER_INITIAL_PROTECTED_PACKET: [u8; 135] = hex!(
     "
 );
 
+/// The example server Initial packet protected with QUIC version 2 keys, from
+/// <https://www.rfc-editor.org/rfc/rfc9369#appendix-A.3>
+pub const EXAMPLE_SERVER_INITIAL_PROTECTED_PACKET_V2: [u8; 135] = hex!(
+    "
+   dc6b3343cf0008f067a5502a4262b500 4075d92faaf16f05d8a4398c47089698
+   baeea26b91eb761d9b89237bbf872630 17915358230035f7fd3945d88965cf17
+   f9af6e16886c61bfc703106fbaf3cb4c fa52382dd16a393e42757507698075b2
+   c984c707f0a0812d8cd5a6881eaf21ce da98f4bd23f6fe1a3e2c43edd9ce7ca8
+   4bed8521e2e140
+    "
+);
+
 #[cfg(test)]
 fn header_protection_test_helper(
     mask: crate::crypto::HeaderProtectionMask,
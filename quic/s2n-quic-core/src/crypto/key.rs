@@ -115,11 +115,11 @@ pub mod testing {
     impl InitialKey for Key {
         type HeaderKey = HeaderKey;
 
-        fn new_server(_connection_id: &[u8]) -> (Self, Self::HeaderKey) {
+        fn new_server(_version: u32, _connection_id: &[u8]) -> (Self, Self::HeaderKey) {
             (Key::default(), HeaderKey::default())
         }
 
-        fn new_client(_connection_id: &[u8]) -> (Self, Self::HeaderKey) {
+        fn new_client(_version: u32, _connection_id: &[u8]) -> (Self, Self::HeaderKey) {
             (Key::default(), HeaderKey::default())
         }
     }
@@ -26,6 +26,13 @@ pub const SECRET_KEY_BYTES: [u8; 16] = hex!("be0c690b9f66575a1d766b54e368c84e");
 
 pub const NONCE_BYTES: [u8; 12] = hex!("461599d35d632bf2239825bb");
 
+// QUIC version 2 uses a different key and nonce to compute the Retry Integrity Tag.
+// See https://www.rfc-editor.org/rfc/rfc9369#section-3.3.3
+
+pub const SECRET_KEY_BYTES_V2: [u8; 16] = hex!("8fb4b01b56ac48e260fbcbcead7ccc92");
+
+pub const NONCE_BYTES_V2: [u8; 12] = hex!("d86969bc2d7c6d9990efb04a");
+
 pub mod example {
     use super::*;
 
@@ -76,4 +83,18 @@ pub mod example {
     pub const TOKEN: [u8; 5] = hex!("746f6b656e");
 
     pub const TOKEN_LEN: usize = 5;
+
+    // The QUIC version 2 Retry packet for the same Initial packet.
+    // See https://www.rfc-editor.org/rfc/rfc9369#appendix-A.4
+    pub const PACKET_V2: [u8; PACKET_LEN] = hex!(
+        "
+        cf6b3343cf0008f067a5502a4262b574 6f6b656ec8646ce8bfe33952d9555436
+        65dcc7b6
+        "
+    );
+
+    pub const PSEUDO_PACKET_V2: [u8; 29] =
+        hex!("088394c8f03e515708 cf6b3343cf 00 08f067a5502a4262b5 746f6b656e");
+
+    pub const EXPECTED_TAG_V2: [u8; 16] = hex!("c8646ce8bfe33952d955543665dcc7b6");
 }
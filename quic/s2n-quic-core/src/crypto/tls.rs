@@ -54,6 +54,58 @@ pub trait Context<Crypto: crate::crypto::CryptoSuite> {
         application_protocol: Bytes,
     ) -> Result<(), crate::transport::Error>;
 
+    /// Called by the client when the server issues a ticket for resuming the session
+    ///
+    /// Providers which don't support resumption don't need to call this.
+    fn on_session_ticket(&mut self, session_ticket: Bytes) -> Result<(), crate::transport::Error> {
+        let _ = session_ticket;
+        Ok(())
+    }
+
+    /// Called by the client once the server has indicated if it accepted early data
+    ///
+    /// Providers which don't support early data don't need to call this.
+    fn on_early_data_status(
+        &mut self,
+        status: EarlyDataStatus,
+    ) -> Result<(), crate::transport::Error> {
+        let _ = status;
+        Ok(())
+    }
+
+    /// Called by the server before it decides if early data from the client is accepted
+    ///
+    /// The TLS provider MUST reject early data in the handshake unless `Accept` is returned,
+    /// so the client knows to send the data again after the handshake. Providers which can't
+    /// accept early data don't need to call this.
+    fn on_early_data(&mut self) -> crate::anti_replay::Outcome {
+        crate::anti_replay::Outcome::Reject
+    }
+
+    /// Returns the QUIC version used to derive packet protection keys
+    ///
+    /// The version can change during the handshake with compatible version negotiation, so
+    /// providers call this each time they derive keys.
+    fn quic_version(&self) -> u32 {
+        crate::packet::long::VERSION_1
+    }
+
+    /// Called by the server with the client's transport parameters before it derives the
+    /// handshake keys
+    ///
+    /// This lets the server switch to a compatible version from the client's
+    /// `version_information` transport parameter. If the server's transport parameters change
+    /// as a result, the updated parameters are returned and the provider MUST send them instead
+    /// of the ones the session was created with. Providers which only support QUIC version 1
+    /// don't need to call this.
+    fn on_client_application_params(
+        &mut self,
+        client_params: ApplicationParameters,
+    ) -> Result<Option<Bytes>, crate::transport::Error> {
+        let _ = client_params;
+        Ok(None)
+    }
+
     //= https://www.rfc-editor.org/rfc/rfc9001#section-4.1.1
     //# The TLS handshake is considered complete when the
     //# TLS stack has reported that the handshake is complete.  This happens
@@ -106,6 +158,29 @@ pub trait Endpoint: 'static + Sized + Send {
         server_name: crate::application::ServerName,
     ) -> Self::Session;
 
+    /// Creates a new client session which resumes a previous session
+    ///
+    /// The `session_ticket` was issued to a previous session created by the same provider and
+    /// passed to [`Context::on_session_ticket`]. If the ticket allows it, the session provides
+    /// the transport with 0-RTT keys so it can send early data.
+    ///
+    /// Providers which don't support resumption create a new session instead.
+    fn resume_client_session<Params: s2n_codec::EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+        server_name: crate::application::ServerName,
+        session_ticket: &[u8],
+    ) -> Self::Session {
+        let _ = session_ticket;
+        self.new_client_session(transport_parameters, server_name)
+    }
+
+    /// The QUIC versions the provider can derive packet protection keys for
+    ///
+    /// Providers which derive keys for the version returned by [`Context::quic_version`] can
+    /// also list QUIC version 2.
+    const QUIC_VERSIONS: &'static [u32] = &[crate::packet::long::VERSION_1];
+
     /// The maximum length of a tag for any algorithm that may be negotiated
     fn max_tag_length(&self) -> usize;
 }
@@ -118,6 +193,39 @@ pub trait Session: crate::crypto::CryptoSuite + Sized + Send + Debug {
     ) -> core::task::Poll<Result<(), crate::transport::Error>>;
 }
 
+/// The outcome of a client attempting to send early (0-RTT) data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EarlyDataStatus {
+    /// Early data was not attempted, either because no session ticket was available or
+    /// the TLS provider does not support it
+    NotAttempted,
+    /// The server accepted the early data
+    Accepted,
+    /// The server rejected the early data
+    ///
+    /// Any data sent in 0-RTT packets needs to be retransmitted in 1-RTT packets.
+    Rejected,
+}
+
+impl Default for EarlyDataStatus {
+    #[inline]
+    fn default() -> Self {
+        Self::NotAttempted
+    }
+}
+
+impl EarlyDataStatus {
+    #[inline]
+    pub fn is_accepted(self) -> bool {
+        matches!(self, Self::Accepted)
+    }
+
+    #[inline]
+    pub fn is_rejected(self) -> bool {
+        matches!(self, Self::Rejected)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 #[allow(non_camel_case_types)]
 pub enum CipherSuite {
@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    anti_replay,
     application::ServerName,
     crypto::{
         header_crypto::{LONG_HEADER_MASK, SHORT_HEADER_MASK},
         tls, CryptoSuite, HeaderKey, Key,
     },
-    endpoint, transport,
+    endpoint,
+    packet::long::{VERSION_1, VERSION_2},
+    transport,
 };
 use alloc::sync::Arc;
 use bytes::Bytes;
@@ -63,6 +66,8 @@ impl super::Endpoint for Endpoint {
         Session
     }
 
+    const QUIC_VERSIONS: &'static [u32] = &[VERSION_1, VERSION_2];
+
     fn max_tag_length(&self) -> usize {
         16
     }
@@ -124,18 +129,73 @@ impl<S: tls::Session, C: tls::Session> Pair<S, C> {
     where
         SE: tls::Endpoint<Session = S>,
         CE: tls::Endpoint<Session = C>,
+    {
+        Self::with_quic_version(server_endpoint, client_endpoint, server_name, VERSION_1)
+    }
+
+    /// Creates a pair which derives keys for the given QUIC version
+    pub fn with_quic_version<SE, CE>(
+        server_endpoint: &mut SE,
+        client_endpoint: &mut CE,
+        server_name: ServerName,
+        quic_version: u32,
+    ) -> Self
+    where
+        SE: tls::Endpoint<Session = S>,
+        CE: tls::Endpoint<Session = C>,
+    {
+        let client =
+            client_endpoint.new_client_session(&TEST_CLIENT_TRANSPORT_PARAMS, server_name.clone());
+
+        Self::with_client_session(server_endpoint, client, server_name, quic_version)
+    }
+
+    /// Creates a pair with a client which resumes the session of a previous pair
+    pub fn resume<SE, CE>(
+        server_endpoint: &mut SE,
+        client_endpoint: &mut CE,
+        server_name: ServerName,
+        session_ticket: &[u8],
+    ) -> Self
+    where
+        SE: tls::Endpoint<Session = S>,
+        CE: tls::Endpoint<Session = C>,
+    {
+        let client = client_endpoint.resume_client_session(
+            &TEST_CLIENT_TRANSPORT_PARAMS,
+            server_name.clone(),
+            session_ticket,
+        );
+
+        Self::with_client_session(server_endpoint, client, server_name, VERSION_1)
+    }
+
+    fn with_client_session<SE>(
+        server_endpoint: &mut SE,
+        client: C,
+        server_name: ServerName,
+        quic_version: u32,
+    ) -> Self
+    where
+        SE: tls::Endpoint<Session = S>,
     {
         use crate::crypto::InitialKey;
 
         let server = server_endpoint.new_server_session(&TEST_SERVER_TRANSPORT_PARAMS);
         let mut server_context =
             Context::new(endpoint::Type::Server, ServerState::WaitingClientHello);
-        server_context.initial.crypto = Some(S::InitialKey::new_server(server_name.as_bytes()));
+        server_context.quic_version = quic_version;
+        server_context.initial.crypto = Some(S::InitialKey::new_server(
+            quic_version,
+            server_name.as_bytes(),
+        ));
 
-        let client =
-            client_endpoint.new_client_session(&TEST_CLIENT_TRANSPORT_PARAMS, server_name.clone());
         let mut client_context = Context::new(endpoint::Type::Client, ClientState::ClientHelloSent);
-        client_context.initial.crypto = Some(C::InitialKey::new_client(server_name.as_bytes()));
+        client_context.quic_version = quic_version;
+        client_context.initial.crypto = Some(C::InitialKey::new_client(
+            quic_version,
+            server_name.as_bytes(),
+        ));
 
         Self {
             server: TlsEndpoint::new(server, server_context),
@@ -179,6 +239,17 @@ impl<S: tls::Session, C: tls::Session> Pair<S, C> {
         Ok(())
     }
 
+    /// Delivers the session tickets the server issued after the handshake to the client
+    pub fn receive_session_tickets(&mut self) -> Result<(), transport::Error> {
+        self.client.context.transfer(&mut self.server.context);
+        match self.client.session.poll(&mut self.client.context) {
+            Poll::Ready(res) => res?,
+            Poll::Pending => (),
+        }
+
+        Ok(())
+    }
+
     fn check_progress(&mut self) {
         match (&self.client.context.state, &self.server.context.state) {
             (ClientState::ClientHelloSent, ServerState::WaitingClientHello) => {
@@ -231,6 +302,31 @@ impl<S: tls::Session, C: tls::Session> Pair<S, C> {
     pub fn finish(&self) {
         self.client.context.finish(&self.server.context);
 
+        // the server only derives 0-RTT keys if it accepts early data
+        if self.client.context.early_data_status == Some(tls::EarlyDataStatus::Accepted) {
+            let (client_key, client_header_key) = self
+                .client
+                .context
+                .zero_rtt_crypto
+                .as_ref()
+                .expect("client accepted early data without 0-rtt keys");
+            let (server_key, server_header_key) = self
+                .server
+                .context
+                .zero_rtt_crypto
+                .as_ref()
+                .expect("server accepted early data without 0-rtt keys");
+
+            // 0-RTT packets are only sent by the client
+            seal_open(client_key, server_key);
+            protect_unprotect(client_header_key, server_header_key, LONG_HEADER_MASK);
+        } else {
+            assert!(
+                self.server.context.zero_rtt_crypto.is_none(),
+                "server derived 0-rtt keys without accepting early data"
+            );
+        }
+
         assert_eq!(
             self.client.context.transport_parameters.as_ref().unwrap(),
             TEST_SERVER_TRANSPORT_PARAMS,
@@ -267,8 +363,6 @@ impl<S: tls::Session, C: tls::Session> Pair<S, C> {
             "server state did not complete: current state: {:?}",
             self.server.context.state,
         );
-
-        // TODO check 0-rtt keys
     }
 }
 
@@ -328,6 +422,12 @@ pub struct Context<C: CryptoSuite, State: Debug> {
     pub server_name: Option<Bytes>,
     pub application_protocol: Option<Bytes>,
     pub transport_parameters: Option<Bytes>,
+    pub early_data_status: Option<tls::EarlyDataStatus>,
+    pub session_tickets: Vec<Bytes>,
+    /// The decision returned to a server asking if it can accept early data
+    pub early_data: anti_replay::Outcome,
+    /// The QUIC version the keys are derived for
+    pub quic_version: u32,
     endpoint: endpoint::Type,
     pub state: State,
     waker: Waker,
@@ -344,6 +444,10 @@ impl<C: CryptoSuite, State: Debug> fmt::Debug for Context<C, State> {
             .field("sni", &self.server_name)
             .field("application_protocol", &self.application_protocol)
             .field("transport_parameters", &self.transport_parameters)
+            .field("early_data_status", &self.early_data_status)
+            .field("session_tickets", &self.session_tickets.len())
+            .field("early_data", &self.early_data)
+            .field("quic_version", &self.quic_version)
             .field("endpoint", &self.endpoint)
             .finish()
     }
@@ -361,6 +465,10 @@ impl<C: CryptoSuite, State: Debug> Context<C, State> {
             server_name: None,
             application_protocol: None,
             transport_parameters: None,
+            early_data_status: None,
+            session_tickets: Vec::new(),
+            early_data: anti_replay::Outcome::Reject,
+            quic_version: VERSION_1,
             endpoint,
             state,
             waker,
@@ -388,12 +496,6 @@ impl<C: CryptoSuite, State: Debug> Context<C, State> {
             "application_protocol is not consistent between endpoints"
         );
 
-        assert_eq!(
-            self.zero_rtt_crypto.is_some(),
-            other.zero_rtt_crypto.is_some(),
-            "0-rtt keys are not consistent between endpoints"
-        );
-
         self.initial.finish(&other.initial);
         self.handshake.finish(&other.handshake);
         self.application.finish(&other.application);
@@ -612,6 +714,63 @@ impl<C: CryptoSuite, State: Debug> tls::Context<C> for Context<C, State> {
         Ok(())
     }
 
+    fn on_session_ticket(&mut self, session_ticket: Bytes) -> Result<(), transport::Error> {
+        assert!(
+            self.endpoint.is_client(),
+            "session tickets should only be received by the client"
+        );
+        self.log("session ticket");
+        self.session_tickets.push(session_ticket);
+        Ok(())
+    }
+
+    fn on_early_data_status(
+        &mut self,
+        status: tls::EarlyDataStatus,
+    ) -> Result<(), transport::Error> {
+        assert!(
+            self.endpoint.is_client(),
+            "early data status should only be reported by the client"
+        );
+        assert!(
+            self.early_data_status.is_none(),
+            "early data status reported multiple times"
+        );
+        self.log("early data status");
+        self.early_data_status = Some(status);
+        Ok(())
+    }
+
+    fn on_early_data(&mut self) -> anti_replay::Outcome {
+        assert!(
+            self.endpoint.is_server(),
+            "early data should only be checked by the server"
+        );
+        self.log("early data");
+        self.early_data
+    }
+
+    fn quic_version(&self) -> u32 {
+        self.quic_version
+    }
+
+    fn on_client_application_params(
+        &mut self,
+        client_params: tls::ApplicationParameters,
+    ) -> Result<Option<Bytes>, transport::Error> {
+        assert!(
+            self.endpoint.is_server(),
+            "client params should only be received by the server"
+        );
+        assert!(
+            self.handshake.crypto.is_none(),
+            "client params should be received before the handshake keys"
+        );
+        self.log("client params");
+        self.on_application_params(client_params);
+        Ok(None)
+    }
+
     fn on_handshake_complete(&mut self) -> Result<(), transport::Error> {
         assert!(
             !self.handshake_complete,
@@ -5,15 +5,20 @@
 
 use crate::{
     connection,
-    datagram::{ConnectionInfo, Packet, PreConnectionInfo, ReceiveContext},
+    datagram::{ConnectionInfo, DropReason, Packet, PreConnectionInfo, ReceiveContext},
+    query,
     transport::parameters::MaxDatagramFrameSize,
 };
 use alloc::collections::VecDeque;
 use bytes::Bytes;
+#[cfg(feature = "std")]
+use core::time::Duration;
 use core::{
     fmt,
     task::{Context, Poll, Waker},
 };
+#[cfg(feature = "std")]
+use std::time::Instant;
 
 /// Handles configuring unreliable datagram support.
 ///
@@ -237,15 +242,16 @@ impl ReceiverBuilder {
 /// data. This is to ensure there is a balance between the amount of reliable
 /// and unreliable data getting sent.
 ///
-/// Datagrams are written to the packet in the order they are added to the queue.
+/// Datagrams are written to the packet in order of their priority and, within the
+/// same priority, in the order they are added to the queue.
 /// A datagram that is too large to fit in the packet will be dropped, unless the
 /// packet already contains written datagrams. This attempts to prevent
 /// the case where all datagrams are dropped because only a small amount of packet
 /// space remains.
 ///
-/// Note that there is currently no expiration date for datagrams to live on the queue.
-/// Implement the [`Sender`](s2n-quic-core::datagram::traits::Sender) trait if
-/// this behavior is necessary for your use-case.
+/// Datagrams sent with a time to live are dropped once it has passed since they were
+/// queued, before they are written to a packet. Dropped datagrams are reported to the
+/// connection's event subscriber.
 ///
 #[derive(Debug)]
 pub struct Sender {
@@ -263,6 +269,62 @@ pub struct Sender {
 #[derive(Debug, PartialEq)]
 pub struct Datagram {
     pub data: Bytes,
+    /// Datagrams with a higher priority are transmitted first
+    pub priority: u8,
+    /// The datagram is dropped if it was not transmitted by this time
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
+impl Datagram {
+    #[inline]
+    fn new(data: Bytes, options: SendOptions) -> Self {
+        Self {
+            data,
+            priority: options.priority,
+            // the time to live starts when the datagram is queued
+            #[cfg(feature = "std")]
+            deadline: options.ttl.map(|ttl| Instant::now() + ttl),
+        }
+    }
+}
+
+/// Options for sending a datagram with [`Sender::send_datagram_with`]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SendOptions {
+    /// Datagrams with a higher priority are transmitted first
+    ///
+    /// Note that this is the opposite of stream urgency, where lower values are
+    /// transmitted first.
+    pub priority: u8,
+    /// The datagram is dropped if it was not transmitted within this duration of being
+    /// queued
+    #[cfg(feature = "std")]
+    pub ttl: Option<Duration>,
+}
+
+impl SendOptions {
+    /// Sets the priority of the datagram
+    ///
+    /// Datagrams with a higher priority are transmitted before, and evicted after,
+    /// datagrams with a lower priority. The default priority is `0`, so datagrams sent
+    /// without options are transmitted last. Note that this is the opposite of stream
+    /// urgency, where streams with a lower value are transmitted first.
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets how long the datagram may wait in the queue before it is dropped
+    ///
+    /// The time to live starts when the datagram is queued, and is measured with the
+    /// system's monotonic clock.
+    #[cfg(feature = "std")]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
 }
 
 #[non_exhaustive]
@@ -274,6 +336,8 @@ pub enum DatagramError {
     ExceedsPeerTransportLimits,
     #[non_exhaustive]
     ConnectionError { error: connection::Error },
+    #[non_exhaustive]
+    QueryError { error: query::Error },
 }
 
 impl fmt::Display for DatagramError {
@@ -291,10 +355,20 @@ impl fmt::Display for DatagramError {
             Self::ConnectionError { .. } => {
                 write!(f, "Connection-level error occurred.")
             }
+            Self::QueryError { error } => {
+                write!(f, "Datagram provider query failed: {error}")
+            }
         }
     }
 }
 
+impl From<query::Error> for DatagramError {
+    #[inline]
+    fn from(error: query::Error) -> Self {
+        Self::QueryError { error }
+    }
+}
+
 impl Sender {
     /// Creates a builder for the default datagram sender
     fn builder() -> SenderBuilder {
@@ -332,10 +406,11 @@ impl Sender {
             return Poll::Pending;
         }
 
-        let datagram = Datagram {
-            data: core::mem::replace(data, bytes::Bytes::new()),
-        };
-        self.queue.push_back(datagram);
+        let datagram = Datagram::new(
+            core::mem::replace(data, bytes::Bytes::new()),
+            SendOptions::default(),
+        );
+        self.enqueue(datagram);
         Poll::Ready(Ok(()))
     }
 
@@ -351,6 +426,25 @@ impl Sender {
     pub fn send_datagram_forced(
         &mut self,
         data: bytes::Bytes,
+    ) -> Result<Option<Bytes>, DatagramError> {
+        self.send_datagram_forced_with(data, SendOptions::default())
+    }
+
+    /// Adds a datagram with the provided options on the queue to be sent
+    ///
+    /// If the datagram queue is at capacity the oldest datagram with the lowest
+    /// priority is evicted to make space for the new datagram. If the new datagram
+    /// has a lower priority than every queued datagram, it is not queued.
+    ///
+    /// # Return value
+    /// - `Ok(None)` if the datagram was enqueued for sending
+    /// - `Ok(Some(Bytes))` if the queue is at capacity this will be the datagram that was
+    ///   evicted, or the new datagram if it was not queued
+    /// - `Err(DatagramError)` if some error occurred
+    pub fn send_datagram_forced_with(
+        &mut self,
+        data: bytes::Bytes,
+        options: SendOptions,
     ) -> Result<Option<Bytes>, DatagramError> {
         if data.len() as u64 > self.max_datagram_payload {
             return Err(DatagramError::ExceedsPeerTransportLimits);
@@ -362,19 +456,32 @@ impl Sender {
             return Err(DatagramError::ConnectionError { error: err });
         }
 
-        // Pop oldest datagram off the queue if it is at capacity
-        let mut oldest = None;
-        if self.queue.len() == self.capacity {
-            oldest = self.queue.pop_front();
+        let datagram = Datagram::new(data, options);
+
+        if self.queue.len() < self.capacity {
+            self.enqueue(datagram);
+            return Ok(None);
         }
 
-        let datagram = Datagram { data };
-        self.queue.push_back(datagram);
+        // The lowest priority datagrams are at the back of the queue
+        let lowest = match self.queue.back() {
+            Some(queued) => queued.priority,
+            // the queue has no capacity
+            None => return Ok(Some(datagram.data)),
+        };
 
-        match oldest {
-            Some(datagram) => Ok(Some(datagram.data)),
-            None => Ok(None),
+        if datagram.priority < lowest {
+            return Ok(Some(datagram.data));
         }
+
+        // Evict the oldest of the lowest priority datagrams
+        let index = self
+            .queue
+            .partition_point(|queued| queued.priority > lowest);
+        let evicted = self.queue.remove(index).map(|datagram| datagram.data);
+        self.enqueue(datagram);
+
+        Ok(evicted)
     }
 
     /// Adds datagrams on the queue to be sent
@@ -385,6 +492,24 @@ impl Sender {
     /// - `Ok()` if the datagram was enqueued for sending
     /// - `Err(DatagramError)` if some error occurred
     pub fn send_datagram(&mut self, data: bytes::Bytes) -> Result<(), DatagramError> {
+        self.send_datagram_with(data, SendOptions::default())
+    }
+
+    /// Adds a datagram with the provided priority and time to live on the queue to be sent
+    ///
+    /// The datagram is transmitted before all queued datagrams with a lower priority.
+    /// If the time to live passes before the datagram is transmitted, it is dropped.
+    ///
+    /// If the queue is full the newest datagram is not added and an error is returned.
+    ///
+    /// # Return value
+    /// - `Ok()` if the datagram was enqueued for sending
+    /// - `Err(DatagramError)` if some error occurred
+    pub fn send_datagram_with(
+        &mut self,
+        data: bytes::Bytes,
+        options: SendOptions,
+    ) -> Result<(), DatagramError> {
         if data.len() as u64 > self.max_datagram_payload {
             return Err(DatagramError::ExceedsPeerTransportLimits);
         }
@@ -399,11 +524,47 @@ impl Sender {
             return Err(DatagramError::QueueAtCapacity);
         }
 
-        let datagram = Datagram { data };
-        self.queue.push_back(datagram);
+        let datagram = Datagram::new(data, options);
+        self.enqueue(datagram);
         Ok(())
     }
 
+    /// Inserts the datagram behind all queued datagrams with the same or a higher priority
+    fn enqueue(&mut self, datagram: Datagram) {
+        let index = self
+            .queue
+            .partition_point(|queued| queued.priority >= datagram.priority);
+        self.queue.insert(index, datagram);
+    }
+
+    /// Drops all datagrams whose time to live has passed
+    #[cfg(feature = "std")]
+    fn drop_expired<P: Packet>(&mut self, packet: &mut P) {
+        let len = self.queue.len();
+        // only read the clock if a datagram has a deadline
+        let mut now = None;
+
+        self.queue.retain(|datagram| {
+            let is_expired = datagram.deadline.map_or(false, |deadline| {
+                deadline <= *now.get_or_insert_with(Instant::now)
+            });
+
+            if is_expired {
+                packet.on_datagram_dropped(datagram.data.len(), DropReason::Expired);
+                false
+            } else {
+                true
+            }
+        });
+
+        // Wake the application if there is space on the queue again
+        if self.queue.len() < len {
+            if let Some(w) = self.waker.take() {
+                w.wake();
+            }
+        }
+    }
+
     /// Filter through the datagrams in the send queue and only keep those that
     /// match a predicate
     pub fn retain_datagrams<F>(&mut self, f: F)
@@ -453,6 +614,9 @@ impl Sender {
 
 impl super::Sender for Sender {
     fn on_transmit<P: Packet>(&mut self, packet: &mut P) {
+        #[cfg(feature = "std")]
+        self.drop_expired(packet);
+
         // Cede space to stream data when datagrams are not prioritized
         if packet.has_pending_streams() && !packet.datagrams_prioritized() {
             return;
@@ -465,7 +629,8 @@ impl super::Sender for Sender {
                 if packet.remaining_capacity() >= datagram.data.len() {
                     match packet.write_datagram(&datagram.data) {
                         Ok(()) => has_written = true,
-                        Err(_error) => {
+                        Err(error) => {
+                            packet.on_datagram_dropped(datagram.data.len(), error.into());
                             continue;
                         }
                     }
@@ -482,6 +647,11 @@ impl super::Sender for Sender {
                         self.queue.push_front(datagram);
                         return;
                     }
+
+                    packet.on_datagram_dropped(
+                        datagram.data.len(),
+                        DropReason::ExceedsPacketCapacity,
+                    );
                 }
             } else {
                 // If there are no datagrams on the queue we return
@@ -552,10 +722,20 @@ impl SenderBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::datagram::WriteError;
-    use core::task::{Context, Poll};
+    use crate::{
+        datagram::WriteError,
+        time::clock::{Clock, NoopClock},
+    };
+    use core::{
+        task::{Context, Poll},
+        time::Duration,
+    };
     use futures_test::task::{new_count_waker, noop_waker};
 
+    fn now() -> Timestamp {
+        NoopClock.get_time()
+    }
+
     #[test]
     fn send_datagram_forced() {
         let conn_info = ConnectionInfo {
@@ -595,6 +775,57 @@ mod tests {
         assert!(default_sender.queue.is_empty());
     }
 
+    #[test]
+    fn send_datagram_forced_with_priority() {
+        let conn_info = ConnectionInfo::new(100, noop_waker());
+        let mut default_sender = Sender::builder()
+            .with_capacity(3)
+            .with_connection_info(&conn_info)
+            .build()
+            .unwrap();
+
+        let send = |sender: &mut Sender, data: &'static [u8], priority: u8| {
+            let options = SendOptions::default().with_priority(priority);
+            sender.send_datagram_forced_with(bytes::Bytes::from_static(data), options)
+        };
+
+        assert_eq!(send(&mut default_sender, &[1], 1), Ok(None));
+        assert_eq!(send(&mut default_sender, &[2], 5), Ok(None));
+        assert_eq!(send(&mut default_sender, &[3], 1), Ok(None));
+
+        // The oldest datagram with the lowest priority is evicted
+        assert_eq!(
+            send(&mut default_sender, &[4], 5),
+            Ok(Some(bytes::Bytes::from_static(&[1])))
+        );
+        // Datagrams with a higher priority are kept over newer datagrams
+        assert_eq!(
+            send(&mut default_sender, &[5], 1),
+            Ok(Some(bytes::Bytes::from_static(&[3])))
+        );
+        // A datagram with a lower priority than every queued datagram is not queued
+        assert_eq!(
+            send(&mut default_sender, &[6], 0),
+            Ok(Some(bytes::Bytes::from_static(&[6])))
+        );
+        assert_eq!(
+            send(&mut default_sender, &[7], 5),
+            Ok(Some(bytes::Bytes::from_static(&[5])))
+        );
+        // Once only higher priorities are left, the oldest of them is evicted
+        assert_eq!(
+            send(&mut default_sender, &[8], 5),
+            Ok(Some(bytes::Bytes::from_static(&[2])))
+        );
+
+        let order: Vec<u8> = default_sender
+            .queue
+            .iter()
+            .map(|datagram| datagram.data[0])
+            .collect();
+        assert_eq!(order, [4, 7, 8]);
+    }
+
     #[test]
     fn send_datagram() {
         let conn_info = ConnectionInfo {
@@ -678,6 +909,8 @@ mod tests {
             remaining_capacity: 10,
             has_pending_streams: false,
             datagrams_prioritized: false,
+            current_time: now(),
+            dropped: Vec::new(),
         };
         crate::datagram::Sender::on_transmit(&mut default_sender, &mut packet);
 
@@ -731,6 +964,112 @@ mod tests {
         assert!(default_sender.queue.is_empty());
     }
 
+    #[test]
+    fn send_datagram_with_priority() {
+        let conn_info = ConnectionInfo::new(100, noop_waker());
+        let mut default_sender = Sender::builder()
+            .with_connection_info(&conn_info)
+            .build()
+            .unwrap();
+
+        let send = |sender: &mut Sender, data: &'static [u8], priority: u8| {
+            let options = SendOptions::default().with_priority(priority);
+            sender.send_datagram_with(bytes::Bytes::from_static(data), options)
+        };
+
+        assert_eq!(send(&mut default_sender, &[1], 0), Ok(()));
+        assert_eq!(send(&mut default_sender, &[2], 5), Ok(()));
+        assert_eq!(send(&mut default_sender, &[3], 1), Ok(()));
+        assert_eq!(send(&mut default_sender, &[4], 5), Ok(()));
+        assert_eq!(
+            default_sender.send_datagram(bytes::Bytes::from_static(&[5])),
+            Ok(())
+        );
+
+        // Higher priorities are sent first, in the order they were queued
+        let order: Vec<u8> = default_sender
+            .queue
+            .iter()
+            .map(|datagram| datagram.data[0])
+            .collect();
+        assert_eq!(order, [2, 4, 3, 1, 5]);
+    }
+
+    #[test]
+    fn expired_datagrams_are_dropped() {
+        let conn_info = ConnectionInfo::new(100, noop_waker());
+        let mut default_sender = Sender::builder()
+            .with_capacity(3)
+            .with_connection_info(&conn_info)
+            .build()
+            .unwrap();
+
+        let send = |sender: &mut Sender, data: &'static [u8], ttl: Duration| {
+            let options = SendOptions::default().with_ttl(ttl);
+            sender.send_datagram_with(bytes::Bytes::from_static(data), options)
+        };
+
+        assert_eq!(
+            send(&mut default_sender, &[1, 2, 3], Duration::ZERO),
+            Ok(())
+        );
+        assert_eq!(
+            send(&mut default_sender, &[4], Duration::from_secs(3600)),
+            Ok(())
+        );
+        assert_eq!(
+            default_sender.send_datagram(bytes::Bytes::from_static(&[5, 6])),
+            Ok(())
+        );
+
+        let (waker, wake_count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(
+            default_sender.poll_send_datagram(&mut bytes::Bytes::from_static(&[7]), &mut cx),
+            Poll::Pending
+        );
+
+        // Only the datagram whose time to live has passed is dropped
+        let mut packet = MockPacket {
+            remaining_capacity: 100,
+            has_pending_streams: false,
+            datagrams_prioritized: false,
+            current_time: now(),
+            dropped: Vec::new(),
+        };
+        crate::datagram::Sender::on_transmit(&mut default_sender, &mut packet);
+
+        assert_eq!(packet.remaining_capacity, 97);
+        assert_eq!(packet.dropped, [(3, DropReason::Expired)]);
+        assert!(default_sender.queue.is_empty());
+        assert_eq!(wake_count, 1);
+    }
+
+    #[test]
+    fn oversized_datagrams_are_reported() {
+        let conn_info = ConnectionInfo::new(100, noop_waker());
+        let mut default_sender = Sender::builder()
+            .with_connection_info(&conn_info)
+            .build()
+            .unwrap();
+        assert_eq!(
+            default_sender.send_datagram(bytes::Bytes::from_static(&[1, 2, 3, 4])),
+            Ok(())
+        );
+
+        let mut packet = MockPacket {
+            remaining_capacity: 3,
+            has_pending_streams: false,
+            datagrams_prioritized: false,
+            current_time: now(),
+            dropped: Vec::new(),
+        };
+        crate::datagram::Sender::on_transmit(&mut default_sender, &mut packet);
+
+        assert_eq!(packet.dropped, [(4, DropReason::ExceedsPacketCapacity)]);
+        assert!(default_sender.queue.is_empty());
+    }
+
     #[test]
     fn record_capacity_stats() {
         // Here we test that record_capacity_stats() is working as expected. We use
@@ -771,6 +1110,8 @@ mod tests {
             remaining_capacity: 5,
             has_pending_streams: false,
             datagrams_prioritized: false,
+            current_time: now(),
+            dropped: Vec::new(),
         };
         crate::datagram::Sender::on_transmit(&mut default_sender, &mut packet);
 
@@ -906,6 +1247,8 @@ mod tests {
         has_pending_streams: bool,
         datagrams_prioritized: bool,
         remaining_capacity: usize,
+        current_time: Timestamp,
+        dropped: Vec<(usize, DropReason)>,
     }
 
     impl crate::datagram::Packet for MockPacket {
@@ -928,5 +1271,13 @@ mod tests {
         fn datagrams_prioritized(&self) -> bool {
             self.datagrams_prioritized
         }
+
+        fn current_time(&self) -> Timestamp {
+            self.current_time
+        }
+
+        fn on_datagram_dropped(&mut self, len: usize, reason: DropReason) {
+            self.dropped.push((len, reason));
+        }
     }
 }
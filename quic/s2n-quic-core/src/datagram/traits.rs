@@ -3,7 +3,11 @@
 
 use core::task::Waker;
 
-use crate::connection;
+use crate::{
+    connection,
+    event::{builder, IntoEvent},
+    time::Timestamp,
+};
 
 /// The datagram endpoint trait provides a way to implement custom unreliable datagram
 /// sending and receiving logic. The Sender type should be implemented for custom
@@ -117,6 +121,51 @@ pub trait Packet {
     /// Datagrams get prioritized every other packet, which gives the application the best
     /// chance to send a large datagram.
     fn datagrams_prioritized(&self) -> bool;
+
+    /// Returns the time at which the packet is being assembled
+    ///
+    /// Use method to drop datagrams which are no longer relevant to the peer.
+    fn current_time(&self) -> Timestamp;
+
+    /// Reports a queued datagram which was dropped without being written to a packet
+    ///
+    /// The drop is published to the connection's event subscriber.
+    fn on_datagram_dropped(&mut self, len: usize, reason: DropReason);
+}
+
+/// The reason a datagram was dropped by a [`Sender`]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropReason {
+    /// The deadline of the datagram passed before it could be transmitted
+    Expired,
+    /// The datagram did not fit into the remaining space of the packet
+    ExceedsPacketCapacity,
+    /// The datagram is larger than the peer is willing to accept
+    ExceedsPeerTransportLimits,
+}
+
+impl IntoEvent<builder::DatagramFrameDropReason> for DropReason {
+    #[inline]
+    fn into_event(self) -> builder::DatagramFrameDropReason {
+        match self {
+            Self::Expired => builder::DatagramFrameDropReason::Expired,
+            Self::ExceedsPacketCapacity => builder::DatagramFrameDropReason::ExceedsPacketCapacity,
+            Self::ExceedsPeerTransportLimits => {
+                builder::DatagramFrameDropReason::ExceedsPeerTransportLimits
+            }
+        }
+    }
+}
+
+impl From<WriteError> for DropReason {
+    #[inline]
+    fn from(error: WriteError) -> Self {
+        match error {
+            WriteError::ExceedsPacketCapacity => Self::ExceedsPacketCapacity,
+            WriteError::ExceedsPeerTransportLimits => Self::ExceedsPeerTransportLimits,
+        }
+    }
 }
 
 #[non_exhaustive]
@@ -119,4 +119,15 @@ pub trait Limiter: 'static + Send {
     /// }
     /// ```
     fn on_connection_attempt(&mut self, info: &ConnectionAttempt) -> Outcome;
+
+    /// Returns the maximum number of bytes that can be buffered across all of the stream send
+    /// buffers on the endpoint
+    ///
+    /// Once the limit is reached, writes to send streams are blocked until buffered data is
+    /// acknowledged by peers. A write is accepted as long as at least 1 byte is available, so the
+    /// limit can be exceeded by up to one written chunk per stream. Returning `None` disables the
+    /// limit.
+    fn max_send_buffer_memory(&self) -> Option<usize> {
+        None
+    }
 }
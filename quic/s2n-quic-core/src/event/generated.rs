@@ -25,7 +25,10 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
-    pub struct ConnectionInfo {}
+    pub struct ConnectionInfo<'a> {
+        #[doc = " The destination connection ID of the first Initial packet sent by the client"]
+        pub original_destination_connection_id: ConnectionId<'a>,
+    }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
     pub struct TransportParameters<'a> {
@@ -199,6 +202,15 @@ pub mod api {
         HandshakeDone {},
         #[non_exhaustive]
         Datagram { len: u16 },
+        #[non_exhaustive]
+        AckFrequency {
+            sequence_number: u64,
+            ack_eliciting_threshold: u64,
+            request_max_ack_delay: u64,
+            reordering_threshold: u64,
+        },
+        #[non_exhaustive]
+        ImmediateAck {},
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
@@ -542,6 +554,19 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    pub enum DatagramFrameDropReason {
+        #[non_exhaustive]
+        #[doc = " The deadline of the datagram passed before it could be transmitted."]
+        Expired {},
+        #[non_exhaustive]
+        #[doc = " The datagram did not fit into the remaining space of the packet."]
+        ExceedsPacketCapacity {},
+        #[non_exhaustive]
+        #[doc = " The datagram is larger than the peer is willing to accept."]
+        ExceedsPeerTransportLimits {},
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " Application level protocol"]
     pub struct ApplicationProtocolInformation<'a> {
         pub chosen_application_protocol: &'a [u8],
@@ -952,6 +977,16 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " An unreliable datagram was dropped by the datagram sender before being transmitted"]
+    pub struct DatagramFrameDropped {
+        pub len: u16,
+        pub reason: DatagramFrameDropReason,
+    }
+    impl Event for DatagramFrameDropped {
+        const NAME: &'static str = "transport:datagram_frame_dropped";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " QUIC version"]
     pub struct VersionInformation<'a> {
         pub server_versions: &'a [u32],
@@ -1108,6 +1143,13 @@ pub mod api {
         #[doc = " Emitted when ECN support is configured"]
         Ecn { enabled: bool },
         #[non_exhaustive]
+        #[doc = " Emitted when receive offload was configured"]
+        Gro {
+            #[doc = " If `true`, multiple datagrams from the same peer may be coalesced into a single"]
+            #[doc = " receive operation"]
+            enabled: bool,
+        },
+        #[non_exhaustive]
         #[doc = " Emitted when the maximum transmission unit is configured"]
         MaxMtu { mtu: u16 },
     }
@@ -1420,6 +1462,21 @@ pub mod api {
             builder::Frame::HandshakeDone {}
         }
     }
+    impl IntoEvent<builder::Frame> for &crate::frame::AckFrequency {
+        fn into_event(self) -> builder::Frame {
+            builder::Frame::AckFrequency {
+                sequence_number: self.sequence_number.as_u64(),
+                ack_eliciting_threshold: self.ack_eliciting_threshold.as_u64(),
+                request_max_ack_delay: self.request_max_ack_delay.as_u64(),
+                reordering_threshold: self.reordering_threshold.as_u64(),
+            }
+        }
+    }
+    impl IntoEvent<builder::Frame> for &crate::frame::ImmediateAck {
+        fn into_event(self) -> builder::Frame {
+            builder::Frame::ImmediateAck {}
+        }
+    }
     impl<Data> IntoEvent<builder::Frame> for &crate::frame::Stream<Data>
     where
         Data: s2n_codec::EncoderValue,
@@ -2068,6 +2125,17 @@ pub mod tracing {
             tracing :: event ! (target : "bbr_state_changed" , parent : id , tracing :: Level :: DEBUG , path_id = tracing :: field :: debug (path_id) , state = tracing :: field :: debug (state));
         }
         #[inline]
+        fn on_datagram_frame_dropped(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::DatagramFrameDropped,
+        ) {
+            let id = context.id();
+            let api::DatagramFrameDropped { len, reason } = event;
+            tracing :: event ! (target : "datagram_frame_dropped" , parent : id , tracing :: Level :: DEBUG , len = tracing :: field :: debug (len) , reason = tracing :: field :: debug (reason));
+        }
+        #[inline]
         fn on_version_information(
             &mut self,
             meta: &api::EndpointMeta,
@@ -2289,12 +2357,19 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
-    pub struct ConnectionInfo {}
-    impl IntoEvent<api::ConnectionInfo> for ConnectionInfo {
+    pub struct ConnectionInfo<'a> {
+        #[doc = " The destination connection ID of the first Initial packet sent by the client"]
+        pub original_destination_connection_id: ConnectionId<'a>,
+    }
+    impl<'a> IntoEvent<api::ConnectionInfo<'a>> for ConnectionInfo<'a> {
         #[inline]
-        fn into_event(self) -> api::ConnectionInfo {
-            let ConnectionInfo {} = self;
-            api::ConnectionInfo {}
+        fn into_event(self) -> api::ConnectionInfo<'a> {
+            let ConnectionInfo {
+                original_destination_connection_id,
+            } = self;
+            api::ConnectionInfo {
+                original_destination_connection_id: original_destination_connection_id.into_event(),
+            }
         }
     }
     #[derive(Clone, Debug)]
@@ -2612,6 +2687,13 @@ pub mod builder {
         Datagram {
             len: u16,
         },
+        AckFrequency {
+            sequence_number: u64,
+            ack_eliciting_threshold: u64,
+            request_max_ack_delay: u64,
+            reordering_threshold: u64,
+        },
+        ImmediateAck,
     }
     impl IntoEvent<api::Frame> for Frame {
         #[inline]
@@ -2700,6 +2782,18 @@ pub mod builder {
                 Self::Datagram { len } => Datagram {
                     len: len.into_event(),
                 },
+                Self::AckFrequency {
+                    sequence_number,
+                    ack_eliciting_threshold,
+                    request_max_ack_delay,
+                    reordering_threshold,
+                } => AckFrequency {
+                    sequence_number: sequence_number.into_event(),
+                    ack_eliciting_threshold: ack_eliciting_threshold.into_event(),
+                    request_max_ack_delay: request_max_ack_delay.into_event(),
+                    reordering_threshold: reordering_threshold.into_event(),
+                },
+                Self::ImmediateAck => ImmediateAck {},
             }
         }
     }
@@ -3233,6 +3327,26 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    pub enum DatagramFrameDropReason {
+        #[doc = " The deadline of the datagram passed before it could be transmitted."]
+        Expired,
+        #[doc = " The datagram did not fit into the remaining space of the packet."]
+        ExceedsPacketCapacity,
+        #[doc = " The datagram is larger than the peer is willing to accept."]
+        ExceedsPeerTransportLimits,
+    }
+    impl IntoEvent<api::DatagramFrameDropReason> for DatagramFrameDropReason {
+        #[inline]
+        fn into_event(self) -> api::DatagramFrameDropReason {
+            use api::DatagramFrameDropReason::*;
+            match self {
+                Self::Expired => Expired {},
+                Self::ExceedsPacketCapacity => ExceedsPacketCapacity {},
+                Self::ExceedsPeerTransportLimits => ExceedsPeerTransportLimits {},
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     #[doc = " Application level protocol"]
     pub struct ApplicationProtocolInformation<'a> {
         pub chosen_application_protocol: &'a [u8],
@@ -3954,6 +4068,22 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " An unreliable datagram was dropped by the datagram sender before being transmitted"]
+    pub struct DatagramFrameDropped {
+        pub len: u16,
+        pub reason: DatagramFrameDropReason,
+    }
+    impl IntoEvent<api::DatagramFrameDropped> for DatagramFrameDropped {
+        #[inline]
+        fn into_event(self) -> api::DatagramFrameDropped {
+            let DatagramFrameDropped { len, reason } = self;
+            api::DatagramFrameDropped {
+                len: len.into_event(),
+                reason: reason.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     #[doc = " QUIC version"]
     pub struct VersionInformation<'a> {
         pub server_versions: &'a [u32],
@@ -4197,6 +4327,12 @@ pub mod builder {
         },
         #[doc = " Emitted when ECN support is configured"]
         Ecn { enabled: bool },
+        #[doc = " Emitted when receive offload was configured"]
+        Gro {
+            #[doc = " If `true`, multiple datagrams from the same peer may be coalesced into a single"]
+            #[doc = " receive operation"]
+            enabled: bool,
+        },
         #[doc = " Emitted when the maximum transmission unit is configured"]
         MaxMtu { mtu: u16 },
     }
@@ -4211,6 +4347,9 @@ pub mod builder {
                 Self::Ecn { enabled } => Ecn {
                     enabled: enabled.into_event(),
                 },
+                Self::Gro { enabled } => Gro {
+                    enabled: enabled.into_event(),
+                },
                 Self::MaxMtu { mtu } => MaxMtu {
                     mtu: mtu.into_event(),
                 },
@@ -4864,6 +5003,18 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `DatagramFrameDropped` event is triggered"]
+        #[inline]
+        fn on_datagram_frame_dropped(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &DatagramFrameDropped,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `VersionInformation` event is triggered"]
         #[inline]
         fn on_version_information(&mut self, meta: &EndpointMeta, event: &VersionInformation) {
@@ -5466,6 +5617,16 @@ mod traits {
             (self.1).on_bbr_state_changed(&mut context.1, meta, event);
         }
         #[inline]
+        fn on_datagram_frame_dropped(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &DatagramFrameDropped,
+        ) {
+            (self.0).on_datagram_frame_dropped(&mut context.0, meta, event);
+            (self.1).on_datagram_frame_dropped(&mut context.1, meta, event);
+        }
+        #[inline]
         fn on_version_information(&mut self, meta: &EndpointMeta, event: &VersionInformation) {
             (self.0).on_version_information(meta, event);
             (self.1).on_version_information(meta, event);
@@ -5844,6 +6005,8 @@ mod traits {
         fn on_pacing_rate_updated(&mut self, event: builder::PacingRateUpdated);
         #[doc = "Publishes a `BbrStateChanged` event to the publisher's subscriber"]
         fn on_bbr_state_changed(&mut self, event: builder::BbrStateChanged);
+        #[doc = "Publishes a `DatagramFrameDropped` event to the publisher's subscriber"]
+        fn on_datagram_frame_dropped(&mut self, event: builder::DatagramFrameDropped);
         #[doc = r" Returns the QUIC version negotiated for the current connection, if any"]
         fn quic_version(&self) -> u32;
         #[doc = r" Returns the [`Subject`] for the current publisher"]
@@ -6239,6 +6402,15 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_datagram_frame_dropped(&mut self, event: builder::DatagramFrameDropped) {
+            let event = event.into_event();
+            self.subscriber
+                .on_datagram_frame_dropped(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn quic_version(&self) -> u32 {
             self.quic_version
         }
@@ -6294,6 +6466,7 @@ pub mod testing {
         pub delivery_rate_sampled: u32,
         pub pacing_rate_updated: u32,
         pub bbr_state_changed: u32,
+        pub datagram_frame_dropped: u32,
         pub version_information: u32,
         pub endpoint_packet_sent: u32,
         pub endpoint_packet_received: u32,
@@ -6371,6 +6544,7 @@ pub mod testing {
                 delivery_rate_sampled: 0,
                 pacing_rate_updated: 0,
                 bbr_state_changed: 0,
+                datagram_frame_dropped: 0,
                 version_information: 0,
                 endpoint_packet_sent: 0,
                 endpoint_packet_received: 0,
@@ -6826,6 +7000,17 @@ pub mod testing {
                 self.output.push(format!("{meta:?} {event:?}"));
             }
         }
+        fn on_datagram_frame_dropped(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::DatagramFrameDropped,
+        ) {
+            self.datagram_frame_dropped += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{meta:?} {event:?}"));
+            }
+        }
         fn on_version_information(
             &mut self,
             meta: &api::EndpointMeta,
@@ -6966,6 +7151,7 @@ pub mod testing {
         pub delivery_rate_sampled: u32,
         pub pacing_rate_updated: u32,
         pub bbr_state_changed: u32,
+        pub datagram_frame_dropped: u32,
         pub version_information: u32,
         pub endpoint_packet_sent: u32,
         pub endpoint_packet_received: u32,
@@ -7033,6 +7219,7 @@ pub mod testing {
                 delivery_rate_sampled: 0,
                 pacing_rate_updated: 0,
                 bbr_state_changed: 0,
+                datagram_frame_dropped: 0,
                 version_information: 0,
                 endpoint_packet_sent: 0,
                 endpoint_packet_received: 0,
@@ -7409,6 +7596,13 @@ pub mod testing {
                 self.output.push(format!("{event:?}"));
             }
         }
+        fn on_datagram_frame_dropped(&mut self, event: builder::DatagramFrameDropped) {
+            self.datagram_frame_dropped += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{event:?}"));
+            }
+        }
         fn quic_version(&self) -> u32 {
             1
         }
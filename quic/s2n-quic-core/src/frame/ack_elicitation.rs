@@ -64,6 +64,7 @@ impl<AckRanges> AckElicitable for crate::frame::Ack<AckRanges> {
         AckElicitation::NonEliciting
     }
 }
+impl AckElicitable for crate::frame::AckFrequency {}
 impl AckElicitable for crate::frame::ConnectionClose<'_> {
     #[inline]
     fn ack_elicitation(&self) -> AckElicitation {
@@ -77,6 +78,7 @@ impl<Data> AckElicitable for crate::frame::Crypto<Data> {}
 impl<Data> AckElicitable for crate::frame::Datagram<Data> {}
 impl AckElicitable for crate::frame::DataBlocked {}
 impl AckElicitable for crate::frame::HandshakeDone {}
+impl AckElicitable for crate::frame::ImmediateAck {}
 impl AckElicitable for crate::frame::MaxData {}
 impl AckElicitable for crate::frame::MaxStreamData {}
 impl AckElicitable for crate::frame::MaxStreams {}
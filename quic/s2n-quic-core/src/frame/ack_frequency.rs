@@ -0,0 +1,63 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::varint::VarInt;
+
+// See https://datatracker.ietf.org/doc/html/draft-ietf-quic-ack-frequency#section-4
+//
+// Delaying acknowledgements as much as possible reduces both work done by the
+// endpoints and network load. An endpoint's loss detection and congestion
+// control mechanisms however need to be tolerant of this delay at the peer.
+// An endpoint signals its tolerance by sending an ACK_FREQUENCY frame (type=0xaf).
+//
+// Unlike the RFC 9000 frames, the frame type doesn't fit in a single byte varint
+// and is encoded with 2 bytes.
+
+macro_rules! ack_frequency_tag {
+    () => {
+        0xafu8
+    };
+}
+
+// ACK_FREQUENCY Frame {
+//   Type (i) = 0xaf,
+//   Sequence Number (i),
+//   Ack-Eliciting Threshold (i),
+//   Request Max Ack Delay (i),
+//   Reordering Threshold (i),
+// }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AckFrequency {
+    /// A sequence number assigned to the ACK_FREQUENCY frame by the sender
+    /// to allow receivers to ignore obsolete frames.
+    pub sequence_number: VarInt,
+
+    /// The maximum number of ack-eliciting packets the recipient of this
+    /// frame receives before sending an acknowledgment.
+    pub ack_eliciting_threshold: VarInt,
+
+    /// The value to which the endpoint requests the peer update its
+    /// `max_ack_delay`, in microseconds.
+    pub request_max_ack_delay: VarInt,
+
+    /// The maximum packet reordering before eliciting an immediate ACK.
+    /// A value of 0 indicates no immediate ACK is sent for out-of-order packets.
+    pub reordering_threshold: VarInt,
+}
+
+impl AckFrequency {
+    pub const fn tag(self) -> u8 {
+        ack_frequency_tag!()
+    }
+}
+
+simple_frame_codec!(
+    AckFrequency {
+        sequence_number,
+        ack_eliciting_threshold,
+        request_max_ack_delay,
+        reordering_threshold
+    },
+    VarInt::from_u8(ack_frequency_tag!())
+);
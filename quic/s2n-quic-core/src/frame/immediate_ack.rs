@@ -0,0 +1,30 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+// See https://datatracker.ietf.org/doc/html/draft-ietf-quic-ack-frequency#section-5
+//
+// A sender can use an ACK_FREQUENCY frame to reduce the number of
+// acknowledgements sent by a receiver, but doing so increases the time taken
+// to detect losses. An IMMEDIATE_ACK frame (type=0x1f) asks the peer to send
+// an acknowledgement immediately, for instance when probing or during recovery.
+
+macro_rules! immediate_ack_tag {
+    () => {
+        0x1fu8
+    };
+}
+
+// IMMEDIATE_ACK Frame {
+//   Type (i) = 0x1f,
+// }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ImmediateAck;
+
+impl ImmediateAck {
+    pub const fn tag(self) -> u8 {
+        immediate_ack_tag!()
+    }
+}
+
+simple_frame_codec!(ImmediateAck {}, immediate_ack_tag!());
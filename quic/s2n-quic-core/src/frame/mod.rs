@@ -161,14 +161,23 @@ macro_rules! frames {
                 &mut self,
                 buffer: DecoderBufferMut<'a>,
             ) -> DecoderBufferMutResult<'a, Self::Output> {
-                let tag = buffer.peek_byte(0)?;
+                let (tag, tag_len) = match buffer.peek_byte(0)? {
+                    // Single byte frame tags fit into a small variable-integer
+                    tag @ 0b0000_0000..=0b0011_1111 => (tag, 1),
+                    // Extension frames, like ACK_FREQUENCY, can use two byte tags. Only the
+                    // minimal encoding of tags which fit in a `Tag` is supported here;
+                    // anything else falls back to extension selection.
+                    0b0100_0000 => match buffer.peek_byte(1)? {
+                        tag @ 0b0100_0000..=0xff => (tag, 2),
+                        _ => return self.handle_extension_frame(buffer),
+                    },
+                    _ => return self.handle_extension_frame(buffer),
+                };
+
                 match tag {
-                    // Make sure the single byte frame tags fit into a small variable-integer
-                    // otherwise fallback to extension selection
-                    0b0100_0000..=0xff => self.handle_extension_frame(buffer),
                     $(
                         $tag_macro!() => {
-                            let buffer = buffer.skip(core::mem::size_of::<Tag>())?;
+                            let buffer = buffer.skip(tag_len)?;
                             let (frame, buffer) = buffer.decode_parameterized(tag)?;
                             let output = self.$handler(frame)?;
                             Ok((output, buffer))
@@ -255,6 +264,8 @@ frames! {
     connection_close_tag => connection_close, handle_connection_close_frame, ConnectionClose['a];
     handshake_done_tag => handshake_done, handle_handshake_done_frame, HandshakeDone;
     datagram_tag => datagram, handle_datagram_frame, Datagram[Data];
+    ack_frequency_tag => ack_frequency, handle_ack_frequency_frame, AckFrequency;
+    immediate_ack_tag => immediate_ack, handle_immediate_ack_frame, ImmediateAck;
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -546,6 +546,16 @@ impl Strategy for Frame {
                 }
                 .into()
             },
+            |rand, _data, _cap| {
+                frame::AckFrequency {
+                    sequence_number: rand.gen_varint(),
+                    ack_eliciting_threshold: rand.gen_varint(),
+                    request_max_ack_delay: rand.gen_varint(),
+                    reordering_threshold: rand.gen_varint(),
+                }
+                .into()
+            },
+            |_rand, _data, _cap| frame::ImmediateAck.into(),
         ];
 
         let index = rand.gen_range(0..frames.len() as u64) as usize;
@@ -0,0 +1,155 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use core::fmt;
+
+/// Computes the Internet checksum used by IPv4, UDP and the other Internet protocols
+///
+/// The checksum is the 16-bit one's complement of the one's complement sum of all 16-bit
+/// words in the input. Input can be written in any number of calls, split at arbitrary
+/// byte boundaries.
+///
+/// See [RFC 1071](https://www.rfc-editor.org/rfc/rfc1071) for more details.
+#[derive(Clone, Copy, Default)]
+pub struct Checksum {
+    state: u64,
+    /// A byte left over from a previous write which still needs to be paired
+    trailing: Option<u8>,
+}
+
+impl fmt::Debug for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Checksum")
+            .field(&format_args!("0x{:04x}", self.finish()))
+            .finish()
+    }
+}
+
+impl Checksum {
+    /// Writes a slice of bytes to the checksum
+    #[inline]
+    pub fn write(&mut self, mut bytes: &[u8]) {
+        if let Some(high) = self.trailing.take() {
+            if let Some((low, remaining)) = bytes.split_first() {
+                self.write_u16(u16::from_be_bytes([high, *low]));
+                bytes = remaining;
+            } else {
+                self.trailing = Some(high);
+                return;
+            }
+        }
+
+        let mut chunks = bytes.chunks_exact(2);
+
+        for chunk in &mut chunks {
+            self.state += u16::from_be_bytes([chunk[0], chunk[1]]) as u64;
+        }
+
+        if let [byte] = chunks.remainder() {
+            self.trailing = Some(*byte);
+        }
+    }
+
+    /// Writes a single 16-bit word to the checksum
+    #[inline]
+    pub fn write_u16(&mut self, value: u16) {
+        debug_assert!(
+            self.trailing.is_none(),
+            "words should be written on an even byte boundary"
+        );
+        self.state += value as u64;
+    }
+
+    /// Writes a 32-bit value to the checksum as two 16-bit words
+    #[inline]
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_u16((value >> 16) as u16);
+        self.write_u16(value as u16);
+    }
+
+    /// Returns the checksum of all of the written input
+    ///
+    /// When the input included a previously computed checksum, a valid result is `0`.
+    #[inline]
+    pub fn finish(&self) -> u16 {
+        let mut state = self.state;
+
+        // an odd number of bytes is padded with a zero byte
+        if let Some(byte) = self.trailing {
+            state += (byte as u64) << 8;
+        }
+
+        // fold the carries back into the lower 16 bits
+        while state >> 16 != 0 {
+            state = (state & 0xffff) + (state >> 16);
+        }
+
+        !(state as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bolero::check;
+
+    #[test]
+    fn rfc_example_test() {
+        // See https://www.rfc-editor.org/rfc/rfc1071#section-3
+        let mut checksum = Checksum::default();
+        checksum.write(&[0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7]);
+        assert_eq!(checksum.finish(), !0xddf2);
+    }
+
+    #[test]
+    fn ipv4_header_test() {
+        // Example IPv4 header with a valid checksum of 0xb861
+        let header = [
+            0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0xb8, 0x61, 0xc0, 0xa8,
+            0x00, 0x01, 0xc0, 0xa8, 0x00, 0xc7,
+        ];
+
+        let mut checksum = Checksum::default();
+        checksum.write(&header);
+        assert_eq!(checksum.finish(), 0);
+
+        let mut checksum = Checksum::default();
+        checksum.write(&header[..10]);
+        checksum.write(&header[12..]);
+        assert_eq!(checksum.finish(), 0xb861);
+    }
+
+    #[test]
+    fn split_write_test() {
+        check!()
+            .with_type::<(Vec<u8>, usize)>()
+            .cloned()
+            .for_each(|(bytes, split)| {
+                let mut expected = Checksum::default();
+                expected.write(&bytes);
+
+                let split = split.checked_rem(bytes.len() + 1).unwrap_or(0);
+                let (a, b) = bytes.split_at(split);
+                let mut actual = Checksum::default();
+                actual.write(a);
+                actual.write(b);
+
+                assert_eq!(expected.finish(), actual.finish());
+            });
+    }
+
+    #[test]
+    fn round_trip_test() {
+        check!().for_each(|bytes| {
+            // only even-length inputs can have the checksum appended as a word
+            let bytes = &bytes[..bytes.len() & !1];
+
+            let mut checksum = Checksum::default();
+            checksum.write(bytes);
+            let value = checksum.finish();
+
+            checksum.write_u16(value);
+            assert_eq!(checksum.finish(), 0);
+        });
+    }
+}
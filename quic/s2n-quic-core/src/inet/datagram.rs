@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    connection, inet::ExplicitCongestionNotification, path::LocalAddress, time::Timestamp,
+    connection,
+    inet::{Dscp, ExplicitCongestionNotification},
+    path::LocalAddress,
+    time::Timestamp,
 };
 
 /// Header information for a datagram sent/received over the network
@@ -24,12 +27,20 @@ pub struct DatagramInfo {
 
 /// Additional metadata for a datagram sent/received over the network
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
 pub struct AncillaryData {
     pub ecn: ExplicitCongestionNotification,
+    /// The Differentiated Services Code Point, which is read from the same field as the ECN
+    /// markings
+    pub dscp: Dscp,
     pub local_address: LocalAddress,
     /// The network interface the datagram is sent/received on
     ///
     /// Correctly threading this value through to connections ensures packets end up on the same
     /// network interfaces and thereby have consistent MAC addresses.
     pub local_interface: Option<u32>,
+    /// The size of each segment in a payload that was coalesced with Generic Receive Offload
+    ///
+    /// If the value is `None`, the payload contains a single datagram.
+    pub segment_size: Option<usize>,
 }
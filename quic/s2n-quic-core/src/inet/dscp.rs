@@ -0,0 +1,132 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use core::{convert::TryFrom, fmt};
+
+#[cfg(feature = "generator")]
+use bolero_generator::*;
+
+/// Differentiated Services Code Point
+///
+/// The DSCP occupies the most-significant 6 bits of the IPv4 TOS and IPv6 Traffic Class fields,
+/// with the remaining 2 bits used for [`ExplicitCongestionNotification`](super::ExplicitCongestionNotification).
+/// Networks use it to select the per-hop behavior applied to a packet.
+///
+/// See https://www.rfc-editor.org/rfc/rfc2474#section-3
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "generator", derive(TypeGenerator))]
+pub struct Dscp(#[cfg_attr(feature = "generator", generator(0..64))] u8);
+
+impl Dscp {
+    const MAX: u8 = 0b11_1111;
+
+    /// The default forwarding behavior
+    pub const DEFAULT: Self = Self(0);
+
+    /// Lower effort, for traffic which should yield to best-effort traffic
+    ///
+    /// See https://www.rfc-editor.org/rfc/rfc8622
+    pub const LE: Self = Self(1);
+
+    pub const CS1: Self = Self(8);
+    pub const CS2: Self = Self(16);
+    pub const CS3: Self = Self(24);
+    pub const CS4: Self = Self(32);
+    pub const CS5: Self = Self(40);
+    pub const CS6: Self = Self(48);
+    pub const CS7: Self = Self(56);
+
+    pub const AF11: Self = Self(10);
+    pub const AF12: Self = Self(12);
+    pub const AF13: Self = Self(14);
+    pub const AF21: Self = Self(18);
+    pub const AF22: Self = Self(20);
+    pub const AF23: Self = Self(22);
+    pub const AF31: Self = Self(26);
+    pub const AF32: Self = Self(28);
+    pub const AF33: Self = Self(30);
+    pub const AF41: Self = Self(34);
+    pub const AF42: Self = Self(36);
+    pub const AF43: Self = Self(38);
+
+    /// Expedited forwarding, for low-latency traffic
+    ///
+    /// See https://www.rfc-editor.org/rfc/rfc3246
+    pub const EF: Self = Self(46);
+
+    /// Creates a `Dscp` from a 6-bit code point
+    ///
+    /// Returns `None` if the value doesn't fit in 6 bits.
+    #[inline]
+    pub const fn new(value: u8) -> Option<Self> {
+        if value <= Self::MAX {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// Reads the code point from an IPv4 TOS or IPv6 Traffic Class field
+    #[inline]
+    pub const fn from_traffic_class(traffic_class: u8) -> Self {
+        Self(traffic_class >> 2)
+    }
+
+    /// Returns the 6-bit code point
+    #[inline]
+    pub const fn as_u8(self) -> u8 {
+        self.0
+    }
+
+    /// Returns true if the code point is not the default
+    #[inline]
+    pub const fn is_set(self) -> bool {
+        self.0 != 0
+    }
+}
+
+impl TryFrom<u8> for Dscp {
+    type Error = InvalidDscp;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or(InvalidDscp(value))
+    }
+}
+
+impl From<Dscp> for u8 {
+    #[inline]
+    fn from(dscp: Dscp) -> Self {
+        dscp.0
+    }
+}
+
+/// The value is larger than a 6-bit DSCP
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidDscp(u8);
+
+impl fmt::Display for InvalidDscp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is not a valid DSCP value", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidDscp {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        for value in 0..=u8::MAX {
+            let dscp = Dscp::try_from(value);
+            if value < 64 {
+                assert_eq!(dscp.unwrap().as_u8(), value);
+            } else {
+                assert_eq!(dscp, Err(InvalidDscp(value)));
+            }
+        }
+    }
+}
@@ -6,7 +6,9 @@
 #[macro_use]
 mod macros;
 
+pub mod checksum;
 pub mod datagram;
+pub mod dscp;
 pub mod ecn;
 pub mod ethernet;
 pub mod ip;
@@ -16,6 +18,7 @@ pub mod udp;
 pub mod unspecified;
 
 pub use datagram::*;
+pub use dscp::*;
 pub use ecn::*;
 pub use ip::*;
 pub use ipv4::*;
@@ -37,4 +37,20 @@ pub trait Entry {
         &mut self,
         local_address: &path::LocalAddress,
     ) -> Option<(datagram::Header<Self::Handle>, &mut [u8])>;
+
+    /// Reads each of the datagrams contained in the entry
+    ///
+    /// Platforms that support Generic Receive Offload (GRO) may coalesce multiple datagrams from
+    /// the same peer into a single entry. Implementations should split these payloads and call
+    /// `on_datagram` for each segment. By default, the entry is assumed to contain a single
+    /// datagram.
+    #[inline]
+    fn read_segments<F>(&mut self, local_address: &path::LocalAddress, mut on_datagram: F)
+    where
+        F: FnMut(&datagram::Header<Self::Handle>, &mut [u8]),
+    {
+        if let Some((header, payload)) = self.read(local_address) {
+            on_datagram(&header, payload);
+        }
+    }
 }
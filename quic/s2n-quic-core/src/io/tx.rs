@@ -1,7 +1,10 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{inet::ExplicitCongestionNotification, path};
+use crate::{
+    inet::{Dscp, ExplicitCongestionNotification},
+    path,
+};
 use core::time::Duration;
 
 /// A structure capable of queueing and transmitting messages
@@ -42,6 +45,15 @@ pub trait Queue {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns the longest [`Message::delay`] the queue will hold a message for before
+    /// transmitting it
+    ///
+    /// Queues which offload pacing to the platform allow messages to be pushed before they
+    /// are due to depart. Otherwise, messages are transmitted as soon as possible.
+    fn max_delay(&self) -> Duration {
+        Duration::ZERO
+    }
 }
 
 pub struct Outcome {
@@ -90,9 +102,18 @@ pub trait Message {
     /// Returns the ECN markings for the message
     fn ecn(&mut self) -> ExplicitCongestionNotification;
 
+    /// Returns the Differentiated Services Code Point for the message
+    ///
+    /// Messages are sent with [`Dscp::DEFAULT`] unless this is overridden.
+    #[inline]
+    fn dscp(&mut self) -> Dscp {
+        Dscp::DEFAULT
+    }
+
     /// Returns the Duration for which the message will be delayed.
     ///
-    /// This is used in scenarios where packets need to be paced.
+    /// This is used in scenarios where packets need to be paced. Queues call this before
+    /// `write_payload`, since writing the payload may advance the pacer.
     fn delay(&mut self) -> Duration;
 
     /// Returns the IPv6 flow label for the message
@@ -193,6 +214,7 @@ mod tests {
 
         assert_eq!(*message.path_handle(), tuple);
         assert_eq!(message.ecn(), Default::default());
+        assert_eq!(message.dscp(), Default::default());
         assert_eq!(message.delay(), Default::default());
         assert_eq!(message.ipv6_flow_label(), 0);
         assert_eq!(
@@ -31,6 +31,7 @@ macro_rules! assume {
 }
 
 pub mod ack;
+pub mod anti_replay;
 pub mod application;
 #[cfg(feature = "alloc")]
 pub mod buffer;
@@ -54,6 +55,8 @@ pub mod path;
 pub mod query;
 pub mod random;
 pub mod recovery;
+#[cfg(feature = "alloc")]
+pub mod session_ticket;
 pub mod slice;
 pub mod stateless_reset;
 pub mod stream;
@@ -63,6 +66,7 @@ pub mod token;
 pub mod transmission;
 pub mod transport;
 pub mod varint;
+pub mod xdp;
 
 #[cfg(any(test, feature = "testing"))]
 pub mod testing;
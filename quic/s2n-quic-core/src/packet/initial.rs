@@ -7,7 +7,7 @@ use crate::{
         decoding::HeaderDecoder,
         encoding::{PacketEncoder, PacketPayloadEncoder},
         long::{
-            DestinationConnectionIdLen, LongPayloadEncoder, LongPayloadLenCursor,
+            DestinationConnectionIdLen, LongPayloadEncoder, LongPayloadLenCursor, PacketType,
             SourceConnectionIdLen, Version,
         },
         number::{
@@ -250,7 +250,7 @@ impl<DCID: EncoderValue, SCID: EncoderValue, Token: EncoderValue, PacketNumber,
     Initial<DCID, SCID, Token, PacketNumber, Payload>
 {
     fn encode_header<E: Encoder>(&self, packet_number_len: PacketNumberLen, encoder: &mut E) {
-        let mut tag: u8 = initial_tag!() << 4;
+        let mut tag: u8 = PacketType::Initial.into_header_tag(self.version);
         tag |= packet_number_len.into_packet_tag_mask();
         tag.encode(encoder);
 
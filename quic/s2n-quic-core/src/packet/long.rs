@@ -49,6 +49,16 @@ const PACKET_TYPE_OFFSET: u8 = 4;
 
 pub(crate) type Version = u32;
 
+/// QUIC version 1
+///
+/// See <https://www.rfc-editor.org/rfc/rfc9000#section-15>
+pub const VERSION_1: u32 = 0x0000_0001;
+
+/// QUIC version 2
+///
+/// See <https://www.rfc-editor.org/rfc/rfc9369#section-3.1>
+pub const VERSION_2: u32 = 0x6b33_43cf;
+
 //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2
 //# Destination Connection ID Length:  The byte following the version
 //#    contains the length in bytes of the Destination Connection ID
@@ -134,7 +144,7 @@ pub(crate) fn validate_source_connection_id_len(len: usize) -> Result<(), Decode
 //#                   Table 5: Long Header Packet Types
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PacketType {
     Initial = 0x0,
     ZeroRtt = 0x1,
@@ -150,8 +160,44 @@ impl PacketType {
     pub fn from_bits(bits: u8) -> Self {
         (bits & PACKET_TYPE_MASK >> PACKET_TYPE_OFFSET).into()
     }
+
+    /// Returns the long packet type bits for the packet type in the given `version`
+    ///
+    /// QUIC version 2 shifts the packet type codepoints, as described in
+    /// <https://www.rfc-editor.org/rfc/rfc9369#section-3.2>.
+    #[inline]
+    pub const fn into_version_bits(self, version: u32) -> u8 {
+        let bits = self as u8;
+        let bits = if version == VERSION_2 {
+            (bits + 1) % 4
+        } else {
+            bits
+        };
+        bits << PACKET_TYPE_OFFSET & PACKET_TYPE_MASK
+    }
+
+    /// Returns the packet type from the first byte of a long header packet in the given `version`
+    #[inline]
+    pub fn from_version_bits(version: u32, bits: u8) -> Self {
+        let bits = (bits & PACKET_TYPE_MASK) >> PACKET_TYPE_OFFSET;
+        if version == VERSION_2 {
+            ((bits + 3) % 4).into()
+        } else {
+            bits.into()
+        }
+    }
+
+    /// Returns the first byte of a long header packet of this type in the given `version`,
+    /// without any of the type-specific bits set
+    #[inline]
+    pub(crate) const fn into_header_tag(self, version: u32) -> u8 {
+        LONG_HEADER_TAG | self.into_version_bits(version)
+    }
 }
 
+// Header Form and Fixed Bit set
+const LONG_HEADER_TAG: u8 = 0b1100_0000;
+
 impl From<u8> for PacketType {
     fn from(bits: u8) -> Self {
         match bits {
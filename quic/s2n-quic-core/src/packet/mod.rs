@@ -212,15 +212,10 @@ pub trait PacketDecoder<'a> {
         }
 
         macro_rules! long_packet {
-            ($struct:ident, $handler:ident) => {{
-                let (version, _peek) = peek.decode()?;
-                if version == version_negotiation::VERSION {
-                    version_negotiation!(version)
-                } else {
-                    let (packet, buffer) = $struct::decode(tag, version, buffer)?;
-                    let output = self.$handler(packet)?;
-                    Ok((output, buffer))
-                }
+            ($struct:ident, $handler:ident, $version:ident) => {{
+                let (packet, buffer) = $struct::decode(tag, $version, buffer)?;
+                let output = self.$handler(packet)?;
+                Ok((output, buffer))
             }};
         }
 
@@ -243,10 +238,28 @@ pub trait PacketDecoder<'a> {
                 );
                 version_negotiation!(version)
             }
-            initial_tag!() => long_packet!(ProtectedInitial, handle_initial_packet),
-            zero_rtt_tag!() => long_packet!(ProtectedZeroRtt, handle_zero_rtt_packet),
-            handshake_tag!() => long_packet!(ProtectedHandshake, handle_handshake_packet),
-            retry_tag!() => long_packet!(ProtectedRetry, handle_retry_packet),
+            initial_tag!() | zero_rtt_tag!() | handshake_tag!() | retry_tag!() => {
+                let (version, _peek) = peek.decode()?;
+                if version == version_negotiation::VERSION {
+                    return version_negotiation!(version);
+                }
+
+                // the long packet type codepoints depend on the version
+                match long::PacketType::from_version_bits(version, tag) {
+                    long::PacketType::Initial => {
+                        long_packet!(ProtectedInitial, handle_initial_packet, version)
+                    }
+                    long::PacketType::ZeroRtt => {
+                        long_packet!(ProtectedZeroRtt, handle_zero_rtt_packet, version)
+                    }
+                    long::PacketType::Handshake => {
+                        long_packet!(ProtectedHandshake, handle_handshake_packet, version)
+                    }
+                    long::PacketType::Retry => {
+                        long_packet!(ProtectedRetry, handle_retry_packet, version)
+                    }
+                }
+            }
             _ => Err(DecoderError::InvariantViolation("invalid packet").into()),
         }
     }
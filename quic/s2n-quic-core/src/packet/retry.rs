@@ -12,7 +12,7 @@ use crate::{
     packet::{
         decoding::HeaderDecoder,
         initial::ProtectedInitial,
-        long::{DestinationConnectionIdLen, SourceConnectionIdLen, Version},
+        long::{DestinationConnectionIdLen, PacketType, SourceConnectionIdLen, Version},
         Tag,
     },
     random, token,
@@ -209,7 +209,7 @@ impl<'a> Retry<'a> {
             // The last 4 bits are unused. They are set to 0x0f here to allow easy testing with
             // example packets provided in the RFC.
             // https://www.rfc-editor.org/rfc/rfc9001#section-A.2
-            tag: (retry_tag!() << 4) | 0x0f,
+            tag: PacketType::Retry.into_header_tag(initial_packet.version) | 0x0f,
             version: initial_packet.version,
             destination_connection_id: initial_packet.source_connection_id(),
             source_connection_id: local_connection_id,
@@ -379,6 +379,29 @@ mod tests {
         assert_eq!(packet.version, retry::example::VERSION);
     }
 
+    #[test]
+    fn test_decode_version_2() {
+        let mut buf = retry::example::PACKET_V2;
+        let decoder = DecoderBufferMut::new(&mut buf);
+        let remote_address = inet::ip::SocketAddress::default();
+        let connection_info = connection::id::ConnectionInfo::new(&remote_address);
+        let (packet, _) = packet::ProtectedPacket::decode(decoder, &connection_info, &20).unwrap();
+        let packet = match packet {
+            packet::ProtectedPacket::Retry(retry) => retry,
+            _ => panic!("expected retry packet type"),
+        };
+
+        assert_eq!(packet.retry_integrity_tag, &retry::example::EXPECTED_TAG_V2);
+        assert_eq!(packet.retry_token, retry::example::TOKEN);
+        assert_eq!(packet.version, packet::long::VERSION_2);
+
+        // the packet should encode back to the original bytes
+        let mut buf = [0; retry::example::PACKET_LEN];
+        let mut encoder = EncoderBuffer::new(&mut buf);
+        packet.encode(&mut encoder);
+        assert_eq!(retry::example::PACKET_V2[..], buf[..]);
+    }
+
     #[test]
     fn test_decode_no_token() {
         //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.5.2
@@ -168,3 +168,33 @@ fn encode_packet<'a>(packet: CleartextPacket, mut encoder: EncoderBuffer<'a>) ->
         Err(err) => err.take_buffer(),
     }
 }
+
+#[test]
+fn version_packet_types_test() {
+    use crate::packet::long::{PacketType, VERSION_1, VERSION_2};
+
+    let packet_types = [
+        PacketType::Initial,
+        PacketType::ZeroRtt,
+        PacketType::Handshake,
+        PacketType::Retry,
+    ];
+
+    for packet_type in packet_types {
+        assert_eq!(
+            packet_type.into_version_bits(VERSION_1),
+            packet_type.into_bits()
+        );
+
+        for version in [VERSION_1, VERSION_2] {
+            let tag = packet_type.into_header_tag(version);
+            assert_eq!(PacketType::from_version_bits(version, tag), packet_type);
+        }
+    }
+
+    // See https://www.rfc-editor.org/rfc/rfc9369#section-3.2
+    assert_eq!(PacketType::Initial.into_version_bits(VERSION_2), 0x10);
+    assert_eq!(PacketType::ZeroRtt.into_version_bits(VERSION_2), 0x20);
+    assert_eq!(PacketType::Handshake.into_version_bits(VERSION_2), 0x30);
+    assert_eq!(PacketType::Retry.into_version_bits(VERSION_2), 0x00);
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    event,
+    connection, event,
     event::{
         api::{Path, SocketAddress},
         IntoEvent,
@@ -103,6 +103,48 @@ impl IntoEvent<event::builder::ConnectionMigrationDenied> for DenyReason {
     }
 }
 
+/// Reasons a migration to a new local address, initiated by the application, failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Only clients are able to initiate a migration
+    NotClient,
+    /// The handshake has not been confirmed
+    HandshakeNotConfirmed,
+    /// The peer disabled active migration with the `disable_active_migration` transport
+    /// parameter
+    DisabledByPeer,
+    /// The IO provider is unable to send packets from the requested local address
+    Unsupported,
+    /// The connection already has a path for the requested local address
+    PathExists,
+    /// The maximum number of paths for the connection has been reached
+    PathLimitExceeded,
+    /// The peer has not issued enough connection IDs to use a new path
+    InsufficientConnectionIds,
+    /// The connection encountered an error
+    Connection(connection::Error),
+}
+
+impl From<connection::Error> for Error {
+    #[inline]
+    fn from(error: connection::Error) -> Self {
+        Self::Connection(error)
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Connection(error) => error.fmt(f),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 /// Validates a path migration attempt from an active path to another
 pub trait Validator: 'static + Send {
     /// Called on each connection migration attempt for a connection
@@ -16,6 +16,7 @@ use core::{
 use bolero_generator::*;
 
 pub mod migration;
+pub mod preferred_address;
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-14
 //# QUIC MUST NOT be used if the network path cannot support a
@@ -100,6 +101,17 @@ pub trait Handle: 'static + Copy + Send + fmt::Debug {
     /// Returns `true` if the two handles are strictly equal to each other, i.e.
     /// byte-for-byte.
     fn strict_eq(&self, other: &Self) -> bool;
+
+    /// Returns a handle for the same remote address that sends from `local_address`
+    ///
+    /// Returns `None` if the handle is unable to select the local address it sends from, in
+    /// which case the connection is unable to migrate to another local address. Handles don't
+    /// support migration unless they override this method.
+    #[inline]
+    fn with_local_address(&self, local_address: LocalAddress) -> Option<Self> {
+        let _ = local_address;
+        None
+    }
 }
 
 macro_rules! impl_addr {
@@ -221,6 +233,14 @@ impl Handle for Tuple {
     fn strict_eq(&self, other: &Self) -> bool {
         PartialEq::eq(self, other)
     }
+
+    #[inline]
+    fn with_local_address(&self, local_address: LocalAddress) -> Option<Self> {
+        Some(Self {
+            remote_address: self.remote_address,
+            local_address,
+        })
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
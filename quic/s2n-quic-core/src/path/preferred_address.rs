@@ -0,0 +1,97 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Selects the addresses a server advertises in the `preferred_address` transport parameter
+
+use crate::{
+    inet::{SocketAddressV4, SocketAddressV6, Unspecified},
+    path::RemoteAddress,
+};
+
+//= https://www.rfc-editor.org/rfc/rfc9000#section-9.6.1
+//# Servers MAY communicate a preferred address of each address family
+//# (IPv4 and IPv6) to allow clients to pick the one most suited to their
+//# network attachment.
+
+/// The preferred server addresses for a connection
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Addresses {
+    ipv4: Option<SocketAddressV4>,
+    ipv6: Option<SocketAddressV6>,
+}
+
+impl Addresses {
+    /// Sets the preferred IPv4 address
+    #[inline]
+    pub fn with_ipv4<A: Into<SocketAddressV4>>(mut self, address: A) -> Self {
+        self.ipv4 = Some(address.into()).filter(|address| !address.is_unspecified());
+        self
+    }
+
+    /// Sets the preferred IPv6 address
+    #[inline]
+    pub fn with_ipv6<A: Into<SocketAddressV6>>(mut self, address: A) -> Self {
+        self.ipv6 = Some(address.into()).filter(|address| !address.is_unspecified());
+        self
+    }
+
+    /// Returns the preferred IPv4 address
+    #[inline]
+    pub fn ipv4(&self) -> Option<SocketAddressV4> {
+        self.ipv4
+    }
+
+    /// Returns the preferred IPv6 address
+    #[inline]
+    pub fn ipv6(&self) -> Option<SocketAddressV6> {
+        self.ipv6
+    }
+}
+
+impl Unspecified for Addresses {
+    #[inline]
+    fn is_unspecified(&self) -> bool {
+        self.ipv4.is_none() && self.ipv6.is_none()
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Info<'a> {
+    /// The address of the client that is connecting
+    pub remote_address: &'a RemoteAddress,
+}
+
+impl<'a> Info<'a> {
+    #[inline]
+    pub fn new(remote_address: &'a RemoteAddress) -> Self {
+        Self { remote_address }
+    }
+}
+
+/// Selects the preferred addresses to advertise to a client
+pub trait Selector: 'static + Send {
+    /// Called when a server accepts a new connection
+    ///
+    /// Returning `None`, or addresses that are all unspecified, will not advertise a
+    /// preferred address to the client.
+    fn on_connection(&mut self, info: &Info) -> Option<Addresses>;
+}
+
+impl Selector for Addresses {
+    #[inline]
+    fn on_connection(&mut self, _info: &Info) -> Option<Addresses> {
+        Some(*self).filter(|addresses| !addresses.is_unspecified())
+    }
+}
+
+/// Doesn't advertise a preferred address
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Disabled(());
+
+impl Selector for Disabled {
+    #[inline]
+    fn on_connection(&mut self, _info: &Info) -> Option<Addresses> {
+        None
+    }
+}
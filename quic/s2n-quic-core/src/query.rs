@@ -116,7 +116,7 @@ where
 }
 
 #[non_exhaustive]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// Reason for the failed query.
 pub enum Error {
     /// The connection lock is poisoned and the connection unusable.
@@ -61,6 +61,14 @@ const HEADROOM: Ratio<u64> = Ratio::new_raw(85, 100);
 //# that follow an "ACK every other packet" delayed-ACK policy: 4 * SMSS.
 const MIN_PIPE_CWND_PACKETS: u16 = 4;
 
+/// The number of acknowledgements per round trip requested from peers supporting the
+/// ACK frequency extension
+const ACKS_PER_ROUND: u32 = 4;
+
+/// The largest number of ack-eliciting packets the peer is asked to receive before
+/// sending an acknowledgement
+const MAX_ACK_ELICITING_THRESHOLD: u32 = 64;
+
 //= https://tools.ietf.org/id/draft-cardwell-iccrg-bbr-congestion-control-02#4.1.1
 //# The following state transition diagram summarizes the flow of control and the relationship between the different states:
 //#
@@ -532,6 +540,20 @@ impl CongestionController for BbrCongestionController {
     fn send_quantum(&self) -> Option<usize> {
         Some(self.pacer.send_quantum())
     }
+
+    #[inline]
+    fn ack_eliciting_threshold(&self) -> Option<u32> {
+        if self.recovery_state.in_recovery() {
+            // Acknowledgements drive packet conservation and retransmissions while
+            // recovering, so request them as soon as possible
+            return Some(0);
+        }
+
+        // Request a few acknowledgements per round trip, which keeps the delivery rate samples
+        // fresh while reducing the number of ACK frames the peer sends at high bandwidth
+        let packets_per_round = self.cwnd / self.max_datagram_size as u32;
+        Some((packets_per_round / ACKS_PER_ROUND).clamp(1, MAX_ACK_ELICITING_THRESHOLD))
+    }
 }
 
 impl BbrCongestionController {
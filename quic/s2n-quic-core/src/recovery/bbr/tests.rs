@@ -320,6 +320,28 @@ fn target_inflight() {
     assert_eq!(100000, bbr.target_inflight());
 }
 
+#[test]
+fn ack_eliciting_threshold() {
+    let mut bbr = BbrCongestionController::new(MINIMUM_MTU);
+
+    // initial_window = 10 packets, requesting 4 ACKs per round
+    assert_eq!(Some(2), bbr.ack_eliciting_threshold());
+
+    // the peer acknowledges at least every other packet
+    bbr.cwnd = 2 * MINIMUM_MTU as u32;
+    assert_eq!(Some(1), bbr.ack_eliciting_threshold());
+
+    bbr.cwnd = 1000 * MINIMUM_MTU as u32;
+    assert_eq!(Some(64), bbr.ack_eliciting_threshold());
+
+    // acknowledgements are requested without delay while in recovery
+    bbr.recovery_state = bbr::recovery::State::Recovering(
+        NoopClock.get_time(),
+        bbr::recovery::FastRetransmission::Idle,
+    );
+    assert_eq!(Some(0), bbr.ack_eliciting_threshold());
+}
+
 //= https://tools.ietf.org/id/draft-cardwell-iccrg-bbr-congestion-control-02#4.6.4.2
 //= type=test
 //# BBRUpdateMaxInflight()
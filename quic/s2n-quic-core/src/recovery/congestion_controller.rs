@@ -241,6 +241,25 @@ pub trait CongestionController: 'static + Clone + Send + Debug {
     fn send_quantum(&self) -> Option<usize> {
         None
     }
+
+    /// The number of ack-eliciting packets the peer may receive before it sends an acknowledgement.
+    ///
+    /// If the value is `None`, the congestion controller does not influence how often the peer
+    /// acknowledges packets. A value of `0` requests the peer acknowledges every ack-eliciting
+    /// packet without delay.
+    ///
+    /// This value only has an effect if the peer supports the ACK frequency extension.
+    /// See https://datatracker.ietf.org/doc/html/draft-ietf-quic-ack-frequency
+    fn ack_eliciting_threshold(&self) -> Option<u32> {
+        None
+    }
+    /// The ECN-capable transport codepoint used to mark packets on paths that support ECN
+    ///
+    /// Scalable congestion controllers mark packets ECT(1) to identify their traffic to
+    /// L4S-aware network queues. See https://www.rfc-editor.org/rfc/rfc9331
+    fn ect_codepoint(&self) -> inet::ExplicitCongestionNotification {
+        inet::ExplicitCongestionNotification::Ect0
+    }
 }
 
 #[cfg(any(test, feature = "testing"))]
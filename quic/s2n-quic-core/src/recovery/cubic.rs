@@ -604,14 +604,68 @@ impl CubicCongestionController {
         }
     }
 
+    /// Reduces the congestion window by the given `reduction` fraction in response to explicit
+    /// congestion
+    ///
+    /// This is used by scalable congestion controllers, which respond to the extent of congestion
+    /// rather than applying the CUBIC multiplicative decrease.
+    #[inline]
+    pub(crate) fn on_proportional_congestion_event<Pub: Publisher>(
+        &mut self,
+        reduction: f32,
+        event_time: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        if self.state.is_slow_start() {
+            publisher.on_slow_start_exited(SlowStartExitCause::Ecn, self.congestion_window());
+        }
+
+        if !self.enter_recovery(event_time) {
+            return;
+        }
+
+        self.congestion_window = self
+            .cubic
+            .proportional_decrease(self.congestion_window, reduction);
+
+        // Update Hybrid Slow Start with the decreased congestion window.
+        self.slow_start.on_congestion_event(self.congestion_window);
+    }
+
     #[inline]
     fn on_congestion_event(&mut self, event_time: Timestamp) {
+        if !self.enter_recovery(event_time) {
+            return;
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#section-7.3.2
+        //# Implementations MAY reduce the congestion window immediately upon
+        //# entering a recovery period or use other mechanisms, such as
+        //# Proportional Rate Reduction [PRR], to reduce the congestion window
+        //# more gradually.
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#section-7.2
+        //# The minimum congestion window is the smallest value the congestion
+        //# window can attain in response to loss, an increase in the peer-
+        //# reported ECN-CE count, or persistent congestion.
+        self.congestion_window = self.cubic.multiplicative_decrease(self.congestion_window);
+
+        // Update Hybrid Slow Start with the decreased congestion window.
+        self.slow_start.on_congestion_event(self.congestion_window);
+    }
+
+    /// Enters a recovery period in response to a congestion event
+    ///
+    /// Returns `false` if the controller is already in a recovery period, in which case the
+    /// congestion window should not be reduced again.
+    #[inline]
+    fn enter_recovery(&mut self, event_time: Timestamp) -> bool {
         // Reset bytes_in_flight_hi
         self.bytes_in_flight_hi = BytesInFlight::new(0);
 
         // No reaction if already in a recovery period.
         if matches!(self.state, Recovery(_, _)) {
-            return;
+            return false;
         }
 
         // Enter recovery period.
@@ -628,20 +682,7 @@ impl CubicCongestionController {
         //# similar to TCP as described in Section 5 of [RFC6675].
         self.state = Recovery(event_time, RequiresTransmission);
 
-        //= https://www.rfc-editor.org/rfc/rfc9002#section-7.3.2
-        //# Implementations MAY reduce the congestion window immediately upon
-        //# entering a recovery period or use other mechanisms, such as
-        //# Proportional Rate Reduction [PRR], to reduce the congestion window
-        //# more gradually.
-
-        //= https://www.rfc-editor.org/rfc/rfc9002#section-7.2
-        //# The minimum congestion window is the smallest value the congestion
-        //# window can attain in response to loss, an increase in the peer-
-        //# reported ECN-CE count, or persistent congestion.
-        self.congestion_window = self.cubic.multiplicative_decrease(self.congestion_window);
-
-        // Update Hybrid Slow Start with the decreased congestion window.
-        self.slow_start.on_congestion_event(self.congestion_window);
+        true
     }
 
     #[inline]
@@ -829,6 +870,23 @@ impl Cubic {
         cwnd_start
     }
 
+    /// Reduces the window by the given `reduction` fraction
+    ///
+    /// `W_max` is set to the window before the reduction so the window grows back to it within
+    /// the same time a CUBIC flow would take to recover the smaller reduction.
+    #[inline]
+    fn proportional_decrease(&mut self, cwnd: f32, reduction: f32) -> f32 {
+        self.w_max = self.bytes_to_packets(cwnd);
+        self.w_last_max = self.w_max;
+
+        let cwnd_start = (cwnd * (1.0 - reduction.clamp(0.0, 1.0))).max(self.minimum_window());
+
+        self.k =
+            Duration::from_secs_f32(((self.w_max - self.bytes_to_packets(cwnd_start)) / C).cbrt());
+
+        cwnd_start
+    }
+
     //= https://www.rfc-editor.org/rfc/rfc8312#section-4.8
     //# In the case when CUBIC runs the hybrid slow start [HR08], it may exit
     //# the first slow start without incurring any packet loss and thus W_max
@@ -0,0 +1,317 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A congestion controller with a fixed congestion window and optional fixed pacing rate
+//!
+//! This controller does not respond to loss or explicit congestion, so it should only be used on
+//! links with a known, dedicated capacity, such as satellite or lab links, or for testing. The
+//! congestion window should be set to the bandwidth-delay product of the link, and the pacing
+//! rate to the bandwidth of the link.
+
+use crate::{
+    counter::Counter,
+    random,
+    recovery::{
+        bandwidth::Bandwidth,
+        congestion_controller::{self, CongestionController, Publisher},
+        RttEstimator,
+    },
+    time::Timestamp,
+};
+use core::time::Duration;
+
+type BytesInFlight = Counter<u32>;
+
+#[derive(Clone, Debug)]
+pub struct FixedCongestionController {
+    max_datagram_size: u16,
+    congestion_window: u32,
+    /// The rate packets are paced at, if pacing is enabled
+    pacing_rate: Option<Bandwidth>,
+    bytes_in_flight: BytesInFlight,
+    /// The time the next packet should be transmitted
+    next_packet_departure_time: Option<Timestamp>,
+}
+
+impl CongestionController for FixedCongestionController {
+    type PacketInfo = ();
+
+    #[inline]
+    fn congestion_window(&self) -> u32 {
+        self.congestion_window
+    }
+
+    #[inline]
+    fn bytes_in_flight(&self) -> u32 {
+        *self.bytes_in_flight
+    }
+
+    #[inline]
+    fn is_congestion_limited(&self) -> bool {
+        let available_congestion_window =
+            self.congestion_window.saturating_sub(*self.bytes_in_flight);
+        available_congestion_window < self.max_datagram_size as u32
+    }
+
+    #[inline]
+    fn requires_fast_retransmission(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn on_packet_sent<Pub: Publisher>(
+        &mut self,
+        time_sent: Timestamp,
+        bytes_sent: usize,
+        _app_limited: Option<bool>,
+        _rtt_estimator: &RttEstimator,
+        _publisher: &mut Pub,
+    ) -> Self::PacketInfo {
+        if bytes_sent == 0 {
+            // Packet was not congestion controlled
+            return;
+        }
+
+        self.bytes_in_flight
+            .try_add(bytes_sent)
+            .expect("bytes sent should not exceed u32::MAX");
+
+        if let Some(pacing_rate) = self.pacing_rate {
+            // Packets are spaced by the time the link takes to transmit them. If the sender was
+            // idle, the next departure time is in the past, so start from the current time.
+            let departure_time = self
+                .next_packet_departure_time
+                .map_or(time_sent, |departure_time| departure_time.max(time_sent));
+            let interval: Duration = bytes_sent as u64 / pacing_rate;
+            self.next_packet_departure_time = Some(departure_time + interval);
+        }
+    }
+
+    #[inline]
+    fn on_rtt_update<Pub: Publisher>(
+        &mut self,
+        _time_sent: Timestamp,
+        _now: Timestamp,
+        _rtt_estimator: &RttEstimator,
+        _publisher: &mut Pub,
+    ) {
+    }
+
+    #[inline]
+    fn on_ack<Pub: Publisher>(
+        &mut self,
+        _newest_acked_time_sent: Timestamp,
+        bytes_acknowledged: usize,
+        _newest_acked_packet_info: Self::PacketInfo,
+        _rtt_estimator: &RttEstimator,
+        _random_generator: &mut dyn random::Generator,
+        _ack_receive_time: Timestamp,
+        _publisher: &mut Pub,
+    ) {
+        self.bytes_in_flight
+            .try_sub(bytes_acknowledged)
+            .expect("bytes_acknowledged should not exceed u32::MAX");
+    }
+
+    #[inline]
+    fn on_packet_lost<Pub: Publisher>(
+        &mut self,
+        lost_bytes: u32,
+        _packet_info: Self::PacketInfo,
+        _persistent_congestion: bool,
+        _new_loss_burst: bool,
+        _random_generator: &mut dyn random::Generator,
+        _timestamp: Timestamp,
+        _publisher: &mut Pub,
+    ) {
+        debug_assert!(lost_bytes > 0);
+
+        // The capacity of the link is known, so loss does not reduce the congestion window
+        self.bytes_in_flight -= lost_bytes;
+    }
+
+    #[inline]
+    fn on_explicit_congestion<Pub: Publisher>(
+        &mut self,
+        _ce_count: u64,
+        _event_time: Timestamp,
+        _publisher: &mut Pub,
+    ) {
+    }
+
+    #[inline]
+    fn on_mtu_update<Pub: Publisher>(&mut self, max_datagram_size: u16, _publisher: &mut Pub) {
+        // The congestion window is configured in bytes, so it is not scaled with the MTU
+        self.max_datagram_size = max_datagram_size;
+    }
+
+    #[inline]
+    fn on_packet_discarded<Pub: Publisher>(&mut self, bytes_sent: usize, _publisher: &mut Pub) {
+        self.bytes_in_flight
+            .try_sub(bytes_sent)
+            .expect("bytes sent should not exceed u32::MAX");
+    }
+
+    #[inline]
+    fn earliest_departure_time(&self) -> Option<Timestamp> {
+        self.next_packet_departure_time
+    }
+}
+
+impl FixedCongestionController {
+    pub fn new(
+        max_datagram_size: u16,
+        congestion_window: u32,
+        pacing_rate: Option<Bandwidth>,
+    ) -> Self {
+        Self {
+            max_datagram_size,
+            congestion_window,
+            pacing_rate,
+            bytes_in_flight: Counter::new(0),
+            next_packet_departure_time: None,
+        }
+    }
+}
+
+/// Creates congestion controllers with a fixed congestion window and optional pacing rate
+///
+/// ```rust
+/// use s2n_quic_core::recovery::fixed;
+///
+/// // A 100Mbps link with a 600ms round trip time
+/// let bytes_per_second = 100_000_000 / 8;
+/// let congestion_controller = fixed::Endpoint::new(bytes_per_second * 6 / 10)
+///     .with_pacing_rate(bytes_per_second as u64);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Endpoint {
+    congestion_window: u32,
+    pacing_rate: Option<Bandwidth>,
+}
+
+impl Endpoint {
+    /// Creates an endpoint with the given congestion window in bytes
+    ///
+    /// The congestion window should be set to the bandwidth-delay product of the link. Packets
+    /// are not paced unless a pacing rate is set with [`Self::with_pacing_rate`].
+    pub fn new(congestion_window: u32) -> Self {
+        Self {
+            congestion_window,
+            pacing_rate: None,
+        }
+    }
+
+    /// Paces packets at the given rate in bytes per second
+    ///
+    /// Rates above 1 GB/second can't be represented and disable pacing.
+    pub fn with_pacing_rate(mut self, bytes_per_second: u64) -> Self {
+        let pacing_rate = Bandwidth::new(bytes_per_second, Duration::from_secs(1));
+        self.pacing_rate = if pacing_rate == Bandwidth::INFINITY || pacing_rate == Bandwidth::ZERO {
+            None
+        } else {
+            Some(pacing_rate)
+        };
+        self
+    }
+}
+
+impl congestion_controller::Endpoint for Endpoint {
+    type CongestionController = FixedCongestionController;
+
+    fn new_congestion_controller(
+        &mut self,
+        path_info: congestion_controller::PathInfo,
+    ) -> Self::CongestionController {
+        FixedCongestionController::new(
+            path_info.max_datagram_size,
+            self.congestion_window,
+            self.pacing_rate,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event, path,
+        recovery::congestion_controller::PathPublisher,
+        time::{Clock, NoopClock},
+    };
+
+    const MAX_DATAGRAM_SIZE: u16 = 1200;
+
+    #[test]
+    fn ignores_congestion_test() {
+        let mut publisher = event::testing::Publisher::no_snapshot();
+        let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+        let rtt_estimator = RttEstimator::default();
+        let random = &mut random::testing::Generator::default();
+        let now = NoopClock.get_time();
+
+        let mut cc = FixedCongestionController::new(MAX_DATAGRAM_SIZE, 100_000, None);
+
+        for _ in 0..10 {
+            cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+        }
+        assert_eq!(cc.bytes_in_flight(), 12_000);
+
+        cc.on_packet_lost(1200, (), true, true, random, now, &mut publisher);
+        cc.on_explicit_congestion(1, now, &mut publisher);
+        cc.on_ack(now, 1200, (), &rtt_estimator, random, now, &mut publisher);
+
+        assert_eq!(cc.congestion_window(), 100_000);
+        assert_eq!(cc.bytes_in_flight(), 9_600);
+        assert!(!cc.requires_fast_retransmission());
+        assert_eq!(cc.earliest_departure_time(), None);
+    }
+
+    #[test]
+    fn pacing_test() {
+        let mut publisher = event::testing::Publisher::no_snapshot();
+        let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+        let rtt_estimator = RttEstimator::default();
+        let now = NoopClock.get_time();
+
+        // 1000 bytes per millisecond
+        let pacing_rate = Bandwidth::new(1_000_000, Duration::from_secs(1));
+        let mut cc = FixedCongestionController::new(MAX_DATAGRAM_SIZE, 100_000, Some(pacing_rate));
+
+        cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+        assert_eq!(
+            cc.earliest_departure_time(),
+            Some(now + Duration::from_micros(1200))
+        );
+
+        cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+        assert_eq!(
+            cc.earliest_departure_time(),
+            Some(now + Duration::from_micros(2400))
+        );
+
+        // the next departure time starts from the current time after the sender was idle
+        let now = now + Duration::from_millis(10);
+        cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+        assert_eq!(
+            cc.earliest_departure_time(),
+            Some(now + Duration::from_micros(1200))
+        );
+    }
+
+    #[test]
+    fn pacing_rate_test() {
+        let endpoint = Endpoint::new(100_000);
+        assert_eq!(endpoint.pacing_rate, None);
+
+        let endpoint = endpoint.with_pacing_rate(1_000_000);
+        assert_eq!(
+            endpoint.pacing_rate.unwrap().as_bytes_per_second(),
+            1_000_000
+        );
+
+        // rates that can't be represented disable pacing
+        assert_eq!(endpoint.with_pacing_rate(0).pacing_rate, None);
+        assert_eq!(endpoint.with_pacing_rate(u64::MAX).pacing_rate, None);
+    }
+}
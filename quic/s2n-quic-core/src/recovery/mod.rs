@@ -3,6 +3,9 @@
 
 pub use congestion_controller::CongestionController;
 pub use cubic::CubicCongestionController;
+pub use fixed::FixedCongestionController;
+pub use new_reno::NewRenoCongestionController;
+pub use prague::PragueCongestionController;
 pub use rtt_estimator::*;
 pub use sent_packets::*;
 
@@ -10,8 +13,11 @@ pub mod bandwidth;
 pub mod bbr;
 pub mod congestion_controller;
 pub mod cubic;
+pub mod fixed;
 mod hybrid_slow_start;
+pub mod new_reno;
 mod pacing;
+pub mod prague;
 mod rtt_estimator;
 mod sent_packets;
 
@@ -0,0 +1,370 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A congestion controller that implements NewReno as described in the pseudocode of
+//! RFC 9002 Appendix B
+//!
+//! This controller is intended as a reference baseline for evaluating other controllers. It does
+//! not pace packets.
+
+use crate::{
+    counter::Counter,
+    event::builder::SlowStartExitCause,
+    random,
+    recovery::{
+        congestion_controller::{self, CongestionController, Publisher},
+        RttEstimator,
+    },
+    time::Timestamp,
+};
+use core::cmp::{max, min};
+
+//= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.1
+//# kLossReductionFactor:  Scaling factor applied to reduce the
+//# congestion window when a new loss event is detected.  Section 7
+//# recommends a value of 0.5.
+const LOSS_REDUCTION_FACTOR: u32 = 2;
+
+type BytesInFlight = Counter<u32>;
+
+#[derive(Clone, Debug)]
+pub struct NewRenoCongestionController {
+    max_datagram_size: u16,
+    congestion_window: u32,
+    //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.2
+    //# ssthresh:  Slow start threshold in bytes.  When the congestion window
+    //# is below ssthresh, the mode is slow start and the window grows by
+    //# the number of bytes acknowledged.
+    slow_start_threshold: u32,
+    //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.2
+    //# congestion_recovery_start_time:  The time the current recovery period
+    //# started due to the detection of loss or ECN.  When a packet sent
+    //# after this time is acknowledged, QUIC exits congestion recovery.
+    congestion_recovery_start_time: Option<Timestamp>,
+    bytes_in_flight: BytesInFlight,
+    /// The number of bytes acknowledged in congestion avoidance since the window last grew
+    bytes_acked: u32,
+    /// The time the newest acknowledged packet was sent
+    newest_acked_time_sent: Option<Timestamp>,
+    /// Set when a packet may be sent without respecting the congestion window
+    requires_fast_retransmission: bool,
+    under_utilized: bool,
+}
+
+impl CongestionController for NewRenoCongestionController {
+    /// The time the packet was sent
+    type PacketInfo = Timestamp;
+
+    #[inline]
+    fn congestion_window(&self) -> u32 {
+        self.congestion_window
+    }
+
+    #[inline]
+    fn bytes_in_flight(&self) -> u32 {
+        *self.bytes_in_flight
+    }
+
+    #[inline]
+    fn is_congestion_limited(&self) -> bool {
+        let available_congestion_window =
+            self.congestion_window.saturating_sub(*self.bytes_in_flight);
+        available_congestion_window < self.max_datagram_size as u32
+    }
+
+    #[inline]
+    fn requires_fast_retransmission(&self) -> bool {
+        self.requires_fast_retransmission
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.4
+    //# Whenever a packet is sent and it contains non-ACK frames, the packet
+    //# increases bytes_in_flight.
+    #[inline]
+    fn on_packet_sent<Pub: Publisher>(
+        &mut self,
+        time_sent: Timestamp,
+        bytes_sent: usize,
+        app_limited: Option<bool>,
+        _rtt_estimator: &RttEstimator,
+        _publisher: &mut Pub,
+    ) -> Self::PacketInfo {
+        if bytes_sent == 0 {
+            // Packet was not congestion controlled
+            return time_sent;
+        }
+
+        self.bytes_in_flight
+            .try_add(bytes_sent)
+            .expect("bytes sent should not exceed u32::MAX");
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#section-7.8
+        //# When bytes in flight is smaller than the congestion window and
+        //# sending is not pacing limited, the congestion window is
+        //# underutilized.
+        self.under_utilized =
+            app_limited.unwrap_or(false) && *self.bytes_in_flight < self.congestion_window;
+
+        // A packet has been sent since entering recovery
+        self.requires_fast_retransmission = false;
+
+        time_sent
+    }
+
+    #[inline]
+    fn on_rtt_update<Pub: Publisher>(
+        &mut self,
+        _time_sent: Timestamp,
+        _now: Timestamp,
+        _rtt_estimator: &RttEstimator,
+        _publisher: &mut Pub,
+    ) {
+    }
+
+    #[inline]
+    fn on_ack<Pub: Publisher>(
+        &mut self,
+        newest_acked_time_sent: Timestamp,
+        bytes_acknowledged: usize,
+        _newest_acked_packet_info: Self::PacketInfo,
+        _rtt_estimator: &RttEstimator,
+        _random_generator: &mut dyn random::Generator,
+        _ack_receive_time: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        self.bytes_in_flight
+            .try_sub(bytes_acknowledged)
+            .expect("bytes_acknowledged should not exceed u32::MAX");
+        self.newest_acked_time_sent = Some(newest_acked_time_sent);
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.5
+        //# // Do not increase congestion_window if application
+        //# // limited or flow control limited.
+        if self.under_utilized {
+            return;
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.5
+        //# // Do not increase congestion window in recovery period.
+        if self.in_congestion_recovery(newest_acked_time_sent) {
+            return;
+        }
+
+        let bytes_acknowledged = bytes_acknowledged as u32;
+
+        if self.is_slow_start() {
+            //= https://www.rfc-editor.org/rfc/rfc9002#section-7.3.1
+            //# While a sender is in slow start, the congestion window increases by
+            //# the number of bytes acknowledged when each acknowledgment is
+            //# processed.
+            self.congestion_window = self.congestion_window.saturating_add(bytes_acknowledged);
+
+            if !self.is_slow_start() {
+                publisher.on_slow_start_exited(SlowStartExitCause::Other, self.congestion_window);
+            }
+            return;
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#section-7.3.3
+        //# A sender in congestion avoidance uses an Additive Increase
+        //# Multiplicative Decrease (AIMD) approach that MUST limit the increase
+        //# to the congestion window to at most one maximum datagram size for
+        //# each congestion window that is acknowledged.
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.5
+        //# In congestion avoidance, implementers that use an integer
+        //# representation for congestion_window should be careful with division
+        //# and can use the alternative approach suggested in Section 2.1 of
+        //# [RFC3465].
+        self.bytes_acked = self.bytes_acked.saturating_add(bytes_acknowledged);
+        if self.bytes_acked >= self.congestion_window {
+            self.bytes_acked -= self.congestion_window;
+            self.congestion_window = self
+                .congestion_window
+                .saturating_add(self.max_datagram_size as u32);
+        }
+    }
+
+    #[inline]
+    fn on_packet_lost<Pub: Publisher>(
+        &mut self,
+        lost_bytes: u32,
+        packet_info: Self::PacketInfo,
+        persistent_congestion: bool,
+        _new_loss_burst: bool,
+        _random_generator: &mut dyn random::Generator,
+        timestamp: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        debug_assert!(lost_bytes > 0);
+
+        self.bytes_in_flight -= lost_bytes;
+
+        if self.is_slow_start() && !persistent_congestion {
+            publisher.on_slow_start_exited(SlowStartExitCause::PacketLoss, self.congestion_window);
+        }
+
+        self.on_congestion_event(packet_info, timestamp);
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.8
+        //# if (InPersistentCongestion(pc_lost)):
+        //#   congestion_window = kMinimumWindow
+        //#   congestion_recovery_start_time = 0
+        if persistent_congestion {
+            self.congestion_window = self.minimum_window();
+            self.congestion_recovery_start_time = None;
+        }
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.7
+    //# // If the ECN-CE counter reported by the peer has increased,
+    //# // this could be a new congestion event.
+    #[inline]
+    fn on_explicit_congestion<Pub: Publisher>(
+        &mut self,
+        _ce_count: u64,
+        event_time: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        if self.is_slow_start() {
+            publisher.on_slow_start_exited(SlowStartExitCause::Ecn, self.congestion_window);
+        }
+
+        // The ECN counts are processed after the packets in the ACK frame are acknowledged, so
+        // the newest acknowledged packet is the largest acknowledged in the frame
+        let sent_time = self.newest_acked_time_sent.unwrap_or(event_time);
+        self.on_congestion_event(sent_time, event_time);
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc9002#section-7.2
+    //# If the maximum datagram size changes during the connection, the
+    //# initial congestion window SHOULD be recalculated with the new size.
+    #[inline]
+    fn on_mtu_update<Pub: Publisher>(&mut self, max_datagram_size: u16, _publisher: &mut Pub) {
+        let old_max_datagram_size = self.max_datagram_size;
+        self.max_datagram_size = max_datagram_size;
+
+        self.congestion_window = (self.congestion_window as u64 * max_datagram_size as u64
+            / old_max_datagram_size as u64) as u32;
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.9
+    //# When Initial or Handshake keys are discarded, packets sent in that
+    //# space no longer count toward bytes in flight.
+    #[inline]
+    fn on_packet_discarded<Pub: Publisher>(&mut self, bytes_sent: usize, _publisher: &mut Pub) {
+        self.bytes_in_flight
+            .try_sub(bytes_sent)
+            .expect("bytes sent should not exceed u32::MAX");
+
+        // Any lost packets will no longer be retransmitted
+        self.requires_fast_retransmission = false;
+    }
+
+    #[inline]
+    fn earliest_departure_time(&self) -> Option<Timestamp> {
+        None
+    }
+}
+
+impl NewRenoCongestionController {
+    //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.3
+    //# congestion_window = kInitialWindow
+    //# bytes_in_flight = 0
+    //# congestion_recovery_start_time = 0
+    //# ssthresh = infinite
+    pub fn new(max_datagram_size: u16) -> Self {
+        Self {
+            max_datagram_size,
+            congestion_window: Self::initial_window(max_datagram_size),
+            slow_start_threshold: u32::MAX,
+            congestion_recovery_start_time: None,
+            bytes_in_flight: Counter::new(0),
+            bytes_acked: 0,
+            newest_acked_time_sent: None,
+            requires_fast_retransmission: false,
+            under_utilized: false,
+        }
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc9002#section-7.2
+    //# Endpoints SHOULD use an initial congestion
+    //# window of ten times the maximum datagram size (max_datagram_size),
+    //# while limiting the window to the larger of 14,720 bytes or twice the
+    //# maximum datagram size.
+    #[inline]
+    fn initial_window(max_datagram_size: u16) -> u32 {
+        const INITIAL_WINDOW_LIMIT: u32 = 14720;
+        min(
+            10 * max_datagram_size as u32,
+            max(INITIAL_WINDOW_LIMIT, 2 * max_datagram_size as u32),
+        )
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc9002#section-7.2
+    //# The RECOMMENDED
+    //# value is 2 * max_datagram_size.
+    #[inline]
+    fn minimum_window(&self) -> u32 {
+        2 * self.max_datagram_size as u32
+    }
+
+    #[inline]
+    fn is_slow_start(&self) -> bool {
+        self.congestion_window < self.slow_start_threshold
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.5
+    //# InCongestionRecovery(sent_time):
+    //#   return sent_time <= congestion_recovery_start_time
+    #[inline]
+    fn in_congestion_recovery(&self, sent_time: Timestamp) -> bool {
+        self.congestion_recovery_start_time
+            .map_or(false, |start_time| sent_time <= start_time)
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.6
+    //# OnCongestionEvent(sent_time):
+    //#   // No reaction if already in a recovery period.
+    //#   if (InCongestionRecovery(sent_time)):
+    //#     return
+    #[inline]
+    fn on_congestion_event(&mut self, sent_time: Timestamp, now: Timestamp) {
+        if self.in_congestion_recovery(sent_time) {
+            return;
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.6
+        //# // Enter recovery period.
+        //# congestion_recovery_start_time = now()
+        //# ssthresh = congestion_window * kLossReductionFactor
+        //# congestion_window = max(ssthresh, kMinimumWindow)
+        self.congestion_recovery_start_time = Some(now);
+        self.slow_start_threshold = self.congestion_window / LOSS_REDUCTION_FACTOR;
+        self.congestion_window = max(self.slow_start_threshold, self.minimum_window());
+        self.bytes_acked = 0;
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#appendix-B.6
+        //# // A packet can be sent to speed up loss recovery.
+        //# MaybeSendOnePacket()
+        self.requires_fast_retransmission = true;
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct Endpoint {}
+
+impl congestion_controller::Endpoint for Endpoint {
+    type CongestionController = NewRenoCongestionController;
+
+    fn new_congestion_controller(
+        &mut self,
+        path_info: congestion_controller::PathInfo,
+    ) -> Self::CongestionController {
+        NewRenoCongestionController::new(path_info.max_datagram_size)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,219 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    event, path,
+    recovery::congestion_controller::PathPublisher,
+    time::{Clock, Duration, NoopClock},
+};
+
+const MAX_DATAGRAM_SIZE: u16 = 1200;
+
+#[test]
+fn slow_start_test() {
+    let mut publisher = event::testing::Publisher::no_snapshot();
+    let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+    let rtt_estimator = RttEstimator::default();
+    let random = &mut random::testing::Generator::default();
+    let now = NoopClock.get_time();
+
+    let mut cc = NewRenoCongestionController::new(MAX_DATAGRAM_SIZE);
+    assert_eq!(cc.congestion_window(), 12_000);
+
+    let time_sent = cc.on_packet_sent(now, 1200, Some(false), &rtt_estimator, &mut publisher);
+    assert_eq!(cc.bytes_in_flight(), 1200);
+
+    // the window grows by the number of bytes acknowledged
+    cc.on_ack(
+        now,
+        1200,
+        time_sent,
+        &rtt_estimator,
+        random,
+        now,
+        &mut publisher,
+    );
+    assert_eq!(cc.bytes_in_flight(), 0);
+    assert_eq!(cc.congestion_window(), 13_200);
+}
+
+#[test]
+fn congestion_avoidance_test() {
+    let mut publisher = event::testing::Publisher::no_snapshot();
+    let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+    let rtt_estimator = RttEstimator::default();
+    let random = &mut random::testing::Generator::default();
+    let now = NoopClock.get_time();
+
+    let mut cc = NewRenoCongestionController::new(MAX_DATAGRAM_SIZE);
+    cc.slow_start_threshold = 12_000;
+
+    // the window grows by one datagram once a full window has been acknowledged
+    for _ in 0..10 {
+        let time_sent = cc.on_packet_sent(now, 1200, Some(false), &rtt_estimator, &mut publisher);
+        cc.on_ack(
+            now,
+            1200,
+            time_sent,
+            &rtt_estimator,
+            random,
+            now,
+            &mut publisher,
+        );
+    }
+    assert_eq!(cc.congestion_window(), 13_200);
+    assert_eq!(cc.bytes_acked, 0);
+
+    let time_sent = cc.on_packet_sent(now, 1200, Some(false), &rtt_estimator, &mut publisher);
+    cc.on_ack(
+        now,
+        1200,
+        time_sent,
+        &rtt_estimator,
+        random,
+        now,
+        &mut publisher,
+    );
+    assert_eq!(cc.congestion_window(), 13_200);
+    assert_eq!(cc.bytes_acked, 1200);
+}
+
+#[test]
+fn app_limited_test() {
+    let mut publisher = event::testing::Publisher::no_snapshot();
+    let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+    let rtt_estimator = RttEstimator::default();
+    let random = &mut random::testing::Generator::default();
+    let now = NoopClock.get_time();
+
+    let mut cc = NewRenoCongestionController::new(MAX_DATAGRAM_SIZE);
+    let time_sent = cc.on_packet_sent(now, 1200, Some(true), &rtt_estimator, &mut publisher);
+    cc.on_ack(
+        now,
+        1200,
+        time_sent,
+        &rtt_estimator,
+        random,
+        now,
+        &mut publisher,
+    );
+    assert_eq!(cc.congestion_window(), 12_000);
+}
+
+#[test]
+fn congestion_event_test() {
+    let mut publisher = event::testing::Publisher::no_snapshot();
+    let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+    let rtt_estimator = RttEstimator::default();
+    let random = &mut random::testing::Generator::default();
+    let now = NoopClock.get_time();
+
+    let mut cc = NewRenoCongestionController::new(MAX_DATAGRAM_SIZE);
+    let time_sent = cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+    let lost_time_sent = cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+
+    let now = now + Duration::from_millis(100);
+    cc.on_packet_lost(
+        1200,
+        lost_time_sent,
+        false,
+        false,
+        random,
+        now,
+        &mut publisher,
+    );
+    assert_eq!(cc.congestion_window(), 6_000);
+    assert_eq!(cc.slow_start_threshold, 6_000);
+    assert_eq!(cc.congestion_recovery_start_time, Some(now));
+    assert!(cc.requires_fast_retransmission());
+
+    // packets sent before the recovery period don't reduce the window again
+    cc.on_explicit_congestion(1, now, &mut publisher);
+    assert_eq!(cc.congestion_window(), 6_000);
+
+    // or increase it
+    cc.on_ack(
+        time_sent,
+        1200,
+        time_sent,
+        &rtt_estimator,
+        random,
+        now,
+        &mut publisher,
+    );
+    assert_eq!(cc.congestion_window(), 6_000);
+    assert_eq!(cc.bytes_acked, 0);
+
+    // sending a packet clears the fast retransmission
+    let now = now + Duration::from_millis(100);
+    let time_sent = cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+    assert!(!cc.requires_fast_retransmission());
+
+    // acknowledging a packet sent after the recovery period started exits recovery
+    cc.on_ack(
+        time_sent,
+        1200,
+        time_sent,
+        &rtt_estimator,
+        random,
+        now,
+        &mut publisher,
+    );
+    assert_eq!(cc.bytes_acked, 1200);
+
+    // the next congestion event reduces the window again
+    cc.on_explicit_congestion(1, now, &mut publisher);
+    assert_eq!(cc.congestion_window(), 3_000);
+    assert_eq!(cc.congestion_recovery_start_time, Some(now));
+
+    // the window doesn't drop below the minimum window
+    let now = now + Duration::from_millis(100);
+    let time_sent = cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+    cc.on_packet_lost(1200, time_sent, false, false, random, now, &mut publisher);
+    assert_eq!(cc.congestion_window(), 2_400);
+    assert_eq!(cc.slow_start_threshold, 1_500);
+}
+
+#[test]
+fn persistent_congestion_test() {
+    let mut publisher = event::testing::Publisher::no_snapshot();
+    let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+    let rtt_estimator = RttEstimator::default();
+    let random = &mut random::testing::Generator::default();
+    let now = NoopClock.get_time();
+
+    let mut cc = NewRenoCongestionController::new(MAX_DATAGRAM_SIZE);
+    let time_sent = cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+    cc.on_packet_lost(1200, time_sent, true, false, random, now, &mut publisher);
+
+    assert_eq!(cc.congestion_window(), 2_400);
+    assert_eq!(cc.slow_start_threshold, 6_000);
+    assert_eq!(cc.congestion_recovery_start_time, None);
+    assert_eq!(cc.bytes_in_flight(), 0);
+
+    // the window is in slow start again
+    let time_sent = cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+    cc.on_ack(
+        time_sent,
+        1200,
+        time_sent,
+        &rtt_estimator,
+        random,
+        now,
+        &mut publisher,
+    );
+    assert_eq!(cc.congestion_window(), 3_600);
+}
+
+#[test]
+fn on_mtu_update_test() {
+    let mut publisher = event::testing::Publisher::no_snapshot();
+    let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+
+    let mut cc = NewRenoCongestionController::new(MAX_DATAGRAM_SIZE);
+    cc.on_mtu_update(1500, &mut publisher);
+
+    assert_eq!(cc.max_datagram_size, 1500);
+    assert_eq!(cc.congestion_window(), 15_000);
+}
@@ -0,0 +1,263 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A scalable congestion controller for Low Latency, Low Loss, and Scalable throughput (L4S)
+//!
+//! Packets are marked ECT(1) so L4S-aware network queues mark them CE at a shallow queue depth
+//! rather than letting the queue build. The controller tracks the fraction of packets marked CE
+//! in each round trip and reduces the congestion window in proportion to it, rather than applying
+//! a fixed multiplicative decrease.
+//!
+//! Paths that don't validate ECN never report CE marks, so the controller behaves as CUBIC on
+//! them. CE marks from queues that build more delay than an L4S queue would, which are likely
+//! classic ECN queues that treat ECT(1) as ECT(0), also receive the CUBIC response.
+//!
+//! See https://www.rfc-editor.org/rfc/rfc9331
+//! and https://datatracker.ietf.org/doc/html/draft-briscoe-iccrg-prague-congestion-control
+
+use crate::{
+    inet::ExplicitCongestionNotification,
+    random,
+    recovery::{
+        congestion_controller::{self, CongestionController, Publisher},
+        CubicCongestionController, RttEstimator,
+    },
+    time::Timestamp,
+};
+use core::time::Duration;
+
+/// Gain factor for the CE marking fraction samples
+///
+/// Value from DCTCP, see https://www.rfc-editor.org/rfc/rfc8257
+const ALPHA_GAIN: f32 = 1.0 / 16.0;
+
+/// Queuing delay above which CE marks are assumed to come from a classic ECN queue
+///
+/// L4S queues mark packets once they have queued for around 1ms, while classic AQMs target
+/// 5ms or more.
+const CLASSIC_QUEUE_DELAY_THRESHOLD: Duration = Duration::from_millis(5);
+
+#[derive(Clone, Debug)]
+pub struct PragueCongestionController {
+    cubic: CubicCongestionController,
+    max_datagram_size: u16,
+    /// Weighted average fraction of packets marked CE per round trip
+    alpha: f32,
+    /// The number of bytes acknowledged in the current round trip
+    acked_bytes_in_round: u64,
+    /// The number of packets marked CE in the current round trip
+    ce_count_in_round: u64,
+    /// The current round trip ends when a packet sent after this time is acknowledged
+    round_end: Option<Timestamp>,
+    time_of_last_sent_packet: Option<Timestamp>,
+    /// The latest estimate of the delay packets spend queued on the path
+    queue_delay: Duration,
+}
+
+impl CongestionController for PragueCongestionController {
+    type PacketInfo = <CubicCongestionController as CongestionController>::PacketInfo;
+
+    #[inline]
+    fn congestion_window(&self) -> u32 {
+        self.cubic.congestion_window()
+    }
+
+    #[inline]
+    fn bytes_in_flight(&self) -> u32 {
+        self.cubic.bytes_in_flight()
+    }
+
+    #[inline]
+    fn is_congestion_limited(&self) -> bool {
+        self.cubic.is_congestion_limited()
+    }
+
+    #[inline]
+    fn requires_fast_retransmission(&self) -> bool {
+        self.cubic.requires_fast_retransmission()
+    }
+
+    #[inline]
+    fn on_packet_sent<Pub: Publisher>(
+        &mut self,
+        time_sent: Timestamp,
+        bytes_sent: usize,
+        app_limited: Option<bool>,
+        rtt_estimator: &RttEstimator,
+        publisher: &mut Pub,
+    ) -> Self::PacketInfo {
+        if bytes_sent > 0 {
+            self.time_of_last_sent_packet = Some(time_sent);
+            if self.round_end.is_none() {
+                self.round_end = Some(time_sent);
+            }
+        }
+
+        self.cubic
+            .on_packet_sent(time_sent, bytes_sent, app_limited, rtt_estimator, publisher)
+    }
+
+    #[inline]
+    fn on_rtt_update<Pub: Publisher>(
+        &mut self,
+        time_sent: Timestamp,
+        now: Timestamp,
+        rtt_estimator: &RttEstimator,
+        publisher: &mut Pub,
+    ) {
+        self.queue_delay = rtt_estimator
+            .latest_rtt()
+            .saturating_sub(rtt_estimator.min_rtt());
+
+        self.cubic
+            .on_rtt_update(time_sent, now, rtt_estimator, publisher);
+    }
+
+    #[inline]
+    fn on_ack<Pub: Publisher>(
+        &mut self,
+        newest_acked_time_sent: Timestamp,
+        bytes_acknowledged: usize,
+        newest_acked_packet_info: Self::PacketInfo,
+        rtt_estimator: &RttEstimator,
+        random_generator: &mut dyn random::Generator,
+        ack_receive_time: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        self.acked_bytes_in_round += bytes_acknowledged as u64;
+
+        if self
+            .round_end
+            .map_or(false, |round_end| newest_acked_time_sent > round_end)
+        {
+            self.on_round_end();
+        }
+
+        self.cubic.on_ack(
+            newest_acked_time_sent,
+            bytes_acknowledged,
+            newest_acked_packet_info,
+            rtt_estimator,
+            random_generator,
+            ack_receive_time,
+            publisher,
+        );
+    }
+
+    #[inline]
+    fn on_packet_lost<Pub: Publisher>(
+        &mut self,
+        lost_bytes: u32,
+        packet_info: Self::PacketInfo,
+        persistent_congestion: bool,
+        new_loss_burst: bool,
+        random_generator: &mut dyn random::Generator,
+        timestamp: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        // Loss is always treated as a classic congestion signal
+        self.cubic.on_packet_lost(
+            lost_bytes,
+            packet_info,
+            persistent_congestion,
+            new_loss_burst,
+            random_generator,
+            timestamp,
+            publisher,
+        );
+    }
+
+    #[inline]
+    fn on_explicit_congestion<Pub: Publisher>(
+        &mut self,
+        ce_count: u64,
+        event_time: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        self.ce_count_in_round = self.ce_count_in_round.saturating_add(ce_count);
+
+        if self.queue_delay > CLASSIC_QUEUE_DELAY_THRESHOLD {
+            self.cubic
+                .on_explicit_congestion(ce_count, event_time, publisher);
+            return;
+        }
+
+        // The window is reduced at most once per round trip, by half of the fraction of packets
+        // that were recently marked
+        self.cubic
+            .on_proportional_congestion_event(self.alpha / 2.0, event_time, publisher);
+    }
+
+    #[inline]
+    fn on_mtu_update<Pub: Publisher>(&mut self, max_datagram_size: u16, publisher: &mut Pub) {
+        self.max_datagram_size = max_datagram_size;
+        self.cubic.on_mtu_update(max_datagram_size, publisher);
+    }
+
+    #[inline]
+    fn on_packet_discarded<Pub: Publisher>(&mut self, bytes_sent: usize, publisher: &mut Pub) {
+        self.cubic.on_packet_discarded(bytes_sent, publisher);
+    }
+
+    #[inline]
+    fn earliest_departure_time(&self) -> Option<Timestamp> {
+        self.cubic.earliest_departure_time()
+    }
+
+    #[inline]
+    fn ect_codepoint(&self) -> ExplicitCongestionNotification {
+        ExplicitCongestionNotification::Ect1
+    }
+}
+
+impl PragueCongestionController {
+    pub fn new(max_datagram_size: u16) -> Self {
+        Self {
+            cubic: CubicCongestionController::new(max_datagram_size),
+            max_datagram_size,
+            // Start with the most conservative response until the marking fraction is measured
+            alpha: 1.0,
+            acked_bytes_in_round: 0,
+            ce_count_in_round: 0,
+            round_end: None,
+            time_of_last_sent_packet: None,
+            queue_delay: Duration::ZERO,
+        }
+    }
+
+    /// Updates `alpha` with the fraction of packets marked CE over the round trip
+    #[inline]
+    fn on_round_end(&mut self) {
+        if self.acked_bytes_in_round > 0 {
+            // Estimate the number of bytes marked CE by multiplying the CE count by the
+            // max_datagram_size
+            let ce_bytes = self
+                .ce_count_in_round
+                .saturating_mul(self.max_datagram_size as u64);
+            let ce_fraction = (ce_bytes as f32 / self.acked_bytes_in_round as f32).min(1.0);
+            self.alpha = (1.0 - ALPHA_GAIN) * self.alpha + ALPHA_GAIN * ce_fraction;
+        }
+
+        self.acked_bytes_in_round = 0;
+        self.ce_count_in_round = 0;
+        self.round_end = self.time_of_last_sent_packet;
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct Endpoint {}
+
+impl congestion_controller::Endpoint for Endpoint {
+    type CongestionController = PragueCongestionController;
+
+    fn new_congestion_controller(
+        &mut self,
+        path_info: congestion_controller::PathInfo,
+    ) -> Self::CongestionController {
+        PragueCongestionController::new(path_info.max_datagram_size)
+    }
+}
+
+#[cfg(test)]
+mod tests;
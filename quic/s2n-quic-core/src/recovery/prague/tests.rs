@@ -0,0 +1,119 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    assert_delta, event,
+    packet::number::PacketNumberSpace,
+    path,
+    recovery::congestion_controller::PathPublisher,
+    time::{Clock, NoopClock},
+};
+
+const MAX_DATAGRAM_SIZE: u16 = 1200;
+
+#[test]
+fn ect_codepoint() {
+    let cc = PragueCongestionController::new(MAX_DATAGRAM_SIZE);
+    assert_eq!(cc.ect_codepoint(), ExplicitCongestionNotification::Ect1);
+}
+
+#[test]
+fn proportional_response() {
+    let mut publisher = event::testing::Publisher::no_snapshot();
+    let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+    let now = NoopClock.get_time();
+
+    // alpha starts at 1, so the first response halves the window
+    let mut cc = PragueCongestionController::new(MAX_DATAGRAM_SIZE);
+    let initial_window = cc.congestion_window() as f32;
+    cc.on_explicit_congestion(1, now, &mut publisher);
+    assert_delta!(cc.congestion_window() as f32, initial_window * 0.5, 1.0);
+
+    // the window is reduced by half of alpha
+    let mut cc = PragueCongestionController::new(MAX_DATAGRAM_SIZE);
+    cc.alpha = 0.2;
+    cc.on_explicit_congestion(1, now, &mut publisher);
+    assert_delta!(cc.congestion_window() as f32, initial_window * 0.9, 1.0);
+
+    // the window is only reduced once per round trip
+    cc.on_explicit_congestion(1, now, &mut publisher);
+    assert_delta!(cc.congestion_window() as f32, initial_window * 0.9, 1.0);
+}
+
+#[test]
+fn classic_queue_response() {
+    let mut publisher = event::testing::Publisher::no_snapshot();
+    let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+    let now = NoopClock.get_time();
+
+    let mut rtt_estimator = RttEstimator::default();
+    rtt_estimator.update_rtt(
+        Duration::ZERO,
+        Duration::from_millis(10),
+        now,
+        true,
+        PacketNumberSpace::ApplicationData,
+    );
+    rtt_estimator.update_rtt(
+        Duration::ZERO,
+        Duration::from_millis(30),
+        now,
+        true,
+        PacketNumberSpace::ApplicationData,
+    );
+
+    let mut cc = PragueCongestionController::new(MAX_DATAGRAM_SIZE);
+    let initial_window = cc.congestion_window() as f32;
+    cc.alpha = 0.2;
+    cc.on_rtt_update(now, now, &rtt_estimator, &mut publisher);
+    assert_eq!(cc.queue_delay, Duration::from_millis(20));
+
+    // the queue built more delay than an L4S queue would, so CUBIC's decrease is applied
+    cc.on_explicit_congestion(1, now, &mut publisher);
+    assert_delta!(cc.congestion_window() as f32, initial_window * 0.7, 1.0);
+}
+
+#[test]
+fn alpha_update() {
+    let mut publisher = event::testing::Publisher::no_snapshot();
+    let mut publisher = PathPublisher::new(&mut publisher, path::Id::test_id());
+    let rtt_estimator = RttEstimator::default();
+    let random = &mut random::testing::Generator::default();
+    let now = NoopClock.get_time();
+
+    let mut cc = PragueCongestionController::new(MAX_DATAGRAM_SIZE);
+
+    cc.on_packet_sent(now, 1200, None, &rtt_estimator, &mut publisher);
+    let round_end = now + Duration::from_millis(10);
+    for _ in 0..10 {
+        cc.on_packet_sent(round_end, 1200, None, &rtt_estimator, &mut publisher);
+    }
+
+    // the first packet of the round doesn't end it
+    cc.on_ack(now, 1200, (), &rtt_estimator, random, now, &mut publisher);
+    assert_delta!(cc.alpha, 1.0, 0.0001);
+    cc.ce_count_in_round = 5;
+
+    // acknowledging a packet sent after the first ends the round; 5 of 10 packets were marked
+    cc.acked_bytes_in_round = 9 * MAX_DATAGRAM_SIZE as u64;
+    cc.on_ack(
+        round_end,
+        1200,
+        (),
+        &rtt_estimator,
+        random,
+        now,
+        &mut publisher,
+    );
+    assert_delta!(cc.alpha, (1.0 - ALPHA_GAIN) + ALPHA_GAIN * 0.5, 0.0001);
+    assert_eq!(cc.acked_bytes_in_round, 0);
+    assert_eq!(cc.ce_count_in_round, 0);
+    assert_eq!(cc.round_end, Some(round_end));
+
+    // a round without marks decays alpha
+    let alpha = cc.alpha;
+    cc.acked_bytes_in_round = 12_000;
+    cc.on_round_end();
+    assert_delta!(cc.alpha, (1.0 - ALPHA_GAIN) * alpha, 0.0001);
+}
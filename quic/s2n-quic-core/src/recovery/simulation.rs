@@ -9,7 +9,7 @@ use crate::{
     random,
     recovery::{
         congestion_controller::PathPublisher, CongestionController, CubicCongestionController,
-        RttEstimator,
+        FixedCongestionController, NewRenoCongestionController, RttEstimator,
     },
     time::{Clock, NoopClock, Timestamp},
 };
@@ -68,6 +68,73 @@ fn loss_at_3mb_and_2_75mb_test() {
     loss_at_3mb_and_2_75mb(cc, 120).finish();
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn new_reno_slow_start_unlimited_test() {
+    let cc = NewRenoCongestionController::new(MINIMUM_MTU);
+
+    slow_start_unlimited(cc, 12).finish();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn new_reno_loss_at_3mb_test() {
+    let cc = NewRenoCongestionController::new(MINIMUM_MTU);
+
+    loss_at_3mb(cc, 135).finish();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn new_reno_app_limited_1mb_test() {
+    let cc = NewRenoCongestionController::new(MINIMUM_MTU);
+
+    app_limited_1mb(cc, 120).finish();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn new_reno_minimum_window_test() {
+    let cc = NewRenoCongestionController::new(MINIMUM_MTU);
+
+    minimum_window(cc, 10).finish();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn new_reno_loss_at_3mb_and_2_75mb_test() {
+    let cc = NewRenoCongestionController::new(MINIMUM_MTU);
+
+    loss_at_3mb_and_2_75mb(cc, 120).finish();
+}
+
+/// The congestion window used to simulate the fixed congestion controller
+const FIXED_CONGESTION_WINDOW: u32 = 3_600_000;
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn fixed_slow_start_unlimited_test() {
+    let cc = FixedCongestionController::new(MINIMUM_MTU, FIXED_CONGESTION_WINDOW, None);
+
+    slow_start_unlimited(cc, 12).finish();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn fixed_loss_at_3mb_test() {
+    let cc = FixedCongestionController::new(MINIMUM_MTU, FIXED_CONGESTION_WINDOW, None);
+
+    loss_at_3mb(cc, 12).finish();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn fixed_minimum_window_test() {
+    let cc = FixedCongestionController::new(MINIMUM_MTU, FIXED_CONGESTION_WINDOW, None);
+
+    minimum_window(cc, 10).finish();
+}
+
 #[derive(Debug)]
 struct Simulation {
     name: &'static str,
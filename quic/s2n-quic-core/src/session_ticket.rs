@@ -0,0 +1,47 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Storage for TLS session tickets, which allow clients to resume previous sessions
+
+use crate::application::ServerName;
+use bytes::Bytes;
+
+/// Stores session tickets received from servers so later connections can resume the session
+///
+/// Tickets are opaque to the transport and can only be used with the TLS provider that
+/// received them.
+///
+/// The store is cloned into each client connection so implementations should share their
+/// state between clones, e.g. with an `Arc`.
+pub trait Store: 'static + Send + Clone {
+    /// Indicates if the store retains session tickets
+    ///
+    /// When disabled, clients discard the TLS session as soon as the handshake completes,
+    /// rather than waiting for a session ticket from the server.
+    const ENABLED: bool = true;
+
+    /// Called when the client receives a session ticket from `server_name`
+    fn on_session_ticket(&self, server_name: &ServerName, session_ticket: Bytes);
+
+    /// Returns a session ticket to resume a session with `server_name`, if one is available
+    ///
+    /// Tickets should only be used once so implementations should remove the returned ticket
+    /// from the store.
+    fn take_session_ticket(&self, server_name: &ServerName) -> Option<Bytes>;
+}
+
+/// A session ticket store that doesn't retain any tickets
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Disabled(());
+
+impl Store for Disabled {
+    const ENABLED: bool = false;
+
+    #[inline]
+    fn on_session_ticket(&self, _server_name: &ServerName, _session_ticket: Bytes) {}
+
+    #[inline]
+    fn take_session_ticket(&self, _server_name: &ServerName) -> Option<Bytes> {
+        None
+    }
+}
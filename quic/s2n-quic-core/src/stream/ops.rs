@@ -28,7 +28,10 @@
 //!     .await?;
 //! ```
 
-use crate::{application, stream};
+use crate::{
+    application,
+    stream::{self, Priority},
+};
 use core::task::Poll;
 
 /// A request made on a stream
@@ -125,6 +128,13 @@ impl<'a> Request<'a> {
         self
     }
 
+    /// Sets the transmission priority of the tx stream
+    pub fn set_priority(&mut self, priority: Priority) -> &mut Self {
+        let tx = self.tx_mut();
+        tx.priority = Some(priority);
+        self
+    }
+
     /// Lazily creates and returns the `tx` request
     fn tx_mut(&mut self) -> &mut tx::Request<'a> {
         if self.tx.is_none() {
@@ -196,6 +206,9 @@ pub mod tx {
         /// Marks the tx stream as detached, which makes the stream make progress, regardless of
         /// application observations.
         pub detached: bool,
+
+        /// Optionally updates the transmission priority of the stream
+        pub priority: Option<Priority>,
     }
 
     /// The result of a tx request
@@ -499,6 +512,7 @@ mod tests {
             .finish()
             .flush()
             .reset(application::Error::new(1).unwrap())
+            .set_priority(Priority::new(1, true))
             .receive(&mut receive_chunks)
             .with_watermark(5, 10)
             .stop_sending(application::Error::new(2).unwrap());
@@ -512,6 +526,7 @@ mod tests {
                     flush: true,
                     reset: Some(reset),
                     detached: false,
+                    priority: Some(priority),
                 }),
                 rx: Some(rx::Request {
                     chunks: Some(rx_chunks),
@@ -521,6 +536,7 @@ mod tests {
                     detached: false,
                 })
             } if reset == application::Error::new(1).unwrap()
+              && priority == Priority::new(1, true)
               && stop_sending == application::Error::new(2).unwrap()
               && tx_chunks.len() == 1
               && rx_chunks.len() == 2
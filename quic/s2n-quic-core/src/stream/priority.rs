@@ -0,0 +1,86 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+// See https://www.rfc-editor.org/rfc/rfc9218#section-4
+
+/// The transmission priority of a stream
+///
+/// Follows the semantics of the Extensible Prioritization Scheme for HTTP: streams with a
+/// lower urgency value are transmitted before streams with a higher value. Incremental streams
+/// of the same urgency share the available capacity, while non-incremental streams are
+/// transmitted one after the other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Priority {
+    urgency: u8,
+    incremental: bool,
+}
+
+impl Default for Priority {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Priority {
+    /// The highest urgency a stream can be assigned
+    pub const MAX_URGENCY: u8 = 0;
+
+    /// The lowest urgency a stream can be assigned
+    pub const MIN_URGENCY: u8 = 7;
+
+    /// The priority streams are assigned if no priority is set
+    pub const DEFAULT: Self = Self {
+        urgency: 3,
+        incremental: false,
+    };
+
+    /// Creates a new `Priority`
+    ///
+    /// Urgency values greater than [`Self::MIN_URGENCY`] are treated as [`Self::MIN_URGENCY`].
+    #[inline]
+    pub const fn new(urgency: u8, incremental: bool) -> Self {
+        let urgency = if urgency > Self::MIN_URGENCY {
+            Self::MIN_URGENCY
+        } else {
+            urgency
+        };
+
+        Self {
+            urgency,
+            incremental,
+        }
+    }
+
+    /// Returns the urgency of the stream
+    #[inline]
+    pub const fn urgency(&self) -> u8 {
+        self.urgency
+    }
+
+    /// Returns `true` if the stream data can be interleaved with other streams of the same
+    /// urgency
+    #[inline]
+    pub const fn is_incremental(&self) -> bool {
+        self.incremental
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_test() {
+        let priority = Priority::default();
+        assert_eq!(priority.urgency(), 3);
+        assert!(!priority.is_incremental());
+
+        let priority = Priority::new(Priority::MAX_URGENCY, true);
+        assert_eq!(priority.urgency(), 0);
+        assert!(priority.is_incremental());
+
+        // urgencies are clamped to the lowest value
+        assert_eq!(Priority::new(100, false).urgency(), Priority::MIN_URGENCY);
+    }
+}
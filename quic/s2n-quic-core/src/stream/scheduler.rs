@@ -0,0 +1,83 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Policies for ordering the transmission of stream data
+
+use crate::stream::Priority;
+
+/// The number of transmission queues streams can be scheduled in
+///
+/// Queues are drained in order, starting with the first queue. Streams in later queues only
+/// transmit once all of the streams in earlier queues are blocked or done.
+pub const QUEUE_COUNT: usize = Priority::MIN_URGENCY as usize + 1;
+
+/// The position a stream is inserted in its transmission queue
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Position {
+    /// The stream transmits before the other streams in the queue
+    Front,
+    /// The stream transmits after the other streams in the queue
+    Back,
+}
+
+/// Decides the order in which streams of a connection transmit data
+///
+/// The scheduler is consulted each time a stream with new data to send is placed in one of the
+/// [`QUEUE_COUNT`] transmission queues.
+pub trait Scheduler: 'static + Send {
+    /// Returns the queue for a stream with the given `priority`
+    ///
+    /// Values greater than or equal to [`QUEUE_COUNT`] select the last queue.
+    fn queue(&self, priority: Priority) -> usize;
+
+    /// Returns the position of a stream which ran out of space in the current transmission
+    /// before it could send all of its data
+    ///
+    /// Streams which were able to send all of the data they had are always placed at the back
+    /// of their queue.
+    fn on_interrupted(&self, priority: Priority) -> Position;
+}
+
+/// Schedules streams based on their [`Priority`]
+///
+/// Each urgency level is assigned its own queue. Interrupted non-incremental streams stay at
+/// the front of their queue and transmit one after the other, while incremental streams are
+/// moved to the back and share the capacity with the other streams of the same urgency.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Urgency(());
+
+impl Scheduler for Urgency {
+    #[inline]
+    fn queue(&self, priority: Priority) -> usize {
+        priority.urgency() as usize
+    }
+
+    #[inline]
+    fn on_interrupted(&self, priority: Priority) -> Position {
+        if priority.is_incremental() {
+            Position::Back
+        } else {
+            Position::Front
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urgency_test() {
+        let scheduler = Urgency::default();
+
+        for urgency in Priority::MAX_URGENCY..=Priority::MIN_URGENCY {
+            let priority = Priority::new(urgency, false);
+            assert_eq!(scheduler.queue(priority), urgency as usize);
+            assert!(scheduler.queue(priority) < QUEUE_COUNT);
+            assert_eq!(scheduler.on_interrupted(priority), Position::Front);
+
+            let priority = Priority::new(urgency, true);
+            assert_eq!(scheduler.on_interrupted(priority), Position::Back);
+        }
+    }
+}
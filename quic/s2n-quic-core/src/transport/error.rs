@@ -317,6 +317,11 @@ impl_errors! {
     /// confidentiality or integrity limit for the AEAD algorithm used by
     /// the given connection.
     AEAD_LIMIT_REACHED = 0xf.with_frame_type(UNKNOWN_FRAME_TYPE),
+
+    // See https://www.rfc-editor.org/rfc/rfc9368#section-10.2
+    /// An endpoint detected that the version information of
+    /// the peer doesn't match the negotiated QUIC version.
+    VERSION_NEGOTIATION_ERROR = 0x11.with_frame_type(UNKNOWN_FRAME_TYPE),
 }
 
 //= https://www.rfc-editor.org/rfc/rfc9000#section-20.1
@@ -144,7 +144,7 @@ decoder_value!(
         fn decode(buffer: Buffer) -> Result<Self> {
             let len = buffer.len();
             let (slice, buffer) = buffer.decode_slice(len)?;
-            let parameters = Self::decode_parameters(slice.peek())?;
+            let parameters = Self::decode_parameters(slice.peek())?.validate_min_ack_delay()?;
             Ok((parameters, buffer))
         }
     }
@@ -155,7 +155,7 @@ decoder_value!(
         fn decode(buffer: Buffer) -> Result<Self> {
             let len = buffer.len();
             let (slice, buffer) = buffer.decode_slice(len)?;
-            let parameters = Self::decode_parameters(slice.peek())?;
+            let parameters = Self::decode_parameters(slice.peek())?.validate_min_ack_delay()?;
             Ok((parameters, buffer))
         }
     }
@@ -1068,6 +1068,205 @@ impl From<connection::id::LocalId> for InitialSourceConnectionId {
 connection_id_parameter!(RetrySourceConnectionId, LocalId, 0x10);
 optional_transport_parameter!(RetrySourceConnectionId);
 
+// The version_information (0x11) transport parameter is used by compatible version
+// negotiation to authenticate the negotiated version.
+// See https://www.rfc-editor.org/rfc/rfc9368#section-3
+//
+// Version Information {
+//   Chosen Version (32),
+//   Available Versions (32) ...,
+// }
+
+optional_transport_parameter!(VersionInformation);
+
+/// The maximum number of available versions stored in [`VersionInformation`]
+///
+/// Any additional versions sent by the peer are ignored.
+pub const MAX_AVAILABLE_VERSIONS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionInformation {
+    chosen_version: u32,
+    available_versions: [u32; MAX_AVAILABLE_VERSIONS],
+    available_versions_len: u8,
+}
+
+impl VersionInformation {
+    /// Creates a `VersionInformation` parameter
+    ///
+    /// Returns `None` if more than [`MAX_AVAILABLE_VERSIONS`] versions are provided.
+    pub fn new(chosen_version: u32, available_versions: &[u32]) -> Option<Self> {
+        if available_versions.len() > MAX_AVAILABLE_VERSIONS {
+            return None;
+        }
+
+        let mut value = Self {
+            chosen_version,
+            available_versions: [0; MAX_AVAILABLE_VERSIONS],
+            available_versions_len: available_versions.len() as u8,
+        };
+        value.available_versions[..available_versions.len()].copy_from_slice(available_versions);
+        Some(value)
+    }
+
+    /// The version that the endpoint used for the packet carrying the parameter
+    #[inline]
+    pub fn chosen_version(&self) -> u32 {
+        self.chosen_version
+    }
+
+    /// The versions the endpoint is willing to use, in order of preference
+    #[inline]
+    pub fn available_versions(&self) -> &[u32] {
+        &self.available_versions[..self.available_versions_len as usize]
+    }
+}
+
+impl TransportParameter for VersionInformation {
+    type CodecValue = Self;
+
+    const ID: TransportParameterId = TransportParameterId::from_u8(0x11);
+
+    fn from_codec_value(value: Self) -> Self {
+        value
+    }
+
+    fn try_into_codec_value(&self) -> Option<&Self> {
+        Some(self)
+    }
+
+    fn default_value() -> Self {
+        unimplemented!(
+            "VersionInformation is an optional transport parameter, so the default is None"
+        )
+    }
+}
+
+impl TransportParameterValidator for VersionInformation {
+    fn validate(self) -> Result<Self, DecoderError> {
+        // Version 0x00000000 is reserved for Version Negotiation and is not a valid
+        // chosen or available version.
+        // See https://www.rfc-editor.org/rfc/rfc9368#section-3
+        decoder_invariant!(self.chosen_version != 0, "invalid chosen version");
+        decoder_invariant!(
+            !self.available_versions().contains(&0),
+            "invalid available version"
+        );
+        Ok(self)
+    }
+}
+
+decoder_value!(
+    impl<'a> VersionInformation {
+        fn decode(buffer: Buffer) -> Result<Self> {
+            let (chosen_version, mut buffer) = buffer.decode::<u32>()?;
+
+            let mut value = Self {
+                chosen_version,
+                available_versions: [0; MAX_AVAILABLE_VERSIONS],
+                available_versions_len: 0,
+            };
+
+            while !buffer.is_empty() {
+                let (version, remaining) = buffer.decode::<u32>()?;
+                buffer = remaining;
+
+                let len = value.available_versions_len as usize;
+                if len < MAX_AVAILABLE_VERSIONS {
+                    value.available_versions[len] = version;
+                    value.available_versions_len += 1;
+                }
+            }
+
+            Ok((value, buffer))
+        }
+    }
+);
+
+impl EncoderValue for VersionInformation {
+    fn encode<E: Encoder>(&self, buffer: &mut E) {
+        buffer.encode(&self.chosen_version);
+        for version in self.available_versions() {
+            buffer.encode(version);
+        }
+    }
+}
+
+// The min_ack_delay (0xff04de1b) transport parameter is the minimum amount of time, in
+// microseconds, that the endpoint can delay an acknowledgement. Its presence indicates the
+// endpoint supports receiving ACK_FREQUENCY and IMMEDIATE_ACK frames. Values of 2^24 or
+// greater are invalid, as are values larger than the max_ack_delay parameter.
+// See https://datatracker.ietf.org/doc/html/draft-ietf-quic-ack-frequency#section-3
+
+optional_transport_parameter!(MinAckDelay);
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord)]
+pub struct MinAckDelay(VarInt);
+
+impl MinAckDelay {
+    pub const fn as_duration(self) -> Duration {
+        Duration::from_micros(self.0.as_u64())
+    }
+}
+
+impl TransportParameter for MinAckDelay {
+    type CodecValue = Self;
+
+    const ID: TransportParameterId = TransportParameterId::from_u32(0xff04de1b);
+
+    fn from_codec_value(value: Self) -> Self {
+        value
+    }
+
+    fn try_into_codec_value(&self) -> Option<&Self> {
+        Some(self)
+    }
+
+    fn default_value() -> Self {
+        unimplemented!("MinAckDelay is an optional transport parameter, so the default is None")
+    }
+}
+
+impl TransportParameterValidator for MinAckDelay {
+    fn validate(self) -> Result<Self, DecoderError> {
+        decoder_invariant!(
+            *self.0 < 2u64.pow(24),
+            "min_ack_delay must be less than 2^24"
+        );
+        Ok(self)
+    }
+}
+
+impl TryFrom<Duration> for MinAckDelay {
+    type Error = ValidationError;
+
+    fn try_from(value: Duration) -> Result<Self, Self::Error> {
+        let value: VarInt = value.as_micros().try_into()?;
+        Ok(Self(value).validate()?)
+    }
+}
+
+impl From<MinAckDelay> for Duration {
+    fn from(value: MinAckDelay) -> Self {
+        value.as_duration()
+    }
+}
+
+decoder_value!(
+    impl<'a> MinAckDelay {
+        fn decode(buffer: Buffer) -> Result<Self> {
+            let (value, buffer) = buffer.decode()?;
+            Ok((Self(value), buffer))
+        }
+    }
+);
+
+impl EncoderValue for MinAckDelay {
+    fn encode<E: Encoder>(&self, encoder: &mut E) {
+        self.0.encode(encoder)
+    }
+}
+
 //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
 //# If present, transport parameters that set initial per-stream flow
 //# control limits (initial_max_stream_data_bidi_local,
@@ -1171,6 +1370,17 @@ impl<
         }
     }
 
+    /// Ensures the min_ack_delay doesn't exceed the max_ack_delay
+    fn validate_min_ack_delay(self) -> Result<Self, DecoderError> {
+        if let Some(min_ack_delay) = self.min_ack_delay {
+            decoder_invariant!(
+                min_ack_delay.as_duration() <= self.max_ack_delay.as_duration(),
+                "min_ack_delay cannot be greater than max_ack_delay"
+            );
+        }
+        Ok(self)
+    }
+
     // Calculates the maximum datagram payload size
     pub fn datagram_limits(&self) -> DatagramLimits {
         let max_datagram_payload = self.max_datagram_frame_size.as_u64();
@@ -1424,6 +1634,8 @@ impl_transport_parameters!(
         preferred_address: PreferredAddress,
         initial_source_connection_id: Option<InitialSourceConnectionId>,
         retry_source_connection_id: RetrySourceConnectionId,
+        version_information: Option<VersionInformation>,
+        min_ack_delay: Option<MinAckDelay>,
     }
 );
 
@@ -1471,5 +1683,8 @@ impl<
         load!(ack_delay_exponent, ack_delay_exponent);
         load!(max_active_connection_ids, active_connection_id_limit);
         load!(max_datagram_frame_size, max_datagram_frame_size);
+        self.min_ack_delay = limits
+            .min_ack_delay()
+            .and_then(|min_ack_delay| min_ack_delay.try_into().ok());
     }
 }
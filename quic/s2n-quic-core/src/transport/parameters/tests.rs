@@ -80,6 +80,8 @@ fn server_transport_parameters() -> ServerTransportParameters {
         }),
         initial_source_connection_id: Some([1, 2, 3, 4][..].try_into().unwrap()),
         retry_source_connection_id: Some([1, 2, 3, 4][..].try_into().unwrap()),
+        version_information: Default::default(),
+        min_ack_delay: Default::default(),
     }
 }
 
@@ -117,6 +119,8 @@ fn client_transport_parameters() -> ClientTransportParameters {
         preferred_address: Default::default(),
         initial_source_connection_id: Some([1, 2, 3, 4][..].try_into().unwrap()),
         retry_source_connection_id: Default::default(),
+        version_information: Default::default(),
+        min_ack_delay: Default::default(),
     }
 }
 
@@ -180,6 +184,77 @@ fn ignore_unknown_parameter() {
     assert_eq!(0, remaining.len());
 }
 
+#[test]
+fn version_information_test() {
+    use crate::packet::long::{VERSION_1, VERSION_2};
+    use s2n_codec::EncoderBuffer;
+
+    let mut value = client_transport_parameters();
+    value.version_information = VersionInformation::new(VERSION_1, &[VERSION_2, VERSION_1]);
+    assert_codec_round_trip_value!(ClientTransportParameters, value);
+
+    let version_information = value.version_information.unwrap();
+    assert_eq!(version_information.chosen_version(), VERSION_1);
+    assert_eq!(
+        version_information.available_versions(),
+        &[VERSION_2, VERSION_1]
+    );
+
+    assert!(VersionInformation::new(VERSION_1, &[VERSION_1; MAX_AVAILABLE_VERSIONS + 1]).is_none());
+
+    let decode = |chosen_version: u32, available_versions: &[u32]| {
+        let mut buffer = vec![0; 1024];
+        let mut encoder = EncoderBuffer::new(&mut buffer);
+        encoder.encode(&chosen_version);
+        for version in available_versions {
+            encoder.encode(version);
+        }
+        let (encoded, _) = encoder.split_off();
+        let (value, _) = DecoderBuffer::new(encoded)
+            .decode::<VersionInformation>()
+            .unwrap();
+        value.validate()
+    };
+
+    // versions past the maximum are ignored
+    let value = decode(VERSION_2, &[VERSION_1; MAX_AVAILABLE_VERSIONS + 2]).unwrap();
+    assert_eq!(
+        value.available_versions(),
+        &[VERSION_1; MAX_AVAILABLE_VERSIONS]
+    );
+
+    // version 0 is reserved for version negotiation
+    assert!(decode(0, &[VERSION_1]).is_err());
+    assert!(decode(VERSION_1, &[VERSION_1, 0]).is_err());
+}
+
+#[test]
+fn min_ack_delay_test() {
+    use s2n_codec::EncoderBuffer;
+
+    let mut value = client_transport_parameters();
+    value.min_ack_delay = Some(Duration::from_millis(1).try_into().unwrap());
+    assert_codec_round_trip_value!(ClientTransportParameters, value);
+
+    assert!(MinAckDelay::try_from(Duration::from_micros(2u64.pow(24))).is_err());
+
+    let decode = |min_ack_delay: u32, max_ack_delay: u8| {
+        let mut buffer = vec![0; 1024];
+        let mut encoder = EncoderBuffer::new(&mut buffer);
+        let min_ack_delay = MinAckDelay(VarInt::from_u32(min_ack_delay));
+        encoder.encode(&TransportParameterCodec(&min_ack_delay));
+        let max_ack_delay = MaxAckDelay::new(max_ack_delay).unwrap();
+        encoder.encode(&TransportParameterCodec(&max_ack_delay));
+        let (encoded, _) = encoder.split_off();
+        ClientTransportParameters::decode(DecoderBuffer::new(encoded))
+    };
+
+    assert!(decode(1_000, 1).is_ok());
+    // the min_ack_delay can't be greater than the max_ack_delay
+    assert!(decode(1_001, 1).is_err());
+    assert!(decode(2u32.pow(24), 255).is_err());
+}
+
 #[test]
 fn compute_data_window_test() {
     assert_eq!(
@@ -0,0 +1,353 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::path::Tuple;
+use crate::inet::{
+    checksum::Checksum,
+    datagram,
+    ethernet::{self, EtherType},
+    ip, ipv4, ipv6, udp, ExplicitCongestionNotification, SocketAddressV4, SocketAddressV6,
+};
+use s2n_codec::{DecoderBufferMut, DecoderError};
+
+pub type Header = datagram::Header<Tuple>;
+
+type Result<'a> = core::result::Result<Option<(Header, DecoderBufferMut<'a>)>, DecoderError>;
+
+/// Decodes a UDP datagram from an Ethernet frame
+///
+/// On success, the datagram header and UDP payload are returned. `Ok(None)` is returned for
+/// well-formed frames which don't contain a UDP datagram this stack can process, e.g. ARP
+/// packets, IP fragments or IPv6 packets with extension headers. Frames which are truncated
+/// or fail checksum validation return an error.
+#[inline]
+pub fn decode_packet(buffer: DecoderBufferMut) -> Result {
+    let (ethernet, buffer) = buffer.decode::<&ethernet::Header>()?;
+
+    let mut path = Tuple::default();
+    path.remote_address.mac = *ethernet.source();
+    path.local_address.mac = *ethernet.destination();
+
+    match *ethernet.ethertype() {
+        EtherType::IPV4 => decode_ipv4(path, buffer),
+        EtherType::IPV6 => decode_ipv6(path, buffer),
+        _ => Ok(None),
+    }
+}
+
+#[inline]
+fn decode_ipv4(mut path: Tuple, buffer: DecoderBufferMut) -> Result {
+    let (header, buffer) = buffer.decode::<&ipv4::Header>()?;
+
+    if header.vihl().version() != 4 {
+        return Ok(None);
+    }
+
+    // the header length is in units of 32-bit words and includes any options
+    let header_len = header.vihl().header_len() as usize * 4;
+    let options_len = header_len
+        .checked_sub(core::mem::size_of::<ipv4::Header>())
+        .ok_or(DecoderError::InvariantViolation(
+            "invalid IPv4 header length",
+        ))?;
+    let (options, buffer) = buffer.decode_slice(options_len)?;
+
+    let mut checksum = Checksum::default();
+    checksum.write(header.as_bytes());
+    checksum.write(options.into_less_safe_slice());
+    if checksum.finish() != 0 {
+        return Err(DecoderError::InvariantViolation("invalid IPv4 checksum"));
+    }
+
+    let flags = header.flag_fragment();
+    if flags.more_fragments() || flags.fragment_offset() != 0 {
+        return Ok(None);
+    }
+
+    if !header.protocol().is_udp() {
+        return Ok(None);
+    }
+
+    // drop any padding after the IP packet
+    let payload_len = (header.total_len().get() as usize)
+        .checked_sub(header_len)
+        .ok_or(DecoderError::InvariantViolation(
+            "invalid IPv4 total length",
+        ))?;
+    let (buffer, _padding) = buffer.decode_slice(payload_len)?;
+
+    let source = *header.source();
+    let destination = *header.destination();
+
+    // See https://www.rfc-editor.org/rfc/rfc768 for the pseudo header
+    let mut checksum = Checksum::default();
+    checksum.write(source.as_bytes());
+    checksum.write(destination.as_bytes());
+    checksum.write_u16(ip::Protocol::UDP.id as u16);
+
+    let ecn = header.tos().ecn();
+
+    let (ports, payload) = match decode_udp(buffer, checksum, true)? {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+
+    path.remote_address.socket = SocketAddressV4::new(source, ports.0).into();
+    path.local_address.socket = SocketAddressV4::new(destination, ports.1).into();
+
+    Ok(Some((Header { path, ecn }, payload)))
+}
+
+#[inline]
+fn decode_ipv6(mut path: Tuple, buffer: DecoderBufferMut) -> Result {
+    let (header, buffer) = buffer.decode::<&ipv6::Header>()?;
+
+    if header.vtcfl().version() != 6 {
+        return Ok(None);
+    }
+
+    // extension headers aren't currently supported
+    if !header.next_header().is_udp() {
+        return Ok(None);
+    }
+
+    // drop any padding after the IP packet
+    let payload_len = header.payload_len().get() as usize;
+    let (buffer, _padding) = buffer.decode_slice(payload_len)?;
+
+    let source = *header.source();
+    let destination = *header.destination();
+
+    // See https://www.rfc-editor.org/rfc/rfc8200#section-8.1 for the pseudo header
+    let mut checksum = Checksum::default();
+    checksum.write(source.as_bytes());
+    checksum.write(destination.as_bytes());
+    checksum.write_u32(ip::Protocol::UDP.id as u32);
+
+    let ecn: ExplicitCongestionNotification = header.vtcfl().ecn();
+
+    let (ports, payload) = match decode_udp(buffer, checksum, false)? {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+
+    path.remote_address.socket = SocketAddressV6::new(source, ports.0).into();
+    path.local_address.socket = SocketAddressV6::new(destination, ports.1).into();
+
+    Ok(Some((Header { path, ecn }, payload)))
+}
+
+/// Decodes the UDP header and validates the checksum
+///
+/// The `checksum` should already include the addresses and protocol of the pseudo header.
+/// Returns the source and destination ports along with the payload.
+#[inline]
+fn decode_udp(
+    buffer: DecoderBufferMut,
+    mut checksum: Checksum,
+    is_checksum_optional: bool,
+) -> core::result::Result<Option<((u16, u16), DecoderBufferMut)>, DecoderError> {
+    let (header, buffer) = buffer.decode::<&udp::Header>()?;
+
+    let len = header.len().get();
+    let payload_len = (len as usize)
+        .checked_sub(core::mem::size_of::<udp::Header>())
+        .ok_or(DecoderError::InvariantViolation("invalid UDP length"))?;
+    let (payload, _padding) = buffer.decode_slice(payload_len)?;
+
+    // An all zero transmitted checksum value means that the transmitter generated no checksum
+    // for IPv4. IPv6 requires the checksum so these datagrams are dropped.
+    if header.checksum().get() != 0 || !is_checksum_optional {
+        checksum.write_u16(len);
+        checksum.write(header.as_bytes());
+        checksum.write(payload.as_less_safe_slice());
+
+        if checksum.finish() != 0 {
+            return Err(DecoderError::InvariantViolation("invalid UDP checksum"));
+        }
+    }
+
+    if payload.is_empty() {
+        return Ok(None);
+    }
+
+    let ports = (header.source().get(), header.destination().get());
+
+    Ok(Some((ports, payload)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bolero::check;
+
+    #[test]
+    fn does_not_panic_test() {
+        check!().for_each(|bytes| {
+            let mut bytes = bytes.to_vec();
+            let _ = decode_packet(DecoderBufferMut::new(&mut bytes));
+        });
+    }
+
+    #[test]
+    fn ipv4_test() {
+        // ethernet, ipv4, udp and payload
+        let mut frame = hex_literal::hex!(
+            "020000000002 020000000001 0800
+             4502 0022 0000 4000 4011 0000 c0a80001 c0a80002
+             1234 1235 000e 0000
+             010203040506"
+        );
+
+        // fill in the IPv4 checksum
+        let mut checksum = Checksum::default();
+        checksum.write(&frame[14..34]);
+        frame[24..26].copy_from_slice(&checksum.finish().to_be_bytes());
+
+        let (header, payload) = decode_packet(DecoderBufferMut::new(&mut frame))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(payload.as_less_safe_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(header.ecn, ExplicitCongestionNotification::Ect0);
+        assert_eq!(
+            header.path.remote_address.mac,
+            ethernet::MacAddress::new([2, 0, 0, 0, 0, 1])
+        );
+        assert_eq!(
+            header.path.remote_address.socket,
+            SocketAddressV4::new([192, 168, 0, 1], 0x1234).into()
+        );
+        assert_eq!(
+            header.path.local_address.socket,
+            SocketAddressV4::new([192, 168, 0, 2], 0x1235).into()
+        );
+    }
+
+    #[test]
+    fn ipv4_invalid_checksum_test() {
+        let mut frame = hex_literal::hex!(
+            "020000000002 020000000001 0800
+             4500 0022 0000 4000 4011 ffff c0a80001 c0a80002
+             1234 1235 000e 0000
+             010203040506"
+        );
+
+        assert!(decode_packet(DecoderBufferMut::new(&mut frame)).is_err());
+    }
+
+    #[test]
+    fn ipv4_fragment_test() {
+        // the more fragments flag is set
+        let mut frame = hex_literal::hex!(
+            "020000000002 020000000001 0800
+             4500 0022 0000 2000 4011 0000 c0a80001 c0a80002
+             1234 1235 000e 0000
+             010203040506"
+        );
+
+        let mut checksum = Checksum::default();
+        checksum.write(&frame[14..34]);
+        frame[24..26].copy_from_slice(&checksum.finish().to_be_bytes());
+
+        assert!(decode_packet(DecoderBufferMut::new(&mut frame))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn ipv4_truncated_test() {
+        // the total length includes 4 more bytes than the frame
+        let mut frame = hex_literal::hex!(
+            "020000000002 020000000001 0800
+             4500 0026 0000 4000 4011 0000 c0a80001 c0a80002
+             1234 1235 0012 0000
+             010203040506"
+        );
+
+        let mut checksum = Checksum::default();
+        checksum.write(&frame[14..34]);
+        frame[24..26].copy_from_slice(&checksum.finish().to_be_bytes());
+
+        assert!(decode_packet(DecoderBufferMut::new(&mut frame)).is_err());
+    }
+
+    /// Returns an IPv6 frame with the UDP checksum left empty
+    fn ipv6_frame() -> [u8; 68] {
+        // ethernet, ipv6 with the ECN codepoint set to CE, udp and payload
+        hex_literal::hex!(
+            "020000000002 020000000001 86dd
+             6030 0000 000e 1140
+             fe800000000000000000000000000001
+             fe800000000000000000000000000002
+             1234 1235 000e 0000
+             010203040506"
+        )
+    }
+
+    #[test]
+    fn ipv6_test() {
+        let mut frame = ipv6_frame();
+
+        // fill in the UDP checksum
+        let mut checksum = Checksum::default();
+        checksum.write(&frame[22..54]);
+        checksum.write_u32(14);
+        checksum.write_u32(17);
+        checksum.write(&frame[54..]);
+        frame[60..62].copy_from_slice(&checksum.finish().to_be_bytes());
+
+        let (header, payload) = decode_packet(DecoderBufferMut::new(&mut frame))
+            .unwrap()
+            .unwrap();
+
+        let mut remote_ip = [0u8; 16];
+        remote_ip[..2].copy_from_slice(&[0xfe, 0x80]);
+        let mut local_ip = remote_ip;
+        remote_ip[15] = 1;
+        local_ip[15] = 2;
+
+        assert_eq!(payload.as_less_safe_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(header.ecn, ExplicitCongestionNotification::Ce);
+        assert_eq!(
+            header.path.local_address.mac,
+            ethernet::MacAddress::new([2, 0, 0, 0, 0, 2])
+        );
+        assert_eq!(
+            header.path.remote_address.socket,
+            SocketAddressV6::new(remote_ip, 0x1234).into()
+        );
+        assert_eq!(
+            header.path.local_address.socket,
+            SocketAddressV6::new(local_ip, 0x1235).into()
+        );
+    }
+
+    #[test]
+    fn ipv6_missing_checksum_test() {
+        // the UDP checksum is required for IPv6
+        let mut frame = ipv6_frame();
+
+        assert!(decode_packet(DecoderBufferMut::new(&mut frame)).is_err());
+    }
+
+    #[test]
+    fn ipv6_extension_header_test() {
+        // the next header is a hop-by-hop options header
+        let mut frame = ipv6_frame();
+        frame[20] = 0;
+
+        assert!(decode_packet(DecoderBufferMut::new(&mut frame))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn arp_test() {
+        let mut frame = hex_literal::hex!("ffffffffffff 020000000001 0806 0001");
+
+        assert!(decode_packet(DecoderBufferMut::new(&mut frame))
+            .unwrap()
+            .is_none());
+    }
+}
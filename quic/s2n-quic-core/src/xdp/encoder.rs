@@ -0,0 +1,433 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::path::Tuple;
+use crate::{
+    inet::{
+        checksum::Checksum,
+        ethernet::{self, EtherType},
+        ip, ipv4, ipv6, udp, SocketAddress,
+    },
+    io::tx,
+};
+use core::mem::size_of;
+use s2n_codec::DecoderBufferMut;
+
+/// The default number of hops a packet can take before being discarded
+const DEFAULT_TTL: u8 = 64;
+
+/// Returns the length of the headers which precede the UDP payload for the given path
+#[inline]
+pub fn header_len(path: &Tuple) -> usize {
+    let ip_len = if is_ipv4(path) {
+        size_of::<ipv4::Header>()
+    } else {
+        size_of::<ipv6::Header>()
+    };
+
+    size_of::<ethernet::Header>() + ip_len + size_of::<udp::Header>()
+}
+
+#[inline]
+fn is_ipv4(path: &Tuple) -> bool {
+    matches!(
+        (
+            path.remote_address.socket.unmap(),
+            path.local_address.socket.unmap()
+        ),
+        (SocketAddress::IpV4(_), SocketAddress::IpV4(_))
+    )
+}
+
+/// Encodes a message into `buffer` as an Ethernet frame containing a UDP datagram
+///
+/// The payload is written first, directly into its final position, after which the Ethernet,
+/// IP and UDP headers are filled in along with their checksums. Paths where both addresses are
+/// IPv4, or IPv4-mapped, are sent as IPv4. Otherwise the IPv4 addresses are mapped and sent as
+/// IPv6.
+///
+/// Returns the total length of the frame.
+#[inline]
+pub fn encode_packet<M: tx::Message<Handle = Tuple>>(
+    buffer: &mut [u8],
+    message: &mut M,
+) -> Result<usize, tx::Error> {
+    let path = *message.path_handle();
+    let header_len = header_len(&path);
+
+    let payload = buffer
+        .get_mut(header_len..)
+        .ok_or(tx::Error::UndersizedBuffer)?;
+    let payload_len = message.write_payload(tx::PayloadBuffer::new(payload), 0)?;
+
+    if payload_len == 0 {
+        return Err(tx::Error::EmptyPayload);
+    }
+
+    let udp_len = size_of::<udp::Header>() + payload_len;
+    let udp_len: u16 = udp_len
+        .try_into()
+        .map_err(|_| tx::Error::UndersizedBuffer)?;
+
+    let buffer = DecoderBufferMut::new(&mut buffer[..header_len + payload_len]);
+    let (ethernet, buffer) = buffer
+        .decode::<&mut ethernet::Header>()
+        .map_err(|_| tx::Error::UndersizedBuffer)?;
+
+    *ethernet.destination_mut() = path.remote_address.mac;
+    *ethernet.source_mut() = path.local_address.mac;
+
+    let ecn = message.ecn();
+    let dscp = message.dscp().as_u8();
+    let mut checksum = Checksum::default();
+
+    let buffer = if is_ipv4(&path) {
+        *ethernet.ethertype_mut() = EtherType::IPV4;
+
+        let (header, buffer) = buffer
+            .decode::<&mut ipv4::Header>()
+            .map_err(|_| tx::Error::UndersizedBuffer)?;
+
+        let (source, destination) = match (
+            path.local_address.socket.unmap(),
+            path.remote_address.socket.unmap(),
+        ) {
+            (SocketAddress::IpV4(source), SocketAddress::IpV4(destination)) => {
+                (*source.ip(), *destination.ip())
+            }
+            _ => unreachable!("the path was checked for IPv4 addresses"),
+        };
+
+        let total_len = size_of::<ipv4::Header>() as u16 + udp_len;
+
+        header.vihl_mut().set_version(4).set_header_len(5);
+        header.tos_mut().set_dscp(dscp).set_ecn(ecn);
+        header.total_len_mut().set(total_len);
+        header.id_mut().set(0);
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-14
+        //# In IPv4 [IPv4], the Don't Fragment (DF) bit MUST be set if possible, to
+        //# prevent fragmentation on the path.
+        header
+            .flag_fragment_mut()
+            .set_reserved(false)
+            .set_dont_fragment(true)
+            .set_more_fragments(false)
+            .set_fragment_offset(0);
+
+        *header.ttl_mut() = DEFAULT_TTL;
+        *header.protocol_mut() = ip::Protocol::UDP;
+        *header.source_mut() = source;
+        *header.destination_mut() = destination;
+        header.checksum_mut().set(0);
+
+        let mut header_checksum = Checksum::default();
+        header_checksum.write(header.as_bytes());
+        header.checksum_mut().set(header_checksum.finish());
+
+        // See https://www.rfc-editor.org/rfc/rfc768 for the pseudo header
+        checksum.write(source.as_bytes());
+        checksum.write(destination.as_bytes());
+        checksum.write_u16(ip::Protocol::UDP.id as u16);
+        checksum.write_u16(udp_len);
+
+        buffer
+    } else {
+        *ethernet.ethertype_mut() = EtherType::IPV6;
+
+        let (header, buffer) = buffer
+            .decode::<&mut ipv6::Header>()
+            .map_err(|_| tx::Error::UndersizedBuffer)?;
+
+        let source = *path.local_address.socket.to_ipv6_mapped().ip();
+        let destination = *path.remote_address.socket.to_ipv6_mapped().ip();
+
+        header
+            .vtf_mut()
+            .set_version(6)
+            .set_dscp(dscp)
+            .set_ecn(ecn)
+            .set_flow_label(message.ipv6_flow_label());
+        header.payload_len_mut().set(udp_len);
+        *header.next_header_mut() = ip::Protocol::UDP;
+        *header.hop_limit_mut() = DEFAULT_TTL;
+        *header.source_mut() = source;
+        *header.destination_mut() = destination;
+
+        // See https://www.rfc-editor.org/rfc/rfc8200#section-8.1 for the pseudo header
+        checksum.write(source.as_bytes());
+        checksum.write(destination.as_bytes());
+        checksum.write_u32(udp_len as u32);
+        checksum.write_u32(ip::Protocol::UDP.id as u32);
+
+        buffer
+    };
+
+    let (header, payload) = buffer
+        .decode::<&mut udp::Header>()
+        .map_err(|_| tx::Error::UndersizedBuffer)?;
+
+    header.source_mut().set(path.local_address.socket.port());
+    header
+        .destination_mut()
+        .set(path.remote_address.socket.port());
+    header.len_mut().set(udp_len);
+    header.checksum_mut().set(0);
+
+    checksum.write(header.as_bytes());
+    checksum.write(payload.as_less_safe_slice());
+
+    // A computed checksum of zero is transmitted as all ones, since zero means no checksum
+    let value = match checksum.finish() {
+        0 => 0xffff,
+        value => value,
+    };
+    header.checksum_mut().set(value);
+
+    Ok(header_len + payload_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        inet::{ethernet::MacAddress, Dscp, ExplicitCongestionNotification},
+        path::Handle,
+        xdp::{decoder::decode_packet, path::Address},
+    };
+    use bolero::check;
+
+    struct Message<'a> {
+        path: Tuple,
+        ecn: ExplicitCongestionNotification,
+        dscp: Dscp,
+        payload: &'a [u8],
+    }
+
+    impl<'a> tx::Message for Message<'a> {
+        type Handle = Tuple;
+
+        fn path_handle(&self) -> &Self::Handle {
+            &self.path
+        }
+
+        fn ecn(&mut self) -> ExplicitCongestionNotification {
+            self.ecn
+        }
+
+        fn dscp(&mut self) -> Dscp {
+            self.dscp
+        }
+
+        fn delay(&mut self) -> core::time::Duration {
+            Default::default()
+        }
+
+        fn ipv6_flow_label(&mut self) -> u32 {
+            0
+        }
+
+        fn can_gso(&self, _segment_len: usize, _segment_count: usize) -> bool {
+            false
+        }
+
+        fn write_payload(
+            &mut self,
+            mut buffer: tx::PayloadBuffer,
+            _gso_offset: usize,
+        ) -> Result<usize, tx::Error> {
+            buffer.write(self.payload)
+        }
+    }
+
+    #[test]
+    fn round_trip_test() {
+        check!()
+            .with_type::<(Tuple, ExplicitCongestionNotification, Vec<u8>)>()
+            .for_each(|(path, ecn, payload)| {
+                let mut message = Message {
+                    path: *path,
+                    ecn: *ecn,
+                    dscp: Dscp::default(),
+                    payload,
+                };
+
+                let mut buffer = [0u8; 1500];
+                let len = match encode_packet(&mut buffer, &mut message) {
+                    Ok(len) => len,
+                    Err(tx::Error::EmptyPayload) => {
+                        assert!(payload.is_empty());
+                        return;
+                    }
+                    Err(tx::Error::UndersizedBuffer) => {
+                        assert!(header_len(path) + payload.len() > buffer.len());
+                        return;
+                    }
+                    Err(err) => panic!("unexpected error {err:?}"),
+                };
+
+                let (header, decoded) = decode_packet(DecoderBufferMut::new(&mut buffer[..len]))
+                    .unwrap()
+                    .unwrap();
+
+                assert_eq!(decoded.as_less_safe_slice(), &payload[..]);
+                assert_eq!(header.ecn, *ecn);
+
+                // the receiver sees the path from the other direction
+                let mut received = header.path;
+                received.swap();
+                assert!(Handle::eq(&received, path));
+                assert_eq!(received.remote_address.mac, path.remote_address.mac);
+                assert_eq!(received.local_address.mac, path.local_address.mac);
+            });
+    }
+
+    #[test]
+    fn ipv4_header_test() {
+        let path = Tuple {
+            remote_address: Address {
+                mac: MacAddress::new([2, 0, 0, 0, 0, 2]),
+                socket: crate::inet::SocketAddressV4::new([10, 0, 0, 2], 4433).into(),
+            },
+            local_address: Address {
+                mac: MacAddress::new([2, 0, 0, 0, 0, 1]),
+                socket: crate::inet::SocketAddressV4::new([10, 0, 0, 1], 443).into(),
+            },
+        };
+
+        let mut message = Message {
+            path,
+            ecn: ExplicitCongestionNotification::Ect0,
+            dscp: Dscp::AF41,
+            payload: &[1, 2, 3],
+        };
+
+        let mut buffer = [0u8; 128];
+        let len = encode_packet(&mut buffer, &mut message).unwrap();
+        assert_eq!(len, 14 + 20 + 8 + 3);
+        assert_eq!(header_len(&path), 14 + 20 + 8);
+
+        let buffer = s2n_codec::DecoderBuffer::new(&buffer[..len]);
+        let (_, buffer) = buffer.decode::<&ethernet::Header>().unwrap();
+        let (header, _) = buffer.decode::<&ipv4::Header>().unwrap();
+        assert!(header.flag_fragment().dont_fragment());
+        assert_eq!(header.total_len().get(), 31);
+        assert_eq!(header.tos().ecn(), ExplicitCongestionNotification::Ect0);
+        assert_eq!(header.tos().dscp(), Dscp::AF41.as_u8());
+    }
+
+    #[test]
+    fn ipv6_header_test() {
+        // a mix of IPv4 and IPv6 addresses is sent as IPv6 with the IPv4 address mapped
+        let path = Tuple {
+            remote_address: Address {
+                mac: MacAddress::new([2, 0, 0, 0, 0, 2]),
+                socket: crate::inet::SocketAddressV6::new(
+                    [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2],
+                    4433,
+                )
+                .into(),
+            },
+            local_address: Address {
+                mac: MacAddress::new([2, 0, 0, 0, 0, 1]),
+                socket: crate::inet::SocketAddressV4::new([10, 0, 0, 1], 443).into(),
+            },
+        };
+
+        struct FlowLabel<'a>(Message<'a>);
+
+        impl<'a> tx::Message for FlowLabel<'a> {
+            type Handle = Tuple;
+
+            fn path_handle(&self) -> &Self::Handle {
+                self.0.path_handle()
+            }
+
+            fn ecn(&mut self) -> ExplicitCongestionNotification {
+                self.0.ecn()
+            }
+
+            fn dscp(&mut self) -> Dscp {
+                self.0.dscp()
+            }
+
+            fn delay(&mut self) -> core::time::Duration {
+                self.0.delay()
+            }
+
+            fn ipv6_flow_label(&mut self) -> u32 {
+                0x12345
+            }
+
+            fn can_gso(&self, segment_len: usize, segment_count: usize) -> bool {
+                self.0.can_gso(segment_len, segment_count)
+            }
+
+            fn write_payload(
+                &mut self,
+                buffer: tx::PayloadBuffer,
+                gso_offset: usize,
+            ) -> Result<usize, tx::Error> {
+                self.0.write_payload(buffer, gso_offset)
+            }
+        }
+
+        let mut message = FlowLabel(Message {
+            path,
+            ecn: ExplicitCongestionNotification::Ect1,
+            dscp: Dscp::AF41,
+            payload: &[1, 2, 3],
+        });
+
+        let mut buffer = [0u8; 128];
+        let len = encode_packet(&mut buffer, &mut message).unwrap();
+        assert_eq!(len, 14 + 40 + 8 + 3);
+        assert_eq!(header_len(&path), 14 + 40 + 8);
+
+        let buffer = s2n_codec::DecoderBuffer::new(&buffer[..len]);
+        let (ethernet, buffer) = buffer.decode::<&ethernet::Header>().unwrap();
+        assert_eq!(*ethernet.ethertype(), EtherType::IPV6);
+        assert_eq!(*ethernet.destination(), path.remote_address.mac);
+        assert_eq!(*ethernet.source(), path.local_address.mac);
+
+        let (header, _) = buffer.decode::<&ipv6::Header>().unwrap();
+        assert_eq!(header.vtcfl().version(), 6);
+        assert_eq!(header.vtcfl().ecn(), ExplicitCongestionNotification::Ect1);
+        assert_eq!(header.vtcfl().dscp(), Dscp::AF41.as_u8());
+        assert_eq!(header.vtcfl().flow_label(), 0x12345);
+        assert_eq!(header.payload_len().get(), 11);
+        assert_eq!(*header.hop_limit(), DEFAULT_TTL);
+        assert_eq!(
+            *header.source(),
+            *path.local_address.socket.to_ipv6_mapped().ip()
+        );
+    }
+
+    #[test]
+    fn undersized_buffer_test() {
+        let path = Tuple {
+            remote_address: Address {
+                mac: MacAddress::new([2, 0, 0, 0, 0, 2]),
+                socket: crate::inet::SocketAddressV4::new([10, 0, 0, 2], 4433).into(),
+            },
+            local_address: Address {
+                mac: MacAddress::new([2, 0, 0, 0, 0, 1]),
+                socket: crate::inet::SocketAddressV4::new([10, 0, 0, 1], 443).into(),
+            },
+        };
+
+        let mut message = Message {
+            path,
+            ecn: ExplicitCongestionNotification::default(),
+            dscp: Dscp::default(),
+            payload: &[1, 2, 3],
+        };
+
+        // the buffer can't hold the headers
+        let mut buffer = [0u8; 20];
+        assert!(matches!(
+            encode_packet(&mut buffer, &mut message),
+            Err(tx::Error::UndersizedBuffer)
+        ));
+    }
+}
@@ -0,0 +1,11 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal software UDP/IP stack for IO providers which send and receive raw Ethernet frames,
+//! such as AF_XDP sockets.
+//!
+//! Frames are decoded and encoded in place with the header types in [`crate::inet`].
+
+pub mod decoder;
+pub mod encoder;
+pub mod path;
@@ -0,0 +1,90 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    inet::{ethernet::MacAddress, SocketAddress, SocketAddressV4},
+    path::{self, Handle},
+};
+
+#[cfg(any(test, feature = "generator"))]
+use bolero_generator::*;
+
+/// A link-layer and network address for one side of a path
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(any(test, feature = "generator"), derive(TypeGenerator))]
+pub struct Address {
+    pub mac: MacAddress,
+    pub socket: SocketAddress,
+}
+
+/// A path handle which includes the link-layer addresses of each side
+///
+/// Receiving a datagram records the MAC addresses from the Ethernet header so a response can be
+/// sent without resolving the peer's address. Paths that are created from only a remote
+/// address, e.g. by a client, have unspecified MAC addresses, which are resolved by the IO
+/// provider before transmission.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(any(test, feature = "generator"), derive(TypeGenerator))]
+pub struct Tuple {
+    pub remote_address: Address,
+    pub local_address: Address,
+}
+
+impl Tuple {
+    /// Swaps the direction of the path
+    #[inline]
+    pub fn swap(&mut self) {
+        core::mem::swap(&mut self.remote_address, &mut self.local_address)
+    }
+}
+
+impl Handle for Tuple {
+    #[inline]
+    fn from_remote_address(remote_address: path::RemoteAddress) -> Self {
+        Self {
+            remote_address: Address {
+                mac: Default::default(),
+                socket: remote_address.0,
+            },
+            local_address: Address {
+                mac: Default::default(),
+                socket: SocketAddressV4::UNSPECIFIED.into(),
+            },
+        }
+    }
+
+    #[inline]
+    fn remote_address(&self) -> path::RemoteAddress {
+        self.remote_address.socket.into()
+    }
+
+    #[inline]
+    fn local_address(&self) -> path::LocalAddress {
+        self.local_address.socket.into()
+    }
+
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        // the link-layer addresses can change without the network path changing
+        PartialEq::eq(
+            &self.local_address.socket.unmap(),
+            &other.local_address.socket.unmap(),
+        ) && Handle::eq(&self.remote_address(), &other.remote_address())
+    }
+
+    #[inline]
+    fn strict_eq(&self, other: &Self) -> bool {
+        PartialEq::eq(self, other)
+    }
+
+    #[inline]
+    fn with_local_address(&self, local_address: path::LocalAddress) -> Option<Self> {
+        Some(Self {
+            remote_address: self.remote_address,
+            local_address: Address {
+                mac: self.local_address.mac,
+                socket: local_address.0,
+            },
+        })
+    }
+}
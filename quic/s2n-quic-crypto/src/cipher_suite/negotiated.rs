@@ -47,19 +47,23 @@ impl From<TLS_AES_128_GCM_SHA256> for NegotiatedCipherSuite {
 }
 
 impl NegotiatedCipherSuite {
-    /// Create a cipher_suite with a given negotiated algorithm and secret
-    pub fn new(algorithm: &aead::Algorithm, secret: hkdf::Prk) -> Option<(Self, HeaderKey)> {
+    /// Create a cipher_suite for the QUIC `version` with a given negotiated algorithm and secret
+    pub fn new(
+        version: u32,
+        algorithm: &aead::Algorithm,
+        secret: hkdf::Prk,
+    ) -> Option<(Self, HeaderKey)> {
         Some(match algorithm {
             _ if algorithm == &aead::AES_256_GCM => {
-                let (cipher_suite, header_key) = TLS_AES_256_GCM_SHA384::new(secret);
+                let (cipher_suite, header_key) = TLS_AES_256_GCM_SHA384::new(version, secret);
                 (cipher_suite.into(), header_key)
             }
             _ if algorithm == &aead::CHACHA20_POLY1305 => {
-                let (cipher_suite, header_key) = TLS_CHACHA20_POLY1305_SHA256::new(secret);
+                let (cipher_suite, header_key) = TLS_CHACHA20_POLY1305_SHA256::new(version, secret);
                 (cipher_suite.into(), header_key)
             }
             _ if algorithm == &aead::AES_128_GCM => {
-                let (cipher_suite, header_key) = TLS_AES_128_GCM_SHA256::new(secret);
+                let (cipher_suite, header_key) = TLS_AES_128_GCM_SHA256::new(version, secret);
                 (cipher_suite.into(), header_key)
             }
             _ => return None,
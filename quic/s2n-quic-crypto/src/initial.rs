@@ -7,9 +7,10 @@ use s2n_quic_core::{
     crypto::{
         self,
         label::{CLIENT_IN, SERVER_IN},
-        CryptoError, Key, INITIAL_SALT,
+        CryptoError, Key, INITIAL_SALT, INITIAL_SALT_V2,
     },
     endpoint,
+    packet::long::VERSION_2,
 };
 
 header_key!(InitialHeaderKey);
@@ -25,12 +26,24 @@ pub struct InitialKey {
 lazy_static::lazy_static! {
     /// Compute the Initial salt once, as the seed is constant
     static ref INITIAL_SIGNING_KEY: hkdf::Salt = hkdf::Salt::new(hkdf::HKDF_SHA256, &INITIAL_SALT);
+
+    static ref INITIAL_SIGNING_KEY_V2: hkdf::Salt =
+        hkdf::Salt::new(hkdf::HKDF_SHA256, &INITIAL_SALT_V2);
 }
 
 impl InitialKey {
-    fn new(endpoint: endpoint::Type, connection_id: &[u8]) -> (Self, InitialHeaderKey) {
-        let initial_secret = INITIAL_SIGNING_KEY.extract(connection_id);
-        let digest = INITIAL_SIGNING_KEY.algorithm();
+    fn new(
+        endpoint: endpoint::Type,
+        version: u32,
+        connection_id: &[u8],
+    ) -> (Self, InitialHeaderKey) {
+        let signing_key = if version == VERSION_2 {
+            &*INITIAL_SIGNING_KEY_V2
+        } else {
+            &*INITIAL_SIGNING_KEY
+        };
+        let initial_secret = signing_key.extract(connection_id);
+        let digest = signing_key.algorithm();
 
         let client_secret = initial_secret
             .expand(&[&CLIENT_IN], digest)
@@ -44,12 +57,12 @@ impl InitialKey {
 
         let (sealer, opener) = match endpoint {
             endpoint::Type::Client => (
-                CipherSuite::new(client_secret),
-                CipherSuite::new(server_secret),
+                CipherSuite::new(version, client_secret),
+                CipherSuite::new(version, server_secret),
             ),
             endpoint::Type::Server => (
-                CipherSuite::new(server_secret),
-                CipherSuite::new(client_secret),
+                CipherSuite::new(version, server_secret),
+                CipherSuite::new(version, client_secret),
             ),
         };
 
@@ -71,12 +84,12 @@ impl InitialKey {
 impl crypto::InitialKey for InitialKey {
     type HeaderKey = InitialHeaderKey;
 
-    fn new_server(connection_id: &[u8]) -> (Self, Self::HeaderKey) {
-        Self::new(endpoint::Type::Server, connection_id)
+    fn new_server(version: u32, connection_id: &[u8]) -> (Self, Self::HeaderKey) {
+        Self::new(endpoint::Type::Server, version, connection_id)
     }
 
-    fn new_client(connection_id: &[u8]) -> (Self, Self::HeaderKey) {
-        Self::new(endpoint::Type::Client, connection_id)
+    fn new_client(version: u32, connection_id: &[u8]) -> (Self, Self::HeaderKey) {
+        Self::new(endpoint::Type::Client, version, connection_id)
     }
 }
 
@@ -133,18 +146,24 @@ mod tests {
                 EXAMPLE_CLIENT_INITIAL_PAYLOAD, EXAMPLE_CLIENT_INITIAL_PROTECTED_PACKET,
                 EXAMPLE_DCID, EXAMPLE_SERVER_INITIAL_PAYLOAD,
                 EXAMPLE_SERVER_INITIAL_PROTECTED_PACKET,
+                EXAMPLE_SERVER_INITIAL_PROTECTED_PACKET_V2,
             },
             InitialKey as _,
         },
         inet::SocketAddress,
-        packet::{encoding::PacketEncoder, initial::CleartextInitial, ProtectedPacket},
+        packet::{
+            encoding::PacketEncoder,
+            initial::CleartextInitial,
+            long::{VERSION_1, VERSION_2},
+            ProtectedPacket,
+        },
     };
 
     #[test]
     fn rfc_example_server_test() {
         test_round_trip(
-            &InitialKey::new_client(&EXAMPLE_DCID),
-            &InitialKey::new_server(&EXAMPLE_DCID),
+            &InitialKey::new_client(VERSION_1, &EXAMPLE_DCID),
+            &InitialKey::new_server(VERSION_1, &EXAMPLE_DCID),
             &EXAMPLE_CLIENT_INITIAL_PROTECTED_PACKET,
             &EXAMPLE_CLIENT_INITIAL_PAYLOAD,
         );
@@ -153,13 +172,44 @@ mod tests {
     #[test]
     fn rfc_example_client_test() {
         test_round_trip(
-            &InitialKey::new_server(&EXAMPLE_DCID),
-            &InitialKey::new_client(&EXAMPLE_DCID),
+            &InitialKey::new_server(VERSION_1, &EXAMPLE_DCID),
+            &InitialKey::new_client(VERSION_1, &EXAMPLE_DCID),
             &EXAMPLE_SERVER_INITIAL_PROTECTED_PACKET,
             &EXAMPLE_SERVER_INITIAL_PAYLOAD,
         );
     }
 
+    #[test]
+    fn rfc_example_client_version_2_test() {
+        test_round_trip(
+            &InitialKey::new_server(VERSION_2, &EXAMPLE_DCID),
+            &InitialKey::new_client(VERSION_2, &EXAMPLE_DCID),
+            &EXAMPLE_SERVER_INITIAL_PROTECTED_PACKET_V2,
+            &EXAMPLE_SERVER_INITIAL_PAYLOAD,
+        );
+    }
+
+    #[test]
+    fn version_mismatch_test() {
+        let (key, header_key) = InitialKey::new_client(VERSION_1, &EXAMPLE_DCID);
+        let mut protected_packet = EXAMPLE_SERVER_INITIAL_PROTECTED_PACKET_V2.to_vec();
+        let decoder = DecoderBufferMut::new(&mut protected_packet);
+        let remote_address = SocketAddress::default();
+        let connection_info = ConnectionInfo::new(&remote_address);
+        let (packet, _) = ProtectedPacket::decode(decoder, &connection_info, &20).unwrap();
+
+        let packet = match packet {
+            ProtectedPacket::Initial(initial) => initial,
+            _ => panic!("expected initial packet type"),
+        };
+
+        // version 1 keys can't open a version 2 packet
+        assert!(packet
+            .unprotect(&header_key, Default::default())
+            .and_then(|packet| packet.decrypt(&key))
+            .is_err());
+    }
+
     fn test_round_trip(
         sealer: &(InitialKey, InitialHeaderKey),
         opener: &(InitialKey, InitialHeaderKey),
@@ -31,7 +31,7 @@ mod tests {
     use crate::cipher_suite::TLS_CHACHA20_POLY1305_SHA256;
     use hex_literal::hex;
     use ring::hkdf;
-    use s2n_quic_core::crypto::Key;
+    use s2n_quic_core::{crypto::Key, packet::long::VERSION_1};
 
     //= https://www.rfc-editor.org/rfc/rfc9001#appendix-A.5
     //# In this example, TLS produces an application write secret from which
@@ -75,14 +75,14 @@ mod tests {
     ) -> (TLS_CHACHA20_POLY1305_SHA256, TLS_CHACHA20_POLY1305_SHA256) {
         // Create a cipher based on the initial secret
         let key = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, secret);
-        let cipher = TLS_CHACHA20_POLY1305_SHA256::new(key);
+        let cipher = TLS_CHACHA20_POLY1305_SHA256::new(VERSION_1, key);
 
         // Create the cipher after a Key Update has occurred
         let next_cipher = cipher.0.update();
 
         // Create a cipher based on the expected post-update secret
         let next_key = hkdf::Prk::new_less_safe(hkdf::HKDF_SHA256, next_secret);
-        let expected_next_cipher = TLS_CHACHA20_POLY1305_SHA256::new(next_key);
+        let expected_next_cipher = TLS_CHACHA20_POLY1305_SHA256::new(VERSION_1, next_key);
 
         (next_cipher, expected_next_cipher.0)
     }
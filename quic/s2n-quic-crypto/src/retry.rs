@@ -3,10 +3,13 @@
 
 use core::convert::TryInto;
 use ring::aead;
-use s2n_quic_core::crypto::{
-    self,
-    retry::{IntegrityTag, NONCE_BYTES, SECRET_KEY_BYTES},
-    CryptoError,
+use s2n_quic_core::{
+    crypto::{
+        self,
+        retry::{IntegrityTag, NONCE_BYTES, NONCE_BYTES_V2, SECRET_KEY_BYTES, SECRET_KEY_BYTES_V2},
+        CryptoError,
+    },
+    packet::long::VERSION_2,
 };
 
 lazy_static::lazy_static! {
@@ -14,6 +17,22 @@ lazy_static::lazy_static! {
     static ref SECRET_KEY: aead::LessSafeKey = aead::LessSafeKey::new(
         aead::UnboundKey::new(&aead::AES_128_GCM, &SECRET_KEY_BYTES).unwrap(),
     );
+
+    static ref SECRET_KEY_V2: aead::LessSafeKey = aead::LessSafeKey::new(
+        aead::UnboundKey::new(&aead::AES_128_GCM, &SECRET_KEY_BYTES_V2).unwrap(),
+    );
+}
+
+/// Returns the version of the Retry packet contained in the pseudo packet
+///
+/// The pseudo packet starts with the length-prefixed original destination connection ID,
+/// followed by the first byte and the version of the Retry packet.
+#[inline]
+fn pseudo_packet_version(pseudo_packet: &[u8]) -> Option<u32> {
+    let odcid_len = *pseudo_packet.first()? as usize;
+    let offset = 1 + odcid_len + 1;
+    let version = pseudo_packet.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes(version.try_into().ok()?))
 }
 
 #[derive(Debug)]
@@ -21,8 +40,13 @@ pub struct RetryKey;
 
 impl crypto::RetryKey for RetryKey {
     fn generate_tag(pseudo_packet: &[u8]) -> IntegrityTag {
-        let nonce = aead::Nonce::assume_unique_for_key(NONCE_BYTES);
-        let tag = SECRET_KEY
+        let (key, nonce) = if pseudo_packet_version(pseudo_packet) == Some(VERSION_2) {
+            (&*SECRET_KEY_V2, NONCE_BYTES_V2)
+        } else {
+            (&*SECRET_KEY, NONCE_BYTES)
+        };
+        let nonce = aead::Nonce::assume_unique_for_key(nonce);
+        let tag = key
             .seal_in_place_separate_tag(nonce, aead::Aad::from(pseudo_packet), &mut [])
             .expect("in_out len is 0 and should always be less than the nonce max bytes");
 
@@ -65,6 +89,22 @@ mod tests {
         assert!(RetryKey::validate(&retry::example::PSEUDO_PACKET, invalid_tag).is_err());
     }
 
+    #[test]
+    fn test_tag_validation_version_2() {
+        assert!(RetryKey::validate(
+            &retry::example::PSEUDO_PACKET_V2,
+            retry::example::EXPECTED_TAG_V2
+        )
+        .is_ok());
+
+        // the version 1 tag is not valid for a version 2 packet
+        assert!(RetryKey::validate(
+            &retry::example::PSEUDO_PACKET_V2,
+            retry::example::EXPECTED_TAG
+        )
+        .is_err());
+    }
+
     fn pn(space: PacketNumberSpace) -> TruncatedPacketNumber {
         let pn = space.new_packet_number(VarInt::new(0x1).unwrap());
         pn.truncate(pn).unwrap()
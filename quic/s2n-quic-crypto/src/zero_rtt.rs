@@ -8,9 +8,9 @@ use s2n_quic_core::crypto::{self, CryptoError, HeaderProtectionMask, Key};
 pub struct ZeroRttKey(CipherSuite);
 
 impl ZeroRttKey {
-    /// Create a ZeroRTT cipher suite with a given secret
-    pub fn new(secret: crate::Prk) -> (Self, ZeroRttHeaderKey) {
-        let (key, header_key) = CipherSuite::new(secret);
+    /// Create a ZeroRTT cipher suite for the QUIC `version` with a given secret
+    pub fn new(version: u32, secret: crate::Prk) -> (Self, ZeroRttHeaderKey) {
+        let (key, header_key) = CipherSuite::new(version, secret);
         let key = Self(key);
         let header_key = ZeroRttHeaderKey(header_key);
         (key, header_key)
@@ -19,7 +19,10 @@ struct EndpointMeta {
     timestamp: crate::event::Timestamp,
 }
 
-struct ConnectionInfo {}
+struct ConnectionInfo<'a> {
+    /// The destination connection ID of the first Initial packet sent by the client
+    original_destination_connection_id: ConnectionId<'a>,
+}
 
 // https://tools.ietf.org/id/draft-marx-qlog-event-definitions-quic-h3-02#5.3.3
 struct TransportParameters<'a> {
@@ -364,6 +367,13 @@ enum Frame {
     Datagram {
         len: u16,
     },
+    AckFrequency {
+        sequence_number: u64,
+        ack_eliciting_threshold: u64,
+        request_max_ack_delay: u64,
+        reordering_threshold: u64,
+    },
+    ImmediateAck,
 }
 
 impl IntoEvent<builder::Frame> for &crate::frame::Padding {
@@ -506,6 +516,23 @@ impl IntoEvent<builder::Frame> for &crate::frame::HandshakeDone {
     }
 }
 
+impl IntoEvent<builder::Frame> for &crate::frame::AckFrequency {
+    fn into_event(self) -> builder::Frame {
+        builder::Frame::AckFrequency {
+            sequence_number: self.sequence_number.as_u64(),
+            ack_eliciting_threshold: self.ack_eliciting_threshold.as_u64(),
+            request_max_ack_delay: self.request_max_ack_delay.as_u64(),
+            reordering_threshold: self.reordering_threshold.as_u64(),
+        }
+    }
+}
+
+impl IntoEvent<builder::Frame> for &crate::frame::ImmediateAck {
+    fn into_event(self) -> builder::Frame {
+        builder::Frame::ImmediateAck {}
+    }
+}
+
 impl<Data> IntoEvent<builder::Frame> for &crate::frame::Stream<Data>
 where
     Data: s2n_codec::EncoderValue,
@@ -892,3 +919,12 @@ enum BbrState {
     ProbeBwUp,
     ProbeRtt,
 }
+
+enum DatagramFrameDropReason {
+    /// The deadline of the datagram passed before it could be transmitted.
+    Expired,
+    /// The datagram did not fit into the remaining space of the packet.
+    ExceedsPacketCapacity,
+    /// The datagram is larger than the peer is willing to accept.
+    ExceedsPeerTransportLimits,
+}
@@ -315,3 +315,10 @@ struct BbrStateChanged {
     path_id: u64,
     state: BbrState,
 }
+
+#[event("transport:datagram_frame_dropped")]
+/// An unreliable datagram was dropped by the datagram sender before being transmitted
+struct DatagramFrameDropped {
+    len: u16,
+    reason: DatagramFrameDropReason,
+}
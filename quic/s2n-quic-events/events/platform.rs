@@ -64,6 +64,12 @@ enum PlatformFeatureConfiguration {
     },
     /// Emitted when ECN support is configured
     Ecn { enabled: bool },
+    /// Emitted when receive offload was configured
+    Gro {
+        /// If `true`, multiple datagrams from the same peer may be coalesced into a single
+        /// receive operation
+        enabled: bool,
+    },
     /// Emitted when the maximum transmission unit is configured
     MaxMtu { mtu: u16 },
 }
@@ -18,10 +18,12 @@ fn main() -> Result<(), Error> {
 
     match env.target_os.as_str() {
         "linux" => {
+            supports("gro");
             supports("gso");
             supports("mtu_disc");
             supports("pktinfo");
             supports("tos");
+            supports("txtime");
         }
         "macos" => {
             supports("pktinfo");
@@ -3,8 +3,37 @@
 
 mod select;
 
+#[cfg(any(
+    feature = "tokio",
+    all(
+        feature = "io-uring",
+        target_os = "linux",
+        s2n_quic_platform_socket_msg
+    )
+))]
+mod migration;
+
+#[cfg(all(target_os = "linux", any(feature = "io-uring", feature = "xdp")))]
+mod eventfd;
+
 #[cfg(feature = "tokio")]
 pub mod tokio;
 
+#[cfg(feature = "tokio")]
+pub mod local;
+
+#[cfg(all(
+    feature = "io-uring",
+    target_os = "linux",
+    s2n_quic_platform_socket_msg
+))]
+pub mod uring;
+
+#[cfg(all(feature = "xdp", target_os = "linux"))]
+pub mod xdp;
+
 #[cfg(any(test, feature = "io-testing"))]
 pub mod testing;
+
+#[cfg(feature = "pcap")]
+pub mod pcap;
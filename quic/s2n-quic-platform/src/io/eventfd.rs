@@ -0,0 +1,65 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    io,
+    os::unix::io::{AsRawFd, RawFd},
+    sync::Arc,
+    task::Wake,
+};
+
+/// Wakes an event loop from the application by writing to an `eventfd`
+///
+/// Event loops which don't run on an async runtime wait for the `eventfd` to become readable,
+/// alongside their sockets, so any write from a [`std::task::Waker`] returns from the wait.
+#[derive(Debug)]
+pub struct EventFd(RawFd);
+
+impl EventFd {
+    pub fn new() -> io::Result<Self> {
+        let fd = libc!(eventfd(0, libc::EFD_CLOEXEC))?;
+        Ok(Self(fd))
+    }
+
+    /// Resets the counter after the `eventfd` has become readable
+    ///
+    /// The `eventfd` is in blocking mode so this must only be called after a readiness
+    /// notification.
+    pub fn reset(&self) {
+        let mut value: u64 = 0;
+        let _ = libc!(read(
+            self.0,
+            &mut value as *mut _ as _,
+            core::mem::size_of_val(&value)
+        ));
+    }
+}
+
+impl AsRawFd for EventFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl Wake for EventFd {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let value: u64 = 1;
+        // the only possible error is overflowing the counter, in which case the
+        // event loop already has a pending wakeup
+        let _ = libc!(write(
+            self.0,
+            &value as *const _ as _,
+            core::mem::size_of_val(&value)
+        ));
+    }
+}
+
+impl Drop for EventFd {
+    fn drop(&mut self) {
+        let _ = libc!(close(self.0));
+    }
+}
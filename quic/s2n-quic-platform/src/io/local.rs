@@ -0,0 +1,413 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An IO provider for endpoints which run on the same host
+//!
+//! Datagrams are carried over a `SOCK_DGRAM` Unix socket or an in-process [`Switch`] instead of
+//! UDP, so no UDP ports are needed. Endpoints still address each other with socket addresses,
+//! so each endpoint is given a synthetic IPv6 address in the `fd00::/8` unique local range:
+//!
+//! * Endpoints on a [`Switch`] are given the next unused address when they are started.
+//! * Endpoints on a Unix socket are given an address derived from the path of the socket,
+//!   which is returned by [`unix_address`].
+//!
+//! ECN and GSO aren't supported by either transport.
+
+use super::{
+    migration,
+    select::{self, Select},
+    tokio::clock::Clock,
+};
+use crate::{buffer::default as buffer, socket::std as socket};
+use core::task::{Context, Poll};
+use s2n_quic_core::{
+    endpoint::Endpoint,
+    event::{self, EndpointPublisher as _},
+    inet::SocketAddress,
+    path::MaxMtu,
+    time::Clock as ClockTrait,
+};
+use std::{convert::TryInto, io, io::ErrorKind};
+use tokio::runtime::Handle;
+
+mod memory;
+#[cfg(unix)]
+mod unix;
+
+pub use memory::Switch;
+#[cfg(unix)]
+pub use unix::address as unix_address;
+
+pub type PathHandle = socket::Handle;
+
+/// The network in the synthetic address range used by endpoints on a [`Switch`]
+const MEMORY_NETWORK: u16 = 1;
+/// The network in the synthetic address range used by endpoints on a Unix socket
+#[cfg(unix)]
+const UNIX_NETWORK: u16 = 2;
+
+/// The port of every synthetic address
+const SYNTHETIC_PORT: u16 = 443;
+
+/// Returns the synthetic address `fd00:0:0:<network>:<host>`
+fn synthetic_address(network: u16, host: u64) -> SocketAddress {
+    let mut octets = [0u8; 16];
+    octets[0] = 0xfd;
+    octets[6..8].copy_from_slice(&network.to_be_bytes());
+    octets[8..].copy_from_slice(&host.to_be_bytes());
+    let ip = std::net::Ipv6Addr::from(octets);
+    std::net::SocketAddr::new(ip.into(), SYNTHETIC_PORT).into()
+}
+
+/// A transport which can be driven by the event loop
+trait Transport: socket::Socket<Error = io::Error> + Send + Sync + 'static {
+    fn local_address(&self) -> SocketAddress;
+
+    fn poll_recv_ready(&self, cx: &mut Context) -> Poll<io::Result<()>>;
+
+    fn poll_send_ready(&self, cx: &mut Context) -> Poll<io::Result<()>>;
+}
+
+#[derive(Debug, Default)]
+pub struct Io {
+    builder: Builder,
+}
+
+impl Io {
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    pub fn start<E: Endpoint<PathHandle = PathHandle>>(
+        self,
+        mut endpoint: E,
+    ) -> io::Result<(tokio::task::JoinHandle<()>, SocketAddress)> {
+        let Builder {
+            handle,
+            transport,
+            local_address,
+            max_mtu,
+        } = self.builder;
+
+        let transport = transport
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "missing transport"))?;
+
+        endpoint.set_max_mtu(max_mtu);
+
+        let clock = Clock::default();
+
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: E::ENDPOINT_TYPE,
+                timestamp: clock.get_time(),
+            },
+            None,
+            endpoint.subscriber(),
+        );
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::MaxMtu {
+                mtu: max_mtu.into(),
+            },
+        });
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Gso { max_segments: 1 },
+        });
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Gro { enabled: false },
+        });
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Ecn { enabled: false },
+        });
+
+        let handle = if let Some(handle) = handle {
+            handle
+        } else {
+            Handle::try_current().map_err(|err| std::io::Error::new(io::ErrorKind::Other, err))?
+        };
+
+        // the Unix socket needs to be registered with the runtime's reactor
+        let _guard = handle.enter();
+
+        match transport {
+            TransportConfig::Memory(switch) => {
+                let socket = switch.bind(local_address)?;
+                Ok(spawn(&handle, socket, max_mtu, clock, endpoint))
+            }
+            #[cfg(unix)]
+            TransportConfig::Unix { path, peers } => {
+                if local_address.is_some() {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidInput,
+                        "the local address of a Unix socket is derived from its path",
+                    ));
+                }
+                let socket = unix::Socket::bind(&path, &peers)?;
+                Ok(spawn(&handle, socket, max_mtu, clock, endpoint))
+            }
+        }
+    }
+}
+
+fn spawn<T: Transport, E: Endpoint<PathHandle = PathHandle>>(
+    handle: &Handle,
+    transport: T,
+    max_mtu: MaxMtu,
+    clock: Clock,
+    endpoint: E,
+) -> (tokio::task::JoinHandle<()>, SocketAddress) {
+    let local_address = transport.local_address();
+
+    let rx_buffer = buffer::Buffer::new_with_mtu(max_mtu.into());
+    let tx_buffer = buffer::Buffer::new_with_mtu(max_mtu.into());
+    let mut rx = socket::Queue::new(rx_buffer);
+    let tx = socket::Queue::new(tx_buffer);
+
+    // tell the queue the local address so it can fill it in on each message
+    rx.set_local_address(local_address.into());
+
+    let instance = Instance {
+        clock,
+        transport,
+        rx,
+        tx,
+        endpoint,
+    };
+
+    let task = handle.spawn(async move {
+        if let Err(err) = instance.event_loop().await {
+            let debug = format!("A fatal IO error occurred ({:?}): {err}", err.kind());
+            if cfg!(test) {
+                panic!("{debug}");
+            } else {
+                eprintln!("{debug}");
+            }
+        }
+    });
+
+    (task, local_address)
+}
+
+#[derive(Debug)]
+enum TransportConfig {
+    Memory(Switch),
+    #[cfg(unix)]
+    Unix {
+        path: std::path::PathBuf,
+        peers: Vec<std::path::PathBuf>,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct Builder {
+    handle: Option<Handle>,
+    transport: Option<TransportConfig>,
+    local_address: Option<SocketAddress>,
+    max_mtu: MaxMtu,
+}
+
+impl Builder {
+    #[must_use]
+    pub fn with_handle(mut self, handle: Handle) -> Self {
+        self.handle = Some(handle);
+        self
+    }
+
+    /// Sends and receives datagrams through the in-process `switch`
+    pub fn with_switch(mut self, switch: &Switch) -> io::Result<Self> {
+        self.transport = Some(TransportConfig::Memory(switch.clone()));
+        Ok(self)
+    }
+
+    /// Sets the address of the endpoint on the [`Switch`]
+    ///
+    /// By default, the endpoint is given the next unused synthetic address.
+    pub fn with_local_address(mut self, addr: std::net::SocketAddr) -> io::Result<Self> {
+        self.local_address = Some(addr.into());
+        Ok(self)
+    }
+
+    /// Sends and receives datagrams through a `SOCK_DGRAM` Unix socket bound at `path`
+    ///
+    /// The endpoint's address is [`unix_address(path)`](unix_address). A socket left behind at
+    /// `path` by a previous process is replaced, while binding fails if another endpoint is still
+    /// bound to it. The socket file is removed when the endpoint shuts down.
+    #[cfg(unix)]
+    pub fn with_unix_socket<P: Into<std::path::PathBuf>>(mut self, path: P) -> io::Result<Self> {
+        let peers = match self.transport.take() {
+            Some(TransportConfig::Unix { peers, .. }) => peers,
+            _ => vec![],
+        };
+        self.transport = Some(TransportConfig::Unix {
+            path: path.into(),
+            peers,
+        });
+        Ok(self)
+    }
+
+    /// Allows the endpoint to send to the Unix socket bound at `path`
+    ///
+    /// This is needed for peers which the endpoint connects to, since their paths can't be
+    /// derived from their addresses. Peers which send a datagram to the endpoint are learned
+    /// automatically.
+    #[cfg(unix)]
+    pub fn with_unix_peer<P: Into<std::path::PathBuf>>(mut self, path: P) -> io::Result<Self> {
+        match self.transport.as_mut() {
+            Some(TransportConfig::Unix { peers, .. }) => {
+                peers.push(path.into());
+                Ok(self)
+            }
+            _ => Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "peers can only be added after calling `with_unix_socket`",
+            )),
+        }
+    }
+
+    /// Sets the largest maximum transmission unit (MTU) that can be sent on a path
+    pub fn with_max_mtu(mut self, max_mtu: u16) -> io::Result<Self> {
+        self.max_mtu = max_mtu
+            .try_into()
+            .map_err(|err| io::Error::new(ErrorKind::InvalidInput, format!("{err}")))?;
+        Ok(self)
+    }
+
+    pub fn build(self) -> io::Result<Io> {
+        Ok(Io { builder: self })
+    }
+}
+
+#[derive(Debug)]
+struct Instance<T, E> {
+    clock: Clock,
+    transport: T,
+    rx: socket::Queue<buffer::Buffer>,
+    tx: socket::Queue<buffer::Buffer>,
+    endpoint: E,
+}
+
+impl<T: Transport, E: Endpoint<PathHandle = PathHandle>> Instance<T, E> {
+    async fn event_loop(self) -> io::Result<()> {
+        let Self {
+            clock,
+            transport,
+            mut rx,
+            mut tx,
+            mut endpoint,
+        } = self;
+
+        // the transports can't bind other ports, so migrations to them are rejected
+        let port = transport.local_address().port();
+        let mut unsupported = migration::Unsupported;
+
+        let mut timer = clock.timer();
+
+        loop {
+            // Poll for readability if we have free slots available
+            let rx_interest = rx.free_len() > 0;
+            let rx_task = async {
+                if rx_interest {
+                    futures::future::poll_fn(|cx| transport.poll_recv_ready(cx)).await
+                } else {
+                    futures::future::pending().await
+                }
+            };
+
+            // Poll for writablity if we have occupied slots available
+            let tx_interest = tx.occupied_len() > 0;
+            let tx_task = async {
+                if tx_interest {
+                    futures::future::poll_fn(|cx| transport.poll_send_ready(cx)).await
+                } else {
+                    futures::future::pending().await
+                }
+            };
+
+            let wakeups = endpoint.wakeups(&clock);
+            // pin the wakeups future so we don't have to move it into the Select future.
+            tokio::pin!(wakeups);
+
+            let select::Outcome {
+                rx_result,
+                tx_result,
+                timeout_expired,
+                application_wakeup,
+            } = if let Ok(res) = Select::new(rx_task, tx_task, &mut wakeups, &mut timer).await {
+                res
+            } else {
+                // The endpoint has shut down
+                return Ok(());
+            };
+
+            let wakeup_timestamp = clock.get_time();
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp: wakeup_timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            publisher.on_platform_event_loop_wakeup(event::builder::PlatformEventLoopWakeup {
+                timeout_expired,
+                rx_ready: rx_result.is_some(),
+                tx_ready: tx_result.is_some(),
+                application_wakeup,
+            });
+
+            if let Some(result) = tx_result {
+                result?;
+                would_block(tx.tx(&transport, &mut publisher))?;
+            }
+
+            if let Some(result) = rx_result {
+                result?;
+                would_block(rx.rx(&transport, &mut publisher))?;
+                endpoint.receive(&mut rx.rx_queue(), &clock);
+            }
+
+            let mut queue = migration::Queue::new(tx.tx_queue(), port, &mut unsupported);
+            endpoint.transmit(&mut queue, &clock);
+
+            let timeout = endpoint.timeout();
+
+            if let Some(timeout) = timeout {
+                timer.update(timeout);
+            }
+
+            let timestamp = clock.get_time();
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            // notify the application that we're going to sleep
+            let timeout = timeout.map(|t| t.saturating_duration_since(timestamp));
+            publisher.on_platform_event_loop_sleep(event::builder::PlatformEventLoopSleep {
+                timeout,
+                processing_duration: timestamp.saturating_duration_since(wakeup_timestamp),
+            });
+        }
+    }
+}
+
+/// Treats a transport that isn't ready as having made no progress
+///
+/// The readiness is cleared by the transport so it will be polled again on the next iteration.
+#[inline]
+fn would_block(result: io::Result<usize>) -> io::Result<usize> {
+    match result {
+        Err(err) if err.kind() == ErrorKind::WouldBlock => Ok(0),
+        result => result,
+    }
+}
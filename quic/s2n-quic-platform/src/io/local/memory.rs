@@ -0,0 +1,207 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{synthetic_address, Transport, MEMORY_NETWORK};
+use crate::socket::std as socket;
+use core::task::{Context, Poll, Waker};
+use s2n_quic_core::inet::SocketAddress;
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    sync::{Arc, Mutex, Weak},
+};
+
+/// The number of datagrams which can be waiting to be received by an endpoint
+///
+/// Datagrams sent to a full mailbox are dropped, as they would be by a full UDP socket buffer.
+const MAILBOX_CAPACITY: usize = 1024;
+
+/// An in-process network which delivers datagrams between the endpoints bound to it
+///
+/// Each endpoint is given a synthetic address when it is bound, unless one is provided with
+/// [`Builder::with_local_address`](super::Builder::with_local_address). Datagrams sent to an
+/// address without an endpoint are dropped.
+#[derive(Clone, Debug, Default)]
+pub struct Switch {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    mailboxes: HashMap<SocketAddress, Weak<Mailbox>>,
+    next_host: u64,
+}
+
+impl Switch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a synthetic address which isn't bound by any endpoint on the switch
+    pub fn generate_address(&self) -> SocketAddress {
+        let mut state = self.state.lock().unwrap();
+        state.generate_address()
+    }
+
+    pub(super) fn bind(&self, address: Option<SocketAddress>) -> io::Result<Socket> {
+        let mut state = self.state.lock().unwrap();
+
+        let address = match address {
+            Some(address) => {
+                let is_bound = state
+                    .mailboxes
+                    .get(&address)
+                    .map_or(false, |mailbox| mailbox.strong_count() > 0);
+                if is_bound {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AddrInUse,
+                        format!("{address} is already bound"),
+                    ));
+                }
+                address
+            }
+            None => state.generate_address(),
+        };
+
+        let mailbox = Arc::new(Mailbox::default());
+        state.mailboxes.insert(address, Arc::downgrade(&mailbox));
+
+        Ok(Socket {
+            switch: self.clone(),
+            address,
+            mailbox,
+        })
+    }
+
+    fn lookup(&self, address: &SocketAddress) -> Option<Arc<Mailbox>> {
+        let state = self.state.lock().unwrap();
+        state.mailboxes.get(address)?.upgrade()
+    }
+}
+
+impl State {
+    fn generate_address(&mut self) -> SocketAddress {
+        loop {
+            self.next_host += 1;
+            let address = synthetic_address(MEMORY_NETWORK, self.next_host);
+            if !self.mailboxes.contains_key(&address) {
+                return address;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Mailbox {
+    state: Mutex<MailboxState>,
+}
+
+#[derive(Debug, Default)]
+struct MailboxState {
+    datagrams: VecDeque<(SocketAddress, Vec<u8>)>,
+    waker: Option<Waker>,
+}
+
+/// An endpoint's binding on a [`Switch`]
+#[derive(Debug)]
+pub(super) struct Socket {
+    switch: Switch,
+    address: SocketAddress,
+    mailbox: Arc<Mailbox>,
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        let mut state = self.switch.state.lock().unwrap();
+        state.mailboxes.remove(&self.address);
+    }
+}
+
+impl socket::Socket for Socket {
+    type Error = io::Error;
+
+    fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, Option<SocketAddress>), Self::Error> {
+        let mut mailbox = self.mailbox.state.lock().unwrap();
+        let (source, payload) = mailbox
+            .datagrams
+            .pop_front()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::WouldBlock))?;
+
+        // datagrams which don't fit in the buffer are truncated, like they are with UDP
+        let len = payload.len().min(buf.len());
+        buf[..len].copy_from_slice(&payload[..len]);
+
+        Ok((len, Some(source)))
+    }
+
+    fn send_to(&self, buf: &[u8], addr: &SocketAddress) -> Result<usize, Self::Error> {
+        if let Some(mailbox) = self.switch.lookup(addr) {
+            let mut mailbox = mailbox.state.lock().unwrap();
+            if mailbox.datagrams.len() < MAILBOX_CAPACITY {
+                mailbox.datagrams.push_back((self.address, buf.to_vec()));
+                if let Some(waker) = mailbox.waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+
+        // datagrams which can't be delivered are silently dropped
+        Ok(buf.len())
+    }
+}
+
+impl Transport for Socket {
+    fn local_address(&self) -> SocketAddress {
+        self.address
+    }
+
+    fn poll_recv_ready(&self, cx: &mut Context) -> Poll<io::Result<()>> {
+        let mut mailbox = self.mailbox.state.lock().unwrap();
+        if mailbox.datagrams.is_empty() {
+            mailbox.waker = Some(cx.waker().clone());
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn poll_send_ready(&self, _cx: &mut Context) -> Poll<io::Result<()>> {
+        // sending never blocks since datagrams are dropped when the mailbox is full
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use socket::Socket as _;
+
+    #[test]
+    fn switch_test() {
+        let switch = Switch::new();
+        let a = switch.bind(None).unwrap();
+        let b = switch.bind(None).unwrap();
+        assert_ne!(a.local_address(), b.local_address());
+
+        let mut buf = [0u8; 16];
+        assert_eq!(
+            b.recv_from(&mut buf).unwrap_err().kind(),
+            io::ErrorKind::WouldBlock
+        );
+
+        a.send_to(&[1, 2, 3], &b.local_address()).unwrap();
+        let (len, source) = b.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], &[1, 2, 3]);
+        assert_eq!(source, Some(a.local_address()));
+
+        // addresses can't be bound twice while in use
+        let address = a.local_address();
+        assert!(switch.bind(Some(address)).is_err());
+        drop(a);
+        assert!(switch.bind(Some(address)).is_ok());
+
+        // datagrams to unbound addresses are dropped
+        let unbound = switch.generate_address();
+        assert_eq!(b.send_to(&[1], &unbound).unwrap(), 1);
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{synthetic_address, Transport, UNIX_NETWORK};
+use crate::socket::std as socket;
+use core::task::{Context, Poll};
+use s2n_quic_core::inet::SocketAddress;
+use std::{
+    collections::HashMap,
+    io,
+    os::unix::{ffi::OsStrExt, fs::FileTypeExt},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use tokio::net::UnixDatagram;
+
+/// Returns the synthetic address of the endpoint bound to the Unix socket at `path`
+///
+/// The address is derived from the bytes of the path, so the same path must be used by the
+/// endpoint binding the socket and the peers connecting to it.
+pub fn address<P: AsRef<Path>>(path: P) -> SocketAddress {
+    // FNV-1a is used so the address is the same in every process, regardless of how it was built
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in path.as_ref().as_os_str().as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    synthetic_address(UNIX_NETWORK, hash)
+}
+
+/// A `SOCK_DGRAM` Unix socket which maps the paths of its peers to synthetic addresses
+#[derive(Debug)]
+pub(super) struct Socket {
+    socket: UnixDatagram,
+    address: SocketAddress,
+    path: PathBuf,
+    peers: Mutex<HashMap<SocketAddress, PathBuf>>,
+}
+
+impl Socket {
+    /// Binds a socket at `path` which can send to each of the `peers`
+    ///
+    /// A socket left behind at `path` by an endpoint which is no longer running is removed
+    /// first. The socket file is removed again when the socket is dropped.
+    ///
+    /// Must be called in the context of a tokio runtime.
+    pub fn bind(path: &Path, peers: &[PathBuf]) -> io::Result<Self> {
+        remove_stale_socket(path)?;
+        let socket = UnixDatagram::bind(path)?;
+
+        let peers = peers
+            .iter()
+            .map(|peer| (address(peer), peer.clone()))
+            .collect();
+
+        Ok(Self {
+            socket,
+            address: address(path),
+            path: path.to_path_buf(),
+            peers: Mutex::new(peers),
+        })
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Removes the socket file at `path` if no endpoint is bound to it anymore
+///
+/// Files which aren't sockets are left alone so binding fails instead of deleting them.
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    if !metadata.file_type().is_socket() {
+        return Ok(());
+    }
+
+    // connecting only succeeds if another socket is still bound to the path
+    let probe = std::os::unix::net::UnixDatagram::unbound()?;
+    match probe.connect(path) {
+        Ok(()) => Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("{} is bound by another socket", path.display()),
+        )),
+        Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => std::fs::remove_file(path),
+        Err(err) => Err(err),
+    }
+}
+
+impl socket::Socket for Socket {
+    type Error = io::Error;
+
+    fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, Option<SocketAddress>), Self::Error> {
+        let (len, addr) = self.socket.try_recv_from(buf)?;
+
+        // peers which haven't bound their socket to a path can't be replied to
+        let path = match addr.as_pathname() {
+            Some(path) => path,
+            None => return Ok((len, None)),
+        };
+
+        let address = address(path);
+        let mut peers = self.peers.lock().unwrap();
+        peers.entry(address).or_insert_with(|| path.to_path_buf());
+
+        Ok((len, Some(address)))
+    }
+
+    fn send_to(&self, buf: &[u8], addr: &SocketAddress) -> Result<usize, Self::Error> {
+        let path = {
+            let peers = self.peers.lock().unwrap();
+            match peers.get(addr) {
+                Some(path) => path.clone(),
+                // the address doesn't belong to a known peer so the datagram is dropped
+                None => return Ok(buf.len()),
+            }
+        };
+
+        match self.socket.try_send_to(buf, path) {
+            // the peer's socket is gone, which is treated like a lost datagram
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+                ) =>
+            {
+                Ok(buf.len())
+            }
+            result => result,
+        }
+    }
+}
+
+impl Transport for Socket {
+    fn local_address(&self) -> SocketAddress {
+        self.address
+    }
+
+    fn poll_recv_ready(&self, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.socket.poll_recv_ready(cx)
+    }
+
+    fn poll_send_ready(&self, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.socket.poll_send_ready(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use socket::Socket as _;
+
+    #[test]
+    fn address_test() {
+        assert_eq!(address("/run/a.sock"), address("/run/a.sock"));
+        assert_ne!(address("/run/a.sock"), address("/run/b.sock"));
+    }
+
+    #[tokio::test]
+    async fn socket_test() {
+        let dir = std::env::temp_dir().join(format!("s2n-quic-local-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let server_path = dir.join("server.sock");
+        let client_path = dir.join("client.sock");
+
+        let server = Socket::bind(&server_path, &[]).unwrap();
+        let client = Socket::bind(&client_path, &[server_path.clone()]).unwrap();
+
+        client.send_to(&[1, 2, 3], &address(&server_path)).unwrap();
+        futures::future::poll_fn(|cx| server.poll_recv_ready(cx))
+            .await
+            .unwrap();
+
+        let mut buf = [0u8; 16];
+        let (len, source) = server.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], &[1, 2, 3]);
+        assert_eq!(source, Some(client.local_address()));
+
+        // the server learned the path of the client when it received the datagram
+        server.send_to(&[4], &client.local_address()).unwrap();
+        futures::future::poll_fn(|cx| client.poll_recv_ready(cx))
+            .await
+            .unwrap();
+        let (len, source) = client.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], &[4]);
+        assert_eq!(source, Some(server.local_address()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn stale_socket_test() {
+        let dir = std::env::temp_dir().join(format!("s2n-quic-stale-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("server.sock");
+
+        // leave a socket file behind, like a process which exited without cleaning up
+        let stale = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+        drop(stale);
+        assert!(path.exists());
+
+        let socket = Socket::bind(&path, &[]).unwrap();
+
+        // a socket which is still bound isn't replaced
+        let err = Socket::bind(&path, &[]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+        drop(socket);
+        assert!(!path.exists());
+
+        // other files are never removed
+        std::fs::write(&path, b"not a socket").unwrap();
+        assert!(Socket::bind(&path, &[]).is_err());
+        assert!(path.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
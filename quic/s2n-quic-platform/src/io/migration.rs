@@ -0,0 +1,101 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transmission of packets from a local port the endpoint isn't bound to
+//!
+//! Packets are sent from the port their socket is bound to, so a connection which migrates to
+//! another local port needs a socket bound to that port. The [`Queue`] passes those messages to
+//! the IO provider's [`Bind`] implementation, which binds the socket or rejects the message.
+
+use core::time::Duration;
+use s2n_quic_core::{io::tx, path::Handle as _};
+
+/// Sends messages from a local port the endpoint isn't bound to
+pub trait Bind<Handle> {
+    /// Pushes a message to the queue of a socket bound to `port`
+    ///
+    /// Implementations bind the socket on the first message for the port. If the port can't be
+    /// bound, the message is rejected and the path it was sent on fails validation.
+    fn push<M: tx::Message<Handle = Handle>>(
+        &mut self,
+        port: u16,
+        message: M,
+    ) -> Result<tx::Outcome, tx::Error>;
+}
+
+/// Rejects messages from any port other than the one the endpoint is bound to
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Unsupported;
+
+impl<Handle> Bind<Handle> for Unsupported {
+    #[inline]
+    fn push<M: tx::Message<Handle = Handle>>(
+        &mut self,
+        _port: u16,
+        _message: M,
+    ) -> Result<tx::Outcome, tx::Error> {
+        Err(tx::Error::AtCapacity)
+    }
+}
+
+/// A transmission queue which sends messages from the port on their path handle
+///
+/// Messages for the endpoint's port are pushed to the endpoint's queue. Messages for any other
+/// port are passed to `B`.
+pub struct Queue<'a, Q, B> {
+    queue: Q,
+    port: u16,
+    bind: &'a mut B,
+}
+
+impl<'a, Q, B> Queue<'a, Q, B> {
+    /// Creates a queue for an endpoint bound to `port`
+    #[inline]
+    pub fn new(queue: Q, port: u16, bind: &'a mut B) -> Self {
+        Self { queue, port, bind }
+    }
+}
+
+impl<'a, Q: tx::Queue, B: Bind<Q::Handle>> tx::Queue for Queue<'a, Q, B> {
+    type Entry = Q::Entry;
+    type Handle = Q::Handle;
+
+    const SUPPORTS_ECN: bool = Q::SUPPORTS_ECN;
+    const SUPPORTS_PACING: bool = Q::SUPPORTS_PACING;
+    const SUPPORTS_FLOW_LABELS: bool = Q::SUPPORTS_FLOW_LABELS;
+
+    #[inline]
+    fn push<M: tx::Message<Handle = Self::Handle>>(
+        &mut self,
+        message: M,
+    ) -> Result<tx::Outcome, tx::Error> {
+        let port = message.path_handle().local_address().port();
+
+        if port == 0 || port == self.port {
+            return self.queue.push(message);
+        }
+
+        self.bind.push(port, message)
+    }
+
+    /// Returns the pending messages for the endpoint's socket
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        self.queue.as_slice_mut()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[inline]
+    fn max_delay(&self) -> Duration {
+        self.queue.max_delay()
+    }
+}
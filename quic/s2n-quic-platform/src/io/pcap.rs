@@ -0,0 +1,133 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Packet captures for debugging endpoints
+//!
+//! [`Capture`] records each datagram an endpoint sends and receives into a
+//! [pcapng](https://www.ietf.org/archive/id/draft-ietf-opsawg-pcapng-00.html) file, which can
+//! be opened with tools like Wireshark. Datagrams are wrapped in synthesized Ethernet, IP and UDP
+//! headers. If a TLS key log is configured with [`Capture::with_key_log`], the secrets logged
+//! to it are written to the same file as Decryption Secrets Blocks so the packets can be
+//! decrypted without any extra configuration.
+//!
+//! The capture is buffered and flushed every second, as well as when the endpoint is dropped.
+//!
+//! Captures can be read back with a [`Reader`], which also accepts the legacy pcap format
+//! written by tools like tcpdump. Only the testing IO provider can replay a capture to an
+//! endpoint.
+
+mod capture;
+mod reader;
+mod writer;
+
+pub use capture::Capture;
+pub use reader::{Datagram, Reader};
+pub use writer::Writer;
+
+/// The direction of a datagram, relative to the endpoint which captured it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+const SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+const INTERFACE_DESCRIPTION_BLOCK: u32 = 0x0000_0001;
+const SIMPLE_PACKET_BLOCK: u32 = 0x0000_0003;
+const ENHANCED_PACKET_BLOCK: u32 = 0x0000_0006;
+const DECRYPTION_SECRETS_BLOCK: u32 = 0x0000_000A;
+
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+const LINKTYPE_ETHERNET: u16 = 1;
+
+const OPT_END_OF_OPTIONS: u16 = 0;
+const OPT_IF_TSRESOL: u16 = 9;
+const OPT_EPB_FLAGS: u16 = 2;
+
+/// The secrets type for the NSS key log format
+const SECRETS_TYPE_TLS_KEY_LOG: u32 = 0x544c_534b;
+
+/// Splits a capture into the type and body of each block
+///
+/// The blocks are expected to be in the native byte order, which is what [`Writer`] uses.
+#[cfg(test)]
+fn blocks(mut capture: &[u8]) -> Vec<(u32, &[u8])> {
+    let u32_at = |bytes: &[u8], offset: usize| {
+        u32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    };
+
+    let mut blocks = vec![];
+    while !capture.is_empty() {
+        let ty = u32_at(capture, 0);
+        let len = u32_at(capture, 4) as usize;
+        assert_eq!(len % 4, 0, "blocks are padded to 32 bits");
+        assert_eq!(u32_at(capture, len - 4) as usize, len);
+
+        blocks.push((ty, &capture[8..len - 4]));
+        capture = &capture[len..];
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+    use s2n_quic_core::inet::{ExplicitCongestionNotification, SocketAddress};
+
+    #[test]
+    fn round_trip_test() {
+        let local: SocketAddress = "127.0.0.1:4433"
+            .parse::<std::net::SocketAddr>()
+            .unwrap()
+            .into();
+        let remote: SocketAddress = "[::1]:1234".parse::<std::net::SocketAddr>().unwrap().into();
+
+        let mut writer = Writer::new(vec![]).unwrap();
+        writer
+            .write_datagram(
+                Duration::from_millis(1500),
+                Direction::Outbound,
+                &local,
+                &remote,
+                ExplicitCongestionNotification::Ect0,
+                &[1, 2, 3],
+            )
+            .unwrap();
+        writer.write_key_log(b"CLIENT_RANDOM 00 00\n").unwrap();
+        writer
+            .write_datagram(
+                Duration::from_secs(2),
+                Direction::Inbound,
+                &local,
+                &remote,
+                ExplicitCongestionNotification::NotEct,
+                &[4, 5, 6, 7],
+            )
+            .unwrap();
+
+        let capture = writer.into_inner();
+        let datagrams = Reader::new(&capture[..])
+            .unwrap()
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(datagrams.len(), 2);
+
+        let sent = &datagrams[0];
+        assert_eq!(sent.timestamp, Duration::from_millis(1500));
+        assert_eq!(sent.direction, Some(Direction::Outbound));
+        assert_eq!(sent.source.unmap(), local);
+        assert_eq!(sent.destination.unmap(), remote);
+        assert_eq!(sent.ecn, ExplicitCongestionNotification::Ect0);
+        assert_eq!(sent.payload, [1, 2, 3]);
+
+        let received = &datagrams[1];
+        assert_eq!(received.timestamp, Duration::from_secs(2));
+        assert_eq!(received.direction, Some(Direction::Inbound));
+        assert_eq!(received.source.unmap(), remote);
+        assert_eq!(received.destination.unmap(), local);
+        assert_eq!(received.payload, [4, 5, 6, 7]);
+    }
+}
@@ -0,0 +1,714 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Direction, Writer};
+use core::{
+    task::{Context, Poll},
+    time::Duration,
+};
+use s2n_quic_core::{
+    endpoint::{self, CloseError, Endpoint},
+    inet::{Dscp, ExplicitCongestionNotification, SocketAddress, Unspecified as _},
+    io::{
+        rx::{self, Entry as _},
+        tx,
+    },
+    path::{self, MaxMtu},
+    time::{Clock, Timestamp},
+};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+type Output = Box<dyn Write + Send>;
+
+/// How often the buffered capture is flushed to the output
+///
+/// The capture is also flushed when the endpoint is dropped.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An endpoint which records every datagram it sends and receives
+///
+/// Capturing stops if writing to the output fails, since a broken capture shouldn't affect
+/// the endpoint.
+pub struct Capture<E> {
+    endpoint: E,
+    state: Option<State>,
+}
+
+struct State {
+    writer: Writer<Output>,
+    key_log: Option<KeyLog>,
+    /// The endpoint's clock time and the wall clock time when capturing started
+    epoch: Option<(Timestamp, Duration)>,
+    /// The last local address seen on the receive queue, for paths which don't know it
+    local_address: SocketAddress,
+    /// The endpoint's clock time when the capture was last flushed
+    last_flush: Option<Timestamp>,
+}
+
+impl<E: Endpoint> Capture<E> {
+    /// Wraps `endpoint` and writes its datagrams to the pcapng file at `path`
+    pub fn new<P: AsRef<Path>>(endpoint: E, path: P) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Self::with_output(endpoint, Box::new(file))
+    }
+
+    fn with_output(endpoint: E, output: Output) -> io::Result<Self> {
+        let state = State {
+            writer: Writer::new(output)?,
+            key_log: None,
+            epoch: None,
+            local_address: Default::default(),
+            last_flush: None,
+        };
+
+        Ok(Self {
+            endpoint,
+            state: Some(state),
+        })
+    }
+
+    /// Copies the TLS secrets which are appended to the key log at `path` into the capture
+    ///
+    /// Every secret written to the key log while capturing is copied, including the secrets
+    /// of connections from other endpoints which log to the same file.
+    #[must_use]
+    pub fn with_key_log<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        if let Some(state) = self.state.as_mut() {
+            state.key_log = Some(KeyLog::new(path));
+        }
+        self
+    }
+
+    /// Wraps `endpoint` without capturing any datagrams
+    pub fn disabled(endpoint: E) -> Self {
+        Self {
+            endpoint,
+            state: None,
+        }
+    }
+
+    /// Runs `f` with the capture state, disabling the capture if it fails
+    #[inline]
+    fn on_state<F: FnOnce(&mut State) -> io::Result<()>>(&mut self, f: F) {
+        if let Some(state) = self.state.as_mut() {
+            if f(state).is_err() {
+                self.state = None;
+            }
+        }
+    }
+}
+
+impl State {
+    fn timestamp<C: Clock>(&mut self, clock: &C) -> Duration {
+        let now = clock.get_time();
+        let (start, wall) = *self.epoch.get_or_insert_with(|| {
+            let wall = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            (now, wall)
+        });
+        wall + now.saturating_duration_since(start)
+    }
+
+    /// Flushes the capture if it hasn't been flushed for `FLUSH_INTERVAL`
+    fn flush_if_due(&mut self, now: Timestamp) -> io::Result<()> {
+        let last_flush = *self.last_flush.get_or_insert(now);
+        if now.saturating_duration_since(last_flush) < FLUSH_INTERVAL {
+            return Ok(());
+        }
+
+        self.last_flush = Some(now);
+        // copy any secrets which were logged after the last transmission
+        self.poll_key_log()?;
+        self.writer.flush()
+    }
+
+    /// Copies any new secrets from the key log into the capture
+    fn poll_key_log(&mut self) -> io::Result<()> {
+        if let Some(key_log) = self.key_log.as_mut() {
+            let secrets = key_log.poll();
+            self.writer.write_key_log(secrets)?;
+            key_log.consume();
+        }
+        Ok(())
+    }
+
+    fn write<H: path::Handle>(
+        &mut self,
+        timestamp: Duration,
+        direction: Direction,
+        path: &H,
+        ecn: ExplicitCongestionNotification,
+        payload: &[u8],
+    ) -> io::Result<()> {
+        let mut local_address = path.local_address().0;
+        if local_address.is_unspecified() {
+            local_address = self.local_address;
+        }
+        let remote_address = path.remote_address().0;
+
+        self.writer.write_datagram(
+            timestamp,
+            direction,
+            &local_address,
+            &remote_address,
+            ecn,
+            payload,
+        )
+    }
+}
+
+impl<E: Endpoint> Endpoint for Capture<E> {
+    type PathHandle = E::PathHandle;
+    type Subscriber = E::Subscriber;
+
+    const ENDPOINT_TYPE: endpoint::Type = E::ENDPOINT_TYPE;
+
+    fn receive<Rx, C>(&mut self, queue: &mut Rx, clock: &C)
+    where
+        Rx: rx::Queue<Handle = Self::PathHandle>,
+        C: Clock,
+    {
+        self.on_state(|state| {
+            let timestamp = state.timestamp(clock);
+            let local_address = queue.local_address();
+            state.local_address = local_address.0;
+
+            let len = queue.len();
+            let mut result = Ok(());
+            for entry in &mut queue.as_slice_mut()[..len] {
+                entry.read_segments(&local_address, |header, payload| {
+                    if result.is_ok() {
+                        result = state.write(
+                            timestamp,
+                            Direction::Inbound,
+                            &header.path,
+                            header.ecn,
+                            payload,
+                        );
+                    }
+                });
+            }
+            result?;
+
+            state.flush_if_due(clock.get_time())
+        });
+
+        self.endpoint.receive(queue, clock);
+    }
+
+    fn transmit<Tx, C>(&mut self, queue: &mut Tx, clock: &C)
+    where
+        Tx: tx::Queue<Handle = Self::PathHandle>,
+        C: Clock,
+    {
+        let state = match self.state.as_mut() {
+            Some(state) => state,
+            None => return self.endpoint.transmit(queue, clock),
+        };
+
+        let mut queue = TxQueue {
+            queue,
+            state: &mut *state,
+            timestamp: None,
+            clock,
+            result: Ok(()),
+        };
+
+        self.endpoint.transmit(&mut queue, clock);
+
+        let result = queue
+            .result
+            .and_then(|_| state.flush_if_due(clock.get_time()));
+
+        if result.is_err() {
+            self.state = None;
+        }
+    }
+
+    #[inline]
+    fn poll_wakeups<C: Clock>(
+        &mut self,
+        cx: &mut Context<'_>,
+        clock: &C,
+    ) -> Poll<Result<usize, CloseError>> {
+        self.endpoint.poll_wakeups(cx, clock)
+    }
+
+    #[inline]
+    fn timeout(&self) -> Option<Timestamp> {
+        self.endpoint.timeout()
+    }
+
+    #[inline]
+    fn set_max_mtu(&mut self, max_mtu: MaxMtu) {
+        self.endpoint.set_max_mtu(max_mtu)
+    }
+
+    #[inline]
+    fn subscriber(&mut self) -> &mut Self::Subscriber {
+        self.endpoint.subscriber()
+    }
+}
+
+impl<E> Drop for Capture<E> {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.as_mut() {
+            let _ = state.poll_key_log();
+            let _ = state.writer.flush();
+        }
+    }
+}
+
+/// A transmit queue which records each message that is pushed into it
+struct TxQueue<'a, Q, C> {
+    queue: &'a mut Q,
+    state: &'a mut State,
+    timestamp: Option<Duration>,
+    clock: &'a C,
+    result: io::Result<()>,
+}
+
+impl<'a, Q: tx::Queue, C: Clock> TxQueue<'a, Q, C> {
+    fn record<M: tx::Message<Handle = Q::Handle>>(
+        &mut self,
+        message: &Message<M>,
+    ) -> io::Result<()> {
+        let timestamp = match self.timestamp {
+            Some(timestamp) => timestamp,
+            None => {
+                let timestamp = self.state.timestamp(self.clock);
+                self.timestamp = Some(timestamp);
+
+                // The secrets are copied once per batch, before the first packet which may need
+                // them. Secrets logged later in the batch are copied before the next batch or
+                // on the next flush.
+                self.state.poll_key_log()?;
+                timestamp
+            }
+        };
+
+        self.state.write(
+            timestamp,
+            Direction::Outbound,
+            message.message.path_handle(),
+            message.ecn.unwrap_or_default(),
+            &message.payload,
+        )
+    }
+}
+
+impl<'a, Q: tx::Queue, C: Clock> tx::Queue for TxQueue<'a, Q, C> {
+    type Entry = Q::Entry;
+    type Handle = Q::Handle;
+
+    const SUPPORTS_ECN: bool = Q::SUPPORTS_ECN;
+    const SUPPORTS_PACING: bool = Q::SUPPORTS_PACING;
+    const SUPPORTS_FLOW_LABELS: bool = Q::SUPPORTS_FLOW_LABELS;
+
+    fn push<M: tx::Message<Handle = Self::Handle>>(
+        &mut self,
+        message: M,
+    ) -> Result<tx::Outcome, tx::Error> {
+        let mut message = Message {
+            message,
+            ecn: None,
+            payload: vec![],
+        };
+
+        let outcome = self.queue.push(&mut message)?;
+
+        if self.result.is_ok() {
+            self.result = self.record(&message);
+        }
+
+        Ok(outcome)
+    }
+
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        self.queue.as_slice_mut()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[inline]
+    fn max_delay(&self) -> Duration {
+        self.queue.max_delay()
+    }
+}
+
+/// A message which keeps a copy of the payload written by the inner message
+struct Message<M> {
+    message: M,
+    ecn: Option<ExplicitCongestionNotification>,
+    payload: Vec<u8>,
+}
+
+impl<M: tx::Message> tx::Message for &mut Message<M> {
+    type Handle = M::Handle;
+
+    #[inline]
+    fn path_handle(&self) -> &Self::Handle {
+        self.message.path_handle()
+    }
+
+    #[inline]
+    fn ecn(&mut self) -> ExplicitCongestionNotification {
+        let ecn = self.message.ecn();
+        self.ecn = Some(ecn);
+        ecn
+    }
+
+    #[inline]
+    fn dscp(&mut self) -> Dscp {
+        self.message.dscp()
+    }
+
+    #[inline]
+    fn delay(&mut self) -> Duration {
+        self.message.delay()
+    }
+
+    #[inline]
+    fn ipv6_flow_label(&mut self) -> u32 {
+        self.message.ipv6_flow_label()
+    }
+
+    #[inline]
+    fn can_gso(&self, segment_len: usize, segment_count: usize) -> bool {
+        self.message.can_gso(segment_len, segment_count)
+    }
+
+    #[inline]
+    fn write_payload(
+        &mut self,
+        buffer: tx::PayloadBuffer,
+        gso_offset: usize,
+    ) -> Result<usize, tx::Error> {
+        // Safety: the buffer is only passed to the inner message, which performs its own checks
+        let buffer = unsafe { buffer.into_mut_slice() };
+        let len = self
+            .message
+            .write_payload(tx::PayloadBuffer::new(&mut *buffer), gso_offset)?;
+        self.payload.clear();
+        self.payload.extend_from_slice(&buffer[..len]);
+        Ok(len)
+    }
+}
+
+/// Follows the TLS key log file as it is written
+struct KeyLog {
+    path: PathBuf,
+    file: Option<File>,
+    /// Bytes which have been read but don't yet make up a complete line
+    pending: Vec<u8>,
+    /// The length of `pending` which ends with a complete line
+    complete: usize,
+    /// Secrets which were logged before capturing started are skipped
+    skip_existing: bool,
+}
+
+impl KeyLog {
+    fn new<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let mut key_log = Self {
+            path,
+            file: None,
+            pending: vec![],
+            complete: 0,
+            skip_existing: true,
+        };
+        key_log.open();
+        // the file is created by the TLS provider if it doesn't exist yet, in which case all
+        // of its secrets are new
+        key_log.skip_existing = false;
+        key_log
+    }
+
+    fn open(&mut self) {
+        if self.file.is_some() {
+            return;
+        }
+
+        if let Ok(mut file) = File::open(&self.path) {
+            if self.skip_existing {
+                let _ = file.seek(SeekFrom::End(0));
+            }
+            self.file = Some(file);
+        }
+    }
+
+    /// Returns any complete lines which have been written since the last call
+    fn poll(&mut self) -> &[u8] {
+        self.open();
+
+        if let Some(file) = self.file.as_mut() {
+            // errors are ignored since the secrets are only used to help debugging
+            let _ = file.read_to_end(&mut self.pending);
+        }
+
+        self.complete = self
+            .pending
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |index| index + 1);
+
+        &self.pending[..self.complete]
+    }
+
+    /// Removes the lines which were returned by `poll`
+    fn consume(&mut self) {
+        self.pending.drain(..self.complete);
+        self.complete = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::pcap::{blocks, DECRYPTION_SECRETS_BLOCK, ENHANCED_PACKET_BLOCK};
+    use s2n_quic_core::{inet::SocketAddressV4, time::NoopClock};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    /// An output which counts the number of times it's flushed
+    #[derive(Clone, Default)]
+    struct Flushes(Arc<AtomicUsize>);
+
+    impl Write for Flushes {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn flush_interval_test() {
+        let time = |millis| unsafe {
+            // start after the epoch since timestamps can't be zero
+            Timestamp::from_duration(Duration::from_secs(1) + Duration::from_millis(millis))
+        };
+
+        let flushes = Flushes::default();
+        let count = || flushes.0.load(Ordering::Relaxed);
+        let mut capture = Capture {
+            endpoint: (),
+            state: Some(State {
+                writer: Writer::new(Box::new(flushes.clone()) as Output).unwrap(),
+                key_log: None,
+                epoch: None,
+                local_address: Default::default(),
+                last_flush: None,
+            }),
+        };
+
+        let state = capture.state.as_mut().unwrap();
+        state.flush_if_due(time(0)).unwrap();
+        state.flush_if_due(time(500)).unwrap();
+        assert_eq!(count(), 0);
+
+        state.flush_if_due(time(1000)).unwrap();
+        assert_eq!(count(), 1);
+
+        state.flush_if_due(time(1500)).unwrap();
+        assert_eq!(count(), 1);
+
+        // anything which is still buffered is flushed when the capture is dropped
+        drop(capture);
+        assert_eq!(count(), 2);
+    }
+
+    #[test]
+    fn key_log_test() {
+        let path =
+            std::env::temp_dir().join(format!("s2n-quic-pcap-key-log-{}", std::process::id()));
+        std::fs::write(&path, b"EXISTING 00 00\n").unwrap();
+
+        let mut key_log = KeyLog::new(&path);
+        assert!(key_log.poll().is_empty());
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+
+        // partial lines are held back until they are complete
+        file.write_all(b"CLIENT_RANDOM 01").unwrap();
+        assert!(key_log.poll().is_empty());
+        key_log.consume();
+
+        file.write_all(b" 02\nSERVER").unwrap();
+        assert_eq!(key_log.poll(), b"CLIENT_RANDOM 01 02\n");
+        key_log.consume();
+        assert!(key_log.poll().is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// An output which can be read while the capture is writing to it
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A transmit queue which accepts every message
+    #[derive(Default)]
+    struct Queue(Vec<Entry>);
+
+    struct Entry(Vec<u8>);
+
+    impl tx::Entry for Entry {
+        type Handle = path::Tuple;
+
+        fn set<M: tx::Message<Handle = Self::Handle>>(
+            &mut self,
+            mut message: M,
+        ) -> Result<usize, tx::Error> {
+            let len = message.write_payload(tx::PayloadBuffer::new(&mut self.0[..]), 0)?;
+            self.0.truncate(len);
+            Ok(len)
+        }
+
+        fn payload(&self) -> &[u8] {
+            &self.0
+        }
+
+        fn payload_mut(&mut self) -> &mut [u8] {
+            &mut self.0
+        }
+    }
+
+    impl tx::Queue for Queue {
+        type Entry = Entry;
+        type Handle = path::Tuple;
+
+        fn push<M: tx::Message<Handle = Self::Handle>>(
+            &mut self,
+            message: M,
+        ) -> Result<tx::Outcome, tx::Error> {
+            let mut entry = Entry(vec![0; 1200]);
+            let len = tx::Entry::set(&mut entry, message)?;
+            self.0.push(entry);
+            Ok(tx::Outcome {
+                len,
+                index: self.0.len() - 1,
+            })
+        }
+
+        fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+            &mut self.0
+        }
+
+        fn capacity(&self) -> usize {
+            usize::MAX
+        }
+
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[test]
+    fn key_log_batch_test() {
+        let key_log = std::env::temp_dir().join(format!(
+            "s2n-quic-pcap-key-log-batch-{}",
+            std::process::id()
+        ));
+        std::fs::write(&key_log, b"").unwrap();
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&key_log)
+            .unwrap();
+
+        let output = Shared::default();
+        let mut state = State {
+            writer: Writer::new(Box::new(output.clone()) as Output).unwrap(),
+            key_log: Some(KeyLog::new(&key_log)),
+            epoch: None,
+            local_address: Default::default(),
+            last_flush: None,
+        };
+        let mut queue = Queue::default();
+        let handle = path::Tuple {
+            local_address: SocketAddressV4::new([127, 0, 0, 1], 4433).into(),
+            remote_address: SocketAddressV4::new([127, 0, 0, 2], 1234).into(),
+        };
+
+        file.write_all(b"CLIENT_RANDOM 01 01\n").unwrap();
+        {
+            let mut batch = TxQueue {
+                queue: &mut queue,
+                state: &mut state,
+                timestamp: None,
+                clock: &NoopClock,
+                result: Ok(()),
+            };
+            tx::Queue::push(&mut batch, (handle, [1u8])).unwrap();
+            // secrets which are logged during a batch are copied before the next one
+            file.write_all(b"CLIENT_RANDOM 02 02\n").unwrap();
+            tx::Queue::push(&mut batch, (handle, [2u8])).unwrap();
+            assert!(batch.result.is_ok());
+        }
+        {
+            let mut batch = TxQueue {
+                queue: &mut queue,
+                state: &mut state,
+                timestamp: None,
+                clock: &NoopClock,
+                result: Ok(()),
+            };
+            tx::Queue::push(&mut batch, (handle, [3u8])).unwrap();
+            assert!(batch.result.is_ok());
+        }
+        assert_eq!(queue.0.len(), 3);
+
+        let capture = output.0.lock().unwrap();
+        let blocks = blocks(&capture)
+            .iter()
+            // skip the section and interface headers
+            .skip(2)
+            .map(|(ty, _)| *ty)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            blocks,
+            [
+                DECRYPTION_SECRETS_BLOCK,
+                ENHANCED_PACKET_BLOCK,
+                ENHANCED_PACKET_BLOCK,
+                DECRYPTION_SECRETS_BLOCK,
+                ENHANCED_PACKET_BLOCK,
+            ]
+        );
+
+        std::fs::remove_file(&key_log).unwrap();
+    }
+}
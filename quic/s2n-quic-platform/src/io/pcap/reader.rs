@@ -0,0 +1,638 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use core::time::Duration;
+use s2n_codec::DecoderBufferMut;
+use s2n_quic_core::{
+    inet::{ExplicitCongestionNotification, SocketAddress},
+    xdp::decoder,
+};
+use std::io::{self, Read};
+
+const PCAP_MAGIC_MICROS: u32 = 0xA1B2_C3D4;
+const PCAP_MAGIC_NANOS: u32 = 0xA1B2_3C4D;
+
+const LINKTYPE_RAW: u16 = 101;
+const LINKTYPE_LINUX_SLL: u16 = 113;
+
+const ETHERTYPE_IPV4: [u8; 2] = [0x08, 0x00];
+const ETHERTYPE_IPV6: [u8; 2] = [0x86, 0xDD];
+
+/// A UDP datagram read from a capture
+#[derive(Clone, Debug)]
+pub struct Datagram {
+    /// The time the datagram was captured, relative to the UNIX epoch
+    pub timestamp: Duration,
+    /// The direction of the datagram, if it was recorded in the capture
+    pub direction: Option<Direction>,
+    pub source: SocketAddress,
+    pub destination: SocketAddress,
+    pub ecn: ExplicitCongestionNotification,
+    pub payload: Vec<u8>,
+}
+
+/// Reads UDP datagrams from a pcapng or legacy pcap file
+///
+/// Frames which don't contain a valid UDP datagram are skipped. This includes datagrams with
+/// an invalid checksum, which is common for outbound packets captured on hosts which offload
+/// checksums to the NIC.
+#[derive(Debug)]
+pub struct Reader<R: Read> {
+    input: R,
+    format: Format,
+}
+
+#[derive(Debug)]
+enum Format {
+    Pcap {
+        swapped: bool,
+        ticks_per_second: u64,
+        linktype: u16,
+    },
+    Pcapng {
+        swapped: bool,
+        interfaces: Vec<Interface>,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Interface {
+    linktype: u16,
+    ticks_per_second: u64,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(mut input: R) -> io::Result<Self> {
+        let magic = read_u32(&mut input, false)?;
+
+        let format = match magic {
+            SECTION_HEADER_BLOCK => {
+                let swapped = read_section_header(&mut input)?;
+                Format::Pcapng {
+                    swapped,
+                    interfaces: vec![],
+                }
+            }
+            _ => {
+                let (swapped, ticks_per_second) = match magic {
+                    PCAP_MAGIC_MICROS => (false, 1_000_000),
+                    PCAP_MAGIC_NANOS => (false, 1_000_000_000),
+                    magic if magic.swap_bytes() == PCAP_MAGIC_MICROS => (true, 1_000_000),
+                    magic if magic.swap_bytes() == PCAP_MAGIC_NANOS => (true, 1_000_000_000),
+                    _ => return Err(invalid_data("not a pcap or pcapng file")),
+                };
+
+                // version, reserved fields and the snapshot length
+                skip(&mut input, 16)?;
+                let linktype = read_u32(&mut input, swapped)? as u16;
+
+                Format::Pcap {
+                    swapped,
+                    ticks_per_second,
+                    linktype,
+                }
+            }
+        };
+
+        Ok(Self { input, format })
+    }
+
+    fn read_next(&mut self) -> io::Result<Option<Datagram>> {
+        loop {
+            let frame = match &mut self.format {
+                Format::Pcap {
+                    swapped,
+                    ticks_per_second,
+                    linktype,
+                } => {
+                    let swapped = *swapped;
+                    let seconds = match read_u32_or_eof(&mut self.input, swapped)? {
+                        Some(seconds) => seconds as u64,
+                        None => return Ok(None),
+                    };
+                    let fraction = read_u32(&mut self.input, swapped)? as u64;
+                    let captured_len = read_u32(&mut self.input, swapped)? as usize;
+                    let _original_len = read_u32(&mut self.input, swapped)?;
+
+                    let mut data = vec![0; captured_len];
+                    self.input.read_exact(&mut data)?;
+
+                    let timestamp =
+                        Duration::from_secs(seconds) + to_duration(fraction, *ticks_per_second);
+
+                    Frame {
+                        timestamp,
+                        direction: None,
+                        linktype: *linktype,
+                        data,
+                    }
+                }
+                Format::Pcapng {
+                    swapped,
+                    interfaces,
+                } => {
+                    let ty = match read_u32_or_eof(&mut self.input, *swapped)? {
+                        Some(ty) => ty,
+                        None => return Ok(None),
+                    };
+
+                    if ty == SECTION_HEADER_BLOCK {
+                        // a new section can change the byte order and resets the interfaces
+                        *swapped = read_section_header(&mut self.input)?;
+                        interfaces.clear();
+                        continue;
+                    }
+
+                    let swapped = *swapped;
+                    let len = read_u32(&mut self.input, swapped)? as usize;
+                    let body_len = len
+                        .checked_sub(12)
+                        .ok_or_else(|| invalid_data("invalid block length"))?;
+                    let mut body = vec![0; body_len];
+                    self.input.read_exact(&mut body)?;
+                    let _trailing_len = read_u32(&mut self.input, swapped)?;
+
+                    let mut body = Body {
+                        bytes: &body,
+                        swapped,
+                    };
+
+                    match ty {
+                        INTERFACE_DESCRIPTION_BLOCK => {
+                            let linktype = body.u16()?;
+                            body.skip(6)?;
+
+                            let mut ticks_per_second = 1_000_000;
+                            while let Some((code, value)) = body.option()? {
+                                if code == OPT_IF_TSRESOL {
+                                    let resolution = *value.first().unwrap_or(&6);
+                                    ticks_per_second = if resolution & 0x80 == 0 {
+                                        10u64.pow((resolution as u32).min(19))
+                                    } else {
+                                        1u64 << (resolution & 0x7f).min(63)
+                                    };
+                                }
+                            }
+
+                            interfaces.push(Interface {
+                                linktype,
+                                ticks_per_second,
+                            });
+                            continue;
+                        }
+                        ENHANCED_PACKET_BLOCK => {
+                            let interface = body.u32()? as usize;
+                            let high = body.u32()? as u64;
+                            let low = body.u32()? as u64;
+                            let captured_len = body.u32()? as usize;
+                            let _original_len = body.u32()?;
+                            let data = body.bytes(captured_len)?.to_vec();
+                            body.skip((4 - captured_len % 4) % 4)?;
+
+                            let mut direction = None;
+                            while let Some((code, value)) = body.option()? {
+                                if code == OPT_EPB_FLAGS && value.len() == 4 {
+                                    let flags = Body {
+                                        bytes: value,
+                                        swapped,
+                                    }
+                                    .u32()?;
+                                    direction = match flags & 0b11 {
+                                        0b01 => Some(Direction::Inbound),
+                                        0b10 => Some(Direction::Outbound),
+                                        _ => None,
+                                    };
+                                }
+                            }
+
+                            let interface = *interfaces
+                                .get(interface)
+                                .ok_or_else(|| invalid_data("unknown interface"))?;
+
+                            Frame {
+                                timestamp: to_duration(
+                                    high << 32 | low,
+                                    interface.ticks_per_second,
+                                ),
+                                direction,
+                                linktype: interface.linktype,
+                                data,
+                            }
+                        }
+                        SIMPLE_PACKET_BLOCK => {
+                            let original_len = body.u32()? as usize;
+                            let data = body.bytes(original_len.min(body.bytes.len()))?;
+                            let interface = *interfaces
+                                .first()
+                                .ok_or_else(|| invalid_data("unknown interface"))?;
+
+                            Frame {
+                                timestamp: Duration::ZERO,
+                                direction: None,
+                                linktype: interface.linktype,
+                                data: data.to_vec(),
+                            }
+                        }
+                        // skip any other blocks, including decryption secrets
+                        _ => continue,
+                    }
+                }
+            };
+
+            if let Some(datagram) = frame.decode() {
+                return Ok(Some(datagram));
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = io::Result<Datagram>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_next().transpose()
+    }
+}
+
+struct Frame {
+    timestamp: Duration,
+    direction: Option<Direction>,
+    linktype: u16,
+    data: Vec<u8>,
+}
+
+impl Frame {
+    fn decode(self) -> Option<Datagram> {
+        let mut data = match self.linktype {
+            LINKTYPE_ETHERNET => self.data,
+            LINKTYPE_RAW => {
+                let ethertype = match self.data.first()? >> 4 {
+                    4 => ETHERTYPE_IPV4,
+                    6 => ETHERTYPE_IPV6,
+                    _ => return None,
+                };
+                with_ethernet_header(ethertype, &self.data)
+            }
+            LINKTYPE_LINUX_SLL => {
+                let ethertype = [*self.data.get(14)?, *self.data.get(15)?];
+                with_ethernet_header(ethertype, self.data.get(16..)?)
+            }
+            _ => return None,
+        };
+
+        let (header, payload) = decoder::decode_packet(DecoderBufferMut::new(&mut data))
+            .ok()
+            .flatten()?;
+
+        Some(Datagram {
+            timestamp: self.timestamp,
+            direction: self.direction,
+            source: header.path.remote_address.socket,
+            destination: header.path.local_address.socket,
+            ecn: header.ecn,
+            payload: payload.into_less_safe_slice().to_vec(),
+        })
+    }
+}
+
+/// Replaces the link-layer header of a frame with an Ethernet header
+fn with_ethernet_header(ethertype: [u8; 2], packet: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(14 + packet.len());
+    // the MAC addresses are unknown
+    frame.resize(12, 0);
+    frame.extend_from_slice(&ethertype);
+    frame.extend_from_slice(packet);
+    frame
+}
+
+struct Body<'a> {
+    bytes: &'a [u8],
+    swapped: bool,
+}
+
+impl<'a> Body<'a> {
+    fn bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(invalid_data("truncated block"));
+        }
+        let (bytes, remaining) = self.bytes.split_at(len);
+        self.bytes = remaining;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> io::Result<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        let value = u16::from_ne_bytes(self.bytes(2)?.try_into().unwrap());
+        Ok(if self.swapped {
+            value.swap_bytes()
+        } else {
+            value
+        })
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        let value = u32::from_ne_bytes(self.bytes(4)?.try_into().unwrap());
+        Ok(if self.swapped {
+            value.swap_bytes()
+        } else {
+            value
+        })
+    }
+
+    /// Reads the next option, returning `None` at the end of the options
+    fn option(&mut self) -> io::Result<Option<(u16, &'a [u8])>> {
+        if self.bytes.len() < 4 {
+            return Ok(None);
+        }
+
+        let code = self.u16()?;
+        let len = self.u16()? as usize;
+
+        if code == OPT_END_OF_OPTIONS {
+            return Ok(None);
+        }
+
+        let value = self.bytes(len)?;
+        self.skip((4 - len % 4) % 4)?;
+        Ok(Some((code, value)))
+    }
+}
+
+/// Reads the remainder of a section header block after its type, returning whether the
+/// section's byte order is swapped
+fn read_section_header<R: Read>(input: &mut R) -> io::Result<bool> {
+    let len = read_u32(input, false)?;
+    let swapped = match read_u32(input, false)? {
+        BYTE_ORDER_MAGIC => false,
+        magic if magic.swap_bytes() == BYTE_ORDER_MAGIC => true,
+        _ => return Err(invalid_data("invalid byte order magic")),
+    };
+
+    let len = if swapped { len.swap_bytes() } else { len } as usize;
+
+    // skip the version, section length and options along with the trailing block length
+    let remaining = len
+        .checked_sub(12)
+        .ok_or_else(|| invalid_data("invalid block length"))?;
+    skip(input, remaining)?;
+
+    Ok(swapped)
+}
+
+fn read_u32<R: Read>(input: &mut R, swapped: bool) -> io::Result<u32> {
+    read_u32_or_eof(input, swapped)?.ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+}
+
+/// Reads a `u32`, returning `None` if the input ended before the first byte
+fn read_u32_or_eof<R: Read>(input: &mut R, swapped: bool) -> io::Result<Option<u32>> {
+    let mut bytes = [0; 4];
+    let mut len = 0;
+
+    while len < bytes.len() {
+        match input.read(&mut bytes[len..]) {
+            Ok(0) if len == 0 => return Ok(None),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => len += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    let value = u32::from_ne_bytes(bytes);
+    Ok(Some(if swapped { value.swap_bytes() } else { value }))
+}
+
+fn skip<R: Read>(input: &mut R, len: usize) -> io::Result<()> {
+    let copied = io::copy(&mut input.take(len as u64), &mut io::sink())?;
+    if copied as usize != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+#[inline]
+fn to_duration(ticks: u64, ticks_per_second: u64) -> Duration {
+    let nanos = ticks as u128 * 1_000_000_000 / ticks_per_second as u128;
+    Duration::from_nanos(nanos as u64)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(address: &str) -> SocketAddress {
+        address.parse::<std::net::SocketAddr>().unwrap().into()
+    }
+
+    /// Returns a pcapng capture with a datagram carrying `payload`
+    fn capture(payload: &[u8]) -> Vec<u8> {
+        let mut writer = Writer::new(vec![]).unwrap();
+        writer
+            .write_datagram(
+                Duration::from_secs(1),
+                Direction::Inbound,
+                &address("127.0.0.1:4433"),
+                &address("127.0.0.2:1234"),
+                ExplicitCongestionNotification::Ect1,
+                payload,
+            )
+            .unwrap();
+        writer.into_inner()
+    }
+
+    /// Returns the body of the Enhanced Packet Block for a datagram carrying `payload`
+    fn packet(payload: &[u8]) -> Vec<u8> {
+        let capture = capture(payload);
+        let (_, packet) = *blocks(&capture).last().unwrap();
+        packet.to_vec()
+    }
+
+    /// Returns the Ethernet frame of a datagram carrying `payload`
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        let packet = packet(payload);
+        let len = u32::from_ne_bytes(packet[12..16].try_into().unwrap()) as usize;
+        packet[20..20 + len].to_vec()
+    }
+
+    fn block(ty: u32, body: &[u8]) -> Vec<u8> {
+        let len = (body.len() + 12) as u32;
+        let mut block = vec![];
+        block.extend_from_slice(&ty.to_ne_bytes());
+        block.extend_from_slice(&len.to_ne_bytes());
+        block.extend_from_slice(body);
+        block.extend_from_slice(&len.to_ne_bytes());
+        block
+    }
+
+    /// Returns a pcapng header followed by `blocks`
+    fn pcapng(blocks: &[Vec<u8>]) -> Vec<u8> {
+        let mut file = Writer::new(vec![]).unwrap().into_inner();
+        for block in blocks {
+            file.extend_from_slice(block);
+        }
+        file
+    }
+
+    /// Returns a legacy pcap file with a record for each of the `(seconds, fraction, frame)`
+    fn pcap(magic: u32, swapped: bool, linktype: u16, records: &[(u32, u32, &[u8])]) -> Vec<u8> {
+        let bytes16 = |value: u16| (if swapped { value.swap_bytes() } else { value }).to_ne_bytes();
+        let bytes32 = |value: u32| (if swapped { value.swap_bytes() } else { value }).to_ne_bytes();
+
+        let mut file = vec![];
+        file.extend_from_slice(&bytes32(magic));
+        // version 2.4
+        file.extend_from_slice(&bytes16(2));
+        file.extend_from_slice(&bytes16(4));
+        // the time zone and timestamp accuracy are unused
+        file.extend_from_slice(&bytes32(0));
+        file.extend_from_slice(&bytes32(0));
+        file.extend_from_slice(&bytes32(u16::MAX as u32));
+        file.extend_from_slice(&bytes32(linktype as u32));
+
+        for (seconds, fraction, frame) in records {
+            file.extend_from_slice(&bytes32(*seconds));
+            file.extend_from_slice(&bytes32(*fraction));
+            file.extend_from_slice(&bytes32(frame.len() as u32));
+            file.extend_from_slice(&bytes32(frame.len() as u32));
+            file.extend_from_slice(frame);
+        }
+
+        file
+    }
+
+    fn read(file: &[u8]) -> io::Result<Vec<Datagram>> {
+        Reader::new(file)?.collect()
+    }
+
+    #[test]
+    fn pcap_micros_test() {
+        let frame = frame(&[1, 2, 3]);
+        let file = pcap(
+            PCAP_MAGIC_MICROS,
+            false,
+            LINKTYPE_ETHERNET,
+            &[(2, 500_000, &frame[..])],
+        );
+
+        let datagrams = read(&file).unwrap();
+        assert_eq!(datagrams.len(), 1);
+
+        let datagram = &datagrams[0];
+        assert_eq!(datagram.timestamp, Duration::from_millis(2500));
+        // legacy captures don't record the direction
+        assert_eq!(datagram.direction, None);
+        assert_eq!(datagram.source.unmap(), address("127.0.0.2:1234"));
+        assert_eq!(datagram.destination.unmap(), address("127.0.0.1:4433"));
+        assert_eq!(datagram.ecn, ExplicitCongestionNotification::Ect1);
+        assert_eq!(datagram.payload, [1, 2, 3]);
+    }
+
+    #[test]
+    fn pcap_nanos_swapped_test() {
+        let frame = frame(&[1, 2, 3]);
+        // raw frames start at the IP header
+        let packet = &frame[14..];
+        let file = pcap(
+            PCAP_MAGIC_NANOS,
+            true,
+            LINKTYPE_RAW,
+            &[(2, 500, packet), (3, 0, &[0xff; 8][..]), (4, 0, packet)],
+        );
+
+        // frames which don't contain a UDP datagram are skipped
+        let datagrams = read(&file).unwrap();
+        assert_eq!(datagrams.len(), 2);
+        assert_eq!(
+            datagrams[0].timestamp,
+            Duration::from_secs(2) + Duration::from_nanos(500)
+        );
+        assert_eq!(datagrams[1].timestamp, Duration::from_secs(4));
+        assert_eq!(datagrams[1].payload, [1, 2, 3]);
+    }
+
+    #[test]
+    fn pcap_truncated_record_test() {
+        let frame = frame(&[1, 2, 3]);
+        let file = pcap(
+            PCAP_MAGIC_MICROS,
+            false,
+            LINKTYPE_ETHERNET,
+            &[(2, 0, &frame[..])],
+        );
+
+        let err = read(&file[..file.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn unknown_block_test() {
+        let packet = block(ENHANCED_PACKET_BLOCK, &packet(&[1, 2, 3]));
+        // blocks which don't contain packets are skipped
+        let unknown = block(0x0BAD, &[0; 4]);
+        let file = pcapng(&[packet.clone(), unknown, packet]);
+
+        let datagrams = read(&file).unwrap();
+        assert_eq!(datagrams.len(), 2);
+    }
+
+    #[test]
+    fn invalid_magic_test() {
+        let err = Reader::new(&[0u8; 24][..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "not a pcap or pcapng file");
+
+        let err = Reader::new(&[][..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut section = vec![];
+        section.extend_from_slice(&0x1234_5678u32.to_ne_bytes());
+        section.extend_from_slice(&[0; 12]);
+        let err = Reader::new(&block(SECTION_HEADER_BLOCK, &section)[..]).unwrap_err();
+        assert_eq!(err.to_string(), "invalid byte order magic");
+    }
+
+    #[test]
+    fn invalid_block_length_test() {
+        let mut file = pcapng(&[]);
+        file.extend_from_slice(&ENHANCED_PACKET_BLOCK.to_ne_bytes());
+        file.extend_from_slice(&8u32.to_ne_bytes());
+
+        let err = read(&file).unwrap_err();
+        assert_eq!(err.to_string(), "invalid block length");
+    }
+
+    #[test]
+    fn truncated_block_test() {
+        // the input ends in the middle of a block
+        let file = capture(&[1, 2, 3]);
+        let err = read(&file[..file.len() - 4]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        // the captured length is longer than the block
+        let mut packet = packet(&[1, 2, 3]);
+        packet[12..16].copy_from_slice(&u32::MAX.to_ne_bytes());
+        let file = pcapng(&[block(ENHANCED_PACKET_BLOCK, &packet)]);
+
+        let err = read(&file).unwrap_err();
+        assert_eq!(err.to_string(), "truncated block");
+    }
+
+    #[test]
+    fn unknown_interface_test() {
+        // only the first interface is described
+        let mut packet = packet(&[1, 2, 3]);
+        packet[..4].copy_from_slice(&1u32.to_ne_bytes());
+        let file = pcapng(&[block(ENHANCED_PACKET_BLOCK, &packet)]);
+
+        let err = read(&file).unwrap_err();
+        assert_eq!(err.to_string(), "unknown interface");
+    }
+}
@@ -0,0 +1,327 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use core::time::Duration;
+use s2n_quic_core::{
+    inet::{Dscp, ExplicitCongestionNotification, SocketAddress},
+    io::tx,
+    xdp::{
+        encoder,
+        path::{Address, Tuple},
+    },
+};
+use std::io::{self, Write};
+
+/// The largest UDP payload plus room for the synthesized headers
+const MAX_FRAME_LEN: usize = u16::MAX as usize + 128;
+
+/// Writes datagrams to a pcapng file
+///
+/// The file contains a single Ethernet interface with microsecond timestamps.
+#[derive(Debug)]
+pub struct Writer<W: Write> {
+    out: W,
+    frame: Vec<u8>,
+}
+
+impl<W: Write> Writer<W> {
+    /// Creates a writer and writes the section and interface headers to `out`
+    pub fn new(out: W) -> io::Result<Self> {
+        let mut writer = Self {
+            out,
+            frame: vec![0; MAX_FRAME_LEN],
+        };
+
+        let mut section = vec![];
+        section.extend_from_slice(&BYTE_ORDER_MAGIC.to_ne_bytes());
+        // version 1.0
+        section.extend_from_slice(&1u16.to_ne_bytes());
+        section.extend_from_slice(&0u16.to_ne_bytes());
+        // the section length isn't known ahead of time
+        section.extend_from_slice(&(-1i64).to_ne_bytes());
+        writer.write_block(SECTION_HEADER_BLOCK, &section)?;
+
+        let mut interface = vec![];
+        interface.extend_from_slice(&LINKTYPE_ETHERNET.to_ne_bytes());
+        interface.extend_from_slice(&0u16.to_ne_bytes());
+        // no limit on the snapshot length
+        interface.extend_from_slice(&0u32.to_ne_bytes());
+        // timestamps are in microseconds
+        push_option(&mut interface, OPT_IF_TSRESOL, &[6]);
+        push_option(&mut interface, OPT_END_OF_OPTIONS, &[]);
+        writer.write_block(INTERFACE_DESCRIPTION_BLOCK, &interface)?;
+
+        Ok(writer)
+    }
+
+    /// Writes a UDP datagram sent or received by the endpoint bound to `local_address`
+    ///
+    /// The `timestamp` is the time since the UNIX epoch.
+    pub fn write_datagram(
+        &mut self,
+        timestamp: Duration,
+        direction: Direction,
+        local_address: &SocketAddress,
+        remote_address: &SocketAddress,
+        ecn: ExplicitCongestionNotification,
+        payload: &[u8],
+    ) -> io::Result<()> {
+        let local_address = Address {
+            mac: Default::default(),
+            socket: *local_address,
+        };
+        let remote_address = Address {
+            mac: Default::default(),
+            socket: *remote_address,
+        };
+
+        // the encoder treats the local address as the source of the frame
+        let path = match direction {
+            Direction::Outbound => Tuple {
+                local_address,
+                remote_address,
+            },
+            Direction::Inbound => Tuple {
+                local_address: remote_address,
+                remote_address: local_address,
+            },
+        };
+
+        let mut message = Message { path, ecn, payload };
+        let len = match encoder::encode_packet(&mut self.frame, &mut message) {
+            Ok(len) => len,
+            // empty datagrams can't be encoded
+            Err(tx::Error::EmptyPayload) => return Ok(()),
+            Err(err) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("could not encode datagram: {err:?}"),
+                ))
+            }
+        };
+
+        let flags: u32 = match direction {
+            Direction::Inbound => 0b01,
+            Direction::Outbound => 0b10,
+        };
+
+        let micros = timestamp.as_micros() as u64;
+
+        let mut block = Vec::with_capacity(len + 40);
+        // interface id
+        block.extend_from_slice(&0u32.to_ne_bytes());
+        block.extend_from_slice(&((micros >> 32) as u32).to_ne_bytes());
+        block.extend_from_slice(&(micros as u32).to_ne_bytes());
+        // captured and original lengths
+        block.extend_from_slice(&(len as u32).to_ne_bytes());
+        block.extend_from_slice(&(len as u32).to_ne_bytes());
+        block.extend_from_slice(&self.frame[..len]);
+        pad(&mut block);
+        push_option(&mut block, OPT_EPB_FLAGS, &flags.to_ne_bytes());
+        push_option(&mut block, OPT_END_OF_OPTIONS, &[]);
+
+        self.write_block(ENHANCED_PACKET_BLOCK, &block)
+    }
+
+    /// Writes TLS secrets in the NSS key log format
+    ///
+    /// Tools which decrypt packets in a single pass, like `tshark` without `-2`, only use
+    /// secrets which appear before the packets that need them.
+    pub fn write_key_log(&mut self, key_log: &[u8]) -> io::Result<()> {
+        if key_log.is_empty() {
+            return Ok(());
+        }
+
+        let mut block = Vec::with_capacity(key_log.len() + 12);
+        block.extend_from_slice(&SECRETS_TYPE_TLS_KEY_LOG.to_ne_bytes());
+        block.extend_from_slice(&(key_log.len() as u32).to_ne_bytes());
+        block.extend_from_slice(key_log);
+        pad(&mut block);
+
+        self.write_block(DECRYPTION_SECRETS_BLOCK, &block)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    /// Returns the underlying output
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn write_block(&mut self, ty: u32, body: &[u8]) -> io::Result<()> {
+        debug_assert_eq!(body.len() % 4, 0, "block bodies are padded to 32 bits");
+
+        // the block length includes the type and both length fields
+        let len = (body.len() + 12) as u32;
+        self.out.write_all(&ty.to_ne_bytes())?;
+        self.out.write_all(&len.to_ne_bytes())?;
+        self.out.write_all(body)?;
+        self.out.write_all(&len.to_ne_bytes())?;
+        Ok(())
+    }
+}
+
+#[inline]
+fn push_option(block: &mut Vec<u8>, code: u16, value: &[u8]) {
+    block.extend_from_slice(&code.to_ne_bytes());
+    block.extend_from_slice(&(value.len() as u16).to_ne_bytes());
+    block.extend_from_slice(value);
+    pad(block);
+}
+
+/// Pads the block to a multiple of 32 bits
+#[inline]
+fn pad(block: &mut Vec<u8>) {
+    let len = (block.len() + 3) & !3;
+    block.resize(len, 0);
+}
+
+struct Message<'a> {
+    path: Tuple,
+    ecn: ExplicitCongestionNotification,
+    payload: &'a [u8],
+}
+
+impl<'a> tx::Message for Message<'a> {
+    type Handle = Tuple;
+
+    fn path_handle(&self) -> &Self::Handle {
+        &self.path
+    }
+
+    fn ecn(&mut self) -> ExplicitCongestionNotification {
+        self.ecn
+    }
+
+    fn dscp(&mut self) -> Dscp {
+        Default::default()
+    }
+
+    fn delay(&mut self) -> Duration {
+        Default::default()
+    }
+
+    fn ipv6_flow_label(&mut self) -> u32 {
+        0
+    }
+
+    fn can_gso(&self, _segment_len: usize, _segment_count: usize) -> bool {
+        false
+    }
+
+    fn write_payload(
+        &mut self,
+        mut buffer: tx::PayloadBuffer,
+        _gso_offset: usize,
+    ) -> Result<usize, tx::Error> {
+        buffer.write(self.payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(address: &str) -> SocketAddress {
+        address.parse::<std::net::SocketAddr>().unwrap().into()
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn header_test() {
+        let capture = Writer::new(vec![]).unwrap().into_inner();
+        let blocks = blocks(&capture);
+        assert_eq!(blocks.len(), 2);
+
+        let (ty, section) = blocks[0];
+        assert_eq!(ty, SECTION_HEADER_BLOCK);
+        assert_eq!(u32_at(section, 0), BYTE_ORDER_MAGIC);
+
+        let (ty, interface) = blocks[1];
+        assert_eq!(ty, INTERFACE_DESCRIPTION_BLOCK);
+        assert_eq!(interface[..2], LINKTYPE_ETHERNET.to_ne_bytes());
+        // the timestamp resolution follows the link type, reserved field and snapshot length
+        assert_eq!(interface[8..10], OPT_IF_TSRESOL.to_ne_bytes());
+        assert_eq!(interface[12], 6);
+    }
+
+    #[test]
+    fn datagram_test() {
+        let local = address("127.0.0.1:4433");
+        let remote = address("127.0.0.2:1234");
+
+        let mut writer = Writer::new(vec![]).unwrap();
+        writer
+            .write_datagram(
+                Duration::from_micros(0x1_0000_0002),
+                Direction::Inbound,
+                &local,
+                &remote,
+                ExplicitCongestionNotification::NotEct,
+                &[1, 2, 3],
+            )
+            .unwrap();
+        // empty datagrams can't be encoded so they're skipped
+        writer
+            .write_datagram(
+                Duration::from_secs(1),
+                Direction::Outbound,
+                &local,
+                &remote,
+                ExplicitCongestionNotification::NotEct,
+                &[],
+            )
+            .unwrap();
+
+        let capture = writer.into_inner();
+        let blocks = blocks(&capture);
+        assert_eq!(blocks.len(), 3);
+
+        let (ty, packet) = blocks[2];
+        assert_eq!(ty, ENHANCED_PACKET_BLOCK);
+        // the first interface, with the timestamp split into its high and low bits
+        assert_eq!(u32_at(packet, 0), 0);
+        assert_eq!(u32_at(packet, 4), 1);
+        assert_eq!(u32_at(packet, 8), 2);
+
+        // the payload follows the Ethernet, IPv4 and UDP headers
+        let len = u32_at(packet, 12) as usize;
+        assert_eq!(len, 14 + 20 + 8 + 3);
+        assert_eq!(u32_at(packet, 16) as usize, len);
+        assert_eq!(packet[20 + len - 3..20 + len], [1, 2, 3]);
+
+        // the options start after the frame is padded to 32 bits
+        let options = &packet[(20 + len + 3) & !3..];
+        assert_eq!(options[..2], OPT_EPB_FLAGS.to_ne_bytes());
+        assert_eq!(u32_at(options, 4), 0b01);
+        assert_eq!(options[8..10], OPT_END_OF_OPTIONS.to_ne_bytes());
+    }
+
+    #[test]
+    fn key_log_test() {
+        let key_log = b"CLIENT_RANDOM 1 2\n";
+
+        let mut writer = Writer::new(vec![]).unwrap();
+        // empty key logs are skipped
+        writer.write_key_log(b"").unwrap();
+        writer.write_key_log(key_log).unwrap();
+
+        let capture = writer.into_inner();
+        let blocks = blocks(&capture);
+        assert_eq!(blocks.len(), 3);
+
+        let (ty, secrets) = blocks[2];
+        assert_eq!(ty, DECRYPTION_SECRETS_BLOCK);
+        assert_eq!(u32_at(secrets, 0), SECRETS_TYPE_TLS_KEY_LOG);
+        assert_eq!(u32_at(secrets, 4) as usize, key_log.len());
+        assert_eq!(&secrets[8..8 + key_log.len()], key_log);
+        // the secrets are padded to 32 bits
+        assert_eq!(secrets.len(), 8 + 20);
+    }
+}
@@ -14,13 +14,17 @@ use s2n_quic_core::{
 type Error = std::io::Error;
 type Result<T = (), E = Error> = core::result::Result<T, E>;
 
+mod bottleneck;
 mod model;
 pub mod network;
 pub mod time;
+mod topology;
 
+pub use bottleneck::Aqm;
 pub use model::{Model, TxRecorder};
 pub use network::{Network, PathHandle};
 pub use time::now;
+pub use topology::{Host, Link, Topology};
 
 pub use bach::task::{self, primary, spawn};
 
@@ -224,14 +228,68 @@ impl Handle {
             handle: self.clone(),
             address: None,
             max_mtu: MaxMtu::default(),
+            #[cfg(feature = "pcap")]
+            pcap: None,
+            #[cfg(feature = "pcap")]
+            pcap_key_log: None,
         }
     }
+
+    /// Replays the inbound datagrams from a capture to the endpoint bound to `local_address`
+    ///
+    /// Datagrams are delivered with the same spacing as they were captured, starting
+    /// immediately. Datagrams which the capture marks as outbound are skipped.
+    ///
+    /// Captures can only be replayed on simulated time with this IO provider. The other IO
+    /// providers don't support replaying captures.
+    #[cfg(feature = "pcap")]
+    pub fn replay<R: std::io::Read>(
+        &self,
+        reader: super::pcap::Reader<R>,
+        local_address: SocketAddress,
+    ) -> Result<executor::JoinHandle<()>> {
+        let datagrams = reader
+            .filter(|datagram| {
+                datagram.as_ref().map_or(true, |datagram| {
+                    datagram.direction != Some(super::pcap::Direction::Outbound)
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let buffers = self.buffers.clone();
+        let join = self.executor.spawn(async move {
+            let start = time::now();
+            let epoch = datagrams.first().map(|datagram| datagram.timestamp);
+
+            for datagram in datagrams {
+                let offset = datagram.timestamp.saturating_sub(epoch.unwrap_or_default());
+                time::delay_until(start + offset).await;
+
+                let packet = network::Packet {
+                    path: network::PathHandle {
+                        local_address: local_address.into(),
+                        remote_address: datagram.source.into(),
+                    },
+                    ecn: datagram.ecn,
+                    payload: datagram.payload,
+                };
+
+                buffers.rx(local_address, |queue| queue.receive(packet));
+            }
+        });
+
+        Ok(join)
+    }
 }
 
 pub struct Builder {
     handle: Handle,
     address: Option<SocketAddress>,
     max_mtu: MaxMtu,
+    #[cfg(feature = "pcap")]
+    pcap: Option<std::path::PathBuf>,
+    #[cfg(feature = "pcap")]
+    pcap_key_log: Option<std::path::PathBuf>,
 }
 
 impl Builder {
@@ -243,6 +301,35 @@ impl Builder {
         self.max_mtu = max_mtu.try_into().unwrap();
         self
     }
+
+    /// Binds the endpoint to the given address instead of generating one
+    ///
+    /// This is used to place endpoints on the hosts of a [`Topology`].
+    pub fn with_address(mut self, address: SocketAddress) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Writes every datagram the endpoint sends and receives to a pcapng file at `path`
+    ///
+    /// See [`pcap`](super::pcap) for details on the contents of the capture.
+    #[cfg(feature = "pcap")]
+    pub fn with_pcap<P: Into<std::path::PathBuf>>(mut self, path: P) -> Self {
+        self.pcap = Some(path.into());
+        self
+    }
+
+    /// Copies the TLS secrets which are appended to the key log at `path` into the capture
+    /// configured with `with_pcap`, so the packets can be decrypted
+    ///
+    /// This is usually the path in `SSLKEYLOGFILE`. Every secret written to the key log while
+    /// capturing is copied, including the secrets of other endpoints which log to the same
+    /// file, so this should only be enabled while debugging.
+    #[cfg(feature = "pcap")]
+    pub fn with_pcap_key_log<P: Into<std::path::PathBuf>>(mut self, path: P) -> Self {
+        self.pcap_key_log = Some(path.into());
+        self
+    }
 }
 
 pub struct Io {
@@ -258,9 +345,25 @@ impl Io {
             handle: Handle { executor, buffers },
             address,
             max_mtu,
+            #[cfg(feature = "pcap")]
+            pcap,
+            #[cfg(feature = "pcap")]
+            pcap_key_log,
         } = self.builder;
         endpoint.set_max_mtu(max_mtu);
 
+        #[cfg(feature = "pcap")]
+        let endpoint = match pcap {
+            Some(path) => {
+                let capture = super::pcap::Capture::new(endpoint, path)?;
+                match pcap_key_log {
+                    Some(key_log) => capture.with_key_log(key_log),
+                    None => capture,
+                }
+            }
+            None => super::pcap::Capture::disabled(endpoint),
+        };
+
         let handle = address.unwrap_or_else(|| buffers.generate_addr());
 
         buffers.register(handle);
@@ -354,3 +457,85 @@ impl<E: Endpoint<PathHandle = network::PathHandle>> Instance<E> {
         }
     }
 }
+
+#[cfg(all(test, feature = "pcap"))]
+mod tests {
+    use super::*;
+    use crate::io::pcap::{Direction, Reader, Writer};
+    use core::time::Duration;
+    use s2n_quic_core::inet::ExplicitCongestionNotification;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn replay_test() {
+        let local: SocketAddress = "127.0.0.1:4433"
+            .parse::<std::net::SocketAddr>()
+            .unwrap()
+            .into();
+        let remote: SocketAddress = "127.0.0.2:1234"
+            .parse::<std::net::SocketAddr>()
+            .unwrap()
+            .into();
+
+        let mut writer = Writer::new(vec![]).unwrap();
+        for (millis, direction, payload) in [
+            (10_000, Direction::Inbound, 1u8),
+            (10_100, Direction::Outbound, 2),
+            (10_250, Direction::Inbound, 3),
+        ] {
+            writer
+                .write_datagram(
+                    Duration::from_millis(millis),
+                    direction,
+                    &local,
+                    &remote,
+                    ExplicitCongestionNotification::Ect0,
+                    &[payload],
+                )
+                .unwrap();
+        }
+        let capture = writer.into_inner();
+
+        let mut executor = Executor::new(TxRecorder::default(), 123);
+        let handle = executor.handle().clone();
+        let received = Arc::new(Mutex::new(vec![]));
+
+        executor.enter(|| {
+            handle.buffers.register(local);
+            handle
+                .replay(Reader::new(&capture[..]).unwrap(), local)
+                .unwrap();
+
+            let buffers = handle.buffers.clone();
+            let received = received.clone();
+            primary::spawn(async move {
+                let start = time::now();
+                while received.lock().unwrap().len() < 2 {
+                    buffers.readiness(local).await.unwrap();
+                    buffers.rx(local, |queue| {
+                        for packet in queue.drain() {
+                            let offset = time::now().saturating_duration_since(start);
+                            received.lock().unwrap().push((offset, packet));
+                        }
+                    });
+                }
+            });
+        });
+
+        executor.run();
+
+        // the outbound datagram is skipped and the others keep their spacing
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+
+        let (first, packet) = &received[0];
+        assert_eq!(packet.path.remote_address.0, remote);
+        assert_eq!(packet.path.local_address.0, local);
+        assert_eq!(packet.ecn, ExplicitCongestionNotification::Ect0);
+        assert_eq!(packet.payload, [1]);
+
+        let (second, packet) = &received[1];
+        assert_eq!(*second - *first, Duration::from_millis(250));
+        assert_eq!(packet.payload, [3]);
+    }
+}
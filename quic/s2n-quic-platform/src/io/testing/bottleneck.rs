@@ -0,0 +1,433 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use core::time::Duration;
+use s2n_quic_core::time::Timestamp;
+use std::collections::VecDeque;
+
+/// The active queue management algorithm used by the bottleneck
+///
+/// When an algorithm signals congestion, packets using ECN are marked with CE instead of
+/// being dropped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aqm {
+    /// Packets are only dropped once the queue is full
+    DropTail,
+    /// Controlled Delay
+    ///
+    /// See https://www.rfc-editor.org/rfc/rfc8289
+    Codel {
+        /// The acceptable minimum standing queue delay
+        target: Duration,
+        /// The window in which the queue delay must stay above `target` before dropping
+        interval: Duration,
+    },
+    /// Random Early Detection
+    ///
+    /// See https://www.icir.org/floyd/papers/red/red.html
+    Red {
+        /// The average queue size, in bytes, at which packets start being dropped
+        min_threshold: u64,
+        /// The average queue size, in bytes, at which every packet is dropped
+        max_threshold: u64,
+        /// The probability of dropping a packet as the average reaches `max_threshold`
+        max_probability: f64,
+    },
+}
+
+impl Default for Aqm {
+    fn default() -> Self {
+        Self::DropTail
+    }
+}
+
+impl Aqm {
+    /// CoDel with the recommended `target` of 5ms and `interval` of 100ms
+    pub fn codel() -> Self {
+        Self::Codel {
+            target: Duration::from_millis(5),
+            interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// The weight given to the current queue size in the RED average
+const RED_WEIGHT: f64 = 0.002;
+
+/// A bottleneck link with a token bucket rate limiter in front of a finite FIFO queue
+///
+/// The link never reorders packets, so the time each packet leaves the queue is known as
+/// soon as it is enqueued. This lets the queue run entirely on virtual time without a task
+/// draining it.
+#[derive(Debug)]
+pub struct Queue {
+    /// The rate at which tokens are added to the bucket, in bytes per second
+    pub rate: u64,
+    /// The maximum number of tokens in the bucket, in bytes
+    pub burst: u64,
+    /// The maximum number of bytes in the queue
+    pub capacity: u64,
+    /// The number of queued bytes above which packets are marked with CE
+    pub ecn_threshold: u64,
+    pub aqm: Aqm,
+    tokens: f64,
+    last_refill: Option<Timestamp>,
+    /// The departure time of the last packet to enter the queue
+    tail: Option<Timestamp>,
+    departures: VecDeque<(Timestamp, u64)>,
+    len: u64,
+    red_average: f64,
+    codel: Codel,
+}
+
+impl Default for Queue {
+    fn default() -> Self {
+        Self {
+            rate: u64::MAX,
+            burst: 0,
+            capacity: u64::MAX,
+            ecn_threshold: u64::MAX,
+            aqm: Aqm::default(),
+            tokens: 0.0,
+            last_refill: None,
+            tail: None,
+            departures: VecDeque::new(),
+            len: 0,
+            red_average: 0.0,
+            codel: Codel::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The packet was dropped by the queue
+    Drop,
+    /// The packet leaves the queue at `departure`
+    Forward {
+        departure: Timestamp,
+        /// The packet should be marked with CE
+        congestion_experienced: bool,
+    },
+}
+
+impl Queue {
+    /// Returns an empty queue with the same configuration as `self`
+    pub fn clone_config(&self) -> Self {
+        Self {
+            rate: self.rate,
+            burst: self.burst,
+            capacity: self.capacity,
+            ecn_threshold: self.ecn_threshold,
+            aqm: self.aqm,
+            ..Default::default()
+        }
+    }
+
+    /// Enqueues a packet of `len` bytes which arrives at the bottleneck at `now`
+    ///
+    /// `gen_rate` is called with a probability to make a random decision.
+    pub fn enqueue(
+        &mut self,
+        now: Timestamp,
+        len: u64,
+        using_ecn: bool,
+        gen_rate: impl FnOnce(f64) -> bool,
+    ) -> Outcome {
+        // remove any packets which have left the queue
+        while let Some((departure, packet_len)) = self.departures.front().copied() {
+            if departure > now {
+                break;
+            }
+            self.departures.pop_front();
+            self.len -= packet_len;
+        }
+
+        if self.len + len > self.capacity {
+            return Outcome::Drop;
+        }
+
+        let queued = self.len;
+        let start = self.tail.map_or(now, |tail| tail.max(now));
+        let (departure, tokens) = self.departure(start, len);
+
+        let mut congestion_experienced = using_ecn && queued > self.ecn_threshold;
+
+        let is_congested = match self.aqm {
+            Aqm::DropTail => false,
+            Aqm::Codel { target, interval } => {
+                // the queue is FIFO so running CoDel at enqueue time with the departure time
+                // makes the same decisions as running it when the packet is dequeued
+                let sojourn = departure - now;
+                self.codel
+                    .should_drop(departure, sojourn, target, interval, queued)
+            }
+            Aqm::Red {
+                min_threshold,
+                max_threshold,
+                max_probability,
+            } => {
+                self.red_average =
+                    (1.0 - RED_WEIGHT) * self.red_average + RED_WEIGHT * queued as f64;
+                let average = self.red_average;
+
+                if average < min_threshold as f64 {
+                    false
+                } else if average >= max_threshold as f64 {
+                    true
+                } else {
+                    let range = (max_threshold - min_threshold) as f64;
+                    let probability = max_probability * (average - min_threshold as f64) / range;
+                    gen_rate(probability)
+                }
+            }
+        };
+
+        if is_congested {
+            if !using_ecn {
+                return Outcome::Drop;
+            }
+            congestion_experienced = true;
+        }
+
+        self.tokens = tokens;
+        self.last_refill = Some(departure);
+        self.tail = Some(departure);
+        self.departures.push_back((departure, len));
+        self.len += len;
+
+        Outcome::Forward {
+            departure,
+            congestion_experienced,
+        }
+    }
+
+    /// Returns the time the packet can leave the queue and the number of tokens left after
+    #[inline]
+    fn departure(&self, start: Timestamp, len: u64) -> (Timestamp, f64) {
+        if self.rate == u64::MAX {
+            return (start, self.tokens);
+        }
+
+        let rate = self.rate as f64;
+        // the bucket needs to hold at least a single packet for it to ever be sent
+        let burst = self.burst.max(len) as f64;
+        let elapsed = self
+            .last_refill
+            .map_or(Duration::ZERO, |last| start.saturating_duration_since(last));
+        let tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(burst);
+        let len = len as f64;
+
+        if tokens >= len {
+            (start, tokens - len)
+        } else {
+            let wait = Duration::from_secs_f64((len - tokens) / rate);
+            (start + wait, 0.0)
+        }
+    }
+}
+
+/// The state of the CoDel algorithm
+///
+/// See https://www.rfc-editor.org/rfc/rfc8289#section-5
+#[derive(Debug, Default)]
+struct Codel {
+    first_above_time: Option<Timestamp>,
+    drop_next: Option<Timestamp>,
+    count: u32,
+    last_count: u32,
+    dropping: bool,
+}
+
+impl Codel {
+    fn should_drop(
+        &mut self,
+        now: Timestamp,
+        sojourn: Duration,
+        target: Duration,
+        interval: Duration,
+        queued: u64,
+    ) -> bool {
+        let ok_to_drop = self.ok_to_drop(now, sojourn, target, interval, queued);
+
+        if self.dropping {
+            if !ok_to_drop {
+                self.dropping = false;
+                return false;
+            }
+
+            let drop_next = self.drop_next.unwrap_or(now);
+            if now >= drop_next {
+                self.count += 1;
+                self.drop_next = Some(control_law(drop_next, interval, self.count));
+                return true;
+            }
+
+            return false;
+        }
+
+        if !ok_to_drop {
+            return false;
+        }
+
+        self.dropping = true;
+
+        // start with the previous drop rate if we were recently dropping
+        let delta = self.count.saturating_sub(self.last_count);
+        let recently_dropped = self.drop_next.map_or(false, |drop_next| {
+            now.saturating_duration_since(drop_next) < interval * 16
+        });
+        self.count = if delta > 1 && recently_dropped {
+            delta
+        } else {
+            1
+        };
+        self.last_count = self.count;
+        self.drop_next = Some(control_law(now, interval, self.count));
+
+        true
+    }
+
+    fn ok_to_drop(
+        &mut self,
+        now: Timestamp,
+        sojourn: Duration,
+        target: Duration,
+        interval: Duration,
+        queued: u64,
+    ) -> bool {
+        // the queue isn't standing if it only holds the packet in front of this one
+        if sojourn < target || queued == 0 {
+            self.first_above_time = None;
+            return false;
+        }
+
+        match self.first_above_time {
+            Some(first_above_time) => now >= first_above_time,
+            None => {
+                self.first_above_time = Some(now + interval);
+                false
+            }
+        }
+    }
+}
+
+#[inline]
+fn control_law(t: Timestamp, interval: Duration, count: u32) -> Timestamp {
+    t + Duration::from_secs_f64(interval.as_secs_f64() / (count as f64).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(millis: u64) -> Timestamp {
+        // start after the epoch since timestamps can't be zero
+        let epoch = Duration::from_secs(1);
+        unsafe { Timestamp::from_duration(epoch + Duration::from_millis(millis)) }
+    }
+
+    fn departure(outcome: Outcome) -> Timestamp {
+        match outcome {
+            Outcome::Forward { departure, .. } => departure,
+            Outcome::Drop => panic!("packet was dropped"),
+        }
+    }
+
+    #[test]
+    fn token_bucket_test() {
+        let mut queue = Queue {
+            // 1000 bytes per second
+            rate: 1000,
+            burst: 2000,
+            ..Default::default()
+        };
+
+        // the bucket starts empty so the first packet waits for its tokens
+        let outcome = queue.enqueue(time(0), 100, false, |_| false);
+        assert_eq!(departure(outcome), time(100));
+
+        // packets are serialized behind the previous one
+        let outcome = queue.enqueue(time(0), 100, false, |_| false);
+        assert_eq!(departure(outcome), time(200));
+        assert_eq!(queue.len, 200);
+
+        // after an idle period the bucket allows a burst
+        for _ in 0..20 {
+            let outcome = queue.enqueue(time(10_000), 100, false, |_| false);
+            assert_eq!(departure(outcome), time(10_000));
+        }
+        let outcome = queue.enqueue(time(10_000), 100, false, |_| false);
+        assert_eq!(departure(outcome), time(10_100));
+    }
+
+    #[test]
+    fn drop_tail_test() {
+        let mut queue = Queue {
+            rate: 1000,
+            capacity: 250,
+            ..Default::default()
+        };
+
+        assert_ne!(queue.enqueue(time(0), 100, false, |_| false), Outcome::Drop);
+        assert_ne!(queue.enqueue(time(0), 100, false, |_| false), Outcome::Drop);
+        assert_eq!(queue.enqueue(time(0), 100, false, |_| false), Outcome::Drop);
+
+        // the first packet has left the queue, which makes room for another
+        assert_ne!(
+            queue.enqueue(time(100), 100, false, |_| false),
+            Outcome::Drop
+        );
+    }
+
+    #[test]
+    fn ecn_threshold_test() {
+        let mut queue = Queue {
+            rate: 1000,
+            ecn_threshold: 150,
+            ..Default::default()
+        };
+
+        let mut marks = vec![];
+        for _ in 0..4 {
+            match queue.enqueue(time(0), 100, true, |_| false) {
+                Outcome::Forward {
+                    congestion_experienced,
+                    ..
+                } => marks.push(congestion_experienced),
+                Outcome::Drop => panic!("packet was dropped"),
+            }
+        }
+
+        assert_eq!(marks, [false, false, true, true]);
+    }
+
+    #[test]
+    fn codel_test() {
+        let mut queue = Queue {
+            rate: 1000,
+            aqm: Aqm::codel(),
+            ..Default::default()
+        };
+
+        // keep a standing queue for longer than the interval
+        let mut dropped = 0;
+        let mut marked = 0;
+        for now in (0..1000).step_by(50) {
+            match queue.enqueue(time(now), 100, false, |_| false) {
+                Outcome::Drop => dropped += 1,
+                Outcome::Forward { .. } => {}
+            }
+            if let Outcome::Forward {
+                congestion_experienced: true,
+                ..
+            } = queue.enqueue(time(now), 100, true, |_| false)
+            {
+                marked += 1;
+            }
+        }
+
+        assert!(dropped > 0);
+        assert!(marked > 0);
+    }
+}
@@ -1,11 +1,19 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::network::{Buffers, Network, Packet};
+use super::{
+    bottleneck::{self, Aqm},
+    network::{Buffers, Network, Packet},
+};
 use core::time::Duration;
-use s2n_quic_core::{havoc, path::MaxMtu};
+use s2n_quic_core::{
+    havoc,
+    inet::ExplicitCongestionNotification,
+    path::{LocalAddress, MaxMtu, RemoteAddress},
+};
 use std::{
     borrow::Cow,
+    collections::HashMap,
     sync::{
         atomic::{AtomicU16, AtomicU64, Ordering},
         Arc, Mutex,
@@ -179,6 +187,135 @@ impl Model {
             .store(value, Ordering::SeqCst);
         self
     }
+
+    pub fn bandwidth(&self) -> u64 {
+        match self.0.bottleneck.lock().unwrap().config.rate {
+            u64::MAX => u64::MAX,
+            rate => rate * 8,
+        }
+    }
+
+    /// Sets the rate of the bottleneck link, in bits per second
+    ///
+    /// Packets sent faster than this rate wait in the bottleneck queue. By default, the
+    /// bandwidth is unlimited.
+    pub fn set_bandwidth(&self, value: u64) -> &Self {
+        let rate = match value {
+            u64::MAX => u64::MAX,
+            value => (value / 8).max(1),
+        };
+        self.0
+            .bottleneck
+            .lock()
+            .unwrap()
+            .update(|queue| queue.rate = rate);
+        self
+    }
+
+    pub fn burst(&self) -> u64 {
+        self.0.bottleneck.lock().unwrap().config.burst
+    }
+
+    /// Sets the number of bytes the bottleneck can send at once after being idle
+    ///
+    /// By default, only a single packet can be sent at once.
+    pub fn set_burst(&self, value: u64) -> &Self {
+        self.0
+            .bottleneck
+            .lock()
+            .unwrap()
+            .update(|queue| queue.burst = value);
+        self
+    }
+
+    pub fn queue_capacity(&self) -> u64 {
+        self.0.bottleneck.lock().unwrap().config.capacity
+    }
+
+    /// Sets the maximum number of bytes which can wait in the bottleneck queue
+    ///
+    /// Any packets that would exceed this amount are dropped.
+    pub fn set_queue_capacity(&self, value: u64) -> &Self {
+        self.0
+            .bottleneck
+            .lock()
+            .unwrap()
+            .update(|queue| queue.capacity = value);
+        self
+    }
+
+    pub fn ecn_threshold(&self) -> u64 {
+        self.0.bottleneck.lock().unwrap().config.ecn_threshold
+    }
+
+    /// Sets the number of queued bytes above which packets using ECN are marked with CE
+    pub fn set_ecn_threshold(&self, value: u64) -> &Self {
+        self.0
+            .bottleneck
+            .lock()
+            .unwrap()
+            .update(|queue| queue.ecn_threshold = value);
+        self
+    }
+
+    pub fn aqm(&self) -> Aqm {
+        self.0.bottleneck.lock().unwrap().config.aqm
+    }
+
+    /// Sets the active queue management algorithm of the bottleneck queue
+    pub fn set_aqm(&self, value: Aqm) -> &Self {
+        self.0
+            .bottleneck
+            .lock()
+            .unwrap()
+            .update(|queue| queue.aqm = value);
+        self
+    }
+
+    /// Returns the number of packets which have been dropped by the bottleneck queues
+    pub fn bottleneck_drops(&self) -> u64 {
+        self.0.bottleneck.lock().unwrap().drops
+    }
+
+    /// Returns the number of packets which have been marked with CE by the bottleneck queues
+    pub fn bottleneck_marks(&self) -> u64 {
+        self.0.bottleneck.lock().unwrap().marks
+    }
+}
+
+/// The bottleneck links between each pair of addresses
+///
+/// Each direction has its own queue so packets sent one way never wait behind the packets
+/// sent the other way.
+#[derive(Debug, Default)]
+struct Bottleneck {
+    /// The configuration of every queue
+    config: bottleneck::Queue,
+    queues: HashMap<(LocalAddress, RemoteAddress), bottleneck::Queue>,
+    drops: u64,
+    marks: u64,
+}
+
+impl Bottleneck {
+    /// Applies a configuration change to every queue
+    fn update(&mut self, f: impl Fn(&mut bottleneck::Queue)) {
+        f(&mut self.config);
+        for queue in self.queues.values_mut() {
+            f(queue);
+        }
+    }
+
+    /// Returns the queue for packets sent from `local_address` to `remote_address`
+    fn queue(
+        &mut self,
+        local_address: LocalAddress,
+        remote_address: RemoteAddress,
+    ) -> &mut bottleneck::Queue {
+        let config = &self.config;
+        self.queues
+            .entry((local_address, remote_address))
+            .or_insert_with(|| config.clone_config())
+    }
 }
 
 fn rate_to_u64(rate: f64) -> u64 {
@@ -200,6 +337,7 @@ struct State {
     inflight_delay: AtomicU64,
     inflight_delay_threshold: AtomicU64,
     current_inflight: AtomicU64,
+    bottleneck: Mutex<Bottleneck>,
 }
 
 impl Default for State {
@@ -217,6 +355,7 @@ impl Default for State {
             inflight_delay: AtomicU64::new(0),
             inflight_delay_threshold: AtomicU64::new(u64::MAX),
             current_inflight: AtomicU64::new(0),
+            bottleneck: Default::default(),
         }
     }
 }
@@ -237,6 +376,8 @@ impl Network for Model {
         let mut transmit_time = now + self.delay();
         let transmit_time = &mut transmit_time;
 
+        let mut bottlenecks = self.0.bottleneck.lock().unwrap();
+
         #[inline]
         fn gen_rate(rate: u64) -> bool {
             // ensure the rate isn't 0 before actually generating a random number
@@ -259,8 +400,36 @@ impl Network for Model {
                 return 0;
             }
 
+            // wait for the packet to make it through the bottleneck queue
+            let outcome = bottlenecks
+                .queue(packet.path.local_address, packet.path.remote_address)
+                .enqueue(
+                    now,
+                    packet.payload.len() as u64,
+                    packet.ecn.using_ecn(),
+                    |rate| gen_rate(rate_to_u64(rate)),
+                );
+            let (queue_delay, congestion_experienced) = match outcome {
+                bottleneck::Outcome::Drop => {
+                    bottlenecks.drops += 1;
+                    return 0;
+                }
+                bottleneck::Outcome::Forward {
+                    departure,
+                    congestion_experienced,
+                } => (departure - now, congestion_experienced),
+            };
+
+            if congestion_experienced {
+                bottlenecks.marks += 1;
+            }
+
             let mut packet = packet.into_owned();
 
+            if congestion_experienced {
+                packet.ecn = ExplicitCongestionNotification::Ce;
+            }
+
             if !packet.payload.is_empty() && gen_rate(corrupt_rate) {
                 use havoc::Strategy as _;
 
@@ -282,7 +451,7 @@ impl Network for Model {
             }
 
             // copy the transmit time for this packet
-            let mut transmit_time = *transmit_time;
+            let mut transmit_time = *transmit_time + queue_delay;
 
             if !network_jitter.is_zero() {
                 transmit_time += gen_jitter(network_jitter);
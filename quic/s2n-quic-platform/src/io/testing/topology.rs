@@ -0,0 +1,437 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    bottleneck::{self, Aqm},
+    network::{Buffers, Network, Packet},
+};
+use core::{ops::Range, time::Duration};
+use s2n_quic_core::{
+    inet::{ExplicitCongestionNotification, IpAddress, IpV4Address, SocketAddress},
+    path::MaxMtu,
+    time::Timestamp,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// A network of named hosts connected by links
+///
+/// Each endpoint binds to an address on one of the hosts with
+/// [`Builder::with_address`](super::Builder::with_address). Datagrams are only delivered between
+/// hosts which are connected by a link in the direction of travel. Every random decision is made
+/// with the executor's seed, so a test with the same seed always produces the same run.
+///
+/// ```ignore
+/// let topology = Topology::default();
+/// let client = topology.host("client");
+/// let server = topology.host("server");
+/// topology.connect(&client, &server, Link::default().with_delay(Duration::from_millis(20)));
+///
+/// // partition the hosts after 1 second and heal the link a second later
+/// topology.partition(&client, &server, Duration::from_secs(1)..Duration::from_secs(2));
+///
+/// test(topology, |handle| {
+///     let server = Server::builder()
+///         .with_io(handle.builder().with_address(server.bind(443)).build()?)?
+///     // ...
+/// })
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Topology(Arc<Mutex<State>>);
+
+impl Topology {
+    /// Returns the host with the given name, creating it if it doesn't exist
+    pub fn host(&self, name: &str) -> Host {
+        let mut state = self.0.lock().unwrap();
+
+        if let Some(host) = state.hosts.get(name) {
+            return host.clone();
+        }
+
+        // hosts are assigned addresses in the order they are created, starting at 10.0.0.1
+        let id = state.hosts.len() as u32 + 1;
+        let ip = IpV4Address::from((u32::from_be_bytes([10, 0, 0, 0]) + id).to_be_bytes());
+
+        let host = Host {
+            name: name.into(),
+            ip: ip.into(),
+        };
+        state.hosts.insert(name.to_string(), host.clone());
+        state.addresses.insert(host.ip, host.name.clone());
+        host
+    }
+
+    /// Connects two hosts with a link in both directions
+    ///
+    /// Each direction has its own bandwidth and partition schedule.
+    pub fn connect(&self, a: &Host, b: &Host, link: Link) -> &Self {
+        self.connect_one_way(a, b, link.clone());
+        self.connect_one_way(b, a, link)
+    }
+
+    /// Connects two hosts with a link which only carries datagrams from `from` to `to`
+    ///
+    /// If the hosts were already connected in this direction, the link configuration is
+    /// replaced and any scheduled partitions are kept.
+    pub fn connect_one_way(&self, from: &Host, to: &Host, config: Link) -> &Self {
+        let mut state = self.0.lock().unwrap();
+        let key = (from.name.clone(), to.name.clone());
+        let link = state.links.entry(key).or_default();
+        link.queue = config.queue();
+        link.link = config;
+        link.is_connected = true;
+        self
+    }
+
+    /// Partitions the hosts in both directions for the given range of time since the start of
+    /// the test
+    pub fn partition(&self, a: &Host, b: &Host, during: Range<Duration>) -> &Self {
+        self.partition_one_way(a, b, during.clone());
+        self.partition_one_way(b, a, during)
+    }
+
+    /// Partitions the link from `from` to `to` for the given range of time since the start of
+    /// the test
+    ///
+    /// Datagrams which are sent during the partition, or would arrive during it, are dropped.
+    pub fn partition_one_way(&self, from: &Host, to: &Host, during: Range<Duration>) -> &Self {
+        let mut state = self.0.lock().unwrap();
+        let key = (from.name.clone(), to.name.clone());
+        state.links.entry(key).or_default().partitions.push(during);
+        self
+    }
+
+    fn transmit(&self, now: Timestamp, mut packet: Packet, buffers: &Buffers) -> usize {
+        let mut state = self.0.lock().unwrap();
+
+        let key = match state.route(&packet) {
+            Some(key) => key,
+            None => return 0,
+        };
+
+        let link = state
+            .links
+            .get_mut(&key)
+            .expect("route returned a valid link");
+
+        if link.is_partitioned(now) {
+            return 0;
+        }
+
+        let config = &link.link;
+
+        // drop the packet if it's over the link MTU
+        if packet.payload.len() > config.max_udp_payload as usize {
+            return 0;
+        }
+
+        if gen_rate(config.drop_rate) {
+            return 0;
+        }
+
+        // packets are serialized onto the link one at a time, and wait in the link's queue
+        // until then
+        let len = packet.payload.len() as u64;
+        let mut arrival = match link
+            .queue
+            .enqueue(now, len, packet.ecn.using_ecn(), gen_rate)
+        {
+            bottleneck::Outcome::Drop => return 0,
+            bottleneck::Outcome::Forward {
+                departure,
+                congestion_experienced,
+            } => {
+                if congestion_experienced {
+                    packet.ecn = ExplicitCongestionNotification::Ce;
+                }
+                departure
+            }
+        };
+
+        let config = &link.link;
+        arrival += config.delay;
+
+        if !config.jitter.is_zero() {
+            arrival += gen_duration(config.jitter);
+        }
+
+        if gen_rate(config.reorder_rate) {
+            arrival += config.reorder_delay;
+        }
+
+        drop(state);
+
+        // reverse the addresses so the dst/src are correct for the receiver
+        packet.switch();
+
+        let topology = self.clone();
+        let buffers = buffers.clone();
+
+        super::spawn(async move {
+            if now != arrival {
+                super::time::delay_until(arrival).await;
+            }
+
+            // the partition may have started while the packet was in flight
+            let is_partitioned = topology
+                .0
+                .lock()
+                .unwrap()
+                .links
+                .get(&key)
+                .map_or(true, |link| link.is_partitioned(arrival));
+
+            if is_partitioned {
+                return;
+            }
+
+            buffers.rx(*packet.path.local_address, |queue| {
+                queue.receive(packet);
+            });
+        });
+
+        1
+    }
+}
+
+impl Network for Topology {
+    fn execute(&mut self, buffers: &Buffers) -> usize {
+        let now = super::time::now();
+        let mut transmission_count = 0;
+
+        buffers.drain_pending_transmissions(|packet| {
+            transmission_count += self.transmit(now, packet, buffers);
+            Ok(())
+        });
+
+        transmission_count
+    }
+}
+
+/// A host in a [`Topology`] with a single IP address
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Host {
+    name: String,
+    ip: IpAddress,
+}
+
+impl Host {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn ip(&self) -> IpAddress {
+        self.ip
+    }
+
+    /// Returns the socket address of the host with the given port
+    pub fn bind(&self, port: u16) -> SocketAddress {
+        match self.ip {
+            IpAddress::Ipv4(ip) => ip.with_port(port).into(),
+            IpAddress::Ipv6(ip) => ip.with_port(port).into(),
+        }
+    }
+}
+
+/// The characteristics of a link between two hosts
+#[derive(Clone, Debug)]
+pub struct Link {
+    delay: Duration,
+    jitter: Duration,
+    bandwidth: Option<u64>,
+    queue_capacity: Option<u64>,
+    ecn_threshold: u64,
+    aqm: Aqm,
+    drop_rate: f64,
+    reorder_rate: f64,
+    reorder_delay: Duration,
+    max_udp_payload: u16,
+}
+
+impl Default for Link {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(50),
+            jitter: Duration::ZERO,
+            bandwidth: None,
+            queue_capacity: None,
+            ecn_threshold: u64::MAX,
+            aqm: Aqm::default(),
+            drop_rate: 0.0,
+            reorder_rate: 0.0,
+            reorder_delay: Duration::ZERO,
+            max_udp_payload: MaxMtu::default().into(),
+        }
+    }
+}
+
+impl Link {
+    /// Returns the queue in front of the link
+    fn queue(&self) -> bottleneck::Queue {
+        let mut queue = bottleneck::Queue {
+            ecn_threshold: self.ecn_threshold,
+            aqm: self.aqm,
+            ..Default::default()
+        };
+
+        if let Some(bandwidth) = self.bandwidth {
+            queue.rate = (bandwidth / 8).max(1);
+            queue.capacity = self
+                .queue_capacity
+                .unwrap_or_else(|| (queue.rate / 10).max(self.max_udp_payload as u64));
+        }
+
+        queue
+    }
+
+    /// The amount of time a packet takes to propagate across the link
+    #[must_use]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// The maximum amount of random delay added to each packet
+    ///
+    /// Setting this value to `>0` will cause packets to be reordered.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// The rate at which packets are serialized onto the link, in bits per second
+    ///
+    /// By default, the bandwidth of the link is unlimited. Packets sent faster than the
+    /// bandwidth wait in the link's queue, which is bounded by
+    /// [`with_queue_capacity`](Self::with_queue_capacity).
+    #[must_use]
+    pub fn with_bandwidth(mut self, bits_per_second: u64) -> Self {
+        self.bandwidth = Some(bits_per_second.max(1));
+        self
+    }
+
+    /// The maximum number of bytes which can wait to be serialized onto the link
+    ///
+    /// Any packets that would exceed this amount are dropped. By default, the queue holds 100ms
+    /// of data at the link's bandwidth, and at least a single packet.
+    #[must_use]
+    pub fn with_queue_capacity(mut self, bytes: u64) -> Self {
+        self.queue_capacity = Some(bytes);
+        self
+    }
+
+    /// The number of queued bytes above which packets using ECN are marked with CE
+    ///
+    /// By default, packets are never marked.
+    #[must_use]
+    pub fn with_ecn_threshold(mut self, bytes: u64) -> Self {
+        self.ecn_threshold = bytes;
+        self
+    }
+
+    /// The active queue management algorithm of the link's queue
+    ///
+    /// By default, packets are only dropped once the queue is full.
+    #[must_use]
+    pub fn with_aqm(mut self, aqm: Aqm) -> Self {
+        self.aqm = aqm;
+        self
+    }
+
+    /// The odds a packet will be dropped.
+    ///
+    /// Each packet will make an independent decision with odds of `0.0..1.0`, with `0.0` having no
+    /// chance and `1.0` occurring with each packet.
+    #[must_use]
+    pub fn with_drop_rate(mut self, rate: f64) -> Self {
+        self.drop_rate = rate;
+        self
+    }
+
+    /// The odds a packet will be held back by `delay`, which reorders it with the packets sent
+    /// after it
+    #[must_use]
+    pub fn with_reordering(mut self, rate: f64, delay: Duration) -> Self {
+        self.reorder_rate = rate;
+        self.reorder_delay = delay;
+        self
+    }
+
+    /// The maximum payload size for the link
+    #[must_use]
+    pub fn with_max_udp_payload(mut self, max_udp_payload: u16) -> Self {
+        self.max_udp_payload = max_udp_payload;
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    hosts: HashMap<String, Host>,
+    addresses: HashMap<IpAddress, String>,
+    links: HashMap<(String, String), LinkState>,
+}
+
+impl State {
+    /// Returns the link which carries the packet from its source to its destination
+    fn route(&self, packet: &Packet) -> Option<(String, String)> {
+        let from = self
+            .addresses
+            .get(&packet.path.local_address.ip().unmap())?;
+        let to = self
+            .addresses
+            .get(&packet.path.remote_address.ip().unmap())?;
+        let key = (from.clone(), to.clone());
+
+        // only links which were connected can carry packets
+        let link = self.links.get(&key)?;
+        if link.is_connected {
+            Some(key)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LinkState {
+    link: Link,
+    is_connected: bool,
+    partitions: Vec<Range<Duration>>,
+    /// The packets waiting to be serialized onto the link
+    queue: bottleneck::Queue,
+}
+
+impl Default for LinkState {
+    fn default() -> Self {
+        Self {
+            link: Link::default(),
+            is_connected: false,
+            partitions: vec![],
+            queue: bottleneck::Queue::default(),
+        }
+    }
+}
+
+impl LinkState {
+    fn is_partitioned(&self, now: Timestamp) -> bool {
+        let now = unsafe { now.as_duration() };
+        self.partitions.iter().any(|range| range.contains(&now))
+    }
+}
+
+#[inline]
+fn gen_rate(rate: f64) -> bool {
+    let rate = rate.max(0.0).min(1.0);
+    let rate = (rate * u64::MAX as f64).round() as u64;
+    // ensure the rate isn't 0 before actually generating a random number
+    rate > 0 && super::rand::gen::<u64>() < rate
+}
+
+#[inline]
+fn gen_duration(max: Duration) -> Duration {
+    Duration::from_micros(super::rand::gen_range(0..max.as_micros() as u64))
+}
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::select::{self, Select};
-use crate::{buffer::default as buffer, features::gso, socket::default as socket};
+use crate::{buffer::default as buffer, features::gso, socket::default as socket, syscall};
 use cfg_if::cfg_if;
+use core::task::Poll;
 use s2n_quic_core::{
     endpoint::Endpoint,
     event::{self, EndpointPublisher as _},
@@ -16,9 +17,18 @@ use tokio::{net::UdpSocket, runtime::Handle};
 
 pub type PathHandle = socket::Handle;
 
-mod clock;
+pub(super) mod clock;
+mod migration;
 use clock::Clock;
 
+cfg_if! {
+    if #[cfg(any(s2n_quic_platform_socket_msg, s2n_quic_platform_socket_mmsg))] {
+        type AsyncFd = tokio::io::unix::AsyncFd<std::net::UdpSocket>;
+    } else {
+        use async_fd_shim::AsyncFd;
+    }
+}
+
 impl crate::socket::std::Socket for UdpSocket {
     type Error = io::Error;
 
@@ -62,7 +72,14 @@ impl Io {
             send_buffer_size,
             max_mtu,
             max_segments,
+            gro_disabled,
+            pacing_offload,
             reuse_port,
+            shard_count,
+            #[cfg(feature = "pcap")]
+            pcap,
+            #[cfg(feature = "pcap")]
+            pcap_key_log,
         } = self.builder;
 
         endpoint.set_max_mtu(max_mtu);
@@ -103,7 +120,7 @@ impl Io {
             rx_socket.set_nonblocking(true)?;
             rx_socket
         } else if let Some(recv_addr) = recv_addr {
-            bind(recv_addr, reuse_port)?
+            syscall::bind_udp(recv_addr, reuse_port)?
         } else {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
@@ -116,7 +133,7 @@ impl Io {
             tx_socket.set_nonblocking(true)?;
             tx_socket
         } else if let Some(send_addr) = send_addr {
-            bind(send_addr, reuse_port)?
+            syscall::bind_udp(send_addr, reuse_port)?
         } else {
             // No tx_socket or send address was specified, so the tx socket
             // will be a handle to the rx socket.
@@ -143,114 +160,58 @@ impl Io {
             convert_addr_to_std(rx_socket.local_addr()?)?,
         );
 
-        //= https://www.rfc-editor.org/rfc/rfc9000#section-14
-        //# UDP datagrams MUST NOT be fragmented at the IP layer.
-
-        //= https://www.rfc-editor.org/rfc/rfc9000#section-14
-        //# In IPv4 [IPv4], the Don't Fragment (DF) bit MUST be set if possible, to
-        //# prevent fragmentation on the path.
-
-        //= https://www.rfc-editor.org/rfc/rfc8899#section-3
-        //# In IPv4, a probe packet MUST be sent with the Don't
-        //# Fragment (DF) bit set in the IP header and without network layer
-        //# endpoint fragmentation.
-
-        //= https://www.rfc-editor.org/rfc/rfc8899#section-4.5
-        //# A PL implementing this specification MUST suspend network layer
-        //# processing of outgoing packets that enforces a PMTU
-        //# [RFC1191][RFC8201] for each flow utilizing DPLPMTUD and instead use
-        //# DPLPMTUD to control the size of packets that are sent by a flow.
-        #[cfg(s2n_quic_platform_mtu_disc)]
-        {
-            use std::os::unix::io::AsRawFd;
-
-            // IP_PMTUDISC_PROBE setting will set the DF (Don't Fragment) flag
-            // while also ignoring the Path MTU. This means packets will not
-            // be fragmented, and the EMSGSIZE error will not be returned for
-            // packets larger than the Path MTU according to the kernel.
-            libc!(setsockopt(
-                tx_socket.as_raw_fd(),
-                libc::IPPROTO_IP,
-                libc::IP_MTU_DISCOVER,
-                &libc::IP_PMTUDISC_PROBE as *const _ as _,
-                core::mem::size_of_val(&libc::IP_PMTUDISC_PROBE) as _,
-            ))?;
-
-            if tx_addr.is_ipv6() {
-                libc!(setsockopt(
-                    tx_socket.as_raw_fd(),
-                    libc::IPPROTO_IPV6,
-                    libc::IPV6_MTU_DISCOVER,
-                    &libc::IP_PMTUDISC_PROBE as *const _ as _,
-                    core::mem::size_of_val(&libc::IP_PMTUDISC_PROBE) as _,
-                ))?;
-            }
-        }
+        syscall::configure_mtu_disc(&tx_socket, &tx_addr)?;
 
         // Set up the RX socket to pass ECN information
-        #[cfg(s2n_quic_platform_tos)]
-        {
-            use std::os::unix::io::AsRawFd;
-            let enabled: libc::c_int = 1;
-
-            // This option needs to be enabled regardless of domain (IPv4 vs IPv6), except on mac
-            if rx_addr.is_ipv4() || !cfg!(any(target_os = "macos", target_os = "ios")) {
-                libc!(setsockopt(
-                    rx_socket.as_raw_fd(),
-                    libc::IPPROTO_IP,
-                    libc::IP_RECVTOS,
-                    &enabled as *const _ as _,
-                    core::mem::size_of_val(&enabled) as _,
-                ))?;
-            }
-
-            if rx_addr.is_ipv6() {
-                libc!(setsockopt(
-                    rx_socket.as_raw_fd(),
-                    libc::IPPROTO_IPV6,
-                    libc::IPV6_RECVTCLASS,
-                    &enabled as *const _ as _,
-                    core::mem::size_of_val(&enabled) as _,
-                ))?;
-            }
-        }
+        let ecn_enabled = syscall::configure_tos(&rx_socket, &rx_addr)?;
         publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
             configuration: event::builder::PlatformFeatureConfiguration::Ecn {
-                enabled: cfg!(s2n_quic_platform_tos),
+                enabled: ecn_enabled,
             },
         });
 
         // Set up the RX socket to pass information about the local address and interface
-        #[cfg(s2n_quic_platform_pktinfo)]
-        {
-            use std::os::unix::io::AsRawFd;
-            let enabled: libc::c_int = 1;
-
-            if rx_addr.is_ipv4() {
-                libc!(setsockopt(
-                    rx_socket.as_raw_fd(),
-                    libc::IPPROTO_IP,
-                    libc::IP_PKTINFO,
-                    &enabled as *const _ as _,
-                    core::mem::size_of_val(&enabled) as _,
-                ))?;
-            } else {
-                libc!(setsockopt(
-                    rx_socket.as_raw_fd(),
-                    libc::IPPROTO_IPV6,
-                    libc::IPV6_RECVPKTINFO,
-                    &enabled as *const _ as _,
-                    core::mem::size_of_val(&enabled) as _,
-                ))?;
-            }
+        syscall::configure_pktinfo(&rx_socket, &rx_addr)?;
+
+        // Steer datagrams to the socket which owns the connection ID
+        if let Some(shard_count) = shard_count {
+            #[cfg(target_os = "linux")]
+            syscall::configure_reuse_port_steering(&rx_socket, shard_count)?;
+
+            // mark the variable as "used" regardless of platform support
+            let _ = shard_count;
         }
 
+        // Set up the RX socket to coalesce datagrams from the same peer
+        let gro_enabled = !gro_disabled && syscall::configure_gro(&rx_socket);
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Gro {
+                enabled: gro_enabled,
+            },
+        });
+
+        // Set up the TX socket to accept transmit times so the kernel can pace the packets
+        let txtime_enabled = pacing_offload && syscall::configure_txtime(&tx_socket);
+
         let rx_buffer = buffer::Buffer::new_with_mtu(max_mtu.into());
         let tx_buffer = buffer::Buffer::new_with_mtu(max_mtu.into());
         cfg_if! {
             if #[cfg(any(s2n_quic_platform_socket_msg, s2n_quic_platform_socket_mmsg))] {
-                let mut rx = socket::Queue::<buffer::Buffer>::new(rx_buffer, max_segments.into());
-                let tx = socket::Queue::<buffer::Buffer>::new(tx_buffer, max_segments.into());
+                // The kernel can coalesce up to the maximum number of segments into a single
+                // payload so make sure there is enough room for all of them.
+                let rx_segments = if gro_enabled {
+                    crate::features::get().gso.max_segments()
+                } else {
+                    max_segments.into()
+                };
+                let mut rx = socket::Queue::<buffer::Buffer>::new(rx_buffer, rx_segments);
+                if gro_enabled {
+                    rx.enable_gro();
+                }
+                let mut tx = socket::Queue::<buffer::Buffer>::new(tx_buffer, max_segments.into());
+                if txtime_enabled {
+                    tx.enable_txtime();
+                }
             } else {
                 // If you are using an LSP to jump into this code, it will
                 // probably take you to the wrong implementation. socket.rs does
@@ -258,6 +219,9 @@ impl Io {
                 // actually in socket/std.rs, not socket/mmsg.rs
                 let mut rx = socket::Queue::new(rx_buffer);
                 let tx = socket::Queue::new(tx_buffer);
+
+                // mark the variable as "used" regardless of platform support
+                let _ = txtime_enabled;
             }
         }
 
@@ -267,12 +231,28 @@ impl Io {
             addr.into()
         });
 
+        #[cfg(feature = "pcap")]
+        let endpoint = match pcap {
+            Some(path) => {
+                let capture = super::pcap::Capture::new(endpoint, path)?;
+                match pcap_key_log {
+                    Some(key_log) => capture.with_key_log(key_log),
+                    None => capture,
+                }
+            }
+            None => super::pcap::Capture::disabled(endpoint),
+        };
+
+        // sockets for other local ports are bound when a connection migrates to one
+        let migration = migration::Sockets::new(rx_addr, max_mtu);
+
         let instance = Instance {
             clock,
             rx_socket: rx_socket.into(),
             tx_socket: tx_socket.into(),
             rx,
             tx,
+            migration,
             endpoint,
         };
 
@@ -295,58 +275,6 @@ impl Io {
     }
 }
 
-fn bind<A: std::net::ToSocketAddrs>(addr: A, reuse_port: bool) -> io::Result<socket2::Socket> {
-    use socket2::{Domain, Protocol, Socket, Type};
-
-    let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
-        std::io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "the provided bind address was empty",
-        )
-    })?;
-
-    let domain = Domain::for_address(addr);
-    let socket_type = Type::DGRAM;
-    let protocol = Some(Protocol::UDP);
-
-    cfg_if! {
-        // Set non-blocking mode in a single syscall if supported
-        if #[cfg(any(
-            target_os = "android",
-            target_os = "dragonfly",
-            target_os = "freebsd",
-            target_os = "fuchsia",
-            target_os = "illumos",
-            target_os = "linux",
-            target_os = "netbsd",
-            target_os = "openbsd"
-        ))] {
-            let socket_type = socket_type.nonblocking();
-            let socket = Socket::new(domain, socket_type, protocol)?;
-        } else {
-            let socket = Socket::new(domain, socket_type, protocol)?;
-            socket.set_nonblocking(true)?;
-        }
-    };
-
-    // allow ipv4 to also connect
-    if addr.is_ipv6() {
-        socket.set_only_v6(false)?;
-    }
-
-    socket.set_reuse_address(true)?;
-
-    #[cfg(unix)]
-    socket.set_reuse_port(reuse_port)?;
-
-    // mark the variable as "used" regardless of platform support
-    let _ = reuse_port;
-
-    socket.bind(&addr.into())?;
-
-    Ok(socket)
-}
-
 #[derive(Debug, Default)]
 pub struct Builder {
     handle: Option<Handle>,
@@ -358,7 +286,14 @@ pub struct Builder {
     send_buffer_size: Option<usize>,
     max_mtu: MaxMtu,
     max_segments: gso::MaxSegments,
+    gro_disabled: bool,
+    pacing_offload: bool,
     reuse_port: bool,
+    shard_count: Option<u8>,
+    #[cfg(feature = "pcap")]
+    pcap: Option<std::path::PathBuf>,
+    #[cfg(feature = "pcap")]
+    pcap_key_log: Option<std::path::PathBuf>,
 }
 
 impl Builder {
@@ -443,6 +378,29 @@ impl Builder {
         Ok(self)
     }
 
+    /// Disables Generic Receive Offload (GRO)
+    ///
+    /// By default, GRO will be used if the platform supports it, which allows the kernel to
+    /// coalesce multiple datagrams from the same peer into a single receive operation. Each
+    /// receive buffer is sized to hold the maximum number of coalesced segments, so fewer
+    /// messages can be received in a single syscall when the traffic can't be coalesced.
+    pub fn with_gro_disabled(mut self) -> io::Result<Self> {
+        self.gro_disabled = true;
+        Ok(self)
+    }
+
+    /// Offloads packet pacing to the kernel with the `SO_TXTIME` socket option
+    ///
+    /// Each packet carries the time it should depart, which allows the endpoint to send several
+    /// paced packets in a single wakeup. The kernel only honors the departure time if the
+    /// interface uses a qdisc which supports it, such as `fq`; otherwise, the packets are
+    /// transmitted immediately. If the platform or kernel doesn't support `SO_TXTIME`, the
+    /// endpoint falls back to pacing the packets itself.
+    pub fn with_pacing_offload(mut self) -> io::Result<Self> {
+        self.pacing_offload = true;
+        Ok(self)
+    }
+
     /// Enables the port reuse (SO_REUSEPORT) socket option
     pub fn with_reuse_port(mut self) -> io::Result<Self> {
         if !cfg!(unix) {
@@ -455,6 +413,51 @@ impl Builder {
         Ok(self)
     }
 
+    /// Enables port reuse and steers received datagrams between the sockets in the group by
+    /// connection ID
+    ///
+    /// The first byte of each destination connection ID, modulo `shard_count`, selects the
+    /// socket in the `SO_REUSEPORT` group. The endpoint's connection ID format must encode the
+    /// index of the socket in the group, which is the order the sockets were bound in.
+    pub fn with_reuse_port_steering(mut self, shard_count: u8) -> io::Result<Self> {
+        if !cfg!(target_os = "linux") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "reuse_port steering is not supported on the current platform",
+            ));
+        }
+        if shard_count == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "shard_count must be at least 1",
+            ));
+        }
+        self.reuse_port = true;
+        self.shard_count = Some(shard_count);
+        Ok(self)
+    }
+
+    /// Writes every datagram the endpoint sends and receives to a pcapng file at `path`
+    ///
+    /// See [`pcap`](super::pcap) for details on the contents of the capture.
+    #[cfg(feature = "pcap")]
+    pub fn with_pcap<P: Into<std::path::PathBuf>>(mut self, path: P) -> io::Result<Self> {
+        self.pcap = Some(path.into());
+        Ok(self)
+    }
+
+    /// Copies the TLS secrets which are appended to the key log at `path` into the capture
+    /// configured with `with_pcap`, so the packets can be decrypted
+    ///
+    /// This is usually the path in `SSLKEYLOGFILE`. Every secret written to the key log while
+    /// capturing is copied, including the secrets of other endpoints which log to the same
+    /// file, so this should only be enabled while debugging.
+    #[cfg(feature = "pcap")]
+    pub fn with_pcap_key_log<P: Into<std::path::PathBuf>>(mut self, path: P) -> io::Result<Self> {
+        self.pcap_key_log = Some(path.into());
+        Ok(self)
+    }
+
     pub fn build(self) -> io::Result<Io> {
         Ok(Io { builder: self })
     }
@@ -467,6 +470,7 @@ struct Instance<E> {
     tx_socket: std::net::UdpSocket,
     rx: socket::Queue<buffer::Buffer>,
     tx: socket::Queue<buffer::Buffer>,
+    migration: migration::Sockets,
     endpoint: E,
 }
 
@@ -478,41 +482,38 @@ impl<E: Endpoint<PathHandle = PathHandle>> Instance<E> {
             tx_socket,
             mut rx,
             mut tx,
+            mut migration,
             mut endpoint,
         } = self;
 
-        cfg_if! {
-            if #[cfg(any(s2n_quic_platform_socket_msg, s2n_quic_platform_socket_mmsg))] {
-                let rx_socket = tokio::io::unix::AsyncFd::new(rx_socket)?;
-                let tx_socket = tokio::io::unix::AsyncFd::new(tx_socket)?;
-            } else {
-                let rx_socket = async_fd_shim::AsyncFd::new(rx_socket)?;
-                let tx_socket = async_fd_shim::AsyncFd::new(tx_socket)?;
-            }
-        }
+        let rx_socket = AsyncFd::new(rx_socket)?;
+        let tx_socket = AsyncFd::new(tx_socket)?;
 
         let mut timer = clock.timer();
 
         loop {
-            // Poll for readability if we have free slots available
+            // Poll for readability if we have free slots available. The task returns the index of
+            // the migration socket which is ready, or `None` for the endpoint's socket.
             let rx_interest = rx.free_len() > 0;
-            let rx_task = async {
+            let rx_task = futures::future::poll_fn(|cx| {
                 if rx_interest {
-                    rx_socket.readable().await
-                } else {
-                    futures::future::pending().await
+                    if let Poll::Ready(res) = rx_socket.poll_read_ready(cx) {
+                        return Poll::Ready(res.map(|_| None));
+                    }
                 }
-            };
+                migration.poll_rx_ready(cx).map_ok(Some)
+            });
 
             // Poll for writablity if we have occupied slots available
             let tx_interest = tx.occupied_len() > 0;
-            let tx_task = async {
+            let tx_task = futures::future::poll_fn(|cx| {
                 if tx_interest {
-                    tx_socket.writable().await
-                } else {
-                    futures::future::pending().await
+                    if let Poll::Ready(res) = tx_socket.poll_write_ready(cx) {
+                        return Poll::Ready(res.map(|_| None));
+                    }
                 }
-            };
+                migration.poll_tx_ready(cx).map_ok(Some)
+            });
 
             let wakeups = endpoint.wakeups(&clock);
             // pin the wakeups future so we don't have to move it into the Select future.
@@ -548,20 +549,33 @@ impl<E: Endpoint<PathHandle = PathHandle>> Instance<E> {
                 application_wakeup,
             });
 
-            if let Some(guard) = tx_result {
-                if let Ok(result) = guard?.try_io(|socket| tx.tx(socket, &mut publisher)) {
+            if let Some(socket) = tx_result {
+                if let Some(index) = socket? {
+                    migration.tx(index, &mut publisher).await?;
+                } else if let Ok(result) = tx_socket
+                    .writable()
+                    .await?
+                    .try_io(|socket| tx.tx(socket, &mut publisher))
+                {
                     result?;
                 }
             }
 
-            if let Some(guard) = rx_result {
-                if let Ok(result) = guard?.try_io(|socket| rx.rx(socket, &mut publisher)) {
+            if let Some(socket) = rx_result {
+                if let Some(index) = socket? {
+                    migration.rx(index, &mut publisher).await?;
+                } else if let Ok(result) = rx_socket
+                    .readable()
+                    .await?
+                    .try_io(|socket| rx.rx(socket, &mut publisher))
+                {
                     result?;
                 }
                 endpoint.receive(&mut rx.rx_queue(), &clock);
+                migration.receive(&mut endpoint, &clock);
             }
 
-            endpoint.transmit(&mut tx.tx_queue(), &clock);
+            endpoint.transmit(&mut migration.tx_queue(tx.tx_queue()), &clock);
 
             let timeout = endpoint.timeout();
 
@@ -599,6 +613,7 @@ mod async_fd_shim {
     #![allow(dead_code)]
 
     use super::*;
+    use core::task::Context;
 
     pub struct AsyncFd(tokio::net::UdpSocket);
 
@@ -617,6 +632,14 @@ mod async_fd_shim {
             self.0.writable().await?;
             Ok(TryIo(&self.0))
         }
+
+        pub fn poll_read_ready(&self, cx: &mut Context) -> Poll<io::Result<TryIo<'_>>> {
+            self.0.poll_recv_ready(cx).map_ok(|()| TryIo(&self.0))
+        }
+
+        pub fn poll_write_ready(&self, cx: &mut Context) -> Poll<io::Result<TryIo<'_>>> {
+            self.0.poll_send_ready(cx).map_ok(|()| TryIo(&self.0))
+        }
     }
 
     pub struct TryIo<'a>(&'a tokio::net::UdpSocket);
@@ -650,13 +673,15 @@ mod tests {
             rx::{self, Entry as _},
             tx,
         },
-        path::Handle as _,
+        path::{Handle as _, LocalAddress},
         time::{Clock, Duration, Timestamp},
     };
     use std::collections::BTreeMap;
 
     struct TestEndpoint {
         addr: SocketAddress,
+        /// The local address messages are sent from, if not the endpoint's address
+        local_address: Option<LocalAddress>,
         messages: BTreeMap<u32, Option<Timestamp>>,
         now: Option<Timestamp>,
         subscriber: NoopSubscriber,
@@ -667,6 +692,7 @@ mod tests {
             let messages = (0..1000).map(|id| (id, None)).collect();
             Self {
                 addr,
+                local_address: None,
                 messages,
                 now: None,
                 subscriber: Default::default(),
@@ -711,7 +737,10 @@ mod tests {
                     }
                     _ => {
                         let payload = id.to_be_bytes();
-                        let addr = PathHandle::from_remote_address(self.addr.into());
+                        let mut addr = PathHandle::from_remote_address(self.addr.into());
+                        if let Some(local_address) = self.local_address {
+                            addr = addr.with_local_address(local_address).unwrap();
+                        }
                         let msg = (addr, payload);
                         if queue.push(msg).is_ok() {
                             *tx_time = Some(now);
@@ -735,13 +764,20 @@ mod tests {
             let entries = queue.as_slice_mut();
             let len = entries.len();
             for entry in entries {
-                if let Some((_header, payload)) = entry.read(&local_address) {
+                // datagrams may be coalesced with GRO
+                entry.read_segments(&local_address, |header, payload| {
                     assert_eq!(payload.len(), 4, "invalid payload {:?}", payload);
 
+                    if let Some(local_address) = self.local_address {
+                        let port = local_address.port();
+                        assert_eq!(header.path.local_address().port(), port);
+                        assert_eq!(header.path.remote_address().port(), port);
+                    }
+
                     let id = (&*payload).try_into().unwrap();
                     let id = u32::from_be_bytes(id);
                     self.messages.remove(&id);
-                }
+                });
             }
             queue.finish(len);
         }
@@ -778,14 +814,14 @@ mod tests {
         receive_addr: A,
         send_addr: Option<A>,
     ) -> io::Result<()> {
-        let rx_socket = bind(receive_addr, false)?;
+        let rx_socket = syscall::bind_udp(receive_addr, false)?;
         let rx_socket: std::net::UdpSocket = rx_socket.into();
         let addr = rx_socket.local_addr()?;
 
         let mut io_builder = Io::builder().with_rx_socket(rx_socket)?;
 
         if let Some(addr) = send_addr {
-            let tx_socket = bind(addr, false)?;
+            let tx_socket = syscall::bind_udp(addr, false)?;
             let tx_socket: std::net::UdpSocket = tx_socket.into();
             io_builder = io_builder.with_tx_socket(tx_socket)?
         }
@@ -815,6 +851,34 @@ mod tests {
         test("127.0.0.1:0", Some("127.0.0.1:0")).await
     }
 
+    /// Sends from a port the endpoint isn't bound to, which binds a migration socket
+    ///
+    /// The messages are sent to the migration socket itself, so they are both transmitted and
+    /// received on it.
+    #[cfg(s2n_quic_platform_pktinfo)]
+    #[tokio::test]
+    async fn ipv4_migration_test() -> io::Result<()> {
+        // find a port which is free for the migration socket
+        let port = std::net::UdpSocket::bind("127.0.0.1:0")?
+            .local_addr()?
+            .port();
+        let migration_addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+
+        let io = Io::builder()
+            .with_receive_address("127.0.0.1:0".parse().unwrap())?
+            .build()?;
+
+        let mut endpoint = TestEndpoint::new(migration_addr.into());
+        endpoint.local_address = Some(SocketAddress::from(migration_addr).into());
+
+        let (task, local_addr) = io.start(endpoint)?;
+        assert_ne!(local_addr.port(), port);
+
+        task.await?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn ipv6_test() -> io::Result<()> {
         match test(("::1", 0), None).await {
@@ -0,0 +1,223 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sockets bound for connections migrating to another local port
+//!
+//! Packets are sent from the port their socket is bound to, so the endpoint's socket is unable to
+//! send from any other port. The first time a connection transmits from another port, a socket is
+//! bound to that port with [`Sockets::bind`]. The socket is then polled alongside the endpoint's
+//! socket and stays bound until the endpoint is closed.
+
+use super::AsyncFd;
+use crate::{buffer::default as buffer, io::migration, socket::default as socket, syscall};
+use cfg_if::cfg_if;
+use core::{
+    fmt,
+    task::{Context, Poll},
+};
+use s2n_quic_core::{endpoint::Endpoint, event, inet, io::tx, path::MaxMtu, time::Clock};
+use std::io;
+
+/// The maximum number of ports that are bound for migrations
+const MAX_SOCKETS: usize = 4;
+
+/// The number of messages in each queue of a migration socket
+const MESSAGE_COUNT: usize = 64;
+
+pub struct Sockets {
+    /// The address the endpoint's socket is bound to
+    local_address: std::net::SocketAddr,
+    max_mtu: MaxMtu,
+    sockets: Vec<AsyncFd>,
+    queues: Vec<Queues>,
+    /// Ports which failed to bind, so binding isn't retried on every transmission
+    failed: Vec<u16>,
+}
+
+struct Queues {
+    port: u16,
+    rx: socket::Queue<buffer::Buffer>,
+    tx: socket::Queue<buffer::Buffer>,
+}
+
+impl fmt::Debug for Sockets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sockets")
+            .field("local_address", &self.local_address)
+            .field(
+                "ports",
+                &self.queues.iter().map(|q| q.port).collect::<Vec<_>>(),
+            )
+            .field("failed", &self.failed)
+            .finish()
+    }
+}
+
+impl Sockets {
+    pub fn new(local_address: std::net::SocketAddr, max_mtu: MaxMtu) -> Self {
+        Self {
+            local_address,
+            max_mtu,
+            sockets: vec![],
+            queues: vec![],
+            failed: vec![],
+        }
+    }
+
+    /// Returns a transmission queue which sends messages for other local ports from their socket
+    pub fn tx_queue<Q>(&mut self, queue: Q) -> migration::Queue<'_, Q, Self> {
+        let port = self.local_address.port();
+        migration::Queue::new(queue, port, self)
+    }
+
+    /// Binds a socket to `port` on the endpoint's local IP address
+    ///
+    /// The local IP address of each packet is still selected with `IP_PKTINFO`, so only the port
+    /// needs to be bound. Returns the index of the socket.
+    pub fn bind(&mut self, port: u16) -> io::Result<usize> {
+        if self.queues.len() >= MAX_SOCKETS {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "the maximum number of migration sockets are bound",
+            ));
+        }
+
+        let mut addr = self.local_address;
+        addr.set_port(port);
+
+        let socket = syscall::bind_udp(addr, false)?;
+        syscall::configure_mtu_disc(&socket, &addr)?;
+        syscall::configure_tos(&socket, &addr)?;
+        syscall::configure_pktinfo(&socket, &addr)?;
+        let socket = AsyncFd::new(socket.into())?;
+
+        let rx_buffer = buffer::Buffer::new(MESSAGE_COUNT, self.max_mtu.into());
+        let tx_buffer = buffer::Buffer::new(MESSAGE_COUNT, self.max_mtu.into());
+        cfg_if! {
+            if #[cfg(any(s2n_quic_platform_socket_msg, s2n_quic_platform_socket_mmsg))] {
+                // migration sockets carry little traffic, so GSO and GRO aren't enabled
+                let mut rx = socket::Queue::new(rx_buffer, 1);
+                let tx = socket::Queue::new(tx_buffer, 1);
+            } else {
+                let mut rx = socket::Queue::new(rx_buffer);
+                let tx = socket::Queue::new(tx_buffer);
+            }
+        }
+
+        rx.set_local_address({
+            let addr: inet::SocketAddress = addr.into();
+            addr.into()
+        });
+
+        self.sockets.push(socket);
+        self.queues.push(Queues { port, rx, tx });
+
+        Ok(self.queues.len() - 1)
+    }
+
+    /// Returns the index of the socket bound to `port`, binding one if needed
+    fn index(&mut self, port: u16) -> Option<usize> {
+        if let Some(index) = self.queues.iter().position(|q| q.port == port) {
+            return Some(index);
+        }
+
+        if self.failed.contains(&port) {
+            return None;
+        }
+
+        match self.bind(port) {
+            Ok(index) => Some(index),
+            Err(_) => {
+                self.failed.push(port);
+                None
+            }
+        }
+    }
+
+    /// Polls for a socket with room to receive that is readable
+    pub fn poll_rx_ready(&self, cx: &mut Context) -> Poll<io::Result<usize>> {
+        for (index, socket) in self.sockets.iter().enumerate() {
+            if self.queues[index].rx.free_len() == 0 {
+                continue;
+            }
+
+            if let Poll::Ready(res) = socket.poll_read_ready(cx) {
+                return Poll::Ready(res.map(|_| index));
+            }
+        }
+
+        Poll::Pending
+    }
+
+    /// Polls for a socket with pending transmissions that is writable
+    pub fn poll_tx_ready(&self, cx: &mut Context) -> Poll<io::Result<usize>> {
+        for (index, socket) in self.sockets.iter().enumerate() {
+            if self.queues[index].tx.occupied_len() == 0 {
+                continue;
+            }
+
+            if let Poll::Ready(res) = socket.poll_write_ready(cx) {
+                return Poll::Ready(res.map(|_| index));
+            }
+        }
+
+        Poll::Pending
+    }
+
+    pub async fn rx<P: event::EndpointPublisher>(
+        &mut self,
+        index: usize,
+        publisher: &mut P,
+    ) -> io::Result<()> {
+        let rx = &mut self.queues[index].rx;
+        if let Ok(result) = self.sockets[index]
+            .readable()
+            .await?
+            .try_io(|socket| rx.rx(socket, publisher))
+        {
+            result?;
+        }
+        Ok(())
+    }
+
+    pub async fn tx<P: event::EndpointPublisher>(
+        &mut self,
+        index: usize,
+        publisher: &mut P,
+    ) -> io::Result<()> {
+        let tx = &mut self.queues[index].tx;
+        if let Ok(result) = self.sockets[index]
+            .writable()
+            .await?
+            .try_io(|socket| tx.tx(socket, publisher))
+        {
+            result?;
+        }
+        Ok(())
+    }
+
+    /// Passes the datagrams received on each socket to the endpoint
+    pub fn receive<E: Endpoint<PathHandle = socket::Handle>, C: Clock>(
+        &mut self,
+        endpoint: &mut E,
+        clock: &C,
+    ) {
+        for queues in &mut self.queues {
+            if queues.rx.occupied_len() > 0 {
+                endpoint.receive(&mut queues.rx.rx_queue(), clock);
+            }
+        }
+    }
+}
+
+impl migration::Bind<socket::Handle> for Sockets {
+    #[inline]
+    fn push<M: tx::Message<Handle = socket::Handle>>(
+        &mut self,
+        port: u16,
+        message: M,
+    ) -> Result<tx::Outcome, tx::Error> {
+        let index = self.index(port).ok_or(tx::Error::AtCapacity)?;
+        self.queues[index].tx.tx_queue().push(message)
+    }
+}
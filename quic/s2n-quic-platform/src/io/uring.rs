@@ -0,0 +1,1383 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An IO provider which drives the endpoint with [io_uring](https://man7.org/linux/man-pages/man7/io_uring.7.html)
+//!
+//! Rather than waiting for socket readiness and issuing a syscall per batch of messages, receive
+//! and transmit operations are pushed to the submission queue for every available slot in the
+//! [`message::queue`](crate::message::queue) and the kernel completes them asynchronously. The
+//! endpoint runs on a dedicated thread, which blocks in `io_uring_enter` until either a completion
+//! is available, the endpoint timer expires, or the application wakes up the endpoint.
+//!
+//! On kernels which support it (6.0+), the receive buffers are registered with the kernel as a
+//! provided buffer ring and a single multishot `recvmsg` fills them, instead of submitting an
+//! operation for every free slot. Each completed datagram is copied into a free slot of the
+//! receive queue and the buffer is handed back to the kernel.
+//!
+//! The event loop waits for completions with a timeout, which requires Linux 5.11 or later.
+//! Building the provider on an older kernel returns an [`ErrorKind::Unsupported`] error, in which
+//! case another IO provider should be used.
+
+use super::{eventfd::EventFd, migration};
+use crate::{
+    buffer::default as buffer,
+    features::gso,
+    message::{cmsg, msg, queue, Message as _},
+    syscall,
+};
+use core::{
+    mem::size_of,
+    ptr::NonNull,
+    sync::atomic::{AtomicU16, Ordering},
+};
+use io_uring::{cqueue, opcode, squeue, types, IoUring, Submitter};
+use s2n_quic_core::{
+    endpoint::Endpoint,
+    event::{self, EndpointPublisher as _},
+    inet::{self, SocketAddress},
+    path::MaxMtu,
+    time::{Clock as _, StdClock},
+};
+use std::{
+    alloc::{self, Layout},
+    collections::VecDeque,
+    convert::TryInto,
+    io,
+    io::ErrorKind,
+    os::unix::io::AsRawFd,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
+pub type PathHandle = msg::Handle;
+
+type Queue = queue::Queue<msg::Ring<buffer::Buffer>>;
+
+#[derive(Debug, Default)]
+pub struct Io {
+    builder: Builder,
+}
+
+impl Io {
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    pub fn new<A: std::net::ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let address = addr.to_socket_addrs()?.next().expect("missing address");
+        let builder = Builder::default().with_receive_address(address)?;
+        builder.build()
+    }
+
+    pub fn start<E: Endpoint<PathHandle = PathHandle>>(
+        self,
+        mut endpoint: E,
+    ) -> io::Result<(std::thread::JoinHandle<()>, SocketAddress)> {
+        let Builder {
+            rx_socket,
+            tx_socket,
+            recv_addr,
+            send_addr,
+            recv_buffer_size,
+            send_buffer_size,
+            max_mtu,
+            max_segments,
+            reuse_port,
+            entries,
+        } = self.builder;
+
+        endpoint.set_max_mtu(max_mtu);
+
+        let clock = StdClock::default();
+
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: E::ENDPOINT_TYPE,
+                timestamp: clock.get_time(),
+            },
+            None,
+            endpoint.subscriber(),
+        );
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::MaxMtu {
+                mtu: max_mtu.into(),
+            },
+        });
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Gso {
+                max_segments: max_segments.into(),
+            },
+        });
+
+        let rx_socket = if let Some(rx_socket) = rx_socket {
+            rx_socket
+        } else if let Some(recv_addr) = recv_addr {
+            syscall::bind_udp(recv_addr, reuse_port)?
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "missing bind address",
+            ));
+        };
+
+        let tx_socket = if let Some(tx_socket) = tx_socket {
+            tx_socket
+        } else if let Some(send_addr) = send_addr {
+            syscall::bind_udp(send_addr, reuse_port)?
+        } else {
+            // No tx_socket or send address was specified, so the tx socket
+            // will be a handle to the rx socket.
+            rx_socket.try_clone()?
+        };
+
+        // The ring takes care of waiting for the sockets to become ready so operations are
+        // submitted in blocking mode. This avoids completing entries with `EAGAIN` on kernels
+        // that honor `O_NONBLOCK` for io_uring requests.
+        rx_socket.set_nonblocking(false)?;
+        tx_socket.set_nonblocking(false)?;
+
+        if let Some(size) = send_buffer_size {
+            tx_socket.set_send_buffer_size(size)?;
+        }
+
+        if let Some(size) = recv_buffer_size {
+            rx_socket.set_recv_buffer_size(size)?;
+        }
+
+        fn convert_addr_to_std(addr: socket2::SockAddr) -> io::Result<std::net::SocketAddr> {
+            addr.as_socket().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "invalid domain for socket")
+            })
+        }
+
+        let (tx_addr, rx_addr) = (
+            convert_addr_to_std(tx_socket.local_addr()?)?,
+            convert_addr_to_std(rx_socket.local_addr()?)?,
+        );
+
+        syscall::configure_mtu_disc(&tx_socket, &tx_addr)?;
+
+        // Set up the RX socket to pass ECN information
+        let ecn_enabled = syscall::configure_tos(&rx_socket, &rx_addr)?;
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Ecn {
+                enabled: ecn_enabled,
+            },
+        });
+
+        // Set up the RX socket to pass information about the local address and interface
+        syscall::configure_pktinfo(&rx_socket, &rx_addr)?;
+
+        // GRO is not currently used with the ring, since each receive buffer would need to be
+        // sized for the maximum number of coalesced segments.
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Gro { enabled: false },
+        });
+
+        let rx_buffer = buffer::Buffer::new_with_mtu(max_mtu.into());
+        let tx_buffer = buffer::Buffer::new_with_mtu(max_mtu.into());
+        let mut rx = Queue::new(msg::Ring::new(rx_buffer, max_segments.into()));
+        let tx = Queue::new(msg::Ring::new(tx_buffer, max_segments.into()));
+
+        // tell the queue the local address so it can fill it in on each message
+        rx.set_local_address({
+            let addr: inet::SocketAddress = rx_addr.into();
+            addr.into()
+        });
+
+        // size the ring so every slot in both queues, plus the wakeup read, can be in flight
+        let entries = entries.unwrap_or_else(|| {
+            let len = rx.capacity() + tx.capacity() + 1;
+            len.next_power_of_two().min(MAX_ENTRIES as usize) as u32
+        });
+        let ring = IoUring::new(entries)?;
+        check_features(&ring)?;
+
+        // Kernels older than 5.19 can't register a buffer ring, in which case a receive is
+        // submitted for every free slot instead
+        let buf_ring = BufRing::new(rx.capacity(), rx.mtu());
+        let buf_ring = unsafe {
+            // Safety: the buffer ring is owned by the instance and is only released after
+            // every operation that could select one of its buffers has completed
+            buf_ring.register(&ring.submitter())
+        }
+        .ok()
+        .map(|_| buf_ring);
+
+        let waker = Arc::new(EventFd::new()?);
+
+        let instance = Instance {
+            clock,
+            ring,
+            waker,
+            rx_socket: rx_socket.into(),
+            tx_socket: tx_socket.into(),
+            rx,
+            tx,
+            buf_ring,
+            endpoint,
+        };
+
+        let local_addr = rx_addr.into();
+
+        let thread = std::thread::Builder::new()
+            .name("s2n-quic-io-uring".to_string())
+            .spawn(move || {
+                if let Err(err) = instance.event_loop() {
+                    let debug = format!("A fatal IO error occurred ({:?}): {err}", err.kind());
+                    if cfg!(test) {
+                        panic!("{debug}");
+                    } else {
+                        eprintln!("{debug}");
+                    }
+                }
+            })?;
+
+        Ok((thread, local_addr))
+    }
+}
+
+/// Returns an error if the kernel doesn't support the features the event loop needs
+fn check_features(ring: &IoUring) -> io::Result<()> {
+    // waiting for completions with a timeout requires `IORING_FEAT_EXT_ARG`, which was added
+    // in Linux 5.11
+    if !ring.params().is_feature_ext_arg() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the io_uring IO provider requires Linux 5.11 or later",
+        ));
+    }
+
+    Ok(())
+}
+
+/// The smallest number of submission queue entries that leaves room for the wakeup read, a
+/// receive and a transmit to be in flight at the same time
+const MIN_ENTRIES: u32 = 2;
+
+/// The largest number of submission queue entries supported by the kernel
+const MAX_ENTRIES: u32 = 32768;
+
+#[derive(Debug, Default)]
+pub struct Builder {
+    rx_socket: Option<socket2::Socket>,
+    tx_socket: Option<socket2::Socket>,
+    recv_addr: Option<std::net::SocketAddr>,
+    send_addr: Option<std::net::SocketAddr>,
+    recv_buffer_size: Option<usize>,
+    send_buffer_size: Option<usize>,
+    max_mtu: MaxMtu,
+    max_segments: gso::MaxSegments,
+    reuse_port: bool,
+    entries: Option<u32>,
+}
+
+impl Builder {
+    /// Sets the local address for the runtime to listen on. If no send address
+    /// or tx socket is specified, this address will also be used for transmitting from.
+    ///
+    /// NOTE: this method is mutually exclusive with `with_rx_socket`
+    pub fn with_receive_address(mut self, addr: std::net::SocketAddr) -> io::Result<Self> {
+        debug_assert!(self.rx_socket.is_none(), "rx socket has already been set");
+        self.recv_addr = Some(addr);
+        Ok(self)
+    }
+
+    /// Sets the local address for the runtime to transmit from. If no send address
+    /// or tx socket is specified, the receive_address will be used for transmitting.
+    ///
+    /// NOTE: this method is mutually exclusive with `with_tx_socket`
+    pub fn with_send_address(mut self, addr: std::net::SocketAddr) -> io::Result<Self> {
+        debug_assert!(self.tx_socket.is_none(), "tx socket has already been set");
+        self.send_addr = Some(addr);
+        Ok(self)
+    }
+
+    /// Sets the socket used for receiving for the runtime. If no tx_socket or send address is
+    /// specified, this socket will be used for transmitting.
+    ///
+    /// NOTE: this method is mutually exclusive with `with_receive_address`
+    pub fn with_rx_socket(mut self, socket: std::net::UdpSocket) -> io::Result<Self> {
+        debug_assert!(
+            self.recv_addr.is_none(),
+            "recv address has already been set"
+        );
+        self.rx_socket = Some(socket.into());
+        Ok(self)
+    }
+
+    /// Sets the socket used for transmitting on for the runtime. If no tx_socket or send address is
+    /// specified, the rx_socket will be used for transmitting.
+    ///
+    /// NOTE: this method is mutually exclusive with `with_send_address`
+    pub fn with_tx_socket(mut self, socket: std::net::UdpSocket) -> io::Result<Self> {
+        debug_assert!(
+            self.send_addr.is_none(),
+            "send address has already been set"
+        );
+        self.tx_socket = Some(socket.into());
+        Ok(self)
+    }
+
+    /// Sets the size of the operating system’s send buffer associated with the tx socket
+    pub fn with_send_buffer_size(mut self, send_buffer_size: usize) -> io::Result<Self> {
+        self.send_buffer_size = Some(send_buffer_size);
+        Ok(self)
+    }
+
+    /// Sets the size of the operating system’s receive buffer associated with the rx socket
+    pub fn with_recv_buffer_size(mut self, recv_buffer_size: usize) -> io::Result<Self> {
+        self.recv_buffer_size = Some(recv_buffer_size);
+        Ok(self)
+    }
+
+    /// Sets the largest maximum transmission unit (MTU) that can be sent on a path
+    pub fn with_max_mtu(mut self, max_mtu: u16) -> io::Result<Self> {
+        self.max_mtu = max_mtu
+            .try_into()
+            .map_err(|err| io::Error::new(ErrorKind::InvalidInput, format!("{err}")))?;
+        Ok(self)
+    }
+
+    /// Disables Generic Segmentation Offload (GSO)
+    ///
+    /// By default, GSO will be used unless the platform does not support it or an attempt to use
+    /// GSO fails. If it is known that GSO is not available, set this option to explicitly disable it.
+    pub fn with_gso_disabled(mut self) -> io::Result<Self> {
+        self.max_segments = 1.try_into().expect("1 is always a valid MaxSegments value");
+        Ok(self)
+    }
+
+    /// Enables the port reuse (SO_REUSEPORT) socket option
+    pub fn with_reuse_port(mut self) -> io::Result<Self> {
+        self.reuse_port = true;
+        Ok(self)
+    }
+
+    /// Sets the number of submission queue entries for the ring
+    ///
+    /// By default, the ring is sized to allow every receive and transmit slot to be in flight
+    /// at the same time. Smaller rings will submit the remaining slots as entries complete,
+    /// with at most half of them used for receives so transmissions can always make progress.
+    pub fn with_entries(mut self, entries: u32) -> io::Result<Self> {
+        if !(MIN_ENTRIES..=MAX_ENTRIES).contains(&entries) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("entries must be between {MIN_ENTRIES} and {MAX_ENTRIES}"),
+            ));
+        }
+        self.entries = Some(entries);
+        Ok(self)
+    }
+
+    /// Builds the provider
+    ///
+    /// Returns an [`ErrorKind::Unsupported`] error if io_uring isn't available or the kernel is
+    /// older than Linux 5.11.
+    pub fn build(self) -> io::Result<Io> {
+        // probe with a small ring so unsupported kernels are reported before the endpoint starts
+        let ring = IoUring::new(MIN_ENTRIES).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("io_uring is not available: {err}"),
+            )
+        })?;
+        check_features(&ring)?;
+
+        Ok(Io { builder: self })
+    }
+}
+
+/// The kind of operation associated with a completion
+///
+/// The kind is stored in the upper bits of the entry's `user_data` and the
+/// sequence number of the slot in the lower bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Rx = 0,
+    Tx = 1,
+    Wakeup = 2,
+}
+
+const KIND_SHIFT: u32 = 62;
+const SEQUENCE_MASK: u64 = (1 << KIND_SHIFT) - 1;
+
+impl Kind {
+    #[inline]
+    fn user_data(self, sequence: u64) -> u64 {
+        ((self as u64) << KIND_SHIFT) | (sequence & SEQUENCE_MASK)
+    }
+
+    #[inline]
+    fn decode(user_data: u64) -> (Self, u64) {
+        let kind = match user_data >> KIND_SHIFT {
+            0 => Self::Rx,
+            1 => Self::Tx,
+            _ => Self::Wakeup,
+        };
+        (kind, user_data & SEQUENCE_MASK)
+    }
+}
+
+/// Tracks the submitted operations for a message queue
+///
+/// Completions can arrive in any order but the queue can only be advanced in order, so the
+/// results are stored until a contiguous prefix of the slots has completed.
+#[derive(Debug, Default)]
+struct InFlight {
+    /// The sequence number of the first slot in `results`
+    base: u64,
+    /// The result of each submitted slot, if it has completed
+    results: VecDeque<Option<i32>>,
+    /// The sequence numbers of failed slots that need to be submitted again
+    retry: Vec<u64>,
+}
+
+impl InFlight {
+    #[inline]
+    fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Returns the number of slots the kernel still holds a reference to
+    fn pending_len(&self) -> usize {
+        let waiting = self.results.iter().filter(|r| r.is_none()).count();
+        waiting - self.retry.len()
+    }
+
+    /// Returns the sequence number for the next submitted slot
+    #[inline]
+    fn push(&mut self) -> u64 {
+        let sequence = self.base + self.results.len() as u64;
+        self.results.push_back(None);
+        sequence
+    }
+
+    #[inline]
+    fn complete(&mut self, sequence: u64, result: i32) {
+        let index = sequence.wrapping_sub(self.base) as usize;
+        debug_assert!(index < self.results.len(), "unknown sequence {sequence}");
+        if let Some(slot) = self.results.get_mut(index) {
+            *slot = Some(result);
+        }
+    }
+
+    /// Returns the number of contiguous slots that have completed
+    #[inline]
+    fn completed_len(&self) -> usize {
+        self.results.iter().take_while(|r| r.is_some()).count()
+    }
+
+    #[inline]
+    fn result(&self, index: usize) -> Option<i32> {
+        self.results[index]
+    }
+
+    /// Marks the slot to be submitted again
+    #[inline]
+    fn retry(&mut self, index: usize) {
+        self.results[index] = None;
+        self.retry.push(self.base + index as u64);
+    }
+
+    /// Returns the index of the slot for a sequence number
+    #[inline]
+    fn index(&self, sequence: u64) -> usize {
+        (sequence - self.base) as usize
+    }
+
+    #[inline]
+    fn finish(&mut self, count: usize) {
+        self.results.drain(..count);
+        self.base += count as u64;
+    }
+}
+
+/// The buffer group the receive buffers are registered under
+const RX_BUFFER_GROUP: u16 = 0;
+
+/// The size of the `io_uring_recvmsg_out` header the kernel writes before each datagram
+const RECVMSG_OUT_LEN: usize = 16;
+
+/// The `io_uring_buf` entries shared with the kernel in a provided buffer ring
+///
+/// The `resv` field of the first entry is used as the tail of the ring.
+#[repr(C)]
+struct BufRingEntry {
+    addr: u64,
+    len: u32,
+    bid: u16,
+    resv: u16,
+}
+
+/// Receive buffers registered with the kernel as a provided buffer ring
+///
+/// The kernel selects a buffer for each datagram received by a multishot `recvmsg` and the
+/// buffer is handed back once the datagram has been copied into the queue.
+///
+/// See https://man7.org/linux/man-pages/man3/io_uring_register_buf_ring.3.html
+struct BufRing {
+    entries: NonNull<BufRingEntry>,
+    entries_layout: Layout,
+    buffers: NonNull<u8>,
+    buffers_layout: Layout,
+    buffer_len: usize,
+    len: u16,
+    tail: u16,
+    /// Reserves space for the address and control messages in each buffer
+    msghdr: Box<libc::msghdr>,
+}
+
+// Safety: the pointers are owned by the buffer ring and are only accessed by the event loop
+unsafe impl Send for BufRing {}
+
+impl BufRing {
+    fn new(capacity: usize, mtu: usize) -> Self {
+        let len = capacity.next_power_of_two().min(MAX_ENTRIES as usize);
+
+        let name_len = size_of::<libc::sockaddr_in6>();
+        let control_len = cmsg::MAX_LEN;
+        let buffer_len = RECVMSG_OUT_LEN + name_len + control_len + mtu;
+
+        // the kernel requires the ring to be aligned to a page
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(4096) as usize;
+        let entries_layout =
+            Layout::from_size_align(len * size_of::<BufRingEntry>(), page_size).unwrap();
+        let buffers_layout = Layout::from_size_align(len * buffer_len, page_size).unwrap();
+
+        let (entries, buffers) = unsafe {
+            let entries = alloc::alloc_zeroed(entries_layout) as *mut BufRingEntry;
+            let buffers = alloc::alloc_zeroed(buffers_layout);
+            (
+                NonNull::new(entries).unwrap_or_else(|| alloc::handle_alloc_error(entries_layout)),
+                NonNull::new(buffers).unwrap_or_else(|| alloc::handle_alloc_error(buffers_layout)),
+            )
+        };
+
+        let mut msghdr: Box<libc::msghdr> = Box::new(unsafe { core::mem::zeroed() });
+        msghdr.msg_namelen = name_len as _;
+        msghdr.msg_controllen = control_len as _;
+
+        let mut ring = Self {
+            entries,
+            entries_layout,
+            buffers,
+            buffers_layout,
+            buffer_len,
+            len: len as u16,
+            tail: 0,
+            msghdr,
+        };
+
+        for id in 0..ring.len {
+            ring.push(id);
+        }
+        ring.publish();
+
+        ring
+    }
+
+    /// Registers the ring with the kernel
+    ///
+    /// # Safety
+    ///
+    /// The ring must outlive any operation that selects buffers from it
+    unsafe fn register(&self, submitter: &Submitter) -> io::Result<()> {
+        submitter.register_buf_ring(self.entries.as_ptr() as u64, self.len, RX_BUFFER_GROUP)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    #[inline]
+    fn msghdr(&self) -> *const libc::msghdr {
+        &*self.msghdr
+    }
+
+    /// Copies the datagram received into a buffer to a free message in the queue
+    ///
+    /// Returns the message to the kernel afterwards. Any part of the address, control messages or
+    /// payload which doesn't fit in the message is truncated.
+    // some platforms encode lengths as `u32` so we cast everything to be safe
+    #[allow(clippy::unnecessary_cast)]
+    fn copy_to(&mut self, id: u16, len: usize, msghdr: &mut libc::msghdr) {
+        let name_len = self.msghdr.msg_namelen as usize;
+        let control_len = self.msghdr.msg_controllen as usize;
+
+        let buffer = unsafe {
+            // Safety: the kernel handed the buffer to us and doesn't write to it until it's
+            // pushed back to the ring
+            let offset = id as usize * self.buffer_len;
+            core::slice::from_raw_parts(self.buffers.as_ptr().add(offset), self.buffer_len)
+        };
+        let buffer = &buffer[..len.min(self.buffer_len)];
+
+        let header_len = RECVMSG_OUT_LEN + name_len + control_len;
+        if buffer.len() >= header_len {
+            let read_len = |offset: usize| {
+                let bytes = buffer[offset..offset + 4].try_into().unwrap();
+                u32::from_ne_bytes(bytes) as usize
+            };
+
+            let (name, control) = buffer[RECVMSG_OUT_LEN..header_len].split_at(name_len);
+            let name = &name[..read_len(0).min(name_len)];
+            let control = &control[..read_len(4).min(control_len)];
+            let payload = &buffer[header_len..];
+
+            unsafe {
+                // Safety: the lengths are limited to the space reserved by the queue's messages
+                core::ptr::copy_nonoverlapping(
+                    name.as_ptr(),
+                    msghdr.msg_name as *mut u8,
+                    name.len(),
+                );
+                msghdr.msg_namelen = name.len() as _;
+
+                let control = &control[..control.len().min(msghdr.msg_controllen as usize)];
+                core::ptr::copy_nonoverlapping(
+                    control.as_ptr(),
+                    msghdr.msg_control as *mut u8,
+                    control.len(),
+                );
+                msghdr.msg_controllen = control.len() as _;
+
+                let payload = &payload[..payload.len().min(msghdr.payload_len())];
+                core::ptr::copy_nonoverlapping(
+                    payload.as_ptr(),
+                    msghdr.payload_ptr_mut(),
+                    payload.len(),
+                );
+                msghdr.set_payload_len(payload.len());
+            }
+        } else {
+            // the datagram is malformed so deliver an empty payload, which the endpoint discards
+            unsafe {
+                msghdr.set_payload_len(0);
+            }
+        }
+
+        self.push(id);
+        self.publish();
+    }
+
+    #[inline]
+    fn push(&mut self, id: u16) {
+        let index = (self.tail & (self.len - 1)) as usize;
+        unsafe {
+            // Safety: the index is masked to the length of the ring
+            let entry = &mut *self.entries.as_ptr().add(index);
+            entry.addr = self.buffers.as_ptr().add(id as usize * self.buffer_len) as u64;
+            entry.len = self.buffer_len as u32;
+            entry.bid = id;
+        }
+        self.tail = self.tail.wrapping_add(1);
+    }
+
+    /// Makes the pushed buffers visible to the kernel
+    #[inline]
+    fn publish(&self) {
+        unsafe {
+            // Safety: the tail overlaps the reserved field of the first entry, which the kernel
+            // reads atomically
+            let tail = core::ptr::addr_of!((*self.entries.as_ptr()).resv) as *const AtomicU16;
+            (*tail).store(self.tail, Ordering::Release);
+        }
+    }
+}
+
+impl Drop for BufRing {
+    fn drop(&mut self) {
+        unsafe {
+            alloc::dealloc(self.entries.as_ptr() as *mut u8, self.entries_layout);
+            alloc::dealloc(self.buffers.as_ptr(), self.buffers_layout);
+        }
+    }
+}
+
+struct Instance<E> {
+    clock: StdClock,
+    ring: IoUring,
+    waker: Arc<EventFd>,
+    rx_socket: std::net::UdpSocket,
+    tx_socket: std::net::UdpSocket,
+    rx: Queue,
+    tx: Queue,
+    buf_ring: Option<BufRing>,
+    endpoint: E,
+}
+
+impl<E: Endpoint<PathHandle = PathHandle>> Instance<E> {
+    fn event_loop(self) -> io::Result<()> {
+        let Self {
+            clock,
+            mut ring,
+            waker,
+            rx_socket,
+            tx_socket,
+            mut rx,
+            mut tx,
+            mut buf_ring,
+            mut endpoint,
+        } = self;
+
+        // the provider doesn't bind other ports, so migrations to them are rejected
+        let port = rx_socket.local_addr()?.port();
+        let mut unsupported = migration::Unsupported;
+
+        let rx_fd = types::Fd(rx_socket.as_raw_fd());
+        let tx_fd = types::Fd(tx_socket.as_raw_fd());
+        let wakeup_fd = types::Fd(waker.as_raw_fd());
+        // the buffer for the eventfd counter needs a stable address while the read is in flight
+        let mut wakeup_buf = Box::new(0u64);
+        let mut wakeup_in_flight = false;
+
+        let mut rx_in_flight = InFlight::default();
+        let mut tx_in_flight = InFlight::default();
+
+        // the state of the multishot receive, which is only used with the buffer ring
+        let mut multishot_in_flight = false;
+        let mut multishot_errno = None;
+        // datagrams in the buffer ring which are waiting for a free slot in the queue
+        let mut rx_pending = VecDeque::new();
+
+        let task_waker = Waker::from(waker.clone());
+        let mut cx = Context::from_waker(&task_waker);
+
+        let (submitter, mut sq, mut cq) = ring.split();
+        let max_in_flight = cq.capacity();
+        // Receives only complete once a peer sends something, which may be waiting on one of our
+        // transmissions. Limit them to half of the ring so sends can always be submitted.
+        let max_rx_in_flight = ((max_in_flight - 1) / 2).max(1);
+
+        let mut timeout_expired = false;
+        let mut wakeup_timestamp = clock.get_time();
+
+        let result = loop {
+            let mut rx_ready = false;
+            let mut tx_ready = false;
+            let mut application_wakeup = false;
+
+            cq.sync();
+            for cqe in &mut cq {
+                let (kind, sequence) = Kind::decode(cqe.user_data());
+                match kind {
+                    Kind::Rx if buf_ring.is_some() => {
+                        rx_ready = true;
+                        if !cqueue::more(cqe.flags()) {
+                            multishot_in_flight = false;
+                        }
+                        match cqueue::buffer_select(cqe.flags()) {
+                            Some(id) => rx_pending.push_back((id, cqe.result() as usize)),
+                            None if cqe.result() < 0 => multishot_errno = Some(-cqe.result()),
+                            None => {}
+                        }
+                    }
+                    Kind::Rx => {
+                        rx_ready = true;
+                        rx_in_flight.complete(sequence, cqe.result());
+                    }
+                    Kind::Tx => {
+                        tx_ready = true;
+                        tx_in_flight.complete(sequence, cqe.result());
+                    }
+                    Kind::Wakeup => {
+                        wakeup_in_flight = false;
+                    }
+                }
+            }
+
+            match endpoint.poll_wakeups(&mut cx, &clock) {
+                Poll::Ready(Ok(_)) => application_wakeup = true,
+                // The endpoint has shut down
+                Poll::Ready(Err(_)) => break Ok(()),
+                Poll::Pending => {}
+            }
+
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp: wakeup_timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            publisher.on_platform_event_loop_wakeup(event::builder::PlatformEventLoopWakeup {
+                timeout_expired,
+                rx_ready,
+                tx_ready,
+                application_wakeup,
+            });
+
+            if let Some(errno) = multishot_errno.take() {
+                match errno {
+                    // The kernel supports buffer rings but not multishot receives, which were
+                    // added in 6.0, so fall back to submitting a receive for every free slot
+                    libc::EINVAL | libc::EOPNOTSUPP if rx_pending.is_empty() => {
+                        let _ = submitter.unregister_buf_ring(RX_BUFFER_GROUP);
+                        buf_ring = None;
+                    }
+                    // the receive is submitted again once a buffer is handed back to the kernel
+                    libc::ENOBUFS | libc::EAGAIN | libc::EINTR => {}
+                    errno => {
+                        publisher.on_platform_rx_error(event::builder::PlatformRxError { errno });
+                    }
+                }
+            }
+
+            if tx_ready {
+                complete_tx(&mut tx, &mut tx_in_flight, &mut publisher);
+            }
+
+            if rx_ready || !rx_pending.is_empty() {
+                loop {
+                    let count = if let Some(buf_ring) = buf_ring.as_mut() {
+                        complete_rx_multishot(&mut rx, buf_ring, &mut rx_pending, &mut publisher)
+                    } else {
+                        complete_rx(&mut rx, &mut rx_in_flight, &mut publisher)
+                    };
+
+                    if count == 0 {
+                        break;
+                    }
+
+                    endpoint.receive(&mut rx.occupied_mut(), &clock);
+
+                    // keep going while datagrams are waiting for the slots the endpoint released
+                    if rx_pending.is_empty() {
+                        break;
+                    }
+                }
+            }
+
+            let mut queue = migration::Queue::new(tx.free_mut(), port, &mut unsupported);
+            endpoint.transmit(&mut queue, &clock);
+
+            // The wakeup read is submitted first so the application can always reach the endpoint
+            if !wakeup_in_flight && !sq.is_full() {
+                let entry = opcode::Read::new(
+                    wakeup_fd,
+                    &mut *wakeup_buf as *mut u64 as *mut u8,
+                    core::mem::size_of::<u64>() as _,
+                )
+                .build()
+                .user_data(Kind::Wakeup.user_data(0));
+
+                unsafe {
+                    // Safety: the buffer is owned by the event loop, which outlives the ring
+                    push(&mut sq, &entry);
+                }
+                wakeup_in_flight = true;
+            }
+
+            // Submit a send for every occupied slot that isn't already in flight
+            {
+                let mut entries = tx.occupied_mut();
+                for index in tx_in_flight.len()..entries.len() {
+                    if sq.is_full() || in_flight(&rx_in_flight, &tx_in_flight) >= max_in_flight {
+                        break;
+                    }
+
+                    let sequence = tx_in_flight.push();
+                    let entry = opcode::SendMsg::new(tx_fd, entries[index].as_mut_ptr() as _)
+                        .build()
+                        .user_data(Kind::Tx.user_data(sequence));
+
+                    unsafe {
+                        // Safety: the message is owned by the queue, which outlives the ring,
+                        // and isn't returned to the endpoint until the operation completes
+                        push(&mut sq, &entry);
+                    }
+                }
+            }
+
+            if let Some(buf_ring) = buf_ring.as_ref() {
+                // Rearm the multishot receive once the kernel has a buffer to select
+                if !multishot_in_flight && rx_pending.len() < buf_ring.len() && !sq.is_full() {
+                    let entry =
+                        opcode::RecvMsgMulti::new(rx_fd, buf_ring.msghdr(), RX_BUFFER_GROUP)
+                            .build()
+                            .user_data(Kind::Rx.user_data(0));
+
+                    unsafe {
+                        // Safety: the header and the buffers are owned by the buffer ring, which
+                        // outlives the ring
+                        push(&mut sq, &entry);
+                    }
+                    multishot_in_flight = true;
+                }
+            } else {
+                // Submit a receive for every free slot that isn't already in flight
+                let mut entries = rx.free_mut();
+
+                // failed slots are already accounted for so they only need space in the queue
+                while !sq.is_full() {
+                    let sequence = if let Some(sequence) = rx_in_flight.retry.pop() {
+                        sequence
+                    } else {
+                        break;
+                    };
+
+                    let index = rx_in_flight.index(sequence);
+                    let entry = opcode::RecvMsg::new(rx_fd, entries[index].as_mut_ptr() as _)
+                        .build()
+                        .user_data(Kind::Rx.user_data(sequence));
+
+                    unsafe {
+                        // Safety: the message is owned by the queue, which outlives the ring,
+                        // and isn't handed to the endpoint until the operation completes
+                        push(&mut sq, &entry);
+                    }
+                }
+
+                for index in rx_in_flight.len()..entries.len() {
+                    if sq.is_full()
+                        || rx_in_flight.len() >= max_rx_in_flight
+                        || in_flight(&rx_in_flight, &tx_in_flight) >= max_in_flight
+                    {
+                        break;
+                    }
+
+                    let sequence = rx_in_flight.push();
+                    let entry = opcode::RecvMsg::new(rx_fd, entries[index].as_mut_ptr() as _)
+                        .build()
+                        .user_data(Kind::Rx.user_data(sequence));
+
+                    unsafe {
+                        // Safety: the message is owned by the queue, which outlives the ring,
+                        // and isn't handed to the endpoint until the operation completes
+                        push(&mut sq, &entry);
+                    }
+                }
+            }
+
+            sq.sync();
+
+            let timeout = endpoint.timeout();
+
+            let timestamp = clock.get_time();
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            // notify the application that we're going to sleep
+            let timeout = timeout.map(|t| t.saturating_duration_since(timestamp));
+            publisher.on_platform_event_loop_sleep(event::builder::PlatformEventLoopSleep {
+                timeout,
+                processing_duration: timestamp.saturating_duration_since(wakeup_timestamp),
+            });
+
+            // don't block if there are completions we haven't processed yet
+            cq.sync();
+            let want = if cq.is_empty() { 1 } else { 0 };
+
+            let result = if let Some(timeout) = timeout.filter(|_| want > 0) {
+                let timespec = types::Timespec::new()
+                    .sec(timeout.as_secs())
+                    .nsec(timeout.subsec_nanos());
+                let args = types::SubmitArgs::new().timespec(&timespec);
+                submitter.submit_with_args(want, &args)
+            } else {
+                submitter.submit_and_wait(want)
+            };
+
+            timeout_expired = false;
+
+            match result {
+                Ok(_) => {}
+                Err(err) if err.raw_os_error() == Some(libc::ETIME) => {
+                    timeout_expired = true;
+                }
+                // the completion queue is full or the wait was interrupted so try again
+                Err(err) if matches!(err.raw_os_error(), Some(libc::EBUSY) | Some(libc::EINTR)) => {
+                }
+                Err(err) => break Err(err),
+            }
+
+            wakeup_timestamp = clock.get_time();
+        };
+
+        // The kernel holds references to the queue messages until each operation completes so
+        // wait for everything in flight before they're released. Shutting down the read half
+        // of the socket completes any pending receives.
+        let _ = libc!(shutdown(rx_socket.as_raw_fd(), libc::SHUT_RD));
+        waker.wake_by_ref();
+
+        let mut pending = rx_in_flight.pending_len()
+            + tx_in_flight.pending_len()
+            + wakeup_in_flight as usize
+            + multishot_in_flight as usize;
+
+        // A multishot receive stays armed on a socket that has been shut down so cancel it
+        if multishot_in_flight {
+            let entry = opcode::AsyncCancel::new(Kind::Rx.user_data(0))
+                .build()
+                .user_data(Kind::Wakeup.user_data(0));
+
+            // Safety: the cancellation doesn't reference any buffers
+            if unsafe { sq.push(&entry) }.is_ok() {
+                sq.sync();
+                pending += 1;
+            }
+        }
+
+        while pending > 0 {
+            match submitter.submit_and_wait(1) {
+                Ok(_) => {}
+                Err(err) if err.raw_os_error() == Some(libc::EINTR) => continue,
+                Err(err) => return Err(err),
+            }
+
+            cq.sync();
+            for cqe in &mut cq {
+                // a multishot receive keeps going until the kernel clears the `more` flag
+                let (kind, _) = Kind::decode(cqe.user_data());
+                if kind == Kind::Rx && buf_ring.is_some() && cqueue::more(cqe.flags()) {
+                    continue;
+                }
+                pending = pending.saturating_sub(1);
+            }
+        }
+
+        if buf_ring.take().is_some() {
+            let _ = submitter.unregister_buf_ring(RX_BUFFER_GROUP);
+        }
+
+        result
+    }
+}
+
+#[inline]
+fn in_flight(rx: &InFlight, tx: &InFlight) -> usize {
+    // include the wakeup read
+    rx.len() + tx.len() + 1
+}
+
+/// Pushes an entry onto the submission queue
+///
+/// # Safety
+///
+/// Any buffers referenced by the entry must be valid until the operation completes
+#[inline]
+unsafe fn push(sq: &mut squeue::SubmissionQueue, entry: &squeue::Entry) {
+    let result = sq.push(entry);
+    debug_assert!(result.is_ok(), "callers should check the queue capacity");
+}
+
+/// Moves the completed receive operations to the occupied segment of the queue
+///
+/// Failed operations are marked to be submitted again, since the queue can only be advanced
+/// in order. Returns the number of messages that were received.
+fn complete_rx<Publisher: event::EndpointPublisher>(
+    rx: &mut Queue,
+    in_flight: &mut InFlight,
+    publisher: &mut Publisher,
+) -> usize {
+    for index in 0..in_flight.len() {
+        if let Some(result) = in_flight.result(index).filter(|result| *result < 0) {
+            let errno = -result;
+            if errno != libc::EAGAIN && errno != libc::EINTR {
+                publisher.on_platform_rx_error(event::builder::PlatformRxError { errno });
+            }
+            in_flight.retry(index);
+        }
+    }
+
+    let count = in_flight.completed_len();
+    if count == 0 {
+        return 0;
+    }
+
+    let mut entries = rx.free_mut();
+
+    for (index, entry) in entries.iter_mut().take(count).enumerate() {
+        let len = in_flight.result(index).unwrap_or_default() as usize;
+
+        // ensure the returned length does not exceed what is
+        // allocated
+        debug_assert!(len <= entry.payload_len(), "cannot exceed payload_len");
+        let len = len.min(entry.payload_len());
+        unsafe {
+            // Safety: len will always be <= the initially allocated size
+            entry.set_payload_len(len);
+        }
+    }
+
+    entries.finish(count);
+    in_flight.finish(count);
+
+    publisher.on_platform_rx(event::builder::PlatformRx { count });
+
+    count
+}
+
+/// Copies the datagrams received into the buffer ring to the free segment of the queue
+///
+/// Returns the number of messages that were received.
+fn complete_rx_multishot<Publisher: event::EndpointPublisher>(
+    rx: &mut Queue,
+    buf_ring: &mut BufRing,
+    pending: &mut VecDeque<(u16, usize)>,
+    publisher: &mut Publisher,
+) -> usize {
+    let mut entries = rx.free_mut();
+    let count = entries.len().min(pending.len());
+    if count == 0 {
+        return 0;
+    }
+
+    for entry in entries.iter_mut().take(count) {
+        let (id, len) = pending
+            .pop_front()
+            .expect("count is limited to the pending length");
+        buf_ring.copy_to(id, len, entry);
+    }
+
+    entries.finish(count);
+
+    publisher.on_platform_rx(event::builder::PlatformRx { count });
+
+    count
+}
+
+/// Releases the completed transmit operations back to the free segment of the queue
+fn complete_tx<Publisher: event::EndpointPublisher>(
+    tx: &mut Queue,
+    in_flight: &mut InFlight,
+    publisher: &mut Publisher,
+) {
+    let len = in_flight.completed_len();
+    if len == 0 {
+        return;
+    }
+
+    let mut count = 0;
+
+    for index in 0..len {
+        let result = in_flight.result(index).unwrap_or_default();
+
+        if result >= 0 {
+            count += 1;
+            continue;
+        }
+
+        let errno = -result;
+
+        // check to see if we need to disable GSO
+        #[cfg(s2n_quic_platform_gso)]
+        if errno == libc::EIO && tx.max_gso() > 1 {
+            tx.disable_gso();
+
+            publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+                configuration: event::builder::PlatformFeatureConfiguration::Gso {
+                    max_segments: tx.max_gso(),
+                },
+            });
+        }
+
+        // Ignore other transmission errors
+        // - Permissions issues are observed in case of unsuitable iptable
+        //   rules. Those can be changed while the application is running.
+        // - Network unreachable errors can be observed for certain
+        //   destination addresses.
+        publisher.on_platform_tx_error(event::builder::PlatformTxError { errno });
+    }
+
+    if count > 0 {
+        publisher.on_platform_tx(event::builder::PlatformTx { count });
+    }
+
+    tx.occupied_mut().finish(len);
+    in_flight.finish(len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::{
+        endpoint::{self, CloseError},
+        io::{
+            rx::{self, Entry as _},
+            tx,
+        },
+        path::Handle as _,
+        time::{Clock, Duration, Timestamp},
+    };
+    use std::collections::BTreeMap;
+
+    /// Sends datagrams to its own address until every one of them has been received
+    struct LoopbackEndpoint {
+        addr: SocketAddress,
+        messages: BTreeMap<u32, Option<Timestamp>>,
+        now: Option<Timestamp>,
+        subscriber: NoopSubscriber,
+    }
+
+    impl LoopbackEndpoint {
+        fn new(addr: SocketAddress) -> Self {
+            let messages = (0..1000).map(|id| (id, None)).collect();
+            Self {
+                addr,
+                messages,
+                now: None,
+                subscriber: Default::default(),
+            }
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct NoopSubscriber;
+
+    impl event::Subscriber for NoopSubscriber {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &event::api::ConnectionMeta,
+            _info: &event::api::ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+    }
+
+    impl Endpoint for LoopbackEndpoint {
+        type PathHandle = PathHandle;
+        type Subscriber = NoopSubscriber;
+
+        const ENDPOINT_TYPE: endpoint::Type = endpoint::Type::Server;
+
+        fn transmit<Tx: tx::Queue<Handle = PathHandle>, C: Clock>(
+            &mut self,
+            queue: &mut Tx,
+            clock: &C,
+        ) {
+            let now = clock.get_time();
+            self.now = Some(now);
+
+            for (id, tx_time) in &mut self.messages {
+                match tx_time {
+                    Some(time)
+                        if now.saturating_duration_since(*time) < Duration::from_millis(50) =>
+                    {
+                        continue
+                    }
+                    _ => {
+                        let payload = id.to_be_bytes();
+                        let addr = PathHandle::from_remote_address(self.addr.into());
+                        if queue.push((addr, payload)).is_ok() {
+                            *tx_time = Some(now);
+                        } else {
+                            // no more capacity
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+
+        fn receive<Rx: rx::Queue<Handle = PathHandle>, C: Clock>(
+            &mut self,
+            queue: &mut Rx,
+            clock: &C,
+        ) {
+            self.now = Some(clock.get_time());
+            let local_address = queue.local_address();
+            let entries = queue.as_slice_mut();
+            let len = entries.len();
+            for entry in entries {
+                entry.read_segments(&local_address, |header, payload| {
+                    assert_eq!(header.path.remote_address(), self.addr.into());
+                    assert_eq!(payload.len(), 4, "invalid payload {:?}", payload);
+
+                    let id = (&*payload).try_into().unwrap();
+                    self.messages.remove(&u32::from_be_bytes(id));
+                });
+            }
+            queue.finish(len);
+        }
+
+        fn poll_wakeups<C: Clock>(
+            &mut self,
+            _cx: &mut Context<'_>,
+            clock: &C,
+        ) -> Poll<Result<usize, CloseError>> {
+            self.now = Some(clock.get_time());
+
+            if self.messages.is_empty() {
+                return Err(CloseError).into();
+            }
+
+            Poll::Pending
+        }
+
+        fn timeout(&self) -> Option<Timestamp> {
+            self.now.map(|now| now + Duration::from_millis(50))
+        }
+
+        fn set_max_mtu(&mut self, _max_mtu: MaxMtu) {
+            // noop
+        }
+
+        fn subscriber(&mut self) -> &mut Self::Subscriber {
+            &mut self.subscriber
+        }
+    }
+
+    fn test(entries: Option<u32>) -> io::Result<()> {
+        let rx_socket: std::net::UdpSocket = syscall::bind_udp("127.0.0.1:0", false)?.into();
+        let addr = rx_socket.local_addr()?;
+
+        let mut builder = Io::builder().with_rx_socket(rx_socket)?;
+        if let Some(entries) = entries {
+            builder = builder.with_entries(entries)?;
+        }
+
+        // io_uring can be disabled by the kernel configuration or a seccomp filter, or the kernel
+        // can be too old
+        let io = match builder.build() {
+            Ok(io) => io,
+            Err(err) if err.kind() == io::ErrorKind::Unsupported => {
+                eprintln!("{err}; skipping");
+                return Ok(());
+            }
+            Err(err) => return Err(err),
+        };
+
+        let (thread, local_addr) = io.start(LoopbackEndpoint::new(addr.into()))?;
+        assert_eq!(std::net::SocketAddr::from(local_addr), addr);
+
+        // the thread panics in tests if the event loop fails
+        thread.join().unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn loopback_test() -> io::Result<()> {
+        test(None)
+    }
+
+    /// Receives must not be able to use every entry in the ring, since they are waiting on
+    /// the transmissions behind them
+    #[test]
+    fn small_ring_test() -> io::Result<()> {
+        test(Some(MIN_ENTRIES))
+    }
+
+    #[test]
+    fn entries_test() {
+        assert!(Io::builder().with_entries(MIN_ENTRIES - 1).is_err());
+        assert!(Io::builder().with_entries(MAX_ENTRIES + 1).is_err());
+        assert!(Io::builder().with_entries(MIN_ENTRIES).is_ok());
+    }
+}
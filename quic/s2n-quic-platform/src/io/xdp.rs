@@ -0,0 +1,1312 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An IO provider which bypasses the kernel's network stack with [AF_XDP](https://www.kernel.org/doc/html/latest/networking/af_xdp.html)
+//!
+//! **This provider is experimental.** It is only tested against a veth pair in generic (copy)
+//! mode and may change in incompatible ways.
+//!
+//! Frames are received and transmitted directly from a region of memory shared with the kernel,
+//! called the UMEM. The Ethernet, IP and UDP headers are encoded and decoded in software by
+//! [`s2n_quic_core::xdp`], including checksums.
+//!
+//! The provider doesn't load an XDP program. The application is expected to attach a program to
+//! the interface which redirects the endpoint's traffic to an `XSKMAP`, and pass the map to
+//! [`Builder::with_xsk_map_fd`]. The socket is inserted into the map at the configured queue id.
+//!
+//! # Next-hop resolution
+//!
+//! The kernel's neighbor table is bypassed and ARP and NDP aren't implemented. The destination
+//! MAC address of each transmitted frame is chosen in the following order:
+//!
+//! 1. The source MAC address of the frame the path was received on. For peers on another
+//!    network this is the address of the last router, which is also the correct next hop.
+//! 2. The address configured for the peer's IP with [`Builder::with_neighbor`].
+//! 3. The address configured with [`Builder::with_gateway`].
+//!
+//! If none of these are available, e.g. when a client connects to a server which isn't
+//! configured as a neighbor and no gateway is set, the datagram is dropped and a
+//! `PlatformTxError` event is published with `EHOSTUNREACH`. The configured addresses are never
+//! refreshed, so they must be updated by restarting the provider if they change.
+
+use super::eventfd::EventFd;
+use s2n_codec::DecoderBufferMut;
+use s2n_quic_core::{
+    endpoint::Endpoint,
+    event::{self, EndpointPublisher as _},
+    inet::{datagram, ethernet::MacAddress, SocketAddress, Unspecified as _},
+    io::{rx, tx},
+    path::{self, MaxMtu},
+    time::{Clock as _, StdClock},
+    xdp::{
+        decoder, encoder,
+        path::{Address, Tuple},
+    },
+};
+use std::{
+    convert::TryInto,
+    ffi::CString,
+    io,
+    io::ErrorKind,
+    os::unix::io::{AsRawFd, RawFd},
+    sync::Arc,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+mod if_xdp;
+mod mmap;
+mod neighbor;
+mod ring;
+mod socket;
+
+use socket::Socket;
+
+pub type PathHandle = Tuple;
+
+/// The size of each frame in the UMEM
+///
+/// Frames are a page in size so every frame can hold a datagram of the largest supported MTU
+/// with its headers.
+const FRAME_SIZE: u32 = 4096;
+
+/// The size of the Ethernet header, which isn't included in the MTU
+const ETHERNET_HEADER_LEN: usize = 14;
+
+/// The longest the event loop sleeps while transmitted frames are waiting to be completed
+///
+/// The kernel doesn't notify the socket when frames are returned to the completion ring so
+/// the ring is polled instead.
+const COMPLETION_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+#[derive(Debug, Default)]
+pub struct Io {
+    builder: Builder,
+}
+
+impl Io {
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    pub fn start<E: Endpoint<PathHandle = PathHandle>>(
+        self,
+        mut endpoint: E,
+    ) -> io::Result<(std::thread::JoinHandle<()>, SocketAddress)> {
+        let Builder {
+            interface,
+            queue_id,
+            local_address,
+            xsk_map_fd,
+            neighbors,
+            max_mtu,
+            frame_count,
+            ring_size,
+            bind_flags,
+        } = self.builder;
+
+        let interface = interface
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "missing interface"))?;
+        let local_address: SocketAddress = local_address
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "missing local address"))?
+            .into();
+        let xsk_map_fd = xsk_map_fd
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "missing XSKMAP fd"))?;
+
+        if local_address.ip().is_unspecified() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "the local address must be specified to encode IP headers",
+            ));
+        }
+
+        if u16::from(max_mtu) as usize + ETHERNET_HEADER_LEN > FRAME_SIZE as usize {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "max_mtu must be at most {} bytes",
+                    FRAME_SIZE as usize - ETHERNET_HEADER_LEN
+                ),
+            ));
+        }
+
+        let ifindex = if_nametoindex(&interface)?;
+        let local_mac = interface_mac(&interface)?;
+
+        let socket = Socket::open(&socket::Config {
+            ifindex,
+            queue_id,
+            frame_size: FRAME_SIZE,
+            frame_count,
+            ring_size,
+            bind_flags,
+        })?;
+        socket.register(xsk_map_fd, queue_id)?;
+
+        endpoint.set_max_mtu(max_mtu);
+
+        let clock = StdClock::default();
+
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: E::ENDPOINT_TYPE,
+                timestamp: clock.get_time(),
+            },
+            None,
+            endpoint.subscriber(),
+        );
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::MaxMtu {
+                mtu: max_mtu.into(),
+            },
+        });
+
+        // each frame holds a single datagram
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Gso { max_segments: 1 },
+        });
+
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Gro { enabled: false },
+        });
+
+        // the ECN markings are read from and written to the IP header directly
+        publisher.on_platform_feature_configured(event::builder::PlatformFeatureConfigured {
+            configuration: event::builder::PlatformFeatureConfiguration::Ecn { enabled: true },
+        });
+
+        let waker = Arc::new(EventFd::new()?);
+
+        let instance = Instance {
+            clock,
+            socket,
+            waker,
+            local: Address {
+                mac: local_mac,
+                socket: local_address,
+            },
+            neighbors,
+            max_mtu,
+            endpoint,
+        };
+
+        let thread = std::thread::Builder::new()
+            .name("s2n-quic-xdp".to_string())
+            .spawn(move || {
+                if let Err(err) = instance.event_loop() {
+                    let debug = format!("A fatal IO error occurred ({:?}): {err}", err.kind());
+                    if cfg!(test) {
+                        panic!("{debug}");
+                    } else {
+                        eprintln!("{debug}");
+                    }
+                }
+            })?;
+
+        Ok((thread, local_address))
+    }
+}
+
+#[derive(Debug)]
+pub struct Builder {
+    interface: Option<String>,
+    queue_id: u32,
+    local_address: Option<std::net::SocketAddr>,
+    xsk_map_fd: Option<RawFd>,
+    neighbors: neighbor::Table,
+    max_mtu: MaxMtu,
+    frame_count: u32,
+    ring_size: u32,
+    bind_flags: u16,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            interface: None,
+            queue_id: 0,
+            local_address: None,
+            xsk_map_fd: None,
+            neighbors: Default::default(),
+            max_mtu: Default::default(),
+            frame_count: 4096,
+            ring_size: 2048,
+            bind_flags: 0,
+        }
+    }
+}
+
+impl Builder {
+    /// Sets the name of the network interface to bind to, e.g. `eth0`
+    pub fn with_interface(mut self, name: &str) -> io::Result<Self> {
+        self.interface = Some(name.to_string());
+        Ok(self)
+    }
+
+    /// Sets the receive queue of the interface to bind to
+    ///
+    /// Defaults to `0`. The XDP program must redirect frames received on this queue to the
+    /// socket's entry in the `XSKMAP`.
+    pub fn with_queue_id(mut self, queue_id: u32) -> io::Result<Self> {
+        self.queue_id = queue_id;
+        Ok(self)
+    }
+
+    /// Sets the IP address and port of the endpoint
+    ///
+    /// Frames addressed to any other destination are dropped.
+    pub fn with_local_address(mut self, addr: std::net::SocketAddr) -> io::Result<Self> {
+        self.local_address = Some(addr);
+        Ok(self)
+    }
+
+    /// Sets the file descriptor of the `XSKMAP` used by the XDP program to redirect frames
+    ///
+    /// The socket is inserted into the map with the queue id as the key.
+    pub fn with_xsk_map_fd(mut self, fd: RawFd) -> io::Result<Self> {
+        self.xsk_map_fd = Some(fd);
+        Ok(self)
+    }
+
+    /// Adds the link-layer address of a peer on the local network
+    pub fn with_neighbor(mut self, ip: std::net::IpAddr, mac: [u8; 6]) -> io::Result<Self> {
+        let ip = SocketAddress::from((ip, 0)).ip();
+        self.neighbors.insert(ip, MacAddress::new(mac));
+        Ok(self)
+    }
+
+    /// Sets the link-layer address of the gateway, which is used for peers without a
+    /// neighbor entry
+    pub fn with_gateway(mut self, mac: [u8; 6]) -> io::Result<Self> {
+        self.neighbors.set_gateway(MacAddress::new(mac));
+        Ok(self)
+    }
+
+    /// Sets the largest maximum transmission unit (MTU) that can be sent on a path
+    pub fn with_max_mtu(mut self, max_mtu: u16) -> io::Result<Self> {
+        self.max_mtu = max_mtu
+            .try_into()
+            .map_err(|err| io::Error::new(ErrorKind::InvalidInput, format!("{err}")))?;
+        Ok(self)
+    }
+
+    /// Sets the number of frames in the UMEM
+    ///
+    /// Half of the frames are used for receiving and the other half for transmitting.
+    /// Defaults to `4096`.
+    pub fn with_frame_count(mut self, frame_count: u32) -> io::Result<Self> {
+        if frame_count < 2 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "frame_count must be at least 2",
+            ));
+        }
+        self.frame_count = frame_count;
+        Ok(self)
+    }
+
+    /// Sets the number of entries in each of the rings
+    ///
+    /// The size must be a power of two. Defaults to `2048`.
+    pub fn with_ring_size(mut self, ring_size: u32) -> io::Result<Self> {
+        if !ring_size.is_power_of_two() {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "ring_size must be a power of two",
+            ));
+        }
+        self.ring_size = ring_size;
+        Ok(self)
+    }
+
+    /// Requires the driver to support zero-copy mode
+    ///
+    /// By default, zero-copy mode is used if the driver supports it, otherwise frames are
+    /// copied between the UMEM and the driver's buffers.
+    pub fn with_zerocopy_mode(mut self) -> io::Result<Self> {
+        self.bind_flags = if_xdp::XDP_ZEROCOPY;
+        Ok(self)
+    }
+
+    /// Forces frames to be copied between the UMEM and the driver's buffers
+    pub fn with_copy_mode(mut self) -> io::Result<Self> {
+        self.bind_flags = if_xdp::XDP_COPY;
+        Ok(self)
+    }
+
+    pub fn build(self) -> io::Result<Io> {
+        Ok(Io { builder: self })
+    }
+}
+
+fn if_nametoindex(name: &str) -> io::Result<u32> {
+    let name = CString::new(name)
+        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "invalid interface name"))?;
+
+    match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+        0 => Err(io::Error::last_os_error()),
+        index => Ok(index),
+    }
+}
+
+/// Reads the link-layer address of the interface from sysfs
+fn interface_mac(name: &str) -> io::Result<MacAddress> {
+    let address = std::fs::read_to_string(format!("/sys/class/net/{name}/address"))?;
+
+    let mut octets = [0u8; 6];
+    let mut parts = address.trim().split(':');
+    for octet in octets.iter_mut() {
+        *octet = parts
+            .next()
+            .and_then(|part| u8::from_str_radix(part, 16).ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid address for interface {name}: {address}"),
+                )
+            })?;
+    }
+
+    Ok(MacAddress::new(octets))
+}
+
+struct Instance<E> {
+    clock: StdClock,
+    socket: Socket,
+    waker: Arc<EventFd>,
+    local: Address,
+    neighbors: neighbor::Table,
+    max_mtu: MaxMtu,
+    endpoint: E,
+}
+
+impl<E: Endpoint<PathHandle = PathHandle>> Instance<E> {
+    fn event_loop(self) -> io::Result<()> {
+        let Self {
+            clock,
+            mut socket,
+            waker,
+            local,
+            neighbors,
+            max_mtu,
+            mut endpoint,
+        } = self;
+
+        // split the UMEM between the two directions
+        let frame_count = socket.frame_count();
+        let rx_frames = frame_count / 2;
+        let mut rx_free: Vec<u64> = (0..rx_frames).map(|i| socket.frame_addr(i)).collect();
+        let mut tx_free: Vec<u64> = (rx_frames..frame_count)
+            .map(|i| socket.frame_addr(i))
+            .collect();
+        let tx_frames = tx_free.len();
+
+        let mut rx_entries = Vec::with_capacity(rx_frames as usize);
+        let mut tx_entries = Vec::with_capacity(tx_frames);
+
+        let task_waker = Waker::from(waker.clone());
+        let mut cx = Context::from_waker(&task_waker);
+
+        let mut timeout_expired = false;
+        let mut rx_ready = false;
+        let mut application_wakeup_fd = false;
+        let mut wakeup_timestamp = clock.get_time();
+
+        loop {
+            let mut application_wakeup = false;
+
+            if application_wakeup_fd {
+                waker.reset();
+            }
+
+            match endpoint.poll_wakeups(&mut cx, &clock) {
+                Poll::Ready(Ok(_)) => application_wakeup = true,
+                // The endpoint has shut down
+                Poll::Ready(Err(_)) => return Ok(()),
+                Poll::Pending => {}
+            }
+
+            // return any transmitted frames to the free list
+            let completed = {
+                let count = socket.completion.acquire(u32::MAX);
+                for index in 0..count {
+                    let addr = socket.completion.read(index);
+                    tx_free.push(addr - addr % FRAME_SIZE as u64);
+                }
+                socket.completion.release(count);
+                count as usize
+            };
+            let tx_ready = completed > 0;
+
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp: wakeup_timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            publisher.on_platform_event_loop_wakeup(event::builder::PlatformEventLoopWakeup {
+                timeout_expired,
+                rx_ready,
+                tx_ready,
+                application_wakeup,
+            });
+
+            if tx_ready {
+                publisher.on_platform_tx(event::builder::PlatformTx { count: completed });
+            }
+
+            // read the received frames from the RX ring
+            {
+                let count = socket.rx.acquire(u32::MAX);
+                for index in 0..count {
+                    let desc = socket.rx.read(index);
+                    let frame = socket.frame_mut(desc.addr);
+                    let len = (desc.len as usize).min(frame.len());
+                    rx_entries.push(RxEntry {
+                        frame: frame.as_mut_ptr(),
+                        len,
+                        addr: desc.addr,
+                    });
+                }
+                socket.rx.release(count);
+
+                if count > 0 {
+                    publisher.on_platform_rx(event::builder::PlatformRx {
+                        count: count as usize,
+                    });
+                }
+            }
+
+            if !rx_entries.is_empty() {
+                let mut queue = RxQueue {
+                    entries: &mut rx_entries,
+                    local_address: local.socket.into(),
+                };
+                endpoint.receive(&mut queue, &clock);
+
+                // return the frames to the kernel once the endpoint is done with them
+                for entry in rx_entries.drain(..) {
+                    rx_free.push(entry.addr - entry.addr % FRAME_SIZE as u64);
+                }
+            }
+
+            // give the free frames to the kernel to receive into
+            {
+                let count = socket.fill.acquire(rx_free.len() as u32);
+                for index in 0..count {
+                    let addr = rx_free.pop().expect("count is limited to the free frames");
+                    socket.fill.write(index, addr);
+                }
+                socket.fill.release(count);
+            }
+
+            {
+                let capacity = socket
+                    .tx
+                    .acquire(tx_free.len() as u32)
+                    .min(tx_frames as u32) as usize;
+
+                let mut queue = TxQueue {
+                    socket: &mut socket,
+                    free: &mut tx_free,
+                    entries: &mut tx_entries,
+                    local,
+                    neighbors: &neighbors,
+                    max_mtu: u16::from(max_mtu) as usize,
+                    capacity,
+                };
+                endpoint.transmit(&mut queue, &clock);
+
+                let subscriber = endpoint.subscriber();
+                let mut publisher = event::EndpointPublisherSubscriber::new(
+                    event::builder::EndpointMeta {
+                        endpoint_type: E::ENDPOINT_TYPE,
+                        timestamp: wakeup_timestamp,
+                    },
+                    None,
+                    subscriber,
+                );
+
+                let mut count = 0;
+                for entry in tx_entries.drain(..) {
+                    if entry.is_resolved {
+                        socket.tx.write(
+                            count,
+                            if_xdp::xdp_desc {
+                                addr: entry.addr,
+                                len: entry.len as u32,
+                                options: 0,
+                            },
+                        );
+                        count += 1;
+                    } else {
+                        // the peer's link-layer address isn't known so the datagram is dropped
+                        publisher.on_platform_tx_error(event::builder::PlatformTxError {
+                            errno: libc::EHOSTUNREACH,
+                        });
+                        tx_free.push(entry.addr);
+                    }
+                }
+
+                if count > 0 {
+                    socket.tx.release(count);
+                }
+            }
+
+            // the kernel only processes the TX ring when it is notified
+            let tx_in_flight = tx_free.len() < tx_frames;
+            if tx_in_flight && socket.tx.needs_wakeup() {
+                socket.kick_tx()?;
+            }
+
+            let timeout = endpoint.timeout();
+
+            let timestamp = clock.get_time();
+            let subscriber = endpoint.subscriber();
+            let mut publisher = event::EndpointPublisherSubscriber::new(
+                event::builder::EndpointMeta {
+                    endpoint_type: E::ENDPOINT_TYPE,
+                    timestamp,
+                },
+                None,
+                subscriber,
+            );
+
+            // notify the application that we're going to sleep
+            let timeout = timeout.map(|t| t.saturating_duration_since(timestamp));
+            publisher.on_platform_event_loop_sleep(event::builder::PlatformEventLoopSleep {
+                timeout,
+                processing_duration: timestamp.saturating_duration_since(wakeup_timestamp),
+            });
+
+            let sleep = if tx_in_flight {
+                Some(
+                    timeout
+                        .unwrap_or(COMPLETION_POLL_INTERVAL)
+                        .min(COMPLETION_POLL_INTERVAL),
+                )
+            } else {
+                timeout
+            };
+
+            let mut fds = [
+                libc::pollfd {
+                    fd: socket.as_raw_fd(),
+                    events: libc::POLLIN,
+                    revents: 0,
+                },
+                libc::pollfd {
+                    fd: waker.as_raw_fd(),
+                    events: libc::POLLIN,
+                    revents: 0,
+                },
+            ];
+
+            let poll_timeout = sleep.map_or(-1, |sleep| {
+                // round up so the timer has always expired on wakeup
+                let millis = (sleep.as_nanos() + 999_999) / 1_000_000;
+                millis.min(libc::c_int::MAX as u128) as libc::c_int
+            });
+
+            timeout_expired = false;
+            rx_ready = false;
+            application_wakeup_fd = false;
+
+            match libc!(poll(fds.as_mut_ptr(), fds.len() as _, poll_timeout)) {
+                Ok(0) => timeout_expired = true,
+                Ok(_) => {
+                    rx_ready = fds[0].revents & libc::POLLIN != 0;
+                    application_wakeup_fd = fds[1].revents & libc::POLLIN != 0;
+                }
+                Err(err) if err.raw_os_error() == Some(libc::EINTR) => {}
+                Err(err) => return Err(err),
+            }
+
+            wakeup_timestamp = clock.get_time();
+        }
+    }
+}
+
+/// A frame which was received from the RX ring
+#[derive(Debug)]
+struct RxEntry {
+    frame: *mut u8,
+    len: usize,
+    addr: u64,
+}
+
+impl rx::Entry for RxEntry {
+    type Handle = PathHandle;
+
+    #[inline]
+    fn read(
+        &mut self,
+        local_address: &path::LocalAddress,
+    ) -> Option<(datagram::Header<Self::Handle>, &mut [u8])> {
+        let frame = unsafe {
+            // Safety: the frame is owned by the application until it is returned to the fill
+            //         ring, which happens after the endpoint has finished with the queue
+            core::slice::from_raw_parts_mut(self.frame, self.len)
+        };
+
+        let (header, payload) = decoder::decode_packet(DecoderBufferMut::new(frame)).ok()??;
+
+        // the XDP program may redirect more traffic than the endpoint is bound to
+        if header.path.local_address.socket.unmap() != local_address.unmap() {
+            return None;
+        }
+
+        Some((header, payload.into_less_safe_slice()))
+    }
+}
+
+struct RxQueue<'a> {
+    entries: &'a mut Vec<RxEntry>,
+    local_address: path::LocalAddress,
+}
+
+impl<'a> rx::Queue for RxQueue<'a> {
+    type Entry = RxEntry;
+    type Handle = PathHandle;
+
+    #[inline]
+    fn local_address(&self) -> path::LocalAddress {
+        self.local_address
+    }
+
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        self.entries
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    fn finish(&mut self, _count: usize) {
+        // the frames are recycled by the event loop after the endpoint returns
+    }
+}
+
+/// A frame which is waiting to be pushed to the TX ring
+#[derive(Debug)]
+struct TxEntry {
+    frame: *mut u8,
+    frame_len: usize,
+    addr: u64,
+    header_len: usize,
+    len: usize,
+    /// `true` if the link-layer address of the peer is known
+    is_resolved: bool,
+}
+
+impl TxEntry {
+    #[inline]
+    fn frame_mut(&mut self) -> &mut [u8] {
+        unsafe {
+            // Safety: the frame is owned by the application until it is pushed to the TX ring
+            core::slice::from_raw_parts_mut(self.frame, self.frame_len)
+        }
+    }
+}
+
+impl tx::Entry for TxEntry {
+    type Handle = PathHandle;
+
+    #[inline]
+    fn set<M: tx::Message<Handle = Self::Handle>>(
+        &mut self,
+        mut message: M,
+    ) -> Result<usize, tx::Error> {
+        let header_len = encoder::header_len(message.path_handle());
+        let len = encoder::encode_packet(self.frame_mut(), &mut message)?;
+        self.header_len = header_len;
+        self.len = len;
+        Ok(len - header_len)
+    }
+
+    #[inline]
+    fn payload(&self) -> &[u8] {
+        unsafe {
+            // Safety: the frame is owned by the application until it is pushed to the TX ring
+            core::slice::from_raw_parts(self.frame.add(self.header_len), self.len - self.header_len)
+        }
+    }
+
+    #[inline]
+    fn payload_mut(&mut self) -> &mut [u8] {
+        let range = self.header_len..self.len;
+        &mut self.frame_mut()[range]
+    }
+}
+
+struct TxQueue<'a> {
+    socket: &'a mut Socket,
+    free: &'a mut Vec<u64>,
+    entries: &'a mut Vec<TxEntry>,
+    local: Address,
+    neighbors: &'a neighbor::Table,
+    max_mtu: usize,
+    capacity: usize,
+}
+
+impl<'a> tx::Queue for TxQueue<'a> {
+    type Entry = TxEntry;
+    type Handle = PathHandle;
+
+    const SUPPORTS_ECN: bool = true;
+    const SUPPORTS_FLOW_LABELS: bool = true;
+
+    #[inline]
+    fn push<M: tx::Message<Handle = Self::Handle>>(
+        &mut self,
+        message: M,
+    ) -> Result<tx::Outcome, tx::Error> {
+        if self.entries.len() >= self.capacity {
+            return Err(tx::Error::AtCapacity);
+        }
+
+        let addr = *self.free.last().ok_or(tx::Error::AtCapacity)?;
+
+        // fill in any addresses that weren't known when the path was created
+        let mut path = *message.path_handle();
+        if path.local_address.socket.ip().is_unspecified() {
+            path.local_address.socket = self.local.socket;
+        }
+        if path.local_address.mac.is_unspecified() {
+            path.local_address.mac = self.local.mac;
+        }
+        if path.remote_address.mac.is_unspecified() {
+            if let Some(mac) = self.neighbors.lookup(path.remote_address.socket.ip()) {
+                path.remote_address.mac = mac;
+            }
+        }
+        let is_resolved = !path.remote_address.mac.is_unspecified();
+
+        let frame = self.socket.frame_mut(addr);
+        let header_len = encoder::header_len(&path);
+        // limit the IP packet to the MTU, rather than the size of the frame
+        let frame_len = frame.len().min(ETHERNET_HEADER_LEN + self.max_mtu);
+        let mut entry = TxEntry {
+            frame: frame.as_mut_ptr(),
+            frame_len,
+            addr,
+            header_len: 0,
+            len: 0,
+            is_resolved,
+        };
+
+        let len = tx::Entry::set(&mut entry, Message { message, path })?;
+
+        self.free.pop();
+        let index = self.entries.len();
+        self.entries.push(entry);
+
+        Ok(tx::Outcome { len, index })
+    }
+
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        self.entries
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity.saturating_sub(self.entries.len())
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// A message with its path resolved to link-layer and network addresses
+struct Message<M> {
+    message: M,
+    path: Tuple,
+}
+
+impl<M: tx::Message<Handle = Tuple>> tx::Message for Message<M> {
+    type Handle = Tuple;
+
+    #[inline]
+    fn path_handle(&self) -> &Self::Handle {
+        &self.path
+    }
+
+    #[inline]
+    fn ecn(&mut self) -> s2n_quic_core::inet::ExplicitCongestionNotification {
+        self.message.ecn()
+    }
+
+    #[inline]
+    fn dscp(&mut self) -> s2n_quic_core::inet::Dscp {
+        self.message.dscp()
+    }
+
+    #[inline]
+    fn delay(&mut self) -> Duration {
+        self.message.delay()
+    }
+
+    #[inline]
+    fn ipv6_flow_label(&mut self) -> u32 {
+        self.message.ipv6_flow_label()
+    }
+
+    #[inline]
+    fn can_gso(&self, _segment_len: usize, _segment_count: usize) -> bool {
+        false
+    }
+
+    #[inline]
+    fn write_payload(
+        &mut self,
+        buffer: tx::PayloadBuffer,
+        gso_offset: usize,
+    ) -> Result<usize, tx::Error> {
+        self.message.write_payload(buffer, gso_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::{
+        endpoint::{self, CloseError},
+        io::rx::Entry as _,
+        time::{Clock, Timestamp},
+    };
+    use std::{
+        net::UdpSocket,
+        process::Command,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    const LOCAL_ADDRESS: &str = "10.99.0.1:4433";
+    const PEER_IP: &str = "10.99.0.2";
+
+    /// A veth pair with one end in a network namespace
+    ///
+    /// The provider binds to the end in the current namespace, while the kernel's network stack
+    /// sends datagrams to it from the namespace. Deleting the namespace removes both ends.
+    struct Veth {
+        netns: String,
+        interface: String,
+    }
+
+    impl Veth {
+        fn new() -> io::Result<Self> {
+            let id = std::process::id() % 100_000;
+            let veth = Self {
+                netns: format!("s2n-quic-xdp-{id}"),
+                interface: format!("s2nxdp{id}"),
+            };
+            let peer = format!("{}p", veth.interface);
+
+            ip(&["netns", "add", &veth.netns])?;
+            ip(&[
+                "link",
+                "add",
+                &veth.interface,
+                "type",
+                "veth",
+                "peer",
+                "name",
+                &peer,
+                "netns",
+                &veth.netns,
+            ])?;
+            ip(&["link", "set", &veth.interface, "up"])?;
+
+            let local_mac = interface_mac(&veth.interface)?.to_string();
+            let local_ip = LOCAL_ADDRESS.split(':').next().unwrap();
+            let peer_ip = format!("{PEER_IP}/24");
+            ip(&["-n", &veth.netns, "addr", "add", &peer_ip, "dev", &peer])?;
+            ip(&["-n", &veth.netns, "link", "set", &peer, "up"])?;
+            // there's no ARP responder on the provider's side
+            ip(&[
+                "-n",
+                &veth.netns,
+                "neigh",
+                "replace",
+                local_ip,
+                "lladdr",
+                &local_mac,
+                "dev",
+                &peer,
+            ])?;
+
+            Ok(veth)
+        }
+
+        /// Runs `f` on a thread which has entered the network namespace
+        fn spawn<F: 'static + Send + FnOnce()>(&self, f: F) -> std::thread::JoinHandle<()> {
+            let path = format!("/var/run/netns/{}", self.netns);
+            std::thread::spawn(move || {
+                let netns = std::fs::File::open(path).unwrap();
+                libc!(setns(netns.as_raw_fd(), libc::CLONE_NEWNET)).unwrap();
+                f()
+            })
+        }
+    }
+
+    impl Drop for Veth {
+        fn drop(&mut self) {
+            let _ = ip(&["netns", "delete", &self.netns]);
+        }
+    }
+
+    fn ip(args: &[&str]) -> io::Result<()> {
+        let output = Command::new("ip").args(args).output()?;
+        if !output.status.success() {
+            return Err(io::Error::new(
+                ErrorKind::Other,
+                format!(
+                    "ip {} failed: {}",
+                    args.join(" "),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The XDP program and `XSKMAP` which redirect every frame on the interface to the socket
+    ///
+    /// The program is attached in generic mode with a BPF link, which requires Linux 5.9, and is
+    /// detached when the link is closed.
+    struct Program {
+        map: RawFd,
+        prog: RawFd,
+        link: RawFd,
+    }
+
+    impl Program {
+        fn attach(ifindex: u32) -> io::Result<Self> {
+            const BPF_MAP_CREATE: libc::c_long = 0;
+            const BPF_PROG_LOAD: libc::c_long = 5;
+            const BPF_LINK_CREATE: libc::c_long = 28;
+            const BPF_MAP_TYPE_XSKMAP: u32 = 17;
+            const BPF_PROG_TYPE_XDP: u32 = 6;
+            const BPF_XDP: u32 = 37;
+            const XDP_FLAGS_SKB_MODE: u32 = 1 << 1;
+            const XDP_PASS: i32 = 2;
+            const BPF_FUNC_REDIRECT_MAP: i32 = 51;
+
+            #[repr(C, align(8))]
+            #[derive(Default)]
+            struct MapCreate {
+                map_type: u32,
+                key_size: u32,
+                value_size: u32,
+                max_entries: u32,
+            }
+
+            #[repr(C, align(8))]
+            #[derive(Default)]
+            struct ProgLoad {
+                prog_type: u32,
+                insn_cnt: u32,
+                insns: u64,
+                license: u64,
+            }
+
+            #[repr(C, align(8))]
+            #[derive(Default)]
+            struct LinkCreate {
+                prog_fd: u32,
+                target_ifindex: u32,
+                attach_type: u32,
+                flags: u32,
+            }
+
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            struct Insn {
+                code: u8,
+                regs: u8,
+                off: i16,
+                imm: i32,
+            }
+
+            fn bpf<T>(cmd: libc::c_long, attr: &T) -> io::Result<RawFd> {
+                let res = unsafe {
+                    libc::syscall(
+                        libc::SYS_bpf,
+                        cmd,
+                        attr as *const T,
+                        core::mem::size_of::<T>(),
+                    )
+                };
+                if res < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(res as RawFd)
+            }
+
+            let map = bpf(
+                BPF_MAP_CREATE,
+                &MapCreate {
+                    map_type: BPF_MAP_TYPE_XSKMAP,
+                    key_size: 4,
+                    value_size: 4,
+                    max_entries: 1,
+                },
+            )?;
+
+            let insns = [
+                // r2 = ctx->rx_queue_index
+                Insn {
+                    code: 0x61,
+                    regs: 0x12,
+                    off: 16,
+                    imm: 0,
+                },
+                // r1 = map
+                Insn {
+                    code: 0x18,
+                    regs: 0x11,
+                    off: 0,
+                    imm: map,
+                },
+                Insn {
+                    code: 0,
+                    regs: 0,
+                    off: 0,
+                    imm: 0,
+                },
+                // r3 = XDP_PASS, which is returned if the queue doesn't have a socket
+                Insn {
+                    code: 0xb7,
+                    regs: 0x03,
+                    off: 0,
+                    imm: XDP_PASS,
+                },
+                // r0 = bpf_redirect_map(r1, r2, r3)
+                Insn {
+                    code: 0x85,
+                    regs: 0,
+                    off: 0,
+                    imm: BPF_FUNC_REDIRECT_MAP,
+                },
+                // return r0
+                Insn {
+                    code: 0x95,
+                    regs: 0,
+                    off: 0,
+                    imm: 0,
+                },
+            ];
+            let license = b"Apache-2.0\0";
+
+            let prog = bpf(
+                BPF_PROG_LOAD,
+                &ProgLoad {
+                    prog_type: BPF_PROG_TYPE_XDP,
+                    insn_cnt: insns.len() as u32,
+                    insns: insns.as_ptr() as u64,
+                    license: license.as_ptr() as u64,
+                },
+            )
+            .map_err(|err| {
+                let _ = libc!(close(map));
+                err
+            })?;
+
+            let link = bpf(
+                BPF_LINK_CREATE,
+                &LinkCreate {
+                    prog_fd: prog as u32,
+                    target_ifindex: ifindex,
+                    attach_type: BPF_XDP,
+                    flags: XDP_FLAGS_SKB_MODE,
+                },
+            )
+            .map_err(|err| {
+                let _ = libc!(close(prog));
+                let _ = libc!(close(map));
+                err
+            })?;
+
+            Ok(Self { map, prog, link })
+        }
+    }
+
+    impl Drop for Program {
+        fn drop(&mut self) {
+            for fd in [self.link, self.prog, self.map] {
+                let _ = libc!(close(fd));
+            }
+        }
+    }
+
+    /// Sends every received datagram back to its sender until the test is done
+    struct EchoEndpoint {
+        pending: Vec<(PathHandle, Vec<u8>)>,
+        now: Option<Timestamp>,
+        is_done: Arc<AtomicBool>,
+        subscriber: NoopSubscriber,
+    }
+
+    #[derive(Debug, Default)]
+    struct NoopSubscriber;
+
+    impl event::Subscriber for NoopSubscriber {
+        type ConnectionContext = ();
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &event::api::ConnectionMeta,
+            _info: &event::api::ConnectionInfo,
+        ) -> Self::ConnectionContext {
+        }
+    }
+
+    impl Endpoint for EchoEndpoint {
+        type PathHandle = PathHandle;
+        type Subscriber = NoopSubscriber;
+
+        const ENDPOINT_TYPE: endpoint::Type = endpoint::Type::Server;
+
+        fn transmit<Tx: tx::Queue<Handle = PathHandle>, C: Clock>(
+            &mut self,
+            queue: &mut Tx,
+            clock: &C,
+        ) {
+            self.now = Some(clock.get_time());
+
+            while let Some((path, payload)) = self.pending.first() {
+                if queue.push((*path, &payload[..])).is_err() {
+                    return;
+                }
+                self.pending.remove(0);
+            }
+        }
+
+        fn receive<Rx: rx::Queue<Handle = PathHandle>, C: Clock>(
+            &mut self,
+            queue: &mut Rx,
+            clock: &C,
+        ) {
+            self.now = Some(clock.get_time());
+            let local_address = queue.local_address();
+            let entries = queue.as_slice_mut();
+            let len = entries.len();
+            for entry in entries {
+                entry.read_segments(&local_address, |header, payload| {
+                    self.pending.push((header.path, payload.to_vec()));
+                });
+            }
+            queue.finish(len);
+        }
+
+        fn poll_wakeups<C: Clock>(
+            &mut self,
+            _cx: &mut Context<'_>,
+            clock: &C,
+        ) -> Poll<Result<usize, CloseError>> {
+            self.now = Some(clock.get_time());
+
+            if self.is_done.load(Ordering::Relaxed) {
+                return Err(CloseError).into();
+            }
+
+            Poll::Pending
+        }
+
+        fn timeout(&self) -> Option<Timestamp> {
+            // check if the test is done
+            self.now.map(|now| now + Duration::from_millis(10))
+        }
+
+        fn set_max_mtu(&mut self, _max_mtu: MaxMtu) {
+            // noop
+        }
+
+        fn subscriber(&mut self) -> &mut Self::Subscriber {
+            &mut self.subscriber
+        }
+    }
+
+    /// Echoes datagrams from the kernel's network stack over a veth pair
+    ///
+    /// Setting up the interfaces requires `CAP_NET_ADMIN`, `CAP_SYS_ADMIN` and Linux 5.9, so the
+    /// test is skipped in environments which don't allow it.
+    #[test]
+    fn veth_test() -> io::Result<()> {
+        let veth = match Veth::new() {
+            Ok(veth) => veth,
+            Err(err) => {
+                eprintln!("unable to create a veth pair ({err}); skipping");
+                return Ok(());
+            }
+        };
+
+        let program = match Program::attach(if_nametoindex(&veth.interface)?) {
+            Ok(program) => program,
+            Err(err) => {
+                eprintln!("unable to attach an XDP program ({err}); skipping");
+                return Ok(());
+            }
+        };
+
+        let local_address: std::net::SocketAddr = LOCAL_ADDRESS.parse().unwrap();
+        let is_done = Arc::new(AtomicBool::new(false));
+        let endpoint = EchoEndpoint {
+            pending: vec![],
+            now: None,
+            is_done: is_done.clone(),
+            subscriber: Default::default(),
+        };
+
+        let (thread, addr) = Io::builder()
+            .with_interface(&veth.interface)?
+            .with_local_address(local_address)?
+            .with_xsk_map_fd(program.map)?
+            .with_copy_mode()?
+            .with_frame_count(64)?
+            .with_ring_size(32)?
+            .build()?
+            .start(endpoint)?;
+        assert_eq!(std::net::SocketAddr::from(addr), local_address);
+
+        let client = veth.spawn(move || {
+            let socket = UdpSocket::bind((PEER_IP, 0)).unwrap();
+            socket
+                .set_read_timeout(Some(Duration::from_millis(100)))
+                .unwrap();
+
+            for id in 0u32..10 {
+                let payload = id.to_be_bytes();
+                let mut buffer = [0u8; 16];
+
+                let received = (0..50).any(|_| {
+                    socket.send_to(&payload, local_address).unwrap();
+                    matches!(
+                        socket.recv_from(&mut buffer),
+                        Ok((4, from)) if from == local_address && buffer[..4] == payload
+                    )
+                });
+                assert!(received, "datagram {id} was not echoed");
+            }
+        });
+
+        let result = client.join();
+        is_done.store(true, Ordering::Relaxed);
+
+        // the thread panics in tests if the event loop fails
+        thread.join().unwrap();
+        result.unwrap();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Definitions from `linux/if_xdp.h` and `linux/bpf.h` which aren't available in all versions
+//! of the `libc` crate
+//!
+//! See <https://www.kernel.org/doc/html/latest/networking/af_xdp.html> for more details.
+
+#![allow(non_camel_case_types, dead_code)]
+
+pub const AF_XDP: libc::c_int = 44;
+pub const SOL_XDP: libc::c_int = 283;
+
+// Socket options
+pub const XDP_MMAP_OFFSETS: libc::c_int = 1;
+pub const XDP_RX_RING: libc::c_int = 2;
+pub const XDP_TX_RING: libc::c_int = 3;
+pub const XDP_UMEM_REG: libc::c_int = 4;
+pub const XDP_UMEM_FILL_RING: libc::c_int = 5;
+pub const XDP_UMEM_COMPLETION_RING: libc::c_int = 6;
+
+// Page offsets for mapping each of the rings
+pub const XDP_PGOFF_RX_RING: libc::off_t = 0;
+pub const XDP_PGOFF_TX_RING: libc::off_t = 0x80000000;
+pub const XDP_UMEM_PGOFF_FILL_RING: libc::off_t = 0x100000000;
+pub const XDP_UMEM_PGOFF_COMPLETION_RING: libc::off_t = 0x180000000;
+
+// Flags for `sockaddr_xdp::sxdp_flags`
+pub const XDP_SHARED_UMEM: u16 = 1 << 0;
+pub const XDP_COPY: u16 = 1 << 1;
+pub const XDP_ZEROCOPY: u16 = 1 << 2;
+pub const XDP_USE_NEED_WAKEUP: u16 = 1 << 3;
+
+// Flags for `xdp_ring_offset::flags`
+pub const XDP_RING_NEED_WAKEUP: u32 = 1 << 0;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct sockaddr_xdp {
+    pub sxdp_family: u16,
+    pub sxdp_flags: u16,
+    pub sxdp_ifindex: u32,
+    pub sxdp_queue_id: u32,
+    pub sxdp_shared_umem_fd: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct xdp_ring_offset {
+    pub producer: u64,
+    pub consumer: u64,
+    pub desc: u64,
+    pub flags: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct xdp_mmap_offsets {
+    pub rx: xdp_ring_offset,
+    pub tx: xdp_ring_offset,
+    pub fr: xdp_ring_offset,
+    pub cr: xdp_ring_offset,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct xdp_umem_reg {
+    pub addr: u64,
+    pub len: u64,
+    pub chunk_size: u32,
+    pub headroom: u32,
+    pub flags: u32,
+    pub tx_metadata_len: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct xdp_desc {
+    pub addr: u64,
+    pub len: u32,
+    pub options: u32,
+}
+
+pub const BPF_MAP_UPDATE_ELEM: libc::c_long = 2;
+pub const BPF_ANY: u64 = 0;
+
+/// The `BPF_MAP_*_ELEM` variant of `union bpf_attr`
+#[repr(C, align(8))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct bpf_map_elem_attr {
+    pub map_fd: u32,
+    pub key: u64,
+    pub value: u64,
+    pub flags: u64,
+}
@@ -0,0 +1,69 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{io, os::unix::io::RawFd, ptr::NonNull};
+
+/// A memory mapped region which is unmapped on drop
+#[derive(Debug)]
+pub struct Mmap {
+    addr: NonNull<u8>,
+    len: usize,
+}
+
+unsafe impl Send for Mmap {}
+
+impl Mmap {
+    /// Maps an anonymous, zero-initialized region of memory
+    pub fn anonymous(len: usize) -> io::Result<Self> {
+        Self::new(
+            len,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_POPULATE,
+            -1,
+            0,
+        )
+    }
+
+    /// Maps a region of memory shared with the kernel through `fd`
+    pub fn shared(fd: RawFd, len: usize, offset: libc::off_t) -> io::Result<Self> {
+        Self::new(len, libc::MAP_SHARED | libc::MAP_POPULATE, fd, offset)
+    }
+
+    fn new(len: usize, flags: libc::c_int, fd: RawFd, offset: libc::off_t) -> io::Result<Self> {
+        let addr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                flags,
+                fd,
+                offset,
+            )
+        };
+
+        if addr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        let addr = NonNull::new(addr as *mut u8).expect("mmap should not return null");
+
+        Ok(Self { addr, len })
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.addr.as_ptr()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = libc::munmap(self.addr.as_ptr() as _, self.len);
+        }
+    }
+}
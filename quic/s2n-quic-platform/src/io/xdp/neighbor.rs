@@ -0,0 +1,64 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use s2n_quic_core::inet::{ethernet::MacAddress, IpAddress};
+use std::collections::HashMap;
+
+/// A static table of link-layer addresses for peers on the local network
+///
+/// Since the kernel's network stack is bypassed, the kernel's neighbor table isn't consulted
+/// when transmitting. Peers which aren't in the table are sent to the gateway, if one is
+/// configured.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    entries: HashMap<IpAddress, MacAddress>,
+    gateway: Option<MacAddress>,
+}
+
+impl Table {
+    /// Adds the link-layer address for a peer
+    #[inline]
+    pub fn insert(&mut self, ip: IpAddress, mac: MacAddress) {
+        self.entries.insert(ip.unmap(), mac);
+    }
+
+    /// Sets the link-layer address of the gateway for peers that aren't in the table
+    #[inline]
+    pub fn set_gateway(&mut self, mac: MacAddress) {
+        self.gateway = Some(mac);
+    }
+
+    /// Returns the link-layer address that should be used to reach `ip`
+    #[inline]
+    pub fn lookup(&self, ip: IpAddress) -> Option<MacAddress> {
+        self.entries.get(&ip.unmap()).copied().or(self.gateway)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::inet::{IpV4Address, IpV6Address};
+
+    #[test]
+    fn lookup_test() {
+        let peer = MacAddress::new([2, 0, 0, 0, 0, 2]);
+        let gateway = MacAddress::new([2, 0, 0, 0, 0, 1]);
+        let ip: IpAddress = IpV4Address::new([10, 0, 0, 2]).into();
+        let other: IpAddress = IpV4Address::new([10, 0, 0, 3]).into();
+
+        let mut table = Table::default();
+        table.insert(ip, peer);
+        assert_eq!(table.lookup(ip), Some(peer));
+        assert_eq!(table.lookup(other), None);
+
+        // IPv4-mapped addresses resolve to the same entry
+        let mapped: IpAddress =
+            IpV6Address::new([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 10, 0, 0, 2]).into();
+        assert_eq!(table.lookup(mapped), Some(peer));
+
+        table.set_gateway(gateway);
+        assert_eq!(table.lookup(other), Some(gateway));
+        assert_eq!(table.lookup(ip), Some(peer));
+    }
+}
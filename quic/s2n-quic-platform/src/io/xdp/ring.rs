@@ -0,0 +1,317 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{if_xdp, mmap::Mmap};
+use std::{
+    io,
+    os::unix::io::RawFd,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+/// A single-producer, single-consumer ring shared with the kernel
+///
+/// The producer and consumer indexes are free-running and wrap at `u32::MAX`. Each side caches
+/// the other side's index to avoid reading the shared cache line on every operation.
+#[derive(Debug)]
+struct Ring<T> {
+    producer: *const AtomicU32,
+    consumer: *const AtomicU32,
+    flags: *const AtomicU32,
+    descriptors: *mut T,
+    mask: u32,
+    size: u32,
+    cached_producer: u32,
+    cached_consumer: u32,
+    // keeps the mapping alive for as long as the pointers above are in use
+    _mmap: Mmap,
+}
+
+unsafe impl<T: Send> Send for Ring<T> {}
+
+impl<T> Ring<T> {
+    fn new(
+        fd: RawFd,
+        offsets: &if_xdp::xdp_ring_offset,
+        size: u32,
+        page_offset: libc::off_t,
+    ) -> io::Result<Self> {
+        debug_assert!(size.is_power_of_two(), "ring sizes must be a power of two");
+
+        let len = offsets.desc as usize + size as usize * core::mem::size_of::<T>();
+        let mmap = Mmap::shared(fd, len, page_offset)?;
+        Ok(Self::from_mmap(mmap, offsets, size))
+    }
+
+    fn from_mmap(mmap: Mmap, offsets: &if_xdp::xdp_ring_offset, size: u32) -> Self {
+        let base = mmap.as_ptr();
+
+        unsafe {
+            // Safety: the kernel provides the offsets for each field within the mapping
+            Self {
+                producer: base.add(offsets.producer as usize) as *const AtomicU32,
+                consumer: base.add(offsets.consumer as usize) as *const AtomicU32,
+                flags: base.add(offsets.flags as usize) as *const AtomicU32,
+                descriptors: base.add(offsets.desc as usize) as *mut T,
+                mask: size - 1,
+                size,
+                cached_producer: 0,
+                cached_consumer: 0,
+                _mmap: mmap,
+            }
+        }
+    }
+
+    #[inline]
+    fn producer(&self) -> &AtomicU32 {
+        unsafe { &*self.producer }
+    }
+
+    #[inline]
+    fn consumer(&self) -> &AtomicU32 {
+        unsafe { &*self.consumer }
+    }
+
+    #[inline]
+    fn descriptor(&self, index: u32) -> *mut T {
+        unsafe {
+            // Safety: the index is masked to the size of the ring
+            self.descriptors.add((index & self.mask) as usize)
+        }
+    }
+}
+
+/// The side of a ring which the application writes to, i.e. the fill and TX rings
+#[derive(Debug)]
+pub struct Producer<T>(Ring<T>);
+
+impl<T: Copy> Producer<T> {
+    pub fn new(
+        fd: RawFd,
+        offsets: &if_xdp::xdp_ring_offset,
+        size: u32,
+        page_offset: libc::off_t,
+    ) -> io::Result<Self> {
+        let ring = Ring::new(fd, offsets, size, page_offset)?;
+        Ok(Self::from_ring(ring))
+    }
+
+    fn from_ring(mut ring: Ring<T>) -> Self {
+        ring.cached_producer = ring.producer().load(Ordering::Relaxed);
+        ring.cached_consumer = ring
+            .consumer()
+            .load(Ordering::Relaxed)
+            .wrapping_add(ring.size);
+        Self(ring)
+    }
+
+    /// Returns the number of slots which can be written, up to `wanted`
+    #[inline]
+    pub fn acquire(&mut self, wanted: u32) -> u32 {
+        let ring = &mut self.0;
+        let free = ring.cached_consumer.wrapping_sub(ring.cached_producer);
+
+        if free >= wanted {
+            return wanted;
+        }
+
+        // refresh the consumer index from the kernel
+        ring.cached_consumer = ring
+            .consumer()
+            .load(Ordering::Acquire)
+            .wrapping_add(ring.size);
+        ring.cached_consumer
+            .wrapping_sub(ring.cached_producer)
+            .min(wanted)
+    }
+
+    /// Writes a value to the slot at `offset` from the current producer index
+    ///
+    /// The slot must have been acquired with [`Self::acquire`].
+    #[inline]
+    pub fn write(&mut self, offset: u32, value: T) {
+        let ring = &mut self.0;
+        debug_assert!(offset < ring.cached_consumer.wrapping_sub(ring.cached_producer));
+        unsafe {
+            // Safety: the slot is owned by the producer until it is released
+            ring.descriptor(ring.cached_producer.wrapping_add(offset))
+                .write(value);
+        }
+    }
+
+    /// Makes `count` written slots available to the kernel
+    #[inline]
+    pub fn release(&mut self, count: u32) {
+        let ring = &mut self.0;
+        ring.cached_producer = ring.cached_producer.wrapping_add(count);
+        ring.producer()
+            .store(ring.cached_producer, Ordering::Release);
+    }
+
+    /// Returns `true` if the kernel needs to be notified of new entries
+    #[inline]
+    pub fn needs_wakeup(&self) -> bool {
+        let flags = unsafe { &*self.0.flags }.load(Ordering::Relaxed);
+        flags & if_xdp::XDP_RING_NEED_WAKEUP != 0
+    }
+}
+
+/// The side of a ring which the application reads from, i.e. the RX and completion rings
+#[derive(Debug)]
+pub struct Consumer<T>(Ring<T>);
+
+impl<T: Copy> Consumer<T> {
+    pub fn new(
+        fd: RawFd,
+        offsets: &if_xdp::xdp_ring_offset,
+        size: u32,
+        page_offset: libc::off_t,
+    ) -> io::Result<Self> {
+        let ring = Ring::new(fd, offsets, size, page_offset)?;
+        Ok(Self::from_ring(ring))
+    }
+
+    fn from_ring(mut ring: Ring<T>) -> Self {
+        ring.cached_producer = ring.producer().load(Ordering::Relaxed);
+        ring.cached_consumer = ring.consumer().load(Ordering::Relaxed);
+        Self(ring)
+    }
+
+    /// Returns the number of slots which can be read, up to `wanted`
+    #[inline]
+    pub fn acquire(&mut self, wanted: u32) -> u32 {
+        let ring = &mut self.0;
+        let available = ring.cached_producer.wrapping_sub(ring.cached_consumer);
+
+        if available >= wanted {
+            return wanted;
+        }
+
+        // refresh the producer index from the kernel
+        ring.cached_producer = ring.producer().load(Ordering::Acquire);
+        ring.cached_producer
+            .wrapping_sub(ring.cached_consumer)
+            .min(wanted)
+    }
+
+    /// Reads the value in the slot at `offset` from the current consumer index
+    ///
+    /// The slot must have been acquired with [`Self::acquire`].
+    #[inline]
+    pub fn read(&self, offset: u32) -> T {
+        let ring = &self.0;
+        debug_assert!(offset < ring.cached_producer.wrapping_sub(ring.cached_consumer));
+        unsafe {
+            // Safety: the slot is owned by the consumer until it is released
+            ring.descriptor(ring.cached_consumer.wrapping_add(offset))
+                .read()
+        }
+    }
+
+    /// Returns `count` read slots to the kernel
+    #[inline]
+    pub fn release(&mut self, count: u32) {
+        let ring = &mut self.0;
+        ring.cached_consumer = ring.cached_consumer.wrapping_add(count);
+        ring.consumer()
+            .store(ring.cached_consumer, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: u32 = 4;
+
+    /// Maps a ring in anonymous memory, with the indexes starting at `index`
+    ///
+    /// The test plays the part of the kernel by updating the other side's index directly.
+    fn ring<T>(index: u32) -> Ring<T> {
+        let offsets = if_xdp::xdp_ring_offset {
+            producer: 0,
+            consumer: 64,
+            flags: 128,
+            desc: 192,
+        };
+        let len = offsets.desc as usize + SIZE as usize * core::mem::size_of::<T>();
+        let ring = Ring::from_mmap(Mmap::anonymous(len).unwrap(), &offsets, SIZE);
+        ring.producer().store(index, Ordering::Relaxed);
+        ring.consumer().store(index, Ordering::Relaxed);
+        ring
+    }
+
+    #[test]
+    fn producer_test() {
+        for index in [0, u32::MAX - 1] {
+            let mut producer = Producer::<u64>::from_ring(ring(index));
+
+            assert_eq!(producer.acquire(8), SIZE);
+            for offset in 0..SIZE {
+                producer.write(offset, offset as u64);
+            }
+            producer.release(SIZE);
+            assert_eq!(
+                producer.0.producer().load(Ordering::Relaxed),
+                index.wrapping_add(SIZE)
+            );
+
+            // the ring is full until the kernel consumes entries
+            assert_eq!(producer.acquire(1), 0);
+
+            for offset in 0..SIZE {
+                let value = unsafe { producer.0.descriptor(index.wrapping_add(offset)).read() };
+                assert_eq!(value, offset as u64);
+            }
+
+            producer
+                .0
+                .consumer()
+                .store(index.wrapping_add(2), Ordering::Release);
+            assert_eq!(producer.acquire(SIZE), 2);
+        }
+    }
+
+    #[test]
+    fn consumer_test() {
+        for index in [0, u32::MAX - 1] {
+            let mut consumer = Consumer::<u64>::from_ring(ring(index));
+
+            assert_eq!(consumer.acquire(SIZE), 0);
+
+            for offset in 0..3 {
+                unsafe {
+                    consumer
+                        .0
+                        .descriptor(index.wrapping_add(offset))
+                        .write(offset as u64 + 10);
+                }
+            }
+            consumer
+                .0
+                .producer()
+                .store(index.wrapping_add(3), Ordering::Release);
+
+            assert_eq!(consumer.acquire(8), 3);
+            for offset in 0..3 {
+                assert_eq!(consumer.read(offset), offset as u64 + 10);
+            }
+
+            consumer.release(2);
+            assert_eq!(
+                consumer.0.consumer().load(Ordering::Relaxed),
+                index.wrapping_add(2)
+            );
+            assert_eq!(consumer.acquire(8), 1);
+            assert_eq!(consumer.read(0), 12);
+        }
+    }
+
+    #[test]
+    fn needs_wakeup_test() {
+        let producer = Producer::<u64>::from_ring(ring(0));
+        assert!(!producer.needs_wakeup());
+
+        unsafe { &*producer.0.flags }.store(if_xdp::XDP_RING_NEED_WAKEUP, Ordering::Relaxed);
+        assert!(producer.needs_wakeup());
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    if_xdp::{self, xdp_desc},
+    mmap::Mmap,
+    ring::{Consumer, Producer},
+};
+use std::{
+    io,
+    os::unix::io::{AsRawFd, RawFd},
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    pub ifindex: u32,
+    pub queue_id: u32,
+    pub frame_size: u32,
+    pub frame_count: u32,
+    pub ring_size: u32,
+    pub bind_flags: u16,
+}
+
+/// An `AF_XDP` socket with its own UMEM and rings
+#[derive(Debug)]
+pub struct Socket {
+    /// Frames are handed to the kernel through the fill ring to receive into
+    pub fill: Producer<u64>,
+    /// Frames which have finished transmitting are returned through the completion ring
+    pub completion: Consumer<u64>,
+    pub rx: Consumer<xdp_desc>,
+    pub tx: Producer<xdp_desc>,
+    umem: Mmap,
+    frame_size: u32,
+    fd: RawFd,
+}
+
+impl Socket {
+    pub fn open(config: &Config) -> io::Result<Self> {
+        let fd = libc!(socket(
+            if_xdp::AF_XDP,
+            libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+            0
+        ))?;
+
+        // close the socket if any of the remaining steps fail
+        struct Guard(RawFd);
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                if self.0 >= 0 {
+                    let _ = libc!(close(self.0));
+                }
+            }
+        }
+        let mut guard = Guard(fd);
+
+        let umem = Mmap::anonymous(config.frame_size as usize * config.frame_count as usize)?;
+
+        let reg = if_xdp::xdp_umem_reg {
+            addr: umem.as_ptr() as u64,
+            len: umem.len() as u64,
+            chunk_size: config.frame_size,
+            ..Default::default()
+        };
+        setsockopt(fd, if_xdp::XDP_UMEM_REG, &reg)?;
+
+        for opt in [
+            if_xdp::XDP_UMEM_FILL_RING,
+            if_xdp::XDP_UMEM_COMPLETION_RING,
+            if_xdp::XDP_RX_RING,
+            if_xdp::XDP_TX_RING,
+        ] {
+            setsockopt(fd, opt, &config.ring_size)?;
+        }
+
+        let mut offsets = if_xdp::xdp_mmap_offsets::default();
+        let mut len = core::mem::size_of_val(&offsets) as libc::socklen_t;
+        libc!(getsockopt(
+            fd,
+            if_xdp::SOL_XDP,
+            if_xdp::XDP_MMAP_OFFSETS,
+            &mut offsets as *mut _ as _,
+            &mut len
+        ))?;
+
+        let size = config.ring_size;
+        let fill = Producer::new(fd, &offsets.fr, size, if_xdp::XDP_UMEM_PGOFF_FILL_RING)?;
+        let completion = Consumer::new(
+            fd,
+            &offsets.cr,
+            size,
+            if_xdp::XDP_UMEM_PGOFF_COMPLETION_RING,
+        )?;
+        let rx = Consumer::new(fd, &offsets.rx, size, if_xdp::XDP_PGOFF_RX_RING)?;
+        let tx = Producer::new(fd, &offsets.tx, size, if_xdp::XDP_PGOFF_TX_RING)?;
+
+        let addr = if_xdp::sockaddr_xdp {
+            sxdp_family: if_xdp::AF_XDP as _,
+            sxdp_flags: config.bind_flags | if_xdp::XDP_USE_NEED_WAKEUP,
+            sxdp_ifindex: config.ifindex,
+            sxdp_queue_id: config.queue_id,
+            sxdp_shared_umem_fd: 0,
+        };
+        libc!(bind(
+            fd,
+            &addr as *const _ as _,
+            core::mem::size_of_val(&addr) as _
+        ))?;
+
+        guard.0 = -1;
+
+        Ok(Self {
+            fill,
+            completion,
+            rx,
+            tx,
+            umem,
+            frame_size: config.frame_size,
+            fd,
+        })
+    }
+
+    /// Registers the socket in an `XSKMAP` so the XDP program can redirect frames to it
+    pub fn register(&self, xsk_map_fd: RawFd, queue_id: u32) -> io::Result<()> {
+        let key = queue_id;
+        let value = self.fd as u32;
+        let attr = if_xdp::bpf_map_elem_attr {
+            map_fd: xsk_map_fd as u32,
+            key: &key as *const _ as u64,
+            value: &value as *const _ as u64,
+            flags: if_xdp::BPF_ANY,
+        };
+
+        let res = unsafe {
+            libc::syscall(
+                libc::SYS_bpf,
+                if_xdp::BPF_MAP_UPDATE_ELEM,
+                &attr as *const _,
+                core::mem::size_of_val(&attr),
+            )
+        };
+
+        if res < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of frames in the UMEM
+    #[inline]
+    pub fn frame_count(&self) -> u32 {
+        (self.umem.len() / self.frame_size as usize) as u32
+    }
+
+    /// Returns the UMEM address of the frame at `index`
+    #[inline]
+    pub fn frame_addr(&self, index: u32) -> u64 {
+        index as u64 * self.frame_size as u64
+    }
+
+    /// Returns the frame containing the UMEM address
+    ///
+    /// Descriptors can point anywhere in a frame when the kernel reserves headroom, so the
+    /// returned slice starts at `addr` and extends to the end of the frame.
+    #[inline]
+    pub fn frame_mut(&mut self, addr: u64) -> &mut [u8] {
+        let frame_size = self.frame_size as u64;
+        let end = (addr - addr % frame_size + frame_size) as usize;
+        let addr = addr as usize;
+        assert!(end <= self.umem.len(), "address outside of the UMEM");
+
+        unsafe {
+            // Safety: the range was checked against the length of the mapping and frames are
+            //         only accessed while they are owned by the application
+            core::slice::from_raw_parts_mut(self.umem.as_ptr().add(addr), end - addr)
+        }
+    }
+
+    /// Notifies the kernel of new entries in the TX ring
+    #[inline]
+    pub fn kick_tx(&self) -> io::Result<()> {
+        match libc!(sendto(
+            self.fd,
+            core::ptr::null(),
+            0,
+            libc::MSG_DONTWAIT,
+            core::ptr::null(),
+            0
+        )) {
+            Ok(_) => Ok(()),
+            // the kernel is still processing the previous entries or is out of completion slots
+            Err(err)
+                if matches!(
+                    err.raw_os_error(),
+                    Some(libc::EAGAIN) | Some(libc::EBUSY) | Some(libc::ENOBUFS)
+                ) =>
+            {
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl AsRawFd for Socket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        let _ = libc!(close(self.fd));
+    }
+}
+
+fn setsockopt<T>(fd: RawFd, opt: libc::c_int, value: &T) -> io::Result<()> {
+    libc!(setsockopt(
+        fd,
+        if_xdp::SOL_XDP,
+        opt,
+        value as *const _ as _,
+        core::mem::size_of::<T>() as _
+    ))?;
+    Ok(())
+}
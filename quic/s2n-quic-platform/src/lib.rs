@@ -16,4 +16,6 @@ pub mod features;
 pub mod io;
 pub mod message;
 pub mod socket;
+#[cfg(feature = "std")]
+pub mod syscall;
 pub mod time;
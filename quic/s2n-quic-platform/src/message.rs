@@ -18,7 +18,7 @@ pub mod simple;
 
 use core::ffi::c_void;
 use s2n_quic_core::{
-    inet::{ExplicitCongestionNotification, SocketAddress},
+    inet::{Dscp, ExplicitCongestionNotification, SocketAddress},
     io::tx,
     path,
 };
@@ -29,11 +29,19 @@ pub trait Message {
 
     const SUPPORTS_GSO: bool;
 
+    /// Set to `true` if the message can carry a transmit time for the `SO_TXTIME` socket option
+    const SUPPORTS_TXTIME: bool = false;
+
     /// Returns the ECN values for the message
     fn ecn(&self) -> ExplicitCongestionNotification;
 
-    /// Sets the ECN values for the message
-    fn set_ecn(&mut self, ecn: ExplicitCongestionNotification, remote_address: &SocketAddress);
+    /// Sets the DSCP and ECN values for the message
+    fn set_traffic_class(
+        &mut self,
+        dscp: Dscp,
+        ecn: ExplicitCongestionNotification,
+        remote_address: &SocketAddress,
+    );
 
     /// Returns the `SocketAddress` for the message
     fn remote_address(&self) -> Option<SocketAddress>;
@@ -84,6 +92,12 @@ pub trait Message {
         panic!("cannot use GSO on the current platform");
     }
 
+    /// Sets the earliest time the message should be transmitted, in nanoseconds of
+    /// `CLOCK_MONOTONIC`
+    fn set_txtime(&mut self, _txtime: u64) {
+        panic!("cannot use SO_TXTIME on the current platform");
+    }
+
     /// Resets the message for future use
     ///
     /// # Safety
@@ -4,11 +4,11 @@
 #![allow(clippy::unnecessary_cast)] // some platforms encode lengths as `u32` so we cast everything to be safe
 
 use core::mem::{align_of, size_of};
-use s2n_quic_core::inet::{AncillaryData, ExplicitCongestionNotification};
+use s2n_quic_core::inet::{AncillaryData, Dscp, ExplicitCongestionNotification};
 
 /// The maximum number of bytes allocated for cmsg data
 ///
-/// This should be enough for UDP_SEGMENT + IP_TOS + IP_PKTINFO. It may need to be increased
+/// This should be enough for UDP_SEGMENT + UDP_GRO + IP_TOS + IP_PKTINFO. It may need to be increased
 /// to allow for future control messages.
 pub const MAX_LEN: usize = 128;
 
@@ -20,6 +20,12 @@ fn max_len_test() {
         // UDP_SEGMENT
         len += libc::CMSG_LEN(size_of::<u16>() as _) as usize;
 
+        // UDP_GRO
+        #[cfg(s2n_quic_platform_gro)]
+        {
+            len += libc::CMSG_LEN(size_of::<libc::c_int>() as _) as usize;
+        }
+
         // IP_TOS
         len += libc::CMSG_LEN(size_of::<libc::c_int>() as _) as usize;
 
@@ -30,6 +36,12 @@ fn max_len_test() {
                 size_of::<libc::in_pktinfo>().max(size_of::<libc::in6_pktinfo>()) as _,
             ) as usize;
         }
+
+        // SCM_TXTIME
+        #[cfg(s2n_quic_platform_txtime)]
+        {
+            len += libc::CMSG_LEN(size_of::<u64>() as _) as usize;
+        }
     }
 
     // We use the MAX_LEN to determine if the cmsg has been populated at all so the actual
@@ -118,21 +130,23 @@ pub fn decode(msghdr: &libc::msghdr) -> AncillaryData {
                 | (libc::IPPROTO_IP, libc::IP_RECVTOS, cmsg_len)
                     if cmsg_len == libc::CMSG_LEN(mem::size_of::<u8>() as _) as usize =>
                 {
-                    result.ecn = ExplicitCongestionNotification::new(decode_value::<u8>(cmsg));
+                    let tos = decode_value::<u8>(cmsg);
+                    result.ecn = ExplicitCongestionNotification::new(tos);
+                    result.dscp = Dscp::from_traffic_class(tos);
                 }
                 (libc::IPPROTO_IP, libc::IP_TOS, cmsg_len)
                 | (libc::IPPROTO_IP, libc::IP_RECVTOS, cmsg_len)
                     if cmsg_len == libc::CMSG_LEN(mem::size_of::<libc::c_int>() as _) as usize =>
                 {
                     // IP_TOS cmsgs should be 1 byte, but occasionally are reported as 4 bytes
-                    result.ecn = ExplicitCongestionNotification::new(decode_value::<libc::c_int>(
-                        cmsg,
-                    ) as u8);
+                    let tos = decode_value::<libc::c_int>(cmsg) as u8;
+                    result.ecn = ExplicitCongestionNotification::new(tos);
+                    result.dscp = Dscp::from_traffic_class(tos);
                 }
                 (libc::IPPROTO_IPV6, libc::IPV6_TCLASS, _) => {
-                    result.ecn = ExplicitCongestionNotification::new(decode_value::<libc::c_int>(
-                        cmsg,
-                    ) as u8);
+                    let traffic_class = decode_value::<libc::c_int>(cmsg) as u8;
+                    result.ecn = ExplicitCongestionNotification::new(traffic_class);
+                    result.dscp = Dscp::from_traffic_class(traffic_class);
                 }
                 #[cfg(s2n_quic_platform_pktinfo)]
                 (libc::IPPROTO_IP, libc::IP_PKTINFO, _) => {
@@ -174,6 +188,14 @@ pub fn decode(msghdr: &libc::msghdr) -> AncillaryData {
                     // ignore GSO settings when reading
                     continue;
                 }
+                #[cfg(s2n_quic_platform_gro)]
+                (libc::SOL_UDP, libc::UDP_GRO, _) => {
+                    // the kernel reports the size of each coalesced segment
+                    let segment_size = decode_value::<libc::c_int>(cmsg);
+                    if segment_size > 0 {
+                        result.segment_size = Some(segment_size as usize);
+                    }
+                }
                 (level, ty, len) if cfg!(test) => {
                     // if we're getting an unexpected cmsg we should know about it in testing
                     panic!("unexpected cmsghdr {{ level: {level}, type: {ty}, len: {len} }}");
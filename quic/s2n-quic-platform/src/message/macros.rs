@@ -10,16 +10,24 @@ macro_rules! impl_message_delegate {
 
             const SUPPORTS_GSO: bool = <$field_ty as $crate::message::Message>::SUPPORTS_GSO;
 
+            const SUPPORTS_TXTIME: bool = <$field_ty as $crate::message::Message>::SUPPORTS_TXTIME;
+
             fn ecn(&self) -> ExplicitCongestionNotification {
                 $crate::message::Message::ecn(&self.$field)
             }
 
-            fn set_ecn(
+            fn set_traffic_class(
                 &mut self,
+                dscp: s2n_quic_core::inet::Dscp,
                 ecn: ExplicitCongestionNotification,
                 remote_address: &SocketAddress,
             ) {
-                $crate::message::Message::set_ecn(&mut self.$field, ecn, remote_address)
+                $crate::message::Message::set_traffic_class(
+                    &mut self.$field,
+                    dscp,
+                    ecn,
+                    remote_address,
+                )
             }
 
             fn remote_address(&self) -> Option<SocketAddress> {
@@ -50,6 +58,10 @@ macro_rules! impl_message_delegate {
                 $crate::message::Message::set_segment_size(&mut self.$field, size)
             }
 
+            fn set_txtime(&mut self, txtime: u64) {
+                $crate::message::Message::set_txtime(&mut self.$field, txtime)
+            }
+
             unsafe fn reset(&mut self, mtu: usize) {
                 $crate::message::Message::reset(&mut self.$field, mtu)
             }
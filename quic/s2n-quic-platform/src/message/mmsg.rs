@@ -9,7 +9,7 @@ use alloc::vec::Vec;
 use core::{fmt, mem::zeroed};
 use libc::mmsghdr;
 use s2n_quic_core::{
-    inet::{datagram, ExplicitCongestionNotification, SocketAddress},
+    inet::{datagram, Dscp, ExplicitCongestionNotification, SocketAddress},
     io::{rx, tx},
     path,
 };
@@ -52,8 +52,13 @@ impl MessageTrait for mmsghdr {
     }
 
     #[inline]
-    fn set_ecn(&mut self, ecn: ExplicitCongestionNotification, remote_address: &SocketAddress) {
-        self.msg_hdr.set_ecn(ecn, remote_address)
+    fn set_traffic_class(
+        &mut self,
+        dscp: Dscp,
+        ecn: ExplicitCongestionNotification,
+        remote_address: &SocketAddress,
+    ) {
+        self.msg_hdr.set_traffic_class(dscp, ecn, remote_address)
     }
 
     #[inline]
@@ -213,7 +218,7 @@ impl tx::Entry for Message {
 
         let handle = *message.path_handle();
         handle.update_msg_hdr(&mut self.0.msg_hdr);
-        self.set_ecn(message.ecn(), &handle.remote_address.0);
+        self.set_traffic_class(message.dscp(), message.ecn(), &handle.remote_address.0);
 
         Ok(len)
     }
@@ -237,15 +242,113 @@ impl rx::Entry for Message {
         &mut self,
         local_address: &path::LocalAddress,
     ) -> Option<(datagram::Header<Self::Handle>, &mut [u8])> {
-        let mut header = msg::Message::header(&self.0.msg_hdr)?;
+        let (header, _ancillary_data) = msg::Message::rx_header(&self.0.msg_hdr, local_address)?;
+        let payload = self.payload_mut();
+        Some((header, payload))
+    }
 
-        if cfg!(s2n_quic_platform_pktinfo) {
-            header.path.local_address.set_port(local_address.port());
-        } else {
-            header.path.local_address = *local_address;
+    #[inline]
+    fn read_segments<F>(&mut self, local_address: &path::LocalAddress, on_datagram: F)
+    where
+        F: FnMut(&datagram::Header<Self::Handle>, &mut [u8]),
+    {
+        if let Some((header, ancillary_data)) =
+            msg::Message::rx_header(&self.0.msg_hdr, local_address)
+        {
+            msg::Message::for_each_segment(
+                &header,
+                ancillary_data,
+                self.payload_mut(),
+                on_datagram,
+            );
         }
+    }
+}
 
-        let payload = self.payload_mut();
-        Some((header, payload))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bolero::check;
+    use bolero_generator::*;
+    use core::mem::size_of;
+    use libc::{iovec, sockaddr_in6};
+    use s2n_quic_core::io::rx::Entry as _;
+
+    #[test]
+    #[cfg(s2n_quic_platform_gro)]
+    fn read_segments_test() {
+        use crate::message::cmsg::{self, Encoder};
+
+        check!()
+            .with_generator((gen::<Handle>(), 1..=64usize, 1..=4096usize))
+            .cloned()
+            .for_each(|(handle, segment_size, payload_len)| {
+                let mut mmsghdr = unsafe { zeroed::<mmsghdr>() };
+
+                let mut msgname = unsafe { zeroed::<sockaddr_in6>() };
+                mmsghdr.msg_hdr.msg_name = &mut msgname as *mut _ as *mut _;
+                mmsghdr.msg_hdr.msg_namelen = size_of::<sockaddr_in6>() as _;
+
+                let mut iovec = unsafe { zeroed::<iovec>() };
+                let mut iovec_buf = [0u8; 4096];
+                iovec.iov_len = payload_len as _;
+                iovec.iov_base = (&mut iovec_buf[0]) as *mut u8 as _;
+                mmsghdr.msg_hdr.msg_iov = &mut iovec;
+                // recvmmsg reports the received length in `msg_len`
+                mmsghdr.msg_len = payload_len as _;
+
+                let mut cmsg_buf = [0u8; cmsg::MAX_LEN];
+                mmsghdr.msg_hdr.msg_controllen = cmsg_buf.len() as _;
+                mmsghdr.msg_hdr.msg_control = (&mut cmsg_buf[0]) as *mut u8 as _;
+
+                let mut message = Message(mmsghdr);
+
+                handle.update_msg_hdr(&mut message.0.msg_hdr);
+                message.0.msg_hdr.encode_cmsg(
+                    libc::SOL_UDP,
+                    libc::UDP_GRO,
+                    segment_size as libc::c_int,
+                );
+
+                let mut segments = vec![];
+                message.read_segments(&handle.local_address, |header, payload| {
+                    assert_eq!(header.path.remote_address, handle.remote_address);
+                    segments.push(payload.len());
+                });
+
+                let expected: Vec<_> = (0..payload_len)
+                    .step_by(segment_size)
+                    .map(|offset| (payload_len - offset).min(segment_size))
+                    .collect();
+                assert_eq!(segments, expected);
+            });
+    }
+
+    #[test]
+    fn read_test() {
+        check!()
+            .with_generator((gen::<Handle>(), 1..=4096usize))
+            .cloned()
+            .for_each(|(handle, payload_len)| {
+                let mut mmsghdr = unsafe { zeroed::<mmsghdr>() };
+
+                let mut msgname = unsafe { zeroed::<sockaddr_in6>() };
+                mmsghdr.msg_hdr.msg_name = &mut msgname as *mut _ as *mut _;
+                mmsghdr.msg_hdr.msg_namelen = size_of::<sockaddr_in6>() as _;
+
+                let mut iovec = unsafe { zeroed::<iovec>() };
+                let mut iovec_buf = [0u8; 4096];
+                iovec.iov_len = payload_len as _;
+                iovec.iov_base = (&mut iovec_buf[0]) as *mut u8 as _;
+                mmsghdr.msg_hdr.msg_iov = &mut iovec;
+                mmsghdr.msg_len = payload_len as _;
+
+                let mut message = Message(mmsghdr);
+                handle.update_msg_hdr(&mut message.0.msg_hdr);
+
+                let (header, payload) = message.read(&handle.local_address).unwrap();
+                assert_eq!(header.path.remote_address, handle.remote_address);
+                assert_eq!(payload.len(), payload_len);
+            });
     }
 }
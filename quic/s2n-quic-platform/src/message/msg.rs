@@ -11,7 +11,7 @@ use core::{
 use libc::{c_void, iovec, msghdr, sockaddr_in, sockaddr_in6, AF_INET, AF_INET6};
 use s2n_quic_core::{
     inet::{
-        datagram, AncillaryData, ExplicitCongestionNotification, IpV4Address, IpV6Address,
+        datagram, AncillaryData, Dscp, ExplicitCongestionNotification, IpV4Address, IpV6Address,
         SocketAddress, SocketAddressV4, SocketAddressV6,
     },
     io::{rx, tx},
@@ -114,6 +114,25 @@ impl path::Handle for Handle {
     fn strict_eq(&self, other: &Self) -> bool {
         PartialEq::eq(self, other)
     }
+
+    #[inline]
+    fn with_local_address(&self, mut local_address: LocalAddress) -> Option<Self> {
+        // the local address can only be selected if the OS supports it
+        if !cfg!(s2n_quic_platform_pktinfo) {
+            return None;
+        }
+
+        // an unspecified port keeps the current port. Any other port is sent from a socket the
+        // IO provider binds to it.
+        if local_address.port() == 0 {
+            local_address.set_port(self.local_address.port());
+        }
+
+        Some(Self {
+            remote_address: self.remote_address,
+            local_address,
+        })
+    }
 }
 
 impl_message_delegate!(Message, 0, msghdr);
@@ -160,6 +179,51 @@ impl Message {
 
     #[inline]
     pub(crate) fn header(msghdr: &msghdr) -> Option<datagram::Header<Handle>> {
+        let (header, _ancillary_data) = Self::header_with_ancillary_data(msghdr)?;
+        Some(header)
+    }
+
+    /// Reads the header of a received message, filling in the local address of the socket
+    #[inline]
+    pub(crate) fn rx_header(
+        msghdr: &msghdr,
+        local_address: &path::LocalAddress,
+    ) -> Option<(datagram::Header<Handle>, AncillaryData)> {
+        let (mut header, ancillary_data) = Self::header_with_ancillary_data(msghdr)?;
+
+        // only copy the port if we are told the IP address
+        if cfg!(s2n_quic_platform_pktinfo) {
+            header.path.local_address.set_port(local_address.port());
+        } else {
+            header.path.local_address = *local_address;
+        }
+
+        Some((header, ancillary_data))
+    }
+
+    /// Calls `on_datagram` for each datagram that was coalesced into the payload with GRO
+    #[inline]
+    pub(crate) fn for_each_segment<F>(
+        header: &datagram::Header<Handle>,
+        ancillary_data: AncillaryData,
+        payload: &mut [u8],
+        mut on_datagram: F,
+    ) where
+        F: FnMut(&datagram::Header<Handle>, &mut [u8]),
+    {
+        if let Some(segment_size) = ancillary_data.segment_size {
+            for segment in payload.chunks_mut(segment_size) {
+                on_datagram(header, segment);
+            }
+        } else {
+            on_datagram(header, payload);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn header_with_ancillary_data(
+        msghdr: &msghdr,
+    ) -> Option<(datagram::Header<Handle>, AncillaryData)> {
         let addr = msghdr.remote_address()?;
         let mut path = Handle::from_remote_address(addr.into());
 
@@ -168,7 +232,7 @@ impl Message {
 
         path.with_ancillary_data(ancillary_data);
 
-        Some(datagram::Header { path, ecn })
+        Some((datagram::Header { path, ecn }, ancillary_data))
     }
 }
 
@@ -176,6 +240,7 @@ impl MessageTrait for msghdr {
     type Handle = Handle;
 
     const SUPPORTS_GSO: bool = cfg!(s2n_quic_platform_gso);
+    const SUPPORTS_TXTIME: bool = cfg!(s2n_quic_platform_txtime);
 
     #[inline]
     fn ecn(&self) -> ExplicitCongestionNotification {
@@ -184,12 +249,18 @@ impl MessageTrait for msghdr {
     }
 
     #[inline]
-    fn set_ecn(&mut self, ecn: ExplicitCongestionNotification, remote_address: &SocketAddress) {
-        if ecn == ExplicitCongestionNotification::NotEct {
+    fn set_traffic_class(
+        &mut self,
+        dscp: Dscp,
+        ecn: ExplicitCongestionNotification,
+        remote_address: &SocketAddress,
+    ) {
+        if ecn == ExplicitCongestionNotification::NotEct && !dscp.is_set() {
             return;
         }
 
-        let ecn = ecn as libc::c_int;
+        // the DSCP occupies the upper 6 bits of the field and ECN the lower 2 bits
+        let traffic_class = (dscp.as_u8() << 2 | ecn as u8) as libc::c_int;
 
         // the remote address needs to be unmapped in order to set the appropriate cmsg
         match remote_address.unmap() {
@@ -197,11 +268,13 @@ impl MessageTrait for msghdr {
                 // FreeBSD uses an unsigned_char for IP_TOS
                 // see https://svnweb.freebsd.org/base/stable/8/sys/netinet/ip_input.c?view=markup&pathrev=247944#l1716
                 #[cfg(target_os = "freebsd")]
-                let ecn = ecn as libc::c_uchar;
+                let traffic_class = traffic_class as libc::c_uchar;
 
-                self.encode_cmsg(libc::IPPROTO_IP, libc::IP_TOS, ecn)
+                self.encode_cmsg(libc::IPPROTO_IP, libc::IP_TOS, traffic_class)
+            }
+            SocketAddress::IpV6(_) => {
+                self.encode_cmsg(libc::IPPROTO_IPV6, libc::IPV6_TCLASS, traffic_class)
             }
-            SocketAddress::IpV6(_) => self.encode_cmsg(libc::IPPROTO_IPV6, libc::IPV6_TCLASS, ecn),
         };
     }
 
@@ -267,7 +340,7 @@ impl MessageTrait for msghdr {
 
     #[inline]
     fn can_gso<M: tx::Message<Handle = Self::Handle>>(&self, other: &mut M) -> bool {
-        if let Some(header) = Message::header(self) {
+        if let Some((header, ancillary_data)) = Message::header_with_ancillary_data(self) {
             let mut other_handle = *other.path_handle();
 
             // when reading the header back from the msghdr, we don't know the port
@@ -277,7 +350,9 @@ impl MessageTrait for msghdr {
             // check the path handles match
             header.path.strict_eq(&other_handle) &&
                 // check the ECN markings match
-                header.ecn == other.ecn()
+                header.ecn == other.ecn() &&
+                // check the DSCP markings match
+                ancillary_data.dscp == other.dscp()
         } else {
             false
         }
@@ -290,6 +365,13 @@ impl MessageTrait for msghdr {
         self.encode_cmsg(libc::SOL_UDP, libc::UDP_SEGMENT, size as SegmentType);
     }
 
+    #[cfg(s2n_quic_platform_txtime)]
+    #[inline]
+    fn set_txtime(&mut self, txtime: u64) {
+        // encode the bytes rather than the u64 since the cmsghdr may have a smaller alignment
+        self.encode_cmsg(libc::SOL_SOCKET, libc::SCM_TXTIME, txtime.to_ne_bytes());
+    }
+
     #[inline]
     unsafe fn reset(&mut self, mtu: usize) {
         // reset the payload
@@ -531,7 +613,7 @@ impl tx::Entry for Message {
 
         let handle = *message.path_handle();
         handle.update_msg_hdr(&mut self.0);
-        self.set_ecn(message.ecn(), &handle.remote_address.0);
+        self.set_traffic_class(message.dscp(), message.ecn(), &handle.remote_address.0);
 
         Ok(len)
     }
@@ -555,18 +637,20 @@ impl rx::Entry for Message {
         &mut self,
         local_address: &path::LocalAddress,
     ) -> Option<(datagram::Header<Self::Handle>, &mut [u8])> {
-        let mut header = Self::header(&self.0)?;
-
-        // only copy the port if we are told the IP address
-        if cfg!(s2n_quic_platform_pktinfo) {
-            header.path.local_address.set_port(local_address.port());
-        } else {
-            header.path.local_address = *local_address;
-        }
-
+        let (header, _ancillary_data) = Self::rx_header(&self.0, local_address)?;
         let payload = self.payload_mut();
         Some((header, payload))
     }
+
+    #[inline]
+    fn read_segments<F>(&mut self, local_address: &path::LocalAddress, on_datagram: F)
+    where
+        F: FnMut(&datagram::Header<Self::Handle>, &mut [u8]),
+    {
+        if let Some((header, ancillary_data)) = Self::rx_header(&self.0, local_address) {
+            Self::for_each_segment(&header, ancillary_data, self.payload_mut(), on_datagram);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -610,9 +694,11 @@ mod tests {
             .with_generator((
                 gen::<Handle>(),
                 1..=crate::features::get().gso.max_segments(),
+                gen::<Dscp>(),
+                gen::<ExplicitCongestionNotification>(),
             ))
             .cloned()
-            .for_each(|(handle, segment_size)| {
+            .for_each(|(handle, segment_size, dscp, ecn)| {
                 use core::mem::zeroed;
 
                 let mut msghdr = unsafe { zeroed::<msghdr>() };
@@ -634,14 +720,18 @@ mod tests {
                 let mut message = Message(msghdr);
 
                 handle.update_msg_hdr(&mut message.0);
+                message.set_traffic_class(dscp, ecn, &handle.remote_address.0);
 
                 if segment_size > 1 {
                     message.set_segment_size(segment_size);
                 }
 
-                let header = Message::header(&message.0).unwrap();
+                let (header, ancillary_data) =
+                    Message::header_with_ancillary_data(&message.0).unwrap();
 
                 assert_eq!(header.path.remote_address, handle.remote_address);
+                assert_eq!(header.ecn, ecn);
+                assert_eq!(ancillary_data.dscp, dscp);
 
                 if cfg!(s2n_quic_platform_pktinfo) && !handle.local_address.ip().is_unspecified() {
                     assert_eq!(header.path.local_address.ip(), handle.local_address.ip());
@@ -656,4 +746,80 @@ mod tests {
                 assert!(header.path.remote_address.is_unspecified());
             });
     }
+
+    #[test]
+    #[cfg(s2n_quic_platform_pktinfo)]
+    fn with_local_address_test() {
+        check!()
+            .with_generator((gen::<Handle>(), gen::<LocalAddress>()))
+            .cloned()
+            .for_each(|(handle, mut local_address)| {
+                let bound_port = handle.local_address.port();
+
+                // an unspecified port keeps the current port
+                local_address.set_port(0);
+                let migrated = handle.with_local_address(local_address).unwrap();
+                assert_eq!(migrated.remote_address, handle.remote_address);
+                assert_eq!(migrated.local_address.ip(), local_address.ip());
+                assert_eq!(migrated.local_address.port(), bound_port);
+
+                local_address.set_port(bound_port);
+                let migrated = handle.with_local_address(local_address).unwrap();
+                assert_eq!(migrated.local_address, local_address);
+
+                // other ports are sent from a socket bound to that port
+                local_address.set_port(bound_port.wrapping_add(1).max(1));
+                let migrated = handle.with_local_address(local_address).unwrap();
+                assert_eq!(migrated.local_address, local_address);
+            });
+    }
+
+    #[test]
+    #[cfg(s2n_quic_platform_gro)]
+    fn read_segments_test() {
+        use core::mem::zeroed;
+        use s2n_quic_core::io::rx::Entry as _;
+
+        check!()
+            .with_generator((gen::<Handle>(), 1..=64usize, 1..=4096usize))
+            .cloned()
+            .for_each(|(handle, segment_size, payload_len)| {
+                let mut msghdr = unsafe { zeroed::<msghdr>() };
+
+                let mut msgname = unsafe { zeroed::<sockaddr_in6>() };
+                msghdr.msg_name = &mut msgname as *mut _ as *mut _;
+                msghdr.msg_namelen = size_of::<sockaddr_in6>() as _;
+
+                let mut iovec = unsafe { zeroed::<iovec>() };
+                let mut iovec_buf = [0u8; 4096];
+                iovec.iov_len = payload_len as _;
+                iovec.iov_base = (&mut iovec_buf[0]) as *mut u8 as _;
+                msghdr.msg_iov = &mut iovec;
+
+                let mut cmsg_buf = [0u8; cmsg::MAX_LEN];
+                msghdr.msg_controllen = cmsg_buf.len() as _;
+                msghdr.msg_control = (&mut cmsg_buf[0]) as *mut u8 as _;
+
+                let mut message = Message(msghdr);
+
+                handle.update_msg_hdr(&mut message.0);
+                message
+                    .0
+                    .encode_cmsg(libc::SOL_UDP, libc::UDP_GRO, segment_size as libc::c_int);
+
+                let mut segments = vec![];
+                message.read_segments(&handle.local_address, |header, payload| {
+                    assert_eq!(header.path.remote_address, handle.remote_address);
+                    segments.push(payload.len());
+                });
+
+                // the payload should be split into `segment_size` datagrams, with the last one
+                // containing the remaining bytes
+                let expected: Vec<_> = (0..payload_len)
+                    .step_by(segment_size)
+                    .map(|offset| (payload_len - offset).min(segment_size))
+                    .collect();
+                assert_eq!(segments, expected);
+            });
+    }
 }
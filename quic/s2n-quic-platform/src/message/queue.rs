@@ -12,8 +12,24 @@ pub use slice::Slice;
 pub type Free<'a, M> = Slice<'a, M, behavior::Free>;
 pub type Occupied<'a, M> = Slice<'a, M, behavior::Occupied>;
 
+/// The longest a message can be held by the kernel before it is transmitted when `SO_TXTIME` is
+/// enabled
+///
+/// Messages are committed to their transmit time once they're sent to the socket, so this is
+/// kept short enough that the pacer can react to changes in the congestion controller while
+/// still allowing several packets to be sent per wakeup.
+pub const MAX_TXTIME_DELAY: Duration = Duration::from_millis(2);
+
+/// The longest a segment can be held back behind the first segment of a GSO payload when
+/// `SO_TXTIME` is enabled
+///
+/// The segments of a GSO payload depart together at the transmit time of the first segment.
+/// Segments which are due within this duration of the first are sent early, rather than
+/// ending the payload every time the pacer moves to the next interval.
+pub const MAX_TXTIME_GSO_SPREAD: Duration = Duration::from_micros(500);
+
 use crate::message;
-use core::fmt;
+use core::{fmt, time::Duration};
 use s2n_quic_core::path::LocalAddress;
 
 /// Structure for queueing network messages
@@ -51,6 +67,12 @@ pub struct Queue<Ring: message::Ring> {
     free: Segment,
     /// The local address that the queue is bound to
     local_address: LocalAddress,
+    /// The length messages are reset to after being consumed
+    ///
+    /// This is the MTU, unless the queue is receiving coalesced payloads with GRO.
+    payload_len: usize,
+    /// Set to `true` if messages carry their transmit time
+    txtime: bool,
 }
 
 impl<Ring> Default for Queue<Ring>
@@ -66,6 +88,7 @@ impl<Ring: message::Ring> Queue<Ring> {
     /// Creates a new `MessageQueue` with a `MessageBuffer`
     pub fn new(ring: Ring) -> Self {
         let capacity = ring.len();
+        let payload_len = ring.mtu();
         let occupied = Segment {
             index: 0,
             len: 0,
@@ -82,9 +105,43 @@ impl<Ring: message::Ring> Queue<Ring> {
             occupied,
             free,
             local_address: Default::default(),
+            payload_len,
+            txtime: false,
         }
     }
 
+    /// Allows received messages to use all of the segments allocated to each message
+    ///
+    /// This is required to receive payloads coalesced with Generic Receive Offload (GRO), which
+    /// can contain up to `max_gso` datagrams of `mtu` size.
+    pub fn enable_gro(&mut self) {
+        debug_assert_eq!(
+            self.occupied.len, 0,
+            "GRO should be enabled on an empty queue"
+        );
+
+        self.payload_len = self.ring.mtu() * self.ring.max_gso();
+
+        for message in self.ring.as_mut_slice() {
+            unsafe {
+                // Safety: the ring allocates `mtu * max_gso` bytes for each payload
+                message.reset(self.payload_len);
+            }
+        }
+    }
+
+    /// Sets the transmit time on each message with a delay so the kernel can pace them
+    ///
+    /// The socket must have `SO_TXTIME` enabled with `CLOCK_MONOTONIC`. Otherwise, the messages
+    /// will fail to send.
+    pub fn enable_txtime(&mut self) {
+        debug_assert!(
+            <Ring::Message as message::Message>::SUPPORTS_TXTIME,
+            "SO_TXTIME is not supported on the current platform"
+        );
+        self.txtime = true;
+    }
+
     pub fn set_local_address(&mut self, local_address: LocalAddress) {
         self.local_address = local_address;
     }
@@ -119,7 +176,7 @@ impl<Ring: message::Ring> Queue<Ring> {
 
     /// Returns a slice of all of the `free` messages
     pub fn free_mut(&mut self) -> Free<Ring::Message> {
-        let mtu = self.mtu();
+        let mtu = self.payload_len;
         let max_gso = self.max_gso();
         Slice {
             messages: self.ring.as_mut_slice(),
@@ -129,12 +186,13 @@ impl<Ring: message::Ring> Queue<Ring> {
             max_gso,
             gso_segment: None,
             local_address: &self.local_address,
+            txtime: self.txtime,
         }
     }
 
     /// Returns a slice of all of the `occupied` messages
     pub fn occupied_mut(&mut self) -> Occupied<Ring::Message> {
-        let mtu = self.mtu();
+        let mtu = self.payload_len;
         let max_gso = self.max_gso();
         Slice {
             messages: self.ring.as_mut_slice(),
@@ -144,6 +202,7 @@ impl<Ring: message::Ring> Queue<Ring> {
             max_gso,
             gso_segment: None,
             local_address: &self.local_address,
+            txtime: self.txtime,
         }
     }
 }
@@ -301,6 +360,91 @@ mod tests {
 
     differential_test!(simple_differential_test, message::simple::Ring);
 
+    #[cfg(all(
+        s2n_quic_platform_socket_msg,
+        s2n_quic_platform_gso,
+        s2n_quic_platform_txtime
+    ))]
+    #[test]
+    fn txtime_gso_test() {
+        use core::time::Duration;
+        use message::msg::{Handle, Ring};
+        use s2n_quic_core::{
+            inet::ExplicitCongestionNotification,
+            io::tx::{self, Queue as _},
+            path::Handle as _,
+        };
+
+        struct Paced {
+            handle: Handle,
+            delay: Duration,
+        }
+
+        impl tx::Message for Paced {
+            type Handle = Handle;
+
+            fn path_handle(&self) -> &Self::Handle {
+                &self.handle
+            }
+
+            fn ecn(&mut self) -> ExplicitCongestionNotification {
+                Default::default()
+            }
+
+            fn delay(&mut self) -> Duration {
+                self.delay
+            }
+
+            fn ipv6_flow_label(&mut self) -> u32 {
+                0
+            }
+
+            fn can_gso(&self, segment_len: usize, _segment_count: usize) -> bool {
+                segment_len >= 100
+            }
+
+            fn write_payload(
+                &mut self,
+                mut buffer: tx::PayloadBuffer,
+                _gso_offset: usize,
+            ) -> Result<usize, tx::Error> {
+                buffer.write(&[1; 100])
+            }
+        }
+
+        let max_gso = 4;
+        let payloads = VecBuffer::new(4 * max_gso, MTU);
+        let mut queue = Queue::new(Ring::new(payloads, max_gso));
+        queue.enable_txtime();
+
+        let address: inet::SocketAddress = inet::SocketAddressV4::new([127, 0, 0, 1], 443).into();
+        let handle = Handle::from_remote_address(address.into());
+
+        let delays = [
+            // the pacer moving to the next interval doesn't split the payload
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+            Duration::from_millis(1) + Duration::from_micros(100),
+            // segments which are due too long after the first segment start a new payload
+            Duration::from_millis(1) + MAX_TXTIME_GSO_SPREAD + Duration::from_micros(1),
+            Duration::from_millis(2),
+        ];
+
+        {
+            let mut free = queue.free_mut();
+            for delay in delays {
+                free.push(Paced { handle, delay }).unwrap();
+            }
+        }
+
+        let occupied = queue.occupied_mut();
+        let lens: Vec<_> = occupied
+            .iter()
+            .map(|message| message.payload_len())
+            .collect();
+        assert_eq!(lens, [300, 200]);
+    }
+
     #[cfg(s2n_quic_platform_socket_msg)]
     differential_test!(msg_differential_test, message::msg::Ring);
 
@@ -3,7 +3,10 @@
 
 use super::{Behavior, Segment};
 use crate::message;
-use core::ops::{Deref, DerefMut};
+use core::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
 use s2n_quic_core::{
     io::{rx, tx},
     path::{self, LocalAddress},
@@ -26,6 +29,8 @@ pub struct Slice<'a, Message: message::Message, Behavior> {
     pub(crate) gso_segment: Option<GsoSegment>,
     /// The base handle for all of the messages to inherit
     pub(crate) local_address: &'a LocalAddress,
+    /// Set to `true` if delayed messages should carry their transmit time
+    pub(crate) txtime: bool,
 }
 
 #[derive(Debug, Default)]
@@ -33,6 +38,8 @@ pub struct GsoSegment {
     index: usize,
     count: usize,
     size: usize,
+    /// The delay of the first segment, which applies to the entire payload
+    delay: Duration,
 }
 
 impl<'a, Message: message::Message, B: Behavior> Slice<'a, Message, B> {
@@ -153,6 +160,13 @@ impl<'a, Message: message::Message, B> Slice<'a, Message, B> {
             "gso_segment should only be set when max_gso > 1"
         );
 
+        // segments depart together at the transmit time of the first segment, so only segments
+        // which are due shortly after it can be included
+        if self.txtime && message.delay().saturating_sub(gso.delay) > super::MAX_TXTIME_GSO_SPREAD {
+            self.flush_gso();
+            return Ok(Err(message));
+        }
+
         let prev_message = &mut self.messages[gso.index];
         // check to make sure the message can be GSO'd and can be included in the same
         // GSO payload as the previous message
@@ -309,7 +323,7 @@ impl<
         message: M,
     ) -> Result<tx::Outcome, tx::Error> {
         // first try to write a GSO payload
-        let message = match self.try_gso(message)? {
+        let mut message = match self.try_gso(message)? {
             Ok(outcome) => return Ok(outcome),
             Err(message) => message,
         };
@@ -320,7 +334,20 @@ impl<
             .index(self.secondary)
             .ok_or(tx::Error::AtCapacity)?;
 
+        // the delay needs to be queried before the payload is written, which may advance the pacer
+        let delay = if Message::SUPPORTS_TXTIME && self.txtime {
+            message.delay()
+        } else {
+            Duration::ZERO
+        };
+
         let size = self.messages[index].set(message)?;
+
+        // the transmit time needs to be set before advancing so it's replicated to the primary message
+        if delay > Duration::ZERO {
+            self.messages[index].set_txtime(monotonic_time() + delay.as_nanos() as u64);
+        }
+
         self.advance(1);
 
         // if we support GSO then mark the message as GSO-capable
@@ -329,6 +356,7 @@ impl<
                 index,
                 count: 1,
                 size,
+                delay,
             });
         }
 
@@ -350,4 +378,35 @@ impl<
     fn len(&self) -> usize {
         self.secondary.len
     }
+
+    #[inline]
+    fn max_delay(&self) -> Duration {
+        if Message::SUPPORTS_TXTIME && self.txtime {
+            super::MAX_TXTIME_DELAY
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Returns the current time of `CLOCK_MONOTONIC` in nanoseconds, which is the clock used for
+/// `SO_TXTIME`
+#[cfg(s2n_quic_platform_txtime)]
+#[inline]
+fn monotonic_time() -> u64 {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe {
+        // Safety: CLOCK_MONOTONIC is always available and `time` is a valid pointer
+        libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time);
+    }
+    time.tv_sec as u64 * 1_000_000_000 + time.tv_nsec as u64
+}
+
+#[cfg(not(s2n_quic_platform_txtime))]
+#[inline]
+fn monotonic_time() -> u64 {
+    unreachable!("SO_TXTIME is not supported on the current platform")
 }
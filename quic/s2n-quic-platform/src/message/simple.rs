@@ -5,7 +5,7 @@ use crate::message::Message as MessageTrait;
 use alloc::vec::Vec;
 use core::pin::Pin;
 use s2n_quic_core::{
-    inet::{datagram, ExplicitCongestionNotification, SocketAddress},
+    inet::{datagram, Dscp, ExplicitCongestionNotification, SocketAddress},
     io::{rx, tx},
     path::{self, Handle as _},
 };
@@ -31,8 +31,13 @@ impl MessageTrait for Message {
         ExplicitCongestionNotification::default()
     }
 
-    fn set_ecn(&mut self, _ecn: ExplicitCongestionNotification, _remote_address: &SocketAddress) {
-        // the std UDP socket doesn't provide a method to set ECN
+    fn set_traffic_class(
+        &mut self,
+        _dscp: Dscp,
+        _ecn: ExplicitCongestionNotification,
+        _remote_address: &SocketAddress,
+    ) {
+        // the std UDP socket doesn't provide a method to set the traffic class
     }
 
     fn remote_address(&self) -> Option<SocketAddress> {
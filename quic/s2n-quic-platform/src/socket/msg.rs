@@ -36,6 +36,14 @@ impl<B: Buffer> Queue<B> {
         self.0.set_local_address(local_address)
     }
 
+    pub fn enable_gro(&mut self) {
+        self.0.enable_gro()
+    }
+
+    pub fn enable_txtime(&mut self) {
+        self.0.enable_txtime()
+    }
+
     pub fn tx<Socket: AsRawFd, Publisher: event::EndpointPublisher>(
         &mut self,
         socket: &Socket,
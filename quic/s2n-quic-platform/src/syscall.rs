@@ -0,0 +1,360 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Socket setup shared between the IO providers
+
+use cfg_if::cfg_if;
+use socket2::{Domain, Protocol, Socket, Type};
+use std::io;
+
+pub fn bind_udp<A: std::net::ToSocketAddrs>(addr: A, reuse_port: bool) -> io::Result<Socket> {
+    let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+        std::io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the provided bind address was empty",
+        )
+    })?;
+
+    let domain = Domain::for_address(addr);
+    let socket_type = Type::DGRAM;
+    let protocol = Some(Protocol::UDP);
+
+    cfg_if! {
+        // Set non-blocking mode in a single syscall if supported
+        if #[cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "fuchsia",
+            target_os = "illumos",
+            target_os = "linux",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))] {
+            let socket_type = socket_type.nonblocking();
+            let socket = Socket::new(domain, socket_type, protocol)?;
+        } else {
+            let socket = Socket::new(domain, socket_type, protocol)?;
+            socket.set_nonblocking(true)?;
+        }
+    };
+
+    // allow ipv4 to also connect
+    if addr.is_ipv6() {
+        socket.set_only_v6(false)?;
+    }
+
+    socket.set_reuse_address(true)?;
+
+    #[cfg(unix)]
+    socket.set_reuse_port(reuse_port)?;
+
+    // mark the variable as "used" regardless of platform support
+    let _ = reuse_port;
+
+    socket.bind(&addr.into())?;
+
+    Ok(socket)
+}
+
+/// Configures the socket to set the Don't Fragment (DF) bit on transmitted packets
+#[allow(unused_variables)] // some platform builds won't use these so ignore warnings
+pub fn configure_mtu_disc(tx_socket: &Socket, tx_addr: &std::net::SocketAddr) -> io::Result<()> {
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-14
+    //# UDP datagrams MUST NOT be fragmented at the IP layer.
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-14
+    //# In IPv4 [IPv4], the Don't Fragment (DF) bit MUST be set if possible, to
+    //# prevent fragmentation on the path.
+
+    //= https://www.rfc-editor.org/rfc/rfc8899#section-3
+    //# In IPv4, a probe packet MUST be sent with the Don't
+    //# Fragment (DF) bit set in the IP header and without network layer
+    //# endpoint fragmentation.
+
+    //= https://www.rfc-editor.org/rfc/rfc8899#section-4.5
+    //# A PL implementing this specification MUST suspend network layer
+    //# processing of outgoing packets that enforces a PMTU
+    //# [RFC1191][RFC8201] for each flow utilizing DPLPMTUD and instead use
+    //# DPLPMTUD to control the size of packets that are sent by a flow.
+    #[cfg(s2n_quic_platform_mtu_disc)]
+    {
+        use std::os::unix::io::AsRawFd;
+
+        // IP_PMTUDISC_PROBE setting will set the DF (Don't Fragment) flag
+        // while also ignoring the Path MTU. This means packets will not
+        // be fragmented, and the EMSGSIZE error will not be returned for
+        // packets larger than the Path MTU according to the kernel.
+        libc!(setsockopt(
+            tx_socket.as_raw_fd(),
+            libc::IPPROTO_IP,
+            libc::IP_MTU_DISCOVER,
+            &libc::IP_PMTUDISC_PROBE as *const _ as _,
+            core::mem::size_of_val(&libc::IP_PMTUDISC_PROBE) as _,
+        ))?;
+
+        if tx_addr.is_ipv6() {
+            libc!(setsockopt(
+                tx_socket.as_raw_fd(),
+                libc::IPPROTO_IPV6,
+                libc::IPV6_MTU_DISCOVER,
+                &libc::IP_PMTUDISC_PROBE as *const _ as _,
+                core::mem::size_of_val(&libc::IP_PMTUDISC_PROBE) as _,
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Configures the socket to pass ECN information
+///
+/// Returns `true` if ECN is supported on the platform
+#[allow(unused_variables)] // some platform builds won't use these so ignore warnings
+pub fn configure_tos(rx_socket: &Socket, rx_addr: &std::net::SocketAddr) -> io::Result<bool> {
+    #[cfg(s2n_quic_platform_tos)]
+    {
+        use std::os::unix::io::AsRawFd;
+        let enabled: libc::c_int = 1;
+
+        // This option needs to be enabled regardless of domain (IPv4 vs IPv6), except on mac
+        if rx_addr.is_ipv4() || !cfg!(any(target_os = "macos", target_os = "ios")) {
+            libc!(setsockopt(
+                rx_socket.as_raw_fd(),
+                libc::IPPROTO_IP,
+                libc::IP_RECVTOS,
+                &enabled as *const _ as _,
+                core::mem::size_of_val(&enabled) as _,
+            ))?;
+        }
+
+        if rx_addr.is_ipv6() {
+            libc!(setsockopt(
+                rx_socket.as_raw_fd(),
+                libc::IPPROTO_IPV6,
+                libc::IPV6_RECVTCLASS,
+                &enabled as *const _ as _,
+                core::mem::size_of_val(&enabled) as _,
+            ))?;
+        }
+    }
+
+    Ok(cfg!(s2n_quic_platform_tos))
+}
+
+/// Configures the socket to pass information about the local address and interface
+#[allow(unused_variables)] // some platform builds won't use these so ignore warnings
+pub fn configure_pktinfo(rx_socket: &Socket, rx_addr: &std::net::SocketAddr) -> io::Result<()> {
+    #[cfg(s2n_quic_platform_pktinfo)]
+    {
+        use std::os::unix::io::AsRawFd;
+        let enabled: libc::c_int = 1;
+
+        if rx_addr.is_ipv4() {
+            libc!(setsockopt(
+                rx_socket.as_raw_fd(),
+                libc::IPPROTO_IP,
+                libc::IP_PKTINFO,
+                &enabled as *const _ as _,
+                core::mem::size_of_val(&enabled) as _,
+            ))?;
+        } else {
+            libc!(setsockopt(
+                rx_socket.as_raw_fd(),
+                libc::IPPROTO_IPV6,
+                libc::IPV6_RECVPKTINFO,
+                &enabled as *const _ as _,
+                core::mem::size_of_val(&enabled) as _,
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Enables Generic Receive Offload (GRO) on the socket
+///
+/// Returns `false` if the option is not supported by the platform or kernel.
+#[cfg(s2n_quic_platform_gro)]
+pub fn configure_gro(rx_socket: &Socket) -> bool {
+    use std::os::unix::io::AsRawFd;
+    let enabled: libc::c_int = 1;
+
+    libc!(setsockopt(
+        rx_socket.as_raw_fd(),
+        libc::SOL_UDP,
+        libc::UDP_GRO,
+        &enabled as *const _ as _,
+        core::mem::size_of_val(&enabled) as _,
+    ))
+    .is_ok()
+}
+
+#[cfg(not(s2n_quic_platform_gro))]
+pub fn configure_gro(_rx_socket: &Socket) -> bool {
+    false
+}
+
+/// Enables `SO_TXTIME` on the socket so each message can carry the time it should be
+/// transmitted, measured with `CLOCK_MONOTONIC`
+///
+/// The kernel only honors the transmit time when the interface uses a qdisc which supports it,
+/// such as `fq`. Otherwise, messages are transmitted as soon as they're sent.
+///
+/// Returns `false` if the option is not supported by the platform or kernel.
+#[cfg(s2n_quic_platform_txtime)]
+pub fn configure_txtime(tx_socket: &Socket) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    // See https://man7.org/linux/man-pages/man8/tc-etf.8.html
+    #[repr(C)]
+    struct sock_txtime {
+        clockid: libc::clockid_t,
+        flags: u32,
+    }
+
+    let config = sock_txtime {
+        clockid: libc::CLOCK_MONOTONIC,
+        flags: 0,
+    };
+
+    libc!(setsockopt(
+        tx_socket.as_raw_fd(),
+        libc::SOL_SOCKET,
+        libc::SO_TXTIME,
+        &config as *const _ as _,
+        core::mem::size_of_val(&config) as _,
+    ))
+    .is_ok()
+}
+
+#[cfg(not(s2n_quic_platform_txtime))]
+pub fn configure_txtime(_tx_socket: &Socket) -> bool {
+    false
+}
+
+/// Attaches a program to the socket's `SO_REUSEPORT` group which steers each datagram to the
+/// socket at the index encoded in the first byte of the destination connection ID, modulo
+/// `shard_count`
+///
+/// Sockets are indexed in the order they were added to the group so each shard needs to bind
+/// in order. Datagrams which are too short to contain a connection ID are steered to the first
+/// socket.
+#[cfg(target_os = "linux")]
+pub fn configure_reuse_port_steering(rx_socket: &Socket, shard_count: u8) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // Classic BPF opcodes from `linux/filter.h`
+    const BPF_LD: u16 = 0x00;
+    const BPF_ALU: u16 = 0x04;
+    const BPF_JMP: u16 = 0x05;
+    const BPF_RET: u16 = 0x06;
+    const BPF_B: u16 = 0x10;
+    const BPF_ABS: u16 = 0x20;
+    const BPF_JA: u16 = 0x00;
+    const BPF_JSET: u16 = 0x40;
+    const BPF_MOD: u16 = 0x90;
+    const BPF_K: u16 = 0x00;
+    const BPF_A: u16 = 0x10;
+
+    const fn stmt(code: u16, k: u32) -> libc::sock_filter {
+        libc::sock_filter {
+            code,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    const fn jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter { code, jt, jf, k }
+    }
+
+    if shard_count == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "shard_count must be at least 1",
+        ));
+    }
+
+    // The program is run with the UDP payload as its packet data
+    let mut program = [
+        // load the first byte of the QUIC packet
+        stmt(BPF_LD | BPF_B | BPF_ABS, 0),
+        // check the header form bit
+        jump(BPF_JMP | BPF_JSET | BPF_K, 0x80, 0, 2),
+        // long headers have the first byte of the destination connection ID after the
+        // version and connection ID length
+        stmt(BPF_LD | BPF_B | BPF_ABS, 6),
+        jump(BPF_JMP | BPF_JA, 1, 0, 0),
+        // short headers have the destination connection ID immediately after the first byte
+        stmt(BPF_LD | BPF_B | BPF_ABS, 1),
+        stmt(BPF_ALU | BPF_MOD | BPF_K, shard_count as u32),
+        stmt(BPF_RET | BPF_A, 0),
+    ];
+
+    let program = libc::sock_fprog {
+        len: program.len() as _,
+        filter: program.as_mut_ptr(),
+    };
+
+    libc!(setsockopt(
+        rx_socket.as_raw_fd(),
+        libc::SOL_SOCKET,
+        libc::SO_ATTACH_REUSEPORT_CBPF,
+        &program as *const _ as _,
+        core::mem::size_of_val(&program) as _,
+    ))?;
+
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::{net::UdpSocket, time::Duration};
+
+    #[test]
+    fn reuse_port_steering_test() {
+        let first = bind_udp("127.0.0.1:0", true).unwrap();
+        let addr = first.local_addr().unwrap().as_socket().unwrap();
+        let second = bind_udp(addr, true).unwrap();
+        configure_reuse_port_steering(&first, 2).unwrap();
+
+        let shards: Vec<UdpSocket> = [first, second]
+            .into_iter()
+            .map(|socket| {
+                let socket: UdpSocket = socket.into();
+                socket.set_nonblocking(false).unwrap();
+                socket
+                    .set_read_timeout(Some(Duration::from_secs(1)))
+                    .unwrap();
+                socket
+            })
+            .collect();
+
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        for (packet, shard) in [
+            // short header with the connection ID starting with 1
+            (&[0x40, 1, 0xaa, 0xbb][..], 1),
+            // short header with the connection ID starting with 4
+            (&[0x40, 4, 0xaa, 0xbb][..], 0),
+            // long header with the connection ID starting with 3
+            (&[0xc0, 0, 0, 0, 1, 2, 3, 0xaa][..], 1),
+        ] {
+            client.send_to(packet, addr).unwrap();
+
+            let mut buffer = [0u8; 16];
+            let (len, _) = shards[shard].recv_from(&mut buffer).unwrap();
+            assert_eq!(&buffer[..len], packet);
+        }
+    }
+
+    #[test]
+    #[cfg(s2n_quic_platform_txtime)]
+    fn txtime_test() {
+        let socket = bind_udp("127.0.0.1:0", false).unwrap();
+        assert!(configure_txtime(&socket));
+    }
+}
@@ -1,7 +1,10 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{certificate, encode_transport_parameters, session::Session};
+use crate::{
+    certificate, encode_transport_parameters,
+    session::{Session, SessionStorage},
+};
 use core::convert::TryFrom;
 use rustls::{quic, ClientConfig};
 use s2n_codec::EncoderValue;
@@ -23,6 +26,39 @@ impl Client {
     pub fn builder() -> Builder {
         Builder::new()
     }
+
+    fn session<Params: EncoderValue>(
+        &self,
+        transport_parameters: &Params,
+        server_name: ServerName,
+        session_ticket: Option<&[u8]>,
+    ) -> Session {
+        use quic::ClientQuicExt;
+
+        //= https://www.rfc-editor.org/rfc/rfc9001#section-8.2
+        //# Endpoints MUST send the quic_transport_parameters extension;
+        let transport_parameters = encode_transport_parameters(transport_parameters);
+
+        let rustls_server_name =
+            rustls::ServerName::try_from(server_name.as_ref()).expect("invalid server name");
+
+        // rustls looks up resumption data and stores new session tickets through the config's
+        // session storage so each connection gets its own storage, which passes tickets to and
+        // from the transport
+        let session_storage = Arc::new(SessionStorage::new(session_ticket));
+        let mut config = (*self.config).clone();
+        config.session_storage = session_storage.clone();
+
+        let session = rustls::ClientConnection::new_quic(
+            Arc::new(config),
+            crate::QUIC_VERSION,
+            rustls_server_name,
+            transport_parameters,
+        )
+        .expect("could not create rustls client session");
+
+        Session::new(session.into(), Some(server_name), Some(session_storage))
+    }
 }
 
 impl Default for Client {
@@ -61,24 +97,16 @@ impl tls::Endpoint for Client {
         transport_parameters: &Params,
         server_name: ServerName,
     ) -> Self::Session {
-        use quic::ClientQuicExt;
-
-        //= https://www.rfc-editor.org/rfc/rfc9001#section-8.2
-        //# Endpoints MUST send the quic_transport_parameters extension;
-        let transport_parameters = encode_transport_parameters(transport_parameters);
-
-        let rustls_server_name =
-            rustls::ServerName::try_from(server_name.as_ref()).expect("invalid server name");
-
-        let session = rustls::ClientConnection::new_quic(
-            self.config.clone(),
-            crate::QUIC_VERSION,
-            rustls_server_name,
-            transport_parameters,
-        )
-        .expect("could not create rustls client session");
+        self.session(transport_parameters, server_name, None)
+    }
 
-        Session::new(session.into(), Some(server_name))
+    fn resume_client_session<Params: EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+        server_name: ServerName,
+        session_ticket: &[u8],
+    ) -> Self::Session {
+        self.session(transport_parameters, server_name, Some(session_ticket))
     }
 
     fn max_tag_length(&self) -> usize {
@@ -90,6 +118,7 @@ pub struct Builder {
     cert_store: rustls::RootCertStore,
     application_protocols: Vec<Vec<u8>>,
     key_log: Option<Arc<dyn rustls::KeyLog>>,
+    enable_early_data: bool,
 }
 
 impl Default for Builder {
@@ -104,6 +133,7 @@ impl Builder {
             cert_store: rustls::RootCertStore::empty(),
             application_protocols: vec![b"h3".to_vec()],
             key_log: None,
+            enable_early_data: false,
         }
     }
 
@@ -140,6 +170,12 @@ impl Builder {
         Ok(self)
     }
 
+    /// Enables sending early (0-RTT) data when resuming a session
+    pub fn with_early_data(mut self) -> Result<Self, rustls::Error> {
+        self.enable_early_data = true;
+        Ok(self)
+    }
+
     pub fn build(self) -> Result<Client, rustls::Error> {
         // TODO load system root store?
         if self.cert_store.is_empty() {
@@ -159,6 +195,7 @@ impl Builder {
 
         config.max_fragment_size = None;
         config.alpn_protocols = self.application_protocols;
+        config.enable_early_data = self.enable_early_data;
 
         if let Some(key_log) = self.key_log {
             config.key_log = key_log;
@@ -64,7 +64,7 @@ impl tls::Endpoint for Server {
         )
         .expect("could not create rustls server session");
 
-        Session::new(session.into(), None)
+        Session::new(session.into(), None, None)
     }
 
     fn new_client_session<Params: EncoderValue>(
@@ -84,6 +84,7 @@ pub struct Builder {
     cert_resolver: Option<Arc<dyn rustls::server::ResolvesServerCert>>,
     application_protocols: Vec<Vec<u8>>,
     key_log: Option<Arc<dyn rustls::KeyLog>>,
+    enable_early_data: bool,
 }
 
 impl Default for Builder {
@@ -98,6 +99,7 @@ impl Builder {
             cert_resolver: None,
             application_protocols: vec![b"h3".to_vec()],
             key_log: None,
+            enable_early_data: false,
         }
     }
 
@@ -134,6 +136,15 @@ impl Builder {
         Ok(self)
     }
 
+    /// Enables accepting early (0-RTT) data from clients resuming a session
+    ///
+    /// Early data is only accepted if the anti-replay guard configured on the server accepts
+    /// the ClientHello.
+    pub fn with_early_data(mut self) -> Result<Self, rustls::Error> {
+        self.enable_early_data = true;
+        Ok(self)
+    }
+
     pub fn build(self) -> Result<Server, rustls::Error> {
         let builder = ServerConfig::builder()
             .with_cipher_suites(crate::cipher_suite::DEFAULT_CIPHERSUITES)
@@ -153,6 +164,13 @@ impl Builder {
         config.max_fragment_size = None;
         config.alpn_protocols = self.application_protocols;
 
+        if self.enable_early_data {
+            //= https://www.rfc-editor.org/rfc/rfc9001#section-4.6.1
+            //# Servers MUST NOT send the early_data extension with a
+            //# max_early_data_size field set to any value other than 0xffffffff.
+            config.max_early_data_size = u32::MAX;
+        }
+
         if let Some(key_log) = self.key_log {
             config.key_log = key_log;
         }
@@ -15,16 +15,20 @@ use s2n_quic_core::{
     crypto::{self, tls, CryptoError},
     transport,
 };
+use std::sync::{Arc, Mutex};
 
 pub struct Session {
     connection: Connection,
     rx_phase: HandshakePhase,
     tx_phase: HandshakePhase,
     emitted_zero_rtt_keys: bool,
+    emitted_early_data_status: bool,
+    checked_early_data: bool,
     emitted_handshake_complete: bool,
     emitted_server_name: bool,
     emitted_application_protocol: bool,
     server_name: Option<ServerName>,
+    session_storage: Option<Arc<SessionStorage>>,
 }
 
 impl fmt::Debug for Session {
@@ -37,16 +41,23 @@ impl fmt::Debug for Session {
 }
 
 impl Session {
-    pub fn new(connection: Connection, server_name: Option<ServerName>) -> Self {
+    pub fn new(
+        connection: Connection,
+        server_name: Option<ServerName>,
+        session_storage: Option<Arc<SessionStorage>>,
+    ) -> Self {
         Self {
             connection,
             rx_phase: Default::default(),
             tx_phase: Default::default(),
             emitted_zero_rtt_keys: false,
+            emitted_early_data_status: false,
+            checked_early_data: false,
             emitted_handshake_complete: false,
             emitted_server_name: false,
             emitted_application_protocol: false,
             server_name,
+            session_storage,
         }
     }
 
@@ -134,6 +145,39 @@ impl Session {
         Some(keys)
     }
 
+    /// Returns the early data status once the client has been notified by the server
+    fn early_data_status(&self) -> Option<tls::EarlyDataStatus> {
+        if let Connection::Client(client) = &self.connection {
+            if !self.emitted_zero_rtt_keys {
+                Some(tls::EarlyDataStatus::NotAttempted)
+            } else if client.is_early_data_accepted() {
+                Some(tls::EarlyDataStatus::Accepted)
+            } else {
+                Some(tls::EarlyDataStatus::Rejected)
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Asks the transport if a server can accept early data before the ClientHello is processed
+    ///
+    /// rustls only allows early data to be rejected while the server is handshaking, which
+    /// makes sure the rejection is signaled to the client.
+    fn check_early_data<C: tls::Context<Self>>(&mut self, context: &mut C) {
+        if self.checked_early_data {
+            return;
+        }
+
+        self.checked_early_data = true;
+
+        if let Connection::Server(server) = &mut self.connection {
+            if !context.on_early_data().is_accepted() {
+                server.reject_early_data();
+            }
+        }
+    }
+
     /// Check and process TLS handshake complete.
     ///
     /// Upon TLS handshake complete, emit an event to notify the transport layer.
@@ -146,6 +190,14 @@ impl Session {
             // handshake
             self.emit_events(context)?;
 
+            // the server's decision on early data is known by the time the handshake is complete
+            if !self.emitted_early_data_status {
+                if let Some(status) = self.early_data_status() {
+                    context.on_early_data_status(status)?;
+                }
+                self.emitted_early_data_status = true;
+            }
+
             // the handshake is complete!
             if !self.emitted_handshake_complete {
                 self.rx_phase.transition();
@@ -178,6 +230,7 @@ impl Session {
 
             // receive anything in the incoming buffer
             if let Some(crypto_data) = crypto_data {
+                self.check_early_data(context);
                 self.receive(&crypto_data)?;
             } else if has_tried_receive {
                 return self.poll_complete_handshake(context);
@@ -285,6 +338,70 @@ impl Session {
 
         Ok(())
     }
+
+    fn emit_session_tickets<C: tls::Context<Self>>(
+        &mut self,
+        context: &mut C,
+    ) -> Result<(), transport::Error> {
+        if let Some(session_storage) = self.session_storage.as_ref() {
+            for session_ticket in session_storage.take_session_tickets() {
+                context.on_session_ticket(session_ticket.into())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Session storage for a single client connection
+///
+/// Resumes the session from the ticket provided by the transport and collects any tickets
+/// issued by the server.
+#[derive(Debug, Default)]
+pub struct SessionStorage {
+    resumption_ticket: Option<Vec<u8>>,
+    session_tickets: Mutex<Vec<Vec<u8>>>,
+}
+
+impl SessionStorage {
+    pub fn new(resumption_ticket: Option<&[u8]>) -> Self {
+        Self {
+            resumption_ticket: resumption_ticket.map(<[u8]>::to_vec),
+            session_tickets: Default::default(),
+        }
+    }
+
+    fn take_session_tickets(&self) -> Vec<Vec<u8>> {
+        self.session_tickets
+            .lock()
+            .map(|mut session_tickets| core::mem::take(&mut *session_tickets))
+            .unwrap_or_default()
+    }
+}
+
+impl rustls::client::StoresClientSessions for SessionStorage {
+    fn put(&self, key: Vec<u8>, value: Vec<u8>) -> bool {
+        // rustls prefixes the resumption data key with `session`; any other keys (like
+        // key exchange hints) aren't stored
+        if !key.starts_with(b"session") {
+            return false;
+        }
+
+        if let Ok(mut session_tickets) = self.session_tickets.lock() {
+            session_tickets.push(value);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        if key.starts_with(b"session") {
+            self.resumption_ticket.clone()
+        } else {
+            None
+        }
+    }
 }
 
 impl crypto::CryptoSuite for Session {
@@ -308,6 +425,7 @@ impl tls::Session for Session {
         // attempt to emit server_name and application_protocol events prior to possibly
         // returning with an error
         self.emit_events(context)?;
+        self.emit_session_tickets(context)?;
         result
     }
 }
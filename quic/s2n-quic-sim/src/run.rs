@@ -4,12 +4,15 @@
 use crate::{stats, Result};
 use indicatif::{ParallelProgressIterator, ProgressBar};
 use rayon::prelude::*;
-use s2n_quic::provider::io::testing::{test_seed, Model};
+use s2n_quic::provider::io::testing::{test_seed, Aqm, Model};
 use structopt::StructOpt;
 
 mod config;
 pub use config::Config;
 
+/// The queue capacity used to derive the RED thresholds when the capacity is unlimited
+const DEFAULT_RED_CAPACITY: u64 = 150_000;
+
 mod endpoint;
 mod events;
 
@@ -168,6 +171,33 @@ impl Run {
         zero_param!(transmit_rate, set_transmit_rate);
         zero_param!(max_inflight, set_max_inflight);
         zero_param!(inflight_delay_threshold, set_inflight_delay_threshold);
+        zero_param!(bandwidth, set_bandwidth);
+        zero_param!(queue_capacity, set_queue_capacity);
+        zero_param!(ecn_threshold, set_ecn_threshold);
+
+        if self.burst.end > 0 {
+            let value = self.burst.gen();
+            model.set_burst(value);
+            events.burst = value;
+        }
+
+        let aqm = match self.aqm {
+            config::Aqm::DropTail => Aqm::DropTail,
+            config::Aqm::Codel => Aqm::codel(),
+            config::Aqm::Red => {
+                let capacity = match model.queue_capacity() {
+                    u64::MAX => DEFAULT_RED_CAPACITY,
+                    capacity => capacity,
+                };
+                Aqm::Red {
+                    min_threshold: capacity / 4,
+                    max_threshold: capacity * 3 / 4,
+                    max_probability: 0.1,
+                }
+            }
+        };
+        model.set_aqm(aqm);
+        events.aqm = self.aqm.id();
 
         events.into()
     }
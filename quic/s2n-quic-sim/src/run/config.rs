@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::CliRange;
+use core::{fmt, str::FromStr};
 use humantime::Duration;
 use serde::Deserialize;
 use structopt::StructOpt;
@@ -91,6 +92,26 @@ config!(
         #[default = "0"]
         inflight_delay_threshold: CliRange<u64>,
 
+        #[name = "bandwidth"]
+        #[default = "0"]
+        bandwidth: CliRange<u64>,
+
+        #[name = "burst"]
+        #[default = "0"]
+        burst: CliRange<u64>,
+
+        #[name = "queue_capacity"]
+        #[default = "0"]
+        queue_capacity: CliRange<u64>,
+
+        #[name = "ecn_threshold"]
+        #[default = "0"]
+        ecn_threshold: CliRange<u64>,
+
+        #[name = "aqm"]
+        #[default = "drop-tail"]
+        aqm: Aqm,
+
         #[name = "clients"]
         #[default = "1"]
         clients: CliRange<u32>,
@@ -120,3 +141,48 @@ config!(
         iterations: u64,
     }
 );
+
+/// The active queue management algorithm of the bottleneck queue
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Aqm {
+    DropTail,
+    Codel,
+    Red,
+}
+
+impl Aqm {
+    pub fn id(self) -> u32 {
+        match self {
+            Self::DropTail => 0,
+            Self::Codel => 1,
+            Self::Red => 2,
+        }
+    }
+}
+
+impl FromStr for Aqm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop-tail" => Ok(Self::DropTail),
+            "codel" => Ok(Self::Codel),
+            "red" => Ok(Self::Red),
+            _ => Err(format!(
+                "invalid aqm {s:?}; expected drop-tail, codel or red"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Aqm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DropTail => "drop-tail",
+            Self::Codel => "codel",
+            Self::Red => "red",
+        }
+        .fmt(f)
+    }
+}
@@ -155,6 +155,16 @@ pub struct Parameters {
     pub inflight_delay: Option<Duration>,
     #[prost(uint64, tag = "15")]
     pub inflight_delay_threshold: u64,
+    #[prost(uint64, tag = "16")]
+    pub bandwidth: u64,
+    #[prost(uint64, tag = "17")]
+    pub burst: u64,
+    #[prost(uint64, tag = "18")]
+    pub queue_capacity: u64,
+    #[prost(uint64, tag = "19")]
+    pub ecn_threshold: u64,
+    #[prost(uint32, tag = "20")]
+    pub aqm: u32,
 }
 
 impl From<Parameters> for Stats {
@@ -627,6 +637,21 @@ static QUERIES: &[(&str, Type, Q)] = &[
                 .as_secs_f64(),
         )
     }),
+    ("net.bandwidth", I, |params, _conn, _conns| {
+        Some(params.bandwidth as f64)
+    }),
+    ("net.burst", I, |params, _conn, _conns| {
+        Some(params.burst as f64)
+    }),
+    ("net.queue-capacity", I, |params, _conn, _conns| {
+        Some(params.queue_capacity as f64)
+    }),
+    ("net.ecn-threshold", I, |params, _conn, _conns| {
+        Some(params.ecn_threshold as f64)
+    }),
+    ("net.aqm", I, |params, _conn, _conns| {
+        Some(params.aqm as f64)
+    }),
 ];
 
 pub static QUERY_NAMES: Lazy<Vec<&'static str>> =
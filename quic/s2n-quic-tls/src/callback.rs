@@ -216,16 +216,36 @@ where
 
                 match self.state.tx_phase {
                     HandshakePhase::Initial => {
-                        let (key, header_key) = HandshakeKey::new(self.endpoint, aead_algo, pair)
-                            .expect("invalid cipher");
+                        if self.endpoint.is_server() {
+                            // the server can switch to a compatible version based on the
+                            // client's transport parameters, before deriving any more keys
+                            let params = unsafe {
+                                // Safety: conn needs to outlive params
+                                get_application_params(conn)?
+                            };
+                            if let Some(params) =
+                                self.context.on_client_application_params(params)?
+                            {
+                                // EncryptedExtensions hasn't been written yet, so the peer
+                                // receives the updated parameters
+                                unsafe { set_transport_parameters(conn, &params)? };
+                            }
+                        }
+
+                        let version = self.context.quic_version();
+                        let (key, header_key) =
+                            HandshakeKey::new(self.endpoint, version, aead_algo, pair)
+                                .expect("invalid cipher");
 
                         self.context.on_handshake_keys(key, header_key)?;
                         self.state.tx_phase.transition();
                         self.state.rx_phase.transition();
                     }
                     _ => {
+                        let version = self.context.quic_version();
                         let (key, header_key) =
-                            OneRttKey::new(self.endpoint, aead_algo, pair).expect("invalid cipher");
+                            OneRttKey::new(self.endpoint, version, aead_algo, pair)
+                                .expect("invalid cipher");
 
                         let params = unsafe {
                             // Safety: conn needs to outlive params
@@ -500,6 +520,22 @@ unsafe fn get_transport_parameters<'a>(connection: *mut s2n_connection) -> Optio
     get_slice(ptr, len as _)
 }
 
+unsafe fn set_transport_parameters(
+    connection: *mut s2n_connection,
+    params: &[u8],
+) -> Result<(), transport::Error> {
+    let len = params
+        .len()
+        .try_into()
+        .map_err(|_| transport::Error::INTERNAL_ERROR)?;
+
+    s2n_connection_set_quic_transport_parameters(connection, params.as_ptr(), len)
+        .into_result()
+        .map_err(|_| transport::Error::INTERNAL_ERROR)?;
+
+    Ok(())
+}
+
 unsafe fn get_cstr_slice<'a>(ptr: *const libc::c_char) -> Option<&'a [u8]> {
     let len = libc::strlen(ptr);
     get_slice(ptr as *const _, len)
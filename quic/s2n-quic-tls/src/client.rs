@@ -9,7 +9,12 @@ use crate::{
     ConfigLoader,
 };
 use s2n_codec::EncoderValue;
-use s2n_quic_core::{application::ServerName, crypto::tls, endpoint};
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::tls,
+    endpoint,
+    packet::long::{VERSION_1, VERSION_2},
+};
 use s2n_tls::{
     callbacks::VerifyHostNameCallback,
     config::{self, Config},
@@ -75,6 +80,9 @@ impl Default for Builder {
         // https://github.com/aws/s2n-tls/blob/main/docs/USAGE-GUIDE.md#s2n_config_set_cipher_preferences
         config.set_security_policy(crate::DEFAULT_POLICY).unwrap();
         config.set_application_protocol_preference([b"h3"]).unwrap();
+        // Tickets issued by the server are passed to the transport so later connections can
+        // resume the session
+        config.enable_session_tickets(true).unwrap();
 
         Self {
             config,
@@ -193,6 +201,8 @@ impl Builder {
 impl<L: ConfigLoader> tls::Endpoint for Client<L> {
     type Session = Session;
 
+    const QUIC_VERSIONS: &'static [u32] = &[VERSION_1, VERSION_2];
+
     fn new_server_session<Params: EncoderValue>(&mut self, _params: &Params) -> Self::Session {
         panic!("cannot create a server session from a client config");
     }
@@ -210,6 +220,21 @@ impl<L: ConfigLoader> tls::Endpoint for Client<L> {
         })
     }
 
+    fn resume_client_session<Params: EncoderValue>(
+        &mut self,
+        params: &Params,
+        server_name: ServerName,
+        session_ticket: &[u8],
+    ) -> Self::Session {
+        let mut session = self.new_client_session(params, server_name);
+
+        // An expired or otherwise invalid ticket is ignored by s2n-tls, which performs a full
+        // handshake instead
+        let _ = session.connection.set_session_ticket(session_ticket);
+
+        session
+    }
+
     fn max_tag_length(&self) -> usize {
         s2n_quic_crypto::MAX_TAG_LEN
     }
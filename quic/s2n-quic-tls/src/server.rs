@@ -9,7 +9,12 @@ use crate::{
     ConfigLoader,
 };
 use s2n_codec::EncoderValue;
-use s2n_quic_core::{application::ServerName, crypto::tls, endpoint};
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::tls,
+    endpoint,
+    packet::long::{VERSION_1, VERSION_2},
+};
 #[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
 use s2n_tls::callbacks::ClientHelloCallback;
 use s2n_tls::{
@@ -185,6 +190,17 @@ impl Builder {
         Ok(self)
     }
 
+    /// Adds a key for encrypting the session tickets issued to clients
+    ///
+    /// Clients can present the tickets in later connections to resume the session without
+    /// another full handshake. Session tickets are only issued once a key has been added.
+    pub fn with_session_ticket_key(mut self, key_name: &[u8], key: &[u8]) -> Result<Self, Error> {
+        self.config.enable_session_tickets(true)?;
+        self.config
+            .add_session_ticket_key(key_name, key, std::time::SystemTime::now())?;
+        Ok(self)
+    }
+
     pub fn with_key_logging(mut self) -> Result<Self, Error> {
         use crate::keylog::KeyLog;
 
@@ -217,6 +233,8 @@ impl Builder {
 impl<L: ConfigLoader> tls::Endpoint for Server<L> {
     type Session = Session;
 
+    const QUIC_VERSIONS: &'static [u32] = &[VERSION_1, VERSION_2];
+
     fn new_server_session<Params: EncoderValue>(&mut self, params: &Params) -> Self::Session {
         let config = self
             .loader
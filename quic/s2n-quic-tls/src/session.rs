@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::callback::{self, Callback};
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use core::{marker::PhantomData, task::Poll};
 use s2n_quic_core::{
     application::ServerName,
@@ -14,7 +14,7 @@ use s2n_tls::{
     config::Config,
     connection::Connection,
     enums::{Blinding, Mode},
-    error::Error,
+    error::{Error, ErrorType},
 };
 
 #[derive(Debug)]
@@ -99,10 +99,25 @@ impl tls::Session for Session {
             callback.set(&mut self.connection);
         }
 
-        let result = self.connection.poll_negotiate().map_ok(|_| ());
+        let mut session_tickets = vec![];
+
+        let result = if !self.handshake_complete {
+            self.connection.poll_negotiate().map_ok(|_| ())
+        } else if self.endpoint.is_client() {
+            Poll::Ready(receive_session_tickets(
+                &mut self.connection,
+                &mut session_tickets,
+            ))
+        } else {
+            Poll::Ready(Ok(()))
+        };
 
         callback.unset(&mut self.connection)?;
 
+        for session_ticket in session_tickets {
+            context.on_session_ticket(session_ticket)?;
+        }
+
         match result {
             Poll::Ready(Ok(())) => {
                 // s2n-tls has indicated that the handshake is complete
@@ -122,3 +137,30 @@ impl tls::Session for Session {
         }
     }
 }
+
+/// Processes the session tickets the server sends after the handshake
+fn receive_session_tickets(
+    connection: &mut Connection,
+    session_tickets: &mut Vec<Bytes>,
+) -> Result<(), Error> {
+    loop {
+        match connection.quic_process_post_handshake_message() {
+            Ok(_) => {}
+            // all of the received messages have been processed
+            Err(err) if err.kind() == ErrorType::Blocked => return Ok(()),
+            Err(err) => return Err(err),
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9001#section-6
+        //# Endpoints
+        //# MUST NOT send a TLS KeyUpdate message.
+        //
+        // This leaves NewSessionTicket as the only message the server can send
+        let mut session_ticket = vec![0; connection.session_ticket_length()?];
+        if !session_ticket.is_empty() {
+            let len = connection.session_ticket(&mut session_ticket)?;
+            session_ticket.truncate(len);
+            session_tickets.push(session_ticket.into());
+        }
+    }
+}
@@ -7,11 +7,13 @@ use core::{
     task::Poll,
 };
 use s2n_quic_core::{
+    anti_replay,
     crypto::tls::{
         self,
         testing::certificates::{CERT_PEM, KEY_PEM, UNTRUSTED_CERT_PEM, UNTRUSTED_KEY_PEM},
         Endpoint,
     },
+    packet::long::VERSION_2,
     transport,
 };
 #[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
@@ -171,6 +173,16 @@ fn s2n_server_with_client_hello_callback(wait_counter: u8) -> (server::Server, A
     (tls, done)
 }
 
+fn s2n_server_with_session_tickets() -> server::Server {
+    server::Builder::default()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_session_ticket_key(b"test key", &[1; 32])
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
 fn rustls_server() -> s2n_quic_rustls::server::Server {
     s2n_quic_rustls::server::Builder::default()
         .with_certificate(CERT_PEM, KEY_PEM)
@@ -179,6 +191,16 @@ fn rustls_server() -> s2n_quic_rustls::server::Server {
         .unwrap()
 }
 
+fn rustls_server_with_early_data() -> s2n_quic_rustls::server::Server {
+    s2n_quic_rustls::server::Builder::default()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_early_data()
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
 fn rustls_client() -> s2n_quic_rustls::client::Client {
     s2n_quic_rustls::client::Builder::default()
         .with_certificate(CERT_PEM)
@@ -187,6 +209,16 @@ fn rustls_client() -> s2n_quic_rustls::client::Client {
         .unwrap()
 }
 
+fn rustls_client_with_early_data() -> s2n_quic_rustls::client::Client {
+    s2n_quic_rustls::client::Builder::default()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_early_data()
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn s2n_client_s2n_server_ch_callback_test() {
@@ -207,6 +239,27 @@ fn s2n_client_s2n_server_test() {
     run(&mut server_endpoint, &mut client_endpoint, None);
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_s2n_server_version_2_test() {
+    let mut client_endpoint = s2n_client();
+    let mut server_endpoint = s2n_server();
+
+    let mut pair = tls::testing::Pair::with_quic_version(
+        &mut server_endpoint,
+        &mut client_endpoint,
+        "localhost".into(),
+        VERSION_2,
+    );
+
+    while pair.is_handshaking() {
+        pair.poll(None).unwrap();
+    }
+
+    // the keys of both endpoints are derived with the version 2 labels
+    pair.finish();
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn rustls_client_s2n_server_test() {
@@ -317,6 +370,128 @@ fn s2n_client_with_custom_hostname_auth_accepts_server_name() {
     run_result(&mut server_endpoint, &mut client_endpoint, None).unwrap();
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_s2n_server_resumption_test() {
+    let mut client_endpoint = s2n_client();
+    let mut server_endpoint = s2n_server_with_session_tickets();
+
+    let session_ticket = session_ticket(&mut server_endpoint, &mut client_endpoint);
+    let pair = resume(
+        &mut server_endpoint,
+        &mut client_endpoint,
+        &session_ticket,
+        anti_replay::Outcome::Reject,
+    );
+
+    // s2n-tls doesn't support early data in QUIC
+    assert!(pair.client.context.zero_rtt_crypto.is_none());
+    assert_eq!(pair.client.context.early_data_status, None);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn rustls_client_rustls_server_resumption_test() {
+    let mut client_endpoint = rustls_client();
+    let mut server_endpoint = rustls_server();
+
+    let session_ticket = session_ticket(&mut server_endpoint, &mut client_endpoint);
+    let pair = resume(
+        &mut server_endpoint,
+        &mut client_endpoint,
+        &session_ticket,
+        anti_replay::Outcome::Accept,
+    );
+
+    // the client didn't enable early data
+    assert!(pair.client.context.zero_rtt_crypto.is_none());
+    assert_eq!(
+        pair.client.context.early_data_status,
+        Some(tls::EarlyDataStatus::NotAttempted)
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn rustls_client_rustls_server_early_data_accepted_test() {
+    let mut client_endpoint = rustls_client_with_early_data();
+    let mut server_endpoint = rustls_server_with_early_data();
+
+    let session_ticket = session_ticket(&mut server_endpoint, &mut client_endpoint);
+    let pair = resume(
+        &mut server_endpoint,
+        &mut client_endpoint,
+        &session_ticket,
+        anti_replay::Outcome::Accept,
+    );
+
+    assert!(pair.client.context.zero_rtt_crypto.is_some());
+    assert!(pair.server.context.zero_rtt_crypto.is_some());
+    assert_eq!(
+        pair.client.context.early_data_status,
+        Some(tls::EarlyDataStatus::Accepted)
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn rustls_client_rustls_server_early_data_rejected_test() {
+    let mut client_endpoint = rustls_client_with_early_data();
+    let mut server_endpoint = rustls_server_with_early_data();
+
+    let session_ticket = session_ticket(&mut server_endpoint, &mut client_endpoint);
+    let pair = resume(
+        &mut server_endpoint,
+        &mut client_endpoint,
+        &session_ticket,
+        anti_replay::Outcome::Reject,
+    );
+
+    // the client still derives 0-RTT keys since it doesn't know the data will be rejected
+    assert!(pair.client.context.zero_rtt_crypto.is_some());
+    assert!(pair.server.context.zero_rtt_crypto.is_none());
+    assert_eq!(
+        pair.client.context.early_data_status,
+        Some(tls::EarlyDataStatus::Rejected)
+    );
+}
+
+/// Executes the handshake to completion and returns a session ticket issued by the server
+fn session_ticket<S: Endpoint, C: Endpoint>(server: &mut S, client: &mut C) -> bytes::Bytes {
+    let mut pair = tls::testing::Pair::new(server, client, "localhost".into());
+
+    while pair.is_handshaking() {
+        pair.poll(None).unwrap();
+    }
+
+    pair.finish();
+    pair.receive_session_tickets().unwrap();
+
+    pair.client
+        .context
+        .session_tickets
+        .pop()
+        .expect("the server should issue a session ticket")
+}
+
+/// Resumes a session with the `session_ticket` and executes the handshake to completion
+fn resume<S: Endpoint, C: Endpoint>(
+    server: &mut S,
+    client: &mut C,
+    session_ticket: &[u8],
+    early_data: anti_replay::Outcome,
+) -> tls::testing::Pair<S::Session, C::Session> {
+    let mut pair = tls::testing::Pair::resume(server, client, "localhost".into(), session_ticket);
+    pair.server.context.early_data = early_data;
+
+    while pair.is_handshaking() {
+        pair.poll(None).unwrap();
+    }
+
+    pair.finish();
+    pair
+}
+
 /// Executes the handshake to completion
 fn run_result<S: Endpoint, C: Endpoint>(
     server: &mut S,
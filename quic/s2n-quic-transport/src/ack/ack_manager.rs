@@ -11,6 +11,7 @@ use crate::{
     processed_packet::ProcessedPacket,
     transmission,
 };
+use core::time::Duration;
 use s2n_quic_core::{
     ack,
     counter::{Counter, Saturating},
@@ -19,7 +20,7 @@ use s2n_quic_core::{
         builder::{AckAction, AckProcessed},
         IntoEvent as _,
     },
-    frame::{ack::EcnCounts, Ack, Ping},
+    frame::{ack::EcnCounts, Ack, AckFrequency, Ping},
     packet::number::{PacketNumber, PacketNumberSpace},
     time::{timer, Timer, Timestamp},
     varint::VarInt,
@@ -214,19 +215,23 @@ impl AckManager {
         let now = processed_packet.datagram.timestamp;
 
         // perform some checks before inserting into the ack_ranges
-        let (is_ordered, is_largest) = self
+        let (is_largest, reordering_distance) = self
             .ack_ranges
             .max_value()
             .and_then(|max_value| {
-                // check to see if the packet number is the next one in the sequence
-                let is_ordered = packet_number == max_value.next()?;
-
                 // check to see if the packet number is the largest we've seen
                 let is_largest = packet_number > max_value;
 
-                Some((is_ordered, is_largest))
+                // compute how far the packet number is from the next one in the sequence
+                let reordering_distance = if is_largest {
+                    packet_number.checked_distance(max_value.next()?)?
+                } else {
+                    max_value.checked_distance(packet_number)?
+                };
+
+                Some((is_largest, reordering_distance))
             })
-            .unwrap_or((true, true));
+            .unwrap_or((true, 0));
 
         // This will fail if `packet_number` is less than `ack_ranges.min_value()`
         // and `ack_ranges` is at capacity.
@@ -301,14 +306,18 @@ impl AckManager {
             //# *  when the received packet has a packet number less than another
             //#    ack-eliciting packet that has been received, or
 
-            should_activate |= !is_largest;
-
             //= https://www.rfc-editor.org/rfc/rfc9000#section-13.2.1
             //# *  when the packet has a packet number larger than the highest-
             //#    numbered ack-eliciting packet that has been received and there are
             //#    missing packets between that packet and this packet.
 
-            should_activate |= !is_ordered;
+            // The peer can relax these conditions with the Reordering Threshold field of an
+            // ACK_FREQUENCY frame, which defaults to immediately acknowledging any out-of-order
+            // packet.
+            // See https://datatracker.ietf.org/doc/html/draft-ietf-quic-ack-frequency#section-6.2
+            let reordering_threshold = self.ack_settings.reordering_threshold as u64;
+            should_activate |=
+                reordering_threshold > 0 && reordering_distance >= reordering_threshold;
 
             //= https://www.rfc-editor.org/rfc/rfc9000#section-13.2.1
             //# Similarly, packets marked with the ECN Congestion Experienced (CE)
@@ -316,15 +325,10 @@ impl AckManager {
             //# reduce the peer's response time to congestion events.
             should_activate |= processed_packet.datagram.ecn.congestion_experienced();
 
-            // TODO update to draft link after published
-            // https://github.com/quicwg/base-drafts/pull/3623
-            // An ACK frame SHOULD be generated for at least every 10th ack-eliciting packet
-
-            // TODO support delayed ack proposal
-            // https://tools.ietf.org/html/draft-iyengar-quic-delayed-ack-00
-            let packet_tolerance = 10;
-
-            should_activate |= self.processed_packets_since_transmission >= packet_tolerance;
+            // The peer can change the tolerance with the Ack-Eliciting Threshold field of an
+            // ACK_FREQUENCY frame.
+            should_activate |=
+                self.processed_packets_since_transmission >= self.ack_settings.packet_tolerance;
 
             //= https://www.rfc-editor.org/rfc/rfc9000#section-9.3.3
             //# An endpoint that receives a PATH_CHALLENGE on an active path SHOULD
@@ -351,6 +355,28 @@ impl AckManager {
         }
     }
 
+    /// Called when the peer requests a different acknowledgement frequency
+    ///
+    /// The caller is responsible for ignoring frames with an obsolete sequence number.
+    pub fn on_ack_frequency(&mut self, frame: &AckFrequency) {
+        let to_u8 = |value: VarInt| value.as_u64().min(u8::MAX as u64) as u8;
+
+        // An ACK frame is sent once more than the threshold of packets is received
+        self.ack_settings.packet_tolerance = to_u8(
+            frame
+                .ack_eliciting_threshold
+                .saturating_add(VarInt::from_u8(1)),
+        );
+        self.ack_settings.max_ack_delay =
+            Duration::from_micros(frame.request_max_ack_delay.as_u64());
+        self.ack_settings.reordering_threshold = to_u8(frame.reordering_threshold);
+    }
+
+    /// Called when the peer requests an ACK frame to be sent without delay
+    pub fn on_immediate_ack(&mut self) {
+        self.transmission_state.activate();
+    }
+
     /// Called when the connection timer expired
     pub fn on_timeout(&mut self, timestamp: Timestamp) {
         // NOTE: ack_elicitation_timer is not actively polled
@@ -451,6 +477,46 @@ mod tests {
         assert!(manager.transmission_state.is_active());
     }
 
+    #[test]
+    fn ack_frequency() {
+        // Setup:
+        let mut manager =
+            AckManager::new(PacketNumberSpace::ApplicationData, ack::Settings::default());
+        let datagram = helper_datagram_info(ExplicitCongestionNotification::NotEct);
+        let path = helper_path_server();
+        let path_id = path::Id::test_id();
+        let mut publisher = Publisher::no_snapshot();
+
+        // Trigger:
+        manager.on_ack_frequency(&AckFrequency {
+            sequence_number: VarInt::from_u8(0),
+            ack_eliciting_threshold: VarInt::from_u8(9),
+            request_max_ack_delay: VarInt::from_u16(1000),
+            reordering_threshold: VarInt::from_u8(0),
+        });
+
+        // Expectation:
+        assert_eq!(manager.ack_settings.packet_tolerance, 10);
+        assert_eq!(manager.ack_settings.max_ack_delay, Duration::from_millis(1));
+        assert_eq!(manager.ack_settings.reordering_threshold, 0);
+
+        // out-of-order packets don't activate the transmission state with a threshold of 0
+        for pn in [1u8, 3] {
+            let pn = PacketNumberSpace::ApplicationData.new_packet_number(VarInt::from_u8(pn));
+            let mut processed_packet = ProcessedPacket::new(pn, &datagram);
+            processed_packet.ack_elicitation = AckElicitation::Eliciting;
+            manager.on_processed_packet(
+                &processed_packet,
+                path_event!(path, path_id),
+                &mut publisher,
+            );
+        }
+        assert!(!manager.transmission_state.is_active());
+
+        manager.on_immediate_ack();
+        assert!(manager.transmission_state.is_active());
+    }
+
     #[test]
     fn ecn_counts() {
         // Setup:
@@ -16,7 +16,9 @@ use core::{
 use s2n_quic_core::{
     application,
     application::ServerName,
-    inet::SocketAddress,
+    crypto::tls::EarlyDataStatus,
+    inet::{Dscp, SocketAddress},
+    path::migration,
     query::{Query, QueryMut},
     stream::StreamType,
 };
@@ -175,6 +177,16 @@ impl Connection {
         self.api.application_protocol()
     }
 
+    #[inline]
+    pub fn early_data_status(&self) -> Result<EarlyDataStatus, connection::Error> {
+        self.api.early_data_status()
+    }
+
+    #[inline]
+    pub fn received_early_data(&self) -> Result<bool, connection::Error> {
+        self.api.received_early_data()
+    }
+
     #[inline]
     pub fn id(&self) -> u64 {
         self.api.id()
@@ -189,6 +201,16 @@ impl Connection {
         self.api.keep_alive(enabled)
     }
 
+    #[inline]
+    pub fn migrate(&self, local_address: SocketAddress) -> Result<(), migration::Error> {
+        self.api.migrate(local_address)
+    }
+
+    #[inline]
+    pub fn set_dscp(&self, dscp: Dscp) -> Result<(), connection::Error> {
+        self.api.set_dscp(dscp)
+    }
+
     #[inline]
     pub fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         self.api.local_address()
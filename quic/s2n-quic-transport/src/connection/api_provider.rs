@@ -17,7 +17,9 @@ use core::{
 use s2n_quic_core::{
     application,
     application::ServerName,
-    inet::SocketAddress,
+    crypto::tls::EarlyDataStatus,
+    inet::{Dscp, SocketAddress},
+    path::migration,
     query::{Query, QueryMut},
     stream::{ops, StreamId, StreamType},
 };
@@ -58,12 +60,20 @@ pub(crate) trait ConnectionApiProvider: Sync + Send {
 
     fn application_protocol(&self) -> Result<Bytes, connection::Error>;
 
+    fn early_data_status(&self) -> Result<EarlyDataStatus, connection::Error>;
+
+    fn received_early_data(&self) -> Result<bool, connection::Error>;
+
     fn id(&self) -> u64;
 
     fn ping(&self) -> Result<(), connection::Error>;
 
     fn keep_alive(&self, enabled: bool) -> Result<(), connection::Error>;
 
+    fn migrate(&self, local_address: SocketAddress) -> Result<(), migration::Error>;
+
+    fn set_dscp(&self, dscp: Dscp) -> Result<(), connection::Error>;
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error>;
 
     fn remote_address(&self) -> Result<SocketAddress, connection::Error>;
@@ -12,7 +12,7 @@ use core::{task::Poll, time::Duration};
 use s2n_quic_core::{
     counter::{self, Counter},
     event,
-    inet::ExplicitCongestionNotification,
+    inet::{Dscp, ExplicitCongestionNotification},
     io::tx,
     time::{timer, Timer, Timestamp},
 };
@@ -141,9 +141,12 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher> tx::Message
 
     #[inline]
     fn ecn(&mut self) -> ExplicitCongestionNotification {
-        self.path
-            .ecn_controller
-            .ecn(transmission::Mode::Normal, self.timestamp)
+        self.path.ecn(transmission::Mode::Normal, self.timestamp)
+    }
+
+    #[inline]
+    fn dscp(&mut self) -> Dscp {
+        Default::default()
     }
 
     #[inline]
@@ -33,8 +33,10 @@ use intrusive_collections::{
 use s2n_quic_core::{
     application,
     application::ServerName,
+    crypto::tls::EarlyDataStatus,
     event::supervisor,
-    inet::SocketAddress,
+    inet::{Dscp, SocketAddress},
+    path::migration,
     query::{Query, QueryMut},
     recovery::K_GRANULARITY,
     time::Timestamp,
@@ -309,6 +311,14 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionApiProvider for Con
         self.api_read_call(|conn| Ok(conn.application_protocol()))
     }
 
+    fn early_data_status(&self) -> Result<EarlyDataStatus, connection::Error> {
+        self.api_read_call(|conn| Ok(conn.early_data_status()))
+    }
+
+    fn received_early_data(&self) -> Result<bool, connection::Error> {
+        self.api_read_call(|conn| Ok(conn.received_early_data()))
+    }
+
     fn id(&self) -> u64 {
         self.internal_connection_id.into()
     }
@@ -321,6 +331,14 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionApiProvider for Con
         self.api_write_call(|conn| conn.keep_alive(enabled))
     }
 
+    fn migrate(&self, local_address: SocketAddress) -> Result<(), migration::Error> {
+        self.api_write_call(|conn| conn.migrate(local_address))
+    }
+
+    fn set_dscp(&self, dscp: Dscp) -> Result<(), connection::Error> {
+        self.api_write_call(|conn| conn.set_dscp(dscp))
+    }
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         self.api_read_call(|conn| conn.local_address())
     }
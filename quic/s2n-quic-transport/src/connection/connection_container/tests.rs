@@ -19,7 +19,7 @@ use core::{
 use s2n_quic_core::{
     application, event,
     event::builder::DatagramDropReason,
-    inet::{DatagramInfo, SocketAddress},
+    inet::{DatagramInfo, Dscp, SocketAddress},
     io::tx,
     packet::{
         handshake::ProtectedHandshake,
@@ -129,6 +129,8 @@ impl connection::Trait for TestConnection {
     fn on_wakeup(
         &mut self,
         _timestamp: Timestamp,
+        _congestion_controller_endpoint: &mut <Self::Config as endpoint::Config>::CongestionControllerEndpoint,
+        _random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
         _subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
         _datagram: &mut <Self::Config as endpoint::Config>::DatagramEndpoint,
     ) -> Result<(), connection::Error> {
@@ -207,6 +209,7 @@ impl connection::Trait for TestConnection {
         _datagram: &DatagramInfo,
         _path_id: path::Id,
         _packet: ProtectedZeroRtt,
+        _random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
         _subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
         _packet_interceptor: &mut <Self::Config as endpoint::Config>::PacketInterceptor,
     ) -> Result<(), ProcessingError> {
@@ -248,6 +251,15 @@ impl connection::Trait for TestConnection {
         123
     }
 
+    fn on_quic_version(
+        &mut self,
+        _version: u32,
+        _timestamp: Timestamp,
+        _subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
+    ) -> bool {
+        todo!()
+    }
+
     fn poll_stream_request(
         &mut self,
         _stream_id: stream::StreamId,
@@ -286,6 +298,14 @@ impl connection::Trait for TestConnection {
         todo!()
     }
 
+    fn early_data_status(&self) -> s2n_quic_core::crypto::tls::EarlyDataStatus {
+        todo!()
+    }
+
+    fn received_early_data(&self) -> bool {
+        todo!()
+    }
+
     fn ping(&mut self) -> Result<(), connection::Error> {
         todo!()
     }
@@ -294,6 +314,14 @@ impl connection::Trait for TestConnection {
         todo!()
     }
 
+    fn migrate(&mut self, _local_address: SocketAddress) -> Result<(), path::migration::Error> {
+        todo!()
+    }
+
+    fn set_dscp(&mut self, _dscp: Dscp) -> Result<(), connection::Error> {
+        todo!()
+    }
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         todo!()
     }
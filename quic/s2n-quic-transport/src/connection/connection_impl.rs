@@ -42,7 +42,7 @@ use s2n_quic_core::{
         builder::{DatagramDropReason, MtuUpdatedCause, RxStreamProgress, TxStreamProgress},
         supervisor, ConnectionPublisher as _, IntoEvent as _, Subscriber,
     },
-    inet::{DatagramInfo, SocketAddress},
+    inet::{DatagramInfo, Dscp, SocketAddress},
     io::tx,
     packet::{
         handshake::ProtectedHandshake,
@@ -164,6 +164,8 @@ pub struct ConnectionImpl<Config: endpoint::Config> {
     path_manager: path::Manager<Config>,
     /// The limits applied to the current connection
     limits: Limits,
+    /// The Differentiated Services Code Point marked on transmitted packets
+    dscp: Dscp,
     /// The error set on the connection
     ///
     /// This is stored so future calls from the application return the same error
@@ -242,9 +244,7 @@ macro_rules! transmission_context {
         $packet_interceptor:expr,
         $(,)?
     ) => {{
-        let ecn = $self.path_manager[$path_id]
-            .ecn_controller
-            .ecn($transmission_mode, $timestamp);
+        let ecn = $self.path_manager[$path_id].ecn($transmission_mode, $timestamp);
 
         ConnectionTransmissionContext {
             quic_version: $self.event_context.quic_version,
@@ -254,6 +254,7 @@ macro_rules! transmission_context {
             local_id_registry: &mut $self.local_id_registry,
             outcome: $outcome,
             ecn,
+            dscp: $self.dscp,
             min_packet_len: None,
             transmission_mode: $transmission_mode,
             publisher: &mut $self.event_context.publisher($timestamp, $subscriber),
@@ -272,7 +273,7 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
         let mut publisher = self.event_context.publisher(timestamp, subscriber);
         let space_manager = &mut self.space_manager;
 
-        match space_manager.poll_crypto(
+        let poll = space_manager.poll_crypto(
             &mut self.path_manager,
             &mut self.local_id_registry,
             &mut self.limits,
@@ -280,7 +281,12 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
             &self.waker,
             &mut publisher,
             datagram,
-        ) {
+        );
+
+        // The server can switch to a compatible QUIC version once it receives the ClientHello
+        self.event_context.quic_version = space_manager.quic_version();
+
+        match poll {
             Poll::Ready(res) => res?,
             Poll::Pending => return Ok(()),
         }
@@ -436,9 +442,7 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
             }
 
             let transmission_mode = transmission::Mode::PathValidationOnly;
-            let ecn = path_manager[path_id]
-                .ecn_controller
-                .ecn(transmission_mode, timestamp);
+            let ecn = path_manager[path_id].ecn(transmission_mode, timestamp);
 
             if queue
                 .push(ConnectionTransmission {
@@ -451,6 +455,7 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
                         outcome,
                         min_packet_len: None,
                         ecn,
+                        dscp: self.dscp,
                         transmission_mode,
                         publisher: &mut self.event_context.publisher(timestamp, subscriber),
                         packet_interceptor,
@@ -609,6 +614,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             state: ConnectionState::Handshaking,
             path_manager,
             limits: parameters.limits,
+            dscp: parameters.limits.dscp(),
             error: Ok(()),
             close_sender: CloseSender::default(),
             space_manager: parameters.space_manager,
@@ -673,6 +679,26 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         self.event_context.quic_version
     }
 
+    /// Switches the client to the compatible QUIC version chosen by the server
+    fn on_quic_version(
+        &mut self,
+        version: u32,
+        timestamp: Timestamp,
+        subscriber: &mut Config::EventSubscriber,
+    ) -> bool {
+        if Config::ENDPOINT_TYPE.is_server() {
+            return false;
+        }
+
+        let mut publisher = self.event_context.publisher(timestamp, subscriber);
+        if !self.space_manager.on_quic_version(version, &mut publisher) {
+            return false;
+        }
+
+        self.event_context.quic_version = version;
+        true
+    }
+
     /// Initiates closing the connection as described in
     /// https://www.rfc-editor.org/rfc/rfc9000#section-10
     fn close(
@@ -830,6 +856,11 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
     ) -> Result<(), ConnectionOnTransmitError> {
         let mut count = 0;
 
+        // Packets can be written ahead of the pacer if the queue holds them until they are due
+        // to depart
+        let max_delay = queue.max_delay();
+        let pacing_horizon = timestamp + max_delay;
+
         debug_assert!(
             !self.path_manager.is_amplification_limited(),
             "connection should not express transmission interest if amplification limited"
@@ -844,7 +875,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 // MTU probes are prioritized over other data so they are not blocked by the
                 // congestion controller, as they are critical to achieving maximum throughput.
                 if self.state == ConnectionState::Active
-                    && self.path_manager.active_path().can_transmit(timestamp)
+                    && self.path_manager.active_path().can_transmit(pacing_horizon)
                     && self
                         .path_manager
                         .active_path()
@@ -869,7 +900,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 }
 
                 // Send all other data for the active path
-                while self.path_manager.active_path().can_transmit(timestamp)
+                while self.path_manager.active_path().can_transmit(pacing_horizon)
                     && queue
                         .push(ConnectionTransmission {
                             context: transmission_context!(
@@ -898,9 +929,10 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                     .congestion_controller
                     .earliest_departure_time()
                 {
-                    if !edt.has_elapsed(timestamp) {
+                    if !edt.has_elapsed(pacing_horizon) {
                         // We can't transmit more until a future time, so arm the pacing
-                        // timer to pause transmission until the earliest departure time.
+                        // timer to pause transmission until the earliest departure time is
+                        // within the horizon of the queue.
 
                         //= https://www.rfc-editor.org/rfc/rfc9002#section-7.7
                         //# A sender SHOULD pace sending of all in-flight packets based on input
@@ -908,7 +940,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
 
                         //= https://www.rfc-editor.org/rfc/rfc9002#section-7.7
                         //# Senders MUST either use pacing or limit such bursts.
-                        self.timers.pacing_timer.set(edt);
+                        self.timers.pacing_timer.set(edt - max_delay);
                     }
                 }
 
@@ -1079,6 +1111,8 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
     fn on_wakeup(
         &mut self,
         timestamp: Timestamp,
+        congestion_controller_endpoint: &mut Config::CongestionControllerEndpoint,
+        random_generator: &mut Config::RandomGenerator,
         subscriber: &mut Config::EventSubscriber,
         datagram: &mut Config::DatagramEndpoint,
     ) -> Result<(), connection::Error> {
@@ -1088,6 +1122,14 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         // check if crypto progress can be made
         self.update_crypto_state(timestamp, subscriber, datagram)?;
 
+        // create the path for a migration requested by the application
+        let mut publisher = self.event_context.publisher(timestamp, subscriber);
+        self.path_manager.on_wakeup(
+            congestion_controller_endpoint,
+            random_generator,
+            &mut publisher,
+        );
+
         // return an error if the application set one
         self.error?;
 
@@ -1445,8 +1487,30 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
                 packet_interceptor,
             )?;
 
+            // post-handshake CRYPTO data, like session tickets, is processed by the TLS session
+            // on the next wakeup
+            let has_crypto_data = space
+                .crypto_rx
+                .as_ref()
+                .map_or(false, |crypto_rx| !crypto_rx.is_empty());
+
+            if Self::Config::ENDPOINT_TYPE.is_server() {
+                //= https://www.rfc-editor.org/rfc/rfc9001#section-4.9.3
+                //# Additionally, a server MAY discard 0-RTT keys as soon as it receives
+                //# a 1-RTT packet.
+                self.space_manager.discard_zero_rtt_crypto();
+            }
+
             // notify the connection a packet was processed
             self.on_processed_packet(&processed_packet, subscriber)?;
+
+            // the path to the server's preferred address is created on the next wakeup
+            let migrate_to_preferred_address = self.space_manager.is_handshake_confirmed()
+                && self.path_manager.on_handshake_confirmed();
+
+            if has_crypto_data || migrate_to_preferred_address {
+                self.wakeup_handle.wakeup();
+            }
         }
 
         Ok(())
@@ -1497,20 +1561,12 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
     fn handle_zero_rtt_packet(
         &mut self,
         datagram: &DatagramInfo,
-        _path_id: path::Id,
-        _packet: ProtectedZeroRtt,
+        path_id: path::Id,
+        packet: ProtectedZeroRtt,
+        random_generator: &mut Config::RandomGenerator,
         subscriber: &mut Config::EventSubscriber,
-        _packet_interceptor: &mut Config::PacketInterceptor,
+        packet_interceptor: &mut Config::PacketInterceptor,
     ) -> Result<(), ProcessingError> {
-        let mut publisher = self.event_context.publisher(datagram.timestamp, subscriber);
-
-        publisher.on_packet_received(event::builder::PacketReceived {
-            packet_header: event::builder::PacketHeader::ZeroRtt {
-                // FIXME: replace with PacketHeader::new when we support zero-rtt.
-                number: 0,
-                version: publisher.quic_version(),
-            },
-        });
         //= https://www.rfc-editor.org/rfc/rfc9000#section-5.2.2
         //= type=TODO
         //= tracking-issue=339
@@ -1518,7 +1574,53 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         //# number of these packets in anticipation of a late-arriving Initial
         //# packet.
 
-        // TODO
+        // Clients never receive 0-RTT packets and servers only hold 0-RTT keys once early data
+        // has been accepted, so anything else is dropped.
+        if Self::Config::ENDPOINT_TYPE.is_client() {
+            return Ok(());
+        }
+
+        if let Some((space, handshake_status, (key, header_key))) =
+            self.space_manager.zero_rtt_mut()
+        {
+            let mut publisher = self.event_context.publisher(datagram.timestamp, subscriber);
+
+            let packet = space.validate_and_decrypt_zero_rtt_packet(
+                packet,
+                key,
+                header_key,
+                datagram,
+                path_id,
+                &self.path_manager[path_id],
+                &mut publisher,
+            )?;
+
+            publisher.on_packet_received(event::builder::PacketReceived {
+                packet_header: event::builder::PacketHeader::ZeroRtt {
+                    number: packet.packet_number.as_u64(),
+                    version: packet.version,
+                },
+            });
+
+            let processed_packet = space.handle_cleartext_payload(
+                packet.packet_number,
+                packet.payload,
+                datagram,
+                path_id,
+                &mut self.path_manager,
+                handshake_status,
+                &mut self.local_id_registry,
+                random_generator,
+                &mut publisher,
+                packet_interceptor,
+            )?;
+
+            self.space_manager.received_early_data = true;
+
+            // notify the connection a packet was processed
+            self.on_processed_packet(&processed_packet, subscriber)?;
+        }
+
         Ok(())
     }
 
@@ -1646,6 +1748,10 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             );
         }
 
+        if let Some((early_data, _crypto)) = self.space_manager.early_data_mut() {
+            early_data.on_retry_packet(path, path_id, &mut publisher);
+        }
+
         Ok(())
     }
 
@@ -1795,6 +1901,14 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         self.space_manager.application_protocol.clone()
     }
 
+    fn early_data_status(&self) -> tls::EarlyDataStatus {
+        self.space_manager.early_data_status
+    }
+
+    fn received_early_data(&self) -> bool {
+        self.space_manager.received_early_data
+    }
+
     fn ping(&mut self) -> Result<(), connection::Error> {
         self.error?;
 
@@ -1831,6 +1945,28 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         Ok(())
     }
 
+    fn migrate(&mut self, local_address: SocketAddress) -> Result<(), path::migration::Error> {
+        self.error?;
+
+        let handshake_confirmed = self.space_manager.is_handshake_confirmed();
+        self.path_manager
+            .on_migrate_request(local_address.into(), handshake_confirmed)?;
+
+        // the new path is created on the next wakeup
+        self.wakeup_handle.wakeup();
+
+        Ok(())
+    }
+
+    fn set_dscp(&mut self, dscp: Dscp) -> Result<(), connection::Error> {
+        self.error?;
+
+        // the code point is applied to the packets transmitted after this call
+        self.dscp = dscp;
+
+        Ok(())
+    }
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         Ok(*self.path_manager.active_path().handle.local_address())
     }
@@ -20,8 +20,9 @@ use s2n_codec::DecoderBufferMut;
 use s2n_quic_core::{
     application,
     application::ServerName,
+    crypto::tls::EarlyDataStatus,
     event::{self, builder::DatagramDropReason, supervisor, ConnectionPublisher, IntoEvent},
-    inet::{DatagramInfo, SocketAddress},
+    inet::{DatagramInfo, Dscp, SocketAddress},
     io::tx,
     packet::{
         handshake::ProtectedHandshake,
@@ -104,6 +105,8 @@ pub trait ConnectionTrait: 'static + Send + Sized {
     fn on_wakeup(
         &mut self,
         timestamp: Timestamp,
+        congestion_controller_endpoint: &mut <Self::Config as endpoint::Config>::CongestionControllerEndpoint,
+        random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
         subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
         datagram: &mut <Self::Config as endpoint::Config>::DatagramEndpoint,
     ) -> Result<(), connection::Error>;
@@ -176,6 +179,7 @@ pub trait ConnectionTrait: 'static + Send + Sized {
         datagram: &DatagramInfo,
         path_id: path::Id,
         packet: ProtectedZeroRtt,
+        random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
         subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
         packet_interceptor: &mut <Self::Config as endpoint::Config>::PacketInterceptor,
     ) -> Result<(), ProcessingError>;
@@ -208,6 +212,18 @@ pub trait ConnectionTrait: 'static + Send + Sized {
     /// Returns the QUIC version selected for the current connection
     fn quic_version(&self) -> u32;
 
+    /// Called when a client receives an Initial packet with a different QUIC version than the
+    /// connection uses
+    ///
+    /// Returns `true` if the client switched to the version, which the server can choose from
+    /// the compatible versions the client offered.
+    fn on_quic_version(
+        &mut self,
+        version: u32,
+        timestamp: Timestamp,
+        subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
+    ) -> bool;
+
     /// Handles reception of a single QUIC packet
     #[allow(clippy::too_many_arguments)]
     fn handle_packet(
@@ -223,8 +239,15 @@ pub trait ConnectionTrait: 'static + Send + Sized {
         //= https://www.rfc-editor.org/rfc/rfc9000#section-5.2.1
         //# If a client receives a packet that uses a different version than it
         //# initially selected, it MUST discard that packet.
+        //
+        // Compatible version negotiation is the exception, where the server responds with a
+        // version the client offered in its transport parameters.
+        // See https://www.rfc-editor.org/rfc/rfc9368#section-2.2
         if let Some(version) = packet.version() {
-            if version != self.quic_version() {
+            if version != self.quic_version()
+                && !(matches!(packet, ProtectedPacket::Initial(_))
+                    && self.on_quic_version(version, datagram.timestamp, subscriber))
+            {
                 self.with_event_publisher(
                     datagram.timestamp,
                     Some(path_id),
@@ -278,6 +301,7 @@ pub trait ConnectionTrait: 'static + Send + Sized {
                 datagram,
                 path_id,
                 packet,
+                random_generator,
                 subscriber,
                 packet_interceptor,
             ),
@@ -434,10 +458,18 @@ pub trait ConnectionTrait: 'static + Send + Sized {
 
     fn application_protocol(&self) -> Bytes;
 
+    fn early_data_status(&self) -> EarlyDataStatus;
+
+    fn received_early_data(&self) -> bool;
+
     fn ping(&mut self) -> Result<(), connection::Error>;
 
     fn keep_alive(&mut self, enabled: bool) -> Result<(), connection::Error>;
 
+    fn migrate(&mut self, local_address: SocketAddress) -> Result<(), path::migration::Error>;
+
+    fn set_dscp(&mut self, dscp: Dscp) -> Result<(), connection::Error>;
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error>;
 
     fn remote_address(&self) -> Result<SocketAddress, connection::Error>;
@@ -380,6 +380,41 @@ impl LocalIdRegistry {
         Ok(())
     }
 
+    /// Registers the connection ID sent to the peer in the `preferred_address` transport
+    /// parameter
+    ///
+    /// The connection ID is communicated in the handshake rather than in a NEW_CONNECTION_ID
+    /// frame, so it starts in the `Active` status.
+    pub fn register_preferred_address_connection_id(
+        &mut self,
+        id: &connection::LocalId,
+        expiration: Option<Timestamp>,
+        stateless_reset_token: stateless_reset::Token,
+    ) -> Result<(), LocalIdRegistrationError> {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1.1
+        //# If the preferred_address transport
+        //# parameter is sent, the sequence number of the supplied connection ID
+        //# is 1.
+        if self.next_sequence_number != 1 {
+            return Err(LocalIdRegistrationError::InvalidSequenceNumber);
+        }
+
+        self.register_connection_id(id, expiration, stateless_reset_token)?;
+
+        let id_info = self
+            .registered_ids
+            .iter_mut()
+            .find(|id_info| id_info.sequence_number == 1)
+            .expect("preferred address id added above");
+        id_info.status = Active;
+        self.transmission_interest.clear();
+        self.active_id_count.clear();
+
+        self.check_consistency();
+
+        Ok(())
+    }
+
     /// Unregisters connection IDs that have expired
     fn unregister_expired_ids(&mut self, timestamp: Timestamp) {
         {
@@ -130,6 +130,38 @@ fn sequence_number_must_increase_by_one() {
     assert_eq!(1, seq_num_2 - seq_num_1);
 }
 
+//= https://www.rfc-editor.org/rfc/rfc9000#section-5.1.1
+//= type=test
+//# If the preferred_address transport
+//# parameter is sent, the sequence number of the supplied connection ID
+//# is 1.
+#[test]
+fn preferred_address_connection_id() {
+    let ext_id_1 = id(b"id01");
+    let ext_id_2 = id(b"id02");
+    let ext_id_3 = id(b"id03");
+
+    let (_, mut reg) = mapper(ext_id_1, None, TEST_TOKEN_1);
+    reg.set_active_connection_id_limit(3);
+    reg.register_preferred_address_connection_id(&ext_id_2, None, TEST_TOKEN_2)
+        .unwrap();
+
+    let id_info = reg.get_connection_id_info(&ext_id_2).unwrap();
+    assert_eq!(1, id_info.sequence_number);
+    // the connection ID was sent in the transport parameters so it doesn't need to be issued
+    assert_eq!(Active, id_info.status);
+    assert_eq!(
+        transmission::Interest::None,
+        reg.get_transmission_interest()
+    );
+
+    // only the second connection ID can be sent in the preferred address
+    assert_eq!(
+        Err(LocalIdRegistrationError::InvalidSequenceNumber),
+        reg.register_preferred_address_connection_id(&ext_id_3, None, TEST_TOKEN_3)
+    );
+}
+
 #[test]
 fn connection_mapper_test() {
     let mut id_generator = InternalConnectionIdGenerator::new();
@@ -579,6 +579,22 @@ impl PeerIdRegistry {
         self.consume_new_id_inner()
     }
 
+    /// Retires a peer_id that was consumed for a new path that was never created
+    pub fn retire_unused_id(&mut self, peer_id: &connection::PeerId) {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1.2
+        //# An endpoint MUST NOT forget a connection ID without retiring it
+        if let Some(id_info) = self
+            .registered_ids
+            .iter_mut()
+            .find(|id_info| &id_info.id == peer_id && id_info.status == InUse)
+        {
+            id_info.status = PendingRetirement;
+            self.transmission_interest.clear();
+        }
+
+        self.check_consistency();
+    }
+
     // Validate that the ACTIVE_CONNECTION_ID_LIMIT has not been exceeded
     fn check_active_connection_id_limit(
         &self,
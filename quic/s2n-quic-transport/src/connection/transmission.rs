@@ -10,7 +10,7 @@ use s2n_codec::{Encoder, EncoderBuffer};
 use s2n_quic_core::{
     event::{self, ConnectionPublisher as _},
     frame::ack_elicitation::AckElicitable,
-    inet::ExplicitCongestionNotification,
+    inet::{Dscp, ExplicitCongestionNotification},
     io::tx,
     packet::{encoding::PacketEncodingError, number::PacketNumberSpace},
     recovery::{CongestionController, MAX_BURST_PACKETS},
@@ -26,6 +26,7 @@ pub struct ConnectionTransmissionContext<'a, 'sub, Config: endpoint::Config> {
     pub local_id_registry: &'a mut connection::LocalIdRegistry,
     pub outcome: &'a mut transmission::Outcome,
     pub ecn: ExplicitCongestionNotification,
+    pub dscp: Dscp,
     pub min_packet_len: Option<usize>,
     pub transmission_mode: transmission::Mode,
     pub publisher: &'a mut event::ConnectionPublisherSubscriber<'sub, Config::EventSubscriber>,
@@ -47,6 +48,21 @@ pub struct ConnectionTransmission<'a, 'sub, Config: endpoint::Config> {
     pub space_manager: &'a mut PacketSpaceManager<Config>,
 }
 
+impl<'a, 'sub, Config: endpoint::Config> ConnectionTransmission<'a, 'sub, Config> {
+    /// Returns the time the next packet is scheduled to depart
+    ///
+    /// This is never earlier than the time of the transmission.
+    #[inline]
+    fn departure_time(&self) -> Timestamp {
+        let timestamp = self.context.timestamp;
+        self.context
+            .path()
+            .congestion_controller
+            .earliest_departure_time()
+            .map_or(timestamp, |edt| edt.max(timestamp))
+    }
+}
+
 impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<'a, 'sub, Config> {
     type Handle = Config::PathHandle;
 
@@ -60,10 +76,17 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
         self.context.ecn
     }
 
+    #[inline]
+    fn dscp(&mut self) -> Dscp {
+        self.context.dscp
+    }
+
     #[inline]
     fn delay(&mut self) -> Duration {
-        // TODO return delay from pacer
-        Default::default()
+        // packets are only written ahead of the pacer when the queue can hold them until they
+        // are due to depart
+        self.departure_time()
+            .saturating_duration_since(self.context.timestamp)
     }
 
     #[inline]
@@ -97,6 +120,14 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
         buffer: tx::PayloadBuffer,
         gso_offset: usize,
     ) -> Result<usize, tx::Error> {
+        // A packet written ahead of the pacer is held by the queue until its departure time,
+        // so the packet is recorded as sent at that time. Otherwise RTT samples and loss
+        // detection would include the time the packet spent waiting in the queue.
+        //
+        // The queue reads the delay before the payload is written, so the delay is still
+        // relative to the time of the transmission.
+        self.context.timestamp = self.departure_time();
+
         let space_manager = &mut self.space_manager;
         let buffer = unsafe {
             // the WriterContext has its own checks for buffer capacity so convert `buffer` into a
@@ -242,6 +273,53 @@ impl<'a, 'sub, Config: endpoint::Config> tx::Message for ConnectionTransmission<
                 encoder
             };
 
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-12.2
+            //# Coalescing packets in order of increasing encryption levels (Initial,
+            //# 0-RTT, Handshake, 1-RTT; see Section 4.1.4 of [QUIC-TLS]) makes it
+            //# more likely that the receiver will be able to process all the packets
+            //# in a single pass.
+            let encoder = if let Some((early_data, crypto)) = space_manager
+                .early_data_mut()
+                // MTU probes are only sent in the Application Space
+                .filter(|_| !is_mtu_probing)
+            {
+                // The Initial packet is padded by itself when there is no Handshake or 1-RTT
+                // packet, so 0-RTT packets don't need to be padded
+                self.context.min_packet_len = None;
+
+                match early_data.on_transmit(
+                    crypto,
+                    &mut self.context,
+                    transmission_constraint,
+                    encoder,
+                ) {
+                    Ok((outcome, encoder)) => {
+                        *self.context.outcome += outcome;
+                        encoder
+                    }
+                    Err(PacketEncodingError::PacketNumberTruncationError(encoder)) => {
+                        // 0-RTT packets aren't acknowledged until the 1-RTT keys are
+                        // available, so stop sending them and move to the next packet space
+                        early_data.on_packet_number_truncation_error();
+                        encoder
+                    }
+                    Err(PacketEncodingError::InsufficientSpace(encoder)) => {
+                        // move to the next packet space
+                        encoder
+                    }
+                    Err(PacketEncodingError::EmptyPayload(encoder)) => {
+                        // move to the next packet space
+                        encoder
+                    }
+                    Err(PacketEncodingError::AeadLimitReached(encoder)) => {
+                        // move to the next packet space
+                        encoder
+                    }
+                }
+            } else {
+                encoder
+            };
+
             let encoder = if let Some((space, handshake_status)) = space_manager
                 .handshake_mut()
                 // MTU probes are only sent in the Application Space
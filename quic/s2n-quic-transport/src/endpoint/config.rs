@@ -5,8 +5,8 @@
 
 use crate::{connection, stream};
 use s2n_quic_core::{
-    crypto::tls, datagram, endpoint, event, packet, path, random, recovery::congestion_controller,
-    stateless_reset,
+    anti_replay, crypto::tls, datagram, endpoint, event, packet, path, random,
+    recovery::congestion_controller, session_ticket, stateless_reset,
 };
 
 /// Configuration parameters for a QUIC endpoint
@@ -44,6 +44,14 @@ pub trait Config: 'static + Send + Sized + core::fmt::Debug {
     type PacketInterceptor: packet::interceptor::Interceptor;
     /// The datagram implementation for the endpoint
     type DatagramEndpoint: datagram::Endpoint;
+    /// The session ticket store for the endpoint
+    type SessionTicketStore: session_ticket::Store;
+    /// The anti-replay guard for early data accepted by the endpoint
+    type AntiReplayGuard: anti_replay::Guard;
+    /// Selects the preferred address advertised by the endpoint
+    type PreferredAddressSelector: path::preferred_address::Selector;
+    /// Orders the transmission of streams in each connection
+    type StreamScheduler: s2n_quic_core::stream::Scheduler + Clone;
 
     /// The type of the local endpoint
     const ENDPOINT_TYPE: endpoint::Type;
@@ -87,4 +95,12 @@ pub struct Context<'a, Cfg: Config> {
     pub packet_interceptor: &'a mut Cfg::PacketInterceptor,
 
     pub datagram: &'a mut Cfg::DatagramEndpoint,
+
+    pub session_ticket_store: &'a mut Cfg::SessionTicketStore,
+
+    pub anti_replay: &'a mut Cfg::AntiReplayGuard,
+
+    pub preferred_address: &'a mut Cfg::PreferredAddressSelector,
+
+    pub stream_scheduler: &'a mut Cfg::StreamScheduler,
 }
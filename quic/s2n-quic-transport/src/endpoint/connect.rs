@@ -5,6 +5,7 @@ use crate::{
     connection::{self, Connection},
     endpoint::handle::ConnectorSender,
 };
+use bytes::Bytes;
 use core::{
     fmt,
     future::Future,
@@ -12,7 +13,9 @@ use core::{
     task::{Context, Poll},
 };
 use futures_channel::oneshot;
-use s2n_quic_core::{application::ServerName, inet::SocketAddress, path::RemoteAddress};
+use s2n_quic_core::{
+    application::ServerName, inet::SocketAddress, packet::long::VERSION_1, path::RemoteAddress,
+};
 
 /// Held by connection Attempt future. Used to receive the actual connection.
 pub(crate) type ConnectionReceiver = oneshot::Receiver<Result<Connection, connection::Error>>;
@@ -25,6 +28,9 @@ pub(crate) type ConnectionSender = oneshot::Sender<Result<Connection, connection
 pub struct Connect {
     pub(crate) remote_address: RemoteAddress,
     pub(crate) server_name: Option<ServerName>,
+    pub(crate) session_ticket: Option<Bytes>,
+    pub(crate) early_data: Option<Bytes>,
+    pub(crate) quic_version: u32,
 }
 
 impl fmt::Display for Connect {
@@ -49,6 +55,9 @@ impl Connect {
         Self {
             remote_address: addr.into().into(),
             server_name: None,
+            session_ticket: None,
+            early_data: None,
+            quic_version: VERSION_1,
         }
     }
 
@@ -60,6 +69,53 @@ impl Connect {
             ..self
         }
     }
+
+    /// Specifies a session ticket from a previous connection to resume
+    ///
+    /// The ticket is opaque to the transport and must have been issued to a session created by
+    /// the same TLS provider. If the ticket allows it, the client will attempt to send early
+    /// (0-RTT) data. Whether the server accepted the early data can be queried with
+    /// [`Connection::early_data_status`](crate::connection::Connection::early_data_status).
+    #[must_use]
+    pub fn with_session_ticket<Ticket: Into<Bytes>>(self, session_ticket: Ticket) -> Self {
+        Self {
+            session_ticket: Some(session_ticket.into()),
+            ..self
+        }
+    }
+
+    /// Specifies data to send on the first bidirectional stream of the connection
+    ///
+    /// When resuming a session which allows it, the data is sent in 0-RTT packets before the
+    /// handshake completes. The data is sent again after the handshake in case the server
+    /// rejected it or the packets were lost. The stream is returned by the first call to
+    /// [`Connection::poll_open_stream`](crate::connection::Connection::poll_open_stream) for a
+    /// bidirectional stream, which can be used to send the rest of the request and receive the
+    /// response.
+    ///
+    /// The data is discarded if the server doesn't allow the client to open any bidirectional
+    /// streams.
+    #[must_use]
+    pub fn with_early_data<Data: Into<Bytes>>(self, early_data: Data) -> Self {
+        Self {
+            early_data: Some(early_data.into()),
+            ..self
+        }
+    }
+
+    /// Specifies the QUIC version of the client's first Initial packet
+    ///
+    /// Defaults to QUIC version 1. The client also offers the other versions supported by the
+    /// TLS provider in the `version_information` transport parameter, so the server can switch
+    /// to a version it prefers during the handshake. The connection attempt fails if the
+    /// version isn't supported.
+    #[must_use]
+    pub fn with_quic_version(self, quic_version: u32) -> Self {
+        Self {
+            quic_version,
+            ..self
+        }
+    }
 }
 
 /// Make it easy for applications to create a connection attempt without importing the `Connect` struct
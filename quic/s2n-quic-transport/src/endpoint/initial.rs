@@ -8,7 +8,7 @@ use crate::{
         limits::{ConnectionInfo as LimitsInfo, Limiter as _},
         Trait as _,
     },
-    endpoint,
+    endpoint::{self, version},
     recovery::congestion_controller::{self, Endpoint as _},
     space::PacketSpaceManager,
 };
@@ -20,9 +20,15 @@ use s2n_quic_core::{
     event::{self, supervisor, ConnectionPublisher, IntoEvent, Subscriber as _},
     inet::{datagram, DatagramInfo},
     packet::initial::ProtectedInitial,
-    path::Handle as _,
+    path::{
+        preferred_address::{self, Selector as _},
+        Handle as _,
+    },
     stateless_reset::token::Generator as _,
-    transport::{self, parameters::ServerTransportParameters},
+    transport::{
+        self,
+        parameters::{PreferredAddress, ServerTransportParameters},
+    },
 };
 
 impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
@@ -101,11 +107,15 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             .try_into()
             .map_err(transport::Error::from)?;
 
+        // The version negotiator has already checked that the version is supported
+        let quic_version = packet.version;
+
         //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.5.2
         //# Changing the Destination Connection ID field also results in
         //# a change to the keys used to protect the Initial packet.
         let (initial_key, initial_header_key) =
             <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialKey::new_server(
+                quic_version,
                 datagram.destination_connection_id.as_bytes(),
             );
 
@@ -130,7 +140,7 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             .stateless_reset_token_generator
             .generate(initial_connection_id.as_bytes());
 
-        let local_id_registry = self.connection_id_mapper.create_local_id_registry(
+        let mut local_id_registry = self.connection_id_mapper.create_local_id_registry(
             internal_connection_id,
             &initial_connection_id,
             initial_connection_id_expiration_time,
@@ -201,6 +211,52 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
         .try_into()
         .unwrap();
 
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9.6.1
+        //# A server conveys a preferred address by including the
+        //# preferred_address transport parameter in the TLS handshake.
+        let preferred_address = self
+            .config
+            .context()
+            .preferred_address
+            .on_connection(&preferred_address::Info::new(&remote_address));
+
+        if let Some(addresses) = preferred_address {
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
+            //# A server
+            //# that chooses a zero-length connection ID MUST NOT provide a
+            //# preferred address.  Similarly, a server MUST NOT include a zero-
+            //# length connection ID in this transport parameter.
+            let connection_info = ConnectionInfo::new(&remote_address);
+            let connection_id = self
+                .config
+                .context()
+                .connection_id_format
+                .generate(&connection_info);
+            let stateless_reset_token = self
+                .config
+                .context()
+                .stateless_reset_token_generator
+                .generate(connection_id.as_bytes());
+
+            // The connection ID is only advertised if it can be used to route packets to the
+            // connection
+            if local_id_registry
+                .register_preferred_address_connection_id(
+                    &connection_id,
+                    initial_connection_id_expiration_time,
+                    stateless_reset_token,
+                )
+                .is_ok()
+            {
+                transport_parameters.preferred_address = Some(PreferredAddress {
+                    ipv4_address: addresses.ipv4(),
+                    ipv6_address: addresses.ipv6(),
+                    connection_id: connection_id.into(),
+                    stateless_reset_token,
+                });
+            }
+        }
+
         let endpoint_context = self.config.context();
 
         transport_parameters.max_datagram_frame_size = endpoint_context
@@ -209,6 +265,14 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             .try_into()
             .expect("Failed to convert max_datagram_frame_size");
 
+        // The chosen version is updated if the server switches to a compatible version after
+        // receiving the client's transport parameters
+        // See https://www.rfc-editor.org/rfc/rfc9368#section-2.3
+        transport_parameters.version_information = Some(
+            version::SupportedVersions::new::<Config::TLSEndpoint>()
+                .version_information(quic_version),
+        );
+
         let tls_session = endpoint_context
             .tls
             .new_server_session(&transport_parameters);
@@ -218,8 +282,6 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             .congestion_controller
             .new_congestion_controller(path_info);
 
-        let quic_version = packet.version;
-
         let meta = event::builder::ConnectionMeta {
             endpoint_type: Config::ENDPOINT_TYPE,
             id: internal_connection_id.into(),
@@ -235,7 +297,11 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
 
         let mut event_context = endpoint_context.event_subscriber.create_connection_context(
             &meta.clone().into_event(),
-            &event::builder::ConnectionInfo {}.into_event(),
+            &event::builder::ConnectionInfo {
+                original_destination_connection_id: (&original_destination_connection_id)
+                    .into_event(),
+            }
+            .into_event(),
         );
 
         let mut publisher = event::ConnectionPublisherSubscriber::new(
@@ -248,6 +314,12 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
         let space_manager = PacketSpaceManager::new(
             original_destination_connection_id,
             tls_session,
+            endpoint_context.session_ticket_store.clone(),
+            endpoint_context.anti_replay.clone(),
+            endpoint_context.stream_scheduler.clone(),
+            self.send_buffer_budget.clone(),
+            quic_version,
+            Some(transport_parameters),
             initial_key,
             initial_header_key,
             datagram.timestamp,
@@ -14,6 +14,7 @@ use crate::{
     endpoint::close::CloseHandle,
     recovery::congestion_controller::{self, Endpoint as _},
     space::PacketSpaceManager,
+    stream::send_buffer,
     wakeup_queue::WakeupQueue,
 };
 use alloc::collections::VecDeque;
@@ -39,10 +40,11 @@ use s2n_quic_core::{
     path,
     path::{Handle as _, MaxMtu},
     random::Generator as _,
+    session_ticket::Store as _,
     stateless_reset::token::{Generator as _, LEN as StatelessResetTokenLen},
     time::{Clock, Timestamp},
     token::{self, Format},
-    transport::parameters::ClientTransportParameters,
+    transport::{self, parameters::ClientTransportParameters},
 };
 
 pub mod close;
@@ -53,7 +55,7 @@ mod initial;
 mod packet_buffer;
 mod retry;
 mod stateless_reset;
-mod version;
+pub(crate) mod version;
 
 // exports
 pub use config::{Config, Context};
@@ -87,6 +89,8 @@ pub struct Endpoint<Cfg: Config> {
     close_packet_buffer: packet_buffer::Buffer,
     /// The largest maximum transmission unit (MTU) that can be sent on a path
     max_mtu: MaxMtu,
+    /// Limits the memory used by stream send buffers across all connections
+    send_buffer_budget: send_buffer::Budget,
 }
 
 impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
@@ -115,9 +119,9 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
                 }
             };
 
-            if let Some((header, payload)) = entry.read(&local_address) {
-                self.receive_datagram(&header, payload, timestamp)
-            }
+            entry.read_segments(&local_address, |header, payload| {
+                self.receive_datagram(header, payload, timestamp)
+            });
         }
 
         let len = entries.len();
@@ -217,6 +221,8 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
 
                 if let Err(error) = conn.on_wakeup(
                     timestamp,
+                    endpoint_context.congestion_controller,
+                    endpoint_context.random_generator,
                     endpoint_context.event_subscriber,
                     endpoint_context.datagram,
                 ) {
@@ -308,6 +314,13 @@ impl<Cfg: Config> Endpoint<Cfg> {
         let connection_id_mapper =
             ConnectionIdMapper::new(config.context().random_generator, Cfg::ENDPOINT_TYPE);
 
+        let send_buffer_budget = config
+            .context()
+            .endpoint_limits
+            .max_send_buffer_memory()
+            .map(send_buffer::Budget::new)
+            .unwrap_or_default();
+
         let endpoint = Self {
             config,
             connections: ConnectionContainer::new(acceptor_sender, connector_receiver),
@@ -321,6 +334,7 @@ impl<Cfg: Config> Endpoint<Cfg> {
             stateless_reset_dispatch: stateless_reset::Dispatch::default(),
             close_packet_buffer: Default::default(),
             max_mtu: Default::default(),
+            send_buffer_budget,
         };
 
         (endpoint, handle)
@@ -974,10 +988,22 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 endpoint::connect::Connect {
                     remote_address,
                     server_name: hostname,
+                    session_ticket,
+                    early_data,
+                    quic_version,
                 },
             sender,
         } = request;
 
+        let supported_versions = version::SupportedVersions::new::<Cfg::TLSEndpoint>();
+        if !supported_versions.contains(quic_version) {
+            let error = transport::Error::VERSION_NEGOTIATION_ERROR
+                .with_reason("the QUIC version isn't supported")
+                .into();
+            let _ = sender.send(Err(error));
+            return Err(error);
+        }
+
         let internal_connection_id = self.connection_id_generator.generate_id();
         let local_connection_id = self
             .config
@@ -1043,11 +1069,6 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 .new_congestion_controller(path_info)
         };
 
-        //= https://www.rfc-editor.org/rfc/rfc9000#section-15
-        //# This version of the specification is identified by the number
-        //# 0x00000001.
-        let quic_version = 0x00000001;
-
         let meta = event::builder::ConnectionMeta {
             endpoint_type: Cfg::ENDPOINT_TYPE,
             id: internal_connection_id.into(),
@@ -1061,7 +1082,11 @@ impl<Cfg: Config> Endpoint<Cfg> {
         );
         let mut event_context = endpoint_context.event_subscriber.create_connection_context(
             &meta.clone().into_event(),
-            &event::builder::ConnectionInfo {}.into_event(),
+            &event::builder::ConnectionInfo {
+                original_destination_connection_id: (&original_destination_connection_id)
+                    .into_event(),
+            }
+            .into_event(),
         );
         let mut publisher = event::ConnectionPublisherSubscriber::new(
             meta,
@@ -1091,6 +1116,11 @@ impl<Cfg: Config> Endpoint<Cfg> {
         .try_into()
         .unwrap();
 
+        // Offer the other versions so the server can switch to a compatible version
+        // See https://www.rfc-editor.org/rfc/rfc9368#section-2.3
+        transport_parameters.version_information =
+            Some(supported_versions.version_information(quic_version));
+
         //= https://www.rfc-editor.org/rfc/rfc9000#section-7.2
         //# The Destination Connection ID field from the first Initial packet
         //# sent by a client is used to determine packet protection keys for
@@ -1100,25 +1130,50 @@ impl<Cfg: Config> Endpoint<Cfg> {
         // protection keys.
         let (initial_key, initial_header_key) =
             <<Cfg::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialKey::new_client(
+                quic_version,
                 original_destination_connection_id.as_bytes(),
             );
-        let tls_session = endpoint_context
-            .tls
-            // TODO should SNI be optional? rustls expects a SNI but other tls providers dont seem
-            // to require this value.
-            .new_client_session(
+        // TODO should SNI be optional? rustls expects a SNI but other tls providers dont seem
+        // to require this value.
+        let server_name = hostname.expect("application should provide a valid server name");
+
+        // use a ticket from a previous connection if the application didn't provide one
+        let session_ticket = session_ticket.or_else(|| {
+            endpoint_context
+                .session_ticket_store
+                .take_session_ticket(&server_name)
+        });
+
+        let tls_session = if let Some(session_ticket) = session_ticket.as_deref() {
+            endpoint_context.tls.resume_client_session(
                 &transport_parameters,
-                hostname.expect("application should provide a valid server name"),
-            );
-        let space_manager = PacketSpaceManager::new(
+                server_name,
+                session_ticket,
+            )
+        } else {
+            endpoint_context
+                .tls
+                .new_client_session(&transport_parameters, server_name)
+        };
+        let mut space_manager = PacketSpaceManager::new(
             original_destination_connection_id,
             tls_session,
+            endpoint_context.session_ticket_store.clone(),
+            endpoint_context.anti_replay.clone(),
+            endpoint_context.stream_scheduler.clone(),
+            self.send_buffer_budget.clone(),
+            quic_version,
+            None,
             initial_key,
             initial_header_key,
             timestamp,
             &mut publisher,
         );
 
+        if let Some(early_data) = early_data {
+            space_manager.on_early_data(early_data, timestamp);
+        }
+
         let wakeup_handle = self
             .wakeup_queue
             .create_wakeup_handle(internal_connection_id);
@@ -1181,6 +1236,10 @@ pub mod testing {
         type PathMigrationValidator = path::migration::default::Validator;
         type PacketInterceptor = s2n_quic_core::packet::interceptor::Disabled;
         type DatagramEndpoint = s2n_quic_core::datagram::Disabled;
+        type SessionTicketStore = s2n_quic_core::session_ticket::Disabled;
+        type AntiReplayGuard = s2n_quic_core::anti_replay::Disabled;
+        type PreferredAddressSelector = path::preferred_address::Disabled;
+        type StreamScheduler = s2n_quic_core::stream::scheduler::Urgency;
 
         fn context(&mut self) -> super::Context<Self> {
             todo!()
@@ -1211,6 +1270,45 @@ pub mod testing {
         type PathMigrationValidator = path::migration::default::Validator;
         type PacketInterceptor = s2n_quic_core::packet::interceptor::Disabled;
         type DatagramEndpoint = s2n_quic_core::datagram::Disabled;
+        type SessionTicketStore = s2n_quic_core::session_ticket::Disabled;
+        type AntiReplayGuard = s2n_quic_core::anti_replay::Disabled;
+        type PreferredAddressSelector = path::preferred_address::Disabled;
+        type StreamScheduler = s2n_quic_core::stream::scheduler::Urgency;
+
+        fn context(&mut self) -> super::Context<Self> {
+            todo!()
+        }
+
+        const ENDPOINT_TYPE: endpoint::Type = endpoint::Type::Client;
+    }
+
+    /// A client with path handles that track the local address, which is required for migration
+    #[derive(Debug)]
+    pub struct TupleClient;
+
+    impl Config for TupleClient {
+        type CongestionControllerEndpoint =
+            crate::recovery::congestion_controller::testing::mock::Endpoint;
+        type TLSEndpoint = s2n_quic_core::crypto::tls::testing::Endpoint;
+        type PathHandle = path::Tuple;
+        type Connection = connection::Implementation<Self>;
+        type ConnectionLock = std::sync::Mutex<Self::Connection>;
+        type EndpointLimits = Limits;
+        type ConnectionIdFormat = connection::id::testing::Format;
+        type StatelessResetTokenGenerator = stateless_reset::token::testing::Generator;
+        type RandomGenerator = random::testing::Generator;
+        type TokenFormat = s2n_quic_core::token::testing::Format;
+        type ConnectionLimits = s2n_quic_core::connection::limits::Limits;
+        type Stream = crate::stream::StreamImpl;
+        type ConnectionCloseFormatter = s2n_quic_core::connection::close::Development;
+        type EventSubscriber = Subscriber;
+        type PathMigrationValidator = path::migration::default::Validator;
+        type PacketInterceptor = s2n_quic_core::packet::interceptor::Disabled;
+        type DatagramEndpoint = s2n_quic_core::datagram::Disabled;
+        type SessionTicketStore = s2n_quic_core::session_ticket::Disabled;
+        type AntiReplayGuard = s2n_quic_core::anti_replay::Disabled;
+        type PreferredAddressSelector = path::preferred_address::Disabled;
+        type StreamScheduler = s2n_quic_core::stream::scheduler::Urgency;
 
         fn context(&mut self) -> super::Context<Self> {
             todo!()
@@ -6,40 +6,45 @@ use alloc::collections::VecDeque;
 use core::time::Duration;
 use s2n_codec::{Encoder, EncoderBuffer, EncoderValue};
 use s2n_quic_core::{
+    crypto::tls,
     event,
-    inet::ExplicitCongestionNotification,
+    inet::{Dscp, ExplicitCongestionNotification},
     io::tx,
     packet,
-    packet::ProtectedPacket,
+    packet::{
+        long::{VERSION_1, VERSION_2},
+        ProtectedPacket,
+    },
     path::{self, MINIMUM_MTU},
+    transport::parameters::VersionInformation,
 };
 
 #[derive(Debug)]
 pub struct Negotiator<Config: endpoint::Config> {
     transmissions: VecDeque<Transmission<Config::PathHandle>>,
     max_peers: usize,
+    supported_versions: SupportedVersions,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct Error;
 
+/// The QUIC versions supported by the transport, in order of preference
 const SUPPORTED_VERSIONS: &[u32] = &[
-    0x1, // Draft 34 / Version 1 (https://github.com/quicwg/base-drafts/wiki/21st-Implementation-Draft)
+    VERSION_2, // https://www.rfc-editor.org/rfc/rfc9369
+    VERSION_1, // https://www.rfc-editor.org/rfc/rfc9000
 ];
 
 macro_rules! is_supported {
-    ($packet:ident, $publisher:ident) => {{
-        let supported = SUPPORTED_VERSIONS
-            .iter()
-            .cloned()
-            .any(|v| v == $packet.version);
+    ($self:ident, $packet:ident, $publisher:ident) => {{
+        let supported = $self.supported_versions.contains($packet.version);
 
         if supported {
             //= https://tools.ietf.org/id/draft-marx-qlog-event-definitions-quic-h3-02#5.3.1
             //# Upon receiving a client initial with a supported version, the
             //# server logs this event with server_versions and chosen_version set
             $publisher.on_version_information(event::builder::VersionInformation {
-                server_versions: &SUPPORTED_VERSIONS,
+                server_versions: $self.supported_versions.as_slice(),
                 client_versions: &[],
                 chosen_version: Some($packet.version),
             });
@@ -51,7 +56,7 @@ macro_rules! is_supported {
             //# client's attempted version.  The absence of chosen_version implies
             //# no overlap was found.
             $publisher.on_version_information(event::builder::VersionInformation {
-                server_versions: &SUPPORTED_VERSIONS,
+                server_versions: $self.supported_versions.as_slice(),
                 client_versions: &[$packet.version],
                 chosen_version: None,
             });
@@ -76,6 +81,7 @@ impl<Config: endpoint::Config> Negotiator<Config> {
                 VecDeque::new()
             },
             max_peers,
+            supported_versions: SupportedVersions::new::<Config::TLSEndpoint>(),
         }
     }
 
@@ -93,13 +99,13 @@ impl<Config: endpoint::Config> Negotiator<Config> {
 
         let packet = match packet {
             ProtectedPacket::Initial(packet) => {
-                if is_supported!(packet, publisher) {
+                if is_supported!(self, packet, publisher) {
                     return Ok(());
                 }
                 packet
             }
             ProtectedPacket::ZeroRtt(packet) => {
-                if is_supported!(packet, publisher) {
+                if is_supported!(self, packet, publisher) {
                     return Ok(());
                 }
 
@@ -150,8 +156,11 @@ impl<Config: endpoint::Config> Negotiator<Config> {
                 //= https://www.rfc-editor.org/rfc/rfc9000#section-5.2.2
                 //# Servers SHOULD respond with a Version
                 //# Negotiation packet, provided that the datagram is sufficiently long.
-                self.transmissions
-                    .push_back(Transmission::new(*path, packet));
+                self.transmissions.push_back(Transmission::new(
+                    *path,
+                    packet,
+                    self.supported_versions,
+                ));
             }
         }
 
@@ -211,11 +220,15 @@ impl<Path: path::Handle> core::fmt::Debug for Transmission<Path> {
 }
 
 impl<Path: path::Handle> Transmission<Path> {
-    pub fn new(path: Path, initial_packet: &packet::initial::ProtectedInitial) -> Self {
+    pub fn new(
+        path: Path,
+        initial_packet: &packet::initial::ProtectedInitial,
+        supported_versions: SupportedVersions,
+    ) -> Self {
         let mut packet_buf = [0u8; MINIMUM_MTU as usize];
         let version_packet = packet::version_negotiation::VersionNegotiation::from_initial(
             initial_packet,
-            SupportedVersions,
+            supported_versions,
         );
 
         let mut buffer = EncoderBuffer::new(&mut packet_buf);
@@ -248,6 +261,11 @@ impl<Path: path::Handle> tx::Message for &Transmission<Path> {
         Default::default()
     }
 
+    #[inline]
+    fn dscp(&mut self) -> Dscp {
+        Default::default()
+    }
+
     #[inline]
     fn delay(&mut self) -> Duration {
         Default::default()
@@ -273,12 +291,73 @@ impl<Path: path::Handle> tx::Message for &Transmission<Path> {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct SupportedVersions;
+/// The QUIC versions supported by both the transport and the TLS provider, in order of
+/// preference
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SupportedVersions {
+    versions: [u32; SUPPORTED_VERSIONS.len()],
+    len: usize,
+}
+
+impl SupportedVersions {
+    /// Returns the versions supported by the transport which the TLS provider can derive
+    /// packet protection keys for
+    pub fn new<Endpoint: tls::Endpoint>() -> Self {
+        Self::from_tls_versions(Endpoint::QUIC_VERSIONS)
+    }
+
+    fn from_tls_versions(tls_versions: &[u32]) -> Self {
+        let mut value = Self {
+            versions: [0; SUPPORTED_VERSIONS.len()],
+            len: 0,
+        };
+
+        for version in SUPPORTED_VERSIONS {
+            if tls_versions.contains(version) {
+                value.versions[value.len] = *version;
+                value.len += 1;
+            }
+        }
+
+        value
+    }
+
+    #[inline]
+    pub fn contains(&self, version: u32) -> bool {
+        self.as_slice().contains(&version)
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[u32] {
+        &self.versions[..self.len]
+    }
+
+    /// Returns the version a server switches to from the `original_version` of the client
+    ///
+    /// All of the supported versions are compatible with each other, so the most preferred
+    /// version the client is willing to use is selected.
+    ///
+    /// See <https://www.rfc-editor.org/rfc/rfc9369#section-4>
+    pub fn select(&self, original_version: u32, client_versions: &[u32]) -> u32 {
+        debug_assert!(self.contains(original_version));
+
+        self.as_slice()
+            .iter()
+            .copied()
+            .find(|version| *version == original_version || client_versions.contains(version))
+            .unwrap_or(original_version)
+    }
+
+    /// Returns the `version_information` transport parameter for the `chosen_version`
+    pub fn version_information(&self, chosen_version: u32) -> VersionInformation {
+        VersionInformation::new(chosen_version, self.as_slice())
+            .expect("the supported versions fit in the transport parameter")
+    }
+}
 
 impl EncoderValue for SupportedVersions {
     fn encode<E: Encoder>(&self, encoder: &mut E) {
-        for version in SUPPORTED_VERSIONS {
+        for version in self.as_slice() {
             encoder.encode(version);
         }
 
@@ -475,7 +554,7 @@ mod tests {
                 tag: 0,
                 destination_connection_id: &[1u8, 2, 3][..],
                 source_connection_id: &[4u8, 5, 6][..],
-                supported_versions: SupportedVersions,
+                supported_versions: SupportedVersions::new::<C::TLSEndpoint>(),
             }
         )
     }
@@ -656,6 +735,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn server_version_2_test() {
+        let mut server = Server::default();
+        let mut publisher = Publisher::no_snapshot();
+
+        for version in [VERSION_1, VERSION_2] {
+            assert_eq!(
+                on_initial_packet(datagram_info(1200), version, &mut server, &mut publisher),
+                Ok(()),
+                "server implementations should allow supported versions through to connections"
+            );
+        }
+
+        assert!(
+            server.transmissions.is_empty(),
+            "servers should not negotiate with supported versions"
+        );
+    }
+
+    #[test]
+    fn supported_versions_test() {
+        // the TLS provider limits the versions
+        let versions = SupportedVersions::from_tls_versions(&[VERSION_1]);
+        assert_eq!(versions.as_slice(), [VERSION_1]);
+        assert!(!versions.contains(VERSION_2));
+        assert_eq!(
+            versions.select(VERSION_1, &[VERSION_2, VERSION_1]),
+            VERSION_1
+        );
+
+        // the transport's preference is used
+        let versions = SupportedVersions::from_tls_versions(&[VERSION_1, VERSION_2, 0xff00_001d]);
+        assert_eq!(versions.as_slice(), [VERSION_2, VERSION_1]);
+
+        // the server switches to the most preferred version the client offered
+        assert_eq!(
+            versions.select(VERSION_1, &[VERSION_1, VERSION_2]),
+            VERSION_2
+        );
+        assert_eq!(versions.select(VERSION_1, &[VERSION_1]), VERSION_1);
+        assert_eq!(versions.select(VERSION_1, &[]), VERSION_1);
+        assert_eq!(versions.select(VERSION_2, &[VERSION_1]), VERSION_2);
+
+        let version_information = versions.version_information(VERSION_1);
+        assert_eq!(version_information.chosen_version(), VERSION_1);
+        assert_eq!(
+            version_information.available_versions(),
+            [VERSION_2, VERSION_1]
+        );
+    }
+
     #[test]
     fn server_other_packets_test() {
         let mut server = Server::default();
@@ -261,9 +261,15 @@ impl Controller {
         //# From the "unknown" state, successful validation of the ECN counts in an ACK frame
         //# (see Section 13.4.2.1) causes the ECN state for the path to become "capable",
         //# unless no marked packet has been acknowledged.
-        if matches!(self.state, State::Unknown)
-            && newly_acked_ecn_counts.ect_0_count > VarInt::from_u8(0)
-        {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-13.4.2
+        //# Implementations that use the ECT(1)
+        //# codepoint need to perform ECN validation using the reported ECT(1)
+        //# counts.
+        let newly_acked_ect_count = newly_acked_ecn_counts
+            .ect_0_count
+            .saturating_add(newly_acked_ecn_counts.ect_1_count);
+
+        if matches!(self.state, State::Unknown) && newly_acked_ect_count > VarInt::from_u8(0) {
             // Arm the ce suppression timer to send a ECN-CE marked packet to test for
             // CE suppression by the peer.
             let mut ce_suppression_timer = Timer::default();
@@ -283,12 +289,22 @@ impl Controller {
     //# ECN validation also fails if the sum of the increase in ECT(0)
     //# and ECN-CE counts is less than the number of newly acknowledged
     //# packets that were originally sent with an ECT(0) marking.
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-13.4.2.1
+    //# Similarly, ECN
+    //# validation fails if the sum of the increases to ECT(1) and ECN-CE
+    //# counts is less than the number of newly acknowledged packets sent
+    //# with an ECT(1) marking.
     #[inline]
     fn ce_remarking(incremental_ecn_counts: EcnCounts, newly_acked_ecn_counts: EcnCounts) -> bool {
         let ect_0_increase = incremental_ecn_counts
             .ect_0_count
             .saturating_add(incremental_ecn_counts.ce_count);
+        let ect_1_increase = incremental_ecn_counts
+            .ect_1_count
+            .saturating_add(incremental_ecn_counts.ce_count);
         ect_0_increase < newly_acked_ecn_counts.ect_0_count
+            || ect_1_increase < newly_acked_ecn_counts.ect_1_count
     }
 
     //= https://www.rfc-editor.org/rfc/rfc9000#section-13.4.2.1
@@ -331,11 +347,6 @@ impl Controller {
         path: event::builder::Path,
         publisher: &mut Pub,
     ) {
-        debug_assert!(
-            !matches!(ecn, ExplicitCongestionNotification::Ect1),
-            "Ect1 is not used"
-        );
-
         if let (true, State::Testing(ref mut packet_count)) = (ecn.using_ecn(), &mut self.state) {
             *packet_count += 1;
 
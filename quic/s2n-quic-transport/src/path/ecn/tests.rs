@@ -342,6 +342,34 @@ fn validate_ecn_ce_remarking() {
     assert!(matches!(controller.state, State::Failed(_)));
 }
 
+//= https://www.rfc-editor.org/rfc/rfc9000#section-13.4.2.1
+//= type=test
+//# Similarly, ECN
+//# validation fails if the sum of the increases to ECT(1) and ECN-CE
+//# counts is less than the number of newly acknowledged packets sent
+//# with an ECT(1) marking.
+#[test]
+fn validate_ect_1_ce_remarking() {
+    let mut publisher = Publisher::no_snapshot();
+    let mut controller = Controller::default();
+    let now = s2n_quic_platform::time::now();
+    let expected_ecn_counts = helper_ecn_counts(0, 1, 0);
+    let sent_packet_ecn_counts = helper_ecn_counts(0, 1, 0);
+    let outcome = controller.validate(
+        expected_ecn_counts,
+        sent_packet_ecn_counts,
+        EcnCounts::default(),
+        Some(EcnCounts::default()),
+        now,
+        Duration::default(),
+        Path::test(),
+        &mut publisher,
+    );
+
+    assert_eq!(ValidationOutcome::Failed, outcome);
+    assert!(matches!(controller.state, State::Failed(_)));
+}
+
 //= https://www.rfc-editor.org/rfc/rfc9000#section-13.4.2.1
 //= type=test
 //# ECN validation can fail if the received total count for either ECT(0) or ECT(1)
@@ -562,6 +590,37 @@ fn validate_capable() {
     }
 }
 
+//= https://www.rfc-editor.org/rfc/rfc9000#section-13.4.2
+//= type=test
+//# Implementations that use the ECT(1)
+//# codepoint need to perform ECN validation using the reported ECT(1)
+//# counts.
+#[test]
+fn validate_capable_ect_1() {
+    let mut publisher = Publisher::no_snapshot();
+    let mut controller = Controller {
+        state: State::Unknown,
+        ..Default::default()
+    };
+    let now = s2n_quic_platform::time::now();
+    let expected_ecn_counts = helper_ecn_counts(0, 2, 0);
+    let ack_frame_ecn_counts = helper_ecn_counts(0, 2, 0);
+    let sent_packet_ecn_counts = helper_ecn_counts(0, 2, 0);
+    let outcome = controller.validate(
+        expected_ecn_counts,
+        sent_packet_ecn_counts,
+        EcnCounts::default(),
+        Some(ack_frame_ecn_counts),
+        now,
+        Duration::from_millis(50),
+        Path::test(),
+        &mut publisher,
+    );
+
+    assert_eq!(ValidationOutcome::Passed, outcome);
+    assert!(controller.is_capable());
+}
+
 #[test]
 fn validate_capable_congestion_experienced() {
     let mut publisher = Publisher::snapshot();
@@ -763,6 +822,24 @@ fn on_packet_sent() {
     assert_eq!(State::Unknown, controller.state);
 }
 
+// Congestion controllers such as Prague mark packets ECT(1)
+#[test]
+fn on_packet_sent_ect_1() {
+    let mut publisher = Publisher::no_snapshot();
+    let mut controller = Controller::default();
+
+    for i in 0..TESTING_PACKET_THRESHOLD {
+        assert_eq!(State::Testing(i), controller.state);
+        controller.on_packet_sent(
+            ExplicitCongestionNotification::Ect1,
+            Path::test(),
+            &mut publisher,
+        );
+    }
+
+    assert_eq!(State::Unknown, controller.state);
+}
+
 #[test]
 fn on_packet_loss() {
     let mut publisher = Publisher::snapshot();
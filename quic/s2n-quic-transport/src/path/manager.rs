@@ -15,11 +15,11 @@ use s2n_quic_core::{
     event::{self, builder::DatagramDropReason, IntoEvent},
     frame,
     frame::path_validation,
-    inet::DatagramInfo,
+    inet::{DatagramInfo, SocketAddress, Unspecified as _},
     packet::number::PacketNumberSpace,
     path::{
         migration::{self, Validator as _},
-        Handle as _, Id, MaxMtu,
+        Handle as _, Id, LocalAddress, MaxMtu, RemoteAddress,
     },
     random,
     recovery::{
@@ -28,7 +28,7 @@ use s2n_quic_core::{
     },
     stateless_reset,
     time::{timer, Timestamp},
-    transport,
+    transport::{self, parameters::PreferredAddress},
 };
 use smallvec::SmallVec;
 
@@ -68,6 +68,29 @@ pub struct Manager<Config: endpoint::Config> {
     /// The `paths` data structure will need to be enhanced to include garbage collection
     /// of old paths to overcome this limitation.
     pending_packet_authentication: Option<u8>,
+
+    /// A migration to a new local address requested by the application
+    ///
+    /// The path is created the next time the connection is woken up.
+    pending_migration: Option<PendingMigration<Config>>,
+
+    /// Index of the path the application is migrating to
+    ///
+    /// The path becomes active once it has been validated.
+    migrating_path: Option<u8>,
+
+    /// Set if the peer sent the `disable_active_migration` transport parameter
+    peer_migration_disabled: bool,
+
+    /// The server's preferred address the client will migrate to once the handshake is confirmed
+    preferred_address: Option<RemoteAddress>,
+}
+
+#[derive(Debug)]
+struct PendingMigration<Config: endpoint::Config> {
+    handle: Config::PathHandle,
+    /// The connection ID for the new path, if one needs to be created
+    peer_connection_id: Option<PeerId>,
 }
 
 impl<Config: endpoint::Config> Manager<Config> {
@@ -78,6 +101,10 @@ impl<Config: endpoint::Config> Manager<Config> {
             active: 0,
             last_known_active_validated_path: None,
             pending_packet_authentication: None,
+            pending_migration: None,
+            migrating_path: None,
+            peer_migration_disabled: false,
+            preferred_address: None,
         };
         manager.paths[0].activated = true;
         manager.paths[0].is_active = true;
@@ -194,21 +221,33 @@ impl<Config: endpoint::Config> Manager<Config> {
     /// Returns the Path for the provided address if the PathManager knows about it
     #[inline]
     pub fn path(&self, handle: &Config::PathHandle) -> Option<(Id, &Path<Config>)> {
-        self.paths
-            .iter()
-            .enumerate()
-            .find(|(_id, path)| Path::eq_by_handle(path, handle))
-            .map(|(id, path)| (path_id(id as u8), path))
+        let id = self.path_index(handle)?;
+        Some((path_id(id), &self.paths[id as usize]))
     }
 
     /// Returns the Path for the provided address if the PathManager knows about it
     #[inline]
     pub fn path_mut(&mut self, handle: &Config::PathHandle) -> Option<(Id, &mut Path<Config>)> {
-        self.paths
-            .iter_mut()
-            .enumerate()
-            .find(|(_id, path)| Path::eq_by_handle(path, handle))
-            .map(|(id, path)| (path_id(id as u8), path))
+        let id = self.path_index(handle)?;
+        Some((path_id(id), &mut self.paths[id as usize]))
+    }
+
+    #[inline]
+    fn path_index(&self, handle: &Config::PathHandle) -> Option<u8> {
+        let mut paths = self.paths.iter().enumerate();
+
+        let position = if Config::ENDPOINT_TYPE.is_client() {
+            // Paths created by a local migration have a known local address, while the initial
+            // path matches any local address. Search from the most recently created path so the
+            // more specific paths are preferred.
+            paths
+                .rev()
+                .find(|(_id, path)| Path::eq_by_handle(path, handle))
+        } else {
+            paths.find(|(_id, path)| Path::eq_by_handle(path, handle))
+        };
+
+        position.map(|(id, _path)| id as u8)
     }
 
     /// Returns an iterator over all paths pending path_challenge or path_response
@@ -458,6 +497,317 @@ impl<Config: endpoint::Config> Manager<Config> {
         Ok((new_path_id, unblocked))
     }
 
+    /// Called when the peer's transport parameters disable active migration
+    #[inline]
+    pub fn on_peer_migration_disabled(&mut self) {
+        self.peer_migration_disabled = true;
+    }
+
+    /// Called when the server sends the `preferred_address` transport parameter
+    pub fn on_preferred_address(&mut self, preferred_address: &PreferredAddress) {
+        debug_assert!(Config::ENDPOINT_TYPE.is_client());
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9.6.3
+        //# A client that migrates to a new address SHOULD use a preferred
+        //# address from the same address family for the server.
+        let remote_address = match *self.active_path().remote_address() {
+            SocketAddress::IpV4(_) => preferred_address.ipv4_address.map(SocketAddress::from),
+            SocketAddress::IpV6(address) => {
+                if let SocketAddress::IpV4(_) = address.unmap() {
+                    // the IPv6 socket is connected to an IPv4-mapped address
+                    preferred_address
+                        .ipv4_address
+                        .map(|address| address.to_ipv6_mapped().into())
+                } else {
+                    preferred_address.ipv6_address.map(SocketAddress::from)
+                }
+            }
+        };
+
+        self.preferred_address = remote_address.map(RemoteAddress::from);
+    }
+
+    /// Called when the handshake is confirmed
+    ///
+    /// Returns `true` if a migration to the server's preferred address is pending, in which case
+    /// the path is created on the next wakeup.
+    pub fn on_handshake_confirmed(&mut self) -> bool {
+        let remote_address = if let Some(remote_address) = self.preferred_address.take() {
+            remote_address
+        } else {
+            return false;
+        };
+
+        // the application already requested a migration
+        if self.pending_migration.is_some() || self.paths.len() >= MAX_ALLOWED_PATHS {
+            return false;
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9.6.1
+        //# A client constructs packets using any previously
+        //# unused active connection ID, taken from either the preferred_address
+        //# transport parameter or a NEW_CONNECTION_ID frame.
+        let peer_connection_id =
+            if let Some(peer_connection_id) = self.peer_id_registry.consume_new_id_for_new_path() {
+                peer_connection_id
+            } else {
+                return false;
+            };
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9.6.1
+        //# Once the handshake is confirmed, the client SHOULD select one of the
+        //# two addresses provided by the server and initiate path validation
+        //# (see Section 8.2).
+        self.pending_migration = Some(PendingMigration {
+            handle: Config::PathHandle::from_remote_address(remote_address),
+            peer_connection_id: Some(peer_connection_id),
+        });
+
+        true
+    }
+
+    /// Called when the application requests to migrate the connection to a new local address
+    ///
+    /// The new path is validated before it becomes the active path.
+    pub fn on_migrate_request(
+        &mut self,
+        local_address: LocalAddress,
+        handshake_confirmed: bool,
+    ) -> Result<(), migration::Error> {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+        //# Clients are responsible for initiating all migrations.
+        if Config::ENDPOINT_TYPE.is_server() {
+            return Err(migration::Error::NotClient);
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+        //# An endpoint MUST NOT initiate
+        //# connection migration before the handshake is confirmed, as defined in
+        //# Section 4.1.2 of [QUIC-TLS].
+        if !handshake_confirmed {
+            return Err(migration::Error::HandshakeNotConfirmed);
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+        //# If the peer sent the disable_active_migration transport parameter, an
+        //# endpoint also MUST NOT send packets (including probing packets; see
+        //# Section 9.1) from a different local address to the address the peer
+        //# used during the handshake, unless the endpoint has acted on a
+        //# preferred_address transport parameter from the peer.
+        if self.peer_migration_disabled {
+            return Err(migration::Error::DisabledByPeer);
+        }
+
+        // a port of 0 keeps the local port of the active path
+        let mut local_address = local_address;
+        if local_address.port() == 0 {
+            local_address.set_port(self.active_path().local_address().port());
+        }
+
+        let handle = self
+            .active_path()
+            .handle
+            .with_local_address(local_address)
+            .ok_or(migration::Error::Unsupported)?;
+
+        if let Some(id) = self.local_path_index(&handle) {
+            if id == self.active || self.migrating_path == Some(id) {
+                return Err(migration::Error::PathExists);
+            }
+
+            // the connection ID reserved by a previous request is no longer needed
+            self.retire_pending_migration_id();
+
+            // a previous migration to this address failed so validate the path again
+            self.pending_migration = Some(PendingMigration {
+                handle,
+                peer_connection_id: None,
+            });
+
+            return Ok(());
+        }
+
+        if self.paths.len() >= MAX_ALLOWED_PATHS {
+            return Err(migration::Error::PathLimitExceeded);
+        }
+
+        // reuse the connection ID from a previous request that hasn't been processed yet
+        let pending_connection_id = self
+            .pending_migration
+            .as_mut()
+            .and_then(|pending| pending.peer_connection_id.take());
+
+        let peer_connection_id = if let Some(peer_connection_id) = pending_connection_id {
+            peer_connection_id
+        } else {
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-9.5
+            //# An endpoint MUST NOT reuse a connection ID when sending from more
+            //# than one local address -- for example, when initiating connection
+            //# migration as described in Section 9.2 or when probing a new network
+            //# path as described in Section 9.1.
+            self.peer_id_registry
+                .consume_new_id_for_new_path()
+                .ok_or(migration::Error::InsufficientConnectionIds)?
+        };
+
+        self.pending_migration = Some(PendingMigration {
+            handle,
+            peer_connection_id: Some(peer_connection_id),
+        });
+
+        Ok(())
+    }
+
+    /// Retires the connection ID reserved for a pending migration
+    #[inline]
+    fn retire_pending_migration_id(&mut self) {
+        if let Some(peer_connection_id) = self
+            .pending_migration
+            .as_mut()
+            .and_then(|pending| pending.peer_connection_id.take())
+        {
+            self.peer_id_registry.retire_unused_id(&peer_connection_id);
+        }
+    }
+
+    /// Returns the index of the path with the same remote and local address as the handle
+    #[inline]
+    fn local_path_index(&self, handle: &Config::PathHandle) -> Option<u8> {
+        let local = handle.local_address();
+
+        self.paths
+            .iter()
+            .position(|path| {
+                let local_address = path.local_address();
+                !local_address.ip().is_unspecified()
+                    && local_address.ip() == local.ip()
+                    && local_address.port() == local.port()
+                    && s2n_quic_core::path::Handle::eq(
+                        &path.handle.remote_address(),
+                        &handle.remote_address(),
+                    )
+            })
+            .map(|id| id as u8)
+    }
+
+    /// Creates the path for a pending migration requested by the application
+    pub fn on_wakeup<Pub: event::ConnectionPublisher>(
+        &mut self,
+        congestion_controller_endpoint: &mut Config::CongestionControllerEndpoint,
+        random_generator: &mut dyn random::Generator,
+        publisher: &mut Pub,
+    ) {
+        let pending = if let Some(pending) = self.pending_migration.take() {
+            pending
+        } else {
+            return;
+        };
+
+        if let Some(id) = self.local_path_index(&pending.handle) {
+            // the peer may have created the path since the request was made
+            if let Some(peer_connection_id) = pending.peer_connection_id {
+                self.peer_id_registry.retire_unused_id(&peer_connection_id);
+            }
+
+            self.set_challenge(path_id(id), random_generator);
+            self.migrating_path = Some(id);
+            return;
+        }
+
+        let peer_connection_id = if let Some(peer_connection_id) = pending.peer_connection_id {
+            peer_connection_id
+        } else {
+            return;
+        };
+
+        // the path limit may have been reached since the request was made
+        if self.paths.len() >= MAX_ALLOWED_PATHS {
+            self.peer_id_registry.retire_unused_id(&peer_connection_id);
+            return;
+        }
+
+        let new_path_idx = self.paths.len();
+        let new_path_id = path_id(new_path_idx as u8);
+
+        let rtt = RttEstimator::new(self.active_path().rtt_estimator.max_ack_delay());
+        let path_info = congestion_controller::PathInfo::new(&pending.handle.remote_address());
+        let cc = congestion_controller_endpoint.new_congestion_controller(path_info);
+
+        // the peer validated its address during the handshake
+        let path = Path::new(
+            pending.handle,
+            peer_connection_id,
+            self.active_path().local_connection_id,
+            rtt,
+            cc,
+            true,
+            self.max_mtu(),
+        );
+
+        let active_path = self.active_path();
+        let active_path_id = self.active_path_id();
+        publisher.on_path_created(event::builder::PathCreated {
+            active: path_event!(active_path, active_path_id),
+            new: path_event!(path, new_path_id),
+        });
+
+        publisher.on_mtu_updated(event::builder::MtuUpdated {
+            path_id: new_path_id.into_event(),
+            mtu: path.mtu_controller.mtu() as u16,
+            cause: MtuUpdatedCause::NewPath,
+        });
+
+        self.paths.push(path);
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9.2
+        //# To establish reachability on the new path, an endpoint initiates path
+        //# validation (Section 8.2) on the new path.
+        self.set_challenge(new_path_id, random_generator);
+        self.migrating_path = Some(new_path_idx as u8);
+    }
+
+    /// Switches to the path the application is migrating to, once it has been validated
+    fn on_migration_validated<Pub: event::ConnectionPublisher>(
+        &mut self,
+        new_path_id: Id,
+        publisher: &mut Pub,
+    ) {
+        self.migrating_path = None;
+
+        if new_path_id == self.active_path_id() {
+            return;
+        }
+
+        // The path's connection id might have retired since it was created
+        let peer_connection_id = self[new_path_id].peer_connection_id;
+        if !self.peer_id_registry.is_active(&peer_connection_id) {
+            if let Some(peer_connection_id) = self
+                .peer_id_registry
+                .consume_new_id_for_existing_path(new_path_id, peer_connection_id, publisher)
+            {
+                self[new_path_id].peer_connection_id = peer_connection_id;
+            } else {
+                // without a connection id the migration can't continue
+                return;
+            }
+        }
+
+        if self.active_path().is_validated() {
+            self.last_known_active_validated_path = Some(self.active);
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-9.2
+        //# An endpoint can migrate a connection to a new local address by
+        //# sending packets containing non-probing frames from that address.
+        let prev_path_id = self.active_path_id();
+        self.activate_path(publisher, prev_path_id, new_path_id);
+
+        // Restart ECN validation to check that the path still supports ECN
+        let path = self.active_path_mut();
+        path.ecn_controller
+            .restart(path_event!(path, new_path_id), publisher);
+    }
+
     fn set_challenge(&mut self, path_id: Id, random_generator: &mut dyn random::Generator) {
         //= https://www.rfc-editor.org/rfc/rfc9000#section-8.2.1
         //# The endpoint MUST use unpredictable data in every PATH_CHALLENGE
@@ -567,6 +917,8 @@ impl<Config: endpoint::Config> Manager<Config> {
         //# A PATH_RESPONSE frame received on any network path validates the path
         //# on which the PATH_CHALLENGE was sent.
 
+        let mut validated_path = None;
+
         for (id, path) in self.paths.iter_mut().enumerate() {
             if path.on_path_response(response.data) {
                 let id = id as u64;
@@ -580,9 +932,16 @@ impl<Config: endpoint::Config> Manager<Config> {
                 if path.is_activated() {
                     self.last_known_active_validated_path = Some(id as u8);
                 }
+                validated_path = Some(id as u8);
                 break;
             }
         }
+
+        if let Some(id) = validated_path {
+            if self.migrating_path == Some(id) {
+                self.on_migration_validated(path_id(id), publisher);
+            }
+        }
     }
 
     /// Process a packet and update internal state.
@@ -722,6 +1081,13 @@ impl<Config: endpoint::Config> Manager<Config> {
             path.on_timeout(timestamp, path_id(id as u8), random_generator, publisher);
         }
 
+        // The migration is abandoned if the new path failed validation
+        if let Some(id) = self.migrating_path {
+            if !self[path_id(id)].is_challenge_pending() {
+                self.migrating_path = None;
+            }
+        }
+
         if self.active_path().failed_validation() {
             match self.last_known_active_validated_path {
                 Some(last_known_active_validated_path) => {
@@ -7,7 +7,7 @@ use crate::{
     contexts::testing::{MockWriteContext, OutgoingFrameBuffer},
     endpoint::{
         self,
-        testing::{Client, Server},
+        testing::{Client, Server, TupleClient},
     },
     path,
     path::DEFAULT_MAX_MTU,
@@ -15,19 +15,20 @@ use crate::{
 use core::time::Duration;
 use s2n_quic_core::{
     event::testing::Publisher,
-    inet::{DatagramInfo, ExplicitCongestionNotification, SocketAddress},
-    path::{migration, RemoteAddress},
+    inet::{DatagramInfo, ExplicitCongestionNotification, SocketAddress, SocketAddressV4},
+    path::{migration, LocalAddress, RemoteAddress, Tuple},
     random::{self, Generator},
     recovery::RttEstimator,
     stateless_reset::token::testing::*,
     time::{Clock, NoopClock},
 };
-use std::net::SocketAddr;
+use std::net::{SocketAddr, SocketAddrV4};
 
 type ServerManager = super::Manager<Server>;
 type ServerPath = super::Path<Server>;
 type ClientManager = super::Manager<Client>;
 type ClientPath = super::Path<Client>;
+type TupleClientManager = super::Manager<TupleClient>;
 
 // Helper function to easily create a PathManager as a Server
 fn manager_server(first_path: ServerPath) -> ServerManager {
@@ -848,6 +849,314 @@ fn do_not_add_new_path_if_client() {
     assert_eq!(manager.paths.len(), 1);
 }
 
+#[test]
+fn migrate_request_checks() {
+    let local_address: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    let local_address = LocalAddress::from(SocketAddress::from(local_address));
+
+    // Setup:
+    let first_conn_id = connection::PeerId::try_from_bytes(&[1]).unwrap();
+    let server_path = helper_path(first_conn_id);
+    let mut server_manager = manager_server(server_path);
+    let client_path = ClientPath::new(
+        Default::default(),
+        first_conn_id,
+        connection::LocalId::TEST_ID,
+        RttEstimator::default(),
+        Default::default(),
+        false,
+        DEFAULT_MAX_MTU,
+    );
+    let mut client_manager = manager_client(client_path);
+
+    // Expectation:
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+    //= type=test
+    //# Clients are responsible for initiating all migrations.
+    assert_eq!(
+        server_manager.on_migrate_request(local_address, true),
+        Err(migration::Error::NotClient)
+    );
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+    //= type=test
+    //# An endpoint MUST NOT initiate
+    //# connection migration before the handshake is confirmed, as defined in
+    //# Section 4.1.2 of [QUIC-TLS].
+    assert_eq!(
+        client_manager.on_migrate_request(local_address, false),
+        Err(migration::Error::HandshakeNotConfirmed)
+    );
+
+    // the test path handle doesn't track the local address
+    assert_eq!(
+        client_manager.on_migrate_request(local_address, true),
+        Err(migration::Error::Unsupported)
+    );
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-9
+    //= type=test
+    //# If the peer sent the disable_active_migration transport parameter, an
+    //# endpoint also MUST NOT send packets (including probing packets; see
+    //# Section 9.1) from a different local address to the address the peer
+    //# used during the handshake, unless the endpoint has acted on a
+    //# preferred_address transport parameter from the peer.
+    client_manager.on_peer_migration_disabled();
+    assert_eq!(
+        client_manager.on_migrate_request(local_address, true),
+        Err(migration::Error::DisabledByPeer)
+    );
+
+    // no paths were created
+    assert!(client_manager.pending_migration.is_none());
+    assert_eq!(client_manager.paths.len(), 1);
+}
+
+/// Creates a client manager with path handles that support migration
+fn manager_tuple_client() -> TupleClientManager {
+    let remote_address: SocketAddr = "127.0.0.1:443".parse().unwrap();
+    let local_address: SocketAddr = "127.0.0.1:4433".parse().unwrap();
+    let handle = Tuple {
+        remote_address: SocketAddress::from(remote_address).into(),
+        local_address: SocketAddress::from(local_address).into(),
+    };
+    let first_path = super::Path::new(
+        handle,
+        connection::PeerId::try_from_bytes(&[0]).unwrap(),
+        connection::LocalId::TEST_ID,
+        RttEstimator::default(),
+        Default::default(),
+        false,
+        DEFAULT_MAX_MTU,
+    );
+
+    let mut random_generator = random::testing::Generator(123);
+    let peer_id_registry = ConnectionIdMapper::new(&mut random_generator, endpoint::Type::Client)
+        .create_client_peer_id_registry(InternalConnectionIdGenerator::new().generate_id());
+    TupleClientManager::new(first_path, peer_id_registry)
+}
+
+fn local_address(addr: &str) -> LocalAddress {
+    let addr: SocketAddr = addr.parse().unwrap();
+    SocketAddress::from(addr).into()
+}
+
+#[test]
+fn migrate_to_existing_path() {
+    // Setup:
+    let mut publisher = Publisher::no_snapshot();
+    let first_conn_id = connection::PeerId::try_from_bytes(&[1]).unwrap();
+    let second_conn_id = connection::PeerId::try_from_bytes(&[2]).unwrap();
+    let mut manager = manager_tuple_client();
+    manager
+        .peer_id_registry
+        .on_new_connection_id(&first_conn_id, 1, 0, &TEST_TOKEN_1)
+        .unwrap();
+    manager
+        .peer_id_registry
+        .on_new_connection_id(&second_conn_id, 2, 0, &TEST_TOKEN_2)
+        .unwrap();
+
+    // Trigger 1:
+    assert_eq!(
+        manager.on_migrate_request(local_address("127.0.0.2:0"), true),
+        Ok(())
+    );
+    manager.on_wakeup(
+        &mut Default::default(),
+        &mut random::testing::Generator(123),
+        &mut publisher,
+    );
+
+    // Expectation 1:
+    let first_path_id = path_id(1);
+    assert_eq!(manager.paths.len(), 2);
+    assert_eq!(manager.migrating_path, Some(1));
+    assert_eq!(manager[first_path_id].peer_connection_id, first_conn_id);
+    // the path is already being validated
+    assert_eq!(
+        manager.on_migrate_request(local_address("127.0.0.2:0"), true),
+        Err(migration::Error::PathExists)
+    );
+    assert!(manager.pending_migration.is_none());
+
+    // Trigger 2:
+    // the path failed validation, and the application migrates to a new address
+    manager.migrating_path = None;
+    assert_eq!(
+        manager.on_migrate_request(local_address("127.0.0.3:0"), true),
+        Ok(())
+    );
+    assert!(manager.peer_id_registry.is_active(&second_conn_id));
+    // the application changes its mind before the request is processed
+    assert_eq!(
+        manager.on_migrate_request(local_address("127.0.0.2:0"), true),
+        Ok(())
+    );
+
+    // Expectation 2:
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1.2
+    //= type=test
+    //# An endpoint MUST NOT forget a connection ID without retiring it
+    assert!(!manager.peer_id_registry.is_active(&second_conn_id));
+    let pending = manager.pending_migration.as_ref().unwrap();
+    assert_eq!(pending.peer_connection_id, None);
+
+    // Trigger 3:
+    manager.on_wakeup(
+        &mut Default::default(),
+        &mut random::testing::Generator(123),
+        &mut publisher,
+    );
+
+    // Expectation 3:
+    // the existing path is validated again
+    assert_eq!(manager.paths.len(), 2);
+    assert_eq!(manager.migrating_path, Some(1));
+    assert!(manager[first_path_id].is_challenge_pending());
+    assert!(manager.pending_migration.is_none());
+}
+
+#[test]
+fn migrate_request_port_change() {
+    // Setup:
+    let mut publisher = Publisher::no_snapshot();
+    let first_conn_id = connection::PeerId::try_from_bytes(&[1]).unwrap();
+    let mut manager = manager_tuple_client();
+    manager
+        .peer_id_registry
+        .on_new_connection_id(&first_conn_id, 1, 0, &TEST_TOKEN_1)
+        .unwrap();
+
+    // Expectation 1:
+    // a port of 0 keeps the port of the active path
+    for addr in ["127.0.0.1:0", "127.0.0.1:4433"] {
+        assert_eq!(
+            manager.on_migrate_request(local_address(addr), true),
+            Err(migration::Error::PathExists)
+        );
+    }
+    assert!(manager.pending_migration.is_none());
+
+    // Trigger 2:
+    // paths are identified by the full local address, so changing the port is a migration
+    assert_eq!(
+        manager.on_migrate_request(local_address("127.0.0.1:5555"), true),
+        Ok(())
+    );
+    let pending = manager.pending_migration.as_ref().unwrap();
+    assert_eq!(pending.peer_connection_id, Some(first_conn_id));
+    assert_eq!(
+        pending.handle.local_address,
+        local_address("127.0.0.1:5555")
+    );
+    manager.on_wakeup(
+        &mut Default::default(),
+        &mut random::testing::Generator(123),
+        &mut publisher,
+    );
+
+    // Expectation 2:
+    let first_path_id = path_id(1);
+    assert_eq!(manager.paths.len(), 2);
+    assert_eq!(manager.migrating_path, Some(1));
+    assert_eq!(manager[first_path_id].peer_connection_id, first_conn_id);
+    assert_eq!(
+        manager[first_path_id].handle.local_address,
+        local_address("127.0.0.1:5555")
+    );
+    assert!(manager[first_path_id].is_challenge_pending());
+    assert_eq!(
+        manager.on_migrate_request(local_address("127.0.0.1:5555"), true),
+        Err(migration::Error::PathExists)
+    );
+    // the original port is still the active path
+    assert_eq!(
+        manager.on_migrate_request(local_address("127.0.0.1:0"), true),
+        Err(migration::Error::PathExists)
+    );
+}
+
+#[test]
+fn migrate_to_preferred_address() {
+    // Setup:
+    let mut publisher = Publisher::snapshot();
+    let server_addr: SocketAddr = "127.0.0.1:443".parse().unwrap();
+    let server_addr = RemoteAddress::from(SocketAddress::from(server_addr));
+    let preferred_addr: SocketAddrV4 = "127.0.0.2:443".parse().unwrap();
+    let preferred_addr = SocketAddressV4::from(preferred_addr);
+
+    let zero_conn_id = connection::PeerId::try_from_bytes(&[0]).unwrap();
+    let preferred_conn_id = connection::PeerId::try_from_bytes(&[1]).unwrap();
+    let zero_path = ClientPath::new(
+        server_addr,
+        zero_conn_id,
+        connection::LocalId::TEST_ID,
+        RttEstimator::default(),
+        Default::default(),
+        false,
+        DEFAULT_MAX_MTU,
+    );
+    let mut manager = manager_client(zero_path);
+    manager
+        .peer_id_registry
+        .on_new_connection_id(&preferred_conn_id, 1, 0, &TEST_TOKEN_1)
+        .unwrap();
+    manager.on_preferred_address(&PreferredAddress {
+        ipv4_address: Some(preferred_addr),
+        ipv6_address: None,
+        connection_id: preferred_conn_id.into(),
+        stateless_reset_token: TEST_TOKEN_1,
+    });
+
+    // Trigger 1:
+    let is_pending = manager.on_handshake_confirmed();
+    manager.on_wakeup(
+        &mut Default::default(),
+        &mut random::testing::Generator(123),
+        &mut publisher,
+    );
+
+    // Expectation 1:
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-9.6.1
+    //= type=test
+    //# Once the handshake is confirmed, the client SHOULD select one of the
+    //# two addresses provided by the server and initiate path validation
+    //# (see Section 8.2).
+    assert!(is_pending);
+    // the migration is only attempted once
+    assert!(!manager.on_handshake_confirmed());
+    let preferred_path_id = path_id(1);
+    assert_eq!(manager.paths.len(), 2);
+    assert_eq!(manager.active_path_id(), path_id(0));
+    assert!(manager[preferred_path_id].is_challenge_pending());
+    assert_eq!(
+        *manager[preferred_path_id].remote_address(),
+        SocketAddress::from(preferred_addr)
+    );
+    assert_eq!(
+        manager[preferred_path_id].peer_connection_id,
+        preferred_conn_id
+    );
+
+    // Trigger 2:
+    let data: challenge::Data = manager[preferred_path_id]
+        .challenge
+        .challenge_data()
+        .try_into()
+        .unwrap();
+    manager.on_path_response(&frame::PathResponse { data: &data }, &mut publisher);
+
+    // Expectation 2:
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-9.6.1
+    //= type=test
+    //# As soon as path validation succeeds, the client SHOULD begin sending
+    //# all future packets to the new server address using the new connection
+    //# ID and discontinue use of the old server address.
+    assert_eq!(manager.active_path_id(), preferred_path_id);
+    assert!(manager[preferred_path_id].is_validated());
+}
+
 #[test]
 //= https://www.rfc-editor.org/rfc/rfc9000#section-7.2
 //= type=test
@@ -14,7 +14,9 @@ use crate::{
 use s2n_quic_core::{
     counter::{Counter, Saturating},
     event::{self, IntoEvent},
-    frame, packet, random,
+    frame,
+    inet::{ExplicitCongestionNotification, Unspecified as _},
+    packet, random,
     time::{timer, Timestamp},
 };
 
@@ -450,6 +452,21 @@ impl<Config: endpoint::Config> Path<Config> {
         requested_size.min(self.mtu(transmission_mode))
     }
 
+    /// Returns the ECN marking to use on packets sent on the path
+    ///
+    /// ECN-capable packets are marked with the codepoint chosen by the congestion controller.
+    #[inline]
+    pub fn ecn(
+        &mut self,
+        transmission_mode: transmission::Mode,
+        timestamp: Timestamp,
+    ) -> ExplicitCongestionNotification {
+        match self.ecn_controller.ecn(transmission_mode, timestamp) {
+            ExplicitCongestionNotification::Ect0 => self.congestion_controller.ect_codepoint(),
+            ecn => ecn,
+        }
+    }
+
     #[inline]
     pub fn transmission_constraint(&self) -> transmission::Constraint {
         if self.at_amplification_limit() {
@@ -547,14 +564,17 @@ impl<Config: endpoint::Config> Path<Config> {
 
     // Compare a Path based on its PathHandle.
     //
-    // Currently the local_address on the Client connection is unknown and set to
+    // Currently the local_address on the initial Client path is unknown and set to
     // a default un-specified value; therefore only the remote_address is used
-    // to compare Paths.
+    // to compare Paths. Paths created by a client migration have a known local
+    // address, so the local IP is also compared for those.
     fn eq_by_handle(&self, handle: &Config::PathHandle) -> bool {
         if Config::ENDPOINT_TYPE.is_client() {
             // TODO: https://github.com/aws/s2n-quic/issues/954
             // Possibly research a strategy to populate the local_address for Client endpoint
+            let local_ip = self.handle.local_address().ip();
             s2n_quic_core::path::Handle::eq(&self.handle.remote_address(), &handle.remote_address())
+                && (local_ip.is_unspecified() || local_ip == handle.local_address().ip())
         } else {
             self.handle.eq(handle)
         }
@@ -154,6 +154,31 @@ impl<Config: endpoint::Config> Manager<Config> {
         *self = Self::new(self.space);
     }
 
+    /// Invoked when the Client learns the server rejected 0-RTT
+    ///
+    /// Removes the 0-RTT packets from the sent packets and from the count of bytes in flight,
+    /// without declaring them lost to the congestion controller.
+    pub fn on_zero_rtt_rejected<Pub: event::ConnectionPublisher>(
+        &mut self,
+        packet_numbers: PacketNumberRange,
+        path_manager: &mut path::Manager<Config>,
+        publisher: &mut Pub,
+    ) {
+        debug_assert!(self.space.is_application_data());
+
+        //= https://www.rfc-editor.org/rfc/rfc9002#section-6.4
+        //# When 0-RTT is rejected, recovery state for all in-flight 0-RTT
+        //# packets is discarded.
+        for (_, sent_info) in self.sent_packets.remove_range(packet_numbers) {
+            path_manager[sent_info.path_id]
+                .congestion_controller
+                .on_packet_discarded(
+                    sent_info.sent_bytes as usize,
+                    &mut congestion_controller::PathPublisher::new(publisher, sent_info.path_id),
+                );
+        }
+    }
+
     pub fn on_timeout<Ctx: Context<Config>, Pub: event::ConnectionPublisher>(
         &mut self,
         timestamp: Timestamp,
@@ -3000,6 +3000,64 @@ fn probe_packets_count_towards_bytes_in_flight() {
     assert_eq!(context.path().congestion_controller.bytes_in_flight, 100);
 }
 
+#[test]
+fn on_zero_rtt_rejected() {
+    let space = PacketNumberSpace::ApplicationData;
+    let mut manager = Manager::new(space);
+    let ecn = ExplicitCongestionNotification::default();
+    let mut path_manager = helper_generate_path_manager(Duration::from_millis(10));
+    let mut publisher = Publisher::no_snapshot();
+    let time_sent = s2n_quic_platform::time::now();
+    let outcome = transmission::Outcome {
+        ack_elicitation: AckElicitation::Eliciting,
+        is_congestion_controlled: true,
+        bytes_sent: 100,
+        bytes_progressed: 0,
+    };
+
+    // send 2 0-RTT packets followed by a 1-RTT packet
+    for packet_number in 0..3 {
+        let mut context = MockContext::new(&mut path_manager);
+        manager.on_packet_sent(
+            space.new_packet_number(VarInt::from_u8(packet_number)),
+            outcome,
+            time_sent,
+            ecn,
+            transmission::Mode::Normal,
+            None,
+            &mut context,
+            &mut publisher,
+        );
+    }
+    assert_eq!(
+        path_manager
+            .active_path()
+            .congestion_controller
+            .bytes_in_flight,
+        300
+    );
+
+    manager.on_zero_rtt_rejected(
+        PacketNumberRange::new(
+            space.new_packet_number(VarInt::from_u8(0)),
+            space.new_packet_number(VarInt::from_u8(1)),
+        ),
+        &mut path_manager,
+        &mut publisher,
+    );
+
+    // only the 1-RTT packet remains in flight
+    assert_eq!(manager.sent_packets.iter().count(), 1);
+    assert!(manager
+        .sent_packets
+        .get(space.new_packet_number(VarInt::from_u8(2)))
+        .is_some());
+    let congestion_controller = &path_manager.active_path().congestion_controller;
+    assert_eq!(congestion_controller.bytes_in_flight, 100);
+    // the packets are discarded rather than declared lost
+    assert_eq!(congestion_controller.lost_bytes, 0);
+}
+
 //= https://www.rfc-editor.org/rfc/rfc9002#section-6.1.1
 //= type=test
 //# The RECOMMENDED initial value for the packet reordering threshold
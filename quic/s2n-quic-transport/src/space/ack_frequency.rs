@@ -0,0 +1,314 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Negotiates the ACK frequency extension
+//!
+//! As a sender, the controller asks the peer to acknowledge packets less often when the
+//! congestion controller allows it. As a receiver, it validates ACK_FREQUENCY and IMMEDIATE_ACK
+//! frames before they are applied to the `AckManager`.
+//!
+//! See https://datatracker.ietf.org/doc/html/draft-ietf-quic-ack-frequency
+
+use crate::{contexts::WriteContext, transmission};
+use core::{convert::TryInto, time::Duration};
+use s2n_quic_core::{
+    ack,
+    frame::{AckFrequency, ImmediateAck},
+    packet::number::PacketNumber,
+    transport,
+    varint::VarInt,
+};
+
+#[derive(Debug)]
+pub struct Controller {
+    /// The `min_ack_delay` sent to the peer, if the extension is enabled locally
+    local_min_ack_delay: Option<Duration>,
+    /// The largest sequence number received in an ACK_FREQUENCY frame
+    largest_received_sequence_number: Option<VarInt>,
+    /// The `min_ack_delay` sent by the peer, if the peer supports the extension
+    peer_min_ack_delay: Option<Duration>,
+    /// The `max_ack_delay` sent by the peer
+    peer_max_ack_delay: Duration,
+    /// The sequence number of the next ACK_FREQUENCY frame
+    next_sequence_number: VarInt,
+    /// The most recent request sent to the peer
+    request: Option<AckFrequency>,
+    delivery: DeliveryState,
+    /// Set when the peer should acknowledge the next packet immediately
+    immediate_ack: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeliveryState {
+    Idle,
+    RequiresTransmission,
+    RequiresRetransmission,
+    InFlight(PacketNumber),
+}
+
+impl Controller {
+    pub fn new(
+        local_min_ack_delay: Option<Duration>,
+        peer_min_ack_delay: Option<Duration>,
+        peer_max_ack_delay: Duration,
+    ) -> Self {
+        Self {
+            local_min_ack_delay,
+            largest_received_sequence_number: None,
+            peer_min_ack_delay,
+            peer_max_ack_delay,
+            next_sequence_number: VarInt::from_u8(0),
+            request: None,
+            delivery: DeliveryState::Idle,
+            immediate_ack: false,
+        }
+    }
+
+    /// Updates the ACK frequency requested from the peer
+    ///
+    /// `threshold` is the number of ack-eliciting packets the congestion controller allows the
+    /// peer to receive before sending an ACK frame.
+    pub fn update(&mut self, threshold: Option<u32>, smoothed_rtt: Duration) {
+        let (min_ack_delay, threshold) = match (self.peer_min_ack_delay, threshold) {
+            (Some(min_ack_delay), Some(threshold)) => (min_ack_delay, threshold as u64),
+            _ => return,
+        };
+
+        if let Some(request) = self.request {
+            let current = request.ack_eliciting_threshold.as_u64();
+
+            // avoid sending a new frame for small changes in the threshold
+            let diff = current.max(threshold) - current.min(threshold);
+            if diff == 0 || diff * 4 < current {
+                return;
+            }
+
+            // make sure the peer doesn't sit on packets we've already sent under the old
+            // threshold when we need feedback sooner
+            if threshold < current {
+                self.immediate_ack = true;
+            }
+        }
+
+        // ask the peer to acknowledge at least 4 times per round trip, within the bounds it
+        // advertised
+        let max_ack_delay = (smoothed_rtt / 4)
+            .max(min_ack_delay)
+            .min(self.peer_max_ack_delay.max(min_ack_delay));
+
+        let sequence_number = self.next_sequence_number;
+        self.next_sequence_number = sequence_number.checked_add_usize(1).unwrap_or(VarInt::MAX);
+
+        self.request = Some(AckFrequency {
+            sequence_number,
+            ack_eliciting_threshold: VarInt::from_u32(threshold as u32),
+            request_max_ack_delay: max_ack_delay.as_micros().try_into().unwrap_or(VarInt::MAX),
+            reordering_threshold: VarInt::from_u8(ack::Settings::RECOMMENDED.reordering_threshold),
+        });
+        self.delivery = DeliveryState::RequiresTransmission;
+    }
+
+    /// Validates an ACK_FREQUENCY frame received from the peer
+    ///
+    /// Returns `true` if the frame is newer than any previously received and should be applied.
+    pub fn on_ack_frequency_frame(
+        &mut self,
+        frame: &AckFrequency,
+    ) -> Result<bool, transport::Error> {
+        // See https://datatracker.ietf.org/doc/html/draft-ietf-quic-ack-frequency#section-3
+        //
+        // Receiving the frame without having advertised min_ack_delay is a protocol violation
+        let min_ack_delay = self.local_min_ack_delay.ok_or_else(|| {
+            transport::Error::PROTOCOL_VIOLATION
+                .with_reason("the ACK frequency extension was not negotiated")
+                .with_frame_type(frame.tag().into())
+        })?;
+
+        // See https://datatracker.ietf.org/doc/html/draft-ietf-quic-ack-frequency#section-4
+        if Duration::from_micros(frame.request_max_ack_delay.as_u64()) < min_ack_delay {
+            return Err(transport::Error::PROTOCOL_VIOLATION
+                .with_reason("requested max ack delay is less than min_ack_delay")
+                .with_frame_type(frame.tag().into()));
+        }
+
+        // frames that arrive out of order are ignored
+        if let Some(largest) = self.largest_received_sequence_number {
+            if frame.sequence_number <= largest {
+                return Ok(false);
+            }
+        }
+
+        self.largest_received_sequence_number = Some(frame.sequence_number);
+
+        Ok(true)
+    }
+
+    /// Validates an IMMEDIATE_ACK frame received from the peer
+    pub fn on_immediate_ack_frame(&self, frame: &ImmediateAck) -> Result<(), transport::Error> {
+        if self.local_min_ack_delay.is_none() {
+            return Err(transport::Error::PROTOCOL_VIOLATION
+                .with_reason("the ACK frequency extension was not negotiated")
+                .with_frame_type(frame.tag().into()));
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn on_transmit<W: WriteContext>(&mut self, context: &mut W) {
+        let constraint = context.transmission_constraint();
+
+        if self.immediate_ack && (constraint.can_transmit() || constraint.can_retransmit()) {
+            // IMMEDIATE_ACK is ack-eliciting but doesn't need to be retransmitted; the peer will
+            // acknowledge a later packet anyway
+            if context.write_frame(&ImmediateAck).is_some() {
+                self.immediate_ack = false;
+            }
+        }
+
+        let can_send = match self.delivery {
+            DeliveryState::RequiresTransmission => constraint.can_transmit(),
+            DeliveryState::RequiresRetransmission => constraint.can_retransmit(),
+            _ => false,
+        };
+
+        if !can_send {
+            return;
+        }
+
+        if let Some(request) = self.request.as_ref() {
+            if let Some(packet_number) = context.write_frame(request) {
+                self.delivery = DeliveryState::InFlight(packet_number);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A) {
+        if let DeliveryState::InFlight(packet_number) = self.delivery {
+            if ack_set.contains(packet_number) {
+                self.delivery = DeliveryState::Idle;
+            }
+        }
+    }
+
+    #[inline]
+    pub fn on_packet_loss<A: ack::Set>(&mut self, ack_set: &A) {
+        if let DeliveryState::InFlight(packet_number) = self.delivery {
+            if ack_set.contains(packet_number) {
+                self.delivery = DeliveryState::RequiresRetransmission;
+            }
+        }
+    }
+}
+
+impl transmission::interest::Provider for Controller {
+    #[inline]
+    fn transmission_interest<Q: transmission::interest::Query>(
+        &self,
+        query: &mut Q,
+    ) -> transmission::interest::Result {
+        if self.immediate_ack || self.delivery == DeliveryState::RequiresTransmission {
+            query.on_new_data()?;
+        } else if self.delivery == DeliveryState::RequiresRetransmission {
+            query.on_lost_data()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transmission::interest::Provider;
+
+    const RTT: Duration = Duration::from_millis(100);
+
+    fn frame(sequence_number: u32, request_max_ack_delay: u32) -> AckFrequency {
+        AckFrequency {
+            sequence_number: VarInt::from_u32(sequence_number),
+            ack_eliciting_threshold: VarInt::from_u8(1),
+            request_max_ack_delay: VarInt::from_u32(request_max_ack_delay),
+            reordering_threshold: VarInt::from_u8(1),
+        }
+    }
+
+    #[test]
+    fn sender_test() {
+        let mut controller = Controller::new(
+            None,
+            Some(Duration::from_millis(1)),
+            Duration::from_millis(25),
+        );
+
+        controller.update(None, RTT);
+        assert!(!controller.has_transmission_interest());
+
+        controller.update(Some(8), RTT);
+        assert!(controller.has_transmission_interest());
+        let request = controller.request.unwrap();
+        assert_eq!(request.sequence_number, VarInt::from_u8(0));
+        assert_eq!(request.ack_eliciting_threshold, VarInt::from_u8(8));
+        assert_eq!(request.request_max_ack_delay, VarInt::from_u32(25_000));
+        assert!(!controller.immediate_ack);
+
+        // small changes are ignored
+        controller.update(Some(9), RTT);
+        assert_eq!(
+            controller.request.unwrap().sequence_number,
+            VarInt::from_u8(0)
+        );
+
+        // lowering the threshold also requests an immediate ack
+        controller.update(Some(0), RTT);
+        let request = controller.request.unwrap();
+        assert_eq!(request.sequence_number, VarInt::from_u8(1));
+        assert_eq!(request.ack_eliciting_threshold, VarInt::from_u8(0));
+        assert!(controller.immediate_ack);
+    }
+
+    #[test]
+    fn peer_without_extension_test() {
+        let mut controller = Controller::new(None, None, Duration::from_millis(25));
+
+        controller.update(Some(8), RTT);
+        assert!(!controller.has_transmission_interest());
+        assert!(controller.request.is_none());
+    }
+
+    #[test]
+    fn receiver_test() {
+        let mut controller = Controller::new(None, None, Duration::from_millis(25));
+        assert!(controller.on_ack_frequency_frame(&frame(0, 1_000)).is_err());
+        assert!(controller.on_immediate_ack_frame(&ImmediateAck).is_err());
+
+        let mut controller = Controller::new(
+            Some(Duration::from_millis(1)),
+            None,
+            Duration::from_millis(25),
+        );
+        assert!(controller.on_immediate_ack_frame(&ImmediateAck).is_ok());
+
+        // the requested delay can't be less than min_ack_delay
+        assert!(controller.on_ack_frequency_frame(&frame(0, 999)).is_err());
+
+        assert_eq!(
+            controller.on_ack_frequency_frame(&frame(1, 1_000)),
+            Ok(true)
+        );
+        assert_eq!(
+            controller.on_ack_frequency_frame(&frame(3, 1_000)),
+            Ok(true)
+        );
+        // older frames are ignored
+        assert_eq!(
+            controller.on_ack_frequency_frame(&frame(2, 1_000)),
+            Ok(false)
+        );
+        assert_eq!(
+            controller.on_ack_frequency_frame(&frame(3, 1_000)),
+            Ok(false)
+        );
+    }
+}
@@ -8,7 +8,10 @@ use crate::{
     path::{path_event, Path},
     processed_packet::ProcessedPacket,
     recovery,
-    space::{datagram, keep_alive::KeepAlive, HandshakeStatus, PacketSpace, TxPacketNumbers},
+    space::{
+        ack_frequency, datagram, keep_alive::KeepAlive, HandshakeStatus, PacketSpace,
+        TxPacketNumbers,
+    },
     stream::AbstractStreamManager,
     sync::flag,
     transmission,
@@ -16,23 +19,26 @@ use crate::{
 };
 use core::{convert::TryInto, fmt, marker::PhantomData};
 use once_cell::sync::OnceCell;
-use s2n_codec::EncoderBuffer;
+use s2n_codec::{DecoderBufferMut, EncoderBuffer};
 use s2n_quic_core::{
+    buffer::ReceiveBuffer,
     crypto::{application::KeySet, limited, tls, CryptoSuite},
     event::{self, ConnectionPublisher as _, IntoEvent},
     frame::{
         ack::AckRanges, crypto::CryptoRef, datagram::DatagramRef, stream::StreamRef, Ack,
-        ConnectionClose, DataBlocked, HandshakeDone, MaxData, MaxStreamData, MaxStreams,
-        NewConnectionId, NewToken, PathChallenge, PathResponse, ResetStream, RetireConnectionId,
-        StopSending, StreamDataBlocked, StreamsBlocked,
+        AckFrequency, ConnectionClose, DataBlocked, HandshakeDone, ImmediateAck, MaxData,
+        MaxStreamData, MaxStreams, NewConnectionId, NewToken, PathChallenge, PathResponse,
+        ResetStream, RetireConnectionId, StopSending, StreamDataBlocked, StreamsBlocked,
     },
     inet::DatagramInfo,
     packet::{
         encoding::{PacketEncoder, PacketEncodingError},
         number::{PacketNumber, PacketNumberRange, PacketNumberSpace, SlidingWindow},
         short::{CleartextShort, ProtectedShort, Short, SpinBit},
+        zero_rtt::{CleartextZeroRtt, ProtectedZeroRtt},
     },
     path::MaxMtu,
+    recovery::CongestionController,
     time::{timer, Timestamp},
     transport,
 };
@@ -62,10 +68,18 @@ pub struct ApplicationSpace<Config: endpoint::Config> {
     ping: flag::Ping,
     keep_alive: KeepAlive,
     processed_packet_numbers: SlidingWindow,
-    recovery_manager: recovery::Manager<Config>,
+    pub(super) recovery_manager: recovery::Manager<Config>,
     pub datagram_manager: datagram::Manager<Config>,
+    ack_frequency: ack_frequency::Controller,
+    /// Buffers CRYPTO data received after the handshake, such as session tickets
+    ///
+    /// This is `None` once the TLS session has been discarded.
+    pub crypto_rx: Option<ReceiveBuffer>,
 }
 
+/// The maximum amount of post-handshake CRYPTO data buffered for the TLS session
+const CRYPTO_RX_LIMIT: u64 = 16 * 1024;
+
 impl<Config: endpoint::Config> fmt::Debug for ApplicationSpace<Config> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ApplicationSpace")
@@ -90,6 +104,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         keep_alive: KeepAlive,
         max_mtu: MaxMtu,
         datagram_manager: datagram::Manager<Config>,
+        ack_frequency: ack_frequency::Controller,
     ) -> Self {
         let key_set = KeySet::new(key, Self::key_limits(max_mtu));
 
@@ -105,6 +120,8 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
             processed_packet_numbers: SlidingWindow::default(),
             recovery_manager: recovery::Manager::new(PacketNumberSpace::ApplicationData),
             datagram_manager,
+            ack_frequency,
+            crypto_rx: Some(ReceiveBuffer::new()),
         }
     }
 
@@ -162,6 +179,13 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         let min_packet_len = context.min_packet_len;
         let bytes_progressed = self.stream_manager.outgoing_bytes_progressed();
 
+        // ask the peer to adjust its ACK frequency if the congestion controller would like to
+        let path = context.path();
+        self.ack_frequency.update(
+            path.congestion_controller.ack_eliciting_threshold(),
+            path.rtt_estimator.smoothed_rtt(),
+        );
+
         let payload = transmission::Transmission {
             config: <PhantomData<Config>>::default(),
             outcome: &mut outcome,
@@ -177,6 +201,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
                 &mut self.stream_manager,
                 &mut self.recovery_manager,
                 &mut self.datagram_manager,
+                &mut self.ack_frequency,
             ),
             timestamp,
             transmission_constraint,
@@ -211,6 +236,8 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         outcome.bytes_progressed +=
             (self.stream_manager.outgoing_bytes_progressed() - bytes_progressed).as_u64() as usize;
 
+        self.datagram_manager.publish_dropped(context.publisher);
+
         let app_limited = self.is_app_limited(context.path(), outcome.bytes_sent);
 
         let (recovery_manager, mut recovery_context) = self.recovery(
@@ -313,6 +340,23 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         Ok((outcome, buffer))
     }
 
+    /// Called by the client when the server rejects 0-RTT
+    ///
+    /// The recovery state of the 0-RTT packets is discarded and the stream data they carried is
+    /// sent again in 1-RTT packets.
+    pub fn on_zero_rtt_rejected<Pub: event::ConnectionPublisher>(
+        &mut self,
+        packet_numbers: PacketNumberRange,
+        path_manager: &mut path::Manager<Config>,
+        publisher: &mut Pub,
+    ) {
+        debug_assert!(Config::ENDPOINT_TYPE.is_client());
+
+        self.recovery_manager
+            .on_zero_rtt_rejected(packet_numbers, path_manager, publisher);
+        self.stream_manager.on_packet_loss(&packet_numbers);
+    }
+
     /// Signals the connection was previously blocked by anti-amplification limits
     /// but is now no longer limited.
     pub fn on_amplification_unblocked(
@@ -432,6 +476,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
                 handshake_status,
                 ping: &mut self.ping,
                 stream_manager: &mut self.stream_manager,
+                ack_frequency: &mut self.ack_frequency,
                 local_id_registry,
                 path_id,
                 path_manager,
@@ -529,6 +574,105 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         decrypted.map(|x| x.0)
     }
 
+    /// Validates and decrypts a 0-RTT packet received by a server
+    ///
+    /// 0-RTT packets share the application data packet number space with 1-RTT packets.
+    pub fn validate_and_decrypt_zero_rtt_packet<'a, Pub: event::ConnectionPublisher>(
+        &mut self,
+        protected: ProtectedZeroRtt<'a>,
+        key: &<<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttKey,
+        header_key: &<<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttHeaderKey,
+        datagram: &DatagramInfo,
+        path_id: path::Id,
+        path: &path::Path<Config>,
+        publisher: &mut Pub,
+    ) -> Result<CleartextZeroRtt<'a>, ProcessingError> {
+        let largest_acked = self.ack_manager.largest_received_packet_number_acked();
+        let packet = protected
+            .unprotect(header_key, largest_acked)
+            .map_err(|err| {
+                publisher.on_packet_dropped(event::builder::PacketDropped {
+                    reason: event::builder::PacketDropReason::UnprotectFailed {
+                        space: event::builder::KeySpace::ZeroRtt,
+                        path: path_event!(path, path_id),
+                    },
+                });
+                err
+            })?;
+
+        let packet_number = packet.packet_number;
+        let packet_header = event::builder::PacketHeader::ZeroRtt {
+            number: packet_number.as_u64(),
+            version: packet.version,
+        };
+        let decrypted = packet.decrypt(key);
+
+        if decrypted.is_err() {
+            publisher.on_packet_dropped(event::builder::PacketDropped {
+                reason: event::builder::PacketDropReason::DecryptionFailed {
+                    packet_header,
+                    path: path_event!(path, path_id),
+                },
+            });
+        }
+
+        // We perform decryption prior to checking for duplicate to avoid short-circuiting
+        // and maintain constant-time operation.
+        if self.is_duplicate(packet_number, path_id, path, publisher) {
+            return Err(ProcessingError::DuplicatePacket);
+        }
+
+        let mut packet = decrypted?;
+
+        // reset the keep alive timer after receiving a packet
+        self.keep_alive.reset(datagram.timestamp);
+
+        // Decoding frames doesn't modify the payload so the frames are checked with a reborrow
+        // before the payload is handed back for processing
+        let payload = packet.payload.into_less_safe_slice();
+        Self::validate_zero_rtt_frames(DecoderBufferMut::new(&mut *payload))?;
+        packet.payload = DecoderBufferMut::new(payload);
+
+        Ok(packet)
+    }
+
+    fn validate_zero_rtt_frames(mut payload: DecoderBufferMut) -> Result<(), transport::Error> {
+        use s2n_quic_core::{
+            frame::{Frame, FrameMut},
+            varint::VarInt,
+        };
+
+        while !payload.is_empty() {
+            let (frame, remaining) = payload
+                .decode::<FrameMut>()
+                .map_err(transport::Error::from)?;
+
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-12.5
+            //# Note that it is not possible to send the following frames in 0-RTT
+            //# packets for various reasons: ACK, CRYPTO, HANDSHAKE_DONE, NEW_TOKEN,
+            //# PATH_RESPONSE, and RETIRE_CONNECTION_ID.  A server MAY treat receipt
+            //# of these frames in 0-RTT packets as a connection error of type
+            //# PROTOCOL_VIOLATION.
+            if matches!(
+                frame,
+                Frame::Ack(_)
+                    | Frame::Crypto(_)
+                    | Frame::HandshakeDone(_)
+                    | Frame::NewToken(_)
+                    | Frame::PathResponse(_)
+                    | Frame::RetireConnectionId(_)
+            ) {
+                return Err(transport::Error::PROTOCOL_VIOLATION
+                    .with_reason("invalid frame in 0-RTT packet")
+                    .with_frame_type(VarInt::from_u8(frame.tag())));
+            }
+
+            payload = remaining;
+        }
+
+        Ok(())
+    }
+
     fn key_limits(max_mtu: MaxMtu) -> limited::Limits {
         let mut limits = limited::Limits::default();
 
@@ -585,6 +729,7 @@ impl<Config: endpoint::Config> transmission::interest::Provider for ApplicationS
         self.recovery_manager.transmission_interest(query)?;
         self.stream_manager.transmission_interest(query)?;
         self.datagram_manager.transmission_interest(query)?;
+        self.ack_frequency.transmission_interest(query)?;
         Ok(())
     }
 }
@@ -600,6 +745,7 @@ struct RecoveryContext<'a, Config: endpoint::Config> {
     handshake_status: &'a mut HandshakeStatus,
     ping: &'a mut flag::Ping,
     stream_manager: &'a mut AbstractStreamManager<Config::Stream>,
+    ack_frequency: &'a mut ack_frequency::Controller,
     local_id_registry: &'a mut connection::LocalIdRegistry,
     path_id: path::Id,
     path_manager: &'a mut path::Manager<Config>,
@@ -651,6 +797,7 @@ impl<'a, Config: endpoint::Config> recovery::Context<Config> for RecoveryContext
             .on_packet_ack(packet_number_range, publisher);
         self.ping.on_packet_ack(packet_number_range);
         self.stream_manager.on_packet_ack(packet_number_range);
+        self.ack_frequency.on_packet_ack(packet_number_range);
         self.local_id_registry.on_packet_ack(packet_number_range);
         self.path_manager.on_packet_ack(packet_number_range);
     }
@@ -670,6 +817,7 @@ impl<'a, Config: endpoint::Config> recovery::Context<Config> for RecoveryContext
             .on_packet_loss(packet_number_range, publisher);
         self.ping.on_packet_loss(packet_number_range);
         self.stream_manager.on_packet_loss(packet_number_range);
+        self.ack_frequency.on_packet_loss(packet_number_range);
         self.local_id_registry.on_packet_loss(packet_number_range);
         self.path_manager.on_packet_loss(packet_number_range);
     }
@@ -677,8 +825,10 @@ impl<'a, Config: endpoint::Config> recovery::Context<Config> for RecoveryContext
     fn on_rtt_update(&mut self) {
         // Update the stream manager if this RTT update was for the active path
         if self.path_manager.active_path_id() == self.path_id {
+            let path = self.path_manager.active_path();
+            self.stream_manager.on_rtt_update(&path.rtt_estimator);
             self.stream_manager
-                .on_rtt_update(&self.path_manager.active_path().rtt_estimator)
+                .on_congestion_window_update(path.congestion_controller.congestion_window());
         }
     }
 }
@@ -688,7 +838,7 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
 
     fn handle_crypto_frame<Pub: event::ConnectionPublisher>(
         &mut self,
-        _frame: CryptoRef,
+        frame: CryptoRef,
         _datagram: &DatagramInfo,
         _path: &mut Path<Config>,
         _publisher: &mut Pub,
@@ -699,7 +849,23 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         //# CRYPTO frames received in the future, or it MAY close the connection
         //# with a CRYPTO_BUFFER_EXCEEDED error code.
 
-        // we currently just discard CRYPTO frames post-handshake
+        // the TLS session has been discarded so it isn't able to process any data
+        let crypto_rx = if let Some(crypto_rx) = self.crypto_rx.as_mut() {
+            crypto_rx
+        } else {
+            return Ok(());
+        };
+
+        let end = frame.offset.as_u64() + frame.data.len() as u64;
+        if end > crypto_rx.consumed_len() + CRYPTO_RX_LIMIT {
+            return Err(transport::Error::CRYPTO_BUFFER_EXCEEDED
+                .with_reason("post-handshake CRYPTO data exceeded the buffer limit"));
+        }
+
+        crypto_rx
+            .write_at(frame.offset, frame.data)
+            .map_err(|_| transport::Error::CRYPTO_BUFFER_EXCEEDED)?;
+
         Ok(())
     }
 
@@ -812,12 +978,25 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         if Config::ENDPOINT_TYPE.is_server() {
             return Err(transport::Error::PROTOCOL_VIOLATION
                 .with_reason(Self::INVALID_FRAME_ERROR)
-                .with_frame_type(frame.tag().into()));
+                .with_frame_type(VarInt::from_u8(frame.tag())));
         }
         // TODO add support for NEW_TOKEN_FRAMEs on the client
         Ok(())
     }
 
+    fn handle_ack_frequency_frame(&mut self, frame: AckFrequency) -> Result<(), transport::Error> {
+        if self.ack_frequency.on_ack_frequency_frame(&frame)? {
+            self.ack_manager.on_ack_frequency(&frame);
+        }
+        Ok(())
+    }
+
+    fn handle_immediate_ack_frame(&mut self, frame: ImmediateAck) -> Result<(), transport::Error> {
+        self.ack_frequency.on_immediate_ack_frame(&frame)?;
+        self.ack_manager.on_immediate_ack();
+        Ok(())
+    }
+
     fn handle_new_connection_id_frame<Pub: event::ConnectionPublisher>(
         &mut self,
         frame: NewConnectionId,
@@ -929,7 +1108,7 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         if Config::ENDPOINT_TYPE.is_server() {
             return Err(transport::Error::PROTOCOL_VIOLATION
                 .with_reason("Clients MUST NOT send HANDSHAKE_DONE frames")
-                .with_frame_type(frame.tag().into()));
+                .with_frame_type(VarInt::from_u8(frame.tag())));
         }
 
         handshake_status.on_handshake_done_received(publisher);
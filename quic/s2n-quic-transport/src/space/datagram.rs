@@ -9,12 +9,15 @@ use crate::{
         WriteContext,
     },
 };
+use alloc::vec::Vec;
 use core::task::Poll;
 use s2n_codec::EncoderValue;
 use s2n_quic_core::{
-    datagram::{Endpoint, ReceiveContext, Receiver, Sender, WriteError},
+    datagram::{DropReason, Endpoint, ReceiveContext, Receiver, Sender, WriteError},
+    event::{self, ConnectionPublisher, IntoEvent},
     frame::{self, datagram::DatagramRef},
     query,
+    time::Timestamp,
     varint::VarInt,
 };
 
@@ -26,6 +29,8 @@ pub struct Manager<Config: endpoint::Config> {
     pub sender: <<Config as endpoint::Config>::DatagramEndpoint as Endpoint>::Sender,
     pub receiver: <<Config as endpoint::Config>::DatagramEndpoint as Endpoint>::Receiver,
     max_datagram_payload: u64,
+    // Datagrams dropped by the sender which have not been published yet
+    dropped: Vec<event::builder::DatagramFrameDropped>,
 }
 
 impl<Config: endpoint::Config> Manager<Config> {
@@ -38,6 +43,7 @@ impl<Config: endpoint::Config> Manager<Config> {
             sender,
             receiver,
             max_datagram_payload,
+            dropped: Vec::new(),
         }
     }
 
@@ -53,10 +59,18 @@ impl<Config: endpoint::Config> Manager<Config> {
             has_pending_streams: stream_manager.has_pending_streams(),
             datagrams_prioritized,
             max_datagram_payload: self.max_datagram_payload,
+            dropped: &mut self.dropped,
         };
         self.sender.on_transmit(&mut packet);
     }
 
+    /// Publishes the datagrams which were dropped by the sender while transmitting
+    pub fn publish_dropped<Pub: ConnectionPublisher>(&mut self, publisher: &mut Pub) {
+        for event in self.dropped.drain(..) {
+            publisher.on_datagram_frame_dropped(event);
+        }
+    }
+
     // A callback that allows users to access datagrams directly after they are
     // received.
     pub fn on_datagram_frame(
@@ -100,6 +114,7 @@ struct Packet<'a, C: WriteContext> {
     has_pending_streams: bool,
     datagrams_prioritized: bool,
     max_datagram_payload: u64,
+    dropped: &'a mut Vec<event::builder::DatagramFrameDropped>,
 }
 
 impl<'a, C: WriteContext> s2n_quic_core::datagram::Packet for Packet<'a, C> {
@@ -145,4 +160,17 @@ impl<'a, C: WriteContext> s2n_quic_core::datagram::Packet for Packet<'a, C> {
     fn datagrams_prioritized(&self) -> bool {
         self.datagrams_prioritized
     }
+
+    /// Returns the time at which the packet is being assembled
+    fn current_time(&self) -> Timestamp {
+        self.context.current_time()
+    }
+
+    /// Records a datagram dropped by the sender to be published after the transmission
+    fn on_datagram_dropped(&mut self, len: usize, reason: DropReason) {
+        self.dropped.push(event::builder::DatagramFrameDropped {
+            len: len.min(u16::MAX as usize) as u16,
+            reason: reason.into_event(),
+        });
+    }
 }
@@ -0,0 +1,314 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    connection::ConnectionTransmissionContext,
+    endpoint, path,
+    path::Path,
+    recovery,
+    space::{TxPacketNumbers, ZeroRttCrypto},
+    transmission,
+};
+use bytes::Bytes;
+use core::marker::PhantomData;
+use s2n_codec::EncoderBuffer;
+use s2n_quic_core::{
+    event::{self, ConnectionPublisher as _},
+    packet::{
+        encoding::{PacketEncoder, PacketEncodingError},
+        number::{PacketNumber, PacketNumberRange, PacketNumberSpace},
+        zero_rtt::ZeroRtt,
+    },
+    time::Timestamp,
+    transport,
+    transport::parameters::InitialFlowControlLimits,
+};
+
+/// Data a client sends in 0-RTT packets before the handshake completes
+///
+/// The data is the beginning of the first bidirectional stream. 0-RTT packets are recorded in
+/// a recovery manager for the application data space, which is handed to the application space
+/// along with the packet numbers once the 1-RTT keys are available. From then on the 0-RTT
+/// packets are acknowledged and declared lost like any 1-RTT packet.
+#[derive(Debug)]
+pub struct EarlyData<Config: endpoint::Config> {
+    data: Bytes,
+    /// The number of bytes which can be sent in 0-RTT packets
+    max_len: usize,
+    /// The number of bytes which were sent in 0-RTT packets
+    sent_len: usize,
+    /// The number of bytes sent in each 0-RTT packet, in the order the data was sent
+    transmissions: Vec<(PacketNumber, usize)>,
+    /// The packet numbers for 0-RTT packets, which continue in 1-RTT packets
+    tx_packet_numbers: TxPacketNumbers,
+    /// The recovery state of 0-RTT packets, which continues in the application space
+    recovery_manager: recovery::Manager<Config>,
+    /// The packet numbers of all 0-RTT packets, once the 1-RTT keys are available
+    packet_numbers: Option<(PacketNumber, PacketNumber)>,
+}
+
+impl<Config: endpoint::Config> EarlyData<Config> {
+    pub fn new(data: Bytes, now: Timestamp) -> Self {
+        Self {
+            data,
+            max_len: 0,
+            sent_len: 0,
+            transmissions: Vec::new(),
+            tx_packet_numbers: TxPacketNumbers::new(PacketNumberSpace::ApplicationData, now),
+            recovery_manager: recovery::Manager::new(PacketNumberSpace::ApplicationData),
+            packet_numbers: None,
+        }
+    }
+
+    /// Called when the 0-RTT keys are available, with the flow control limits the server
+    /// provided in the connection that issued the session ticket
+    pub fn on_zero_rtt_keys(&mut self, limits: &InitialFlowControlLimits) {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-7.4.1
+        //# When sending frames in 0-RTT packets, a client MUST only use
+        //# remembered transport parameters;
+        if limits.max_open_remote_bidirectional_streams == 0u64 {
+            self.max_len = 0;
+            return;
+        }
+
+        let max_len = limits
+            .max_data
+            .min(limits.stream_limits.max_data_bidi_remote)
+            .as_u64();
+        let max_len = usize::try_from(max_len).unwrap_or(usize::MAX);
+        self.max_len = self.data.len().min(max_len);
+    }
+
+    /// Returns `true` if any data was sent in 0-RTT packets
+    pub fn is_sent(&self) -> bool {
+        self.sent_len > 0
+    }
+
+    /// Called by the client after processing a Retry packet
+    ///
+    /// The server discarded the 0-RTT packets, so their recovery state is discarded and the
+    /// data is sent again in new 0-RTT packets.
+    pub fn on_retry_packet<Pub: event::ConnectionPublisher>(
+        &mut self,
+        path: &mut Path<Config>,
+        path_id: path::Id,
+        publisher: &mut Pub,
+    ) {
+        self.recovery_manager
+            .on_retry_packet(path, path_id, publisher);
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.5.3
+        //# A client MUST NOT reset the packet number for any packet number space
+        //# after processing a Retry packet.
+        self.transmissions.clear();
+        self.sent_len = 0;
+    }
+
+    /// Called when the 1-RTT keys are available so 1-RTT packets continue from the packet
+    /// numbers and recovery state of 0-RTT packets
+    pub fn on_one_rtt_keys(
+        &mut self,
+        tx_packet_numbers: &mut TxPacketNumbers,
+        recovery_manager: &mut recovery::Manager<Config>,
+    ) {
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-12.3
+        //# 0-RTT and 1-RTT data exist in the same packet number space
+        // every 0-RTT packet carries data, so the transmissions cover all of the packets
+        if let (Some((first, _)), Some((last, _))) =
+            (self.transmissions.first(), self.transmissions.last())
+        {
+            self.packet_numbers = Some((*first, *last));
+        }
+
+        core::mem::swap(&mut self.tx_packet_numbers, tx_packet_numbers);
+        core::mem::swap(&mut self.recovery_manager, recovery_manager);
+
+        // the 0-RTT keys are discarded at this point
+        self.max_len = 0;
+    }
+
+    /// Returns the packet numbers of the 0-RTT packets which carried data
+    ///
+    /// This is `None` before the 1-RTT keys are available or if no data was sent.
+    pub fn packet_numbers(&self) -> Option<PacketNumberRange> {
+        let (first, last) = self.packet_numbers?;
+        Some(PacketNumberRange::new(first, last))
+    }
+
+    /// Takes the data so it can be queued on the first bidirectional stream
+    ///
+    /// The returned transmissions contain the number of bytes sent in each 0-RTT packet, in the
+    /// order of the data.
+    pub fn take_data(&mut self) -> (Bytes, Vec<(PacketNumber, usize)>) {
+        (
+            core::mem::take(&mut self.data),
+            core::mem::take(&mut self.transmissions),
+        )
+    }
+
+    pub fn on_transmit<'a>(
+        &mut self,
+        crypto: &ZeroRttCrypto<Config>,
+        context: &mut ConnectionTransmissionContext<Config>,
+        transmission_constraint: transmission::Constraint,
+        buffer: EncoderBuffer<'a>,
+    ) -> Result<(transmission::Outcome, EncoderBuffer<'a>), PacketEncodingError<'a>> {
+        let (key, header_key) = crypto;
+        let packet_number = self.tx_packet_numbers.next();
+        let packet_number_encoder = self.tx_packet_numbers.largest_sent_packet_number_acked();
+        let mut outcome = transmission::Outcome::default();
+        let sent_len = self.sent_len;
+
+        let destination_connection_id = context.path().peer_connection_id;
+        let payload = transmission::Transmission {
+            config: <PhantomData<Config>>::default(),
+            outcome: &mut outcome,
+            packet_number,
+            payload: transmission::zero_rtt::Payload {
+                data: &self.data[..self.max_len],
+                sent_len: &mut self.sent_len,
+            },
+            timestamp: context.timestamp,
+            transmission_constraint,
+            transmission_mode: context.transmission_mode,
+            tx_packet_numbers: &mut self.tx_packet_numbers,
+            path_id: context.path_id,
+            publisher: context.publisher,
+            packet_interceptor: context.packet_interceptor,
+        };
+
+        let packet = ZeroRtt {
+            version: context.quic_version,
+            destination_connection_id,
+            source_connection_id: context.path_manager[context.path_id].local_connection_id,
+            packet_number,
+            payload,
+        };
+
+        let (_protected_packet, buffer) = packet.encode_packet(
+            key,
+            header_key,
+            packet_number_encoder,
+            context.min_packet_len,
+            buffer,
+        )?;
+
+        if self.sent_len > sent_len {
+            self.transmissions
+                .push((packet_number, self.sent_len - sent_len));
+        }
+
+        let mut recovery_context = RecoveryContext {
+            path_id: context.path_id,
+            path_manager: context.path_manager,
+        };
+        self.recovery_manager.on_packet_sent(
+            packet_number,
+            outcome,
+            context.timestamp,
+            context.ecn,
+            context.transmission_mode,
+            None,
+            &mut recovery_context,
+            context.publisher,
+        );
+
+        context
+            .publisher
+            .on_packet_sent(event::builder::PacketSent {
+                packet_header: event::builder::PacketHeader::ZeroRtt {
+                    number: packet_number.as_u64(),
+                    version: context.publisher.quic_version(),
+                },
+                packet_len: outcome.bytes_sent,
+            });
+
+        Ok((outcome, buffer))
+    }
+
+    /// Called when the packet number of the next 0-RTT packet can't be encoded
+    ///
+    /// 0-RTT packets can't be acknowledged before the 1-RTT keys are available, so no more
+    /// 0-RTT packets are sent and the remaining data is sent in 1-RTT packets.
+    pub fn on_packet_number_truncation_error(&mut self) {
+        self.max_len = self.sent_len;
+    }
+}
+
+impl<Config: endpoint::Config> transmission::interest::Provider for EarlyData<Config> {
+    #[inline]
+    fn transmission_interest<Q: transmission::interest::Query>(
+        &self,
+        query: &mut Q,
+    ) -> transmission::interest::Result {
+        if self.sent_len < self.max_len {
+            query.on_new_data()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The recovery context for sending 0-RTT packets
+///
+/// Acknowledgements for 0-RTT packets are carried in 1-RTT packets, so they are processed by the
+/// application space after the recovery manager is handed over.
+struct RecoveryContext<'a, Config: endpoint::Config> {
+    path_id: path::Id,
+    path_manager: &'a mut path::Manager<Config>,
+}
+
+impl<'a, Config: endpoint::Config> recovery::Context<Config> for RecoveryContext<'a, Config> {
+    const ENDPOINT_TYPE: endpoint::Type = Config::ENDPOINT_TYPE;
+
+    fn is_handshake_confirmed(&self) -> bool {
+        false
+    }
+
+    fn path(&self) -> &Path<Config> {
+        &self.path_manager[self.path_id]
+    }
+
+    fn path_mut(&mut self) -> &mut Path<Config> {
+        &mut self.path_manager[self.path_id]
+    }
+
+    fn path_by_id(&self, path_id: path::Id) -> &path::Path<Config> {
+        &self.path_manager[path_id]
+    }
+
+    fn path_mut_by_id(&mut self, path_id: path::Id) -> &mut path::Path<Config> {
+        &mut self.path_manager[path_id]
+    }
+
+    fn path_id(&self) -> path::Id {
+        self.path_id
+    }
+
+    fn validate_packet_ack(
+        &mut self,
+        _timestamp: Timestamp,
+        _packet_number_range: &PacketNumberRange,
+    ) -> Result<(), transport::Error> {
+        // 0-RTT packets are only acknowledged in 1-RTT packets
+        Ok(())
+    }
+
+    fn on_new_packet_ack<Pub: event::ConnectionPublisher>(
+        &mut self,
+        _packet_number_range: &PacketNumberRange,
+        _publisher: &mut Pub,
+    ) {
+    }
+
+    fn on_packet_ack(&mut self, _timestamp: Timestamp, _packet_number_range: &PacketNumberRange) {}
+
+    fn on_packet_loss<Pub: event::ConnectionPublisher>(
+        &mut self,
+        _packet_number_range: &PacketNumberRange,
+        _publisher: &mut Pub,
+    ) {
+    }
+
+    fn on_rtt_update(&mut self) {}
+}
@@ -12,8 +12,9 @@ use crate::{
     transmission,
 };
 use core::{fmt, marker::PhantomData};
-use s2n_codec::EncoderBuffer;
+use s2n_codec::{DecoderBuffer, DecoderError, EncoderBuffer};
 use s2n_quic_core::{
+    anti_replay,
     connection::PeerId,
     crypto::{tls, CryptoSuite, InitialKey},
     event::{self, ConnectionPublisher as _, IntoEvent},
@@ -44,6 +45,10 @@ pub struct InitialSpace<Config: endpoint::Config> {
     pub crypto_stream: CryptoStream,
     pub tx_packet_numbers: TxPacketNumbers,
     pub received_hello_message: bool,
+    /// The `random` field of the ClientHello received by a server
+    pub client_hello_random: Option<[u8; anti_replay::CLIENT_HELLO_RANDOM_LEN]>,
+    /// Set if the ClientHello received by a server includes the `early_data` extension
+    pub client_hello_early_data: bool,
     //= https://www.rfc-editor.org/rfc/rfc9000#section-17.2.5.3
     //# Subsequent Initial packets from the client include the connection ID
     //# and token values from the Retry packet.
@@ -77,6 +82,8 @@ impl<Config: endpoint::Config> InitialSpace<Config> {
             crypto_stream: CryptoStream::new(),
             tx_packet_numbers: TxPacketNumbers::new(PacketNumberSpace::Initial, now),
             received_hello_message: false,
+            client_hello_random: None,
+            client_hello_early_data: false,
             retry_token: Vec::new(),
             processed_packet_numbers: SlidingWindow::default(),
             recovery_manager: recovery::Manager::new(PacketNumberSpace::Initial),
@@ -103,6 +110,7 @@ impl<Config: endpoint::Config> InitialSpace<Config> {
         //# a change to the keys used to protect the Initial packet.
         let (initial_key, initial_header_key) =
                             <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialKey::new_client(
+                                publisher.quic_version(),
                                 retry_source_connection_id.as_bytes(),
                             );
 
@@ -122,6 +130,19 @@ impl<Config: endpoint::Config> InitialSpace<Config> {
             .on_retry_packet(path, path_id, publisher);
     }
 
+    /// Replaces the keys after the QUIC version of the connection changed
+    ///
+    /// Each version derives the Initial keys with a different salt, so packets which are
+    /// sent from now on use the new version.
+    pub fn on_quic_version(
+        &mut self,
+        key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialKey,
+        header_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialHeaderKey,
+    ) {
+        self.key = key;
+        self.header_key = header_key;
+    }
+
     /// Returns true if the packet number has already been processed
     pub fn is_duplicate<Pub: event::ConnectionPublisher>(
         &self,
@@ -444,6 +465,11 @@ impl<Config: endpoint::Config> InitialSpace<Config> {
         debug_assert!(Config::ENDPOINT_TYPE.is_server());
         if let Some(payload) = self.parse_hello(tls::HandshakeType::ClientHello)? {
             publisher.on_tls_client_hello(event::builder::TlsClientHello { payload: &payload });
+
+            if let Ok((random, early_data)) = decode_client_hello(&payload) {
+                self.client_hello_random = Some(random);
+                self.client_hello_early_data = early_data;
+            }
         }
         Ok(())
     }
@@ -707,3 +733,89 @@ impl<Config: endpoint::Config> PacketSpace<Config> for InitialSpace<Config> {
         Ok(())
     }
 }
+
+/// The extension type of `early_data` in a ClientHello
+///
+/// See https://www.rfc-editor.org/rfc/rfc8446#section-4.2
+const EARLY_DATA_EXTENSION: u16 = 42;
+
+/// Returns the `random` field of a ClientHello and if it includes the `early_data` extension
+///
+/// See https://www.rfc-editor.org/rfc/rfc8446#section-4.1.2
+fn decode_client_hello(
+    payload: &[&[u8]],
+) -> Result<([u8; anti_replay::CLIENT_HELLO_RANDOM_LEN], bool), DecoderError> {
+    let hello: Vec<u8> = payload
+        .iter()
+        .flat_map(|chunk| chunk.iter().copied())
+        .collect();
+    let buffer = DecoderBuffer::new(&hello);
+
+    // legacy_version
+    let buffer = buffer.skip(2)?;
+    let (random, buffer) = buffer.decode_slice(anti_replay::CLIENT_HELLO_RANDOM_LEN)?;
+    // legacy_session_id
+    let buffer = buffer.skip_with_len_prefix::<u8>()?;
+    // cipher_suites
+    let buffer = buffer.skip_with_len_prefix::<u16>()?;
+    // legacy_compression_methods
+    let buffer = buffer.skip_with_len_prefix::<u8>()?;
+    let (mut extensions, _) = buffer.decode_slice_with_len_prefix::<u16>()?;
+
+    let mut early_data = false;
+    while !extensions.is_empty() {
+        let (extension_type, remaining) = extensions.decode::<u16>()?;
+        extensions = remaining.skip_with_len_prefix::<u16>()?;
+        early_data |= extension_type == EARLY_DATA_EXTENSION;
+    }
+
+    let mut client_hello_random = [0u8; anti_replay::CLIENT_HELLO_RANDOM_LEN];
+    client_hello_random.copy_from_slice(random.into_less_safe_slice());
+
+    Ok((client_hello_random, early_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client_hello(extensions: &[u16]) -> Vec<u8> {
+        let mut hello = vec![0x03, 0x03];
+        hello.extend_from_slice(&[7; anti_replay::CLIENT_HELLO_RANDOM_LEN]);
+        // legacy_session_id
+        hello.extend_from_slice(&[1, 0xff]);
+        // cipher_suites
+        hello.extend_from_slice(&[0, 2, 0x13, 0x01]);
+        // legacy_compression_methods
+        hello.extend_from_slice(&[1, 0]);
+
+        let mut encoded = vec![];
+        for extension in extensions {
+            encoded.extend_from_slice(&extension.to_be_bytes());
+            encoded.extend_from_slice(&[0, 1, 0]);
+        }
+        hello.extend_from_slice(&(encoded.len() as u16).to_be_bytes());
+        hello.extend_from_slice(&encoded);
+        hello
+    }
+
+    #[test]
+    fn decode_client_hello_test() {
+        let hello = client_hello(&[0, EARLY_DATA_EXTENSION, 57]);
+        // the message can be split across multiple chunks
+        let (first, second) = hello.split_at(20);
+        assert_eq!(
+            decode_client_hello(&[first, second]).unwrap(),
+            ([7; anti_replay::CLIENT_HELLO_RANDOM_LEN], true)
+        );
+
+        let hello = client_hello(&[0, 57]);
+        assert_eq!(
+            decode_client_hello(&[&hello]).unwrap(),
+            ([7; anti_replay::CLIENT_HELLO_RANDOM_LEN], false)
+        );
+
+        // truncated messages can't be decoded
+        assert!(decode_client_hello(&[&hello[..hello.len() - 1]]).is_err());
+    }
+}
@@ -4,9 +4,12 @@
 use crate::{
     ack,
     ack::AckManager,
-    connection, endpoint, path,
+    connection, endpoint,
+    endpoint::version::SupportedVersions,
+    path,
     path::{path_event, Path},
     processed_packet::ProcessedPacket,
+    stream::send_buffer,
     transmission,
 };
 use bytes::Bytes;
@@ -18,23 +21,26 @@ use s2n_codec::DecoderBufferMut;
 use s2n_quic_core::{
     application::ServerName,
     connection::{limits::Limits, InitialId, PeerId},
-    crypto::{tls, tls::Session, CryptoSuite, Key},
+    crypto::{tls, tls::Session, CryptoSuite, InitialKey, Key},
     event::{self, IntoEvent},
     frame::{
         ack::AckRanges, crypto::CryptoRef, datagram::DatagramRef, stream::StreamRef, Ack,
-        ConnectionClose, DataBlocked, HandshakeDone, MaxData, MaxStreamData, MaxStreams,
-        NewConnectionId, NewToken, PathChallenge, PathResponse, ResetStream, RetireConnectionId,
-        StopSending, StreamDataBlocked, StreamsBlocked,
+        AckFrequency, ConnectionClose, DataBlocked, HandshakeDone, ImmediateAck, MaxData,
+        MaxStreamData, MaxStreams, NewConnectionId, NewToken, PathChallenge, PathResponse,
+        ResetStream, RetireConnectionId, StopSending, StreamDataBlocked, StreamsBlocked,
     },
     inet::DatagramInfo,
     packet::number::{PacketNumber, PacketNumberSpace},
-    time::{timer, Timestamp},
-    transport,
+    session_ticket,
+    time::{timer, Timer, Timestamp},
+    transport::{self, parameters::ServerTransportParameters},
 };
 
+pub(crate) mod ack_frequency;
 mod application;
 mod crypto_stream;
 pub(crate) mod datagram;
+mod early_data;
 mod handshake;
 mod handshake_status;
 mod initial;
@@ -44,6 +50,7 @@ mod tx_packet_numbers;
 
 pub(crate) use application::ApplicationSpace;
 pub(crate) use crypto_stream::CryptoStream;
+pub(crate) use early_data::EarlyData;
 pub(crate) use handshake::HandshakeSpace;
 pub(crate) use handshake_status::HandshakeStatus;
 pub(crate) use initial::InitialSpace;
@@ -53,16 +60,29 @@ pub(crate) use tx_packet_numbers::TxPacketNumbers;
 struct SessionInfo<Config: endpoint::Config> {
     session: <Config::TLSEndpoint as tls::Endpoint>::Session,
     initial_cid: InitialId,
+    session_ticket_store: Config::SessionTicketStore,
+    has_session_ticket: bool,
+    anti_replay: Config::AntiReplayGuard,
+    stream_scheduler: Config::StreamScheduler,
+    send_buffer_budget: send_buffer::Budget,
+    /// The QUIC version of the first Initial packet sent by the client
+    original_quic_version: u32,
+    /// The transport parameters of the server, which are sent again with the version it
+    /// switches to
+    server_params: Option<Box<ServerTransportParameters>>,
 }
 
 pub struct PacketSpaceManager<Config: endpoint::Config> {
     session_info: Option<SessionInfo<Config>>,
     retry_cid: Option<Box<PeerId>>,
+    /// The QUIC version used to derive packet protection keys
+    quic_version: u32,
     initial: Option<Box<InitialSpace<Config>>>,
     handshake: Option<Box<HandshakeSpace<Config>>>,
     application: Option<Box<ApplicationSpace<Config>>>,
-    zero_rtt_crypto:
-        Option<Box<<<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttKey>>,
+    zero_rtt_crypto: Option<Box<ZeroRttCrypto<Config>>>,
+    /// The data a client sends on the first bidirectional stream, until the stream is opened
+    early_data: Option<Box<EarlyData<Config>>>,
     handshake_status: HandshakeStatus,
     /// Server Name Indication
     pub server_name: Option<ServerName>,
@@ -74,8 +94,24 @@ pub struct PacketSpaceManager<Config: endpoint::Config> {
     //# another mechanism is used for agreeing on an application protocol,
     //# endpoints MUST use ALPN for this purpose.
     pub application_protocol: Bytes,
+    /// Indicates if the server accepted early data sent by the client
+    pub early_data_status: tls::EarlyDataStatus,
+    /// Set once a server has processed a 0-RTT packet from the client
+    pub received_early_data: bool,
+    /// Limits how long a client keeps its TLS session after the handshake to wait for a session
+    /// ticket
+    session_ticket_timer: Timer,
 }
 
+/// The packet protection and header protection keys for 0-RTT packets
+pub(crate) type ZeroRttCrypto<Config> = (
+    <<<Config as endpoint::Config>::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttKey,
+    <<<Config as endpoint::Config>::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttHeaderKey,
+);
+
+/// The number of PTO periods a client waits for a session ticket after the handshake
+const SESSION_TICKET_PTO_COUNT: u32 = 3;
+
 impl<Config: endpoint::Config> fmt::Debug for PacketSpaceManager<Config> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("PacketSpaceManager")
@@ -136,14 +172,23 @@ macro_rules! packet_space_api {
 }
 
 impl<Config: endpoint::Config> PacketSpaceManager<Config> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new<Pub: event::ConnectionPublisher>(
         initial_cid: InitialId,
         session: <Config::TLSEndpoint as tls::Endpoint>::Session,
+        session_ticket_store: Config::SessionTicketStore,
+        anti_replay: Config::AntiReplayGuard,
+        stream_scheduler: Config::StreamScheduler,
+        send_buffer_budget: send_buffer::Budget,
+        quic_version: u32,
+        server_params: Option<ServerTransportParameters>,
         initial_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialKey,
         header_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialHeaderKey,
         now: Timestamp,
         publisher: &mut Pub,
     ) -> Self {
+        debug_assert_eq!(Config::ENDPOINT_TYPE.is_server(), server_params.is_some());
+
         let ack_manager = AckManager::new(PacketNumberSpace::Initial, ack::Settings::EARLY);
 
         publisher.on_key_update(event::builder::KeyUpdate {
@@ -154,8 +199,16 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
             session_info: Some(SessionInfo {
                 session,
                 initial_cid,
+                session_ticket_store,
+                has_session_ticket: false,
+                anti_replay,
+                stream_scheduler,
+                send_buffer_budget,
+                original_quic_version: quic_version,
+                server_params: server_params.map(Box::new),
             }),
             retry_cid: None,
+            quic_version,
             initial: Some(Box::new(InitialSpace::new(
                 initial_key,
                 header_key,
@@ -165,9 +218,13 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
             handshake: None,
             application: None,
             zero_rtt_crypto: None,
+            early_data: None,
             handshake_status: HandshakeStatus::default(),
             server_name: None,
             application_protocol: Bytes::new(),
+            early_data_status: tls::EarlyDataStatus::default(),
+            received_early_data: false,
+            session_ticket_timer: Timer::default(),
         }
     }
 
@@ -183,17 +240,43 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
 
     packet_space_api!(ApplicationSpace<Config>, application, application_mut);
 
-    #[allow(dead_code)] // 0RTT hasn't been started yet
-    pub fn zero_rtt_crypto(
-        &self,
-    ) -> Option<&<<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttKey> {
+    #[allow(dead_code)]
+    pub fn zero_rtt_crypto(&self) -> Option<&ZeroRttCrypto<Config>> {
         self.zero_rtt_crypto.as_ref().map(Box::as_ref)
     }
 
+    /// Returns the application space along with the 0-RTT keys, if both are available
+    pub fn zero_rtt_mut(
+        &mut self,
+    ) -> Option<(
+        &mut ApplicationSpace<Config>,
+        &mut HandshakeStatus,
+        &ZeroRttCrypto<Config>,
+    )> {
+        let crypto = self.zero_rtt_crypto.as_deref()?;
+        let space = self.application.as_deref_mut()?;
+        Some((space, &mut self.handshake_status, crypto))
+    }
+
     pub fn discard_zero_rtt_crypto(&mut self) {
         self.zero_rtt_crypto = None;
     }
 
+    /// Called by the client with the data to send on the first bidirectional stream
+    ///
+    /// The data is sent in 0-RTT packets if the TLS session provides 0-RTT keys.
+    pub fn on_early_data(&mut self, data: Bytes, now: Timestamp) {
+        debug_assert!(Config::ENDPOINT_TYPE.is_client());
+        self.early_data = Some(Box::new(EarlyData::new(data, now)));
+    }
+
+    /// Returns the early data of the client along with the 0-RTT keys, if both are available
+    pub fn early_data_mut(&mut self) -> Option<(&mut EarlyData<Config>, &ZeroRttCrypto<Config>)> {
+        let crypto = self.zero_rtt_crypto.as_deref()?;
+        let early_data = self.early_data.as_deref_mut()?;
+        Some((early_data, crypto))
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn poll_crypto<Pub: event::ConnectionPublisher>(
         &mut self,
@@ -210,16 +293,26 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
                 now,
                 initial_cid: &session_info.initial_cid,
                 retry_cid: self.retry_cid.as_deref(),
+                quic_version: &mut self.quic_version,
+                original_quic_version: session_info.original_quic_version,
+                server_params: &mut session_info.server_params,
                 initial: &mut self.initial,
                 handshake: &mut self.handshake,
                 application: &mut self.application,
                 zero_rtt_crypto: &mut self.zero_rtt_crypto,
+                early_data: &mut self.early_data,
                 path_manager,
                 handshake_status: &mut self.handshake_status,
                 local_id_registry,
                 limits,
                 server_name: &mut self.server_name,
                 application_protocol: &mut self.application_protocol,
+                early_data_status: &mut self.early_data_status,
+                session_ticket_store: &session_info.session_ticket_store,
+                has_session_ticket: &mut session_info.has_session_ticket,
+                anti_replay: &session_info.anti_replay,
+                stream_scheduler: &session_info.stream_scheduler,
+                send_buffer_budget: &session_info.send_buffer_budget,
                 waker,
                 publisher,
                 datagram,
@@ -227,9 +320,26 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
 
             match session_info.session.poll(&mut context)? {
                 Poll::Ready(_success) => {
-                    // The TLS session and retry_cid is no longer needed
-                    self.session_info = None;
+                    // The retry_cid is no longer needed
                     self.retry_cid = None;
+
+                    // Clients keep the TLS session after the handshake until the server issues a
+                    // session ticket, if the application is storing them
+                    let awaiting_session_ticket = Config::ENDPOINT_TYPE.is_client()
+                        && <Config::SessionTicketStore as session_ticket::Store>::ENABLED
+                        && !session_info.has_session_ticket;
+
+                    if !awaiting_session_ticket {
+                        self.discard_session();
+                    } else if !self.session_ticket_timer.is_armed() {
+                        // Servers usually send tickets right after the handshake, so don't hold
+                        // on to the session if one doesn't arrive
+                        let pto_period = path_manager
+                            .active_path()
+                            .pto_period(PacketNumberSpace::ApplicationData);
+                        self.session_ticket_timer
+                            .set(now + pto_period * SESSION_TICKET_PTO_COUNT);
+                    }
                 }
                 Poll::Pending => return Poll::Pending,
             };
@@ -238,6 +348,16 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
         Poll::Ready(Ok(()))
     }
 
+    /// Discards the TLS session once it is no longer needed after the handshake
+    fn discard_session(&mut self) {
+        self.session_info = None;
+        self.session_ticket_timer.cancel();
+
+        if let Some(space) = self.application.as_mut() {
+            space.crypto_rx = None;
+        }
+    }
+
     /// Called when the connection timer expired
     pub fn on_timeout<Pub: event::ConnectionPublisher>(
         &mut self,
@@ -286,6 +406,15 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
 
         let path = path_manager.active_path_mut();
         path.pto_backoff = path.pto_backoff.min(max_backoff);
+
+        // the server didn't send a session ticket in time
+        if self
+            .session_ticket_timer
+            .poll_expiration(timestamp)
+            .is_ready()
+        {
+            self.discard_session();
+        }
     }
 
     /// Signals the connection was previously blocked by anti-amplification limits
@@ -425,6 +554,7 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
         self.discard_initial(path, path_id, publisher);
         self.discard_handshake(path, path_id, publisher);
         self.discard_zero_rtt_crypto();
+        self.early_data = None;
 
         // Don't discard the application space until the application has read the error
         if let Some((application, _handshake_status)) = self.application_mut() {
@@ -446,6 +576,70 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
     pub fn retry_cid(&self) -> Option<&PeerId> {
         self.retry_cid.as_deref()
     }
+
+    /// Returns the QUIC version used to derive packet protection keys
+    pub fn quic_version(&self) -> u32 {
+        self.quic_version
+    }
+
+    /// Called by the client when the server responds with a different QUIC version
+    ///
+    /// Returns `true` if the client switched to the version, which is only possible once, for
+    /// a version the client offered as compatible.
+    pub fn on_quic_version<Pub: event::ConnectionPublisher>(
+        &mut self,
+        version: u32,
+        publisher: &mut Pub,
+    ) -> bool {
+        debug_assert!(Config::ENDPOINT_TYPE.is_client());
+
+        let session_info = match self.session_info.as_ref() {
+            Some(session_info) => session_info,
+            None => return false,
+        };
+
+        // the server chooses the version when it receives the ClientHello, before the client
+        // has handshake keys
+        if self.quic_version != session_info.original_quic_version || self.handshake.is_some() {
+            return false;
+        }
+
+        if !SupportedVersions::new::<Config::TLSEndpoint>().contains(version) {
+            return false;
+        }
+
+        let space = match self.initial.as_mut() {
+            Some(space) => space,
+            None => return false,
+        };
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-7.2
+        //# The Destination Connection ID field from the first Initial packet
+        //# sent by a client is used to determine packet protection keys for
+        //# Initial packets.
+        let connection_id = self
+            .retry_cid
+            .as_deref()
+            .map(PeerId::as_bytes)
+            .unwrap_or_else(|| session_info.initial_cid.as_bytes());
+        let (key, header_key) =
+            <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialKey::new_client(
+                version,
+                connection_id,
+            );
+
+        publisher.on_key_update(event::builder::KeyUpdate {
+            key_type: event::builder::KeyType::Initial,
+            cipher_suite: key.cipher_suite().into_event(),
+        });
+        space.on_quic_version(key, header_key);
+
+        // 0-RTT packets are protected with keys for the version of the resumed session
+        self.zero_rtt_crypto = None;
+        self.quic_version = version;
+
+        true
+    }
 }
 
 impl<Config: endpoint::Config> timer::Provider for PacketSpaceManager<Config> {
@@ -465,6 +659,7 @@ impl<Config: endpoint::Config> timer::Provider for PacketSpaceManager<Config> {
         if let Some(space) = self.initial.as_ref() {
             space.timers(query)?;
         }
+        self.session_ticket_timer.timers(query)?;
         Ok(())
     }
 }
@@ -489,6 +684,15 @@ impl<Config: endpoint::Config> transmission::interest::Provider for PacketSpaceM
             space.transmission_interest(query)?;
         }
 
+        // early data can only be sent while the 0-RTT keys are available
+        if let Some(early_data) = self
+            .early_data
+            .as_ref()
+            .filter(|_| self.zero_rtt_crypto.is_some())
+        {
+            early_data.transmission_interest(query)?;
+        }
+
         Ok(())
     }
 }
@@ -642,6 +846,8 @@ pub trait PacketSpace<Config: endpoint::Config> {
     default_frame_handler!(handle_stream_data_blocked_frame, StreamDataBlocked);
     default_frame_handler!(handle_streams_blocked_frame, StreamsBlocked);
     default_frame_handler!(handle_new_token_frame, NewToken);
+    default_frame_handler!(handle_ack_frequency_frame, AckFrequency);
+    default_frame_handler!(handle_immediate_ack_frame, ImmediateAck);
 
     fn on_processed_packet<Pub: event::ConnectionPublisher>(
         &mut self,
@@ -864,6 +1070,14 @@ pub trait PacketSpace<Config: endpoint::Config> {
                     )
                     .map_err(on_error)?;
                 }
+                Frame::AckFrequency(frame) => {
+                    let on_error = on_frame_processed!(frame);
+                    self.handle_ack_frequency_frame(frame).map_err(on_error)?;
+                }
+                Frame::ImmediateAck(frame) => {
+                    let on_error = on_frame_processed!(frame);
+                    self.handle_immediate_ack_frame(frame).map_err(on_error)?;
+                }
             }
 
             payload = remaining;
@@ -4,34 +4,40 @@
 use crate::{
     ack::AckManager,
     connection::{self, limits::Limits},
-    endpoint, path,
+    endpoint::{self, version::SupportedVersions},
+    path,
     space::{
-        datagram, keep_alive::KeepAlive, ApplicationSpace, HandshakeSpace, HandshakeStatus,
-        InitialSpace,
+        ack_frequency, datagram, keep_alive::KeepAlive, ApplicationSpace, EarlyData,
+        HandshakeSpace, HandshakeStatus, InitialSpace, ZeroRttCrypto,
     },
-    stream::AbstractStreamManager,
+    stream::{send_buffer, AbstractStreamManager},
 };
 use bytes::Bytes;
-use core::{ops::Not, task::Waker};
-use s2n_codec::{DecoderBuffer, DecoderValue};
+use core::{
+    ops::Not,
+    task::{Context, Waker},
+};
+use s2n_codec::{DecoderBuffer, DecoderValue, EncoderBuffer, EncoderValue};
 use s2n_quic_core::{
     ack,
+    anti_replay::{self, Guard as _},
     application::ServerName,
     connection::{InitialId, PeerId},
     crypto,
-    crypto::{tls, CryptoSuite, Key},
+    crypto::{tls, CryptoSuite, InitialKey, Key},
     ct::ConstantTimeEq,
     datagram::{ConnectionInfo, Endpoint},
     event,
     event::IntoEvent,
     packet::number::PacketNumberSpace,
+    session_ticket::Store as _,
     time::Timestamp,
     transport::{
         self,
         parameters::{
             ActiveConnectionIdLimit, ClientTransportParameters, DatagramLimits,
-            InitialFlowControlLimits, InitialSourceConnectionId, MaxAckDelay,
-            ServerTransportParameters,
+            InitialFlowControlLimits, InitialSourceConnectionId, MaxAckDelay, MigrationSupport,
+            MinAckDelay, ServerTransportParameters, VersionInformation,
         },
     },
 };
@@ -44,14 +50,22 @@ pub struct SessionContext<'a, Config: endpoint::Config, Pub: event::ConnectionPu
     pub initial: &'a mut Option<Box<InitialSpace<Config>>>,
     pub handshake: &'a mut Option<Box<HandshakeSpace<Config>>>,
     pub application: &'a mut Option<Box<ApplicationSpace<Config>>>,
-    pub zero_rtt_crypto: &'a mut Option<
-        Box<<<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttKey>,
-    >,
+    pub zero_rtt_crypto: &'a mut Option<Box<ZeroRttCrypto<Config>>>,
+    pub early_data: &'a mut Option<Box<EarlyData<Config>>>,
+    pub quic_version: &'a mut u32,
+    pub original_quic_version: u32,
+    pub server_params: &'a mut Option<Box<ServerTransportParameters>>,
     pub handshake_status: &'a mut HandshakeStatus,
     pub local_id_registry: &'a mut connection::LocalIdRegistry,
     pub limits: &'a mut Limits,
     pub server_name: &'a mut Option<ServerName>,
     pub application_protocol: &'a mut Bytes,
+    pub early_data_status: &'a mut tls::EarlyDataStatus,
+    pub session_ticket_store: &'a Config::SessionTicketStore,
+    pub has_session_ticket: &'a mut bool,
+    pub anti_replay: &'a Config::AntiReplayGuard,
+    pub stream_scheduler: &'a Config::StreamScheduler,
+    pub send_buffer_budget: &'a send_buffer::Budget,
     pub waker: &'a Waker,
     pub publisher: &'a mut Pub,
     pub datagram: &'a mut Config::DatagramEndpoint,
@@ -70,6 +84,7 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             ActiveConnectionIdLimit,
             DatagramLimits,
             MaxAckDelay,
+            Option<MinAckDelay>,
         ),
         transport::Error,
     > {
@@ -103,6 +118,19 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
                 .as_bytes(),
         )?;
 
+        if peer_parameters.version_information.is_none()
+            && *self.quic_version != self.original_quic_version
+        {
+            // The server is required to authenticate a version it switched to
+            // See https://www.rfc-editor.org/rfc/rfc9368#section-4
+            return Err(transport::Error::VERSION_NEGOTIATION_ERROR
+                .with_reason("version_information absent after a version change"));
+        }
+        self.validate_version_information(
+            peer_parameters.version_information.as_ref(),
+            *self.quic_version,
+        )?;
+
         match (self.retry_cid, peer_parameters.retry_source_connection_id) {
             (Some(retry_packet_value), Some(transport_params_value)) => {
                 if retry_packet_value
@@ -176,6 +204,55 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
                 .register_initial_stateless_reset_token(stateless_reset_token);
         }
 
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
+        //# disable_active_migration (0x0c): The disable active migration
+        //#    transport parameter is included if the endpoint does not support
+        //#    active connection migration (Section 9) on the address being used
+        //#    during the handshake.
+        if peer_parameters.migration_support == MigrationSupport::Disabled {
+            self.path_manager.on_peer_migration_disabled();
+        }
+
+        if let Some(preferred_address) = peer_parameters.preferred_address {
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
+            //# A server
+            //# that chooses a zero-length connection ID MUST NOT provide a
+            //# preferred address.  Similarly, a server MUST NOT include a zero-
+            //# length connection ID in this transport parameter.  A client MUST
+            //# treat a violation of these requirements as a connection error of
+            //# type TRANSPORT_PARAMETER_ERROR.
+            let is_server_id_empty = self
+                .path_manager
+                .active_path()
+                .peer_connection_id
+                .is_empty();
+            let connection_id = PeerId::try_from_bytes(preferred_address.connection_id.as_bytes())
+                .filter(|id| !id.is_empty() && !is_server_id_empty)
+                .ok_or_else(|| {
+                    transport::Error::TRANSPORT_PARAMETER_ERROR
+                        .with_reason("zero-length connection ID with preferred_address")
+                })?;
+
+            //= https://www.rfc-editor.org/rfc/rfc9000#section-18.2
+            //# The Connection ID field and the Stateless Reset Token field
+            //# contain an alternative connection ID that has a sequence number of
+            //# 1; see Section 5.1.1.
+            self.path_manager
+                .peer_id_registry
+                .on_new_connection_id(
+                    &connection_id,
+                    1,
+                    0,
+                    &preferred_address.stateless_reset_token,
+                )
+                .map_err(|_| {
+                    transport::Error::TRANSPORT_PARAMETER_ERROR
+                        .with_reason("invalid preferred_address connection ID")
+                })?;
+
+            self.path_manager.on_preferred_address(&preferred_address);
+        }
+
         // Load the peer's transport parameters into the connection's limits
         self.limits.load_peer(&peer_parameters);
 
@@ -188,6 +265,7 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             active_connection_id_limit,
             datagram_limits,
             peer_parameters.max_ack_delay,
+            peer_parameters.min_ack_delay,
         ))
     }
 
@@ -201,6 +279,7 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             ActiveConnectionIdLimit,
             DatagramLimits,
             MaxAckDelay,
+            Option<MinAckDelay>,
         ),
         transport::Error,
     > {
@@ -234,6 +313,11 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
                 .as_bytes(),
         )?;
 
+        self.validate_version_information(
+            peer_parameters.version_information.as_ref(),
+            self.original_quic_version,
+        )?;
+
         // Load the peer's transport parameters into the connection's limits
         self.limits.load_peer(&peer_parameters);
 
@@ -246,6 +330,7 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             active_connection_id_limit,
             datagram_limits,
             peer_parameters.max_ack_delay,
+            peer_parameters.min_ack_delay,
         ))
     }
 
@@ -291,6 +376,25 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
 
         Ok(())
     }
+
+    // The Chosen Version in the version_information transport parameter needs to match the
+    // version of the Initial packets the peer sent, otherwise an attacker could have modified
+    // the version negotiation.
+    // See https://www.rfc-editor.org/rfc/rfc9368#section-4
+    fn validate_version_information(
+        &self,
+        peer_value: Option<&VersionInformation>,
+        expected_version: u32,
+    ) -> Result<(), transport::Error> {
+        if let Some(peer_value) = peer_value {
+            if peer_value.chosen_version() != expected_version {
+                return Err(transport::Error::VERSION_NEGOTIATION_ERROR
+                    .with_reason("version_information chosen_version mismatch"));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
@@ -332,18 +436,38 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
     fn on_zero_rtt_keys(
         &mut self,
         key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttKey,
-        _header_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttHeaderKey,
-        _application_parameters: tls::ApplicationParameters,
+        header_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttHeaderKey,
+        application_parameters: tls::ApplicationParameters,
     ) -> Result<(), transport::Error> {
         if self.zero_rtt_crypto.is_some() {
             return Err(transport::Error::INTERNAL_ERROR
                 .with_reason("zero rtt keys initialized more than once"));
         }
 
+        if Config::ENDPOINT_TYPE.is_server() && !self.early_data_status.is_accepted() {
+            // The TLS provider is required to reject early data on the wire unless
+            // `on_early_data` accepted it, so the keys can't be used without a decision from the
+            // anti-replay guard.
+            return Err(transport::Error::INTERNAL_ERROR
+                .with_reason("early data was not accepted by the anti-replay guard"));
+        }
+
+        if let Some(early_data) = self.early_data.as_mut() {
+            debug_assert!(Config::ENDPOINT_TYPE.is_client());
+
+            // The parameters are the ones the server provided in the connection which issued the
+            // session ticket. If they can't be decoded, the data is only sent after the handshake.
+            let param_decoder = DecoderBuffer::new(application_parameters.transport_parameters);
+            if let Ok((peer_parameters, _remaining)) =
+                ServerTransportParameters::decode(param_decoder)
+            {
+                early_data.on_zero_rtt_keys(&peer_parameters.flow_control_limits());
+            }
+        }
+
         let cipher_suite = key.cipher_suite().into_event();
 
-        // TODO: also store the header_key https://github.com/aws/s2n-quic/issues/319
-        *self.zero_rtt_crypto = Some(Box::new(key));
+        *self.zero_rtt_crypto = Some(Box::new((key, header_key)));
 
         self.publisher.on_key_update(event::builder::KeyUpdate {
             key_type: event::builder::KeyType::ZeroRtt,
@@ -373,11 +497,16 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
 
         // Parse transport parameters
         let param_decoder = DecoderBuffer::new(application_parameters.transport_parameters);
-        let (peer_flow_control_limits, active_connection_id_limit, datagram_limits, max_ack_delay) =
-            match Config::ENDPOINT_TYPE {
-                endpoint::Type::Client => self.on_server_params(param_decoder)?,
-                endpoint::Type::Server => self.on_client_params(param_decoder)?,
-            };
+        let (
+            peer_flow_control_limits,
+            active_connection_id_limit,
+            datagram_limits,
+            max_ack_delay,
+            min_ack_delay,
+        ) = match Config::ENDPOINT_TYPE {
+            endpoint::Type::Client => self.on_server_params(param_decoder)?,
+            endpoint::Type::Server => self.on_client_params(param_decoder)?,
+        };
 
         self.local_id_registry
             .set_active_connection_id_limit(active_connection_id_limit.as_u64());
@@ -387,6 +516,8 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             Config::ENDPOINT_TYPE,
             self.limits.initial_flow_control_limits(),
             peer_flow_control_limits,
+            self.send_buffer_budget.clone(),
+            Box::new(self.stream_scheduler.clone()),
         );
 
         let ack_manager = AckManager::new(
@@ -408,6 +539,12 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             datagram_limits.max_datagram_payload,
         );
 
+        let ack_frequency = ack_frequency::Controller::new(
+            self.limits.min_ack_delay(),
+            min_ack_delay.map(|min_ack_delay| min_ack_delay.as_duration()),
+            max_ack_delay.as_duration(),
+        );
+
         self.path_manager
             .active_path_mut()
             .rtt_estimator
@@ -415,7 +552,7 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
 
         let cipher_suite = key.cipher_suite().into_event();
         let max_mtu = self.path_manager.max_mtu();
-        *self.application = Some(Box::new(ApplicationSpace::new(
+        let mut space = ApplicationSpace::new(
             key,
             header_key,
             self.now,
@@ -424,7 +561,30 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             keep_alive,
             max_mtu,
             datagram_manager,
-        )));
+            ack_frequency,
+        );
+
+        if let Some(early_data) = self.early_data.as_mut() {
+            early_data.on_one_rtt_keys(&mut space.tx_packet_numbers, &mut space.recovery_manager);
+
+            // The data sent in 0-RTT packets is tracked in those packets, so it's only sent
+            // again in 1-RTT packets if they are lost or the server rejects 0-RTT.
+            let (data, transmissions) = early_data.take_data();
+            space.stream_manager.on_early_data(
+                data,
+                &transmissions,
+                &Context::from_waker(self.waker),
+            );
+
+            // the TLS provider may report the rejection before the 1-RTT keys are available
+            if self.early_data_status.is_rejected() {
+                if let Some(packet_numbers) = early_data.packet_numbers() {
+                    space.on_zero_rtt_rejected(packet_numbers, self.path_manager, self.publisher);
+                }
+            }
+        }
+
+        *self.application = Some(Box::new(space));
         self.publisher.on_key_update(event::builder::KeyUpdate {
             key_type: event::builder::KeyType::OneRtt { generation: 0 },
             cipher_suite,
@@ -457,6 +617,189 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
         Ok(())
     }
 
+    fn on_session_ticket(&mut self, session_ticket: Bytes) -> Result<(), transport::Error> {
+        debug_assert!(Config::ENDPOINT_TYPE.is_client());
+
+        if let Some(server_name) = self.server_name.as_ref() {
+            self.session_ticket_store
+                .on_session_ticket(server_name, session_ticket);
+        }
+
+        *self.has_session_ticket = true;
+
+        Ok(())
+    }
+
+    fn on_early_data_status(
+        &mut self,
+        status: tls::EarlyDataStatus,
+    ) -> Result<(), transport::Error> {
+        debug_assert!(Config::ENDPOINT_TYPE.is_client());
+
+        //= https://www.rfc-editor.org/rfc/rfc9001#section-4.6.2
+        //# A server rejects 0-RTT by sending the EncryptedExtensions without an
+        //# early_data extension.
+        if status.is_rejected() {
+            // the 0-RTT keys can't be used to send any more data
+            *self.zero_rtt_crypto = None;
+
+            // Once the 1-RTT keys are available, the 0-RTT packets are tracked by the application
+            // space. Otherwise they are discarded when the keys become available.
+            let packet_numbers = self
+                .early_data
+                .as_ref()
+                .and_then(|early_data| early_data.packet_numbers());
+            if let (Some(packet_numbers), Some(space)) = (packet_numbers, self.application.as_mut())
+            {
+                space.on_zero_rtt_rejected(packet_numbers, self.path_manager, self.publisher);
+            }
+        }
+
+        let is_sent = self
+            .early_data
+            .as_ref()
+            .map_or(false, |early_data| early_data.is_sent());
+
+        // the TLS provider only knows if early data was offered in the handshake, so don't
+        // report a status for data which was never sent in 0-RTT packets
+        *self.early_data_status = if is_sent {
+            status
+        } else {
+            tls::EarlyDataStatus::NotAttempted
+        };
+
+        Ok(())
+    }
+
+    fn on_early_data(&mut self) -> anti_replay::Outcome {
+        debug_assert!(Config::ENDPOINT_TYPE.is_server());
+
+        let client_hello_random = self.initial.as_ref().and_then(|space| {
+            // only ClientHello messages which offer early data are recorded by the guard
+            if space.client_hello_early_data {
+                space.client_hello_random.as_ref()
+            } else {
+                None
+            }
+        });
+
+        let client_hello_random = if let Some(client_hello_random) = client_hello_random {
+            client_hello_random
+        } else {
+            // either the client didn't offer early data or there's no way to detect a replay
+            return anti_replay::Outcome::Reject;
+        };
+
+        //= https://www.rfc-editor.org/rfc/rfc9001#section-9.2
+        //# Endpoints MUST implement and use the replay protections described in
+        //# [TLS13], however it is recognized that these protections are
+        //# imperfect.
+        let outcome = self
+            .anti_replay
+            .on_early_data(&anti_replay::Context::new(client_hello_random));
+
+        *self.early_data_status = if outcome.is_accepted() {
+            tls::EarlyDataStatus::Accepted
+        } else {
+            tls::EarlyDataStatus::Rejected
+        };
+
+        outcome
+    }
+
+    fn quic_version(&self) -> u32 {
+        *self.quic_version
+    }
+
+    fn on_client_application_params(
+        &mut self,
+        client_params: tls::ApplicationParameters,
+    ) -> Result<Option<Bytes>, transport::Error> {
+        debug_assert!(Config::ENDPOINT_TYPE.is_server());
+
+        let server_params = match self.server_params.as_mut() {
+            Some(server_params) => server_params,
+            None => return Ok(None),
+        };
+
+        let param_decoder = DecoderBuffer::new(client_params.transport_parameters);
+        let (peer_parameters, _remaining) = ClientTransportParameters::decode(param_decoder)
+            .map_err(|_| {
+                transport::Error::TRANSPORT_PARAMETER_ERROR
+                    .with_reason("Invalid transport parameters")
+            })?;
+
+        // clients which don't support compatible version negotiation keep the original version
+        let version_information = match peer_parameters.version_information {
+            Some(version_information) => version_information,
+            None => return Ok(None),
+        };
+
+        if version_information.chosen_version() != self.original_quic_version {
+            return Err(transport::Error::VERSION_NEGOTIATION_ERROR
+                .with_reason("version_information chosen_version mismatch"));
+        }
+
+        // 0-RTT packets are protected with keys for the version of the resumed session, so
+        // the server stays on the version the client chose
+        if self.zero_rtt_crypto.is_some() {
+            return Ok(None);
+        }
+
+        let supported_versions = SupportedVersions::new::<Config::TLSEndpoint>();
+        let version = supported_versions.select(
+            self.original_quic_version,
+            version_information.available_versions(),
+        );
+
+        if version == *self.quic_version {
+            return Ok(None);
+        }
+
+        let space = match self.initial.as_mut() {
+            Some(space) => space,
+            None => return Ok(None),
+        };
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-7.2
+        //# The Destination Connection ID field from the first Initial packet
+        //# sent by a client is used to determine packet protection keys for
+        //# Initial packets.
+        let connection_id = if let Some(retry_cid) =
+            server_params.retry_source_connection_id.as_ref()
+        {
+            retry_cid.as_bytes()
+        } else if let Some(original_cid) = server_params.original_destination_connection_id.as_ref()
+        {
+            original_cid.as_bytes()
+        } else {
+            return Err(transport::Error::INTERNAL_ERROR
+                .with_reason("missing original_destination_connection_id"));
+        };
+
+        // The server switches versions before it sends any packets so the client receives all of
+        // the server's Initial packets with the new version.
+        // See https://www.rfc-editor.org/rfc/rfc9368#section-2.3
+        let (key, header_key) =
+            <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::InitialKey::new_server(
+                version,
+                connection_id,
+            );
+
+        self.publisher.on_key_update(event::builder::KeyUpdate {
+            key_type: event::builder::KeyType::Initial,
+            cipher_suite: key.cipher_suite().into_event(),
+        });
+        space.on_quic_version(key, header_key);
+        *self.quic_version = version;
+
+        server_params.version_information = Some(supported_versions.version_information(version));
+
+        let mut buffer = vec![0; server_params.encoding_size()];
+        server_params.encode(&mut EncoderBuffer::new(&mut buffer));
+        Ok(Some(buffer.into()))
+    }
+
     fn on_handshake_complete(&mut self) -> Result<(), transport::Error> {
         // After the handshake is complete, the handshake crypto stream should be completely
         // finished
@@ -464,6 +807,15 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             space.crypto_stream.finish()?;
         }
 
+        if Config::ENDPOINT_TYPE.is_server()
+            && self.early_data_status.is_accepted()
+            && self.zero_rtt_crypto.is_none()
+        {
+            // The guard accepted the ClientHello but the TLS provider still rejected early data,
+            // e.g. because the session ticket couldn't be resumed
+            *self.early_data_status = tls::EarlyDataStatus::Rejected;
+        }
+
         if self.application_protocol.is_empty() {
             //= https://www.rfc-editor.org/rfc/rfc9001#section-8.1
             //# When using ALPN, endpoints MUST immediately close a connection (see
@@ -481,6 +833,9 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             return Err(err);
         }
 
+        // the status of the early data is known by now, so it's no longer needed
+        *self.early_data = None;
+
         self.handshake_status
             .on_handshake_complete(Config::ENDPOINT_TYPE, self.publisher);
 
@@ -529,9 +884,13 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             .map(|bytes| bytes.freeze())
     }
 
-    fn receive_application(&mut self, _max_len: Option<usize>) -> Option<Bytes> {
-        // Application doesn't currently have a buffer
-        None
+    fn receive_application(&mut self, max_len: Option<usize>) -> Option<Bytes> {
+        self.application
+            .as_deref_mut()?
+            .crypto_rx
+            .as_mut()?
+            .pop_watermarked(max_len.unwrap_or(usize::MAX))
+            .map(|bytes| bytes.freeze())
     }
 
     fn can_send_initial(&self) -> bool {
@@ -13,7 +13,7 @@ use core::{
 };
 pub use s2n_quic_core::{
     application,
-    stream::{ops, StreamError, StreamId, StreamType},
+    stream::{ops, Priority, StreamError, StreamId, StreamType},
 };
 
 #[derive(Clone)]
@@ -224,6 +224,18 @@ macro_rules! tx_stream_apis {
             self.tx_request()?.reset(error_code).poll(None)?;
             Ok(())
         }
+
+        /// Sets the priority with which the stream is scheduled for transmission.
+        ///
+        /// Streams with a lower `urgency` are transmitted before streams with a higher
+        /// `urgency`. `incremental` streams of the same urgency share the available
+        /// capacity, while non-incremental streams are transmitted one after the other.
+        pub fn set_priority(&mut self, urgency: u8, incremental: bool) -> Result<(), StreamError> {
+            self.tx_request()?
+                .set_priority(Priority::new(urgency, incremental))
+                .poll(None)?;
+            Ok(())
+        }
     };
 }
 
@@ -494,6 +506,11 @@ macro_rules! tx_request_apis {
             self.request.flush();
             self
         }
+
+        pub fn set_priority(&mut self, priority: Priority) -> &mut Self {
+            self.request.set_priority(priority);
+            self
+        }
     };
 }
 
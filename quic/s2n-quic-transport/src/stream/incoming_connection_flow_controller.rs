@@ -5,14 +5,15 @@
 
 use crate::{
     contexts::{OnTransmitError, WriteContext},
+    stream::receive_window,
     sync::{IncrementalValueSync, ValueToFrameWriter},
     transmission,
 };
 use alloc::rc::Rc;
-use core::cell::RefCell;
+use core::{cell::RefCell, time::Duration};
 use s2n_quic_core::{
-    ack, frame::max_data::MaxData, packet::number::PacketNumber, stream::StreamId, transport,
-    varint::VarInt,
+    ack, frame::max_data::MaxData, packet::number::PacketNumber, recovery::DEFAULT_INITIAL_RTT,
+    stream::StreamId, transport, varint::VarInt,
 };
 
 /// Writes `MAX_DATA` frames based on the connections flow control window.
@@ -46,10 +47,21 @@ struct IncomingConnectionFlowControllerImpl {
     /// The amount of flow control credits which had been acquired and where the
     /// data had already been consumed by the application
     pub(super) consumed_window: VarInt,
+    /// Grows the `desired_flow_control_window` based on the rate the application consumes data
+    window_tuner: receive_window::Tuner,
+    /// The largest flow control window that streams may grow to
+    max_stream_flow_control_window: u32,
+    /// The smoothed RTT of the active path
+    smoothed_rtt: Duration,
 }
 
 impl IncomingConnectionFlowControllerImpl {
-    pub fn new(initial_window_size: VarInt, desired_flow_control_window: u32) -> Self {
+    pub fn new(
+        initial_window_size: VarInt,
+        desired_flow_control_window: u32,
+        max_flow_control_window: u32,
+        max_stream_flow_control_window: u32,
+    ) -> Self {
         Self {
             read_window_sync: IncrementalValueSync::new(
                 VarInt::from_u32(desired_flow_control_window),
@@ -59,6 +71,9 @@ impl IncomingConnectionFlowControllerImpl {
             desired_flow_control_window,
             acquired_window: VarInt::from_u32(0),
             consumed_window: VarInt::from_u32(0),
+            window_tuner: receive_window::Tuner::new(max_flow_control_window),
+            max_stream_flow_control_window,
+            smoothed_rtt: DEFAULT_INITIAL_RTT,
         }
     }
 
@@ -84,6 +99,32 @@ impl IncomingConnectionFlowControllerImpl {
         );
     }
 
+    /// Grows the desired flow control window to `window` bytes
+    fn grow_window(&mut self, window: u32) {
+        if window <= self.desired_flow_control_window {
+            return;
+        }
+
+        self.desired_flow_control_window = window;
+        self.read_window_sync
+            .set_threshold(VarInt::from_u32(window / 10));
+
+        let latest_value = self
+            .consumed_window
+            .saturating_add(VarInt::from_u32(window))
+            .max(self.read_window_sync.latest_value());
+        self.read_window_sync.update_latest_value(latest_value);
+    }
+
+    /// Makes sure the connection window is large enough for a stream window of the given size
+    fn on_stream_window_update(&mut self, stream_window: u32) {
+        // Keep the connection window larger than any stream window so a single stream can't use
+        // the entire connection window
+        let window =
+            (stream_window as u64 * 3 / 2).min(self.window_tuner.max_window() as u64) as u32;
+        self.grow_window(window);
+    }
+
     pub fn acquire_window(&mut self, desired: VarInt) -> Result<(), transport::Error> {
         if self.remaining_window() < desired {
             //= https://www.rfc-editor.org/rfc/rfc9000#section-4.1
@@ -107,6 +148,15 @@ impl IncomingConnectionFlowControllerImpl {
 
     #[inline]
     pub fn on_transmit<W: WriteContext>(&mut self, context: &mut W) -> Result<(), OnTransmitError> {
+        if let Some(window) = self.window_tuner.on_transmit(
+            context.current_time(),
+            self.consumed_window,
+            self.desired_flow_control_window,
+            self.smoothed_rtt,
+        ) {
+            self.grow_window(window);
+        }
+
         // Stream ID does not matter here, since it does not get transmitted
         self.read_window_sync
             .on_transmit(StreamId::from_varint(VarInt::from_u32(0)), context)
@@ -134,11 +184,22 @@ impl IncomingConnectionFlowController {
     /// `desired_flow_control_window`. This means if the window which is indicated
     /// to the peer is lower than this value the new value will be communicated
     /// to the peer.
-    pub fn new(initial_window_size: VarInt, desired_flow_control_window: u32) -> Self {
+    ///
+    /// The window grows up to `max_flow_control_window` if the application consumes data
+    /// fast enough for the window to limit the peer's sending rate. Streams sharing the
+    /// connection window may grow up to `max_stream_flow_control_window`.
+    pub fn new(
+        initial_window_size: VarInt,
+        desired_flow_control_window: u32,
+        max_flow_control_window: u32,
+        max_stream_flow_control_window: u32,
+    ) -> Self {
         Self {
             inner: Rc::new(RefCell::new(IncomingConnectionFlowControllerImpl::new(
                 initial_window_size,
                 desired_flow_control_window,
+                max_flow_control_window,
+                max_stream_flow_control_window,
             ))),
         }
     }
@@ -157,6 +218,28 @@ impl IncomingConnectionFlowController {
         self.inner.borrow_mut().release_window(amount)
     }
 
+    /// Notifies the flow controller that a stream grew its flow control window
+    pub fn on_stream_window_update(&mut self, stream_window: u32) {
+        self.inner
+            .borrow_mut()
+            .on_stream_window_update(stream_window)
+    }
+
+    /// Returns the largest flow control window that streams may grow to
+    pub fn max_stream_flow_control_window(&self) -> u32 {
+        self.inner.borrow().max_stream_flow_control_window
+    }
+
+    /// This method gets called when the RTT estimate is updated for the active path
+    pub fn on_rtt_update(&mut self, smoothed_rtt: Duration) {
+        self.inner.borrow_mut().smoothed_rtt = smoothed_rtt;
+    }
+
+    /// Returns the smoothed RTT of the active path
+    pub fn smoothed_rtt(&self) -> Duration {
+        self.inner.borrow().smoothed_rtt
+    }
+
     /// This method gets called when a packet delivery got acknowledged
     pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A) {
         self.inner.borrow_mut().on_packet_ack(ack_set)
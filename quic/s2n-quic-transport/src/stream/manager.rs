@@ -11,6 +11,7 @@ use crate::{
         self,
         incoming_connection_flow_controller::IncomingConnectionFlowController,
         outgoing_connection_flow_controller::OutgoingConnectionFlowController,
+        send_buffer,
         stream_container::{StreamContainer, StreamContainerIterationResult},
         stream_events::StreamEvents,
         stream_impl::StreamConfig,
@@ -18,6 +19,7 @@ use crate::{
     },
     transmission::{self, interest::Provider as _},
 };
+use bytes::Bytes;
 use core::{
     task::{Context, Poll, Waker},
     time::Duration,
@@ -29,8 +31,8 @@ use s2n_quic_core::{
         stream::StreamRef, DataBlocked, MaxData, MaxStreamData, MaxStreams, ResetStream,
         StopSending, StreamDataBlocked, StreamsBlocked,
     },
-    packet::number::PacketNumberSpace,
-    stream::{iter::StreamIter, ops, StreamId, StreamType},
+    packet::number::{PacketNumber, PacketNumberSpace},
+    stream::{iter::StreamIter, ops, Scheduler, StreamId, StreamType},
     time::{timer, Timestamp},
     transport::{self, parameters::InitialFlowControlLimits},
     varint::VarInt,
@@ -186,6 +188,8 @@ pub struct StreamManagerState<S> {
     /// Limits for the Stream manager. Since only Stream limits are utilized at
     /// the moment we only store those
     stream_limits: stream::Limits,
+    /// Sizes the send buffers of all streams
+    send_buffer: send_buffer::Controller,
 }
 
 impl<S: StreamTrait> StreamManagerState<S> {
@@ -239,6 +243,7 @@ impl<S: StreamTrait> StreamManagerState<S> {
             desired_flow_control_window: initial_receive_window.as_u64() as u32,
             initial_send_window,
             max_send_buffer_size: self.stream_limits.max_send_buffer_size.as_u32(),
+            send_buffer: self.send_buffer.clone(),
         }));
     }
 
@@ -411,6 +416,9 @@ impl<S: StreamTrait> StreamManagerState<S> {
 pub struct AbstractStreamManager<S> {
     pub(super) inner: StreamManagerState<S>,
     last_blocked_sync_period: Duration,
+    /// The stream carrying the data a client sent in 0-RTT packets, until it is returned to the
+    /// application
+    early_data_stream: Option<StreamId>,
 }
 
 // Sending the `AbstractStreamManager` between threads is safe, since we never expose the `Rc`s
@@ -425,6 +433,8 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
         local_endpoint_type: endpoint::Type,
         initial_local_limits: InitialFlowControlLimits,
         initial_peer_limits: InitialFlowControlLimits,
+        send_buffer_budget: send_buffer::Budget,
+        scheduler: Box<dyn Scheduler>,
     ) -> Self {
         debug_assert!(
             initial_local_limits.max_data <= VarInt::from_u32(core::u32::MAX),
@@ -436,6 +446,8 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
                 incoming_connection_flow_controller: IncomingConnectionFlowController::new(
                     initial_local_limits.max_data,
                     initial_local_limits.max_data.as_u64() as u32,
+                    connection_limits.max_data_window(),
+                    connection_limits.max_stream_data_window(),
                 ),
                 outgoing_connection_flow_controller: OutgoingConnectionFlowController::new(
                     initial_peer_limits.max_data,
@@ -446,7 +458,7 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
                     initial_local_limits,
                     connection_limits.stream_limits(),
                 ),
-                streams: StreamContainer::new(),
+                streams: StreamContainer::new(scheduler),
                 next_stream_ids: StreamIdSet::initial(),
                 local_endpoint_type,
                 initial_local_limits,
@@ -454,8 +466,17 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
                 close_reason: None,
                 accept_state: AcceptState::new(local_endpoint_type),
                 stream_limits: connection_limits.stream_limits(),
+                send_buffer: send_buffer::Controller::new(
+                    send_buffer_budget,
+                    connection_limits
+                        .stream_limits()
+                        .max_send_buffer_size
+                        .as_u32(),
+                    connection_limits.send_buffer_auto_sizing(),
+                ),
             },
             last_blocked_sync_period: Duration::ZERO,
+            early_data_stream: None,
         }
     }
 
@@ -567,6 +588,12 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
             return Err(error).into();
         }
 
+        if stream_type == StreamType::Bidirectional {
+            if let Some(stream_id) = self.early_data_stream.take() {
+                return Ok(stream_id).into();
+            }
+        }
+
         let first_unopened_id =
             ready!(self
                 .inner
@@ -582,6 +609,61 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
         Ok(first_unopened_id).into()
     }
 
+    /// Queues the data a client sent in 0-RTT packets on the first bidirectional stream
+    ///
+    /// `transmissions` contains the number of bytes sent in each 0-RTT packet, in the order of
+    /// the data. Those bytes are tracked as in flight in their packets, so they are only sent
+    /// again in 1-RTT packets if the packets are declared lost or the server rejects 0-RTT.
+    /// The stream is returned by the next call to `poll_open_local_stream` for a bidirectional
+    /// stream. The data is discarded if the server doesn't allow opening bidirectional streams.
+    pub fn on_early_data(
+        &mut self,
+        data: Bytes,
+        transmissions: &[(PacketNumber, usize)],
+        context: &Context,
+    ) {
+        debug_assert!(self.inner.local_endpoint_type.is_client());
+
+        if self
+            .inner
+            .initial_peer_limits
+            .max_open_remote_bidirectional_streams
+            == VarInt::from_u8(0)
+        {
+            return;
+        }
+
+        let mut open_token = connection::OpenToken::new();
+        let stream_id = match self.poll_open_local_stream(
+            StreamType::Bidirectional,
+            &mut open_token,
+            context,
+        ) {
+            Poll::Ready(Ok(stream_id)) => stream_id,
+            _ => return,
+        };
+
+        let mut chunks = [data];
+        let mut request = ops::Request::default();
+        request.send(&mut chunks);
+
+        let _ = self.inner.streams.with_stream(
+            stream_id,
+            &mut self.inner.stream_controller,
+            |stream| {
+                stream.poll_request(&mut request, None)?;
+
+                for (packet_number, len) in transmissions.iter().copied() {
+                    stream.on_early_transmit(packet_number, len);
+                }
+
+                Ok::<_, StreamError>(())
+            },
+        );
+
+        self.early_data_stream = Some(stream_id);
+    }
+
     /// This method gets called when a packet delivery got acknowledged
     pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A) {
         self.inner
@@ -628,6 +710,10 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
 
     /// This method gets called when the RTT estimate is updated for the active path
     pub fn on_rtt_update(&mut self, rtt_estimator: &RttEstimator) {
+        self.inner
+            .incoming_connection_flow_controller
+            .on_rtt_update(rtt_estimator.smoothed_rtt());
+
         let blocked_sync_period = self.blocked_sync_period(rtt_estimator);
 
         {
@@ -664,6 +750,13 @@ impl<S: StreamTrait> AbstractStreamManager<S> {
         );
     }
 
+    /// This method gets called when the congestion window of the active path changes
+    pub fn on_congestion_window_update(&mut self, congestion_window: u32) {
+        self.inner
+            .send_buffer
+            .on_congestion_window_update(congestion_window);
+    }
+
     /// Called when the connection timer expires
     pub fn on_timeout(&mut self, now: Timestamp) {
         self.inner.stream_controller.on_timeout(now);
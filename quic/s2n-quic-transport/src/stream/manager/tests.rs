@@ -38,8 +38,8 @@ use s2n_quic_core::{
         stream::StreamRef, DataBlocked, Frame, MaxData, MaxStreamData, MaxStreams, ResetStream,
         StopSending, Stream as StreamFrame, StreamDataBlocked, StreamsBlocked,
     },
-    packet::number::{PacketNumberRange, PacketNumberSpace},
-    stream::{ops, StreamId, StreamType},
+    packet::number::{PacketNumber, PacketNumberRange, PacketNumberSpace},
+    stream::{ops, scheduler, Priority, StreamId, StreamType},
     time::{
         timer::{self, Provider as _},
         Timestamp,
@@ -65,6 +65,7 @@ struct MockStream {
     on_connection_window_available_retrieve_window: u64,
     on_packet_ack_count: usize,
     on_packet_loss_count: usize,
+    early_transmissions: Vec<(PacketNumber, usize)>,
     update_blocked_sync_period_count: usize,
     on_timeout_count: usize,
     on_internal_reset_count: usize,
@@ -102,6 +103,7 @@ impl MockStream {
 impl StreamInterestProvider for MockStream {
     fn stream_interests(&self, interests: &mut StreamInterests) {
         interests.merge(&self.interests);
+        interests.priority = self.interests.priority;
 
         interests.connection_flow_control_credits |=
             self.on_connection_window_available_retrieve_window > 0;
@@ -136,6 +138,7 @@ impl StreamTrait for MockStream {
             on_connection_window_available_retrieve_window: 0,
             on_packet_ack_count: 0,
             on_packet_loss_count: 0,
+            early_transmissions: Vec::new(),
             update_blocked_sync_period_count: 0,
             on_timeout_count: 0,
             on_internal_reset_count: 0,
@@ -250,6 +253,10 @@ impl StreamTrait for MockStream {
         self.store_wakers(events);
     }
 
+    fn on_early_transmit(&mut self, packet_number: PacketNumber, len: usize) {
+        self.early_transmissions.push((packet_number, len));
+    }
+
     fn update_blocked_sync_period(&mut self, _blocked_sync_period: Duration) {
         self.update_blocked_sync_period_count += 1;
     }
@@ -387,6 +394,13 @@ fn assert_wakeups(wakeup_queue: &mut WakeupQueue<InternalConnectionId>, expected
 }
 
 fn create_stream_manager(local_ep_type: endpoint::Type) -> AbstractStreamManager<MockStream> {
+    create_stream_manager_with_scheduler(local_ep_type, Box::new(scheduler::Urgency::default()))
+}
+
+fn create_stream_manager_with_scheduler(
+    local_ep_type: endpoint::Type,
+    scheduler: Box<dyn scheduler::Scheduler>,
+) -> AbstractStreamManager<MockStream> {
     let initial_local_limits = create_default_initial_flow_control_limits();
     let initial_peer_limits = create_default_initial_flow_control_limits();
 
@@ -402,6 +416,8 @@ fn create_stream_manager(local_ep_type: endpoint::Type) -> AbstractStreamManager
         local_ep_type,
         initial_local_limits,
         initial_peer_limits,
+        send_buffer::Budget::default(),
+        scheduler,
     )
 }
 
@@ -1337,6 +1353,8 @@ fn asymmetric_stream_limits_remote_initiated() {
                     endpoint::Type::Server,
                     initial_local_limits,
                     initial_peer_limits,
+                    send_buffer::Budget::default(),
+                    Box::new(scheduler::Urgency::default()),
                 );
 
                 // The peer opens streams up to the limit we have given them
@@ -1415,6 +1433,8 @@ fn asymmetric_stream_limits_local_initiated() {
                     endpoint::Type::Server,
                     initial_local_limits,
                     initial_peer_limits,
+                    send_buffer::Budget::default(),
+                    Box::new(scheduler::Urgency::default()),
                 );
 
                 // Local endpoint opens streams up to the limit
@@ -2241,6 +2261,134 @@ fn add_and_remove_streams_from_transmission_lists() {
     assert!(manager.streams_waiting_for_transmission().is_empty());
 }
 
+#[test]
+fn transmission_lists_are_ordered_by_urgency() {
+    let mut manager = create_stream_manager(endpoint::Type::Server);
+
+    let stream_1 = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    let stream_2 = try_open(&mut manager, StreamType::Unidirectional).unwrap();
+    let stream_3 = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    let stream_4 = try_open(&mut manager, StreamType::Unidirectional).unwrap();
+
+    for (stream_id, urgency) in [(stream_1, 3), (stream_2, 5), (stream_3, 3), (stream_4, 0)] {
+        manager.with_asserted_stream(stream_id, |stream| {
+            stream.interests.priority = Priority::new(urgency, false);
+            stream.on_transmit_try_write_frames = 1;
+        });
+    }
+
+    assert_eq!(
+        [stream_4, stream_1, stream_3, stream_2],
+        *manager.streams_waiting_for_transmission()
+    );
+
+    // Lowering the urgency moves the stream behind all other streams
+    manager.with_asserted_stream(stream_4, |stream| {
+        stream.interests.priority = Priority::new(Priority::MIN_URGENCY, false);
+    });
+    assert_eq!(
+        [stream_1, stream_3, stream_2, stream_4],
+        *manager.streams_waiting_for_transmission()
+    );
+}
+
+#[test]
+fn incremental_streams_share_transmission_capacity() {
+    for incremental in [false, true] {
+        let mut manager = create_stream_manager(endpoint::Type::Server);
+        let mut frame_buffer = OutgoingFrameBuffer::new();
+
+        let stream_1 = try_open(&mut manager, StreamType::Unidirectional).unwrap();
+        let stream_2 = try_open(&mut manager, StreamType::Unidirectional).unwrap();
+
+        for stream_id in [stream_1, stream_2] {
+            manager.with_asserted_stream(stream_id, |stream| {
+                stream.interests.priority = Priority::new(3, incremental);
+                stream.on_transmit_try_write_frames = 10;
+            });
+        }
+
+        frame_buffer.set_error_write_after_n_frames(5);
+        let mut write_context = MockWriteContext::new(
+            s2n_quic_platform::time::now(),
+            &mut frame_buffer,
+            transmission::Constraint::None,
+            transmission::Mode::Normal,
+            endpoint::Type::Server,
+        );
+
+        assert_eq!(
+            Err(OnTransmitError::CouldNotWriteFrame),
+            manager.on_transmit(&mut write_context)
+        );
+
+        let expected = if incremental {
+            // the interrupted stream yields to the other stream of the same urgency
+            [stream_2, stream_1]
+        } else {
+            // the interrupted stream continues with the next transmission
+            [stream_1, stream_2]
+        };
+        assert_eq!(expected, *manager.streams_waiting_for_transmission());
+    }
+}
+
+/// Transmits streams in the order they became ready, regardless of their priority
+struct Fifo;
+
+impl scheduler::Scheduler for Fifo {
+    fn queue(&self, _priority: Priority) -> usize {
+        0
+    }
+
+    fn on_interrupted(&self, _priority: Priority) -> scheduler::Position {
+        scheduler::Position::Back
+    }
+}
+
+#[test]
+fn transmission_lists_are_ordered_by_scheduler() {
+    let mut manager = create_stream_manager_with_scheduler(endpoint::Type::Server, Box::new(Fifo));
+    let mut frame_buffer = OutgoingFrameBuffer::new();
+
+    let stream_1 = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+    let stream_2 = try_open(&mut manager, StreamType::Unidirectional).unwrap();
+    let stream_3 = try_open(&mut manager, StreamType::Bidirectional).unwrap();
+
+    for (stream_id, urgency) in [(stream_1, 3), (stream_2, 5), (stream_3, 0)] {
+        manager.with_asserted_stream(stream_id, |stream| {
+            stream.interests.priority = Priority::new(urgency, false);
+            stream.on_transmit_try_write_frames = 10;
+        });
+    }
+
+    // the urgency of the streams does not reorder them
+    assert_eq!(
+        [stream_1, stream_2, stream_3],
+        *manager.streams_waiting_for_transmission()
+    );
+
+    frame_buffer.set_error_write_after_n_frames(5);
+    let mut write_context = MockWriteContext::new(
+        s2n_quic_platform::time::now(),
+        &mut frame_buffer,
+        transmission::Constraint::None,
+        transmission::Mode::Normal,
+        endpoint::Type::Server,
+    );
+
+    assert_eq!(
+        Err(OnTransmitError::CouldNotWriteFrame),
+        manager.on_transmit(&mut write_context)
+    );
+
+    // the interrupted non-incremental stream yields to the other streams
+    assert_eq!(
+        [stream_2, stream_3, stream_1],
+        *manager.streams_waiting_for_transmission()
+    );
+}
+
 #[test]
 fn add_and_remove_streams_from_retransmission_lists() {
     let mut manager = create_stream_manager(endpoint::Type::Server);
@@ -3107,3 +3255,43 @@ fn stream_transmission_fairness_test() {
         }
     }
 }
+
+#[test]
+fn early_data_stream_test() {
+    let mut manager = create_stream_manager(endpoint::Type::Client);
+    let (waker, _wake_counter) = new_count_waker();
+
+    let space = PacketNumberSpace::ApplicationData;
+    let transmissions = [
+        (space.new_packet_number(VarInt::from_u8(0)), 6),
+        (space.new_packet_number(VarInt::from_u8(1)), 4),
+    ];
+    manager.on_early_data(
+        Bytes::from_static(b"early data"),
+        &transmissions,
+        &Context::from_waker(&waker),
+    );
+
+    let early_data_stream = StreamId::initial(endpoint::Type::Client, StreamType::Bidirectional);
+    manager.with_asserted_stream(early_data_stream, |stream| {
+        assert_eq!(stream.poll_push_count, 1);
+        // the data sent in 0-RTT packets is tracked in those packets
+        assert_eq!(stream.early_transmissions, transmissions);
+    });
+
+    // unidirectional streams aren't affected
+    assert_eq!(
+        try_open(&mut manager, StreamType::Unidirectional).unwrap(),
+        StreamId::initial(endpoint::Type::Client, StreamType::Unidirectional)
+    );
+
+    // the stream carrying the early data is returned first
+    assert_eq!(
+        try_open(&mut manager, StreamType::Bidirectional).unwrap(),
+        early_data_stream
+    );
+    assert_eq!(
+        try_open(&mut manager, StreamType::Bidirectional).unwrap(),
+        early_data_stream.next_of_type().unwrap()
+    );
+}
@@ -9,6 +9,8 @@ mod incoming_connection_flow_controller;
 mod manager;
 mod outgoing_connection_flow_controller;
 mod receive_stream;
+mod receive_window;
+pub(crate) mod send_buffer;
 mod send_stream;
 mod stream_container;
 mod stream_events;
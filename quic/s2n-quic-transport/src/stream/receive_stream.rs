@@ -5,6 +5,7 @@ use crate::{
     contexts::{OnTransmitError, WriteContext},
     stream::{
         incoming_connection_flow_controller::IncomingConnectionFlowController,
+        receive_window,
         stream_events::StreamEvents,
         stream_interests::{StreamInterestProvider, StreamInterests},
         StreamError,
@@ -183,6 +184,8 @@ pub(super) struct ReceiveStreamFlowController {
     pub(super) acquired_connection_window: VarInt,
     /// The amount of credits which had been released in total
     pub(super) released_connection_window: VarInt,
+    /// Grows the `desired_flow_control_window` based on the rate the application consumes data
+    window_tuner: receive_window::Tuner,
 }
 
 impl ReceiveStreamFlowController {
@@ -191,6 +194,9 @@ impl ReceiveStreamFlowController {
         initial_window: VarInt,
         desired_flow_control_window: u32,
     ) -> Self {
+        let window_tuner =
+            receive_window::Tuner::new(connection_flow_controller.max_stream_flow_control_window());
+
         Self {
             connection_flow_controller,
             read_window_sync: IncrementalValueSync::new(
@@ -201,6 +207,7 @@ impl ReceiveStreamFlowController {
             acquired_connection_window: VarInt::from_u32(0),
             released_connection_window: VarInt::from_u32(0),
             desired_flow_control_window,
+            window_tuner,
         }
     }
 
@@ -292,6 +299,39 @@ impl ReceiveStreamFlowController {
         self.connection_flow_controller.release_window(amount);
     }
 
+    /// Grows the flow control window if the application consumes data fast enough for the
+    /// window to limit the peer's sending rate
+    fn tune_window<W: WriteContext>(&mut self, context: &W) {
+        if self.read_window_sync.is_cancelled() {
+            return;
+        }
+
+        let window = if let Some(window) = self.window_tuner.on_transmit(
+            context.current_time(),
+            self.released_connection_window,
+            self.desired_flow_control_window,
+            self.connection_flow_controller.smoothed_rtt(),
+        ) {
+            window
+        } else {
+            return;
+        };
+
+        self.desired_flow_control_window = window;
+        self.read_window_sync
+            .set_threshold(VarInt::from_u32(window / 10));
+
+        let latest_value = self
+            .released_connection_window
+            .saturating_add(VarInt::from_u32(window))
+            .max(self.read_window_sync.latest_value());
+        self.read_window_sync.update_latest_value(latest_value);
+
+        // make sure the connection window doesn't limit the larger stream window
+        self.connection_flow_controller
+            .on_stream_window_update(window);
+    }
+
     /// Releases all flow credits which had been acquired but not yet released
     /// through previous [`release_window`] calls.
     fn release_outstanding_window(&mut self) {
@@ -713,6 +753,7 @@ impl ReceiveStream {
         //# To avoid blocking a sender, a receiver MAY send a MAX_STREAM_DATA or
         //# MAX_DATA frame multiple times within a round trip or send it early
         //# enough to allow time for loss of the frame and subsequent recovery.
+        self.flow_controller.tune_window(context);
         self.flow_controller
             .read_window_sync
             .on_transmit(stream_id, context)
@@ -13,6 +13,7 @@ use s2n_quic_core::{
     application::Error as ApplicationErrorCode,
     connection, endpoint,
     frame::{Frame, MaxData, MaxStreamData, ResetStream, StopSending},
+    recovery::DEFAULT_INITIAL_RTT,
     stream::{ops, StreamError, StreamType},
     transport::Error as TransportError,
     varint::VarInt,
@@ -775,6 +776,164 @@ fn receiving_data_will_lead_to_a_stream_flow_control_window_update() {
     );
 }
 
+#[test]
+fn stream_flow_control_window_grows_with_consumption_rate() {
+    let mut test_env_config: TestEnvironmentConfig =
+        TestEnvironmentConfig::new(endpoint::Type::Server);
+    test_env_config.stream_id = StreamId::initial(
+        test_env_config.local_endpoint_type.peer_type(),
+        StreamType::Unidirectional,
+    );
+    test_env_config.max_flow_control_window =
+        4 * TestEnvironment::DEFAULT_INITIAL_RECEIVE_WINDOW as u32;
+    let mut test_env = setup_stream_test_env_with_config(test_env_config);
+    let window = TestEnvironment::DEFAULT_INITIAL_RECEIVE_WINDOW;
+
+    // start measuring the consumption rate
+    assert!(test_env.transmit().is_none());
+
+    // consume an entire window within a round trip
+    test_env.feed_data(VarInt::from_u32(0), window as usize);
+    assert_eq!(window as usize, test_env.consume_all_data());
+    test_env.current_time += DEFAULT_INITIAL_RTT;
+
+    // the window doubles and the update includes the larger window
+    let mut sent_frame = test_env.transmit().expect("Frame is written");
+    assert_eq!(
+        Frame::MaxStreamData(MaxStreamData {
+            stream_id: test_env.stream.stream_id.into(),
+            maximum_stream_data: VarInt::new(window + 2 * window).unwrap(),
+        }),
+        sent_frame.as_frame()
+    );
+    assert_eq!(
+        2 * window as u32,
+        test_env
+            .stream
+            .receive_stream
+            .flow_controller
+            .desired_flow_control_window
+    );
+
+    // a slow reader doesn't grow the window any further
+    test_env.feed_data(VarInt::new(window).unwrap(), 1000);
+    assert_eq!(1000, test_env.consume_all_data());
+    test_env.current_time += DEFAULT_INITIAL_RTT;
+    let _ = test_env.transmit();
+    assert_eq!(
+        2 * window as u32,
+        test_env
+            .stream
+            .receive_stream
+            .flow_controller
+            .desired_flow_control_window
+    );
+}
+
+#[test]
+fn connection_flow_control_window_grows_with_consumption_rate() {
+    let mut test_env_config = conn_flow_control_test_env_config();
+    let window = test_env_config.desired_connection_flow_control_window;
+    test_env_config.max_connection_flow_control_window = 4 * window;
+    let mut test_env = setup_stream_test_env_with_config(test_env_config);
+
+    // start measuring the consumption rate
+    test_env.assert_write_frames(0);
+
+    // consume an entire window within a round trip
+    test_env.feed_data(VarInt::from_u32(0), window as usize);
+    assert_eq!(window as usize, test_env.consume_all_data());
+    test_env.current_time += DEFAULT_INITIAL_RTT;
+
+    // the window doubles and the update includes the larger window
+    test_env.assert_write_frames(1);
+    let mut sent_frame = test_env.sent_frames.pop_front().expect("Frame is written");
+    assert_eq!(
+        Frame::MaxData(MaxData {
+            maximum_data: VarInt::from_u32(window + 2 * window),
+        }),
+        sent_frame.as_frame()
+    );
+    assert_eq!(
+        2 * window,
+        test_env
+            .rx_connection_flow_controller
+            .desired_flow_control_window()
+    );
+
+    // the connection window is kept larger than the stream windows
+    test_env
+        .rx_connection_flow_controller
+        .on_stream_window_update(2 * window);
+    assert_eq!(
+        3 * window,
+        test_env
+            .rx_connection_flow_controller
+            .desired_flow_control_window()
+    );
+    assert_eq!(
+        VarInt::from_u32(window + 3 * window),
+        test_env
+            .rx_connection_flow_controller
+            .current_receive_window()
+    );
+
+    // the connection window doesn't grow past the maximum
+    test_env
+        .rx_connection_flow_controller
+        .on_stream_window_update(4 * window);
+    assert_eq!(
+        4 * window,
+        test_env
+            .rx_connection_flow_controller
+            .desired_flow_control_window()
+    );
+
+    // smaller stream windows don't shrink the connection window
+    test_env
+        .rx_connection_flow_controller
+        .on_stream_window_update(window);
+    assert_eq!(
+        4 * window,
+        test_env
+            .rx_connection_flow_controller
+            .desired_flow_control_window()
+    );
+}
+
+#[test]
+fn connection_flow_control_window_is_fixed_without_max_data_window() {
+    let test_env_config = conn_flow_control_test_env_config();
+    let window = test_env_config.desired_connection_flow_control_window;
+    let mut test_env = setup_stream_test_env_with_config(test_env_config);
+
+    test_env.assert_write_frames(0);
+    test_env.feed_data(VarInt::from_u32(0), window as usize);
+    assert_eq!(window as usize, test_env.consume_all_data());
+    test_env.current_time += DEFAULT_INITIAL_RTT;
+
+    // the update only covers the configured window
+    test_env.assert_write_frames(1);
+    let mut sent_frame = test_env.sent_frames.pop_front().expect("Frame is written");
+    assert_eq!(
+        Frame::MaxData(MaxData {
+            maximum_data: VarInt::from_u32(2 * window),
+        }),
+        sent_frame.as_frame()
+    );
+
+    // growing stream windows don't grow the connection window
+    test_env
+        .rx_connection_flow_controller
+        .on_stream_window_update(2 * window);
+    assert_eq!(
+        window,
+        test_env
+            .rx_connection_flow_controller
+            .desired_flow_control_window()
+    );
+}
+
 #[test]
 fn receiving_data_will_lead_to_a_connection_flow_control_window_update() {
     let test_env_config = conn_flow_control_test_env_config();
@@ -0,0 +1,148 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Auto-tunes receive flow control windows
+//!
+//! A peer can send at most one window of data per round trip. If the application consumes
+//! data faster than that, the window limits throughput and is grown, up to a configured maximum.
+//! This allows connections to start with small windows and only use more memory when the
+//! bandwidth-delay product of the path requires it.
+
+use core::time::Duration;
+use s2n_quic_core::{time::Timestamp, varint::VarInt};
+
+#[derive(Debug)]
+pub(super) struct Tuner {
+    /// The largest window the tuner will grow to
+    max_window: u32,
+    /// The start of the current measurement epoch
+    epoch_start: Option<Timestamp>,
+    /// The amount of data consumed at the start of the current measurement epoch
+    epoch_consumed: VarInt,
+}
+
+impl Tuner {
+    pub fn new(max_window: u32) -> Self {
+        Self {
+            max_window,
+            epoch_start: None,
+            epoch_consumed: VarInt::from_u8(0),
+        }
+    }
+
+    /// Returns the largest window the tuner will grow to
+    #[inline]
+    pub fn max_window(&self) -> u32 {
+        self.max_window
+    }
+
+    /// Measures the rate at which the application consumes data
+    ///
+    /// Returns the new window size if the current `window` should grow.
+    pub fn on_transmit(
+        &mut self,
+        now: Timestamp,
+        consumed: VarInt,
+        window: u32,
+        smoothed_rtt: Duration,
+    ) -> Option<u32> {
+        if window >= self.max_window {
+            return None;
+        }
+
+        let epoch_start = if let Some(epoch_start) = self.epoch_start {
+            epoch_start
+        } else {
+            self.start_epoch(now, consumed);
+            return None;
+        };
+
+        // measure the rate over at least a round trip to smooth out bursts
+        let elapsed = now.saturating_duration_since(epoch_start);
+        if elapsed.is_zero() || elapsed < smoothed_rtt {
+            return None;
+        }
+
+        let epoch_consumed = consumed.saturating_sub(self.epoch_consumed).as_u64();
+        self.start_epoch(now, consumed);
+
+        // the amount of data the application consumes in a round trip
+        let consumed_per_rtt =
+            epoch_consumed as u128 * smoothed_rtt.as_nanos() / elapsed.as_nanos();
+
+        // The window is sent to the peer each time a fraction of it is consumed, so the peer
+        // needs to see it a round trip later. Grow the window if it can't cover two round trips
+        // of consumption.
+        if consumed_per_rtt * 2 < window as u128 {
+            return None;
+        }
+
+        Some(window.saturating_mul(2).min(self.max_window))
+    }
+
+    #[inline]
+    fn start_epoch(&mut self, now: Timestamp, consumed: VarInt) {
+        self.epoch_start = Some(now);
+        self.epoch_consumed = consumed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_platform::time;
+
+    const RTT: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn grow_test() {
+        let mut tuner = Tuner::new(4000);
+        let mut now = time::now();
+
+        // the first call starts the epoch
+        assert_eq!(tuner.on_transmit(now, VarInt::from_u32(0), 1000, RTT), None);
+
+        // the rate isn't measured until a round trip has elapsed
+        now += RTT / 2;
+        assert_eq!(
+            tuner.on_transmit(now, VarInt::from_u32(800), 1000, RTT),
+            None
+        );
+
+        // 1000 bytes per round trip is more than half the window
+        now += RTT / 2;
+        assert_eq!(
+            tuner.on_transmit(now, VarInt::from_u32(1000), 1000, RTT),
+            Some(2000)
+        );
+
+        // 1000 bytes per round trip still requires growth
+        now += RTT;
+        assert_eq!(
+            tuner.on_transmit(now, VarInt::from_u32(2000), 2000, RTT),
+            Some(4000)
+        );
+
+        // the window doesn't grow past the maximum
+        now += RTT;
+        assert_eq!(
+            tuner.on_transmit(now, VarInt::from_u32(6000), 4000, RTT),
+            None
+        );
+    }
+
+    #[test]
+    fn slow_consumer_test() {
+        let mut tuner = Tuner::new(4000);
+        let mut now = time::now();
+
+        assert_eq!(tuner.on_transmit(now, VarInt::from_u32(0), 1000, RTT), None);
+
+        // 400 bytes per round trip fits in the window
+        now += RTT * 2;
+        assert_eq!(
+            tuner.on_transmit(now, VarInt::from_u32(800), 1000, RTT),
+            None
+        );
+    }
+}
@@ -0,0 +1,374 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sizes stream send buffers and accounts for their memory usage
+//!
+//! Each connection shares a [`Controller`] between its streams, which can size the send buffers
+//! from the bandwidth-delay product of the active path. All of the connections on an endpoint
+//! share a [`Budget`], which limits the total amount of memory used by send buffers and applies
+//! backpressure to writers once it is exhausted.
+//!
+//! A writer is allowed to push a chunk as long as there is at least 1 byte available, so the
+//! budget can be exceeded by up to one chunk per stream.
+
+use alloc::{rc::Rc, sync::Arc, vec::Vec};
+use core::{
+    cell::Cell,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Waker,
+};
+use std::sync::Mutex;
+
+/// The smallest size of an auto-sized send buffer
+const MIN_CAPACITY: u32 = 16 * 1024;
+
+/// Limits the memory used by the send buffers of all connections on an endpoint
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    state: Option<Arc<BudgetState>>,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    limit: usize,
+    used: AtomicUsize,
+    /// The number of writers waiting for memory to be released
+    ///
+    /// This avoids locking `waiters` on every release when no writers are blocked.
+    waiting: AtomicUsize,
+    waiters: Mutex<Waiters>,
+}
+
+/// Writers waiting for memory to be released
+#[derive(Debug, Default)]
+struct Waiters {
+    wakers: Vec<Waker>,
+    /// Incremented every time the wakers are notified, which invalidates any registrations
+    generation: u64,
+}
+
+/// The position of a writer's waker in the budget's waiters
+#[derive(Clone, Copy, Debug)]
+struct Registration {
+    generation: u64,
+    index: usize,
+}
+
+impl Budget {
+    /// Creates a budget which allows up to `limit` bytes to be buffered
+    pub fn new(limit: usize) -> Self {
+        Self {
+            state: Some(Arc::new(BudgetState {
+                limit,
+                used: AtomicUsize::new(0),
+                waiting: AtomicUsize::new(0),
+                waiters: Mutex::new(Waiters::default()),
+            })),
+        }
+    }
+
+    /// Returns the number of bytes which can be buffered before the budget is exhausted
+    #[inline]
+    pub fn available(&self) -> usize {
+        if let Some(state) = self.state.as_ref() {
+            state
+                .limit
+                .saturating_sub(state.used.load(Ordering::Relaxed))
+        } else {
+            usize::MAX
+        }
+    }
+
+    /// Returns the number of bytes currently buffered
+    #[inline]
+    pub fn used(&self) -> usize {
+        self.state
+            .as_ref()
+            .map_or(0, |state| state.used.load(Ordering::Relaxed))
+    }
+
+    #[inline]
+    fn acquire(&self, len: usize) {
+        if let Some(state) = self.state.as_ref() {
+            state.used.fetch_add(len, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    fn release(&self, len: usize) {
+        let state = if let Some(state) = self.state.as_ref() {
+            state
+        } else {
+            return;
+        };
+
+        let prev = state.used.fetch_sub(len, Ordering::SeqCst);
+        debug_assert!(prev >= len, "released more memory than was acquired");
+
+        if prev.saturating_sub(len) >= state.limit {
+            return;
+        }
+
+        // This is paired with the store in `register`: either the writer observes the released
+        // memory after registering, or this observes the registered writer.
+        if state.waiting.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+
+        // take the waiters out of the lock before waking them
+        let waiters = if let Ok(mut waiters) = state.waiters.lock() {
+            waiters.generation = waiters.generation.wrapping_add(1);
+            state.waiting.store(0, Ordering::SeqCst);
+            core::mem::take(&mut waiters.wakers)
+        } else {
+            return;
+        };
+
+        for waker in waiters {
+            waker.wake();
+        }
+    }
+
+    /// Notifies the `waker` once memory has been released
+    ///
+    /// A writer that registers again before being notified replaces its previous waker, rather
+    /// than being added to the waiters again.
+    fn register(&self, waker: &Waker, registration: &Cell<Option<Registration>>) {
+        let state = if let Some(state) = self.state.as_ref() {
+            state
+        } else {
+            return;
+        };
+
+        if let Ok(mut waiters) = state.waiters.lock() {
+            let generation = waiters.generation;
+
+            match registration.get() {
+                Some(prev) if prev.generation == generation => {
+                    let prev = &mut waiters.wakers[prev.index];
+                    if !prev.will_wake(waker) {
+                        *prev = waker.clone();
+                    }
+                }
+                _ => {
+                    let index = waiters.wakers.len();
+                    waiters.wakers.push(waker.clone());
+                    registration.set(Some(Registration { generation, index }));
+                    state.waiting.store(waiters.wakers.len(), Ordering::SeqCst);
+                }
+            }
+        }
+
+        // memory may have been released before the waker was registered
+        if state.used.load(Ordering::SeqCst) < state.limit {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+/// Sizes the send buffers of the streams in a connection
+#[derive(Clone, Debug)]
+pub struct Controller {
+    budget: Budget,
+    /// The largest size of a send buffer
+    max_capacity: u32,
+    /// The current size of each send buffer, if auto-sizing is enabled
+    capacity: Option<Rc<Cell<u32>>>,
+}
+
+impl Controller {
+    pub fn new(budget: Budget, max_capacity: u32, auto_size: bool) -> Self {
+        let capacity = if auto_size {
+            Some(Rc::new(Cell::new(MIN_CAPACITY.min(max_capacity))))
+        } else {
+            None
+        };
+
+        Self {
+            budget,
+            max_capacity,
+            capacity,
+        }
+    }
+
+    /// Resizes the send buffers from the congestion window of the active path
+    ///
+    /// The congestion window is the amount of data the path can deliver in a round trip. Sent
+    /// data stays in the buffer until it is acknowledged, so the buffer holds two windows to
+    /// keep the next round trip of data ready while the current one is in flight.
+    #[inline]
+    pub fn on_congestion_window_update(&mut self, congestion_window: u32) {
+        if let Some(capacity) = self.capacity.as_ref() {
+            let target = congestion_window
+                .saturating_mul(2)
+                .clamp(MIN_CAPACITY.min(self.max_capacity), self.max_capacity);
+            capacity.set(target);
+        }
+    }
+
+    /// Returns the current size of each send buffer
+    #[inline]
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+            .as_ref()
+            .map_or(self.max_capacity, |capacity| capacity.get())
+    }
+}
+
+/// Tracks the memory a single stream has reserved from the endpoint budget
+#[derive(Debug)]
+pub struct Reservation {
+    controller: Controller,
+    len: usize,
+    /// The waker registered with the endpoint budget, if any
+    registration: Cell<Option<Registration>>,
+}
+
+impl Reservation {
+    pub fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            len: 0,
+            registration: Cell::new(None),
+        }
+    }
+
+    /// Updates the reservation to the current length of the send buffer
+    #[inline]
+    pub fn update(&mut self, len: usize) {
+        if len > self.len {
+            self.controller.budget.acquire(len - self.len);
+        } else if len < self.len {
+            self.controller.budget.release(self.len - len);
+        }
+        self.len = len;
+    }
+
+    /// Returns the amount of data the stream may additionally buffer
+    #[inline]
+    pub fn available(&self) -> usize {
+        let capacity = self.controller.capacity() as usize;
+        capacity
+            .saturating_sub(self.len)
+            .min(self.controller.budget.available())
+    }
+
+    /// Returns `true` if the endpoint budget is exhausted
+    #[inline]
+    pub fn is_budget_exhausted(&self) -> bool {
+        self.controller.budget.available() == 0
+    }
+
+    /// Notifies the `waker` once memory has been released to the endpoint budget
+    #[inline]
+    pub fn register(&self, waker: &Waker) {
+        self.controller.budget.register(waker, &self.registration)
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        self.update(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_test::task::new_count_waker;
+
+    #[test]
+    fn budget_test() {
+        let budget = Budget::new(1000);
+        let controller = Controller::new(budget.clone(), 800, false);
+        let mut a = Reservation::new(controller.clone());
+        let mut b = Reservation::new(controller);
+
+        assert_eq!(a.available(), 800);
+
+        a.update(600);
+        assert_eq!(budget.used(), 600);
+        assert_eq!(a.available(), 200);
+        assert_eq!(b.available(), 400);
+
+        b.update(400);
+        assert!(a.is_budget_exhausted());
+        assert_eq!(a.available(), 0);
+
+        // writers are notified once memory is released
+        let (waker, wake_count) = new_count_waker();
+        a.register(&waker);
+        assert_eq!(wake_count, 0);
+        b.update(100);
+        assert_eq!(wake_count, 1);
+        assert_eq!(a.available(), 200);
+
+        // dropping a reservation releases its memory
+        drop(b);
+        assert_eq!(budget.used(), 600);
+        drop(a);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[test]
+    fn budget_register_test() {
+        let budget = Budget::new(100);
+        let waiting = || {
+            budget
+                .state
+                .as_ref()
+                .unwrap()
+                .waiting
+                .load(Ordering::SeqCst)
+        };
+        let controller = Controller::new(budget.clone(), 100, false);
+        let mut a = Reservation::new(controller.clone());
+        let b = Reservation::new(controller);
+        a.update(100);
+
+        // registering the same writer again doesn't add another waiter
+        let (waker, wake_count) = new_count_waker();
+        for _ in 0..3 {
+            b.register(&waker);
+        }
+        assert_eq!(waiting(), 1);
+
+        // the waker is replaced if the writer is polled from another task
+        let (other_waker, other_wake_count) = new_count_waker();
+        b.register(&other_waker);
+        assert_eq!(waiting(), 1);
+
+        a.update(50);
+        assert_eq!(wake_count, 0);
+        assert_eq!(other_wake_count, 1);
+        assert_eq!(waiting(), 0);
+
+        // the writer can register again once it has been notified
+        a.update(100);
+        b.register(&waker);
+        assert_eq!(waiting(), 1);
+        a.update(0);
+        assert_eq!(wake_count, 1);
+        assert_eq!(other_wake_count, 1);
+    }
+
+    #[test]
+    fn auto_size_test() {
+        let mut controller = Controller::new(Budget::default(), 1_000_000, true);
+        assert_eq!(controller.capacity(), MIN_CAPACITY);
+
+        controller.on_congestion_window_update(100_000);
+        assert_eq!(controller.capacity(), 200_000);
+
+        // the capacity doesn't exceed the configured maximum
+        controller.on_congestion_window_update(1_000_000);
+        assert_eq!(controller.capacity(), 1_000_000);
+
+        // the capacity shrinks with the congestion window
+        controller.on_congestion_window_update(1_000);
+        assert_eq!(controller.capacity(), MIN_CAPACITY);
+
+        let controller = Controller::new(Budget::default(), 1_000_000, false);
+        assert_eq!(controller.capacity(), 1_000_000);
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     contexts::{OnTransmitError, WriteContext},
     stream::{
         outgoing_connection_flow_controller::OutgoingConnectionFlowController,
+        send_buffer,
         stream_events::StreamEvents,
         stream_interests::{StreamInterestProvider, StreamInterests},
         StreamError,
@@ -26,7 +27,7 @@ use s2n_quic_core::{
     ack, application,
     frame::{MaxStreamData, ResetStream, StopSending, StreamDataBlocked},
     packet::number::PacketNumber,
-    stream::{ops, StreamId},
+    stream::{ops, Priority, StreamId},
     time::{timer, Timestamp},
     transport,
     varint::VarInt,
@@ -297,6 +298,11 @@ impl StreamFlowController {
             .update_sync_period(blocked_sync_period);
     }
 
+    /// Records data the client sent in a 0-RTT packet before the stream was opened
+    pub fn on_early_transmit(&mut self, packet_number: PacketNumber, len: usize) {
+        self.data_sender.on_early_transmit(packet_number, len);
+    }
+
     /// Queries the component for any outgoing frames that need to get sent
     pub fn on_transmit<W: WriteContext>(
         &mut self,
@@ -457,6 +463,10 @@ pub struct SendStream {
     final_state_observed: bool,
     /// Marks the stream as detached from the application
     detached: bool,
+    /// The priority with which the stream is scheduled for transmission
+    priority: Priority,
+    /// The memory reserved for the send buffer from the endpoint budget
+    send_buffer: send_buffer::Reservation,
 }
 
 impl SendStream {
@@ -465,6 +475,7 @@ impl SendStream {
         is_closed: bool,
         initial_window: VarInt,
         max_buffer_capacity: u32,
+        send_buffer: send_buffer::Controller,
     ) -> SendStream {
         // If the stream is created in closed state directly move into the
         // terminal state.
@@ -485,6 +496,8 @@ impl SendStream {
             write_waiter: None,
             final_state_observed: is_closed,
             detached: is_closed,
+            priority: Priority::default(),
+            send_buffer: send_buffer::Reservation::new(send_buffer),
         };
 
         if is_closed {
@@ -580,6 +593,7 @@ impl SendStream {
     /// This method gets called when a packet delivery got acknowledged
     pub fn on_packet_ack<A: ack::Set>(&mut self, ack_set: &A, events: &mut StreamEvents) {
         self.data_sender.on_packet_ack(ack_set);
+        self.send_buffer.update(self.data_sender.buffered_len());
         self.data_sender
             .flow_controller_mut()
             .on_packet_ack(ack_set);
@@ -755,6 +769,10 @@ impl SendStream {
             self.detach();
         }
 
+        if let Some(priority) = request.priority {
+            self.priority = priority;
+        }
+
         macro_rules! store_waker {
             ($should_flush:expr) => {
                 // Store the waker, in order to be able to wakeup the caller
@@ -824,6 +842,7 @@ impl SendStream {
 
                 if !self.can_push() {
                     store_waker!(false);
+                    self.register_budget_waker(context);
 
                     // no more progress can be made on the operation
                     return Ok(response);
@@ -834,6 +853,7 @@ impl SendStream {
 
                 self.data_sender
                     .push(core::mem::replace(chunk, Bytes::new()));
+                self.send_buffer.update(self.data_sender.buffered_len());
             }
         } else if !request.finish && !request.flush && context.is_some() {
             // if `chunks` are `None` or `Some(&[])` and we're not ending or flushing the stream,
@@ -845,6 +865,7 @@ impl SendStream {
             // store the waker if we currently can't push
             if !self.can_push() {
                 store_waker!(false);
+                self.register_budget_waker(context);
 
                 return Ok(response);
             }
@@ -884,7 +905,7 @@ impl SendStream {
         match self.data_sender.state() {
             data_sender::State::Sending => {
                 // inform the caller of the available space to send
-                response.bytes.available = self.data_sender.available_buffer_space();
+                response.bytes.available = self.available_buffer_space();
                 // assume chunks are 1 bytes
                 response.chunks.available = response.bytes.available;
             }
@@ -925,7 +946,29 @@ impl SendStream {
     fn can_push(&self) -> bool {
         // We accept the data if there is at least 1 byte of space
         // available in the flow control window.
-        self.data_sender.available_buffer_space() > 0
+        self.available_buffer_space() > 0
+    }
+
+    /// Returns the amount of data the caller can push
+    ///
+    /// This is limited by the size of the send buffer, as well as the memory remaining in the
+    /// endpoint's send buffer budget.
+    fn available_buffer_space(&self) -> usize {
+        self.data_sender
+            .available_buffer_space()
+            .min(self.send_buffer.available())
+    }
+
+    /// Wakes the caller once the endpoint's send buffer budget has memory available
+    ///
+    /// The budget is shared with other connections, so this stream may not be notified by its own
+    /// acknowledgements.
+    fn register_budget_waker(&self, context: Option<&Context>) {
+        if let Some(context) = context {
+            if self.send_buffer.is_budget_exhausted() {
+                self.send_buffer.register(context.waker());
+            }
+        }
     }
 
     /// Ensures a potential push operation would be valid
@@ -984,6 +1027,7 @@ impl SendStream {
         // Clear the send buffer. Since we initiated a RESET, there is no need
         // to send or resend the remaining data.
         self.data_sender.stop_sending(error);
+        self.send_buffer.update(self.data_sender.buffered_len());
 
         // For an internal reset (which provides no error_code) we do not need
         // to transmit the reset frame
@@ -1028,6 +1072,8 @@ impl timer::Provider for SendStream {
 impl StreamInterestProvider for SendStream {
     #[inline]
     fn stream_interests(&self, interests: &mut StreamInterests) {
+        interests.priority = self.priority;
+
         match self.state {
             //= https://www.rfc-editor.org/rfc/rfc9000#section-3.3
             //# A sender MUST NOT send any of these frames from a terminal state
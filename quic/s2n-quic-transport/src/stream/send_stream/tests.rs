@@ -2802,6 +2802,28 @@ fn detach_modes() {
     }
 }
 
+#[test]
+fn set_priority() {
+    let test_env_config = TestEnvironmentConfig {
+        stream_id: StreamId::initial(endpoint::Type::Client, StreamType::Unidirectional),
+        local_endpoint_type: endpoint::Type::Client,
+        ..Default::default()
+    };
+    let mut test_env = setup_stream_test_env_with_config(test_env_config);
+
+    assert_eq!(
+        Priority::default(),
+        test_env.stream.get_stream_interests().priority
+    );
+
+    let priority = Priority::new(0, true);
+    test_env
+        .run_request(ops::Request::default().set_priority(priority), false)
+        .expect("request should succeed");
+
+    assert_eq!(priority, test_env.stream.get_stream_interests().priority);
+}
+
 #[test]
 fn can_query_stream_readiness() {
     let max_send_buffer_size = 1500;
@@ -2854,3 +2876,40 @@ fn can_query_stream_readiness() {
         }
     }
 }
+
+#[test]
+fn send_buffer_budget_blocks_writers_across_connections() {
+    let budget = send_buffer::Budget::new(2000);
+    let test_env_config = TestEnvironmentConfig {
+        stream_id: StreamId::initial(endpoint::Type::Server, StreamType::Unidirectional),
+        ..Default::default()
+    };
+    // each test environment has its own connection flow controllers
+    let mut first_env = setup_stream_test_env_with_budget(test_env_config, budget.clone());
+    let mut second_env = setup_stream_test_env_with_budget(test_env_config, budget.clone());
+
+    assert_eq!(
+        first_env.poll_push(Bytes::from(vec![0u8; 2000])),
+        Poll::Ready(Ok(()))
+    );
+    assert_eq!(budget.used(), 2000);
+
+    // the budget is exhausted so the other connection can't write
+    assert_eq!(
+        second_env.poll_push(Bytes::from_static(b"1")),
+        Poll::Pending
+    );
+    assert_eq!(second_env.wake_counter, 0);
+
+    // acknowledging the data on the first connection releases its memory
+    first_env.assert_write_frames(1);
+    first_env.ack_packet(pn(0), ExpectWakeup(Some(false)));
+    assert_eq!(budget.used(), 0);
+    assert_eq!(second_env.wake_counter, 1);
+
+    assert_eq!(
+        second_env.poll_push(Bytes::from_static(b"1")),
+        Poll::Ready(Ok(()))
+    );
+    assert_eq!(budget.used(), 1);
+}
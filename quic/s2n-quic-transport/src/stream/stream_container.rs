@@ -13,11 +13,20 @@ use crate::{
     transmission,
 };
 use alloc::rc::Rc;
-use core::{cell::RefCell, ops::Deref};
+use core::{
+    cell::{Cell, RefCell},
+    ops::Deref,
+};
 use intrusive_collections::{
     intrusive_adapter, KeyAdapter, LinkedList, LinkedListLink, RBTree, RBTreeLink,
 };
-use s2n_quic_core::{stream::StreamId, time::timer};
+use s2n_quic_core::{
+    stream::{
+        scheduler::{Position, QUEUE_COUNT},
+        Scheduler, StreamId,
+    },
+    time::timer,
+};
 
 // Intrusive list adapter for managing the list of `done` streams
 intrusive_adapter!(DoneStreamsAdapter<S> = Rc<StreamNode<S>>: StreamNode<S> {
@@ -73,6 +82,8 @@ struct StreamNode<S> {
     waiting_for_frame_delivery_link: LinkedListLink,
     /// Allows the Stream to be part of the `waiting_for_transmission` collection
     waiting_for_transmission_link: LinkedListLink,
+    /// The index of the `waiting_for_transmission` queue the Stream is part of
+    transmission_queue: Cell<usize>,
     /// Allows the Stream to be part of the `waiting_for_transmission` collection
     waiting_for_retransmission_link: LinkedListLink,
     /// Allows the Stream to be part of the `waiting_for_connection_flow_control_credits` collection
@@ -90,6 +101,7 @@ impl<S> StreamNode<S> {
             done_streams_link: LinkedListLink::new(),
             waiting_for_frame_delivery_link: LinkedListLink::new(),
             waiting_for_transmission_link: LinkedListLink::new(),
+            transmission_queue: Cell::new(0),
             waiting_for_retransmission_link: LinkedListLink::new(),
             waiting_for_connection_flow_control_credits_link: LinkedListLink::new(),
             waiting_for_stream_flow_control_credits_link: LinkedListLink::new(),
@@ -133,8 +145,10 @@ struct InterestLists<S> {
     /// Streams which are waiting for packet acknowledgements and
     /// packet loss notifications
     waiting_for_frame_delivery: LinkedList<WaitingForFrameDeliveryAdapter<S>>,
-    /// Streams which need to transmit data
-    waiting_for_transmission: LinkedList<WaitingForTransmissionAdapter<S>>,
+    /// Streams which need to transmit data, indexed by their queue
+    waiting_for_transmission: [LinkedList<WaitingForTransmissionAdapter<S>>; QUEUE_COUNT],
+    /// Decides in which queue and position Streams wait for transmission
+    scheduler: Box<dyn Scheduler>,
     /// Streams which need to transmit data
     waiting_for_retransmission: LinkedList<WaitingForRetransmissionAdapter<S>>,
     /// Streams which are blocked on transmission due to waiting on the
@@ -148,11 +162,13 @@ struct InterestLists<S> {
 }
 
 impl<S: StreamTrait> InterestLists<S> {
-    fn new() -> Self {
+    fn new(scheduler: Box<dyn Scheduler>) -> Self {
         Self {
             done_streams: LinkedList::new(DoneStreamsAdapter::new()),
             waiting_for_frame_delivery: LinkedList::new(WaitingForFrameDeliveryAdapter::new()),
-            waiting_for_transmission: LinkedList::new(WaitingForTransmissionAdapter::new()),
+            waiting_for_transmission: [(); QUEUE_COUNT]
+                .map(|_| LinkedList::new(WaitingForTransmissionAdapter::new())),
+            scheduler,
             waiting_for_retransmission: LinkedList::new(WaitingForRetransmissionAdapter::new()),
             waiting_for_connection_flow_control_credits: LinkedList::new(
                 WaitingForConnectionFlowControlCreditsAdapter::new(),
@@ -206,11 +222,7 @@ impl<S: StreamTrait> InterestLists<S> {
             waiting_for_frame_delivery_link,
             waiting_for_frame_delivery
         );
-        sync_interests!(
-            matches!(interests.transmission, transmission::Interest::NewData),
-            waiting_for_transmission_link,
-            waiting_for_transmission
-        );
+        self.sync_transmission_interest(node, interests, result);
         sync_interests!(
             matches!(interests.transmission, transmission::Interest::LostData),
             waiting_for_retransmission_link,
@@ -238,6 +250,66 @@ impl<S: StreamTrait> InterestLists<S> {
             false
         }
     }
+
+    /// Returns `true` if any Stream is waiting for transmission
+    fn has_transmission_interest(&self) -> bool {
+        self.waiting_for_transmission
+            .iter()
+            .any(|list| !list.is_empty())
+    }
+
+    /// Updates the membership of a Node in the `waiting_for_transmission` lists
+    ///
+    /// The scheduler selects the queue of the Node based on its priority, as
+    /// well as the position of Nodes which were interrupted while transmitting.
+    /// Nodes which were not interrupted are always placed at the back.
+    fn sync_transmission_interest(
+        &mut self,
+        node: &Rc<StreamNode<S>>,
+        interests: StreamInterests,
+        result: StreamContainerIterationResult,
+    ) {
+        let interest = matches!(interests.transmission, transmission::Interest::NewData);
+        let queue = self
+            .scheduler
+            .queue(interests.priority)
+            .min(QUEUE_COUNT - 1);
+
+        if node.waiting_for_transmission_link.is_linked() {
+            let current_queue = node.transmission_queue.get();
+
+            if interest && current_queue == queue {
+                return;
+            }
+
+            // Safety: We know that the node is only ever part of the queue
+            // which was recorded when it was inserted.
+            let mut cursor = unsafe {
+                self.waiting_for_transmission[current_queue]
+                    .cursor_mut_from_ptr(node.deref() as *const StreamNode<S>)
+            };
+            cursor.remove();
+        }
+
+        if interest {
+            node.transmission_queue.set(queue);
+
+            let position = match result {
+                StreamContainerIterationResult::Continue => Position::Back,
+                StreamContainerIterationResult::BreakAndInsertAtBack => {
+                    self.scheduler.on_interrupted(interests.priority)
+                }
+            };
+
+            let list = &mut self.waiting_for_transmission[queue];
+            match position {
+                Position::Front => list.push_front(node.clone()),
+                Position::Back => list.push_back(node.clone()),
+            }
+        }
+
+        debug_assert_eq!(interest, node.waiting_for_transmission_link.is_linked());
+    }
 }
 
 /// A collection of all intrusive lists Streams are part of.
@@ -330,12 +402,13 @@ macro_rules! iterate_interruptible {
 }
 
 impl<S: StreamTrait> StreamContainer<S> {
-    /// Creates a new `StreamContainer`
-    pub fn new() -> Self {
+    /// Creates a new `StreamContainer`, which orders the transmission of
+    /// `Stream`s with the given scheduler
+    pub fn new(scheduler: Box<dyn Scheduler>) -> Self {
         Self {
             stream_map: RBTree::new(StreamTreeAdapter::new()),
             nr_active_streams: 0,
-            interest_lists: InterestLists::new(),
+            interest_lists: InterestLists::new(scheduler),
         }
     }
 
@@ -459,7 +532,16 @@ impl<S: StreamTrait> StreamContainer<S> {
             }
 
             remove_stream_from_list!(waiting_for_frame_delivery, waiting_for_frame_delivery_link);
-            remove_stream_from_list!(waiting_for_transmission, waiting_for_transmission_link);
+            if stream.waiting_for_transmission_link.is_linked() {
+                let queue = stream.transmission_queue.get();
+                // Safety: The Stream is part of the list of its recorded queue
+                let mut cursor = unsafe {
+                    self.interest_lists.waiting_for_transmission[queue]
+                        .cursor_mut_from_ptr(stream_ptr)
+                };
+                let remove_result = cursor.remove();
+                debug_assert!(remove_result.is_some());
+            }
             remove_stream_from_list!(waiting_for_retransmission, waiting_for_retransmission_link);
             remove_stream_from_list!(
                 waiting_for_connection_flow_control_credits,
@@ -540,19 +622,65 @@ impl<S: StreamTrait> StreamContainer<S> {
     /// Iterates over all `Stream`s which are waiting for transmission,
     /// and executes the given function on each `Stream`
     ///
+    /// `Stream`s are visited in the order of their queues, as selected by the
+    /// scheduler. The iteration is stopped as soon as `func` returns
+    /// `BreakAndInsertAtBack`, which leaves all `Stream`s in later queues
+    /// waiting for the next transmission.
+    ///
     /// The `stream::Controller` will be notified of streams that have been
     /// closed to allow for further streams to be opened.
     pub fn iterate_transmission_list<F>(&mut self, controller: &mut stream::Controller, mut func: F)
     where
         F: FnMut(&mut S) -> StreamContainerIterationResult,
     {
-        iterate_interruptible!(
-            self,
-            waiting_for_transmission,
-            waiting_for_transmission_link,
-            controller,
-            func
-        );
+        'queues: for queue in 0..QUEUE_COUNT {
+            let mut extracted_list = self.interest_lists.waiting_for_transmission[queue].take();
+            let mut cursor = extracted_list.front_mut();
+
+            while let Some(stream) = cursor.remove() {
+                // Note that while we iterate over the intrusive lists here
+                // `stream` is part of no list anymore, since it also got dropped
+                // from list that is described by the `cursor`.
+                debug_assert!(!stream.waiting_for_transmission_link.is_linked());
+                let mut mut_stream = stream.inner.borrow_mut();
+                let result = func(&mut *mut_stream);
+
+                // Update the interests after the interaction
+                let interests = mut_stream.get_stream_interests();
+                self.interest_lists
+                    .update_interests(&stream, interests, result);
+
+                match result {
+                    StreamContainerIterationResult::BreakAndInsertAtBack => {
+                        let is_front = stream.waiting_for_transmission_link.is_linked()
+                            && stream.transmission_queue.get() == queue
+                            && matches!(
+                                self.interest_lists
+                                    .scheduler
+                                    .on_interrupted(interests.priority),
+                                Position::Front
+                            );
+                        let list = &mut self.interest_lists.waiting_for_transmission[queue];
+
+                        if is_front {
+                            // A stream placed at the front continues with the
+                            // next transmission, followed by the remaining streams
+                            list.front_mut().splice_after(extracted_list);
+                        } else {
+                            // The remaining streams are transmitted before the
+                            // streams which were already visited
+                            list.front_mut().splice_before(extracted_list);
+                        }
+                        break 'queues;
+                    }
+                    StreamContainerIterationResult::Continue => {}
+                }
+            }
+        }
+
+        if !self.interest_lists.done_streams.is_empty() {
+            self.finalize_done_streams(controller);
+        }
     }
 
     /// Iterates over all `Stream`s which are waiting for retransmission,
@@ -617,7 +745,7 @@ impl<S: StreamTrait> StreamContainer<S> {
 
     /// Returns whether or not streams have data to send
     pub fn has_pending_streams(&self) -> bool {
-        !self.interest_lists.waiting_for_transmission.is_empty()
+        self.interest_lists.has_transmission_interest()
             || !self.interest_lists.waiting_for_retransmission.is_empty()
     }
 }
@@ -645,7 +773,7 @@ impl<S: StreamTrait> transmission::interest::Provider for StreamContainer<S> {
     ) -> transmission::interest::Result {
         if !self.interest_lists.waiting_for_retransmission.is_empty() {
             query.on_lost_data()?;
-        } else if !self.interest_lists.waiting_for_transmission.is_empty() {
+        } else if self.interest_lists.has_transmission_interest() {
             query.on_new_data()?;
         }
 
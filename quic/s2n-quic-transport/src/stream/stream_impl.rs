@@ -7,6 +7,7 @@ use crate::{
         incoming_connection_flow_controller::IncomingConnectionFlowController,
         outgoing_connection_flow_controller::OutgoingConnectionFlowController,
         receive_stream::ReceiveStream,
+        send_buffer,
         send_stream::SendStream,
         stream_events::StreamEvents,
         stream_interests::{StreamInterestProvider, StreamInterests},
@@ -17,6 +18,7 @@ use core::{task::Context, time::Duration};
 use s2n_quic_core::{
     ack, endpoint,
     frame::{stream::StreamRef, MaxStreamData, ResetStream, StopSending, StreamDataBlocked},
+    packet::number::PacketNumber,
     stream::{ops, StreamId},
     time::{timer, Timestamp},
     transport,
@@ -42,6 +44,8 @@ pub struct StreamConfig {
     pub initial_send_window: VarInt,
     /// The maximum buffered amount of data on the sending side
     pub max_send_buffer_size: u32,
+    /// Sizes the send buffer and accounts for its memory usage
+    pub send_buffer: send_buffer::Controller,
 }
 
 /// A trait which represents an internally used `Stream`
@@ -100,6 +104,10 @@ pub trait StreamTrait: StreamInterestProvider + timer::Provider + core::fmt::Deb
     /// This method gets called when a packet loss is reported
     fn on_packet_loss<A: ack::Set>(&mut self, ack_set: &A, events: &mut StreamEvents);
 
+    /// This method gets called with the data a client sent in a 0-RTT packet before the
+    /// stream was opened
+    fn on_early_transmit(&mut self, packet_number: PacketNumber, len: usize);
+
     /// Updates the period at which `STREAM_DATA_BLOCKED` frames are sent to the peer
     /// if the application is blocked by peer limits.
     fn update_blocked_sync_period(&mut self, blocked_sync_period: Duration);
@@ -211,6 +219,7 @@ impl StreamTrait for StreamImpl {
                 send_is_closed,
                 config.initial_send_window,
                 config.max_send_buffer_size,
+                config.send_buffer,
             ),
         }
     }
@@ -288,6 +297,11 @@ impl StreamTrait for StreamImpl {
         self.send_stream.on_packet_loss(ack_set);
     }
 
+    #[inline]
+    fn on_early_transmit(&mut self, packet_number: PacketNumber, len: usize) {
+        self.send_stream.on_early_transmit(packet_number, len);
+    }
+
     #[inline]
     fn update_blocked_sync_period(&mut self, blocked_sync_period: Duration) {
         self.send_stream
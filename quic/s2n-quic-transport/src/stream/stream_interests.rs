@@ -4,6 +4,7 @@
 //! A collection of a all the interactions a `Stream` is interested in
 
 use crate::transmission::interest::{Interest, Query, QueryBreak, Result};
+use s2n_quic_core::stream::Priority;
 
 /// A collection of a all the interactions a `Stream` is interested in
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
@@ -22,6 +23,8 @@ pub struct StreamInterests {
     pub delivery_notifications: bool,
     /// Transmission interest for the component
     pub transmission: Interest,
+    /// The priority with which the `Stream` is scheduled for transmission
+    pub priority: Priority,
 }
 
 impl StreamInterests {
@@ -32,6 +35,8 @@ impl StreamInterests {
         self.retained |= other.retained;
         self.delivery_notifications |= other.delivery_notifications;
         let _ = self.transmission.on_interest(other.transmission);
+        // The priority is not merged, since it is only provided by the sending
+        // side of the `Stream`
     }
 
     #[inline]
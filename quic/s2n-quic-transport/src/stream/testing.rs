@@ -5,7 +5,7 @@ pub use crate::contexts::testing::*;
 use crate::{
     stream::{
         incoming_connection_flow_controller::IncomingConnectionFlowController,
-        outgoing_connection_flow_controller::OutgoingConnectionFlowController,
+        outgoing_connection_flow_controller::OutgoingConnectionFlowController, send_buffer,
         stream_impl::StreamConfig, stream_interests::StreamInterests, StreamEvents, StreamImpl,
         StreamTrait,
     },
@@ -483,6 +483,8 @@ pub struct TestEnvironmentConfig {
     pub initial_connection_send_window_size: u64,
     pub initial_connection_receive_window_size: u64,
     pub desired_connection_flow_control_window: u32,
+    pub max_connection_flow_control_window: u32,
+    pub max_flow_control_window: u32,
     pub max_send_buffer_size: usize,
     pub transmission_constraint: transmission::Constraint,
     pub local_endpoint_type: endpoint::Type,
@@ -512,6 +514,9 @@ impl TestEnvironmentConfig {
                 TestEnvironment::DEFAULT_INITIAL_CONNECTION_RECEIVE_WINDOW,
             desired_connection_flow_control_window:
                 TestEnvironment::DEFAULT_INITIAL_CONNECTION_RECEIVE_WINDOW as u32,
+            // auto-tuning is disabled by default
+            max_connection_flow_control_window: 0,
+            max_flow_control_window: 0,
             max_send_buffer_size: TestEnvironment::DEFAULT_MAX_SEND_BUFFER_SIZE,
             transmission_constraint: transmission::Constraint::None,
             max_packet_size: None,
@@ -527,9 +532,20 @@ pub fn setup_stream_test_env() -> TestEnvironment {
 
 /// Sets up a test environment for Stream testing with custom parameters
 pub fn setup_stream_test_env_with_config(config: TestEnvironmentConfig) -> TestEnvironment {
+    setup_stream_test_env_with_budget(config, send_buffer::Budget::default())
+}
+
+/// Sets up a test environment for Stream testing with a send buffer budget, which can be shared
+/// with other test environments
+pub fn setup_stream_test_env_with_budget(
+    config: TestEnvironmentConfig,
+    send_buffer_budget: send_buffer::Budget,
+) -> TestEnvironment {
     let rx_connection_flow_controller = IncomingConnectionFlowController::new(
         VarInt::new(config.initial_connection_receive_window_size).unwrap(),
         config.desired_connection_flow_control_window,
+        config.max_connection_flow_control_window,
+        config.max_flow_control_window,
     );
 
     let tx_connection_flow_controller = OutgoingConnectionFlowController::new(
@@ -545,6 +561,11 @@ pub fn setup_stream_test_env_with_config(config: TestEnvironmentConfig) -> TestE
         desired_flow_control_window: config.desired_flow_control_window,
         initial_send_window: VarInt::new(config.initial_send_window).unwrap(),
         max_send_buffer_size: config.max_send_buffer_size as u32,
+        send_buffer: send_buffer::Controller::new(
+            send_buffer_budget,
+            config.max_send_buffer_size as u32,
+            false,
+        ),
     });
 
     let (waker, wake_counter) = new_count_waker();
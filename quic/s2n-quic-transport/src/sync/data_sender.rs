@@ -196,6 +196,11 @@ impl<FlowController: OutgoingDataFlowController, Writer: FrameWriter>
         self.buffer.total_len()
     }
 
+    /// Returns the amount of data currently held in the buffer
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.enqueued_len().try_into().unwrap_or(usize::MAX)
+    }
+
     /// Returns true if the data sender doesn't have any data enqueued for sending
     pub fn is_empty(&self) -> bool {
         self.buffer.is_empty()
@@ -268,6 +273,29 @@ impl<FlowController: OutgoingDataFlowController, Writer: FrameWriter>
         self.check_integrity();
     }
 
+    /// Records enqueued data which was already transmitted outside of the sender
+    ///
+    /// This is used for the data a client sent in 0-RTT packets before the stream was opened.
+    /// The next `len` bytes waiting for their first transmission are tracked as in flight in
+    /// the packet, so they are only transmitted again if the packet is declared lost.
+    pub fn on_early_transmit(&mut self, packet_number: PacketNumber, len: usize) {
+        let start = self.transmission_offset;
+        let end = (start + len).min(self.buffer.total_len());
+
+        if end <= start {
+            return;
+        }
+
+        // the data was sent within the window the peer remembered from the previous connection
+        let _ = self.flow_controller_mut().acquire_flow_control_window(end);
+
+        self.transmissions
+            .insert(packet_number, (start..end).into());
+        self.transmission_offset = end;
+
+        self.check_integrity();
+    }
+
     /// Starts the finalization process of a `Stream` by enqueuing a `FIN` frame.
     pub fn finish(&mut self) {
         if self.state != State::Sending {
@@ -749,6 +777,55 @@ mod tests {
         frame_buffer
     }
 
+    #[test]
+    fn early_transmit_test() {
+        let mut sender: DataSender<_, writer::Stream> = DataSender::new(
+            TestFlowController {
+                max_offset: VarInt::MAX,
+                is_blocked: false,
+            },
+            u32::MAX,
+        );
+        let mut frame_buffer = OutgoingFrameBuffer::new();
+        let mut context = MockWriteContext {
+            current_time: s2n_quic_platform::time::now(),
+            frame_buffer: &mut frame_buffer,
+            transmission_constraint: transmission::Constraint::None,
+            transmission_mode: transmission::Mode::Normal,
+            endpoint: endpoint::Type::Client,
+        };
+        let space = s2n_quic_core::packet::number::PacketNumberSpace::ApplicationData;
+        let first = space.new_packet_number(VarInt::from_u8(100));
+        let second = space.new_packet_number(VarInt::from_u8(101));
+
+        sender.push(Bytes::from_static(&[1; 100]));
+        sender.on_early_transmit(first, 60);
+        sender.on_early_transmit(second, 40);
+
+        // the data was already transmitted
+        assert!(!sender.has_transmission_interest());
+        assert!(sender.is_inflight());
+
+        sender.on_packet_ack(&first);
+        sender.on_packet_loss(&second);
+
+        // only the lost range is transmitted again
+        let _ = sender.on_transmit(VarInt::from_u8(0), &mut context);
+        assert_eq!(context.frame_buffer.len(), 1);
+        let mut frame = context.frame_buffer.pop_front().unwrap();
+        let packet = frame.packet_nr;
+        if let frame::Frame::Stream(frame) = frame.as_frame() {
+            assert_eq!(frame.offset, VarInt::from_u8(60));
+            assert_eq!(frame.data.len(), 40);
+        } else {
+            panic!("invalid frame");
+        }
+
+        sender.on_packet_ack(&packet);
+        assert!(!sender.is_inflight());
+        assert!(sender.is_empty());
+    }
+
     #[test]
     fn model() {
         check!()
@@ -217,6 +217,16 @@ impl<FlowController: OutgoingDataFlowController, Writer: FrameWriter>
         Ok(())
     }
 
+    /// Records a transmission of `interval` in a packet which was written outside of the sender
+    #[inline]
+    pub fn insert(&mut self, packet_number: PacketNumber, interval: Interval<VarInt>) {
+        self.in_flight.insert(
+            packet_number,
+            interval.start_inclusive(),
+            VarInt::try_from(interval.len()).unwrap_or(VarInt::MAX),
+        );
+    }
+
     /// Remove all inflight transmissions
     #[inline]
     pub fn clear(&mut self) {
@@ -73,6 +73,11 @@ impl<
         self.request_delivery_if_necessary();
     }
 
+    /// Sets the minimum change in value which requires sending an update
+    pub fn set_threshold(&mut self, threshold: T) {
+        self.threshold = threshold;
+    }
+
     /// Stop to synchronize the value to the peer
     pub fn stop_sync(&mut self) {
         self.delivery.cancel();
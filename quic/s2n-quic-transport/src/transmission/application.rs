@@ -8,7 +8,7 @@ use crate::{
     endpoint, path,
     path::mtu,
     recovery,
-    space::{datagram, HandshakeStatus},
+    space::{ack_frequency, datagram, HandshakeStatus},
     stream::{AbstractStreamManager, StreamTrait as Stream},
     sync::{flag, flag::Ping},
     transmission::{self, Mode},
@@ -38,6 +38,7 @@ impl<'a, Config: endpoint::Config> Payload<'a, Config> {
         stream_manager: &'a mut AbstractStreamManager<Config::Stream>,
         recovery_manager: &'a mut recovery::Manager<Config>,
         datagram_manager: &'a mut datagram::Manager<Config>,
+        ack_frequency: &'a mut ack_frequency::Controller,
     ) -> Self {
         if transmission_mode != Mode::PathValidationOnly {
             debug_assert_eq!(path_id, path_manager.active_path_id());
@@ -54,6 +55,7 @@ impl<'a, Config: endpoint::Config> Payload<'a, Config> {
                     path_manager,
                     recovery_manager,
                     datagram_manager,
+                    ack_frequency,
                     prioritize_datagrams: false,
                 })
             }
@@ -110,6 +112,7 @@ pub struct Normal<'a, S: Stream, Config: endpoint::Config> {
     path_manager: &'a mut path::Manager<Config>,
     recovery_manager: &'a mut recovery::Manager<Config>,
     datagram_manager: &'a mut datagram::Manager<Config>,
+    ack_frequency: &'a mut ack_frequency::Controller,
     prioritize_datagrams: bool,
 }
 
@@ -174,6 +177,9 @@ impl<'a, S: Stream, Config: endpoint::Config> Normal<'a, S, Config> {
         // soon as possible
         self.handshake_status.on_transmit(context);
 
+        // send ACK_FREQUENCY frames early so the peer adjusts before acknowledging this packet
+        self.ack_frequency.on_transmit(context);
+
         //= https://www.rfc-editor.org/rfc/rfc9000#section-8.2
         //# An endpoint MAY include other frames with the PATH_CHALLENGE and
         //# PATH_RESPONSE frames used for path validation.
@@ -195,6 +201,7 @@ impl<'a, S: Stream, Config: endpoint::Config> transmission::interest::Provider
     ) -> transmission::interest::Result {
         self.ack_manager.transmission_interest(query)?;
         self.handshake_status.transmission_interest(query)?;
+        self.ack_frequency.transmission_interest(query)?;
         self.stream_manager.transmission_interest(query)?;
         self.datagram_manager.transmission_interest(query)?;
         self.local_id_registry.transmission_interest(query)?;
@@ -0,0 +1,85 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{contexts::WriteContext, transmission};
+use core::{convert::TryFrom, ops::RangeInclusive};
+use s2n_quic_core::{
+    endpoint, frame,
+    packet::number::PacketNumberSpace,
+    stream::{StreamId, StreamType},
+    varint::VarInt,
+};
+
+/// Writes the early data of a client into a STREAM frame for the first bidirectional stream
+pub struct Payload<'a> {
+    /// The early data which is allowed to be sent in 0-RTT packets
+    pub data: &'a [u8],
+    /// The number of bytes which were already sent
+    pub sent_len: &'a mut usize,
+}
+
+impl<'a> super::Payload for Payload<'a> {
+    fn size_hint(&self, range: RangeInclusive<usize>) -> usize {
+        *range.start()
+    }
+
+    fn on_transmit<W: WriteContext>(&mut self, context: &mut W) {
+        debug_assert!(
+            !context.transmission_mode().is_mtu_probing(),
+            "0-RTT transmissions should not be used for MTU probing"
+        );
+
+        //= https://www.rfc-editor.org/rfc/rfc9001#section-4.6.1
+        //# A client that wishes to send 0-RTT packets uses the early_data
+        //# extension in the ClientHello message of a subsequent handshake; see
+        //# Section 4.2.10 of [TLS13].  It then sends application data in 0-RTT
+        //# packets.
+        if !context.transmission_constraint().can_transmit() {
+            return;
+        }
+
+        let data = match self.data.get(*self.sent_len..) {
+            Some(data) if !data.is_empty() => data,
+            _ => return,
+        };
+        let offset = match VarInt::try_from(*self.sent_len) {
+            Ok(offset) => offset,
+            Err(_) => return,
+        };
+
+        let mut frame = frame::Stream {
+            stream_id: StreamId::initial(endpoint::Type::Client, StreamType::Bidirectional).into(),
+            offset,
+            // this will be updated by `try_fit`
+            is_last_frame: false,
+            is_fin: false,
+            data,
+        };
+
+        let len = match frame.try_fit(context.remaining_capacity()) {
+            Ok(len) if len > 0 => len,
+            _ => return,
+        };
+
+        frame.data = &data[..len];
+        context.write_fitted_frame(&frame);
+        *self.sent_len += len;
+    }
+
+    fn packet_number_space(&self) -> PacketNumberSpace {
+        PacketNumberSpace::ApplicationData
+    }
+}
+
+impl<'a> transmission::interest::Provider for Payload<'a> {
+    fn transmission_interest<Q: transmission::interest::Query>(
+        &self,
+        query: &mut Q,
+    ) -> transmission::interest::Result {
+        if *self.sent_len < self.data.len() {
+            query.on_new_data()?;
+        }
+
+        Ok(())
+    }
+}
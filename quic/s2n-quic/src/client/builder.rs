@@ -283,6 +283,52 @@ impl<Providers: ClientProviders> Builder<Providers> {
         ClientProviders
     );
 
+    #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-session-ticket")))]
+    impl_provider_method!(
+        /// Sets the session ticket provider for the [`Client`]
+        ///
+        /// Session tickets received from servers are stored by server name and used to
+        /// resume the session on the next connection to the same server.
+        ///
+        /// ```rust,ignore
+        /// use s2n_quic::{Client, provider::session_ticket};
+        /// let client = Client::builder()
+        ///     .with_session_ticket(session_ticket::Memory::default())?
+        ///     .start()?;
+        /// ```
+        with_session_ticket,
+        session_ticket,
+        ClientProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the stream scheduler provider for the [`Client`]
+        ///
+        /// The stream scheduler decides the order in which the streams of each connection
+        /// transmit data.
+        ///
+        /// # Examples
+        ///
+        /// Schedules streams by their priority
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Client, provider::stream_scheduler};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let client = Client::builder()
+        ///     .with_stream_scheduler(stream_scheduler::Urgency::default())?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_stream_scheduler,
+        stream_scheduler,
+        ClientProviders
+    );
+
     /// Starts the [`Client`] with the configured providers
     ///
     /// # Examples
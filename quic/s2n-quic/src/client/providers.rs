@@ -21,6 +21,8 @@ impl_providers_state! {
         sync: Sync,
         tls: Tls,
         datagram: Datagram,
+        session_ticket: SessionTicket,
+        stream_scheduler: StreamScheduler,
     }
 
     /// Opaque trait containing all of the configured providers
@@ -40,6 +42,8 @@ impl<
         Sync: sync::Provider,
         Tls: tls::Provider,
         Datagram: datagram::Provider,
+        SessionTicket: session_ticket::Provider,
+        StreamScheduler: stream_scheduler::Provider,
     >
     Providers<
         CongestionController,
@@ -54,6 +58,8 @@ impl<
         Sync,
         Tls,
         Datagram,
+        SessionTicket,
+        StreamScheduler,
     >
 {
     pub fn start(self) -> Result<Client, StartError> {
@@ -70,6 +76,8 @@ impl<
             sync,
             tls,
             datagram,
+            session_ticket,
+            stream_scheduler,
         } = self;
 
         let congestion_controller = congestion_controller.start().map_err(StartError::new)?;
@@ -88,6 +96,8 @@ impl<
         let path_migration = PathMigration;
         let tls = tls.start_client().map_err(StartError::new)?;
         let datagram = datagram.start().map_err(StartError::new)?;
+        let session_ticket = session_ticket.start().map_err(StartError::new)?;
+        let stream_scheduler = stream_scheduler.start().map_err(StartError::new)?;
 
         // Validate providers
         // TODO: Add more validation https://github.com/aws/s2n-quic/issues/285
@@ -117,6 +127,10 @@ impl<
             path_handle: PhantomData,
             path_migration,
             datagram,
+            session_ticket,
+            anti_replay: anti_replay::Disabled::default(),
+            preferred_address: preferred_address::Disabled::default(),
+            stream_scheduler,
         };
 
         let (endpoint, connector) = endpoint::Endpoint::new_client(endpoint_config);
@@ -202,6 +216,8 @@ struct EndpointConfig<
     Sync,
     Tls,
     Datagram,
+    SessionTicket,
+    StreamScheduler,
 > {
     congestion_controller: CongestionController,
     connection_close_formatter: ConnectionCloseFormatter,
@@ -218,6 +234,10 @@ struct EndpointConfig<
     path_handle: PhantomData<PathHandle>,
     path_migration: PathMigration,
     datagram: Datagram,
+    session_ticket: SessionTicket,
+    anti_replay: anti_replay::Disabled,
+    preferred_address: preferred_address::Disabled,
+    stream_scheduler: StreamScheduler,
 }
 
 impl<
@@ -233,6 +253,8 @@ impl<
         Sync,
         Tls: crypto::tls::Endpoint,
         Datagram: s2n_quic_core::datagram::Endpoint,
+        SessionTicket: s2n_quic_core::session_ticket::Store,
+        StreamScheduler: stream_scheduler::Scheduler + Clone,
     > core::fmt::Debug
     for EndpointConfig<
         CongestionController,
@@ -247,6 +269,8 @@ impl<
         Sync,
         Tls,
         Datagram,
+        SessionTicket,
+        StreamScheduler,
     >
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -267,6 +291,8 @@ impl<
         Sync: 'static + Send,
         Tls: crypto::tls::Endpoint,
         Datagram: s2n_quic_core::datagram::Endpoint,
+        SessionTicket: s2n_quic_core::session_ticket::Store,
+        StreamScheduler: stream_scheduler::Scheduler + Clone,
     > endpoint::Config
     for EndpointConfig<
         CongestionController,
@@ -281,6 +307,8 @@ impl<
         Sync,
         Tls,
         Datagram,
+        SessionTicket,
+        StreamScheduler,
     >
 {
     type ConnectionIdFormat = ConnectionID;
@@ -301,6 +329,12 @@ impl<
     type PathMigrationValidator = PathMigration;
     type PacketInterceptor = PacketInterceptor;
     type DatagramEndpoint = Datagram;
+    type SessionTicketStore = SessionTicket;
+    // clients don't accept early data
+    type AntiReplayGuard = anti_replay::Disabled;
+    // only servers advertise a preferred address
+    type PreferredAddressSelector = preferred_address::Disabled;
+    type StreamScheduler = StreamScheduler;
 
     const ENDPOINT_TYPE: endpoint::Type = endpoint::Type::Client;
 
@@ -319,6 +353,10 @@ impl<
             event_subscriber: &mut self.event,
             path_migration: &mut self.path_migration,
             datagram: &mut self.datagram,
+            session_ticket_store: &mut self.session_ticket,
+            anti_replay: &mut self.anti_replay,
+            preferred_address: &mut self.preferred_address,
+            stream_scheduler: &mut self.stream_scheduler,
         }
     }
 }
@@ -161,6 +161,30 @@ macro_rules! impl_handle_api {
             self.0.application_protocol()
         }
 
+        /// Returns if the server accepted early (0-RTT) data sent by the client
+        ///
+        /// Early data is only attempted when a session ticket is provided to the
+        /// [`Connect`](`crate::client::Connect`) request. Servers only accept early data
+        /// after it has been checked by the configured
+        /// [anti-replay guard](`crate::provider::anti_replay`).
+        #[inline]
+        pub fn early_data_status(
+            &self,
+        ) -> $crate::connection::Result<$crate::connection::EarlyDataStatus> {
+            self.0.early_data_status()
+        }
+
+        /// Returns `true` if the server received any data from the client in early (0-RTT)
+        /// packets
+        ///
+        /// Early data can be replayed by an attacker. Applications should refuse to process
+        /// requests that aren't idempotent until the handshake is complete when this returns
+        /// `true`. Clients always return `false`.
+        #[inline]
+        pub fn received_early_data(&self) -> $crate::connection::Result<bool> {
+            self.0.received_early_data()
+        }
+
         /// Returns the internal identifier for the [`Connection`](`crate::Connection`)
         ///
         /// Note: This internal identifier is not the same as the connection ID included in packet
@@ -186,6 +210,55 @@ macro_rules! impl_handle_api {
             self.0.keep_alive(enabled)
         }
 
+        /// Migrates the connection to the provided local address
+        ///
+        /// A new path is opened from `local_address` to the current remote address and validated
+        /// with a PATH_CHALLENGE. Once the peer responds, the new path becomes the active path and
+        /// an [`ActivePathUpdated`](`crate::provider::event::events::ActivePathUpdated`) event is
+        /// emitted. If validation fails, the connection continues on the current path.
+        ///
+        /// Only clients can migrate, and only after the handshake is confirmed. A port of `0`
+        /// keeps the local port of the active path. Any other port is sent from a socket that the
+        /// IO provider binds to it; if the port can't be bound, path validation fails. Migration
+        /// is rejected if the peer sent the `disable_active_migration` transport parameter or the
+        /// IO provider is unable to send from the requested address.
+        #[inline]
+        pub fn migrate(
+            &mut self,
+            local_address: std::net::SocketAddr,
+        ) -> $crate::connection::Result<(), $crate::connection::MigrationError> {
+            self.0.migrate(local_address.into())
+        }
+
+        /// Sets the Differentiated Services Code Point (DSCP) marked on packets sent by the
+        /// connection
+        ///
+        /// The code point is written to the IPv4 TOS or IPv6 Traffic Class field alongside the ECN
+        /// markings and applies to all packets transmitted after the call. The default for new
+        /// connections can be configured with
+        /// [`Limits::with_dscp`](`crate::provider::limits::Limits::with_dscp`).
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # fn test() -> s2n_quic::connection::Result<()> {
+        /// #   use s2n_quic::connection::Dscp;
+        /// #   let mut handle: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// // prioritize the connection over bulk traffic
+        /// handle.set_dscp(Dscp::AF41)?;
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn set_dscp(
+            &mut self,
+            dscp: $crate::connection::Dscp,
+        ) -> $crate::connection::Result<()> {
+            self.0.set_dscp(dscp)
+        }
+
         /// Closes the Connection with the provided error code
         ///
         /// This will immediately terminate all outstanding streams.
@@ -405,6 +478,58 @@ macro_rules! impl_handle_api {
 
             query.into()
         }
+
+        /// Receives a datagram from the peer
+        ///
+        /// Requires the connection to use the
+        /// [default datagram receiver](crate::provider::datagram::default::Receiver).
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> Result<(), s2n_quic::provider::datagram::default::DatagramError> {
+        /// #   let connection: s2n_quic::connection::Handle = todo!();
+        /// #
+        /// while let Ok(datagram) = connection.recv_datagram().await {
+        ///     println!("Received datagram: {:?}", datagram);
+        /// }
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[cfg(all(not(docdiff), feature = "unstable-provider-datagram"))]
+        #[inline]
+        pub async fn recv_datagram(
+            &self,
+        ) -> core::result::Result<
+            ::bytes::Bytes,
+            $crate::provider::datagram::default::DatagramError,
+        > {
+            futures::future::poll_fn(|cx| self.poll_recv_datagram(cx)).await
+        }
+
+        /// Polls receiving a datagram from the peer
+        ///
+        /// Requires the connection to use the
+        /// [default datagram receiver](crate::provider::datagram::default::Receiver).
+        #[cfg(all(not(docdiff), feature = "unstable-provider-datagram"))]
+        #[inline]
+        pub fn poll_recv_datagram(
+            &self,
+            cx: &mut core::task::Context,
+        ) -> core::task::Poll<
+            core::result::Result<
+                ::bytes::Bytes,
+                $crate::provider::datagram::default::DatagramError,
+            >,
+        > {
+            use $crate::provider::datagram::default::{DatagramError, Receiver};
+
+            match self.datagram_mut(|receiver: &mut Receiver| receiver.poll_recv_datagram(cx)) {
+                Ok(poll) => poll,
+                Err(error) => Err(DatagramError::from(error)).into(),
+            }
+        }
     };
 }
 
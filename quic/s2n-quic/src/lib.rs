@@ -22,6 +22,13 @@
 //! that multiple servers handle address tokens, this provider should not be used. Instead, a custom
 //! implementation of [`provider::address_token::Format`] should be specified.
 //!
+//! ### `provider-event-qlog`
+//!
+//! Enables the [`provider::event::qlog::Provider`], which writes a
+//! [qlog](https://datatracker.ietf.org/doc/draft-ietf-quic-qlog-main-schema/) trace for each
+//! connection to a configured directory. Errors writing a trace are logged as
+//! [`tracing`](https://docs.rs/tracing) warnings.
+//!
 //! ### `provider-event-tracing`
 //!
 //! Enables event integration with [`tracing`](https://docs.rs/tracing). The
@@ -29,6 +36,13 @@
 //! endpoint and connection events to the application's configured
 //! [`tracing::Subscriber`](https://docs.rs/tracing/latest/tracing/trait.Subscriber.html).
 //!
+//! ### `provider-io-testing`
+//!
+//! Enables the [`provider::io::testing`] IO provider, which runs endpoints on a simulated network
+//! with virtual time. Tests can either use a [`Model`](provider::io::testing::Model) of a single
+//! network path or a [`Topology`](provider::io::testing::Topology) of named hosts connected by
+//! links. The `unstable-provider-io-testing` feature is kept as an alias.
+//!
 //! ### `provider-tls-default`
 //!
 //! _Enabled by default_
@@ -79,10 +93,15 @@ mod tests;
         any(
             feature = "unstable_client_hello",
             feature = "unstable-provider-datagram",
-            feature = "unstable-provider-io-testing",
+            feature = "unstable-provider-io-uring",
+            feature = "unstable-provider-io-xdp",
+            feature = "unstable-provider-io-local",
+            feature = "unstable-provider-io-pcap",
             feature = "unstable-provider-packet-interceptor",
             feature = "unstable-provider-random",
             feature = "unstable-provider-congestion-controller",
+            feature = "unstable-provider-session-ticket",
+            feature = "unstable-server-sharded",
         ),
         // any unstable features requires at least one of the following conditions
         not(any(
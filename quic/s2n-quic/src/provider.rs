@@ -8,12 +8,15 @@ use core::fmt;
 mod macros;
 
 pub mod address_token;
+pub mod anti_replay;
 pub mod connection_id;
 pub mod endpoint_limits;
 pub mod event;
 pub mod io;
 pub mod limits;
+pub mod preferred_address;
 pub mod stateless_reset_token;
+pub mod stream_scheduler;
 pub mod tls;
 
 // These providers are not currently exposed to applications
@@ -53,6 +56,14 @@ cfg_if!(
     }
 );
 
+cfg_if!(
+    if #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-session-ticket")))] {
+        pub mod session_ticket;
+    } else {
+        pub(crate) mod session_ticket;
+    }
+);
+
 /// An error indicating a failure to start an endpoint
 pub struct StartError(Box<dyn 'static + fmt::Display>);
 
@@ -0,0 +1,287 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Default provider for 0-RTT replay protection
+//!
+//! The default provider records the `random` field of each ClientHello that is allowed to send
+//! early data in a cuckoo filter and rejects early data from any ClientHello that has been
+//! seen before.
+//!
+//! ClientHello messages are remembered for at least the configured window. The window should be
+//! at least as long as the period in which the TLS provider accepts a session ticket for early
+//! data, since a replay that arrives after the window has elapsed can't be detected. Each
+//! server keeps its own filter so deployments with multiple servers sharing session ticket keys
+//! should provide a custom implementation of [`super::Guard`].
+
+use core::time::Duration;
+use hash_hasher::HashHasher;
+use s2n_quic_core::{
+    anti_replay::{self, Context, Outcome},
+    time::Timestamp,
+};
+use std::sync::{Arc, Mutex};
+
+/// The default amount of time a ClientHello is remembered
+pub const DEFAULT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Allows the default guard to be built with specific values
+///
+/// # Examples
+///
+/// Remember ClientHello messages for at least 30 seconds
+///
+/// ```rust
+/// use s2n_quic::provider::anti_replay;
+/// use std::time::Duration;
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let guard = anti_replay::Default::builder()
+///     .with_window(Duration::from_secs(30))?
+///     .build()?;
+/// #
+/// #    Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Builder {
+    window: Duration,
+    capacity: usize,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_WINDOW,
+            capacity: cuckoofilter::DEFAULT_CAPACITY,
+        }
+    }
+}
+
+impl Builder {
+    /// Sets the minimum amount of time a ClientHello is remembered
+    pub fn with_window(mut self, window: Duration) -> Result<Self, core::convert::Infallible> {
+        self.window = window;
+        Ok(self)
+    }
+
+    /// Sets the number of ClientHello messages that can be remembered in each window
+    ///
+    /// Early data is rejected once the capacity has been reached, until the window elapses.
+    pub fn with_capacity(mut self, capacity: usize) -> Result<Self, core::convert::Infallible> {
+        self.capacity = capacity;
+        Ok(self)
+    }
+
+    /// Builds the guard
+    pub fn build(self) -> Result<Guard, core::convert::Infallible> {
+        Ok(Guard {
+            state: Arc::new(Mutex::new(State::new(self.window, self.capacity))),
+            capacity: self.capacity,
+        })
+    }
+}
+
+type Filter = cuckoofilter::CuckooFilter<HashHasher>;
+
+#[derive(Clone)]
+pub struct Guard {
+    state: Arc<Mutex<State>>,
+    capacity: usize,
+}
+
+impl Default for Guard {
+    fn default() -> Self {
+        Builder::default()
+            .build()
+            .expect("default configuration is valid")
+    }
+}
+
+impl Guard {
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+}
+
+impl core::fmt::Debug for Guard {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Guard").finish()
+    }
+}
+
+impl anti_replay::Guard for Guard {
+    fn on_early_data(&self, context: &Context) -> Outcome {
+        let now = s2n_quic_platform::time::now();
+
+        let (outcome, expired) = if let Ok(mut state) = self.state.lock() {
+            let expired = state.rotate(now);
+            (state.on_early_data(context.client_hello_random), expired)
+        } else {
+            // the state can't be trusted so fail closed
+            return Outcome::Reject;
+        };
+
+        if let Some(expired) = expired {
+            // The expired filter is freed and its replacement is allocated without holding the
+            // lock so other connections aren't blocked while ~capacity entries are initialized.
+            //
+            // TODO clear and reuse the expired filter instead of recreating. This is pending a
+            // merge to crates.io (https://github.com/axiomhq/rust-cuckoofilter/pull/52)
+            drop(expired);
+            let spare = Filter::with_capacity(self.capacity);
+
+            if let Ok(mut state) = self.state.lock() {
+                state.spare = Some(spare);
+            }
+        }
+
+        outcome
+    }
+}
+
+struct State {
+    window: Duration,
+    rotates_at: Option<Timestamp>,
+    /// ClientHello messages seen in the current window
+    current: Filter,
+    /// ClientHello messages seen in the previous window
+    previous: Filter,
+    /// An empty filter which becomes the current filter on the next rotation
+    spare: Option<Filter>,
+}
+
+impl State {
+    fn new(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            rotates_at: None,
+            current: Filter::with_capacity(capacity),
+            previous: Filter::with_capacity(capacity),
+            spare: Some(Filter::with_capacity(capacity)),
+        }
+    }
+
+    fn on_early_data(
+        &mut self,
+        client_hello_random: &[u8; anti_replay::CLIENT_HELLO_RANDOM_LEN],
+    ) -> Outcome {
+        let is_replay = self.current.contains(client_hello_random)
+            || self.previous.contains(client_hello_random);
+
+        if is_replay {
+            return Outcome::Reject;
+        }
+
+        // if the filter is full, the ClientHello can't be remembered so the early data is
+        // rejected
+        if self.current.add(client_hello_random).is_err() {
+            return Outcome::Reject;
+        }
+
+        Outcome::Accept
+    }
+
+    /// Moves the current filter to the previous filter once the window elapses
+    ///
+    /// Keeping two filters ensures each entry is remembered for at least one full window. The
+    /// spare filter takes the place of the current filter and the expired previous filter is
+    /// returned so the caller can replace the spare after releasing the lock.
+    ///
+    /// If the spare hasn't been replaced yet, the rotation is postponed. Entries are then
+    /// remembered for longer than the window, which can only reject more early data.
+    fn rotate(&mut self, now: Timestamp) -> Option<Filter> {
+        let rotates_at = match self.rotates_at {
+            Some(rotates_at) => rotates_at,
+            None => {
+                self.rotates_at = Some(now + self.window);
+                return None;
+            }
+        };
+
+        if now < rotates_at {
+            return None;
+        }
+
+        let spare = self.spare.take()?;
+        let current = core::mem::replace(&mut self.current, spare);
+        let expired = core::mem::replace(&mut self.previous, current);
+
+        // If more than a full window has elapsed, nothing in the previous filter is within the
+        // window anymore either. It's still kept until the next rotation rather than waiting on
+        // a second spare.
+        self.rotates_at = Some(now + self.window);
+
+        Some(expired)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_platform::time;
+
+    const WINDOW: Duration = Duration::from_secs(1);
+    const CAPACITY: usize = 100;
+
+    /// Mirrors `Guard::on_early_data` without the lock
+    fn on_early_data(state: &mut State, client_hello_random: u8, now: Timestamp) -> Outcome {
+        if state.rotate(now).is_some() {
+            state.spare = Some(Filter::with_capacity(CAPACITY));
+        }
+        state.on_early_data(&[client_hello_random; 32])
+    }
+
+    #[test]
+    fn replay_test() {
+        let mut state = State::new(WINDOW, CAPACITY);
+        let now = time::now();
+
+        assert_eq!(on_early_data(&mut state, 1, now), Outcome::Accept);
+        assert_eq!(on_early_data(&mut state, 2, now), Outcome::Accept);
+
+        // the same ClientHello is rejected
+        assert_eq!(on_early_data(&mut state, 1, now), Outcome::Reject);
+        assert_eq!(on_early_data(&mut state, 2, now), Outcome::Reject);
+    }
+
+    #[test]
+    fn window_test() {
+        let mut state = State::new(WINDOW, CAPACITY);
+        let now = time::now();
+
+        assert_eq!(on_early_data(&mut state, 1, now), Outcome::Accept);
+
+        // the ClientHello is still remembered in the next window
+        let now = now + WINDOW + Duration::from_millis(1);
+        assert_eq!(on_early_data(&mut state, 1, now), Outcome::Reject);
+        assert_eq!(on_early_data(&mut state, 2, now), Outcome::Accept);
+
+        // after two windows the first ClientHello is forgotten
+        let now = now + WINDOW + Duration::from_millis(1);
+        assert_eq!(on_early_data(&mut state, 1, now), Outcome::Accept);
+        assert_eq!(on_early_data(&mut state, 2, now), Outcome::Reject);
+    }
+
+    #[test]
+    fn missing_spare_test() {
+        let mut state = State::new(WINDOW, CAPACITY);
+        let now = time::now();
+
+        assert_eq!(on_early_data(&mut state, 1, now), Outcome::Accept);
+
+        // the first rotation uses up the spare
+        let now = now + WINDOW + Duration::from_millis(1);
+        assert!(state.rotate(now).is_some());
+        assert!(state.spare.is_none());
+
+        // without a spare the rotation is postponed and nothing is forgotten
+        let now = now + WINDOW + Duration::from_millis(1);
+        assert!(state.rotate(now).is_none());
+        assert_eq!(state.on_early_data(&[1; 32]), Outcome::Reject);
+
+        // once the spare is replaced the rotation happens
+        state.spare = Some(Filter::with_capacity(CAPACITY));
+        assert!(state.rotate(now).is_some());
+        assert_eq!(state.on_early_data(&[2; 32]), Outcome::Accept);
+    }
+}
@@ -0,0 +1,33 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides replay protection for early (0-RTT) data accepted by a QUIC server
+//!
+//! Early data can be replayed by an attacker that captures it. The anti-replay guard is
+//! consulted before the TLS provider accepts early data and can reject it in the handshake, in
+//! which case the client sends the data again after the handshake completes.
+
+pub use s2n_quic_core::anti_replay::{Context, Disabled, Guard, Outcome};
+
+pub trait Provider: 'static {
+    type Guard: 'static + Guard;
+    type Error: 'static + core::fmt::Display;
+
+    /// Starts the anti-replay provider
+    fn start(self) -> Result<Self::Guard, Self::Error>;
+}
+
+pub mod default;
+
+pub use default::Guard as Default;
+
+impl_provider_utils!();
+
+impl<T: 'static + Guard> Provider for T {
+    type Guard = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Guard, Self::Error> {
+        Ok(self)
+    }
+}
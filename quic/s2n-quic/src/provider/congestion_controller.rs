@@ -13,7 +13,10 @@ pub trait Provider {
     fn start(self) -> Result<Self::Endpoint, Self::Error>;
 }
 
-pub use s2n_quic_core::recovery::{bbr::Endpoint as Bbr, cubic::Endpoint as Cubic};
+pub use s2n_quic_core::recovery::{
+    bbr::Endpoint as Bbr, cubic::Endpoint as Cubic, fixed::Endpoint as Fixed,
+    new_reno::Endpoint as NewReno, prague::Endpoint as Prague,
+};
 pub type Default = Cubic;
 
 impl_provider_utils!();
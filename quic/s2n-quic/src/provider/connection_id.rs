@@ -206,3 +206,101 @@ pub mod default {
         }
     }
 }
+
+#[cfg(all(not(docdiff), feature = "unstable-server-sharded"))]
+pub mod sharded {
+    use core::{convert::TryInto, time::Duration};
+    use rand::prelude::*;
+    use s2n_quic_core::connection::{
+        self,
+        id::{ConnectionInfo, Generator, Validator},
+    };
+
+    /// Randomly generated connection Id format which encodes the index of an endpoint shard.
+    ///
+    /// The first byte of each generated Id, modulo the number of shards, is the index of the
+    /// shard. This allows datagrams to be steered to the shard which owns the connection, e.g.
+    /// with [`Builder::with_reuse_port_steering`](crate::provider::io::tokio::Builder::with_reuse_port_steering).
+    #[derive(Debug)]
+    pub struct Format {
+        inner: super::default::Format,
+        index: u8,
+        count: u8,
+    }
+
+    impl Format {
+        /// Creates a format for the shard at `index` out of `count` shards
+        ///
+        /// Returns `None` if `index` is not less than `count`.
+        pub fn new(index: u8, count: u8) -> Option<Self> {
+            if index >= count {
+                return None;
+            }
+
+            Some(Self {
+                inner: Default::default(),
+                index,
+                count,
+            })
+        }
+
+        /// Returns the index of the shard which generated the connection Id
+        pub fn shard_index(&self, id: &[u8]) -> Option<u8> {
+            id.first().map(|byte| byte % self.count)
+        }
+    }
+
+    impl Generator for Format {
+        fn generate(&mut self, connection_info: &ConnectionInfo) -> connection::LocalId {
+            let id = self.inner.generate(connection_info);
+            let mut bytes = [0u8; connection::id::MAX_LEN];
+            let bytes = &mut bytes[..id.len()];
+            bytes.copy_from_slice(id.as_ref());
+
+            // pick a random multiple of the shard count so the rest of the first byte stays
+            // random. The index is the first byte modulo the shard count, so it isn't hidden from
+            // an observer who knows the number of shards.
+            let index = self.index as u16;
+            let count = self.count as u16;
+            let multiple = thread_rng().gen_range(0..=(u8::MAX as u16 - index) / count);
+            bytes[0] = (multiple * count + index) as u8;
+
+            (&*bytes).try_into().expect("length already checked")
+        }
+
+        fn lifetime(&self) -> Option<Duration> {
+            self.inner.lifetime()
+        }
+    }
+
+    impl Validator for Format {
+        fn validate(&self, connection_info: &ConnectionInfo, buffer: &[u8]) -> Option<usize> {
+            self.inner.validate(connection_info, buffer)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn shard_index_test() {
+            let remote_address = &s2n_quic_core::inet::SocketAddress::default();
+            let connection_info = ConnectionInfo::new(remote_address);
+
+            for count in [1, 2, 3, 7, 16, 255] {
+                for index in 0..count {
+                    let mut format = Format::new(index, count).unwrap();
+
+                    for _ in 0..16 {
+                        let id = format.generate(&connection_info);
+                        assert_eq!(format.shard_index(id.as_ref()), Some(index));
+                        assert_eq!(format.validate(&connection_info, id.as_ref()), Some(16));
+                    }
+                }
+
+                assert!(Format::new(count, count).is_none());
+            }
+        }
+    }
+}
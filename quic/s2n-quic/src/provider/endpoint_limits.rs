@@ -180,6 +180,7 @@ pub mod default {
     #[derive(Default)]
     pub struct Builder {
         max_inflight_handshake_limit: Option<usize>,
+        max_send_buffer_memory: Option<usize>,
     }
 
     impl Builder {
@@ -189,10 +190,20 @@ pub mod default {
             Ok(self)
         }
 
+        /// Sets the limit on memory used by stream send buffers across all connections
+        ///
+        /// The limit can be exceeded by up to one written chunk per stream, since a write is
+        /// accepted as long as at least 1 byte is available.
+        pub fn with_send_buffer_memory_limit(mut self, limit: usize) -> Result<Self, Infallible> {
+            self.max_send_buffer_memory = Some(limit);
+            Ok(self)
+        }
+
         /// Build the limits
         pub fn build(self) -> Result<Limits, Infallible> {
             Ok(Limits {
                 max_inflight_handshake_limit: self.max_inflight_handshake_limit,
+                max_send_buffer_memory: self.max_send_buffer_memory,
                 rate_limiter: [BasicRateLimiter::default(); THROTTLED_PORTS_LEN],
             })
         }
@@ -202,6 +213,8 @@ pub mod default {
     pub struct Limits {
         /// Maximum number of handshakes to allow before Retry packets are queued
         max_inflight_handshake_limit: Option<usize>,
+        /// Maximum number of bytes buffered by send streams before writes are blocked
+        max_send_buffer_memory: Option<usize>,
         rate_limiter: [BasicRateLimiter; THROTTLED_PORTS_LEN],
     }
 
@@ -235,6 +248,10 @@ pub mod default {
 
             Outcome::allow()
         }
+
+        fn max_send_buffer_memory(&self) -> Option<usize> {
+            self.max_send_buffer_memory
+        }
     }
 
     /// Default limit values are as non-intrusive as possible
@@ -242,6 +259,7 @@ pub mod default {
         fn default() -> Self {
             Self {
                 max_inflight_handshake_limit: None,
+                max_send_buffer_memory: None,
                 rate_limiter: [BasicRateLimiter::default(); THROTTLED_PORTS_LEN],
             }
         }
@@ -252,9 +270,12 @@ pub mod default {
         let elp = Limits::builder()
             .with_inflight_handshake_limit(100)
             .unwrap()
+            .with_send_buffer_memory_limit(1_000_000)
+            .unwrap()
             .build()
             .unwrap();
         assert_eq!(elp.max_inflight_handshake_limit, Some(100));
+        assert_eq!(elp.max_send_buffer_memory(), Some(1_000_000));
     }
 
     #[test]
@@ -25,6 +25,10 @@ pub trait Provider {
 /// Provides an implementation to disable all events
 pub mod disabled;
 
+/// This module contains a subscriber which writes [qlog](https://datatracker.ietf.org/doc/draft-ietf-quic-qlog-main-schema/) traces
+#[cfg(any(feature = "provider-event-qlog", test))]
+pub mod qlog;
+
 /// This module contains event integration with [`tracing`](https://docs.rs/tracing)
 #[cfg(any(feature = "provider-event-tracing", test))]
 pub mod tracing;
@@ -0,0 +1,693 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Writes connection events as [qlog](https://datatracker.ietf.org/doc/draft-ietf-quic-qlog-main-schema/)
+//! traces
+//!
+//! Each connection is written to its own `<ODCID>.sqlog` file in the configured directory using
+//! the `JSON-SEQ` serialization format, which can be loaded directly into tools like
+//! [qvis](https://qvis.quictools.info/).
+
+use crate::provider::event::{events, ConnectionInfo, ConnectionMeta};
+use core::{fmt::Write as _, time::Duration};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write as _},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+// See https://datatracker.ietf.org/doc/html/draft-ietf-quic-qlog-main-schema-03#section-6.2
+const RECORD_SEPARATOR: char = '\u{1e}';
+
+#[derive(Debug)]
+pub struct Provider {
+    dir: PathBuf,
+}
+
+impl Provider {
+    /// Creates a qlog provider which writes traces into the given directory
+    ///
+    /// The directory is created when the endpoint is started, if it doesn't already exist.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl super::Provider for Provider {
+    type Subscriber = Subscriber;
+    type Error = io::Error;
+
+    fn start(self) -> Result<Self::Subscriber, Self::Error> {
+        std::fs::create_dir_all(&self.dir)?;
+        Ok(Subscriber { dir: self.dir })
+    }
+}
+
+pub struct Subscriber {
+    dir: PathBuf,
+}
+
+/// The qlog trace of a single connection
+pub struct ConnectionContext {
+    /// The output file of the trace
+    ///
+    /// This is cleared if an IO error occurs, after which the remaining events are discarded.
+    output: Option<BufWriter<File>>,
+    /// The path of the output file, which is included in error messages
+    path: PathBuf,
+    /// The time at which the connection was created, from which all event times are offset
+    start_time: Duration,
+    /// Scratch buffer used to serialize each record
+    record: String,
+    /// The frames written since the last sent packet
+    sent_frames: String,
+    /// The last received packet, which is held until all of its frames have been processed
+    received_packet: Option<ReceivedPacket>,
+}
+
+struct ReceivedPacket {
+    time: Duration,
+    header: String,
+    frames: String,
+}
+
+impl super::Subscriber for Subscriber {
+    type ConnectionContext = ConnectionContext;
+
+    fn create_connection_context(
+        &mut self,
+        meta: &ConnectionMeta,
+        info: &ConnectionInfo,
+    ) -> Self::ConnectionContext {
+        let odcid = Hex(info.original_destination_connection_id.bytes);
+        let path = self.dir.join(format!("{odcid}.sqlog"));
+
+        let output = match File::create(&path) {
+            Ok(file) => Some(BufWriter::new(file)),
+            Err(err) => {
+                report_error(&path, &err);
+                None
+            }
+        };
+
+        let mut context = ConnectionContext {
+            output,
+            path,
+            start_time: meta.timestamp.duration_since_start(),
+            record: String::new(),
+            sent_frames: String::new(),
+            received_packet: None,
+        };
+
+        let vantage_point = match meta.endpoint_type {
+            events::EndpointType::Client { .. } => "client",
+            events::EndpointType::Server { .. } => "server",
+        };
+
+        // qlog times are relative to the reference time, which is when the connection was created
+        let reference_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let reference_time = as_millis(reference_time);
+
+        let record = &mut context.record;
+        let _ = write!(
+            record,
+            r#"{RECORD_SEPARATOR}{{"qlog_version":"0.3","qlog_format":"JSON-SEQ","title":"s2n-quic","trace":{{"vantage_point":{{"type":"{vantage_point}"}},"common_fields":{{"ODCID":"{odcid}","time_format":"relative","reference_time":{reference_time}}}}}}}"#,
+        );
+        record.push('\n');
+        context.flush_record();
+
+        context
+    }
+
+    fn on_packet_sent(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::PacketSent,
+    ) {
+        let time = meta.timestamp.duration_since_start();
+        let mut data = String::new();
+        let _ = write!(
+            data,
+            r#"{{"header":{},"raw":{{"length":{}}},"frames":[{}]}}"#,
+            PacketHeader(&event.packet_header),
+            event.packet_len,
+            context.sent_frames,
+        );
+        context.sent_frames.clear();
+        context.write_event(time, "transport:packet_sent", &data);
+    }
+
+    fn on_frame_sent(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::FrameSent,
+    ) {
+        // frames are written before the packet is sent so buffer them until then
+        if !context.sent_frames.is_empty() {
+            context.sent_frames.push(',');
+        }
+        let _ = write!(context.sent_frames, "{}", Frame(&event.frame));
+    }
+
+    fn on_packet_received(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::PacketReceived,
+    ) {
+        context.flush_received_packet();
+        context.received_packet = Some(ReceivedPacket {
+            time: meta.timestamp.duration_since_start(),
+            header: PacketHeader(&event.packet_header).to_string(),
+            frames: String::new(),
+        });
+    }
+
+    fn on_frame_received(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &ConnectionMeta,
+        event: &events::FrameReceived,
+    ) {
+        if let Some(packet) = context.received_packet.as_mut() {
+            if !packet.frames.is_empty() {
+                packet.frames.push(',');
+            }
+            let _ = write!(packet.frames, "{}", Frame(&event.frame));
+        }
+    }
+
+    fn on_packet_lost(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::PacketLost,
+    ) {
+        let data = format!(
+            r#"{{"header":{},"trigger":"{}"}}"#,
+            PacketHeader(&event.packet_header),
+            if event.is_mtu_probe {
+                "mtu_probe"
+            } else {
+                "unknown"
+            },
+        );
+        context.write_event(
+            meta.timestamp.duration_since_start(),
+            "recovery:packet_lost",
+            &data,
+        );
+    }
+
+    fn on_recovery_metrics(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::RecoveryMetrics,
+    ) {
+        let data = format!(
+            r#"{{"min_rtt":{},"smoothed_rtt":{},"latest_rtt":{},"rtt_variance":{},"pto_count":{},"congestion_window":{},"bytes_in_flight":{}}}"#,
+            as_millis(event.min_rtt),
+            as_millis(event.smoothed_rtt),
+            as_millis(event.latest_rtt),
+            as_millis(event.rtt_variance),
+            event.pto_count,
+            event.congestion_window,
+            event.bytes_in_flight,
+        );
+        context.write_event(
+            meta.timestamp.duration_since_start(),
+            "recovery:metrics_updated",
+            &data,
+        );
+    }
+
+    fn on_datagram_sent(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::DatagramSent,
+    ) {
+        let data = format!(r#"{{"count":1,"raw":[{{"length":{}}}]}}"#, event.len);
+        context.write_event(
+            meta.timestamp.duration_since_start(),
+            "transport:datagrams_sent",
+            &data,
+        );
+    }
+
+    fn on_datagram_received(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::DatagramReceived,
+    ) {
+        let data = format!(r#"{{"count":1,"raw":[{{"length":{}}}]}}"#, event.len);
+        context.write_event(
+            meta.timestamp.duration_since_start(),
+            "transport:datagrams_received",
+            &data,
+        );
+    }
+
+    fn on_datagram_dropped(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::DatagramDropped,
+    ) {
+        let data = format!(r#"{{"raw":{{"length":{}}}}}"#, event.len);
+        context.write_event(
+            meta.timestamp.duration_since_start(),
+            "transport:datagram_dropped",
+            &data,
+        );
+    }
+
+    fn on_connection_closed(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        meta: &ConnectionMeta,
+        event: &events::ConnectionClosed,
+    ) {
+        let data = format!(r#"{{"reason":"{}"}}"#, Escaped(&event.error.to_string()));
+        context.write_event(
+            meta.timestamp.duration_since_start(),
+            "connectivity:connection_closed",
+            &data,
+        );
+        context.flush();
+    }
+}
+
+impl ConnectionContext {
+    fn write_event(&mut self, time: Duration, name: &str, data: &str) {
+        // make sure any pending packet is written first to keep the trace ordered
+        self.flush_received_packet();
+        self.write_record(time, name, data);
+    }
+
+    fn write_record(&mut self, time: Duration, name: &str, data: &str) {
+        let _ = writeln!(
+            self.record,
+            r#"{RECORD_SEPARATOR}{{"time":{},"name":"{name}","data":{data}}}"#,
+            as_millis(time.saturating_sub(self.start_time)),
+        );
+        self.flush_record();
+    }
+
+    fn flush_received_packet(&mut self) {
+        if let Some(packet) = self.received_packet.take() {
+            let data = format!(
+                r#"{{"header":{},"frames":[{}]}}"#,
+                packet.header, packet.frames
+            );
+            self.write_record(packet.time, "transport:packet_received", &data);
+        }
+    }
+
+    fn flush_record(&mut self) {
+        if let Some(output) = self.output.as_mut() {
+            if let Err(err) = output.write_all(self.record.as_bytes()) {
+                report_error(&self.path, &err);
+                self.output = None;
+            }
+        }
+        self.record.clear();
+    }
+
+    fn flush(&mut self) {
+        self.flush_received_packet();
+        if let Some(output) = self.output.as_mut() {
+            if let Err(err) = output.flush() {
+                report_error(&self.path, &err);
+                self.output = None;
+            }
+        }
+    }
+}
+
+/// Reports an IO error on a trace file
+///
+/// Subscribers aren't able to return errors, so the error is logged as a `tracing` warning and
+/// the rest of the connection's trace is discarded.
+fn report_error(path: &Path, err: &io::Error) {
+    tracing::warn!(path = %path.display(), error = %err, "unable to write qlog trace");
+}
+
+impl Drop for ConnectionContext {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[inline]
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+struct Hex<'a>(&'a [u8]);
+
+impl core::fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a string to be written inside of a JSON string literal
+struct Escaped<'a>(&'a str);
+
+impl core::fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// See https://datatracker.ietf.org/doc/html/draft-ietf-quic-qlog-quic-events-03#section-8.7
+struct PacketHeader<'a>(&'a events::PacketHeader);
+
+impl core::fmt::Display for PacketHeader<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        use events::PacketHeader::*;
+
+        let (packet_type, number) = match self.0 {
+            Initial { number, .. } => ("initial", Some(number)),
+            Handshake { number, .. } => ("handshake", Some(number)),
+            ZeroRtt { number, .. } => ("0RTT", Some(number)),
+            OneRtt { number, .. } => ("1RTT", Some(number)),
+            Retry { .. } => ("retry", None),
+            VersionNegotiation { .. } => ("version_negotiation", None),
+            StatelessReset { .. } => ("stateless_reset", None),
+            _ => ("unknown", None),
+        };
+
+        write!(f, r#"{{"packet_type":"{packet_type}""#)?;
+        if let Some(number) = number {
+            write!(f, r#","packet_number":{number}"#)?;
+        }
+        f.write_char('}')
+    }
+}
+
+// See https://datatracker.ietf.org/doc/html/draft-ietf-quic-qlog-quic-events-03#section-8.13
+struct Frame<'a>(&'a events::Frame);
+
+impl core::fmt::Display for Frame<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        use events::Frame::*;
+
+        match self.0 {
+            Padding { .. } => write!(f, r#"{{"frame_type":"padding"}}"#),
+            Ping { .. } => write!(f, r#"{{"frame_type":"ping"}}"#),
+            Ack {
+                largest_acknowledged,
+                ecn_counts,
+                ..
+            } => {
+                write!(
+                    f,
+                    r#"{{"frame_type":"ack","largest_acknowledged":{largest_acknowledged}"#
+                )?;
+                if let Some(ecn) = ecn_counts {
+                    write!(
+                        f,
+                        r#","ect0":{},"ect1":{},"ce":{}"#,
+                        ecn.ect_0_count, ecn.ect_1_count, ecn.ce_count
+                    )?;
+                }
+                f.write_char('}')
+            }
+            ResetStream {
+                id,
+                error_code,
+                final_size,
+                ..
+            } => write!(
+                f,
+                r#"{{"frame_type":"reset_stream","stream_id":{id},"error_code":{error_code},"final_size":{final_size}}}"#
+            ),
+            StopSending { id, error_code, .. } => write!(
+                f,
+                r#"{{"frame_type":"stop_sending","stream_id":{id},"error_code":{error_code}}}"#
+            ),
+            Crypto { offset, len, .. } => write!(
+                f,
+                r#"{{"frame_type":"crypto","offset":{offset},"length":{len}}}"#
+            ),
+            NewToken { .. } => write!(f, r#"{{"frame_type":"new_token"}}"#),
+            Stream {
+                id,
+                offset,
+                len,
+                is_fin,
+                ..
+            } => write!(
+                f,
+                r#"{{"frame_type":"stream","stream_id":{id},"offset":{offset},"length":{len},"fin":{is_fin}}}"#
+            ),
+            MaxData { value, .. } => {
+                write!(f, r#"{{"frame_type":"max_data","maximum":{value}}}"#)
+            }
+            MaxStreamData { id, value, .. } => write!(
+                f,
+                r#"{{"frame_type":"max_stream_data","stream_id":{id},"maximum":{value}}}"#
+            ),
+            MaxStreams {
+                stream_type, value, ..
+            } => write!(
+                f,
+                r#"{{"frame_type":"max_streams","stream_type":"{}","maximum":{value}}}"#,
+                StreamType(stream_type)
+            ),
+            DataBlocked { data_limit, .. } => {
+                write!(f, r#"{{"frame_type":"data_blocked","limit":{data_limit}}}"#)
+            }
+            StreamDataBlocked {
+                stream_id,
+                stream_data_limit,
+                ..
+            } => write!(
+                f,
+                r#"{{"frame_type":"stream_data_blocked","stream_id":{stream_id},"limit":{stream_data_limit}}}"#
+            ),
+            StreamsBlocked {
+                stream_type,
+                stream_limit,
+                ..
+            } => write!(
+                f,
+                r#"{{"frame_type":"streams_blocked","stream_type":"{}","limit":{stream_limit}}}"#,
+                StreamType(stream_type)
+            ),
+            NewConnectionId { .. } => write!(f, r#"{{"frame_type":"new_connection_id"}}"#),
+            RetireConnectionId { .. } => {
+                write!(f, r#"{{"frame_type":"retire_connection_id"}}"#)
+            }
+            PathChallenge { .. } => write!(f, r#"{{"frame_type":"path_challenge"}}"#),
+            PathResponse { .. } => write!(f, r#"{{"frame_type":"path_response"}}"#),
+            ConnectionClose { .. } => write!(f, r#"{{"frame_type":"connection_close"}}"#),
+            HandshakeDone { .. } => write!(f, r#"{{"frame_type":"handshake_done"}}"#),
+            Datagram { len, .. } => {
+                write!(f, r#"{{"frame_type":"datagram","length":{len}}}"#)
+            }
+            AckFrequency {
+                sequence_number,
+                ack_eliciting_threshold,
+                request_max_ack_delay,
+                reordering_threshold,
+                ..
+            } => write!(
+                f,
+                r#"{{"frame_type":"ack_frequency","sequence_number":{sequence_number},"ack_eliciting_threshold":{ack_eliciting_threshold},"request_max_ack_delay":{request_max_ack_delay},"reordering_threshold":{reordering_threshold}}}"#
+            ),
+            ImmediateAck { .. } => write!(f, r#"{{"frame_type":"immediate_ack"}}"#),
+            _ => write!(f, r#"{{"frame_type":"unknown"}}"#),
+        }
+    }
+}
+
+struct StreamType<'a>(&'a events::StreamType);
+
+impl core::fmt::Display for StreamType<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self.0 {
+            events::StreamType::Bidirectional { .. } => f.write_str("bidirectional"),
+            events::StreamType::Unidirectional { .. } => f.write_str("unidirectional"),
+            _ => f.write_str("unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::event::{Provider as _, Subscriber as _};
+    use s2n_quic_core::{
+        connection, endpoint,
+        event::{builder, ConnectionPublisher as _, ConnectionPublisherSubscriber, IntoEvent as _},
+        time::Timestamp,
+    };
+
+    fn meta(millis: u64) -> builder::ConnectionMeta {
+        builder::ConnectionMeta {
+            endpoint_type: endpoint::Type::Client,
+            id: 0,
+            // start after the epoch since timestamps can't be zero
+            timestamp: unsafe {
+                Timestamp::from_duration(Duration::from_secs(1) + Duration::from_millis(millis))
+            },
+        }
+    }
+
+    fn path(cid: &[u8]) -> builder::Path<'_> {
+        builder::Path {
+            local_addr: builder::SocketAddress::IpV4 {
+                ip: &[127, 0, 0, 1],
+                port: 4433,
+            },
+            local_cid: builder::ConnectionId { bytes: cid },
+            remote_addr: builder::SocketAddress::IpV4 {
+                ip: &[127, 0, 0, 2],
+                port: 1234,
+            },
+            remote_cid: builder::ConnectionId { bytes: cid },
+            id: 0,
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn json_seq_test() {
+        let dir =
+            std::env::temp_dir().join(format!("s2n-quic-qlog-json-seq-{}", std::process::id()));
+        let mut subscriber = Provider::new(&dir).start().unwrap();
+
+        let odcid = [1u8, 2, 3, 4];
+        let info = builder::ConnectionInfo {
+            original_destination_connection_id: builder::ConnectionId { bytes: &odcid },
+        }
+        .into_event();
+        let mut context = subscriber.create_connection_context(&meta(0).into_event(), &info);
+
+        let mut publisher =
+            ConnectionPublisherSubscriber::new(meta(10), 1, &mut subscriber, &mut context);
+        let header = builder::PacketHeader::Initial {
+            number: 0,
+            version: 1,
+        };
+        publisher.on_frame_sent(builder::FrameSent {
+            packet_header: header.clone(),
+            path_id: 0,
+            frame: builder::Frame::Crypto {
+                offset: 0,
+                len: 100,
+            },
+        });
+        publisher.on_frame_sent(builder::FrameSent {
+            packet_header: header.clone(),
+            path_id: 0,
+            frame: builder::Frame::Padding,
+        });
+        publisher.on_packet_sent(builder::PacketSent {
+            packet_header: header,
+            packet_len: 1200,
+        });
+        publisher.on_datagram_sent(builder::DatagramSent {
+            len: 1200,
+            gso_offset: 0,
+        });
+
+        let mut publisher =
+            ConnectionPublisherSubscriber::new(meta(25), 1, &mut subscriber, &mut context);
+        let header = builder::PacketHeader::Handshake {
+            number: 1,
+            version: 1,
+        };
+        publisher.on_datagram_received(builder::DatagramReceived { len: 1200 });
+        publisher.on_packet_received(builder::PacketReceived {
+            packet_header: header.clone(),
+        });
+        publisher.on_frame_received(builder::FrameReceived {
+            packet_header: header,
+            path: path(&odcid),
+            frame: builder::Frame::Ack {
+                ecn_counts: None,
+                largest_acknowledged: 0,
+                ack_range_count: 1,
+            },
+        });
+        publisher.on_recovery_metrics(builder::RecoveryMetrics {
+            path: path(&odcid),
+            min_rtt: Duration::from_millis(10),
+            smoothed_rtt: Duration::from_millis(15),
+            latest_rtt: Duration::from_millis(20),
+            rtt_variance: Duration::from_millis(5),
+            max_ack_delay: Duration::from_millis(25),
+            pto_count: 0,
+            congestion_window: 12000,
+            bytes_in_flight: 1200,
+            congestion_limited: false,
+        });
+
+        let mut publisher =
+            ConnectionPublisherSubscriber::new(meta(40), 1, &mut subscriber, &mut context);
+        publisher.on_packet_lost(builder::PacketLost {
+            packet_header: builder::PacketHeader::OneRtt { number: 2 },
+            path: path(&odcid),
+            bytes_lost: 1200,
+            is_mtu_probe: false,
+        });
+        publisher.on_connection_closed(builder::ConnectionClosed {
+            error: connection::Error::immediate_close("bad \"quote\"\n"),
+        });
+
+        drop(context);
+
+        let trace = std::fs::read_to_string(dir.join("01020304.sqlog")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // each record starts with a record separator and ends with a line feed
+        assert!(trace.ends_with('\n'));
+        let records = trace
+            .split_terminator('\n')
+            .map(|record| record.strip_prefix(RECORD_SEPARATOR).unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(records[0].starts_with(
+            r#"{"qlog_version":"0.3","qlog_format":"JSON-SEQ","title":"s2n-quic","trace":{"vantage_point":{"type":"client"},"common_fields":{"ODCID":"01020304","time_format":"relative","reference_time":"#
+        ));
+
+        assert_eq!(
+            records[1..],
+            [
+                r#"{"time":10,"name":"transport:packet_sent","data":{"header":{"packet_type":"initial","packet_number":0},"raw":{"length":1200},"frames":[{"frame_type":"crypto","offset":0,"length":100},{"frame_type":"padding"}]}}"#,
+                r#"{"time":10,"name":"transport:datagrams_sent","data":{"count":1,"raw":[{"length":1200}]}}"#,
+                r#"{"time":25,"name":"transport:datagrams_received","data":{"count":1,"raw":[{"length":1200}]}}"#,
+                // received packets are written once all of their frames are processed
+                r#"{"time":25,"name":"transport:packet_received","data":{"header":{"packet_type":"handshake","packet_number":1},"frames":[{"frame_type":"ack","largest_acknowledged":0}]}}"#,
+                r#"{"time":25,"name":"recovery:metrics_updated","data":{"min_rtt":10,"smoothed_rtt":15,"latest_rtt":20,"rtt_variance":5,"pto_count":0,"congestion_window":12000,"bytes_in_flight":1200}}"#,
+                r#"{"time":40,"name":"recovery:packet_lost","data":{"header":{"packet_type":"1RTT","packet_number":2},"trigger":"unknown"}}"#,
+                r#"{"time":40,"name":"connectivity:connection_closed","data":{"reason":"The connection was closed due to: bad \"quote\"\n"}}"#,
+            ]
+        );
+    }
+}
@@ -16,11 +16,34 @@ pub trait Provider: 'static {
     ) -> Result<SocketAddress, Self::Error>;
 }
 
-#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-io-testing")))]
+#[cfg(any(test, feature = "provider-io-testing"))]
 pub mod testing;
 
 pub mod tokio;
 
+#[cfg(all(not(docdiff), feature = "unstable-provider-io-local"))]
+pub mod local;
+
+#[cfg(all(
+    not(docdiff),
+    feature = "unstable-provider-io-uring",
+    target_os = "linux"
+))]
+pub mod uring;
+
+#[cfg(all(
+    not(docdiff),
+    feature = "unstable-provider-io-xdp",
+    target_os = "linux"
+))]
+pub mod xdp;
+
+/// Packet captures for debugging the IO providers
+#[cfg(all(not(docdiff), feature = "unstable-provider-io-pcap"))]
+pub mod pcap {
+    pub use s2n_quic_platform::io::pcap::*;
+}
+
 pub use self::tokio as default;
 
 pub use default::Provider as Default;
@@ -0,0 +1,135 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides an implementation of the [`io::Provider`](crate::provider::io::Provider)
+//! for endpoints on the same host
+//!
+//! Datagrams are carried over a `SOCK_DGRAM` Unix socket or an in-process [`Switch`] instead of
+//! UDP. Each endpoint is given a synthetic socket address.
+
+use s2n_quic_core::{endpoint::Endpoint, inet::SocketAddress};
+use s2n_quic_platform::io::local;
+use std::io;
+
+#[cfg(unix)]
+pub use self::local::unix_address;
+pub use self::local::{Builder, Io as Provider, Switch};
+
+impl super::Provider for Provider {
+    type PathHandle = local::PathHandle;
+    type Error = io::Error;
+
+    fn start<E: Endpoint<PathHandle = Self::PathHandle>>(
+        self,
+        endpoint: E,
+    ) -> Result<SocketAddress, Self::Error> {
+        let (_join_handle, local_addr) = Provider::start(self, endpoint)?;
+        Ok(local_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::Connect, Client, Server};
+    use bytes::Bytes;
+    use s2n_quic_core::crypto::tls::testing::certificates;
+    use std::time::Duration;
+
+    /// Echoes a message from a client to a server over the provided IO providers
+    async fn echo(server_io: Provider, client_io: Provider) {
+        let mut server = Server::builder()
+            .with_io(server_io)
+            .unwrap()
+            .with_tls((certificates::CERT_PEM, certificates::KEY_PEM))
+            .unwrap()
+            .start()
+            .unwrap();
+        let server_addr = server.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Some(mut connection) = server.accept().await {
+                tokio::spawn(async move {
+                    while let Ok(Some(mut stream)) = connection.accept_bidirectional_stream().await
+                    {
+                        while let Ok(Some(chunk)) = stream.receive().await {
+                            let _ = stream.send(chunk).await;
+                        }
+                        let _ = stream.finish();
+                    }
+                });
+            }
+        });
+
+        let client = Client::builder()
+            .with_io(client_io)
+            .unwrap()
+            .with_tls(certificates::CERT_PEM)
+            .unwrap()
+            .start()
+            .unwrap();
+
+        let exchange = async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+
+            stream.send(Bytes::from_static(b"hello")).await.unwrap();
+            stream.finish().unwrap();
+
+            let mut received = vec![];
+            while let Some(chunk) = stream.receive().await.unwrap() {
+                received.extend_from_slice(&chunk);
+            }
+            assert_eq!(received, b"hello");
+        };
+
+        tokio::time::timeout(Duration::from_secs(10), exchange)
+            .await
+            .expect("the exchange should complete");
+    }
+
+    #[tokio::test]
+    async fn switch_test() {
+        let switch = Switch::new();
+        let server_io = Provider::builder()
+            .with_switch(&switch)
+            .unwrap()
+            .build()
+            .unwrap();
+        let client_io = Provider::builder()
+            .with_switch(&switch)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        echo(server_io, client_io).await;
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_test() {
+        let dir = std::env::temp_dir().join(format!("s2n-quic-io-local-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let server_path = dir.join("server.sock");
+        let client_path = dir.join("client.sock");
+
+        let server_io = Provider::builder()
+            .with_unix_socket(&server_path)
+            .unwrap()
+            .build()
+            .unwrap();
+        let client_io = Provider::builder()
+            .with_unix_socket(&client_path)
+            .unwrap()
+            .with_unix_peer(&server_path)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        echo(server_io, client_io).await;
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides an implementation of the [`io::Provider`](crate::provider::io::Provider)
+//! using [io_uring](https://man7.org/linux/man-pages/man7/io_uring.7.html)
+//!
+//! The endpoint is driven on a dedicated thread rather than a Tokio task. This requires Linux 5.11
+//! or later; on older kernels, building the provider returns an error and another provider, like
+//! the default Tokio provider, should be used instead.
+
+use s2n_quic_core::{endpoint::Endpoint, inet::SocketAddress};
+use s2n_quic_platform::io::uring;
+use std::io;
+
+pub use self::uring::{Builder, Io as Provider};
+
+impl super::Provider for Provider {
+    type PathHandle = uring::PathHandle;
+    type Error = io::Error;
+
+    fn start<E: Endpoint<PathHandle = Self::PathHandle>>(
+        self,
+        endpoint: E,
+    ) -> Result<SocketAddress, Self::Error> {
+        let (_join_handle, local_addr) = Provider::start(self, endpoint)?;
+        Ok(local_addr)
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides an implementation of the [`io::Provider`](crate::provider::io::Provider)
+//! using [AF_XDP](https://www.kernel.org/doc/html/latest/networking/af_xdp.html) sockets
+//!
+//! The kernel's network stack is bypassed and the endpoint is driven on a dedicated thread. An
+//! XDP program which redirects the endpoint's traffic to an `XSKMAP` must be attached to the
+//! interface by the application.
+//!
+//! ARP and NDP aren't implemented. Replies are sent to the source MAC address of the received
+//! frame, and other peers are reached with the statically configured
+//! [`Builder::with_neighbor`] and [`Builder::with_gateway`] addresses. See
+//! [`s2n_quic_platform::io::xdp`] for the details.
+//!
+//! **This provider is experimental** and may change in incompatible ways.
+
+use s2n_quic_core::{endpoint::Endpoint, inet::SocketAddress};
+use s2n_quic_platform::io::xdp;
+use std::io;
+
+pub use self::xdp::{Builder, Io as Provider};
+
+impl super::Provider for Provider {
+    type PathHandle = xdp::PathHandle;
+    type Error = io::Error;
+
+    fn start<E: Endpoint<PathHandle = Self::PathHandle>>(
+        self,
+        endpoint: E,
+    ) -> Result<SocketAddress, Self::Error> {
+        let (_join_handle, local_addr) = Provider::start(self, endpoint)?;
+        Ok(local_addr)
+    }
+}
@@ -0,0 +1,31 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Advertises a preferred address for the server to clients
+//!
+//! Clients validate the preferred address once the handshake is confirmed and migrate the
+//! connection to it. This allows a server to accept connections on an address shared by
+//! multiple hosts while each connection continues on a unicast address of the host.
+
+pub use s2n_quic_core::path::preferred_address::{Addresses, Disabled, Info, Selector};
+
+pub type Default = Disabled;
+
+/// Provides preferred address support for a server
+pub trait Provider {
+    type Selector: 'static + Send + Selector;
+    type Error: 'static + core::fmt::Display;
+
+    fn start(self) -> Result<Self::Selector, Self::Error>;
+}
+
+impl_provider_utils!();
+
+impl<T: 'static + Send + Selector> Provider for T {
+    type Selector = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Selector, Self::Error> {
+        Ok(self)
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides session ticket storage, which allows clients to resume TLS sessions
+//!
+//! Tickets received from a server are stored by server name and used for the next connection
+//! to the same server, unless the application provides a ticket with
+//! [`Connect::with_session_ticket`](crate::client::Connect::with_session_ticket).
+
+pub use s2n_quic_core::session_ticket::{Disabled, Store};
+
+pub trait Provider: 'static {
+    type Store: 'static + Store;
+    type Error: 'static + core::fmt::Display;
+
+    /// Starts the session ticket provider
+    fn start(self) -> Result<Self::Store, Self::Error>;
+}
+
+pub use memory::Store as Memory;
+
+pub type Default = Disabled;
+
+impl_provider_utils!();
+
+impl<T: 'static + Store> Provider for T {
+    type Store = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Store, Self::Error> {
+        Ok(self)
+    }
+}
+
+pub mod memory {
+    //! An in-memory session ticket store
+    //!
+    //! Tickets are retained for a bounded number of server names. When the store is full, the
+    //! server name that was least recently used is evicted.
+
+    use bytes::Bytes;
+    use s2n_quic_core::application::ServerName;
+    use std::{
+        collections::{HashMap, VecDeque},
+        sync::{Arc, Mutex},
+    };
+
+    /// The default number of server names for which tickets are retained
+    pub const DEFAULT_CAPACITY: usize = 256;
+
+    /// The maximum number of tickets retained for a single server name
+    ///
+    /// Servers commonly issue more than one ticket per connection so multiple connections can
+    /// be resumed without reusing a ticket.
+    pub const MAX_TICKETS_PER_SERVER: usize = 4;
+
+    #[derive(Clone, Debug)]
+    pub struct Store {
+        state: Arc<Mutex<State>>,
+    }
+
+    impl Default for Store {
+        fn default() -> Self {
+            Self::new(DEFAULT_CAPACITY)
+        }
+    }
+
+    impl Store {
+        /// Creates a store which retains tickets for up to `capacity` server names
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                state: Arc::new(Mutex::new(State {
+                    capacity,
+                    tickets: HashMap::new(),
+                    recently_used: VecDeque::new(),
+                })),
+            }
+        }
+    }
+
+    impl s2n_quic_core::session_ticket::Store for Store {
+        fn on_session_ticket(&self, server_name: &ServerName, session_ticket: Bytes) {
+            if let Ok(mut state) = self.state.lock() {
+                state.insert(server_name, session_ticket);
+            }
+        }
+
+        fn take_session_ticket(&self, server_name: &ServerName) -> Option<Bytes> {
+            self.state.lock().ok()?.take(server_name)
+        }
+    }
+
+    #[derive(Debug)]
+    struct State {
+        capacity: usize,
+        tickets: HashMap<Bytes, VecDeque<Bytes>>,
+        /// Server names ordered from least to most recently used
+        recently_used: VecDeque<Bytes>,
+    }
+
+    impl State {
+        fn insert(&mut self, server_name: &ServerName, session_ticket: Bytes) {
+            if self.capacity == 0 {
+                return;
+            }
+
+            let key = server_name.clone().into_bytes();
+            let tickets = self.tickets.entry(key.clone()).or_default();
+
+            if tickets.len() == MAX_TICKETS_PER_SERVER {
+                tickets.pop_front();
+            }
+            tickets.push_back(session_ticket);
+
+            self.touch(key);
+
+            while self.tickets.len() > self.capacity {
+                if let Some(evicted) = self.recently_used.pop_front() {
+                    self.tickets.remove(&evicted);
+                } else {
+                    break;
+                }
+            }
+        }
+
+        fn take(&mut self, server_name: &ServerName) -> Option<Bytes> {
+            let key = server_name.clone().into_bytes();
+            let tickets = self.tickets.get_mut(&key)?;
+
+            // prefer the most recently issued ticket
+            let ticket = tickets.pop_back();
+
+            if tickets.is_empty() {
+                self.tickets.remove(&key);
+                self.recently_used.retain(|name| name != &key);
+            } else {
+                self.touch(key);
+            }
+
+            ticket
+        }
+
+        /// Marks the server name as the most recently used
+        fn touch(&mut self, key: Bytes) {
+            self.recently_used.retain(|name| name != &key);
+            self.recently_used.push_back(key);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use s2n_quic_core::session_ticket::Store as _;
+
+        #[test]
+        fn take_test() {
+            let store = Store::default();
+            let server: ServerName = "a.example.com".into();
+
+            assert_eq!(store.take_session_ticket(&server), None);
+
+            store.on_session_ticket(&server, Bytes::from_static(b"ticket 1"));
+            store.on_session_ticket(&server, Bytes::from_static(b"ticket 2"));
+
+            // tickets are only used once, newest first
+            assert_eq!(
+                store.take_session_ticket(&server),
+                Some(Bytes::from_static(b"ticket 2"))
+            );
+            assert_eq!(
+                store.take_session_ticket(&server),
+                Some(Bytes::from_static(b"ticket 1"))
+            );
+            assert_eq!(store.take_session_ticket(&server), None);
+
+            // other server names don't have any tickets
+            store.on_session_ticket(&server, Bytes::from_static(b"ticket 3"));
+            assert_eq!(store.take_session_ticket(&"b.example.com".into()), None);
+        }
+
+        #[test]
+        fn max_tickets_per_server_test() {
+            let store = Store::default();
+            let server: ServerName = "a.example.com".into();
+
+            for i in 0..(MAX_TICKETS_PER_SERVER + 2) {
+                store.on_session_ticket(&server, Bytes::from(i.to_string()));
+            }
+
+            let mut count = 0;
+            while store.take_session_ticket(&server).is_some() {
+                count += 1;
+            }
+            assert_eq!(count, MAX_TICKETS_PER_SERVER);
+        }
+
+        #[test]
+        fn eviction_test() {
+            let store = Store::new(2);
+            let a: ServerName = "a.example.com".into();
+            let b: ServerName = "b.example.com".into();
+            let c: ServerName = "c.example.com".into();
+
+            store.on_session_ticket(&a, Bytes::from_static(b"a1"));
+            store.on_session_ticket(&a, Bytes::from_static(b"a2"));
+            store.on_session_ticket(&b, Bytes::from_static(b"b"));
+
+            // using `a` makes `b` the least recently used server name
+            assert!(store.take_session_ticket(&a).is_some());
+
+            store.on_session_ticket(&c, Bytes::from_static(b"c"));
+
+            assert!(store.take_session_ticket(&a).is_some());
+            assert!(store.take_session_ticket(&b).is_none());
+            assert!(store.take_session_ticket(&c).is_some());
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decides the order in which streams of a connection transmit data
+//!
+//! By default, streams are scheduled by their [`Priority`], following the Extensible
+//! Prioritization Scheme for HTTP. Applications can provide their own [`Scheduler`] to
+//! order streams differently.
+
+pub use s2n_quic_core::stream::{
+    scheduler::{Position, Urgency, QUEUE_COUNT},
+    Priority, Scheduler,
+};
+
+pub type Default = Urgency;
+
+/// Provides stream scheduling support for an endpoint
+pub trait Provider {
+    type Scheduler: 'static + Send + Clone + Scheduler;
+    type Error: 'static + core::fmt::Display;
+
+    fn start(self) -> Result<Self::Scheduler, Self::Error>;
+}
+
+impl_provider_utils!();
+
+impl<T: 'static + Send + Clone + Scheduler> Provider for T {
+    type Scheduler = T;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Scheduler, Self::Error> {
+        Ok(self)
+    }
+}
@@ -10,10 +10,14 @@ use s2n_quic_transport::endpoint::handle::Acceptor;
 
 mod builder;
 mod providers;
+#[cfg(all(not(docdiff), feature = "unstable-server-sharded"))]
+mod sharded;
 
 pub use builder::*;
 pub use providers::*;
 pub use s2n_quic_core::application::ServerName as Name;
+#[cfg(all(not(docdiff), feature = "unstable-server-sharded"))]
+pub use sharded::{Shard, Sharded};
 
 /// A QUIC server endpoint, capable of accepting connections
 pub struct Server {
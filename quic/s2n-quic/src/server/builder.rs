@@ -201,6 +201,64 @@ impl<Providers: ServerProviders> Builder<Providers> {
         ServerProviders
     );
 
+    impl_provider_method!(
+        /// Sets the anti-replay provider for the [`Server`]
+        ///
+        /// The anti-replay provider decides if early (0-RTT) data from a client is accepted.
+        ///
+        /// # Examples
+        ///
+        /// Disables early data for the server
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Server, provider::anti_replay};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let server = Server::builder()
+        ///     .with_anti_replay(anti_replay::Disabled::default())?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_anti_replay,
+        anti_replay,
+        ServerProviders
+    );
+
+    impl_provider_method!(
+        /// Sets the preferred address provider for the [`Server`]
+        ///
+        /// The preferred address is advertised to clients, which migrate the connection to it
+        /// once the handshake is confirmed. The server must also be able to receive packets on
+        /// the preferred address, for example by binding the IO provider to it.
+        ///
+        /// # Examples
+        ///
+        /// Advertises an IPv4 preferred address
+        ///
+        /// ```rust,no_run
+        /// # use std::{error::Error, net::SocketAddrV4};
+        /// use s2n_quic::{Server, provider::preferred_address};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let address: SocketAddrV4 = "192.0.2.10:443".parse()?;
+        ///
+        /// let server = Server::builder()
+        ///     .with_preferred_address(preferred_address::Addresses::default().with_ipv4(address))?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_preferred_address,
+        preferred_address,
+        ServerProviders
+    );
+
     impl_provider_method!(
         /// Sets the IO provider for the [`Server`]
         ///
@@ -333,6 +391,34 @@ impl<Providers: ServerProviders> Builder<Providers> {
         ServerProviders
     );
 
+    impl_provider_method!(
+        /// Sets the stream scheduler provider for the [`Server`]
+        ///
+        /// The stream scheduler decides the order in which the streams of each connection
+        /// transmit data.
+        ///
+        /// # Examples
+        ///
+        /// Schedules streams by their priority
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// use s2n_quic::{Server, provider::stream_scheduler};
+        /// #
+        /// # #[tokio::main]
+        /// # async fn main() -> Result<(), Box<dyn Error>> {
+        /// let server = Server::builder()
+        ///     .with_stream_scheduler(stream_scheduler::Urgency::default())?
+        ///     .start()?;
+        /// #
+        /// #    Ok(())
+        /// # }
+        /// ```
+        with_stream_scheduler,
+        stream_scheduler,
+        ServerProviders
+    );
+
     /// Starts the [`Server`] with the configured providers
     ///
     /// # Examples
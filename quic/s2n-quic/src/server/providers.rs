@@ -24,6 +24,9 @@ impl_providers_state! {
         tls: Tls,
         address_token: AddressToken,
         datagram: Datagram,
+        anti_replay: AntiReplay,
+        preferred_address: PreferredAddress,
+        stream_scheduler: StreamScheduler,
     }
 
     /// Opaque trait containing all of the configured providers
@@ -46,6 +49,9 @@ impl<
         Tls: tls::Provider,
         AddressToken: address_token::Provider,
         Datagram: datagram::Provider,
+        AntiReplay: anti_replay::Provider,
+        PreferredAddress: preferred_address::Provider,
+        StreamScheduler: stream_scheduler::Provider,
     >
     Providers<
         CongestionController,
@@ -63,6 +69,9 @@ impl<
         Tls,
         AddressToken,
         Datagram,
+        AntiReplay,
+        PreferredAddress,
+        StreamScheduler,
     >
 {
     pub fn start(self) -> Result<Server, StartError> {
@@ -82,6 +91,9 @@ impl<
             sync,
             tls,
             datagram,
+            anti_replay,
+            preferred_address,
+            stream_scheduler,
         } = self;
 
         let congestion_controller = congestion_controller.start().map_err(StartError::new)?;
@@ -100,6 +112,9 @@ impl<
         let path_migration = path_migration.start().map_err(StartError::new)?;
         let tls = tls.start_server().map_err(StartError::new)?;
         let datagram = datagram.start().map_err(StartError::new)?;
+        let anti_replay = anti_replay.start().map_err(StartError::new)?;
+        let preferred_address = preferred_address.start().map_err(StartError::new)?;
+        let stream_scheduler = stream_scheduler.start().map_err(StartError::new)?;
 
         // Validate providers
         // TODO: Add more validation https://github.com/aws/s2n-quic/issues/285
@@ -129,6 +144,10 @@ impl<
             path_handle: PhantomData,
             path_migration,
             datagram,
+            session_ticket_store: session_ticket::Disabled::default(),
+            anti_replay,
+            preferred_address,
+            stream_scheduler,
         };
 
         let (endpoint, acceptor) = endpoint::Endpoint::new_server(endpoint_config);
@@ -160,6 +179,9 @@ struct EndpointConfig<
     Tls,
     AddressToken,
     Datagram,
+    AntiReplay,
+    PreferredAddress,
+    StreamScheduler,
 > {
     congestion_controller: CongestionController,
     connection_close_formatter: ConnectionCloseFormatter,
@@ -176,6 +198,10 @@ struct EndpointConfig<
     path_handle: PhantomData<PathHandle>,
     path_migration: PathMigration,
     datagram: Datagram,
+    session_ticket_store: session_ticket::Disabled,
+    anti_replay: AntiReplay,
+    preferred_address: PreferredAddress,
+    stream_scheduler: StreamScheduler,
 }
 
 impl<
@@ -194,6 +220,9 @@ impl<
         Tls: crypto::tls::Endpoint,
         AddressToken: address_token::Format,
         Datagram: s2n_quic_core::datagram::Endpoint,
+        AntiReplay: anti_replay::Guard,
+        PreferredAddress: preferred_address::Selector,
+        StreamScheduler: stream_scheduler::Scheduler + Clone,
     > core::fmt::Debug
     for EndpointConfig<
         CongestionController,
@@ -211,6 +240,9 @@ impl<
         Tls,
         AddressToken,
         Datagram,
+        AntiReplay,
+        PreferredAddress,
+        StreamScheduler,
     >
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -234,6 +266,9 @@ impl<
         Tls: crypto::tls::Endpoint,
         AddressToken: address_token::Format,
         Datagram: s2n_quic_core::datagram::Endpoint,
+        AntiReplay: anti_replay::Guard,
+        PreferredAddress: preferred_address::Selector,
+        StreamScheduler: stream_scheduler::Scheduler + Clone,
     > endpoint::Config
     for EndpointConfig<
         CongestionController,
@@ -251,6 +286,9 @@ impl<
         Tls,
         AddressToken,
         Datagram,
+        AntiReplay,
+        PreferredAddress,
+        StreamScheduler,
     >
 {
     type ConnectionIdFormat = ConnectionID;
@@ -271,6 +309,11 @@ impl<
     type PathMigrationValidator = PathMigration;
     type PacketInterceptor = PacketInterceptor;
     type DatagramEndpoint = Datagram;
+    // servers issue session tickets rather than storing them
+    type SessionTicketStore = session_ticket::Disabled;
+    type AntiReplayGuard = AntiReplay;
+    type PreferredAddressSelector = PreferredAddress;
+    type StreamScheduler = StreamScheduler;
 
     const ENDPOINT_TYPE: endpoint::Type = endpoint::Type::Server;
 
@@ -289,6 +332,10 @@ impl<
             event_subscriber: &mut self.event,
             path_migration: &mut self.path_migration,
             datagram: &mut self.datagram,
+            session_ticket_store: &mut self.session_ticket_store,
+            anti_replay: &mut self.anti_replay,
+            preferred_address: &mut self.preferred_address,
+            stream_scheduler: &mut self.stream_scheduler,
         }
     }
 }
@@ -0,0 +1,350 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    connection::Connection,
+    provider::{connection_id, io, StartError},
+    server::Server,
+};
+use core::{
+    fmt,
+    task::{Context, Poll},
+};
+use futures::channel::oneshot;
+use std::net::SocketAddr;
+
+/// Information about an endpoint shard, which is passed to the closure building each
+/// [`Server`] in a [`Sharded`] server
+#[derive(Clone, Debug)]
+pub struct Shard {
+    index: u8,
+    count: u8,
+    handle: tokio::runtime::Handle,
+    local_addr: Option<SocketAddr>,
+}
+
+impl Shard {
+    /// Returns the index of the shard
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns the total number of shards
+    pub fn count(&self) -> u8 {
+        self.count
+    }
+
+    /// Returns the handle of the Tokio runtime which drives the shard
+    pub fn handle(&self) -> &tokio::runtime::Handle {
+        &self.handle
+    }
+
+    /// Returns a connection ID format which encodes the index of the shard
+    pub fn connection_id(&self) -> connection_id::sharded::Format {
+        connection_id::sharded::Format::new(self.index, self.count)
+            .expect("the index is always less than the count")
+    }
+
+    /// Returns the address the first shard is bound to
+    ///
+    /// This is `None` when building the first shard.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// Returns an IO builder which runs on the shard's runtime, receives on `addr` and steers
+    /// datagrams to the shard which owns the connection ID
+    ///
+    /// Every shard after the first receives on the address the first shard is bound to instead
+    /// of `addr`, so a port of `0` results in all of the shards sharing the port picked for the
+    /// first shard.
+    pub fn io(&self, addr: SocketAddr) -> std::io::Result<io::tokio::Builder> {
+        io::tokio::Builder::default()
+            .with_handle(self.handle.clone())
+            .with_reuse_port_steering(self.count)?
+            .with_receive_address(self.local_addr.unwrap_or(addr))
+    }
+}
+
+/// A server which runs an endpoint on each of a number of threads
+///
+/// Each shard is a [`Server`] which is driven by its own single-threaded Tokio runtime. The
+/// shards share a UDP port with `SO_REUSEPORT` and encode their index into each of their
+/// connection IDs, which allows the kernel to steer datagrams to the shard which owns the
+/// connection, even after the peer migrates to a new address.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use std::{error::Error, path::Path};
+/// use s2n_quic::{server::Sharded, Server};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn Error>> {
+/// let mut server = Sharded::start(4, |shard| -> Result<Server, Box<dyn Error>> {
+///     let io = shard.io("127.0.0.1:443".parse()?)?.build()?;
+///
+///     let server = Server::builder()
+///         .with_tls((Path::new("./certs/cert.pem"), Path::new("./certs/key.pem")))?
+///         .with_io(io)?
+///         .with_connection_id(shard.connection_id())?
+///         .start()?;
+///
+///     Ok(server)
+/// })?;
+///
+/// while let Some(connection) = server.accept().await {
+///     println!("new connection: {:?}", connection.remote_addr());
+/// }
+/// #
+/// #    Ok(())
+/// # }
+/// ```
+pub struct Sharded {
+    shards: Vec<Option<Server>>,
+    /// Dropping the senders stops the runtime of each shard
+    _shutdown: Vec<oneshot::Sender<()>>,
+    next: usize,
+}
+
+impl fmt::Debug for Sharded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sharded")
+            .field("shards", &self.shards)
+            .finish()
+    }
+}
+
+impl Sharded {
+    /// Starts `count` shards, each with the [`Server`] returned by `build`
+    ///
+    /// The shards are started in order so the sockets are added to the `SO_REUSEPORT` group at
+    /// the same index as the shard. Once the first shard is bound, its address is passed to the
+    /// other shards with [`Shard::local_addr`].
+    pub fn start<F, E>(count: u8, build: F) -> Result<Self, StartError>
+    where
+        F: 'static + Send + Sync + Fn(Shard) -> Result<Server, E>,
+        E: 'static + fmt::Display,
+    {
+        if count == 0 {
+            return Err(StartError::new("the shard count must be at least 1"));
+        }
+
+        let build = std::sync::Arc::new(build);
+        let mut shards = Vec::with_capacity(count as usize);
+        let mut shutdown = Vec::with_capacity(count as usize);
+        let mut local_addr = None;
+
+        for index in 0..count {
+            let (server_tx, server_rx) = std::sync::mpsc::channel();
+            let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+            let build = build.clone();
+
+            std::thread::Builder::new()
+                .name(format!("s2n-quic-shard-{index}"))
+                .spawn(move || {
+                    let runtime = match tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                    {
+                        Ok(runtime) => runtime,
+                        Err(err) => {
+                            let _ = server_tx.send(Err(err.to_string()));
+                            return;
+                        }
+                    };
+
+                    let shard = Shard {
+                        index,
+                        count,
+                        handle: runtime.handle().clone(),
+                        local_addr,
+                    };
+
+                    let server = {
+                        let _guard = runtime.enter();
+                        build(shard).map_err(|err| err.to_string())
+                    };
+                    let is_ok = server.is_ok();
+
+                    if server_tx.send(server).is_ok() && is_ok {
+                        // drive the endpoint until the sharded server is dropped
+                        let _ = runtime.block_on(shutdown_rx);
+                    }
+                })
+                .map_err(StartError::new)?;
+
+            // wait for the shard to bind before starting the next one
+            let server = server_rx
+                .recv()
+                .map_err(|_| StartError::new("the shard thread exited"))?
+                .map_err(StartError::new)?;
+
+            if local_addr.is_none() {
+                local_addr = Some(server.local_addr().map_err(StartError::new)?);
+            }
+
+            shards.push(Some(server));
+            shutdown.push(shutdown_tx);
+        }
+
+        Ok(Self {
+            shards,
+            _shutdown: shutdown,
+            next: 0,
+        })
+    }
+
+    /// Accepts a new incoming [`Connection`] from any of the shards
+    ///
+    /// Returns `None` once all of the shards have closed.
+    pub async fn accept(&mut self) -> Option<Connection> {
+        futures::future::poll_fn(|cx| self.poll_accept(cx)).await
+    }
+
+    /// Attempts to accept a new incoming [`Connection`] from any of the shards
+    ///
+    /// The shards are polled in a round-robin order so a busy shard can't starve the others.
+    pub fn poll_accept(&mut self, cx: &mut Context) -> Poll<Option<Connection>> {
+        let len = self.shards.len();
+        let mut is_open = false;
+
+        for offset in 0..len {
+            let index = (self.next + offset) % len;
+            let slot = &mut self.shards[index];
+
+            if let Some(server) = slot {
+                match server.poll_accept(cx) {
+                    Poll::Ready(Some(connection)) => {
+                        self.next = (index + 1) % len;
+                        return Poll::Ready(Some(connection));
+                    }
+                    Poll::Ready(None) => *slot = None,
+                    Poll::Pending => is_open = true,
+                }
+            }
+        }
+
+        if is_open {
+            Poll::Pending
+        } else {
+            Poll::Ready(None)
+        }
+    }
+
+    /// Returns the local address that the shards are bound to
+    pub fn local_addr(&self) -> Result<SocketAddr, std::io::Error> {
+        self.shards
+            .iter()
+            .flatten()
+            .next()
+            .ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::NotConnected, "all shards are closed")
+            })?
+            .local_addr()
+    }
+}
+
+impl Drop for Sharded {
+    fn drop(&mut self) {
+        // close the endpoints before their runtimes are stopped
+        self.shards.clear();
+    }
+}
+
+impl futures::stream::Stream for Sharded {
+    type Item = Connection;
+
+    fn poll_next(
+        mut self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Option<Self::Item>> {
+        self.poll_accept(cx)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::{client::Connect, Client};
+    use bytes::Bytes;
+    use s2n_quic_core::crypto::tls::testing::certificates;
+    use std::{
+        error::Error,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn shared_port_test() {
+        const SHARDS: u8 = 4;
+
+        let shard_addrs = Arc::new(Mutex::new(vec![]));
+        let mut server = {
+            let shard_addrs = shard_addrs.clone();
+            Sharded::start(SHARDS, move |shard| -> Result<Server, Box<dyn Error>> {
+                // let the first shard pick a port
+                let io = shard.io("127.0.0.1:0".parse()?)?.build()?;
+                let server = Server::builder()
+                    .with_tls((certificates::CERT_PEM, certificates::KEY_PEM))?
+                    .with_io(io)?
+                    .with_connection_id(shard.connection_id())?
+                    .start()?;
+                shard_addrs.lock().unwrap().push(server.local_addr()?);
+                Ok(server)
+            })
+            .unwrap()
+        };
+
+        let addr = server.local_addr().unwrap();
+        assert_ne!(addr.port(), 0);
+
+        // every shard is bound to the port picked by the first shard
+        let shard_addrs = shard_addrs.lock().unwrap().clone();
+        assert_eq!(shard_addrs, vec![addr; SHARDS as usize]);
+
+        tokio::spawn(async move {
+            while let Some(mut connection) = server.accept().await {
+                tokio::spawn(async move {
+                    while let Ok(Some(mut stream)) = connection.accept_bidirectional_stream().await
+                    {
+                        while let Ok(Some(chunk)) = stream.receive().await {
+                            let _ = stream.send(chunk).await;
+                        }
+                        let _ = stream.finish();
+                    }
+                });
+            }
+        });
+
+        let client = Client::builder()
+            .with_tls(certificates::CERT_PEM)
+            .unwrap()
+            .with_io("127.0.0.1:0")
+            .unwrap()
+            .start()
+            .unwrap();
+
+        // the initial connection IDs are random, so the connections are spread over the shards
+        let exchange = async move {
+            for _ in 0..(SHARDS * 2) {
+                let connect = Connect::new(addr).with_server_name("localhost");
+                let mut connection = client.connect(connect).await.unwrap();
+                let mut stream = connection.open_bidirectional_stream().await.unwrap();
+
+                stream.send(Bytes::from_static(b"hello")).await.unwrap();
+                stream.finish().unwrap();
+
+                let mut received = vec![];
+                while let Some(chunk) = stream.receive().await.unwrap() {
+                    received.extend_from_slice(&chunk);
+                }
+                assert_eq!(received, b"hello");
+            }
+        };
+
+        tokio::time::timeout(Duration::from_secs(10), exchange)
+            .await
+            .expect("the exchanges should complete");
+    }
+}
@@ -376,6 +376,54 @@ macro_rules! impl_send_stream_api {
             let $stream = self;
             $dispatch_body
         }
+
+        /// Sets the priority with which the stream is scheduled for transmission.
+        ///
+        /// Streams with a lower `urgency` are transmitted before streams with a higher `urgency`.
+        /// Urgency values range from `0` to `7`; larger values are treated as `7`. Streams default
+        /// to an urgency of `3`, which allows control streams to preempt bulk transfers.
+        ///
+        /// If `incremental` is `true`, the stream shares the available capacity with other
+        /// incremental streams of the same urgency. Otherwise, streams of the same urgency are
+        /// transmitted one after the other.
+        ///
+        /// # Return value
+        ///
+        /// The function returns:
+        /// - `Ok(())` if the priority was updated successfully.
+        /// - `Err(e)` if the stream encountered a [`stream::Error`](crate::stream::Error).
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # async fn test() -> s2n_quic::stream::Result<()> {
+        /// #   let mut control: s2n_quic::stream::SendStream = todo!();
+        /// #   let mut bulk: s2n_quic::stream::SendStream = todo!();
+        /// #
+        /// control.set_priority(0, false)?;
+        /// bulk.set_priority(7, true)?;
+        /// #
+        /// #   Ok(())
+        /// # }
+        /// ```
+        #[inline]
+        pub fn set_priority(
+            &mut self,
+            urgency: u8,
+            incremental: bool,
+        ) -> $crate::stream::Result<()> {
+            macro_rules! $dispatch {
+                () => {
+                    Err($crate::stream::Error::non_writable())
+                };
+                ($variant: expr) => {
+                    $variant.set_priority(urgency, incremental)
+                };
+            }
+
+            let $stream = self;
+            $dispatch_body
+        }
     };
 }
 
@@ -5,8 +5,13 @@ use crate::{
     client::Connect,
     provider::{
         self,
-        event::{events::PacketSent, ConnectionInfo, ConnectionMeta, Subscriber},
-        io::testing::{rand, spawn, test, time::delay, Model},
+        event::{
+            events::{Congestion, CongestionSource, PacketLost, PacketSent},
+            ConnectionInfo, ConnectionMeta, Subscriber,
+        },
+        io::testing::{
+            rand, spawn, test, test_seed, time::delay, Aqm, Handle, Host, Link, Model, Topology,
+        },
         packet_interceptor::Loss,
     },
     Client, Server,
@@ -22,6 +27,7 @@ use bytes::Bytes;
 use s2n_quic_core::{
     crypto::tls::testing::certificates,
     event::api::{MtuUpdated, MtuUpdatedCause},
+    inet::ExplicitCongestionNotification,
     stream::testing::Data,
 };
 use s2n_quic_platform::io::testing::{network::Packet, primary, TxRecorder};
@@ -78,6 +84,233 @@ fn blackhole_failure_test() {
     blackhole(model, blackhole_duration);
 }
 
+/// Transfers data through a bottleneck and returns the number of packets it dropped and
+/// marked with CE
+fn bottleneck(aqm: Aqm, ecn_threshold: u64) -> (u64, u64) {
+    let model = Model::default();
+    model
+        .set_bandwidth(1_000_000)
+        .set_queue_capacity(20_000)
+        .set_ecn_threshold(ecn_threshold)
+        .set_aqm(aqm);
+
+    test(model.clone(), |handle| {
+        let addr = server(handle)?;
+        let client = build_client(handle)?;
+        start_client(client, addr, Data::new(1_000_000))?;
+        Ok(addr)
+    })
+    .unwrap();
+
+    (model.bottleneck_drops(), model.bottleneck_marks())
+}
+
+#[test]
+fn bottleneck_test() {
+    // without marking, the queue can only signal congestion by dropping packets once it's full
+    let (drops, marks) = bottleneck(Aqm::DropTail, u64::MAX);
+    assert!(drops > 0);
+    assert_eq!(marks, 0);
+
+    for aqm in [
+        Aqm::DropTail,
+        Aqm::codel(),
+        Aqm::Red {
+            min_threshold: 5_000,
+            max_threshold: 15_000,
+            max_probability: 0.1,
+        },
+    ] {
+        // the connection uses ECN so packets are marked before the queue fills up
+        let (_drops, marks) = bottleneck(aqm, 10_000);
+        assert!(marks > 0, "{aqm:?}");
+    }
+}
+
+#[test]
+fn prague_test() {
+    let recorder = TxRecorder::default();
+    let network_packets = recorder.get_packets();
+    let model = Model::default();
+    model
+        .set_bandwidth(1_000_000)
+        .set_queue_capacity(20_000)
+        .set_ecn_threshold(10_000);
+
+    test((recorder, model), |handle| {
+        let server = Server::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(SERVER_CERTS)?
+            .with_congestion_controller(provider::congestion_controller::Prague::default())?
+            .with_event(events())?
+            .start()?;
+        let addr = start_server(server)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_congestion_controller(provider::congestion_controller::Prague::default())?
+            .with_event(events())?
+            .start()?;
+        start_client(client, addr, Data::new(1_000_000))?;
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    // once ECN has been validated on the path, packets are marked ECT(1)
+    assert!(network_packets
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|packet| packet.ecn == ExplicitCongestionNotification::Ect1));
+}
+
+fn topology_client_server(
+    handle: &Handle,
+    client: &Host,
+    server: &Host,
+) -> crate::provider::io::testing::Result {
+    let server = Server::builder()
+        .with_io(handle.builder().with_address(server.bind(443)).build()?)?
+        .with_tls(SERVER_CERTS)?
+        .with_event(events())?
+        .start()?;
+    let addr = start_server(server)?;
+
+    let client = Client::builder()
+        .with_io(handle.builder().with_address(client.bind(49152)).build()?)?
+        .with_tls(certificates::CERT_PEM)?
+        .with_event(events())?
+        .start()?;
+    start_client(client, addr, Data::new(10_000))
+}
+
+#[test]
+fn topology_partition_test() {
+    let topology = Topology::default();
+    let client = topology.host("client");
+    let server = topology.host("server");
+
+    // a slow link makes sure the transfer is still in progress when the partition starts
+    let link = Link::default()
+        .with_delay(Duration::from_millis(20))
+        .with_bandwidth(100_000);
+    topology.connect(&client, &server, link);
+
+    let partition = Duration::from_millis(300)..Duration::from_secs(2);
+    topology.partition(&client, &server, partition.clone());
+
+    let runtime = test(topology, |handle| {
+        topology_client_server(handle, &client, &server)
+    })
+    .unwrap();
+
+    // the transfer can only complete after the link heals
+    assert!(runtime > partition.end, "{:?}", runtime);
+}
+
+#[test]
+fn topology_queue_test() {
+    let topology = Topology::default();
+    let client = topology.host("client");
+    let server = topology.host("server");
+
+    // the queue only holds a few packets so a burst from the sender overflows it
+    let link = Link::default()
+        .with_delay(Duration::from_millis(20))
+        .with_bandwidth(10_000_000)
+        .with_queue_capacity(5_000);
+    topology.connect(&client, &server, link);
+
+    let subscriber = PacketLostRecorder::new();
+    let lost_packets = subscriber.events();
+
+    test(topology, |handle| {
+        let server = Server::builder()
+            .with_io(handle.builder().with_address(server.bind(443)).build()?)?
+            .with_tls(SERVER_CERTS)?
+            .with_event(subscriber)?
+            .start()?;
+        let addr = start_server(server)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().with_address(client.bind(49152)).build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .start()?;
+        start_client(client, addr, Data::new(1_000_000))
+    })
+    .unwrap();
+
+    // the link doesn't drop any packets, other than the ones that don't fit in the queue
+    assert!(!lost_packets.lock().unwrap().is_empty());
+}
+
+#[test]
+fn topology_aqm_test() {
+    let topology = Topology::default();
+    let client = topology.host("client");
+    let server = topology.host("server");
+
+    let link = Link::default()
+        .with_delay(Duration::from_millis(20))
+        .with_bandwidth(10_000_000)
+        .with_queue_capacity(100_000)
+        .with_ecn_threshold(10_000)
+        .with_aqm(Aqm::codel());
+    topology.connect(&client, &server, link);
+
+    let subscriber = CongestionRecorder::new();
+    let congestion = subscriber.events();
+
+    test(topology, |handle| {
+        let server = Server::builder()
+            .with_io(handle.builder().with_address(server.bind(443)).build()?)?
+            .with_tls(SERVER_CERTS)?
+            .with_event(subscriber)?
+            .start()?;
+        let addr = start_server(server)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().with_address(client.bind(49152)).build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events())?
+            .start()?;
+        start_client(client, addr, Data::new(1_000_000))
+    })
+    .unwrap();
+
+    // the link marks the packets the server echoes back with CE, which are reported by the client
+    assert!(congestion
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|source| matches!(source, CongestionSource::Ecn)));
+}
+
+#[test]
+fn topology_seed_test() {
+    let run = |seed| {
+        let topology = Topology::default();
+        let client = topology.host("client");
+        let server = topology.host("server");
+        let link = Link::default()
+            .with_jitter(Duration::from_millis(10))
+            .with_drop_rate(0.05)
+            .with_reordering(0.1, Duration::from_millis(5));
+        topology.connect(&client, &server, link);
+
+        test_seed(topology, seed, |handle| {
+            topology_client_server(handle, &client, &server)
+        })
+        .unwrap()
+    };
+
+    // the same seed always produces the same run
+    assert_eq!(run(123), run(123));
+}
+
 fn intercept_loss(loss: Loss<Random>) {
     let model = Model::default();
     test(model, |handle| {
@@ -304,8 +537,12 @@ fn local_stream_open_notify_test() {
 
 macro_rules! event_recorder {
     ($sub:ident, $con:ident, $event:ty, $method:ident) => {
+        event_recorder!($sub, $con, $event, $method, $event, Clone::clone);
+    };
+    // records the `$output` returned by `$map`, for events which borrow from the connection
+    ($sub:ident, $con:ident, $event:ty, $method:ident, $output:ty, $map:expr) => {
         struct $sub {
-            events: Arc<Mutex<Vec<$event>>>,
+            events: Arc<Mutex<Vec<$output>>>,
         }
 
         impl $sub {
@@ -315,13 +552,13 @@ macro_rules! event_recorder {
                 }
             }
 
-            fn events(&self) -> Arc<Mutex<Vec<$event>>> {
+            fn events(&self) -> Arc<Mutex<Vec<$output>>> {
                 self.events.clone()
             }
         }
 
         struct $con {
-            events: Arc<Mutex<Vec<$event>>>,
+            events: Arc<Mutex<Vec<$output>>>,
         }
 
         impl Subscriber for $sub {
@@ -344,7 +581,7 @@ macro_rules! event_recorder {
                 event: &$event,
             ) {
                 let mut buffer = context.events.lock().unwrap();
-                buffer.push(event.clone());
+                buffer.push($map(event));
             }
         }
     };
@@ -356,6 +593,22 @@ event_recorder!(
     PacketSent,
     on_packet_sent
 );
+event_recorder!(
+    PacketLostRecorder,
+    PacketLostRecorderContext,
+    PacketLost,
+    on_packet_lost,
+    u16,
+    |event: &PacketLost| event.bytes_lost
+);
+event_recorder!(
+    CongestionRecorder,
+    CongestionRecorderContext,
+    Congestion,
+    on_congestion,
+    CongestionSource,
+    |event: &Congestion| event.source.clone()
+);
 event_recorder!(
     MtuUpdatedRecorder,
     MtuUpdatedRecorderContext,
@@ -479,3 +732,506 @@ fn mtu_probe_jumbo_frame_unsupported_test() {
     // ETHERNET_MTU - UDP_HEADER_LEN - IPV4_HEADER_LEN
     assert_eq!(last_mtu.mtu, 1472);
 }
+
+#[test]
+fn qlog_event_test() {
+    let dir = std::env::temp_dir().join(format!("s2n-quic-qlog-{}", std::process::id()));
+    let server_dir = dir.join("server");
+    let client_dir = dir.join("client");
+
+    test(Model::default(), |handle| {
+        let server = Server::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(SERVER_CERTS)?
+            .with_event(provider::event::qlog::Provider::new(&server_dir))?
+            .start()?;
+        let addr = start_server(server)?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(provider::event::qlog::Provider::new(&client_dir))?
+            .start()?;
+        start_client(client, addr, Data::new(1000))?;
+
+        Ok(addr)
+    })
+    .unwrap();
+
+    let read_trace = |dir: &std::path::Path| {
+        let mut entries: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(
+            entries.len(),
+            1,
+            "one trace should be written per connection"
+        );
+        let path = entries.pop().unwrap();
+        assert_eq!(path.extension().unwrap(), "sqlog");
+        let trace = std::fs::read_to_string(&path).unwrap();
+        (path.file_name().unwrap().to_owned(), trace)
+    };
+
+    let (server_name, server_trace) = read_trace(&server_dir);
+    let (client_name, client_trace) = read_trace(&client_dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // both endpoints name the trace after the original destination connection id
+    assert_eq!(server_name, client_name);
+
+    for (trace, vantage_point) in [(server_trace, "server"), (client_trace, "client")] {
+        let mut records = trace.split_terminator('\n');
+
+        let header = records.next().unwrap();
+        assert!(header.starts_with("\u{1e}{\"qlog_version\":\"0.3\""));
+        assert!(header.contains(&format!(
+            "\"vantage_point\":{{\"type\":\"{vantage_point}\"}}"
+        )));
+
+        let mut names = vec![];
+        for record in records {
+            assert!(record.starts_with("\u{1e}{\"time\":"), "{record}");
+            names.push(
+                record
+                    .split("\"name\":\"")
+                    .nth(1)
+                    .unwrap()
+                    .split('"')
+                    .next()
+                    .unwrap(),
+            );
+        }
+
+        assert!(names.contains(&"transport:packet_sent"));
+        assert!(names.contains(&"transport:packet_received"));
+        assert!(names.contains(&"recovery:metrics_updated"));
+    }
+}
+
+#[cfg(feature = "s2n-quic-rustls")]
+mod early_data {
+    use super::*;
+    use crate::provider::{anti_replay, session_ticket, tls::rustls};
+    use s2n_quic_core::crypto::tls::EarlyDataStatus;
+
+    const REQUEST: &[u8] = b"early data request";
+
+    /// Connects to a server twice with early data, where the second connection resumes the
+    /// session of the first one
+    ///
+    /// Returns the early data status of each client connection and whether each server
+    /// connection received early data.
+    fn resume<G: anti_replay::Provider>(guard: G) -> (Vec<EarlyDataStatus>, Vec<bool>) {
+        let client_statuses = Arc::new(Mutex::new(vec![]));
+        let server_received = Arc::new(Mutex::new(vec![]));
+
+        test(Model::default(), |handle| {
+            let tls = rustls::Server::builder()
+                .with_certificate(certificates::CERT_PEM, certificates::KEY_PEM)?
+                .with_early_data()?
+                .build()?;
+            let mut server = Server::builder()
+                .with_io(handle.builder().build()?)?
+                .with_tls(tls)?
+                .with_anti_replay(guard)?
+                .with_event(events())?
+                .start()?;
+            let server_addr = server.local_addr()?;
+
+            let received = server_received.clone();
+            spawn(async move {
+                while let Some(mut connection) = server.accept().await {
+                    let received = received.clone();
+                    spawn(async move {
+                        while let Ok(Some(mut stream)) =
+                            connection.accept_bidirectional_stream().await
+                        {
+                            received
+                                .lock()
+                                .unwrap()
+                                .push(connection.received_early_data().unwrap());
+
+                            // echo the request back
+                            spawn(async move {
+                                while let Ok(Some(chunk)) = stream.receive().await {
+                                    let _ = stream.send(chunk).await;
+                                }
+                                let _ = stream.finish();
+                            });
+                        }
+                    });
+                }
+            });
+
+            let tls = rustls::Client::builder()
+                .with_certificate(certificates::CERT_PEM)?
+                .with_early_data()?
+                .build()?;
+            let client = Client::builder()
+                .with_io(handle.builder().build()?)?
+                .with_tls(tls)?
+                .with_session_ticket(session_ticket::Memory::default())?
+                .with_event(events())?
+                .start()?;
+
+            let statuses = client_statuses.clone();
+            primary::spawn(async move {
+                // the first connection receives a session ticket which the second one uses
+                for _ in 0..2 {
+                    let connect = Connect::new(server_addr)
+                        .with_server_name("localhost")
+                        .with_early_data(REQUEST);
+                    let mut connection = client.connect(connect).await.unwrap();
+                    statuses
+                        .lock()
+                        .unwrap()
+                        .push(connection.early_data_status().unwrap());
+
+                    // the first stream carries the early data
+                    let mut stream = connection.open_bidirectional_stream().await.unwrap();
+                    stream.finish().unwrap();
+
+                    let mut response = vec![];
+                    while let Some(chunk) = stream.receive().await.unwrap() {
+                        response.extend_from_slice(&chunk);
+                    }
+                    assert_eq!(response, REQUEST);
+                }
+            });
+
+            Ok(server_addr)
+        })
+        .unwrap();
+
+        let client_statuses = client_statuses.lock().unwrap().clone();
+        let server_received = server_received.lock().unwrap().clone();
+        (client_statuses, server_received)
+    }
+
+    #[test]
+    fn early_data_accepted_test() {
+        let (client_statuses, server_received) = resume(anti_replay::Default::default());
+
+        assert_eq!(
+            client_statuses,
+            [EarlyDataStatus::NotAttempted, EarlyDataStatus::Accepted]
+        );
+        assert_eq!(server_received, [false, true]);
+    }
+
+    #[test]
+    fn early_data_rejected_test() {
+        // the data is sent again after the handshake when the guard rejects it
+        let (client_statuses, server_received) = resume(anti_replay::Disabled::default());
+
+        assert_eq!(
+            client_statuses,
+            [EarlyDataStatus::NotAttempted, EarlyDataStatus::Rejected]
+        );
+        assert_eq!(server_received, [false, false]);
+    }
+}
+
+#[cfg(feature = "s2n-quic-tls")]
+mod s2n_tls_early_data {
+    use super::*;
+    use crate::provider::{event::events::PacketHeader, session_ticket, tls::s2n_tls};
+    use s2n_quic_core::{
+        application::ServerName, crypto::tls::EarlyDataStatus, session_ticket::Store,
+    };
+
+    const REQUEST: &[u8] = b"early data request";
+
+    /// Records if the client had a session ticket for each connection
+    #[derive(Clone, Default)]
+    struct TicketRecorder {
+        store: session_ticket::Memory,
+        offered: Arc<Mutex<Vec<bool>>>,
+    }
+
+    impl Store for TicketRecorder {
+        fn on_session_ticket(&self, server_name: &ServerName, session_ticket: Bytes) {
+            self.store.on_session_ticket(server_name, session_ticket);
+        }
+
+        fn take_session_ticket(&self, server_name: &ServerName) -> Option<Bytes> {
+            let session_ticket = self.store.take_session_ticket(server_name);
+            self.offered.lock().unwrap().push(session_ticket.is_some());
+            session_ticket
+        }
+    }
+
+    /// Records the number of bytes each connection sends in Handshake packets
+    #[derive(Default)]
+    struct HandshakeRecorder {
+        bytes: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl Subscriber for HandshakeRecorder {
+        type ConnectionContext = usize;
+
+        fn create_connection_context(
+            &mut self,
+            _meta: &ConnectionMeta,
+            _info: &ConnectionInfo,
+        ) -> Self::ConnectionContext {
+            let mut bytes = self.bytes.lock().unwrap();
+            bytes.push(0);
+            bytes.len() - 1
+        }
+
+        fn on_packet_sent(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &ConnectionMeta,
+            event: &PacketSent,
+        ) {
+            if let PacketHeader::Handshake { .. } = event.packet_header {
+                self.bytes.lock().unwrap()[*context] += event.packet_len;
+            }
+        }
+    }
+
+    struct Outcome {
+        /// The early data status of each client connection
+        client_statuses: Vec<EarlyDataStatus>,
+        /// Whether each server connection received early data
+        server_received: Vec<bool>,
+        /// Whether the client had a session ticket for each connection
+        tickets_offered: Vec<bool>,
+        /// The number of bytes each server connection sent in Handshake packets
+        server_handshake_bytes: Vec<usize>,
+    }
+
+    /// Connects to an s2n-tls server twice with early data, where the second connection
+    /// resumes the session of the first one
+    fn resume() -> Outcome {
+        let client_statuses = Arc::new(Mutex::new(vec![]));
+        let server_received = Arc::new(Mutex::new(vec![]));
+        let ticket_recorder = TicketRecorder::default();
+        let tickets_offered = ticket_recorder.offered.clone();
+        let handshake_recorder = HandshakeRecorder::default();
+        let server_handshake_bytes = handshake_recorder.bytes.clone();
+
+        test(Model::default(), |handle| {
+            let tls = s2n_tls::Server::builder()
+                .with_certificate(certificates::CERT_PEM, certificates::KEY_PEM)?
+                .with_session_ticket_key(b"test key", &[1; 32])?
+                .build()?;
+            let mut server = Server::builder()
+                .with_io(handle.builder().build()?)?
+                .with_tls(tls)?
+                .with_event((handshake_recorder, events()))?
+                .start()?;
+            let server_addr = server.local_addr()?;
+
+            let received = server_received.clone();
+            spawn(async move {
+                while let Some(mut connection) = server.accept().await {
+                    let received = received.clone();
+                    spawn(async move {
+                        while let Ok(Some(mut stream)) =
+                            connection.accept_bidirectional_stream().await
+                        {
+                            received
+                                .lock()
+                                .unwrap()
+                                .push(connection.received_early_data().unwrap());
+
+                            // echo the request back
+                            spawn(async move {
+                                while let Ok(Some(chunk)) = stream.receive().await {
+                                    let _ = stream.send(chunk).await;
+                                }
+                                let _ = stream.finish();
+                            });
+                        }
+                    });
+                }
+            });
+
+            let tls = s2n_tls::Client::builder()
+                .with_certificate(certificates::CERT_PEM)?
+                .build()?;
+            let client = Client::builder()
+                .with_io(handle.builder().build()?)?
+                .with_tls(tls)?
+                .with_session_ticket(ticket_recorder)?
+                .with_event(events())?
+                .start()?;
+
+            let statuses = client_statuses.clone();
+            primary::spawn(async move {
+                // the first connection receives a session ticket which the second one uses
+                for _ in 0..2 {
+                    let connect = Connect::new(server_addr)
+                        .with_server_name("localhost")
+                        .with_early_data(REQUEST);
+                    let mut connection = client.connect(connect).await.unwrap();
+                    statuses
+                        .lock()
+                        .unwrap()
+                        .push(connection.early_data_status().unwrap());
+
+                    // the first stream carries the early data
+                    let mut stream = connection.open_bidirectional_stream().await.unwrap();
+                    stream.finish().unwrap();
+
+                    let mut response = vec![];
+                    while let Some(chunk) = stream.receive().await.unwrap() {
+                        response.extend_from_slice(&chunk);
+                    }
+                    assert_eq!(response, REQUEST);
+                }
+            });
+
+            Ok(server_addr)
+        })
+        .unwrap();
+
+        Outcome {
+            client_statuses: client_statuses.lock().unwrap().clone(),
+            server_received: server_received.lock().unwrap().clone(),
+            tickets_offered: tickets_offered.lock().unwrap().clone(),
+            server_handshake_bytes: server_handshake_bytes.lock().unwrap().clone(),
+        }
+    }
+
+    #[test]
+    fn resumption_test() {
+        let outcome = resume();
+
+        assert_eq!(outcome.tickets_offered, [false, true]);
+
+        // a resumed handshake doesn't include the server's certificate
+        let handshake_bytes = &outcome.server_handshake_bytes;
+        assert_eq!(handshake_bytes.len(), 2);
+        assert!(
+            handshake_bytes[1] < handshake_bytes[0],
+            "{:?}",
+            handshake_bytes
+        );
+    }
+
+    #[test]
+    fn early_data_test() {
+        let outcome = resume();
+
+        // The s2n-tls provider doesn't derive 0-RTT keys, so the data is sent once the handshake
+        // completes, even when the session is resumed
+        assert_eq!(
+            outcome.client_statuses,
+            [EarlyDataStatus::NotAttempted, EarlyDataStatus::NotAttempted]
+        );
+        assert_eq!(outcome.server_received, [false, false]);
+    }
+}
+
+#[cfg(feature = "s2n-quic-tls")]
+mod quic_version {
+    use super::*;
+    use crate::provider::{event::events::PacketHeader, tls::s2n_tls};
+    use s2n_quic_core::packet::long::{VERSION_1, VERSION_2};
+
+    /// Performs a handshake where the client's first Initial packet uses `client_version`
+    ///
+    /// Returns the versions of the long header packets sent by the client and the server.
+    fn handshake(client_version: u32) -> (Vec<u32>, Vec<u32>) {
+        let client_subscriber = PacketSentRecorder::new();
+        let client_events = client_subscriber.events();
+        let server_subscriber = PacketSentRecorder::new();
+        let server_events = server_subscriber.events();
+
+        test(Model::default(), |handle| {
+            let tls = s2n_tls::Server::builder()
+                .with_certificate(certificates::CERT_PEM, certificates::KEY_PEM)?
+                .build()?;
+            let server = Server::builder()
+                .with_io(handle.builder().build()?)?
+                .with_tls(tls)?
+                .with_event((server_subscriber, events()))?
+                .start()?;
+            let server_addr = start_server(server)?;
+
+            let tls = s2n_tls::Client::builder()
+                .with_certificate(certificates::CERT_PEM)?
+                .build()?;
+            let client = Client::builder()
+                .with_io(handle.builder().build()?)?
+                .with_tls(tls)?
+                .with_event((client_subscriber, events()))?
+                .start()?;
+
+            primary::spawn(async move {
+                let connect = Connect::new(server_addr)
+                    .with_server_name("localhost")
+                    .with_quic_version(client_version);
+                let mut connection = client.connect(connect).await.unwrap();
+
+                let mut stream = connection.open_bidirectional_stream().await.unwrap();
+                stream.send(Bytes::from_static(b"ping")).await.unwrap();
+                let response = stream.receive().await.unwrap().unwrap();
+                assert_eq!(&response[..], b"ping");
+            });
+
+            Ok(server_addr)
+        })
+        .unwrap();
+
+        let versions = |events: Arc<Mutex<Vec<PacketSent>>>| -> Vec<u32> {
+            events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|event| match event.packet_header {
+                    PacketHeader::Initial { version, .. }
+                    | PacketHeader::Handshake { version, .. } => Some(version),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        (versions(client_events), versions(server_events))
+    }
+
+    #[test]
+    fn version_2_handshake_test() {
+        let (client_versions, server_versions) = handshake(VERSION_2);
+
+        assert!(!client_versions.is_empty());
+        assert!(client_versions.iter().all(|version| *version == VERSION_2));
+        assert!(!server_versions.is_empty());
+        assert!(server_versions.iter().all(|version| *version == VERSION_2));
+    }
+
+    #[test]
+    fn compatible_version_negotiation_test() {
+        // the client starts with version 1 and offers version 2, which the server prefers
+        let (client_versions, server_versions) = handshake(VERSION_1);
+
+        assert_eq!(client_versions.first(), Some(&VERSION_1));
+        assert_eq!(client_versions.last(), Some(&VERSION_2));
+        assert!(!server_versions.is_empty());
+        assert!(server_versions.iter().all(|version| *version == VERSION_2));
+    }
+
+    #[test]
+    fn unsupported_version_test() {
+        test(Model::default(), |handle| {
+            let server_addr = server(handle)?;
+            let client = build_client(handle)?;
+
+            primary::spawn(async move {
+                let connect = Connect::new(server_addr)
+                    .with_server_name("localhost")
+                    .with_quic_version(0xbabababa);
+                assert!(client.connect(connect).await.is_err());
+            });
+
+            Ok(server_addr)
+        })
+        .unwrap();
+    }
+}